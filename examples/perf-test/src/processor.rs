@@ -48,6 +48,7 @@ impl TodoApi {
             owner: "".to_string(),
             roles: vec![],
             groups: vec![],
+            request_id: "".to_string(),
             ext: Default::default(),
             sync_task_fns: Default::default(),
             async_task_fns: Default::default(),
@@ -109,6 +110,7 @@ impl TodoApi {
             owner: "".to_string(),
             roles: vec![],
             groups: vec![],
+            request_id: "".to_string(),
             ext: Default::default(),
             sync_task_fns: Default::default(),
             async_task_fns: Default::default(),
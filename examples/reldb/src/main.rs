@@ -36,6 +36,7 @@ async fn main() -> TardisResult<()> {
         owner: "".to_string(),
         roles: vec![],
         groups: vec![],
+        request_id: "".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
         async_task_fns: Default::default(),
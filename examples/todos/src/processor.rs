@@ -94,12 +94,7 @@ impl TodoApi {
                 page_size.0,
             )
             .await?;
-        TardisResp::ok(TardisPage {
-            page_number: page_number.0,
-            page_size: page_size.0,
-            total_size,
-            records: todos,
-        })
+        TardisResp::ok(TardisPage::from_total(todos, total_size, page_number.0, page_size.0))
     }
 
     #[oai(path = "/:id", method = "delete")]
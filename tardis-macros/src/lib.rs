@@ -22,6 +22,9 @@
 //! | `reldb-mysql`                  | `TardisCreateEntity`    |
 //! | `reldb-mysql`                  | `TardisEmptyBehavior`   |
 //! | `reldb-mysql`                  | `TardisEmptyRelation`   |
+//! | -                              | `TardisMap`             |
+//! | -                              | `TardisValidate`        |
+//! | -                              | `tardis_client`         |
 //!
 //!
 //! Please note that the availability of each macro depends on the enabled features. Make sure to enable the corresponding feature to use the desired macro.
@@ -52,10 +55,8 @@
 //!
 //! [TardisCreateEntity]
 
-#[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
 use proc_macro::TokenStream;
-#[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemTrait};
 
 /// # TardisCreateTable
 /// Generate table creation statement, compatible with `tardis_entity`.
@@ -153,6 +154,37 @@ pub fn tardis_create_index(input: TokenStream) -> TokenStream {
 /// The functionality of `TardisCreateEntity` is equivalent to `TardisCreateIndex` combined with `TardisCreateTable`.
 /// Additionally, it introduces a new attribute called fill_ctx, and automatically implements `ActiveModelBehavior`. \
 /// see [TardisCreateIndex] and [TardisCreateTable]
+///
+/// ## fill_ctx attribute
+///
+/// The fields are filled by `TardisActiveModel::fill_ctx` on insert and update.
+///
+/// - `owner`: `owner` of the `TardisContext`, on insert.
+/// - `ak`: `ak` of the `TardisContext`, on insert.
+/// - `own_paths`: `own_paths` of the `TardisContext`, on insert.
+/// - `create_time`: Current time, on insert. The field is `chrono::DateTime<Utc>` or `chrono::DateTime<FixedOffset>`.
+/// - `update_time`: Current time, on insert and update. The field is `chrono::DateTime<Utc>` or `chrono::DateTime<FixedOffset>`.
+/// - `deleted`: `false` on insert, and implements `TardisSoftDeleteEntity` for `Entity` with the field as the soft-deletion flag.
+///
+/// Example:
+/// ```ignore
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+/// #[sea_orm(table_name = "todos")]
+/// pub struct Model {
+///     #[sea_orm(primary_key, auto_increment = false)]
+///     pub id: String,
+///     #[fill_ctx(owner)]
+///     pub owner: String,
+///     #[fill_ctx(create_time)]
+///     pub create_time: chrono::DateTime<Utc>,
+///     #[fill_ctx(update_time)]
+///     pub update_time: chrono::DateTime<Utc>,
+///     #[fill_ctx(deleted)]
+///     pub deleted: bool,
+/// }
+///
+/// let todos = Entity::find_alive().all(conn.raw_conn()).await?;
+/// ```
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
 #[proc_macro_derive(TardisCreateEntity, attributes(tardis_entity, index, fill_ctx))]
 pub fn tardis_create_entity(input: TokenStream) -> TokenStream {
@@ -188,8 +220,144 @@ pub fn tardis_empty_relation(input: TokenStream) -> TokenStream {
     }
 }
 
+/// # TardisMap
+/// Generates `From`/`TryFrom` conversions between a struct (E.g. web DTO) and other structs (E.g. DB entities).
+/// Fields are matched by name and converted by `Into::into`.
+///
+/// ## tardis_map attribute on struct
+///
+/// - `from`: Source type, generates `From<Source> for Self`,
+///   or `TryFrom<Source, Error = TardisError>` if any field uses `try_with`. (multiple)
+/// - `into`: Target type, generates `From<Self> for Target`. (multiple)
+/// - `into_default`: Fill the fields of target types that are not in `Self` with `Default::default()`. (default: `false`)
+///
+/// ## tardis_map attribute on field
+///
+/// - `rename`: Field name in the source/target types. (optional)
+/// - `skip`: Skip this field, use `Default::default()` when converting from the source types,
+///   and ignore it when converting into the target types. (default: `false`)
+/// - `with`: Conversion function from the source field, `fn(SourceType) -> FieldType`. (optional)
+/// - `try_with`: Fallible conversion function from the source field, `fn(SourceType) -> TardisResult<FieldType>`. (optional)
+/// - `into_with`: Conversion function into the target field, `fn(FieldType) -> TargetType`. (optional)
+///
+/// Example:
+/// ```ignore
+/// #[derive(TardisMap)]
+/// #[tardis_map(from = "todos::Model", into = "todos::Model")]
+/// pub struct TodoDetailResp {
+///     pub id: i32,
+///     #[tardis_map(rename = "description")]
+///     pub desc: String,
+///     #[tardis_map(with = "format_time", into_with = "parse_time")]
+///     pub created_at: String,
+///     #[tardis_map(skip)]
+///     pub tags: Vec<String>,
+/// }
+/// ```
+#[proc_macro_derive(TardisMap, attributes(tardis_map))]
+pub fn tardis_map(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match tardis_map::create_map(input) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// # TardisValidate
+/// Implements `TardisValidate` of tardis by the declarative rules of the fields,
+/// the `ValidJson` payload of tardis rejects the invalid bodies before the handlers run.
+///
+/// ## validate attribute on field
+///
+/// - `length`: Length of the strings (by chars) and the collections, `length(min = 1, max = 64)`. (optional)
+/// - `regex`: Pattern of the strings. (optional)
+/// - `range`: Range of the comparable values, `range(min = 0, max = 100)`, the bounds have the type of the field. (optional)
+/// - `one_of`: Allowed values, `one_of = ["todo", "done"]`. (optional)
+/// - `custom`: Custom validator, `fn(&FieldType) -> TardisResult<()>`, the code of the error is the code of the field. (multiple)
+/// - `nested`: Validate the fields of the value, which implements `TardisValidate`, e.g. a struct, `Vec` or `Option` of structs. (default: `false`)
+///
+/// The rules of the `Option` fields are checked only if the value is present.
+///
+/// Example:
+/// ```ignore
+/// #[derive(Object, TardisValidate)]
+/// pub struct TodoAddReq {
+///     #[validate(length(min = 1, max = 64), regex = "^[a-z0-9-]+$")]
+///     pub code: String,
+///     #[validate(range(min = 1, max = 5))]
+///     pub priority: i32,
+///     #[validate(one_of = ["todo", "done"])]
+///     pub status: String,
+///     #[validate(custom = "check_description")]
+///     pub description: Option<String>,
+///     #[validate(nested)]
+///     pub tags: Vec<TagReq>,
+/// }
+/// ```
+#[proc_macro_derive(TardisValidate, attributes(validate))]
+pub fn tardis_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match tardis_validate::create_validate(input) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// # tardis_client
+/// Generates a typed REST client from the trait of the remote API, the calls are sent by `TardisApiClient` of tardis.
+/// The trait is implemented by the generated `{Trait}Client` struct, and it's made async by `async_trait`,
+/// so don't add `#[async_trait]` to the trait.
+///
+/// ## tardis_client attribute
+///
+/// - `tardis_resp`: The responses are wrapped by `TardisResp`, the non-`200` codes are returned as `TardisError`. (default: `false`)
+/// - `name`: Name of the generated client. (default: `{Trait}Client`)
+///
+/// ## method attribute
+///
+/// One of `get`, `post`, `put`, `patch`, `delete` and `head` with the path relative to the base url,
+/// the `{param}` placeholders are replaced by the percent-encoded arguments with the same names.
+///
+/// ## argument attribute
+///
+/// - `body`: JSON body. (at most one)
+/// - `query`: Struct serialized as the query parameters.
+/// - `header`: Header of the name, e.g. `#[header("X-Tenant")]`.
+///
+/// Arguments without attributes that aren't in the path are sent as the query parameters of their names.
+///
+/// Example:
+/// ```ignore
+/// #[tardis_client(tardis_resp)]
+/// pub trait TodoApi {
+///     #[get("/todos/{id}")]
+///     async fn get_todo(&self, id: i64) -> TardisResult<TodoDetailResp>;
+///     #[get("/todos")]
+///     async fn find_todos(&self, page: u32, #[query] query: &TodoQuery, #[header("X-Tenant")] tenant: &str) -> TardisResult<Vec<TodoDetailResp>>;
+///     #[post("/todos")]
+///     async fn add_todo(&self, #[body] todo: &TodoAddReq) -> TardisResult<i64>;
+///     #[delete("/todos/{id}")]
+///     async fn delete_todo(&self, id: i64) -> TardisResult<()>;
+/// }
+///
+/// let client = TodoApiClient::from_module("todo")?.with_bearer_token(token);
+/// let todo = client.get_todo(1).await?;
+/// ```
+#[proc_macro_attribute]
+pub fn tardis_client(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemTrait);
+
+    match tardis_client::create_client(attr.into(), item) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) mod macro_helpers;
+mod tardis_client;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
 mod tardis_create_entity;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
@@ -198,3 +366,5 @@ mod tardis_create_index;
 mod tardis_create_table;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
 mod tardis_empty_impl;
+mod tardis_map;
+mod tardis_validate;
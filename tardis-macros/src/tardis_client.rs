@@ -0,0 +1,187 @@
+use crate::macro_helpers::helpers::default_doc;
+use darling::ast::NestedMeta;
+use darling::FromMeta;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Error, FnArg, ItemTrait, LitStr, Pat, Result, TraitItem, TraitItemFn};
+
+const HTTP_METHODS: [&str; 6] = ["get", "post", "put", "patch", "delete", "head"];
+
+#[derive(FromMeta, Debug, Default)]
+struct TardisClientMeta {
+    /// unwrap the `TardisResp` responses
+    #[darling(default)]
+    tardis_resp: bool,
+    /// name of the generated client, default is `{Trait}Client`
+    #[darling(default)]
+    name: Option<Ident>,
+}
+
+pub(crate) fn create_client(attr: TokenStream, mut item: ItemTrait) -> Result<TokenStream> {
+    let meta = match NestedMeta::parse_meta_list(attr).map_err(darling::Error::from).and_then(|list| TardisClientMeta::from_list(&list)) {
+        Ok(meta) => meta,
+        Err(err) => return Ok(err.write_errors()),
+    };
+    let trait_ident = item.ident.clone();
+    let client_ident = meta.name.unwrap_or_else(|| format_ident!("{}Client", trait_ident));
+    let vis = item.vis.clone();
+    let mut methods = Vec::new();
+    for trait_item in item.items.iter_mut() {
+        if let TraitItem::Fn(method) = trait_item {
+            methods.push(create_method(method)?);
+        }
+    }
+    let doc = default_doc();
+    let tardis_resp = if meta.tardis_resp {
+        quote! { .with_tardis_resp() }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        #[::tardis::async_trait::async_trait]
+        #item
+
+        #doc
+        #[derive(Clone)]
+        #vis struct #client_ident {
+            api_client: ::tardis::web::web_api_client::TardisApiClient,
+        }
+
+        impl #client_ident {
+            pub fn new(api_client: ::tardis::web::web_api_client::TardisApiClient) -> Self {
+                Self { api_client: api_client #tardis_resp }
+            }
+
+            pub fn from_module(code: &str) -> ::tardis::basic::result::TardisResult<Self> {
+                ::std::result::Result::Ok(Self::new(::tardis::web::web_api_client::TardisApiClient::from_module(code)?))
+            }
+
+            pub fn with_header(self, key: impl ::std::convert::Into<::std::string::String>, value: impl ::std::convert::Into<::std::string::String>) -> Self {
+                Self { api_client: self.api_client.with_header(key, value) }
+            }
+
+            pub fn with_bearer_token(self, token: impl ::std::fmt::Display) -> Self {
+                Self { api_client: self.api_client.with_bearer_token(token) }
+            }
+
+            pub fn api_client(&self) -> &::tardis::web::web_api_client::TardisApiClient {
+                &self.api_client
+            }
+        }
+
+        #[::tardis::async_trait::async_trait]
+        impl #trait_ident for #client_ident {
+            #(#methods)*
+        }
+    })
+}
+
+enum ArgKind {
+    Path,
+    Query,
+    QueryParam,
+    Header(LitStr),
+    Body,
+}
+
+fn create_method(method: &mut TraitItemFn) -> Result<TokenStream> {
+    let span = method.sig.ident.span();
+    let http_attr_index = method
+        .attrs
+        .iter()
+        .position(|attr| HTTP_METHODS.iter().any(|http_method| attr.path().is_ident(http_method)))
+        .ok_or_else(|| Error::new(span, "one of #[get(..)] #[post(..)] #[put(..)] #[patch(..)] #[delete(..)] #[head(..)] is required"))?;
+    let http_attr = method.attrs.remove(http_attr_index);
+    let http_method = format_ident!("{}", http_attr.path().get_ident().expect("http method is an ident").to_string().to_uppercase());
+    let path = http_attr.parse_args::<LitStr>()?;
+    if method.sig.asyncness.is_none() {
+        return Err(Error::new(span, "the api method must be async"));
+    }
+    if !matches!(method.sig.inputs.first(), Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_none()) {
+        return Err(Error::new(span, "the first argument of the api method must be `&self`"));
+    }
+    let (path_format, path_params) = parse_path(&path)?;
+
+    let mut args = Vec::new();
+    for input in method.sig.inputs.iter_mut() {
+        let FnArg::Typed(arg) = input else {
+            continue;
+        };
+        let Pat::Ident(pat) = arg.pat.as_ref() else {
+            return Err(Error::new_spanned(&arg.pat, "only the identifier arguments are supported"));
+        };
+        let ident = pat.ident.clone();
+        let mut kind = if path_params.contains(&ident.to_string()) { ArgKind::Path } else { ArgKind::QueryParam };
+        let mut attrs = Vec::new();
+        for attr in arg.attrs.drain(..) {
+            if attr.path().is_ident("query") {
+                kind = ArgKind::Query;
+            } else if attr.path().is_ident("body") {
+                kind = ArgKind::Body;
+            } else if attr.path().is_ident("header") {
+                kind = ArgKind::Header(attr.parse_args::<LitStr>()?);
+            } else {
+                attrs.push(attr);
+            }
+        }
+        arg.attrs = attrs;
+        args.push((ident, kind));
+    }
+    for param in &path_params {
+        if !args.iter().any(|(ident, kind)| matches!(kind, ArgKind::Path) && ident == param) {
+            return Err(Error::new(path.span(), format!("the path parameter `{param}` isn't an argument")));
+        }
+    }
+    if args.iter().filter(|(_, kind)| matches!(kind, ArgKind::Body)).count() > 1 {
+        return Err(Error::new(span, "at most one #[body] argument is allowed"));
+    }
+
+    let path_args = path_params.iter().map(|param| {
+        let ident = Ident::new(param, Span::call_site());
+        quote! { ::tardis::web::web_api_client::encode_path_param(&#ident) }
+    });
+    let request_args = args.iter().filter_map(|(ident, kind)| match kind {
+        ArgKind::Path => None,
+        ArgKind::Query => Some(quote! { .query(&#ident) }),
+        ArgKind::QueryParam => {
+            let key = ident.to_string();
+            Some(quote! { .query_param(#key, &#ident) })
+        }
+        ArgKind::Header(key) => Some(quote! { .header(#key, &#ident) }),
+        ArgKind::Body => Some(quote! { .json(&#ident) }),
+    });
+    let sig = &method.sig;
+    Ok(quote! {
+        #sig {
+            self.api_client
+                .request(::tardis::web::reqwest::Method::#http_method, ::std::format!(#path_format, #(#path_args),*))
+                #(#request_args)*
+                .send()
+                .await
+        }
+    })
+}
+
+/// Replace the `{param}` placeholders of the path with `{}`, returns the format string and the parameter names
+fn parse_path(path: &LitStr) -> Result<(String, Vec<String>)> {
+    let value = path.value();
+    let mut format = String::new();
+    let mut params = Vec::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').map(|end| start + end).ok_or_else(|| Error::new(path.span(), "unclosed `{` in the path"))?;
+        let param = rest[start + 1..end].trim();
+        if param.is_empty() {
+            return Err(Error::new(path.span(), "empty path parameter"));
+        }
+        format.push_str(&rest[..start]);
+        format.push_str("{}");
+        params.push(param.to_string());
+        rest = &rest[end + 1..];
+    }
+    if rest.contains('}') {
+        return Err(Error::new(path.span(), "unmatched `}` in the path"));
+    }
+    format.push_str(rest);
+    Ok((format, params))
+}
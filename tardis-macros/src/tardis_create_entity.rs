@@ -1,4 +1,4 @@
-use crate::macro_helpers::helpers::default_doc;
+use crate::macro_helpers::helpers::{default_doc, ConvertVariableHelpers};
 use crate::{tardis_create_index, tardis_create_table};
 use darling::FromField;
 use proc_macro2::{Ident, TokenStream};
@@ -19,6 +19,12 @@ struct FillCtxMeta {
     ak: bool,
     #[darling(default)]
     own_paths: bool,
+    #[darling(default)]
+    create_time: bool,
+    #[darling(default)]
+    update_time: bool,
+    #[darling(default)]
+    deleted: bool,
 }
 
 pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
@@ -31,7 +37,11 @@ pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
             let create_table_stat = tardis_create_table::create_table(ident.clone(), data.clone(), None)?;
             let create_index_stat = tardis_create_index::create_index(ident, data, None)?;
 
-            let fill_ctx_stat = create_fill_ctx_statement(data_struct.fields)?;
+            let FillCtxStatement {
+                insert: fill_ctx_stat,
+                always: fill_ctx_always_stat,
+                soft_delete: soft_delete_stat,
+            } = create_fill_ctx_statement(data_struct.fields)?;
             Ok(quote! {
 
                 #doc
@@ -40,6 +50,7 @@ pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
                         if is_insert {
                             #fill_ctx_stat;
                         }
+                        #fill_ctx_always_stat;
                     }
 
                     // Call the method automatically generated by TardisCreateTable macros
@@ -55,14 +66,28 @@ pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
 
             #create_index_stat
 
+            #soft_delete_stat
+
             })
         }
         Data::Enum(_) => Err(Error::new(ident.span(), "enum is not support!")),
         Data::Union(_) => Err(Error::new(ident.span(), "union is not support!")),
     }
 }
-fn create_fill_ctx_statement(fields: Fields) -> Result<TokenStream> {
+struct FillCtxStatement {
+    /// Statements on insert
+    insert: TokenStream,
+    /// Statements on insert and update
+    always: TokenStream,
+    /// Implementation of `TardisSoftDeleteEntity`
+    soft_delete: TokenStream,
+}
+
+fn create_fill_ctx_statement(fields: Fields) -> Result<FillCtxStatement> {
     let mut statement: Punctuated<TokenStream, Semi> = Punctuated::new();
+    let mut always_statement: Punctuated<TokenStream, Semi> = Punctuated::new();
+    let mut deleted_column = None;
+    let mut update_time_column = None;
     for field in fields {
         for attr in field.attrs.clone() {
             if let Some(ident) = attr.path().get_ident() {
@@ -70,7 +95,11 @@ fn create_fill_ctx_statement(fields: Fields) -> Result<TokenStream> {
                     let field_fill_ctx_meta: FillCtxMeta = match FillCtxMeta::from_field(&field) {
                         Ok(field) => field,
                         Err(err) => {
-                            return Ok(err.write_errors());
+                            return Ok(FillCtxStatement {
+                                insert: err.write_errors(),
+                                always: TokenStream::new(),
+                                soft_delete: TokenStream::new(),
+                            });
                         }
                     };
                     if field_fill_ctx_meta.owner {
@@ -88,15 +117,69 @@ fn create_fill_ctx_statement(fields: Fields) -> Result<TokenStream> {
                         }
                     }
                     if field_fill_ctx_meta.own_paths {
-                        if let Some(ident) = field_fill_ctx_meta.ident {
+                        if let Some(ident) = field_fill_ctx_meta.ident.clone() {
                             statement.push(quote! {
                                 self.#ident=Set(ctx.own_paths.to_string())
                             });
                         }
                     }
+                    if field_fill_ctx_meta.create_time {
+                        if let Some(ident) = field_fill_ctx_meta.ident.clone() {
+                            statement.push(quote! {
+                                self.#ident=Set(::tardis::TardisFuns::clock().now().into())
+                            });
+                        }
+                    }
+                    if field_fill_ctx_meta.update_time {
+                        if let Some(ident) = field_fill_ctx_meta.ident.clone() {
+                            always_statement.push(quote! {
+                                self.#ident=Set(::tardis::TardisFuns::clock().now().into())
+                            });
+                            update_time_column = Some(column_ident(&ident));
+                        }
+                    }
+                    if field_fill_ctx_meta.deleted {
+                        if let Some(ident) = field_fill_ctx_meta.ident {
+                            statement.push(quote! {
+                                self.#ident=Set(false)
+                            });
+                            deleted_column = Some(column_ident(&ident));
+                        }
+                    }
                 }
             }
         }
     }
-    Ok(statement.into_token_stream())
+    let soft_delete = match deleted_column {
+        Some(deleted_column) => {
+            let update_time_column = match update_time_column {
+                Some(update_time_column) => quote!(::std::option::Option::Some(Column::#update_time_column)),
+                None => quote!(::std::option::Option::None),
+            };
+            let doc = default_doc();
+            quote! {
+                #doc
+                impl ::tardis::db::reldb_client::TardisSoftDeleteEntity for Entity {
+                    fn deleted_column() -> Column {
+                        Column::#deleted_column
+                    }
+
+                    fn update_time_column() -> ::std::option::Option<Column> {
+                        #update_time_column
+                    }
+                }
+            }
+        }
+        None => TokenStream::new(),
+    };
+    Ok(FillCtxStatement {
+        insert: statement.into_token_stream(),
+        always: always_statement.into_token_stream(),
+        soft_delete,
+    })
+}
+
+/// The variant of `Column` generated by `DeriveEntityModel` for the field
+fn column_ident(field: &Ident) -> Ident {
+    Ident::new(&ConvertVariableHelpers::underscore_to_camel(field.to_string()), field.span())
 }
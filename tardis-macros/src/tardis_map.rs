@@ -0,0 +1,160 @@
+use crate::macro_helpers::helpers::default_doc;
+use darling::{ast, FromDeriveInput, FromField};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{DeriveInput, Error, Path, Result};
+
+#[derive(FromDeriveInput, Debug)]
+#[darling(attributes(tardis_map), supports(struct_named))]
+struct TardisMapMeta {
+    ident: Ident,
+    generics: syn::Generics,
+    data: ast::Data<(), TardisMapFieldMeta>,
+    /// source types, generate `From<Source> for Self` (or `TryFrom` if any field uses `try_with`)
+    /// ```rust ignore
+    /// #[tardis_map(from = "todos::Model", from = "todos::ModelV2")]
+    /// ```
+    #[darling(default, multiple)]
+    from: Vec<Path>,
+    /// target types, generate `From<Self> for Target`
+    #[darling(default, multiple)]
+    into: Vec<Path>,
+    /// fill the fields of target types that are not in `Self` with `Default::default()`
+    #[darling(default)]
+    into_default: bool,
+}
+
+#[derive(FromField, Debug, Clone)]
+#[darling(attributes(tardis_map))]
+struct TardisMapFieldMeta {
+    ident: Option<Ident>,
+    /// field name in the source/target types, the keywords are used as the raw identifiers, e.g. `type` -> `r#type`
+    #[darling(default)]
+    rename: Option<String>,
+    /// skip this field, use `Default::default()` when converting from the source types
+    #[darling(default)]
+    skip: bool,
+    /// conversion function from the source field, `fn(SourceType) -> FieldType`
+    #[darling(default)]
+    with: Option<Path>,
+    /// fallible conversion function from the source field, `fn(SourceType) -> TardisResult<FieldType>`
+    #[darling(default)]
+    try_with: Option<Path>,
+    /// conversion function into the target field, `fn(FieldType) -> TargetType`
+    #[darling(default)]
+    into_with: Option<Path>,
+}
+
+pub(crate) fn create_map(input: DeriveInput) -> Result<TokenStream> {
+    let meta = match TardisMapMeta::from_derive_input(&input) {
+        Ok(meta) => meta,
+        Err(err) => return Ok(err.write_errors()),
+    };
+    let fields = match meta.data {
+        ast::Data::Struct(fields) => fields.fields,
+        ast::Data::Enum(_) => return Err(Error::new(meta.ident.span(), "enum is not support!")),
+    };
+    if meta.from.is_empty() && meta.into.is_empty() {
+        return Err(Error::new(meta.ident.span(), "at least one `from` or `into` is required in #[tardis_map(..)]"));
+    }
+    for field in &fields {
+        if field.with.is_some() && field.try_with.is_some() {
+            return Err(Error::new(
+                field.ident.as_ref().map(|ident| ident.span()).unwrap_or_else(|| meta.ident.span()),
+                "`with` and `try_with` can't be used together",
+            ));
+        }
+    }
+    let doc = default_doc();
+    let ident = &meta.ident;
+    let (impl_generics, ty_generics, where_clause) = meta.generics.split_for_impl();
+    let fallible = fields.iter().any(|field| !field.skip && field.try_with.is_some());
+    let source_idents = fields.iter().map(source_ident).collect::<Result<Vec<_>>>()?;
+
+    let from_fields = fields.iter().zip(&source_idents).map(|(field, source_ident)| {
+        let field_ident = field.ident.as_ref().expect("only named struct is supported");
+        if field.skip {
+            return quote! { #field_ident: ::std::default::Default::default() };
+        }
+        if let Some(with) = &field.with {
+            quote! { #field_ident: #with(value.#source_ident) }
+        } else if let Some(try_with) = &field.try_with {
+            quote! { #field_ident: #try_with(value.#source_ident)? }
+        } else {
+            quote! { #field_ident: ::std::convert::Into::into(value.#source_ident) }
+        }
+    });
+    let from_fields = quote! { #(#from_fields,)* };
+    let from_impls = meta.from.iter().map(|source| {
+        if fallible {
+            quote! {
+                #doc
+                impl #impl_generics ::std::convert::TryFrom<#source> for #ident #ty_generics #where_clause {
+                    type Error = ::tardis::basic::error::TardisError;
+
+                    fn try_from(value: #source) -> ::std::result::Result<Self, Self::Error> {
+                        Ok(Self { #from_fields })
+                    }
+                }
+            }
+        } else {
+            quote! {
+                #doc
+                impl #impl_generics ::std::convert::From<#source> for #ident #ty_generics #where_clause {
+                    fn from(value: #source) -> Self {
+                        Self { #from_fields }
+                    }
+                }
+            }
+        }
+    });
+
+    let into_fields = fields.iter().zip(&source_idents).filter(|(field, _)| !field.skip).map(|(field, target_ident)| {
+        let field_ident = field.ident.as_ref().expect("only named struct is supported");
+        if let Some(into_with) = &field.into_with {
+            quote! { #target_ident: #into_with(value.#field_ident) }
+        } else {
+            quote! { #target_ident: ::std::convert::Into::into(value.#field_ident) }
+        }
+    });
+    let into_fields = quote! { #(#into_fields,)* };
+    let into_rest = if meta.into_default {
+        quote! { ..::std::default::Default::default() }
+    } else {
+        quote! {}
+    };
+    let into_impls = meta.into.iter().map(|target| {
+        quote! {
+            #doc
+            impl #impl_generics ::std::convert::From<#ident #ty_generics> for #target #where_clause {
+                fn from(value: #ident #ty_generics) -> Self {
+                    Self { #into_fields #into_rest }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#from_impls)*
+        #(#into_impls)*
+    })
+}
+
+fn source_ident(field: &TardisMapFieldMeta) -> Result<Ident> {
+    let ident = field.ident.clone().expect("only named struct is supported");
+    match &field.rename {
+        Some(rename) => rename_ident(rename, ident.span()),
+        None => Ok(ident),
+    }
+}
+
+fn rename_ident(rename: &str, span: Span) -> Result<Ident> {
+    if syn::parse_str::<Ident>(rename).is_ok() {
+        return Ok(Ident::new(rename, span));
+    }
+    // these keywords can't be the raw identifiers
+    if !matches!(rename, "self" | "Self" | "super" | "crate" | "_") && syn::parse_str::<Ident>(&format!("r#{rename}")).is_ok() {
+        return Ok(Ident::new_raw(rename, span));
+    }
+    Err(Error::new(span, format!("`{rename}` is not a valid field name in #[tardis_map(rename = ..)]")))
+}
@@ -0,0 +1,158 @@
+use crate::macro_helpers::helpers::default_doc;
+use darling::{ast, FromDeriveInput, FromField, FromMeta};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{DeriveInput, Error, Expr, ExprArray, GenericArgument, Path, PathArguments, Result, Type};
+
+#[derive(FromDeriveInput, Debug)]
+#[darling(attributes(validate), supports(struct_named))]
+struct TardisValidateMeta {
+    ident: Ident,
+    generics: syn::Generics,
+    data: ast::Data<(), TardisValidateFieldMeta>,
+}
+
+#[derive(FromField, Debug, Clone)]
+#[darling(attributes(validate))]
+struct TardisValidateFieldMeta {
+    ident: Option<Ident>,
+    ty: Type,
+    /// length of the strings (by chars) and the collections
+    /// ```rust ignore
+    /// #[validate(length(min = 1, max = 64))]
+    /// ```
+    #[darling(default)]
+    length: Option<LengthMeta>,
+    /// pattern of the strings
+    #[darling(default)]
+    regex: Option<String>,
+    /// range of the comparable values
+    /// ```rust ignore
+    /// #[validate(range(min = 0.0, max = 100.0))]
+    /// ```
+    #[darling(default)]
+    range: Option<RangeMeta>,
+    /// allowed values
+    /// ```rust ignore
+    /// #[validate(one_of = ["todo", "done"])]
+    /// ```
+    #[darling(default)]
+    one_of: Option<ExprArray>,
+    /// custom validator, `fn(&FieldType) -> TardisResult<()>`
+    #[darling(default, multiple)]
+    custom: Vec<Path>,
+    /// validate the fields of the value, which implements `TardisValidate`
+    #[darling(default)]
+    nested: bool,
+}
+
+#[derive(FromMeta, Debug, Clone)]
+struct LengthMeta {
+    #[darling(default)]
+    min: Option<usize>,
+    #[darling(default)]
+    max: Option<usize>,
+}
+
+#[derive(FromMeta, Debug, Clone)]
+struct RangeMeta {
+    #[darling(default)]
+    min: Option<Expr>,
+    #[darling(default)]
+    max: Option<Expr>,
+}
+
+pub(crate) fn create_validate(input: DeriveInput) -> Result<TokenStream> {
+    let meta = match TardisValidateMeta::from_derive_input(&input) {
+        Ok(meta) => meta,
+        Err(err) => return Ok(err.write_errors()),
+    };
+    let fields = match meta.data {
+        ast::Data::Struct(fields) => fields.fields,
+        ast::Data::Enum(_) => return Err(Error::new(meta.ident.span(), "enum is not support!")),
+    };
+    let mut checks = Vec::new();
+    for field in &fields {
+        let field_ident = field.ident.as_ref().expect("only named struct is supported");
+        let field_name = field_ident.to_string();
+        let mut rules = Vec::new();
+        if let Some(length) = &field.length {
+            if length.min.is_none() && length.max.is_none() {
+                return Err(Error::new(field_ident.span(), "at least one `min` or `max` is required in length(..)"));
+            }
+            let min = optional(&length.min.map(|min| quote! { #min }));
+            let max = optional(&length.max.map(|max| quote! { #max }));
+            rules.push(quote! { ::tardis::basic::validation::check_length(errors, &field, value, #min, #max); });
+        }
+        if let Some(regex) = &field.regex {
+            rules.push(quote! { ::tardis::basic::validation::check_regex(errors, &field, value, #regex); });
+        }
+        if let Some(range) = &field.range {
+            if range.min.is_none() && range.max.is_none() {
+                return Err(Error::new(field_ident.span(), "at least one `min` or `max` is required in range(..)"));
+            }
+            let min = optional(&range.min.as_ref().map(|min| quote! { #min }));
+            let max = optional(&range.max.as_ref().map(|max| quote! { #max }));
+            rules.push(quote! { ::tardis::basic::validation::check_range(errors, &field, value, #min, #max); });
+        }
+        if let Some(one_of) = &field.one_of {
+            rules.push(quote! { ::tardis::basic::validation::check_one_of(errors, &field, value, &#one_of); });
+        }
+        for custom in &field.custom {
+            rules.push(quote! { ::tardis::basic::validation::check_custom(errors, &field, #custom(value)); });
+        }
+        if field.nested {
+            rules.push(quote! { ::tardis::basic::validation::TardisValidate::validate_fields(value, errors, &field); });
+        }
+        if rules.is_empty() {
+            continue;
+        }
+        // the rules of the optional fields are checked only if the value is present
+        let check = if is_option(&field.ty) {
+            quote! {
+                if let ::std::option::Option::Some(value) = &self.#field_ident {
+                    #(#rules)*
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#field_ident;
+                #(#rules)*
+            }
+        };
+        checks.push(quote! {
+            {
+                let field = ::tardis::basic::validation::field_path(prefix, #field_name);
+                #check
+            }
+        });
+    }
+    let doc = default_doc();
+    let ident = &meta.ident;
+    let (impl_generics, ty_generics, where_clause) = meta.generics.split_for_impl();
+    Ok(quote! {
+        #doc
+        impl #impl_generics ::tardis::basic::validation::TardisValidate for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn validate_fields(&self, errors: &mut ::tardis::basic::validation::TardisValidationErrors, prefix: &str) {
+                #(#checks)*
+            }
+        }
+    })
+}
+
+fn optional(value: &Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.path.segments.last().is_some_and(|segment| {
+        segment.ident == "Option" && matches!(&segment.arguments, PathArguments::AngleBracketed(args) if matches!(args.args.first(), Some(GenericArgument::Type(_))))
+    })
+}
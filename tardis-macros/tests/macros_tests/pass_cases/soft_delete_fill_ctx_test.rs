@@ -0,0 +1,53 @@
+use tardis::basic::dto::TardisContext;
+use tardis::chrono::{self, Utc};
+use tardis::db::reldb_client::{TardisActiveModel, TardisSoftDeleteEntity};
+use tardis::db::sea_orm;
+use tardis::db::sea_orm::*;
+use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+#[sea_orm(table_name = "tests")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[fill_ctx(owner)]
+    pub owner: String,
+    #[fill_ctx(create_time)]
+    pub create_time: chrono::DateTime<Utc>,
+    #[fill_ctx(update_time)]
+    pub update_time: chrono::DateTime<Utc>,
+    #[fill_ctx(deleted)]
+    pub deleted: bool,
+}
+
+#[allow(dead_code)]
+fn main() {
+    let ctx = TardisContext {
+        owner: "owner".to_string(),
+        ..Default::default()
+    };
+    let mut tests_model = ActiveModel {
+        id: Set("".to_string()),
+        ..Default::default()
+    };
+    tests_model.fill_ctx(&ctx, true);
+    assert_eq!(tests_model.owner, Set("owner".to_string()));
+    assert!(tests_model.create_time.is_set());
+    assert!(tests_model.update_time.is_set());
+    assert_eq!(tests_model.deleted, Set(false));
+
+    // only the update time is filled on update
+    let mut tests_model = ActiveModel {
+        id: Set("".to_string()),
+        ..Default::default()
+    };
+    tests_model.fill_ctx(&ctx, false);
+    assert!(!tests_model.owner.is_set());
+    assert!(!tests_model.create_time.is_set());
+    assert!(tests_model.update_time.is_set());
+
+    assert!(matches!(Entity::deleted_column(), Column::Deleted));
+    assert!(matches!(Entity::update_time_column(), Some(Column::UpdateTime)));
+    let sql = Entity::find_alive().build(DbBackend::Postgres).to_string();
+    assert!(sql.contains(r#""tests"."deleted" = FALSE"#));
+}
@@ -0,0 +1,76 @@
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::TardisMap;
+
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    pub id: i32,
+    pub code: String,
+    pub description: String,
+    pub done: bool,
+    pub priority: String,
+}
+
+#[derive(Debug, TardisMap)]
+#[tardis_map(from = "Model", into = "Model", into_default)]
+pub struct TodoResp {
+    pub id: i32,
+    #[tardis_map(rename = "code")]
+    pub todo_code: String,
+    pub description: String,
+    #[tardis_map(with = "done_to_status", into_with = "status_to_done")]
+    pub status: String,
+    #[tardis_map(skip)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, TardisMap)]
+#[tardis_map(from = "Model")]
+pub struct TodoPriorityResp {
+    pub id: i64,
+    #[tardis_map(try_with = "parse_priority")]
+    pub priority: u8,
+}
+
+fn done_to_status(done: bool) -> String {
+    if done {
+        "done".to_string()
+    } else {
+        "todo".to_string()
+    }
+}
+
+fn status_to_done(status: String) -> bool {
+    status == "done"
+}
+
+fn parse_priority(priority: String) -> TardisResult<u8> {
+    priority.parse::<u8>().map_err(|_| TardisError::bad_request(&format!("invalid priority: {priority}"), ""))
+}
+
+fn main() {
+    let model = Model {
+        id: 1,
+        code: "t001".to_string(),
+        description: "test".to_string(),
+        done: true,
+        priority: "3".to_string(),
+    };
+    let resp = TodoResp::from(model.clone());
+    assert_eq!(resp.id, 1);
+    assert_eq!(resp.todo_code, "t001");
+    assert_eq!(resp.status, "done");
+    assert!(resp.tags.is_empty());
+    let model_from_resp = Model::from(resp);
+    assert_eq!(model_from_resp.code, "t001");
+    assert!(model_from_resp.done);
+    assert_eq!(model_from_resp.priority, "");
+
+    let priority = TodoPriorityResp::try_from(model.clone()).unwrap();
+    assert_eq!(priority.priority, 3);
+    assert!(TodoPriorityResp::try_from(Model {
+        priority: "high".to_string(),
+        ..model
+    })
+    .is_err());
+}
@@ -1,13 +1,29 @@
 use std::env;
 
+pub mod clock;
 pub mod dto;
 pub mod error;
+#[cfg(feature = "sentry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sentry")))]
+pub mod error_report;
 pub mod field;
 pub mod json;
 pub mod locale;
+#[cfg(any(feature = "log-loki", feature = "log-fluentd"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "log-loki", feature = "log-fluentd"))))]
+pub mod log_shipping;
+pub mod metrics;
+#[cfg(feature = "decimal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "decimal")))]
+pub mod money;
 pub mod result;
+#[cfg(feature = "tracing-appender")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing-appender")))]
+pub mod rolling_file;
+pub mod time;
 pub mod tracing;
 pub mod uri;
+pub mod validation;
 
 pub fn fetch_profile() -> String {
     env::var("PROFILE").unwrap_or_else(|_| "".to_string())
@@ -0,0 +1,73 @@
+//! Framework clock / 框架时钟
+//!
+//! The current time used by the framework (e.g. the expiration of the in-memory cache, the node heartbeats of the cluster and the
+//! audit timestamps of the built-in tables), the source can be replaced so that the time-dependent logic is deterministic in tests.
+//!
+//! 框架使用的当前时间（如内存缓存的过期、集群节点心跳及内置表的审计时间戳），可替换时间源从而使依赖时间的逻辑在测试中可确定.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::TardisFuns;
+//! let now = TardisFuns::clock().now();
+//! let ts = TardisFuns::clock().timestamp_millis();
+//! ```
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time / 当前时间的来源
+pub trait TardisClockSource: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system time / 系统时间
+pub struct SystemClockSource;
+
+impl TardisClockSource for SystemClockSource {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Framework clock / 框架时钟
+pub struct TardisClock {
+    source: RwLock<Arc<dyn TardisClockSource>>,
+}
+
+impl TardisClock {
+    pub(crate) fn new() -> TardisClock {
+        TardisClock {
+            source: RwLock::new(Arc::new(SystemClockSource)),
+        }
+    }
+
+    /// Get the current time / 获取当前时间
+    pub fn now(&self) -> DateTime<Utc> {
+        match self.source.read() {
+            Ok(source) => source.now(),
+            Err(_) => Utc::now(),
+        }
+    }
+
+    /// Get the current timestamp in seconds / 获取当前秒级时间戳
+    pub fn timestamp(&self) -> i64 {
+        self.now().timestamp()
+    }
+
+    /// Get the current timestamp in milliseconds / 获取当前毫秒级时间戳
+    pub fn timestamp_millis(&self) -> i64 {
+        self.now().timestamp_millis()
+    }
+
+    /// Replace the source of the current time, the clock is process-wide / 替换当前时间的来源，时钟为进程级共享
+    pub fn set_source(&self, source: Arc<dyn TardisClockSource>) {
+        if let Ok(mut current) = self.source.write() {
+            *current = source;
+        }
+    }
+
+    /// Restore to the system time / 恢复为系统时间
+    pub fn reset(&self) {
+        self.set_source(Arc::new(SystemClockSource));
+    }
+}
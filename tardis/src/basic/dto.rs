@@ -1,8 +1,10 @@
 //! Common DTOs / 常用的DTO
-use std::{collections::HashMap, fmt, pin::Pin, sync::Arc};
+use std::{collections::HashMap, fmt, future::Future, pin::Pin, sync::Arc};
 
+use base64::{engine::general_purpose, Engine};
 use tokio::sync::{Mutex, RwLock};
-use tracing::error;
+use tokio::task::JoinHandle;
+use tracing::{error, Instrument};
 
 use crate::{
     serde::{Deserialize, Serialize},
@@ -13,6 +15,14 @@ use super::result::TardisResult;
 
 type SyncFn = dyn FnOnce() -> Pin<Box<dyn std::future::Future<Output = TardisResult<()>> + Send + Sync>> + Send + 'static;
 type AsyncFn = dyn FnOnce() -> Pin<Box<dyn std::future::Future<Output = TardisResult<()>> + Send + Sync>> + Send + 'static;
+
+/// Default name of the context header / 上下文请求头的默认名称
+pub const DEFAULT_CONTEXT_HEADER: &str = "Tardis-Context";
+
+tokio::task_local! {
+    static CURRENT_CONTEXT: TardisContext;
+}
+
 /// Tardis context / Tardis上下文
 ///
 /// Used to bring in some authentication information when a web request is received.
@@ -23,6 +33,13 @@ type AsyncFn = dyn FnOnce() -> Pin<Box<dyn std::future::Future<Output = TardisRe
 ///
 /// 该信息需要与 IAM 服务对应.
 ///
+/// The context of the current task is available by [`TardisContext::current`] , it's set by the web server for the requests with
+/// the context header and by the MQ client for the messages with the context header, and carried by [`TardisContext::spawn`] ,
+/// [`TardisWebClient`](crate::web::web_client::TardisWebClient) and the MQ client.
+///
+/// 当前task的上下文可通过 [`TardisContext::current`] 获取，Web服务为带上下文请求头的请求、MQ客户端为带上下文消息头的消息设置该上下文，
+/// 并由 [`TardisContext::spawn`] 、 [`TardisWebClient`](crate::web::web_client::TardisWebClient) 及MQ客户端传递.
+///
 #[derive(Deserialize, Serialize, Clone)]
 #[serde(default)]
 pub struct TardisContext {
@@ -36,6 +53,9 @@ pub struct TardisContext {
     pub roles: Vec<String>,
     /// List of requested group ids / 请求的群组Id列表
     pub groups: Vec<String>,
+    /// The request id / 请求Id
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub request_id: String,
     /// Extension information / 扩展信息
     #[serde(skip)]
     pub ext: Arc<RwLock<HashMap<String, String>>>,
@@ -78,6 +98,7 @@ impl fmt::Debug for TardisContext {
             .field("ak", &self.ak)
             .field("roles", &self.roles)
             .field("groups", &self.groups)
+            .field("request_id", &self.request_id)
             .field("ext", &self.ext)
             .finish()
     }
@@ -91,6 +112,7 @@ impl Default for TardisContext {
             owner: "".to_string(),
             roles: vec![],
             groups: vec![],
+            request_id: "".to_string(),
             ext: Default::default(),
             sync_task_fns: Default::default(),
             async_task_fns: Default::default(),
@@ -103,6 +125,68 @@ impl TardisContext {
         TardisFuns::json.obj_to_string(self)
     }
 
+    /// Get the context of the current task / 获取当前task的上下文
+    pub fn current() -> Option<TardisContext> {
+        CURRENT_CONTEXT.try_with(|ctx| ctx.clone()).ok()
+    }
+
+    /// Run the future with the context as the current one / 以该上下文作为当前上下文运行future
+    ///
+    /// The future runs in a `tardis_context` span with the request id, the owner and the ak of the context.
+    ///
+    /// future在带有上下文请求Id、所属者及Ak的 `tardis_context` span中运行.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        let span = tracing::info_span!("tardis_context", request_id = self.request_id.as_str(), owner = self.owner.as_str(), ak = self.ak.as_str());
+        CURRENT_CONTEXT.scope(self, fut.instrument(span)).await
+    }
+
+    /// Spawn a task with the current context and span / 以当前上下文及span启动task
+    pub fn spawn<F>(fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let fut = fut.instrument(tracing::Span::current());
+        match Self::current() {
+            Some(ctx) => tokio::spawn(CURRENT_CONTEXT.scope(ctx, fut)),
+            None => tokio::spawn(fut),
+        }
+    }
+
+    /// Name of the context header, configured by `fw.web_server.context_conf.context_header_name`
+    /// / 上下文请求头名，由 `fw.web_server.context_conf.context_header_name` 配置
+    pub fn header_name() -> String {
+        TardisFuns::fw_config_opt()
+            .and_then(|fw_config| fw_config.web_server.as_ref().map(|web_server| web_server.context_conf.context_header_name.clone()))
+            .unwrap_or_else(|| DEFAULT_CONTEXT_HEADER.to_string())
+    }
+
+    /// Inject the current context into the carrier (e.g. http headers or mq headers) if it's absent
+    /// / 当前上下文不存在于载体（如http请求头或mq消息头）时注入
+    pub fn inject(carrier: &mut HashMap<String, String>) {
+        let Some(ctx) = Self::current() else {
+            return;
+        };
+        let header_name = Self::header_name();
+        if carrier.keys().any(|key| key.eq_ignore_ascii_case(&header_name)) {
+            return;
+        }
+        match ctx.to_json() {
+            Ok(json) => {
+                carrier.insert(header_name, general_purpose::STANDARD.encode(json));
+            }
+            Err(error) => error!("[Tardis.Context] Serialize context error: {error}"),
+        }
+    }
+
+    /// Extract the context carried by [`Self::inject`] / 提取由 [`Self::inject`] 携带的上下文
+    pub fn extract(carrier: &HashMap<String, String>) -> Option<TardisContext> {
+        let header_name = Self::header_name();
+        let value = carrier.iter().find(|(key, _)| key.eq_ignore_ascii_case(&header_name)).map(|(_, value)| value)?;
+        let json = general_purpose::STANDARD.decode(value).ok().and_then(|json| String::from_utf8(json).ok())?;
+        TardisFuns::json.str_to_obj(&json).ok()
+    }
+
     pub async fn add_ext(&self, key: &str, value: &str) -> TardisResult<()> {
         self.ext.write().await.insert(key.to_string(), value.to_string());
         Ok(())
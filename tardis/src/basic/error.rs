@@ -1,21 +1,40 @@
 use crate::basic::locale::TardisLocale;
 use crate::serde::{Deserialize, Serialize};
 use core::fmt::Display;
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::num::{ParseIntError, TryFromIntError};
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
-use std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, PoisonError, RwLockReadGuard, RwLockWriteGuard};
 use std::time::SystemTimeError;
 use tracing::warn;
 
 pub static ERROR_DEFAULT_CODE: &str = "-1";
 
 /// Tardis unified error wrapper / Tardis统一错误封装
+///
+/// # Examples
+/// ```ignore
+/// use tardis::basic::error::TardisError;
+/// TardisError::bad_request("[Todo] Invalid request", "400-todo-invalid").with_extension("name", "must not be empty");
+/// std::fs::read("todo.toml").map_err(|error| TardisError::io_error("[Todo] Read config error", "").with_source(error))?;
+/// ```
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct TardisError {
     pub code: String,
     pub message: String,
+    /// The underlying error, not serialized / 底层错误，不序列化
+    ///
+    /// It's an [`Arc`] to keep the error cloneable.
+    ///
+    /// 使用 [`Arc`] 以保持错误可克隆.
+    #[serde(skip)]
+    pub source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+    /// The details of the error, e.g. the validation errors by field, they are returned in the `ext` of the web responses
+    /// / 错误的详情，如按字段的校验错误，在web响应的 `ext` 中返回
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extensions: HashMap<String, String>,
 }
 
 impl Display for TardisError {
@@ -28,7 +47,12 @@ impl TardisError {
     fn error(code: &str, msg: &str, locale_code: &str) -> TardisError {
         warn!("[Tardis.Error] {}:{}", code, msg);
         let message = TardisLocale::env_message(if locale_code.trim().is_empty() { code } else { locale_code }, msg);
-        TardisError { code: code.to_string(), message }
+        TardisError {
+            code: code.to_string(),
+            message,
+            source: None,
+            extensions: HashMap::new(),
+        }
     }
 
     pub fn internal_error(msg: &str, locale_code: &str) -> TardisError {
@@ -86,9 +110,25 @@ impl TardisError {
     pub fn wrap(msg: &str, locale_code: &str) -> TardisError {
         Self::error(ERROR_DEFAULT_CODE, msg, locale_code)
     }
+
+    /// Attach the underlying error / 附加底层错误
+    pub fn with_source(mut self, source: impl std::error::Error + Send + Sync + 'static) -> TardisError {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Attach a detail, e.g. the validation error of a field / 附加详情，如字段的校验错误
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<String>) -> TardisError {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
 }
 
-impl std::error::Error for TardisError {}
+impl std::error::Error for TardisError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_deref().map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
 
 pub struct TardisErrorWithExt {
     pub ext: String,
@@ -101,7 +141,12 @@ impl TardisErrorWithExt {
         let code = format!("{}-{}-{}-{}", code, self.ext, obj_name, obj_opt);
         warn!("[Tardis.Error] {}:{}", code, msg);
         let message = self.localized_message(if locale_code.trim().is_empty() { &code } else { locale_code }, msg);
-        TardisError { code, message }
+        TardisError {
+            code,
+            message,
+            source: None,
+            extensions: HashMap::new(),
+        }
     }
 
     pub fn localized_message(&self, locale_code: &str, msg: &str) -> String {
@@ -158,31 +203,31 @@ impl From<&dyn std::error::Error> for TardisError {
 
 impl From<std::io::Error> for TardisError {
     fn from(error: std::io::Error) -> Self {
-        TardisError::io_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::io_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<Utf8Error> for TardisError {
     fn from(error: Utf8Error) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<FromUtf8Error> for TardisError {
     fn from(error: FromUtf8Error) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<url::ParseError> for TardisError {
     fn from(error: url::ParseError) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<ParseIntError> for TardisError {
     fn from(error: ParseIntError) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
@@ -194,25 +239,25 @@ impl From<Infallible> for TardisError {
 
 impl From<base64::DecodeError> for TardisError {
     fn from(error: base64::DecodeError) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<hex::FromHexError> for TardisError {
     fn from(error: hex::FromHexError) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<regex::Error> for TardisError {
     fn from(error: regex::Error) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
 impl From<TryFromIntError> for TardisError {
     fn from(error: TryFromIntError) -> Self {
-        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
     }
 }
 
@@ -236,6 +281,20 @@ impl From<tracing_subscriber::reload::Error> for TardisError {
 
 impl From<SystemTimeError> for TardisError {
     fn from(error: SystemTimeError) -> Self {
+        TardisError::internal_error(&format!("[Tardis.Basic] {error}"), "").with_source(error)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Error> for TardisError {
+    fn from(error: rust_decimal::Error) -> Self {
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl From<prometheus::Error> for TardisError {
+    fn from(error: prometheus::Error) -> Self {
         TardisError::internal_error(&format!("[Tardis.Basic] {error}"), "")
     }
 }
@@ -253,3 +312,24 @@ impl From<kube::Error> for TardisError {
         TardisError::internal_error(&format!("[Tardis.Basic] {error}"), "")
     }
 }
+
+#[cfg(feature = "export")]
+impl From<csv::Error> for TardisError {
+    fn from(error: csv::Error) -> Self {
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+    }
+}
+
+#[cfg(feature = "export-xlsx")]
+impl From<rust_xlsxwriter::XlsxError> for TardisError {
+    fn from(error: rust_xlsxwriter::XlsxError) -> Self {
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+    }
+}
+
+#[cfg(feature = "export-xlsx")]
+impl From<calamine::XlsxError> for TardisError {
+    fn from(error: calamine::XlsxError) -> Self {
+        TardisError::format_error(&format!("[Tardis.Basic] {error}"), "")
+    }
+}
@@ -0,0 +1,101 @@
+//! Error reporting to Sentry / 上报错误到Sentry
+//!
+//! When the `sentry` feature is enabled and `fw.sentry` is configured, the [`TardisError`](crate::basic::error::TardisError)s
+//! whose code is not less than `min_code` are reported with the error code, the trace id (as the request id, if the `tracing` feature is enabled),
+//! the request info (method, path and `X-Request-Id` header) and the application info.
+//!
+//! 启用 `sentry` 特性并配置 `fw.sentry` 后，错误码不小于 `min_code` 的 [`TardisError`](crate::basic::error::TardisError) 会被上报，
+//! 包含错误码、链路id（作为请求id，需启用 `tracing` 特性）、请求信息（方法、路径及 `X-Request-Id` 请求头）及应用信息.
+//!
+//! The errors are reported where they are returned, not where they are created, so the handled or retried errors aren't reported:
+//! the web server reports the errors of the responses, the other errors can be reported by [`report`] .
+//!
+//! 错误在返回时上报而非创建时，因此已处理或重试的错误不会上报：Web服务上报响应中的错误，其他错误可通过 [`report`] 上报.
+use std::collections::BTreeMap;
+use std::sync::{Mutex, RwLock};
+
+use tracing::{info, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::fetch_profile;
+use crate::config::config_dto::{AppConfig, SentryConfig};
+use crate::tardis_static;
+
+tardis_static! {
+    reporter: RwLock<Option<ReporterState>>;
+    client_guard: Mutex<Option<sentry::ClientInitGuard>>;
+}
+
+struct ReporterState {
+    min_code: u16,
+}
+
+/// Init the sentry client, the previous client is closed / 初始化sentry客户端，原客户端会被关闭
+pub(crate) fn init(config: &SentryConfig, app: &AppConfig) {
+    let release = config.release.clone().unwrap_or_else(|| format!("{}@{}", app.id, app.version));
+    let environment = config.environment.clone().unwrap_or_else(fetch_profile);
+    let guard = sentry::init((
+        config.dsn.as_str(),
+        sentry::ClientOptions {
+            release: Some(release.into()),
+            environment: if environment.is_empty() { None } else { Some(environment.into()) },
+            ..Default::default()
+        },
+    ));
+    sentry::configure_scope(|scope| {
+        scope.set_context(
+            "app",
+            sentry::protocol::Context::Other(BTreeMap::from([
+                ("id".to_string(), app.id.clone().into()),
+                ("name".to_string(), app.name.clone().into()),
+                ("inst".to_string(), app.inst.clone().into()),
+            ])),
+        );
+    });
+    match (reporter().write(), client_guard().lock()) {
+        (Ok(mut reporter), Ok(mut client_guard)) => {
+            *reporter = Some(ReporterState { min_code: config.min_code });
+            *client_guard = Some(guard);
+            info!("[Tardis.ErrorReport] Sentry initialized.");
+        }
+        _ => warn!("[Tardis.ErrorReport] Sentry initialize error: lock poisoned"),
+    }
+}
+
+/// Report the error if its code is not less than `min_code` / 若错误码不小于 `min_code` 则上报该错误
+///
+/// The codes not starting with a number (e.g. `-1`) are treated as below `min_code` .
+///
+/// 不以数字开头的错误码（如 `-1` ）视为小于 `min_code` .
+pub fn report(error: &TardisError) {
+    report_with_tags(error, &[]);
+}
+
+/// Report the error with the tags, e.g. the request info / 附带标签（如请求信息）上报错误
+pub(crate) fn report_with_tags(error: &TardisError, tags: &[(&str, &str)]) {
+    let Some(min_code) = reporter().read().ok().and_then(|reporter| reporter.as_ref().map(|reporter| reporter.min_code)) else {
+        return;
+    };
+    let level = error.code.get(0..3).and_then(|level| level.parse::<u16>().ok());
+    if !level.is_some_and(|level| level >= min_code) {
+        return;
+    }
+    sentry::with_scope(
+        |scope| {
+            scope.set_tag("code", &error.code);
+            for (key, value) in tags {
+                scope.set_tag(key, value);
+            }
+            #[cfg(feature = "tracing")]
+            {
+                use opentelemetry::trace::TraceContextExt;
+                use tracing_opentelemetry::OpenTelemetrySpanExt;
+                let trace_id = tracing::Span::current().context().span().span_context().trace_id();
+                if trace_id != opentelemetry::trace::TraceId::INVALID {
+                    scope.set_tag("trace_id", trace_id.to_string());
+                }
+            }
+        },
+        || sentry::capture_message(&error.message, sentry::Level::Error),
+    );
+}
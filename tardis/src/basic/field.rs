@@ -1,8 +1,17 @@
+#[cfg(feature = "html-sanitize")]
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::ops::Deref;
 
+use chrono::{NaiveDate, NaiveDateTime};
 use regex::Regex;
+use url::Url;
 
 use crate::{
+    basic::{error::TardisError, result::TardisResult},
+    serde::{Deserialize, Serialize},
     tardis_static,
     utils::mapper::{Base64Decode, Base64Encode, Mapped, Trim},
 };
@@ -13,11 +22,88 @@ tardis_static! {
         .expect("Regular parsing error");
     pub r_code_ncs: Regex = Regex::new(r"^[a-z0-9_]+$").expect("Regular parsing error");
     pub r_code_cs: Regex = Regex::new(r"^[A-Za-z0-9_]+$").expect("Regular parsing error");
+    pub r_mac: Regex = Regex::new(r"^(?:[0-9A-Fa-f]{2}:){5}[0-9A-Fa-f]{2}$|^(?:[0-9A-Fa-f]{2}-){5}[0-9A-Fa-f]{2}$").expect("Regular parsing error");
+    pub r_phone_hk: Regex = Regex::new(r"^[4-79]\d{7}$").expect("Regular parsing error");
+    pub r_phone_mo: Regex = Regex::new(r"^6\d{7}$").expect("Regular parsing error");
+    pub r_phone_tw: Regex = Regex::new(r"^09\d{8}$").expect("Regular parsing error");
+    pub r_phone_us: Regex = Regex::new(r"^[2-9]\d{2}[2-9]\d{6}$").expect("Regular parsing error");
+    pub r_phone_sg: Regex = Regex::new(r"^[89]\d{7}$").expect("Regular parsing error");
+    pub r_id_card_cn: Regex = Regex::new(r"^\d{17}[\dXx]$").expect("Regular parsing error");
+    pub r_id_card_hk: Regex = Regex::new(r"^[A-Z]{1,2}\d{6}\(?[\dA]\)?$").expect("Regular parsing error");
+    pub r_id_card_mo: Regex = Regex::new(r"^[157]\d{6}\(?\d\)?$").expect("Regular parsing error");
+    pub r_id_card_tw: Regex = Regex::new(r"^[A-Z][12]\d{8}$").expect("Regular parsing error");
+    pub r_id_card_us: Regex = Regex::new(r"^(\d{3})-?(\d{2})-?(\d{4})$").expect("Regular parsing error");
+    pub r_id_card_sg: Regex = Regex::new(r"^[STFGM]\d{7}[A-Z]$").expect("Regular parsing error");
+}
+
+static MASK_CHAR: char = '*';
+
+static ID_CARD_CN_WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+static ID_CARD_CN_CHECK_CODES: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// Region used by the region-specific validation / 区域相关校验所使用的区域
+///
+/// The phone number is the local format without the country/region calling code.
+///
+/// 手机号为不带国家/地区区号的本地格式.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FieldRegion {
+    /// Mainland China / 中国大陆
+    CN,
+    /// Hong Kong, China / 中国香港
+    HK,
+    /// Macao, China / 中国澳门
+    MO,
+    /// Taiwan, China / 中国台湾
+    TW,
+    /// United States, the identity number is SSN / 美国，身份证号为SSN
+    US,
+    /// Singapore / 新加坡
+    SG,
 }
 
 static BASE62: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
 static BASE36: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
 
+static NANOID_URL_SAFE: [char; 64] = [
+    '_', '-', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w',
+    'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+static NANOID_LOWERCASE: [char; 36] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y',
+    'z',
+];
+// Excludes lookalike characters: `0` `O` `o` `1` `I` `i` `l` `L` `5` `S` `s` `2` `Z` `z` `u` `v` `V` .
+static NANOID_NO_LOOKALIKE: [char; 45] = [
+    '3', '4', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'j', 'k', 'm', 'n', 'p', 'q', 'r', 't', 'w', 'x', 'y', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K',
+    'M', 'N', 'P', 'Q', 'R', 'T', 'U', 'W', 'X', 'Y',
+];
+
+/// Alphabet used to generate NanoId / 生成NanoId所使用的字符集
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanoidAlphabet<'a> {
+    /// URL-safe characters `A-Za-z0-9_-` (the nanoid default) / URL安全字符（nanoid默认字符集）
+    UrlSafe,
+    /// Lowercase letters and numbers `a-z0-9` / 小写字母及数字
+    Lowercase,
+    /// Letters and numbers without easily confused characters, suitable for share codes, order display numbers, etc. /
+    /// 去除易混淆字符的字母及数字，适用于分享码、订单展示号等
+    NoLookalike,
+    /// Custom alphabet / 自定义字符集
+    Custom(&'a [char]),
+}
+
+impl<'a> NanoidAlphabet<'a> {
+    pub fn chars(&self) -> &'a [char] {
+        match self {
+            NanoidAlphabet::UrlSafe => &NANOID_URL_SAFE,
+            NanoidAlphabet::Lowercase => &NANOID_LOWERCASE,
+            NanoidAlphabet::NoLookalike => &NANOID_NO_LOOKALIKE,
+            NanoidAlphabet::Custom(chars) => chars,
+        }
+    }
+}
+
 /// Field handle / 字段处理
 ///
 /// Provides some common regular, Id generation and other functions.
@@ -39,6 +125,7 @@ static BASE36: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
 /// assert!(!TardisFuns::field.is_code_ncs("Adw834_dfds"));
 /// assert_eq!(TardisFuns::field.nanoid().len(), 21);
 /// assert_eq!(TardisFuns::field.nanoid_len(4).len(), 4);
+/// assert_eq!(TardisFuns::field.nanoid_by(8, NanoidAlphabet::NoLookalike).unwrap().len(), 8);
 /// ```
 pub struct TardisField;
 
@@ -48,6 +135,43 @@ impl TardisField {
         r_phone().is_match(phone)
     }
 
+    /// Determine if it is a cell phone number of the specified region / 判断是否是指定区域的手机号
+    pub fn is_phone_by_region(&self, phone: &str, region: FieldRegion) -> bool {
+        match region {
+            FieldRegion::CN => r_phone().is_match(phone),
+            FieldRegion::HK => r_phone_hk().is_match(phone),
+            FieldRegion::MO => r_phone_mo().is_match(phone),
+            FieldRegion::TW => r_phone_tw().is_match(phone),
+            FieldRegion::US => r_phone_us().is_match(phone),
+            FieldRegion::SG => r_phone_sg().is_match(phone),
+        }
+    }
+
+    /// Determine if it is an identity card number of the specified region / 判断是否是指定区域的身份证号
+    ///
+    /// The mainland China identity card number also verifies the birth date and check code.
+    ///
+    /// 中国大陆的身份证号还会校验出生日期及校验码.
+    pub fn is_id_card_by_region(&self, id_card: &str, region: FieldRegion) -> bool {
+        match region {
+            FieldRegion::CN => {
+                if !r_id_card_cn().is_match(id_card) || NaiveDate::parse_from_str(&id_card[6..14], "%Y%m%d").is_err() {
+                    return false;
+                }
+                let sum: u32 = id_card.chars().take(17).zip(ID_CARD_CN_WEIGHTS.iter()).map(|(c, w)| c.to_digit(10).unwrap_or_default() * w).sum();
+                id_card.chars().last().map(|c| c.to_ascii_uppercase()) == Some(ID_CARD_CN_CHECK_CODES[(sum % 11) as usize])
+            }
+            FieldRegion::HK => r_id_card_hk().is_match(id_card),
+            FieldRegion::MO => r_id_card_mo().is_match(id_card),
+            FieldRegion::TW => r_id_card_tw().is_match(id_card),
+            FieldRegion::US => r_id_card_us()
+                .captures(id_card)
+                .map(|caps| !matches!(&caps[1], "000" | "666") && !caps[1].starts_with('9') && &caps[2] != "00" && &caps[3] != "0000")
+                .unwrap_or(false),
+            FieldRegion::SG => r_id_card_sg().is_match(id_card),
+        }
+    }
+
     /// Determine if it is a email / 判断是否是邮箱
     pub fn is_mail(&self, mail: &str) -> bool {
         r_mail().is_match(mail)
@@ -65,6 +189,88 @@ impl TardisField {
         r_code_ncs().is_match(str)
     }
 
+    /// Determine if it is an IPv4 address / 判断是否是IPv4地址
+    pub fn is_ipv4(&self, str: &str) -> bool {
+        str.parse::<Ipv4Addr>().is_ok()
+    }
+
+    /// Determine if it is an IPv6 address / 判断是否是IPv6地址
+    pub fn is_ipv6(&self, str: &str) -> bool {
+        str.parse::<Ipv6Addr>().is_ok()
+    }
+
+    /// Determine if it is an IPv4 or IPv6 address / 判断是否是IPv4或IPv6地址
+    pub fn is_ip(&self, str: &str) -> bool {
+        str.parse::<IpAddr>().is_ok()
+    }
+
+    /// Determine if it is a http(s) url / 判断是否是http(s)地址
+    pub fn is_url(&self, str: &str) -> bool {
+        Url::parse(str).map(|url| (url.scheme() == "http" || url.scheme() == "https") && url.has_host()).unwrap_or(false)
+    }
+
+    /// Determine if it is a MAC address, separated by `:` or `-` / 判断是否是以 `:` 或 `-` 分隔的MAC地址
+    pub fn is_mac(&self, str: &str) -> bool {
+        r_mac().is_match(str)
+    }
+
+    /// Determine if it is a date string of the specified format / 判断是否是指定格式的日期字符串
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - date string / 日期字符串
+    /// * `format` - date format, E.g. `%Y-%m-%d` / 日期格式，如 `%Y-%m-%d`
+    pub fn is_date(&self, str: &str, format: &str) -> bool {
+        NaiveDate::parse_from_str(str, format).is_ok()
+    }
+
+    /// Determine if it is a date time string of the specified format / 判断是否是指定格式的日期时间字符串
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - date time string / 日期时间字符串
+    /// * `format` - date time format, E.g. `%Y-%m-%d %H:%M:%S` / 日期时间格式，如 `%Y-%m-%d %H:%M:%S`
+    pub fn is_datetime(&self, str: &str, format: &str) -> bool {
+        NaiveDateTime::parse_from_str(str, format).is_ok()
+    }
+
+    /// Sanitize HTML by the specified policy / 按指定策略清理HTML
+    ///
+    /// Used to defend against stored XSS in user-generated rich text, tags and attributes not allowed by the policy are removed.
+    ///
+    /// 用于防御用户生成的富文本中的存储型XSS，策略中未允许的标签及属性都会被移除.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// use tardis::basic::field::HtmlSanitizePolicy;
+    /// assert_eq!(
+    ///     TardisFuns::field.sanitize_html(r#"<p onclick="x()">hi<script>alert(1)</script></p>"#, &HtmlSanitizePolicy::default()).unwrap(),
+    ///     "<p>hi</p>"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the policy is invalid, see [`HtmlSanitizePolicy::validate`] .
+    ///
+    /// 策略无效时返回错误，见 [`HtmlSanitizePolicy::validate`] .
+    #[cfg(feature = "html-sanitize")]
+    pub fn sanitize_html(&self, input: &str, policy: &HtmlSanitizePolicy) -> TardisResult<String> {
+        policy.validate()?;
+        let mut builder = ammonia::Builder::empty();
+        builder
+            .tags(policy.tags.iter().map(|tag| tag.as_str()).collect())
+            .generic_attributes(policy.generic_attributes.iter().map(|attr| attr.as_str()).collect())
+            .tag_attributes(policy.tag_attributes.iter().map(|(tag, attrs)| (tag.as_str(), attrs.iter().map(|attr| attr.as_str()).collect())).collect())
+            .url_schemes(policy.url_schemes.iter().map(|scheme| scheme.as_str()).collect())
+            .allowed_classes(policy.allowed_classes.iter().map(|(tag, classes)| (tag.as_str(), classes.iter().map(|class| class.as_str()).collect())).collect())
+            .link_rel(policy.link_rel.as_deref())
+            .strip_comments(true);
+        Ok(builder.clean(input).to_string())
+    }
+
     /// Generate NanoId / 生成NanoId
     pub fn nanoid(&self) -> String {
         nanoid::nanoid!()
@@ -80,6 +286,44 @@ impl TardisField {
         nanoid::nanoid!(len, alphabet)
     }
 
+    /// Generate NanoId by the specified alphabet / 使用指定的字符集生成NanoId
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - length of the NanoId / NanoId的长度
+    /// * `alphabet` - alphabet preset or custom alphabet / 预设或自定义的字符集
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// use tardis::basic::field::NanoidAlphabet;
+    /// let share_code = TardisFuns::field.nanoid_by(8, NanoidAlphabet::NoLookalike)?;
+    /// let order_no = TardisFuns::field.nanoid_by(12, NanoidAlphabet::Lowercase)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The length must be greater than 0 and the custom alphabet must have 1 to 255 characters, otherwise nanoid panics or never returns.
+    ///
+    /// 长度须大于0，自定义字符集须包含1至255个字符，否则nanoid会panic或无法返回.
+    pub fn nanoid_by(&self, len: usize, alphabet: NanoidAlphabet) -> TardisResult<String> {
+        if len == 0 {
+            return Err(TardisError::bad_request(
+                "[Tardis.Field] The length of the NanoId must be greater than 0",
+                "400-tardis-field-nanoid-len-invalid",
+            ));
+        }
+        let chars = alphabet.chars();
+        if chars.is_empty() || chars.len() > u8::MAX as usize {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.Field] The alphabet of the NanoId must have 1 to 255 characters, got {}", chars.len()),
+                "400-tardis-field-nanoid-alphabet-invalid",
+            ));
+        }
+        Ok(nanoid::nanoid!(len, chars))
+    }
+
     /// Generate self-incrementing ID based on base62 code / 根据base62编码生成自增ID
     ///
     /// `BASE62` refers to Base64 encoding that does not contain `+`
@@ -168,6 +412,412 @@ impl TardisField {
             None
         }
     }
+
+    /// Mask phone number, keep the first 3 and last 4 digits / 手机号脱敏，保留前3位及后4位
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// assert_eq!(TardisFuns::field.mask_phone("18657120202"), "186****0202");
+    /// ```
+    pub fn mask_phone(&self, phone: &str) -> String {
+        self.mask_keep(phone, 3, 4)
+    }
+
+    /// Mask email, keep the first and last characters of the name and the domain / 邮箱脱敏，保留名称的首尾字符及域名
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// assert_eq!(TardisFuns::field.mask_email("gudaoxuri@gmail.com"), "g*******i@gmail.com");
+    /// ```
+    pub fn mask_email(&self, email: &str) -> String {
+        match email.rsplit_once('@') {
+            Some((name, domain)) if name.chars().count() <= 2 => format!("{}@{domain}", self.mask_keep(name, 1, 0)),
+            Some((name, domain)) => format!("{}@{domain}", self.mask_keep(name, 1, 1)),
+            None => self.mask_keep(email, 1, 1),
+        }
+    }
+
+    /// Mask identity card number, keep the first 3 and last 4 characters / 身份证号脱敏，保留前3位及后4位
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// assert_eq!(TardisFuns::field.mask_id_card("11010519491231002X"), "110***********002X");
+    /// ```
+    pub fn mask_id_card(&self, id_card: &str) -> String {
+        self.mask_keep(id_card, 3, 4)
+    }
+
+    /// Mask by the custom pattern, all capture groups of the pattern are masked / 按自定义规则脱敏，规则中的所有捕获组都会被脱敏
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use regex::Regex;
+    /// use tardis::TardisFuns;
+    /// let pattern = Regex::new(r"^\w{2}(\w+)\w{2}$").unwrap();
+    /// assert_eq!(TardisFuns::field.mask_custom("6222021234567890", &pattern), "62************90");
+    /// ```
+    pub fn mask_custom(&self, str: &str, pattern: &Regex) -> String {
+        let mut masked = String::with_capacity(str.len());
+        let mut last_end = 0;
+        for caps in pattern.captures_iter(str) {
+            for group in caps.iter().skip(1).flatten() {
+                if group.start() < last_end {
+                    continue;
+                }
+                masked.push_str(&str[last_end..group.start()]);
+                masked.extend(std::iter::repeat(MASK_CHAR).take(group.as_str().chars().count()));
+                last_end = group.end();
+            }
+        }
+        masked.push_str(&str[last_end..]);
+        masked
+    }
+
+    /// Mask the middle characters, keep the specified number of characters at the beginning and end /
+    /// 脱敏中间字符，保留开头及结尾指定数量的字符
+    ///
+    /// When the string is too short, only the first character is kept.
+    ///
+    /// 当字符串过短时，仅保留首字符.
+    pub fn mask_keep(&self, str: &str, keep_prefix: usize, keep_suffix: usize) -> String {
+        let chars = str.chars().collect::<Vec<_>>();
+        let (keep_prefix, keep_suffix) = if chars.len() > keep_prefix + keep_suffix {
+            (keep_prefix, keep_suffix)
+        } else {
+            (chars.len().min(1), 0)
+        };
+        chars.iter().enumerate().map(|(idx, c)| if idx < keep_prefix || idx >= chars.len() - keep_suffix { *c } else { MASK_CHAR }).collect()
+    }
+}
+
+/// Kinds of data masking used by [`Masked`] / [`Masked`] 所使用的脱敏方式
+pub trait Masker {
+    fn mask(value: &str) -> String;
+}
+
+/// Mask as phone number / 按手机号脱敏
+pub struct MaskPhone;
+
+/// Mask as email / 按邮箱脱敏
+pub struct MaskEmail;
+
+/// Mask as identity card number / 按身份证号脱敏
+pub struct MaskIdCard;
+
+impl Masker for MaskPhone {
+    fn mask(value: &str) -> String {
+        TardisField.mask_phone(value)
+    }
+}
+
+impl Masker for MaskEmail {
+    fn mask(value: &str) -> String {
+        TardisField.mask_email(value)
+    }
+}
+
+impl Masker for MaskIdCard {
+    fn mask(value: &str) -> String {
+        TardisField.mask_id_card(value)
+    }
+}
+
+/// String type that is masked when serialized or printed / 序列化或打印时脱敏的字符串类型
+///
+/// The original value is still available through [`Deref`] or [`Masked::into_inner`],
+/// only the output of [serde] serialization, [`Display`] and [`Debug`] is masked, so that logs and list APIs consistently hide sensitive data.
+///
+/// 原始值仍可通过 [`Deref`] 或 [`Masked::into_inner`] 获取，仅 [serde] 序列化、 [`Display`] 及 [`Debug`] 的输出会被脱敏，从而保证日志及列表接口一致地隐藏敏感数据.
+///
+/// # Examples
+/// ```ignore
+/// use serde::{Serialize,Deserialize};
+/// use tardis::basic::field::{MaskedPhone, MaskedEmail};
+/// #[derive(Serialize, Deserialize, Debug)]
+/// struct AccountResp {
+///     phone: MaskedPhone,
+///     email: MaskedEmail,
+/// }
+/// ```
+pub struct Masked<M: Masker> {
+    inner: String,
+    masker: PhantomData<M>,
+}
+
+pub type MaskedPhone = Masked<MaskPhone>;
+pub type MaskedEmail = Masked<MaskEmail>;
+pub type MaskedIdCard = Masked<MaskIdCard>;
+
+impl<M: Masker> Masked<M> {
+    pub fn new(value: impl Into<String>) -> Self {
+        Masked {
+            inner: value.into(),
+            masker: PhantomData,
+        }
+    }
+
+    /// Get the masked value / 获取脱敏后的值
+    pub fn masked(&self) -> String {
+        M::mask(&self.inner)
+    }
+
+    /// Take the original value / 获取原始值
+    pub fn into_inner(self) -> String {
+        self.inner
+    }
+}
+
+impl<M: Masker> Clone for Masked<M> {
+    fn clone(&self) -> Self {
+        Masked::new(self.inner.clone())
+    }
+}
+
+impl<M: Masker> PartialEq for Masked<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<M: Masker> Eq for Masked<M> {}
+
+impl<M: Masker> Deref for Masked<M> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<M: Masker> From<String> for Masked<M> {
+    fn from(value: String) -> Self {
+        Masked::new(value)
+    }
+}
+
+impl<M: Masker> From<&str> for Masked<M> {
+    fn from(value: &str) -> Self {
+        Masked::new(value)
+    }
+}
+
+impl<M: Masker> Display for Masked<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.masked())
+    }
+}
+
+impl<M: Masker> Debug for Masked<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.masked(), f)
+    }
+}
+
+impl<M: Masker> Serialize for Masked<M> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.masked())
+    }
+}
+
+impl<'de, M: Masker> Deserialize<'de> for Masked<M> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Masked::new(String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(feature = "web-server")]
+mod web_server_ext {
+    use poem_openapi::types::{ParseFromJSON, ParseResult, ToJSON, Type};
+
+    use super::*;
+
+    impl<M: Masker + Send + Sync> Type for Masked<M> {
+        const IS_REQUIRED: bool = true;
+
+        type RawValueType = String;
+
+        type RawElementValueType = String;
+
+        fn name() -> std::borrow::Cow<'static, str> {
+            String::name()
+        }
+
+        fn schema_ref() -> poem_openapi::registry::MetaSchemaRef {
+            String::schema_ref()
+        }
+
+        // the raw value is the secret, it's not exposed to the validators or the logs
+        fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+            None
+        }
+
+        fn raw_element_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+            Box::new(self.as_raw_value().into_iter())
+        }
+    }
+
+    impl<M: Masker + Send + Sync> ToJSON for Masked<M> {
+        fn to_json(&self) -> Option<serde_json::Value> {
+            Some(serde_json::Value::String(self.masked()))
+        }
+    }
+
+    impl<M: Masker + Send + Sync> ParseFromJSON for Masked<M> {
+        fn parse_from_json(value: Option<serde_json::Value>) -> ParseResult<Self> {
+            Ok(Masked::new(String::parse_from_json(value).map_err(poem_openapi::types::ParseError::propagate)?))
+        }
+    }
+}
+
+/// HTML sanitization policy / HTML清理策略
+///
+/// The default policy allows common rich text tags, with `http` `https` `mailto` links only.
+///
+/// 默认策略允许常用的富文本标签，链接仅允许 `http` `https` `mailto` .
+#[cfg(feature = "html-sanitize")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HtmlSanitizePolicy {
+    /// Allowed tags, except `script` `style` and the SVG animation tags / 允许的标签，不能包含 `script` `style` 及SVG动画标签
+    pub tags: HashSet<String>,
+    /// Allowed attributes of all allowed tags / 所有允许的标签均可使用的属性
+    pub generic_attributes: HashSet<String>,
+    /// Allowed attributes of the specified tag / 指定标签允许的属性
+    ///
+    /// Can't be keyed by `script` or `style`, and can't include `rel` of `a` when `link_rel` is set.
+    ///
+    /// 不能以 `script` 或 `style` 为键，设置了 `link_rel` 时不能包含 `a` 的 `rel` 属性.
+    pub tag_attributes: HashMap<String, HashSet<String>>,
+    /// Allowed classes of the specified tag / 指定标签允许的class
+    ///
+    /// The `class` attribute of these tags can't be allowed by `generic_attributes` or `tag_attributes` at the same time.
+    ///
+    /// 不能同时通过 `generic_attributes` 或 `tag_attributes` 允许这些标签的 `class` 属性.
+    pub allowed_classes: HashMap<String, HashSet<String>>,
+    /// Allowed url schemes of `href` `src` etc. / `href` `src` 等属性允许的url协议
+    pub url_schemes: HashSet<String>,
+    /// The `rel` attribute added to all links / 添加到所有链接的 `rel` 属性
+    pub link_rel: Option<String>,
+}
+
+#[cfg(feature = "html-sanitize")]
+impl Default for HtmlSanitizePolicy {
+    fn default() -> Self {
+        let to_set = |items: &[&str]| items.iter().map(|item| item.to_string()).collect::<HashSet<_>>();
+        HtmlSanitizePolicy {
+            tags: to_set(&[
+                "a",
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "del",
+                "em",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "li",
+                "ol",
+                "p",
+                "pre",
+                "s",
+                "span",
+                "strong",
+                "sub",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "th",
+                "thead",
+                "tr",
+                "u",
+                "ul",
+            ]),
+            generic_attributes: HashSet::new(),
+            tag_attributes: HashMap::from([
+                ("a".to_string(), to_set(&["href", "title"])),
+                ("img".to_string(), to_set(&["src", "alt", "title", "width", "height"])),
+                ("td".to_string(), to_set(&["colspan", "rowspan"])),
+                ("th".to_string(), to_set(&["colspan", "rowspan"])),
+            ]),
+            allowed_classes: HashMap::new(),
+            url_schemes: to_set(&["http", "https", "mailto"]),
+            link_rel: Some("noopener noreferrer".to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "html-sanitize")]
+impl HtmlSanitizePolicy {
+    /// Policy that removes all tags and keeps only the text / 移除所有标签仅保留文本的策略
+    pub fn text_only() -> Self {
+        HtmlSanitizePolicy {
+            tags: HashSet::new(),
+            generic_attributes: HashSet::new(),
+            tag_attributes: HashMap::new(),
+            allowed_classes: HashMap::new(),
+            url_schemes: HashSet::new(),
+            link_rel: None,
+        }
+    }
+
+    /// Validate the policy / 校验策略
+    ///
+    /// The `script` `style` and SVG animation tags can't be allowed, `script` and `style` can't have allowed attributes,
+    /// the `rel` attribute can't be allowed when `link_rel` is set, and the `class` attribute can't be allowed together with `allowed_classes`,
+    /// ammonia panics on these policies.
+    ///
+    /// 不能允许 `script` `style` 及SVG动画标签，不能为 `script` 及 `style` 设置允许的属性，设置了 `link_rel` 时不能允许 `rel` 属性，
+    /// 不能同时允许 `class` 属性及设置 `allowed_classes` ，ammonia在这些策略下会panic.
+    pub fn validate(&self) -> TardisResult<()> {
+        const DENIED_TAGS: [&str; 7] = ["script", "style", "animate", "animateTransform", "animateMotion", "animateColor", "set"];
+        let is_clean_content_tag = |tag: &str| tag.eq_ignore_ascii_case("script") || tag.eq_ignore_ascii_case("style");
+        if let Some(tag) = self.tags.iter().find(|tag| DENIED_TAGS.iter().any(|denied| tag.eq_ignore_ascii_case(denied))) {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.Field] The tag [{tag}] can't be allowed by the HTML sanitization policy"),
+                "400-tardis-field-html-policy-invalid",
+            ));
+        }
+        if let Some(tag) = self.tag_attributes.keys().find(|tag| is_clean_content_tag(tag)) {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.Field] The attributes of tag [{tag}] can't be allowed by the HTML sanitization policy"),
+                "400-tardis-field-html-policy-invalid",
+            ));
+        }
+        if !self.allowed_classes.is_empty()
+            && (self.generic_attributes.contains("class") || self.allowed_classes.keys().any(|tag| self.tag_attributes.get(tag).is_some_and(|attrs| attrs.contains("class"))))
+        {
+            return Err(TardisError::bad_request(
+                "[Tardis.Field] The class attribute can't be allowed when allowed_classes is set in the HTML sanitization policy",
+                "400-tardis-field-html-policy-invalid",
+            ));
+        }
+        if self.link_rel.is_some()
+            && (self.generic_attributes.contains("rel") || self.tag_attributes.iter().any(|(tag, attrs)| tag.eq_ignore_ascii_case("a") && attrs.contains("rel")))
+        {
+            return Err(TardisError::bad_request(
+                "[Tardis.Field] The rel attribute can't be allowed when link_rel is set in the HTML sanitization policy",
+                "400-tardis-field-html-policy-invalid",
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// String types that support auto-trim / 支持自动trim的字符串类型
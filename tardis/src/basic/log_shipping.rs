@@ -0,0 +1,301 @@
+//! Log shipping layers / 日志投递层
+//!
+//! Push log events to Grafana Loki (`log-loki` feature) or Fluentd/fluent-bit (`log-fluentd` feature),
+//! for the environments without node-level log agents.
+//!
+//! 将日志事件推送到 Grafana Loki （ `log-loki` 特性）或 Fluentd/fluent-bit （ `log-fluentd` 特性），适用于没有节点级日志代理的环境.
+//!
+//! Events are queued in a bounded queue and sent in batches by a dedicated thread,
+//! new events are dropped when the queue is full, so that the application is never blocked by the log backend.
+//!
+//! 事件进入有界队列并由专用线程批量发送，队列满时丢弃新事件，从而保证应用不会被日志后端阻塞.
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{warn, Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+#[cfg(feature = "log-fluentd")]
+use crate::config::config_dto::FluentdConfig;
+use crate::config::config_dto::LogShippingBatchConfig;
+#[cfg(feature = "log-loki")]
+use crate::config::config_dto::LokiConfig;
+use crate::utils::retry::RetryPolicy;
+
+thread_local! {
+    // events emitted by the shipping threads (e.g. by the http client) are not shipped, to avoid feedback loops
+    static IN_SHIPPING_THREAD: Cell<bool> = Cell::new(false);
+}
+
+/// Log record to be shipped / 待投递的日志记录
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    #[serde(skip)]
+    pub timestamp: SystemTime,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl LogRecord {
+    fn from_event(event: &Event<'_>) -> LogRecord {
+        let mut record = LogRecord {
+            timestamp: SystemTime::now(),
+            level: event.metadata().level().to_string().to_lowercase(),
+            target: event.metadata().target().to_string(),
+            message: String::new(),
+            fields: BTreeMap::new(),
+        };
+        event.record(&mut record);
+        record
+    }
+
+    #[cfg(feature = "log-fluentd")]
+    fn to_map(&self) -> BTreeMap<&str, &str> {
+        let mut map = self.fields.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect::<BTreeMap<_, _>>();
+        map.insert("level", &self.level);
+        map.insert("target", &self.target);
+        map.insert("message", &self.message);
+        map
+    }
+
+    fn timestamp_nanos(&self) -> u128 {
+        self.timestamp.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default()
+    }
+}
+
+impl Visit for LogRecord {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields.insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+}
+
+/// Log shipping layer / 日志投递层
+///
+/// # Examples
+/// ```ignore
+/// use tardis::basic::log_shipping::LogShippingLayer;
+/// use tardis::config::config_dto::LokiConfig;
+/// let layer = LogShippingLayer::loki(&LokiConfig::builder().url("http://localhost:3100").build())?;
+/// tracing_subscriber::registry().with(layer).init();
+/// ```
+pub struct LogShippingLayer {
+    sender: mpsc::Sender<LogRecord>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl LogShippingLayer {
+    /// Create a layer pushing events to Loki / 创建推送事件到Loki的层
+    #[cfg(feature = "log-loki")]
+    pub fn loki(config: &LokiConfig) -> TardisResult<LogShippingLayer> {
+        let sink = LogSink::Loki {
+            client: reqwest::Client::new(),
+            url: format!("{}/loki/api/v1/push", config.url.trim_end_matches('/')),
+            labels: config.labels.clone(),
+            tenant: config.tenant.clone(),
+        };
+        Self::new(sink, &config.batch)
+    }
+
+    /// Create a layer sending events to Fluentd / 创建发送事件到Fluentd的层
+    #[cfg(feature = "log-fluentd")]
+    pub fn fluentd(config: &FluentdConfig) -> TardisResult<LogShippingLayer> {
+        let sink = LogSink::Fluentd {
+            addr: config.addr.clone(),
+            tag: config.tag.clone(),
+            stream: None,
+        };
+        Self::new(sink, &config.batch)
+    }
+
+    fn new(sink: LogSink, batch: &LogShippingBatchConfig) -> TardisResult<LogShippingLayer> {
+        let (sender, receiver) = mpsc::channel(batch.queue_size.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let worker = LogShippingWorker {
+            sink,
+            receiver,
+            dropped: dropped.clone(),
+            batch_size: batch.size.max(1),
+            batch_interval: Duration::from_millis(batch.interval_ms),
+        };
+        std::thread::Builder::new().name(format!("tardis-log-{}", worker.sink.name())).spawn(move || {
+            IN_SHIPPING_THREAD.with(|in_shipping_thread| in_shipping_thread.set(true));
+            match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(worker.run()),
+                Err(error) => warn!("[Tardis.LogShipping] Create runtime error: {error}"),
+            }
+        })?;
+        Ok(LogShippingLayer { sender, dropped })
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogShippingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if IN_SHIPPING_THREAD.with(|in_shipping_thread| in_shipping_thread.get()) {
+            return;
+        }
+        if self.sender.try_send(LogRecord::from_event(event)).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+struct LogShippingWorker {
+    sink: LogSink,
+    receiver: mpsc::Receiver<LogRecord>,
+    dropped: Arc<AtomicU64>,
+    batch_size: usize,
+    batch_interval: Duration,
+}
+
+impl LogShippingWorker {
+    async fn run(mut self) {
+        let policy = RetryPolicy::<TardisError>::exponential(Duration::from_millis(200), Duration::from_secs(5)).with_max_retries(3);
+        // exit when the layer is dropped (e.g. replaced by a new config) and all the queued events are sent
+        while let Some(record) = self.receiver.recv().await {
+            let mut batch = Vec::with_capacity(self.batch_size);
+            batch.push(record);
+            let deadline = tokio::time::sleep(self.batch_interval);
+            tokio::pin!(deadline);
+            while batch.len() < self.batch_size {
+                tokio::select! {
+                    record = self.receiver.recv() => match record {
+                        Some(record) => batch.push(record),
+                        None => break,
+                    },
+                    _ = &mut deadline => break,
+                }
+            }
+            let start = Instant::now();
+            let mut retries = 0;
+            loop {
+                match self.sink.send(&batch).await {
+                    Ok(_) => break,
+                    Err(error) => match policy.next_delay(retries, start.elapsed(), &error) {
+                        Some(delay) => {
+                            retries += 1;
+                            tokio::time::sleep(delay).await;
+                        }
+                        None => {
+                            warn!("[Tardis.LogShipping] Send {} events to {} error: {error}", batch.len(), self.sink.name());
+                            break;
+                        }
+                    },
+                }
+            }
+            let dropped = self.dropped.swap(0, Ordering::Relaxed);
+            if dropped > 0 {
+                warn!("[Tardis.LogShipping] {dropped} events dropped due to the full queue of {}", self.sink.name());
+            }
+        }
+    }
+}
+
+enum LogSink {
+    #[cfg(feature = "log-loki")]
+    Loki {
+        client: reqwest::Client,
+        url: String,
+        labels: std::collections::HashMap<String, String>,
+        tenant: Option<String>,
+    },
+    #[cfg(feature = "log-fluentd")]
+    Fluentd {
+        addr: String,
+        tag: String,
+        stream: Option<tokio::net::TcpStream>,
+    },
+}
+
+impl LogSink {
+    fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "log-loki")]
+            LogSink::Loki { .. } => "loki",
+            #[cfg(feature = "log-fluentd")]
+            LogSink::Fluentd { .. } => "fluentd",
+        }
+    }
+
+    async fn send(&mut self, records: &[LogRecord]) -> TardisResult<()> {
+        match self {
+            #[cfg(feature = "log-loki")]
+            LogSink::Loki { client, url, labels, tenant } => {
+                // one stream per level
+                let mut streams: BTreeMap<&str, Vec<(String, String)>> = BTreeMap::new();
+                for record in records {
+                    streams.entry(record.level.as_str()).or_default().push((record.timestamp_nanos().to_string(), crate::TardisFuns::json.obj_to_string(record)?));
+                }
+                let streams = streams
+                    .into_iter()
+                    .map(|(level, values)| {
+                        let mut stream = labels.clone();
+                        stream.insert("level".to_string(), level.to_string());
+                        crate::serde_json::json!({ "stream": stream, "values": values })
+                    })
+                    .collect::<Vec<_>>();
+                let mut request = client.post(url.as_str()).json(&crate::serde_json::json!({ "streams": streams }));
+                if let Some(tenant) = tenant {
+                    request = request.header("X-Scope-OrgID", tenant.as_str());
+                }
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    return Err(TardisError::bad_gateway(
+                        &format!("[Tardis.LogShipping] Push to loki error, status: {}", response.status()),
+                        "502-tardis-log-shipping-loki-error",
+                    ));
+                }
+                Ok(())
+            }
+            #[cfg(feature = "log-fluentd")]
+            LogSink::Fluentd { addr, tag, stream } => {
+                use tokio::io::AsyncWriteExt;
+                // forward mode: [tag, [[time, record], ...]]
+                let entries = records.iter().map(|record| ((record.timestamp_nanos() / 1_000_000_000) as u64, record.to_map())).collect::<Vec<_>>();
+                let message = rmp_serde::to_vec(&(tag.as_str(), entries)).map_err(|error| {
+                    TardisError::format_error(
+                        &format!("[Tardis.LogShipping] Encode fluentd message error: {error}"),
+                        "406-tardis-log-shipping-fluentd-error",
+                    )
+                })?;
+                if stream.is_none() {
+                    *stream = Some(tokio::net::TcpStream::connect(addr.as_str()).await?);
+                }
+                let result = match stream.as_mut() {
+                    Some(connected) => connected.write_all(&message).await,
+                    None => Ok(()),
+                };
+                if let Err(error) = result {
+                    // reconnect on the next attempt
+                    *stream = None;
+                    return Err(error.into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
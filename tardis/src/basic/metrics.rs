@@ -0,0 +1,239 @@
+//! Metrics of the built-in clients / 内置客户端的监控指标
+//!
+//! When the `metrics` feature is enabled, the operations of the cache, reldb, mq, search, os and mail clients are recorded to a
+//! [Prometheus](https://prometheus.io) registry with the following metrics:
+//!
+//! 启用 `metrics` 特性后，cache、reldb、mq、search、os 及 mail 客户端的操作会被记录到 Prometheus 注册表中，包含以下指标：
+//!
+//! * `tardis_client_requests_total{client, operation, status}`, status is `ok` or `error`
+//! * `tardis_client_request_duration_seconds{client, operation}`
+//! * `tardis_client_slow_requests_total{client, operation}`, operations exceeding the thresholds of [`SlowOperationConfig`]
+//! * `tardis_http_server_requests_total{module, method, status}` and `tardis_http_server_request_duration_seconds{module, method}`,
+//!   requests of the web server modules, `module` is the module code
+//! * `tardis_http_client_requests_total{host, method, status}` and `tardis_http_client_request_duration_seconds{host, method}`,
+//!   outbound requests of the web client, status is the http status or `error`
+//! * `tardis_mq_messages_total{topic, direction, status}`, direction is `publish` or `consume`
+//!
+//! The metrics are exported on the `/metrics` endpoint by [`add_metrics_endpoint`](crate::web::web_server::TardisWebServer::add_metrics_endpoint).
+//!
+//! 指标通过 [`add_metrics_endpoint`](crate::web::web_server::TardisWebServer::add_metrics_endpoint) 在 `/metrics` 端点导出.
+//!
+//! Regardless of the feature, each operation runs in a `tardis_client` span (a child of the current span) with the `tardis.client` and `tardis.operation` attributes.
+//!
+//! 无论是否启用该特性，每个操作都运行在带有 `tardis.client` 及 `tardis.operation` 属性的 `tardis_client` span（当前span的子span）中.
+//!
+//! Operations exceeding the thresholds of [`SlowOperationConfig`] emit a `WARN` event with the `client`, `operation`, `elapsed_ms` and `threshold_ms` fields.
+//!
+//! 超过 [`SlowOperationConfig`] 阈值的操作会输出带有 `client` 、 `operation` 、 `elapsed_ms` 及 `threshold_ms` 字段的 `WARN` 事件.
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use tracing::{warn, Instrument};
+
+use crate::config::config_dto::SlowOperationConfig;
+use crate::tardis_static;
+
+#[cfg(feature = "metrics")]
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+#[cfg(feature = "metrics")]
+use crate::basic::result::TardisResult;
+
+/// Metrics handle / 监控指标操作
+///
+/// # Examples
+/// ```ignore
+/// use tardis::TardisFuns;
+/// // register custom metrics
+/// TardisFuns::metrics().register(Box::new(my_counter.clone()))?;
+/// // export in the Prometheus text format
+/// let text = TardisFuns::metrics().export()?;
+/// ```
+#[cfg(feature = "metrics")]
+pub struct TardisMetrics {
+    registry: Registry,
+    client_requests: IntCounterVec,
+    client_request_duration: HistogramVec,
+    client_slow_requests: IntCounterVec,
+    http_server_requests: IntCounterVec,
+    http_server_request_duration: HistogramVec,
+    http_client_requests: IntCounterVec,
+    http_client_request_duration: HistogramVec,
+    mq_messages: IntCounterVec,
+}
+
+#[cfg(feature = "metrics")]
+impl TardisMetrics {
+    pub(crate) fn new() -> TardisMetrics {
+        let registry = Registry::new();
+        let client_requests = IntCounterVec::new(
+            Opts::new("tardis_client_requests_total", "Total number of the built-in client operations"),
+            &["client", "operation", "status"],
+        )
+        .expect("[Tardis.Metrics] Invalid client requests metric");
+        let client_request_duration = HistogramVec::new(
+            HistogramOpts::new("tardis_client_request_duration_seconds", "Duration of the built-in client operations in seconds"),
+            &["client", "operation"],
+        )
+        .expect("[Tardis.Metrics] Invalid client request duration metric");
+        let client_slow_requests = IntCounterVec::new(
+            Opts::new("tardis_client_slow_requests_total", "Total number of the slow built-in client operations"),
+            &["client", "operation"],
+        )
+        .expect("[Tardis.Metrics] Invalid client slow requests metric");
+        let http_server_requests = IntCounterVec::new(
+            Opts::new("tardis_http_server_requests_total", "Total number of the requests of the web server"),
+            &["module", "method", "status"],
+        )
+        .expect("[Tardis.Metrics] Invalid http server requests metric");
+        let http_server_request_duration = HistogramVec::new(
+            HistogramOpts::new("tardis_http_server_request_duration_seconds", "Duration of the requests of the web server in seconds"),
+            &["module", "method"],
+        )
+        .expect("[Tardis.Metrics] Invalid http server request duration metric");
+        let http_client_requests = IntCounterVec::new(
+            Opts::new("tardis_http_client_requests_total", "Total number of the outbound requests of the web client"),
+            &["host", "method", "status"],
+        )
+        .expect("[Tardis.Metrics] Invalid http client requests metric");
+        let http_client_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "tardis_http_client_request_duration_seconds",
+                "Duration of the outbound requests of the web client in seconds",
+            ),
+            &["host", "method"],
+        )
+        .expect("[Tardis.Metrics] Invalid http client request duration metric");
+        let mq_messages = IntCounterVec::new(
+            Opts::new("tardis_mq_messages_total", "Total number of the published and consumed messages"),
+            &["topic", "direction", "status"],
+        )
+        .expect("[Tardis.Metrics] Invalid mq messages metric");
+        registry.register(Box::new(client_requests.clone())).expect("[Tardis.Metrics] Failed to register client requests metric");
+        registry.register(Box::new(client_request_duration.clone())).expect("[Tardis.Metrics] Failed to register client request duration metric");
+        registry.register(Box::new(client_slow_requests.clone())).expect("[Tardis.Metrics] Failed to register client slow requests metric");
+        registry.register(Box::new(http_server_requests.clone())).expect("[Tardis.Metrics] Failed to register http server requests metric");
+        registry.register(Box::new(http_server_request_duration.clone())).expect("[Tardis.Metrics] Failed to register http server request duration metric");
+        registry.register(Box::new(http_client_requests.clone())).expect("[Tardis.Metrics] Failed to register http client requests metric");
+        registry.register(Box::new(http_client_request_duration.clone())).expect("[Tardis.Metrics] Failed to register http client request duration metric");
+        registry.register(Box::new(mq_messages.clone())).expect("[Tardis.Metrics] Failed to register mq messages metric");
+        TardisMetrics {
+            registry,
+            client_requests,
+            client_request_duration,
+            client_slow_requests,
+            http_server_requests,
+            http_server_request_duration,
+            http_client_requests,
+            http_client_request_duration,
+            mq_messages,
+        }
+    }
+
+    /// Get the registry / 获取注册表
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Register custom metrics / 注册自定义指标
+    pub fn register(&self, collector: Box<dyn prometheus::core::Collector>) -> TardisResult<()> {
+        Ok(self.registry.register(collector)?)
+    }
+
+    /// Record a client operation / 记录一次客户端操作
+    pub fn record_client(&self, client: &str, operation: &str, success: bool, duration: Duration) {
+        self.client_requests.with_label_values(&[client, operation, if success { "ok" } else { "error" }]).inc();
+        self.client_request_duration.with_label_values(&[client, operation]).observe(duration.as_secs_f64());
+    }
+
+    /// Record a slow client operation / 记录一次客户端慢操作
+    pub fn record_client_slow(&self, client: &str, operation: &str) {
+        self.client_slow_requests.with_label_values(&[client, operation]).inc();
+    }
+
+    /// Record a request of the web server / 记录一次Web服务请求
+    pub fn record_http_server(&self, module: &str, method: &str, status: u16, duration: Duration) {
+        self.http_server_requests.with_label_values(&[module, method, &status.to_string()]).inc();
+        self.http_server_request_duration.with_label_values(&[module, method]).observe(duration.as_secs_f64());
+    }
+
+    /// Record an outbound request of the web client, `status` is `None` if the request failed
+    /// / 记录一次Web客户端的出站请求，请求失败时 `status` 为 `None`
+    pub fn record_http_client(&self, host: &str, method: &str, status: Option<u16>, duration: Duration) {
+        let status = status.map(|status| status.to_string()).unwrap_or_else(|| "error".to_string());
+        self.http_client_requests.with_label_values(&[host, method, &status]).inc();
+        self.http_client_request_duration.with_label_values(&[host, method]).observe(duration.as_secs_f64());
+    }
+
+    /// Record a published or consumed message / 记录一条发布或消费的消息
+    pub fn record_mq_message(&self, topic: &str, direction: &str, success: bool) {
+        self.mq_messages.with_label_values(&[topic, direction, if success { "ok" } else { "error" }]).inc();
+    }
+
+    /// Export all metrics in the Prometheus text format / 以Prometheus文本格式导出所有指标
+    pub fn export(&self) -> TardisResult<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+/// Execute the client operation in a child span of the current span and record its metrics / 在当前span的子span中执行客户端操作并记录其监控指标
+#[allow(dead_code)]
+pub(crate) async fn observe_client<T, E, F>(client: &str, operation: &str, fut: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let span = tracing::info_span!(
+        "tardis_client",
+        otel.name = format!("{client} {operation}"),
+        otel.kind = "client",
+        tardis.client = client,
+        tardis.operation = operation,
+        otel.status_code = tracing::field::Empty,
+    );
+    let fut = async {
+        let result = fut.await;
+        tracing::Span::current().record("otel.status_code", if result.is_ok() { "ok" } else { "error" });
+        result
+    }
+    .instrument(span);
+    let start = Instant::now();
+    let result = fut.await;
+    let elapsed = start.elapsed();
+    #[cfg(feature = "metrics")]
+    crate::TardisFuns::metrics().record_client(client, operation, result.is_ok(), elapsed);
+    check_slow_operation(client, operation, elapsed);
+    result
+}
+
+tardis_static! {
+    slow_operation_config: RwLock<SlowOperationConfig>;
+}
+
+/// Update the slow operation thresholds / 更新慢操作阈值
+pub(crate) fn set_slow_operation_config(config: &SlowOperationConfig) {
+    match slow_operation_config().write() {
+        Ok(mut slow_operation_config) => *slow_operation_config = config.clone(),
+        Err(error) => warn!("[Tardis.Metrics] Update slow operation config error: {error}"),
+    }
+}
+
+/// Emit a warning (and record the metric) if the client operation exceeds the slow threshold / 若客户端操作超过慢操作阈值则输出警告（并记录监控指标）
+pub(crate) fn check_slow_operation(client: &str, operation: &str, elapsed: Duration) {
+    let threshold = slow_operation_config().read().ok().and_then(|config| config.threshold(client));
+    if let Some(threshold) = threshold {
+        if elapsed > threshold {
+            warn!(
+                client,
+                operation,
+                elapsed_ms = elapsed.as_millis() as u64,
+                threshold_ms = threshold.as_millis() as u64,
+                "[Tardis.Metrics] Slow operation, client:{client}, operation:{operation}, elapsed:{elapsed:?}"
+            );
+            #[cfg(feature = "metrics")]
+            crate::TardisFuns::metrics().record_client_slow(client, operation);
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Money and decimal arithmetic / 金额及十进制运算
+//!
+//! Amounts are represented by [`Decimal`] instead of `f64` to avoid precision loss in payment related calculations.
+//!
+//! 金额使用 [`Decimal`] 而非 `f64` 表示，以避免支付相关计算中的精度丢失.
+use std::fmt::{Display, Formatter};
+
+use rust_decimal::prelude::ToPrimitive;
+pub use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::serde::{Deserialize, Serialize};
+
+/// Currency (ISO 4217) / 币种（ISO 4217）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "web-server", derive(poem_openapi::Enum))]
+pub enum Currency {
+    CNY,
+    USD,
+    EUR,
+    GBP,
+    HKD,
+    JPY,
+    KRW,
+    SGD,
+}
+
+impl Currency {
+    /// Currency code / 币种代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::CNY => "CNY",
+            Currency::USD => "USD",
+            Currency::EUR => "EUR",
+            Currency::GBP => "GBP",
+            Currency::HKD => "HKD",
+            Currency::JPY => "JPY",
+            Currency::KRW => "KRW",
+            Currency::SGD => "SGD",
+        }
+    }
+
+    /// Currency symbol / 币种符号
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::CNY => "¥",
+            Currency::USD => "$",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+            Currency::HKD => "HK$",
+            Currency::JPY => "JP¥",
+            Currency::KRW => "₩",
+            Currency::SGD => "S$",
+        }
+    }
+
+    /// Number of digits of the minor unit, E.g. `2` for cents / 最小单位的小数位数，如分为 `2`
+    pub fn minor_units(&self) -> u32 {
+        match self {
+            Currency::JPY | Currency::KRW => 0,
+            _ => 2,
+        }
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Money / 金额
+///
+/// The amount is always kept within the precision of the currency minor unit,
+/// the operations that may produce more digits require an explicit [`RoundingStrategy`].
+///
+/// 金额始终保持在币种最小单位的精度内，可能产生更多小数位的运算需要显式指定 [`RoundingStrategy`] .
+///
+/// Serialized as `{"amount":"12.30","currency":"CNY"}`, the amount is a string to avoid precision loss in JSON.
+///
+/// 序列化为 `{"amount":"12.30","currency":"CNY"}` ，金额使用字符串以避免JSON中的精度丢失.
+///
+/// The deserialized amounts (from JSON, the requests or the database) are checked by [`Money::new`] .
+///
+/// 反序列化的金额（来自JSON、请求或数据库）通过 [`Money::new`] 校验.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::basic::money::{Currency, Money, RoundingStrategy};
+/// let price = Money::from_minor(1999, Currency::CNY);
+/// let total = price.checked_mul(3.into(), RoundingStrategy::MidpointAwayFromZero)?;
+/// assert_eq!(total.format(), "¥59.97");
+/// let parts = total.allocate(&[1, 1, 1])?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawMoney")]
+#[cfg_attr(feature = "reldb-core", derive(sea_orm::FromJsonQueryResult))]
+pub struct Money {
+    #[serde(with = "rust_decimal::serde::str")]
+    amount: Decimal,
+    currency: Currency,
+}
+
+/// Unchecked money, converted by [`Money::new`] / 未校验的金额，通过 [`Money::new`] 转换
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "web-server", derive(poem_openapi::Object), oai(rename = "Money"))]
+struct RawMoney {
+    #[serde(with = "rust_decimal::serde::str")]
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl TryFrom<RawMoney> for Money {
+    type Error = TardisError;
+
+    fn try_from(raw: RawMoney) -> Result<Self, Self::Error> {
+        Money::new(raw.amount, raw.currency)
+    }
+}
+
+impl From<Money> for RawMoney {
+    fn from(money: Money) -> Self {
+        RawMoney {
+            amount: money.amount,
+            currency: money.currency,
+        }
+    }
+}
+
+#[cfg(feature = "web-server")]
+mod web_server_ext {
+    use poem_openapi::registry::{MetaSchemaRef, Registry};
+    use poem_openapi::types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type};
+
+    use super::*;
+
+    impl Type for Money {
+        const IS_REQUIRED: bool = true;
+
+        type RawValueType = Self;
+
+        type RawElementValueType = Self;
+
+        fn name() -> std::borrow::Cow<'static, str> {
+            RawMoney::name()
+        }
+
+        fn schema_ref() -> MetaSchemaRef {
+            RawMoney::schema_ref()
+        }
+
+        fn register(registry: &mut Registry) {
+            RawMoney::register(registry)
+        }
+
+        fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+            Some(self)
+        }
+
+        fn raw_element_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+            Box::new(self.as_raw_value().into_iter())
+        }
+    }
+
+    impl ToJSON for Money {
+        fn to_json(&self) -> Option<serde_json::Value> {
+            RawMoney::from(*self).to_json()
+        }
+    }
+
+    impl ParseFromJSON for Money {
+        fn parse_from_json(value: Option<serde_json::Value>) -> ParseResult<Self> {
+            let raw = RawMoney::parse_from_json(value).map_err(ParseError::propagate)?;
+            Money::try_from(raw).map_err(|error| ParseError::custom(error.message))
+        }
+    }
+}
+
+impl Money {
+    /// Create money, error if the amount exceeds the precision of the currency / 创建金额，金额超出币种精度时报错
+    pub fn new(amount: Decimal, currency: Currency) -> TardisResult<Money> {
+        if amount.normalize().scale() > currency.minor_units() {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.Money] The amount [{amount}] exceeds the precision of currency [{currency}]"),
+                "400-tardis-money-precision-exceeded",
+            ));
+        }
+        Ok(Money {
+            amount: amount.round_dp(currency.minor_units()),
+            currency,
+        })
+    }
+
+    /// Create money and round the amount to the precision of the currency / 创建金额并将金额舍入到币种精度
+    pub fn new_with_rounding(amount: Decimal, currency: Currency, strategy: RoundingStrategy) -> Money {
+        Money {
+            amount: amount.round_dp_with_strategy(currency.minor_units(), strategy),
+            currency,
+        }
+    }
+
+    /// Create money by the amount of minor unit, E.g. cents / 通过最小单位（如分）的数量创建金额
+    pub fn from_minor(minor: i64, currency: Currency) -> Money {
+        Money {
+            amount: Decimal::new(minor, currency.minor_units()),
+            currency,
+        }
+    }
+
+    /// Zero amount of the currency / 指定币种的零金额
+    pub fn zero(currency: Currency) -> Money {
+        Money::from_minor(0, currency)
+    }
+
+    /// Parse money from a decimal string / 从十进制字符串解析金额
+    pub fn parse(amount: &str, currency: Currency) -> TardisResult<Money> {
+        let amount = amount.trim().parse::<Decimal>()?;
+        Money::new(amount, currency)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_sign_negative() && !self.amount.is_zero()
+    }
+
+    /// Amount of minor unit, E.g. cents / 最小单位（如分）的数量
+    pub fn to_minor(&self) -> TardisResult<i64> {
+        self.amount
+            .checked_mul(Decimal::from(10_i64.pow(self.currency.minor_units())))
+            .and_then(|amount| amount.to_i64())
+            .ok_or_else(|| TardisError::bad_request(&format!("[Tardis.Money] The amount [{}] is out of range", self.amount), "400-tardis-money-out-of-range"))
+    }
+
+    pub fn checked_add(&self, other: &Money) -> TardisResult<Money> {
+        self.check_currency(other)?;
+        let amount = self.amount.checked_add(other.amount).ok_or_else(Self::overflow_error)?;
+        Ok(Money { amount, currency: self.currency })
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> TardisResult<Money> {
+        self.check_currency(other)?;
+        let amount = self.amount.checked_sub(other.amount).ok_or_else(Self::overflow_error)?;
+        Ok(Money { amount, currency: self.currency })
+    }
+
+    /// Multiply by a factor, E.g. quantity or discount rate / 乘以系数，如数量或折扣率
+    pub fn checked_mul(&self, factor: Decimal, strategy: RoundingStrategy) -> TardisResult<Money> {
+        let amount = self.amount.checked_mul(factor).ok_or_else(Self::overflow_error)?;
+        Ok(Money::new_with_rounding(amount, self.currency, strategy))
+    }
+
+    /// Divide by a divisor / 除以除数
+    ///
+    /// Use [`allocate`](Self::allocate) when splitting the money so that the parts add up to the total.
+    ///
+    /// 拆分金额时请使用 [`allocate`](Self::allocate) 以保证各部分之和等于总额.
+    pub fn checked_div(&self, divisor: Decimal, strategy: RoundingStrategy) -> TardisResult<Money> {
+        if divisor.is_zero() {
+            return Err(TardisError::bad_request("[Tardis.Money] Division by zero", "400-tardis-money-div-zero"));
+        }
+        let amount = self.amount.checked_div(divisor).ok_or_else(Self::overflow_error)?;
+        Ok(Money::new_with_rounding(amount, self.currency, strategy))
+    }
+
+    /// Allocate the money by ratios without losing any minor unit / 按比例分配金额，不丢失任何最小单位
+    ///
+    /// The remainder is distributed one minor unit at a time from the first part.
+    ///
+    /// 余数从第一部分开始每次分配一个最小单位.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::basic::money::{Currency, Money};
+    /// let parts = Money::from_minor(100, Currency::CNY).allocate(&[1, 1, 1])?;
+    /// // 0.34 + 0.33 + 0.33
+    /// ```
+    pub fn allocate(&self, ratios: &[u32]) -> TardisResult<Vec<Money>> {
+        let total_ratio: u64 = ratios.iter().map(|ratio| *ratio as u64).sum();
+        if total_ratio == 0 {
+            return Err(TardisError::bad_request(
+                "[Tardis.Money] The sum of ratios must be greater than zero",
+                "400-tardis-money-ratio-invalid",
+            ));
+        }
+        let total = self.to_minor()? as i128;
+        let mut parts = ratios.iter().map(|ratio| total * *ratio as i128 / total_ratio as i128).collect::<Vec<_>>();
+        let mut remainder = total - parts.iter().sum::<i128>();
+        let step = if remainder < 0 { -1 } else { 1 };
+        for (part, ratio) in parts.iter_mut().zip(ratios.iter()) {
+            if remainder == 0 {
+                break;
+            }
+            if *ratio > 0 {
+                *part += step;
+                remainder -= step;
+            }
+        }
+        Ok(parts.into_iter().map(|part| Money::from_minor(part as i64, self.currency)).collect())
+    }
+
+    /// Format with currency symbol and thousands separator, E.g. `¥1,234.50` / 使用币种符号及千分位格式化，如 `¥1,234.50`
+    pub fn format(&self) -> String {
+        let digits = format!("{:.*}", self.currency.minor_units() as usize, self.amount.abs());
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+        let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+        for (idx, c) in integer.chars().enumerate() {
+            if idx > 0 && (integer.len() - idx) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        let sign = if self.is_negative() { "-" } else { "" };
+        match fraction {
+            Some(fraction) => format!("{sign}{}{grouped}.{fraction}", self.currency.symbol()),
+            None => format!("{sign}{}{grouped}", self.currency.symbol()),
+        }
+    }
+
+    fn check_currency(&self, other: &Money) -> TardisResult<()> {
+        if self.currency != other.currency {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.Money] Currency mismatch: [{}] and [{}]", self.currency, other.currency),
+                "400-tardis-money-currency-mismatch",
+            ));
+        }
+        Ok(())
+    }
+
+    fn overflow_error() -> TardisError {
+        TardisError::bad_request("[Tardis.Money] Arithmetic overflow", "400-tardis-money-overflow")
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.*} {}", self.currency.minor_units() as usize, self.amount, self.currency)
+    }
+}
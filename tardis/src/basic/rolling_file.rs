@@ -0,0 +1,172 @@
+//! Rolling file writer with retention / 带保留策略的滚动文件写入器
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::config::config_dto::TracingAppenderConfig;
+
+/// Rolling file writer / 滚动文件写入器
+///
+/// Writes to `dir/filename`, the file is renamed to `dir/filename.<time>` (and gzip compressed if enabled)
+/// when the rotation period changes or the size exceeds `max_size`, the oldest rotated files beyond `max_files` are removed.
+///
+/// 写入 `dir/filename` ，当滚动周期变化或大小超过 `max_size` 时，文件被重命名为 `dir/filename.<time>` （启用时进行gzip压缩），
+/// 超出 `max_files` 的最旧的滚动文件会被删除.
+#[derive(Clone)]
+pub struct RollingFileWriter {
+    state: Arc<Mutex<RollingFileState>>,
+}
+
+struct RollingFileState {
+    config: TracingAppenderConfig,
+    file: Option<File>,
+    size: u64,
+    period: Option<String>,
+}
+
+impl RollingFileWriter {
+    pub fn new(config: TracingAppenderConfig) -> Self {
+        RollingFileWriter {
+            state: Arc::new(Mutex::new(RollingFileState {
+                config,
+                file: None,
+                size: 0,
+                period: None,
+            })),
+        }
+    }
+}
+
+impl Write for RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().map_err(|_| io::Error::new(io::ErrorKind::Other, "rolling file lock poisoned"))?;
+        state.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut state = self.state.lock().map_err(|_| io::Error::new(io::ErrorKind::Other, "rolling file lock poisoned"))?;
+        match state.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFileWriter {
+    type Writer = RollingFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+impl RollingFileState {
+    fn active_path(&self) -> PathBuf {
+        self.config.dir.join(&self.config.filename)
+    }
+
+    fn period_of(&self, time: DateTime<Utc>) -> Option<String> {
+        self.config.rotation.period_format().map(|format| time.format(format).to_string())
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.config.dir)?;
+        let path = self.active_path();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        self.size = metadata.len();
+        self.period = self.period_of(metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()));
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+        let now = Utc::now();
+        let period = self.period_of(now);
+        if period != self.period {
+            // name the rotated file by the period it belongs to
+            let suffix = self.period.clone().unwrap_or_else(|| now.format("%Y-%m-%d-%H-%M-%S").to_string());
+            self.rotate(&suffix)?;
+            self.period = period;
+        } else if self.config.max_size.map(|max_size| self.size > 0 && self.size + buf.len() as u64 > max_size).unwrap_or(false) {
+            self.rotate(&now.format("%Y-%m-%d-%H-%M-%S").to_string())?;
+        }
+        let file = self.file.as_mut().ok_or_else(|| io::Error::new(io::ErrorKind::Other, "rolling file not opened"))?;
+        let written = file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn rotate(&mut self, suffix: &str) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let active_path = self.active_path();
+        if self.size > 0 {
+            let rotated_path = self.available_path(suffix);
+            fs::rename(&active_path, &rotated_path)?;
+            if self.config.compress {
+                compress(&rotated_path)?;
+            }
+            self.cleanup()?;
+        }
+        self.file = Some(OpenOptions::new().create(true).append(true).open(&active_path)?);
+        self.size = 0;
+        Ok(())
+    }
+
+    fn available_path(&self, suffix: &str) -> PathBuf {
+        let filename = self.config.filename.to_string_lossy();
+        let mut idx = 0;
+        loop {
+            let name = if idx == 0 {
+                format!("{filename}.{suffix}")
+            } else {
+                format!("{filename}.{suffix}.{idx}")
+            };
+            let path = self.config.dir.join(&name);
+            if !path.exists() && !self.config.dir.join(format!("{name}.gz")).exists() {
+                return path;
+            }
+            idx += 1;
+        }
+    }
+
+    fn cleanup(&self) -> io::Result<()> {
+        let Some(max_files) = self.config.max_files else {
+            return Ok(());
+        };
+        let prefix = format!("{}.", self.config.filename.to_string_lossy());
+        let mut rotated_files = fs::read_dir(&self.config.dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .filter_map(|entry| entry.metadata().and_then(|metadata| metadata.modified()).ok().map(|modified| (modified, entry.path())))
+            .collect::<Vec<_>>();
+        if rotated_files.len() <= max_files {
+            return Ok(());
+        }
+        rotated_files.sort_by(|(a, a_path), (b, b_path)| a.cmp(b).then_with(|| a_path.cmp(b_path)));
+        let remove_count = rotated_files.len() - max_files;
+        for (_, path) in rotated_files.into_iter().take(remove_count) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn compress(path: &Path) -> io::Result<()> {
+    let mut gz_path = path.as_os_str().to_owned();
+    gz_path.push(".gz");
+    let mut source = File::open(path)?;
+    let mut encoder = flate2::write::GzEncoder::new(File::create(gz_path)?, flate2::Compression::default());
+    io::copy(&mut source, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
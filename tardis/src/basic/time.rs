@@ -0,0 +1,216 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+/// Supported formats of date time without timezone / 支持的不带时区的日期时间格式
+static NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M",
+    "%Y%m%d%H%M%S",
+];
+
+/// Supported formats of date / 支持的日期格式
+static NAIVE_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+
+/// Time handle / 时间处理
+///
+/// Provides timezone-aware parsing, conversion, truncation and humanization of date time.
+///
+/// 提供带时区的日期时间解析、转换、截断及人性化展示等功能.
+///
+/// The business timezone is specified by [`AppConfig::timezone`](crate::config::config_dto::AppConfig::timezone),
+/// UTC is used when not configured.
+///
+/// 业务时区由 [`AppConfig::timezone`](crate::config::config_dto::AppConfig::timezone) 指定，未配置时使用UTC.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::TardisFuns;
+/// let dt = TardisFuns::time.parse("2023-08-01 10:00:00").unwrap();
+/// let day_start = TardisFuns::time.truncate_to_day(dt);
+/// assert_eq!(TardisFuns::time.humanize_duration(chrono::Duration::seconds(3661)), "1h 1m 1s");
+/// ```
+pub struct TardisTime;
+
+impl TardisTime {
+    /// Get the business timezone / 获取业务时区
+    ///
+    /// The configured timezone is validated when the config is loaded, see [`TardisFuns::init_conf`].
+    ///
+    /// 配置的时区在加载配置时校验，见 [`TardisFuns::init_conf`] .
+    pub fn business_offset(&self) -> FixedOffset {
+        TardisFuns::fw_config()
+            .app
+            .timezone
+            .as_deref()
+            .and_then(|timezone| self.parse_offset(timezone).ok())
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset is always valid"))
+    }
+
+    /// Validate the configured business timezone / 校验配置的业务时区
+    pub(crate) fn validate_business_timezone(&self, timezone: Option<&str>) -> TardisResult<()> {
+        if let Some(timezone) = timezone {
+            self.parse_offset(timezone).map_err(|error| {
+                TardisError::format_error(
+                    &format!("[Tardis.Time] Invalid business timezone [{timezone}] in fw.app.timezone: {}", error.message),
+                    "406-tardis-time-offset-invalid",
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Parse timezone offset / 解析时区偏移
+    ///
+    /// Supported formats: `Z` `UTC` `+08:00` `+0800` `+08` `-05:30` .
+    ///
+    /// 支持的格式： `Z` `UTC` `+08:00` `+0800` `+08` `-05:30` .
+    pub fn parse_offset(&self, offset: &str) -> TardisResult<FixedOffset> {
+        let offset = offset.trim();
+        if offset.eq_ignore_ascii_case("z") || offset.eq_ignore_ascii_case("utc") {
+            return Ok(FixedOffset::east_opt(0).expect("zero offset is always valid"));
+        }
+        let (sign, value) = if let Some(value) = offset.strip_prefix('+') {
+            (1, value)
+        } else if let Some(value) = offset.strip_prefix('-') {
+            (-1, value)
+        } else {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.Time] Invalid timezone offset: {offset}"),
+                "406-tardis-time-offset-invalid",
+            ));
+        };
+        let value = value.replace(':', "");
+        let (hours, minutes) = match value.len() {
+            2 => (value.parse::<i32>()?, 0),
+            4 => (value[..2].parse::<i32>()?, value[2..].parse::<i32>()?),
+            _ => {
+                return Err(TardisError::format_error(
+                    &format!("[Tardis.Time] Invalid timezone offset: {offset}"),
+                    "406-tardis-time-offset-invalid",
+                ))
+            }
+        };
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .ok_or_else(|| TardisError::format_error(&format!("[Tardis.Time] Invalid timezone offset: {offset}"), "406-tardis-time-offset-invalid"))
+    }
+
+    /// Get current time (of [`TardisFuns::clock`]) in the business timezone / 获取业务时区的当前时间（来自 [`TardisFuns::clock`] ）
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.to_business(TardisFuns::clock().now())
+    }
+
+    /// Convert to the business timezone / 转换为业务时区
+    pub fn to_business<Tz: TimeZone>(&self, dt: DateTime<Tz>) -> DateTime<FixedOffset> {
+        dt.with_timezone(&self.business_offset())
+    }
+
+    /// Convert to UTC / 转换为UTC
+    pub fn to_utc<Tz: TimeZone>(&self, dt: DateTime<Tz>) -> DateTime<Utc> {
+        dt.with_timezone(&Utc)
+    }
+
+    /// Parse date time from multiple common formats / 从多种常见格式中解析日期时间
+    ///
+    /// Values without timezone are treated as the business timezone.
+    ///
+    /// 不带时区的值视为业务时区.
+    ///
+    /// Supported formats / 支持的格式:
+    ///
+    /// * RFC 3339 / RFC 2822, E.g. `2023-08-01T10:00:00+08:00` `Tue, 1 Aug 2023 10:00:00 +0800`
+    /// * `2023-08-01 10:00:00` `2023-08-01T10:00:00` `2023/08/01 10:00:00` `2023-08-01 10:00` `2023/08/01 10:00` `20230801100000`
+    /// * `2023-08-01` `2023/08/01` `20230801`
+    /// * Unix timestamp in seconds (10 digits) or milliseconds (13 digits) / 秒（10位）或毫秒（13位）的时间戳
+    pub fn parse(&self, str: &str) -> TardisResult<DateTime<Utc>> {
+        self.parse_with_offset(str, self.business_offset())
+    }
+
+    /// Parse date time from multiple common formats with the specified timezone / 使用指定的时区从多种常见格式中解析日期时间
+    ///
+    /// Values without timezone are treated as `offset`.
+    ///
+    /// 不带时区的值视为 `offset` 时区.
+    pub fn parse_with_offset(&self, str: &str, offset: FixedOffset) -> TardisResult<DateTime<Utc>> {
+        let str = str.trim();
+        if let Ok(dt) = DateTime::parse_from_rfc3339(str) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+        if let Ok(dt) = DateTime::parse_from_rfc2822(str) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+        if str.chars().all(|c| c.is_ascii_digit()) && (str.len() == 10 || str.len() == 13) {
+            let ts = str.parse::<i64>()?;
+            let dt = if str.len() == 10 {
+                Utc.timestamp_opt(ts, 0).single()
+            } else {
+                Utc.timestamp_millis_opt(ts).single()
+            };
+            return dt.ok_or_else(|| TardisError::format_error(&format!("[Tardis.Time] Invalid timestamp: {str}"), "406-tardis-time-parse-error"));
+        }
+        let naive = NAIVE_DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(str, format).ok())
+            .or_else(|| NAIVE_DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(str, format).ok()).and_then(|date| date.and_hms_opt(0, 0, 0)))
+            .ok_or_else(|| TardisError::format_error(&format!("[Tardis.Time] Unsupported date time format: {str}"), "406-tardis-time-parse-error"))?;
+        offset
+            .from_local_datetime(&naive)
+            .single()
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| TardisError::format_error(&format!("[Tardis.Time] Invalid date time: {str}"), "406-tardis-time-parse-error"))
+    }
+
+    /// Truncate to the start of the day in the business timezone / 截断到业务时区的当天开始
+    pub fn truncate_to_day(&self, dt: DateTime<Utc>) -> DateTime<Utc> {
+        self.truncate_to_day_with_offset(dt, self.business_offset())
+    }
+
+    /// Truncate to the start of the day in the specified timezone / 截断到指定时区的当天开始
+    pub fn truncate_to_day_with_offset(&self, dt: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+        let naive = dt.with_timezone(&offset).date_naive().and_hms_opt(0, 0, 0).expect("midnight is always valid");
+        offset.from_local_datetime(&naive).single().expect("fixed offset is always unambiguous").with_timezone(&Utc)
+    }
+
+    /// Truncate to the start of the week (Monday) in the business timezone / 截断到业务时区的当周开始（周一）
+    pub fn truncate_to_week(&self, dt: DateTime<Utc>) -> DateTime<Utc> {
+        self.truncate_to_week_with_offset(dt, self.business_offset())
+    }
+
+    /// Truncate to the start of the week (Monday) in the specified timezone / 截断到指定时区的当周开始（周一）
+    pub fn truncate_to_week_with_offset(&self, dt: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+        let day_start = self.truncate_to_day_with_offset(dt, offset);
+        let days_from_monday = day_start.with_timezone(&offset).weekday().num_days_from_monday();
+        day_start - Duration::days(days_from_monday as i64)
+    }
+
+    /// Humanize duration / 人性化展示时长
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// assert_eq!(TardisFuns::time.humanize_duration(chrono::Duration::seconds(90061)), "1d 1h 1m 1s");
+    /// assert_eq!(TardisFuns::time.humanize_duration(chrono::Duration::minutes(-5)), "-5m");
+    /// assert_eq!(TardisFuns::time.humanize_duration(chrono::Duration::milliseconds(300)), "300ms");
+    /// ```
+    pub fn humanize_duration(&self, duration: Duration) -> String {
+        let sign = if duration < Duration::zero() { "-" } else { "" };
+        let duration = if duration < Duration::zero() { -duration } else { duration };
+        if duration < Duration::seconds(1) {
+            return format!("{sign}{}ms", duration.num_milliseconds());
+        }
+        let total_secs = duration.num_seconds();
+        let parts = [
+            (total_secs / 86400, "d"),
+            (total_secs % 86400 / 3600, "h"),
+            (total_secs % 3600 / 60, "m"),
+            (total_secs % 60, "s"),
+        ];
+        let humanized = parts.iter().filter(|(value, _)| *value > 0).map(|(value, unit)| format!("{value}{unit}")).collect::<Vec<_>>().join(" ");
+        format!("{sign}{humanized}")
+    }
+}
@@ -1,5 +1,9 @@
-use std::sync::{Arc, Once};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Once, RwLock};
+use std::time::Duration;
 
+use crate::basic::error::TardisError;
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::LogConfig;
 
@@ -18,6 +22,11 @@ use tracing_subscriber::{
     EnvFilter, Registry,
 };
 
+#[cfg(feature = "tracing")]
+mod sampler;
+#[cfg(feature = "tracing")]
+pub use sampler::TardisSampler;
+
 /// # Tardis Tracing
 /// Tardis tracing is a wrapper of tracing-subscriber. It provides configurable layers as runtime.
 ///
@@ -25,9 +34,25 @@ use tracing_subscriber::{
 ///
 /// To update config at runtime, use method [`TardisTracing::update_config`].
 ///
+/// To adjust log level at runtime, use method [`TardisTracing::set_level`], [`TardisTracing::set_level_for`] or [`TardisTracing::reload_filter`].
+///
+/// To propagate trace context across async boundaries, use method [`TardisTracing::inject_context`], [`TardisTracing::set_parent_from`]
+/// and [`TardisTracing::spawn_in_current_span`].
+///
+/// The W3C `traceparent`, `tracestate` and `baggage` headers are propagated automatically by the built-in components:
+/// the web server (all the modules, including the raw and grpc modules) extracts them from the requests,
+/// [`TardisWebClient`](crate::web::web_client::TardisWebClient) injects them into the outbound requests,
+/// and the MQ client carries them in the message headers on publish and consume.
+///
+/// 内置组件会自动传播W3C `traceparent` 、 `tracestate` 及 `baggage` 请求头：Web服务（所有模块，包括原始及grpc模块）从请求中提取，
+/// [`TardisWebClient`](crate::web::web_client::TardisWebClient) 注入到出站请求中，MQ客户端在发布及消费时通过消息头携带.
+///
 #[derive(Default)]
 pub struct TardisTracing<C = LogConfig> {
     configer: Vec<Box<dyn Fn(&C) -> TardisResult<()> + Send + Sync>>,
+    config: RwLock<C>,
+    // generations of the runtime levels by target, the pending restores of `set_level_for` are skipped once the level changes again
+    level_generations: RwLock<HashMap<String, u64>>,
 }
 
 // create a configurable layer, recieve a layer and a configer, return a reload layer and a config function
@@ -128,6 +153,10 @@ where
         self.with_configurable_layer(EnvFilter::from_default_env().boxed(), |config: &LogConfig| {
             let mut env_filter = EnvFilter::from_default_env();
             env_filter = env_filter.add_directive(config.level.clone());
+            // the directives added later take precedence
+            for (target, level) in &config.targets {
+                env_filter = env_filter.add_directive(TardisTracing::<LogConfig>::parse_directive(&format!("{target}={level}"))?);
+            }
             for directive in &config.directives {
                 env_filter = env_filter.add_directive(directive.clone());
             }
@@ -143,7 +172,7 @@ where
         ReloadLayer<BoxLayer<S>, S>: tracing_subscriber::Layer<L0>,
     {
         self.with_configurable_layer(
-            tracing_opentelemetry::layer().with_tracer(TardisTracing::<LogConfig>::create_otlp_tracer()).boxed(),
+            tracing_opentelemetry::layer().with_tracer(TardisTracing::<LogConfig>::create_otlp_tracer(None)).boxed(),
             |conf: &LogConfig| {
                 if std::env::var_os(OTEL_EXPORTER_OTLP_ENDPOINT).is_none() {
                     std::env::set_var(OTEL_EXPORTER_OTLP_ENDPOINT, conf.tracing.endpoint.as_str());
@@ -154,7 +183,12 @@ where
                 if std::env::var_os(OTEL_SERVICE_NAME).is_none() {
                     std::env::set_var(OTEL_SERVICE_NAME, conf.tracing.server_name.as_str());
                 }
-                Ok(tracing_opentelemetry::layer().with_tracer(TardisTracing::<LogConfig>::create_otlp_tracer()).boxed())
+                if let Some(headers) = &conf.tracing.headers {
+                    if std::env::var_os(OTEL_EXPORTER_OTLP_HEADERS).is_none() {
+                        std::env::set_var(OTEL_EXPORTER_OTLP_HEADERS, headers.as_str());
+                    }
+                }
+                Ok(tracing_opentelemetry::layer().with_tracer(TardisTracing::<LogConfig>::create_otlp_tracer(Some(&conf.tracing))).boxed())
             },
         )
     }
@@ -174,16 +208,53 @@ where
         S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
         ReloadLayer<BoxLayer<S>, S>: tracing_subscriber::Layer<L0>,
     {
-        use crate::config::config_dto::log::TracingAppenderConfig;
-        let config_file_layer = |cfg: Option<&TracingAppenderConfig>| {
-            if let Some(cfg) = &cfg {
-                let file_appender = tracing_appender::rolling::RollingFileAppender::new(cfg.rotation.into(), &cfg.dir, &cfg.filename);
-                FmtLayer::default().with_writer(file_appender).boxed()
-            } else {
+        use crate::basic::rolling_file::RollingFileWriter;
+        use tracing_subscriber::fmt::writer::MakeWriterExt;
+        let config_file_layer = |cfg: &LogConfig| {
+            let mut layers = Vec::new();
+            if let Some(appender) = &cfg.tracing_appender {
+                layers.push(FmtLayer::default().with_ansi(false).with_writer(RollingFileWriter::new(appender.clone())).boxed());
+            }
+            // filtered by the writers, the per-layer filters don't work with the reload layer
+            for (target, appender) in &cfg.module_appenders {
+                let target = target.clone();
+                let file_writer = RollingFileWriter::new(appender.clone()).with_filter(move |meta: &tracing::Metadata<'_>| {
+                    meta.target().strip_prefix(target.as_str()).map(|rest| rest.is_empty() || rest.starts_with("::")).unwrap_or(false)
+                });
+                layers.push(FmtLayer::default().with_ansi(false).with_writer(file_writer).boxed());
+            }
+            if layers.is_empty() {
                 FmtLayer::default().with_writer(std::io::sink).boxed()
+            } else {
+                layers.boxed()
             }
         };
-        self.with_configurable_layer(config_file_layer(None), move |cfg| TardisResult::Ok(config_file_layer(cfg.tracing_appender.as_ref())))
+        self.with_configurable_layer(config_file_layer(&LogConfig::default()), move |cfg| TardisResult::Ok(config_file_layer(cfg)))
+    }
+    #[cfg(feature = "log-loki")]
+    pub fn with_loki_layer<S>(self) -> TardisTracingInitializer<Layered<ReloadLayer<BoxLayer<S>, S>, L0>, LogConfig>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+        ReloadLayer<BoxLayer<S>, S>: tracing_subscriber::Layer<L0>,
+    {
+        use crate::basic::log_shipping::LogShippingLayer;
+        self.with_configurable_layer(tracing_subscriber::layer::Identity::new().boxed(), |cfg: &LogConfig| match &cfg.loki {
+            Some(loki) => Ok(LogShippingLayer::loki(loki)?.boxed()),
+            None => Ok(tracing_subscriber::layer::Identity::new().boxed()),
+        })
+    }
+
+    #[cfg(feature = "log-fluentd")]
+    pub fn with_fluentd_layer<S>(self) -> TardisTracingInitializer<Layered<ReloadLayer<BoxLayer<S>, S>, L0>, LogConfig>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+        ReloadLayer<BoxLayer<S>, S>: tracing_subscriber::Layer<L0>,
+    {
+        use crate::basic::log_shipping::LogShippingLayer;
+        self.with_configurable_layer(tracing_subscriber::layer::Identity::new().boxed(), |cfg: &LogConfig| match &cfg.fluentd {
+            Some(fluentd) => Ok(LogShippingLayer::fluentd(fluentd)?.boxed()),
+            None => Ok(tracing_subscriber::layer::Identity::new().boxed()),
+        })
     }
 }
 
@@ -199,7 +270,11 @@ where
             tracing::error!("[Tardis.Tracing] Trying to initialize tardis tracing more than once, this initialization will be ignored. If you want to use new config for tracing, use update_config() instead.");
         } else {
             INITIALIZED.call_once(|| self.layered.init());
-            TARDIS_INST.tracing.set(TardisTracing { configer: configer_list });
+            TARDIS_INST.tracing.set(TardisTracing {
+                configer: configer_list,
+                config: RwLock::new(LogConfig::default()),
+                level_generations: RwLock::new(HashMap::new()),
+            });
         }
         crate::TardisFuns::tracing()
     }
@@ -211,7 +286,11 @@ where
     pub fn init_standalone(self) -> TardisTracing {
         let configer_list = self.configers;
         self.layered.init();
-        TardisTracing { configer: configer_list }
+        TardisTracing {
+            configer: configer_list,
+            config: RwLock::new(LogConfig::default()),
+            level_generations: RwLock::new(HashMap::new()),
+        }
     }
 }
 
@@ -227,11 +306,194 @@ impl TardisTracing<LogConfig> {
         for configer in &self.configer {
             (configer)(config)?
         }
+        crate::basic::metrics::set_slow_operation_config(&config.slow_operation);
+        *self.config.write()? = config.clone();
         tracing::debug!("[Tardis.Tracing] Config updated.");
         tracing::trace!("[Tardis.Tracing] New config: {:?}", config);
         Ok(())
     }
 
+    /// Get current tardis tracing config
+    pub fn config(&self) -> TardisResult<LogConfig> {
+        Ok(self.config.read()?.clone())
+    }
+
+    /// Set log level of the target at runtime, an empty target means the global level
+    ///
+    /// ```ignore
+    /// TardisFuns::tracing().set_level("tardis", "trace")?;
+    /// ```
+    pub fn set_level(&self, target: &str, level: &str) -> TardisResult<()> {
+        let mut generations = self.level_generations.write()?;
+        Self::next_generation(&mut generations, target);
+        self.apply_level(target, level)
+    }
+
+    fn apply_level(&self, target: &str, level: &str) -> TardisResult<()> {
+        let mut config = self.config()?;
+        if target.is_empty() {
+            config.level = Self::parse_directive(level)?;
+        } else {
+            let directive = Self::parse_directive(&format!("{target}={level}"))?;
+            config.directives.retain(|d| !Self::is_target_directive(d, target));
+            config.directives.push(directive);
+        }
+        tracing::info!("[Tardis.Tracing] Set log level of [{target}] to [{level}]");
+        self.update_config(&config)
+    }
+
+    /// Replace the global level and the directives by the filter at runtime, e.g. `info,tardis::cache=trace`
+    ///
+    /// The directive without a target (e.g. `info`) sets the global level, the global level is kept if it's absent.
+    /// The `targets` of the config are kept, and the filter is validated before applied.
+    ///
+    /// ```ignore
+    /// TardisFuns::tracing().reload_filter("info,tardis::cache=trace")?;
+    /// ```
+    pub fn reload_filter(&self, filter: &str) -> TardisResult<()> {
+        let mut generations = self.level_generations.write()?;
+        let mut config = self.config()?;
+        let mut directives = Vec::new();
+        for directive in filter.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            let directive = Self::parse_directive(directive)?;
+            // the directives with targets or spans are displayed as `<filter>=<level>`
+            if directive.to_string().contains('=') {
+                directives.push(directive);
+            } else {
+                config.level = directive;
+            }
+        }
+        config.directives = directives;
+        tracing::info!("[Tardis.Tracing] Reload log filter to [{filter}]");
+        generations.values_mut().for_each(|generation| *generation += 1);
+        self.update_config(&config)
+    }
+
+    /// Remove the runtime log level of the target, fallback to the level in `targets` of the config or the global level
+    pub fn reset_level(&self, target: &str) -> TardisResult<()> {
+        let mut generations = self.level_generations.write()?;
+        Self::next_generation(&mut generations, target);
+        self.apply_reset_level(target)
+    }
+
+    fn apply_reset_level(&self, target: &str) -> TardisResult<()> {
+        let mut config = self.config()?;
+        config.directives.retain(|d| !Self::is_target_directive(d, target));
+        tracing::info!("[Tardis.Tracing] Reset log level of [{target}]");
+        self.update_config(&config)
+    }
+
+    /// Set log level of the target for a while, then restore the previous level, must be called within a tokio runtime
+    ///
+    /// The restore is skipped if the level of the target is changed again before it, e.g. by [`set_level`](Self::set_level) ,
+    /// [`reset_level`](Self::reset_level) , [`reload_filter`](Self::reload_filter) or another `set_level_for` .
+    ///
+    /// ```ignore
+    /// TardisFuns::tracing().set_level_for("tardis", "trace", Duration::from_secs(600))?;
+    /// ```
+    pub fn set_level_for(self: &Arc<Self>, target: &str, level: &str, duration: Duration) -> TardisResult<()> {
+        let (previous, generation) = {
+            let mut generations = self.level_generations.write()?;
+            let previous = self.config()?;
+            let generation = Self::next_generation(&mut generations, target);
+            self.apply_level(target, level)?;
+            (previous, generation)
+        };
+        let tracing = self.clone();
+        let target = target.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            let result = tracing.restore_level(&target, previous, generation);
+            if let Err(error) = result {
+                tracing::error!("[Tardis.Tracing] Restore log level of [{target}] error: {error}");
+            }
+        });
+        Ok(())
+    }
+
+    fn restore_level(&self, target: &str, previous: LogConfig, generation: u64) -> TardisResult<()> {
+        let mut generations = self.level_generations.write()?;
+        if generations.get(target) != Some(&generation) {
+            tracing::debug!("[Tardis.Tracing] Log level of [{target}] is changed, skip restoring it");
+            return Ok(());
+        }
+        Self::next_generation(&mut generations, target);
+        let previous_level = if target.is_empty() {
+            Some(previous.level)
+        } else {
+            previous.directives.into_iter().find(|d| Self::is_target_directive(d, target))
+        };
+        match previous_level {
+            Some(previous_level) => self.apply_level(target, &Self::directive_level(&previous_level)),
+            None => self.apply_reset_level(target),
+        }
+    }
+
+    fn next_generation(generations: &mut HashMap<String, u64>, target: &str) -> u64 {
+        let generation = generations.entry(target.to_string()).or_default();
+        *generation += 1;
+        *generation
+    }
+
+    fn parse_directive(directive: &str) -> TardisResult<Directive> {
+        directive
+            .parse::<Directive>()
+            .map_err(|error| TardisError::bad_request(&format!("[Tardis.Tracing] Invalid log level [{directive}]: {error}"), "400-tardis-tracing-level-invalid"))
+    }
+
+    fn is_target_directive(directive: &Directive, target: &str) -> bool {
+        directive.to_string().split_once('=').map(|(t, _)| t == target).unwrap_or(false)
+    }
+
+    fn directive_level(directive: &Directive) -> String {
+        let directive = directive.to_string();
+        directive.rsplit_once('=').map(|(_, level)| level.to_string()).unwrap_or(directive)
+    }
+
+    /// Inject the trace context of the current span into the carrier, e.g. http headers or mq headers
+    ///
+    /// It's a no-op when the `tracing` feature is disabled.
+    pub fn inject_context(carrier: &mut HashMap<String, String>) {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+            let context = tracing::Span::current().context();
+            opentelemetry::global::get_text_map_propagator(|propagator| propagator.inject_context(&context, carrier));
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = carrier;
+    }
+
+    /// Set the parent of the span to the trace context extracted from the carrier
+    ///
+    /// It's a no-op when the `tracing` feature is disabled or the carrier has no trace context.
+    pub fn set_parent_from(span: &tracing::Span, carrier: &HashMap<String, String>) {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+            let context = opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(carrier));
+            span.set_parent(context);
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = (span, carrier);
+    }
+
+    /// Spawn a task running in the current span, so that the spans created by the task are children of the current span,
+    /// the [`TardisContext`](crate::basic::dto::TardisContext) of the current task is also carried
+    ///
+    /// ```ignore
+    /// TardisTracing::spawn_in_current_span(async move {
+    ///     TardisFuns::cache().set("key", "value").await
+    /// });
+    /// ```
+    pub fn spawn_in_current_span<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        crate::basic::dto::TardisContext::spawn(future)
+    }
+
     pub(crate) fn init_default() -> TardisResult<()> {
         tracing::info!("[Tardis.Tracing] Initializing by defualt initializer.");
         let initializer = TardisTracingInitializer::default().with_fmt_layer().with_env_layer();
@@ -242,17 +504,28 @@ impl TardisTracing<LogConfig> {
         let initializer = initializer.with_console_layer();
         #[cfg(feature = "tracing-appender")]
         let initializer = initializer.with_appender_layer();
+        #[cfg(feature = "log-loki")]
+        let initializer = initializer.with_loki_layer();
+        #[cfg(feature = "log-fluentd")]
+        let initializer = initializer.with_fluentd_layer();
         tracing::info!("[Tardis.Tracing] Initialize finished.");
         initializer.init();
         Ok(())
     }
 
     #[cfg(feature = "tracing")]
-    fn create_otlp_tracer() -> opentelemetry::sdk::trace::Tracer {
+    fn create_otlp_tracer(conf: Option<&crate::config::config_dto::TracingConfig>) -> opentelemetry::sdk::trace::Tracer {
+        use opentelemetry::sdk::Resource;
+        use opentelemetry::KeyValue;
         use opentelemetry_otlp::WithExportConfig;
 
         use crate::config::config_dto::OtlpProtocol;
         tracing::debug!("[Tardis.Tracing] Initializing otlp tracer");
+        // W3C `traceparent` / `tracestate` and `baggage` headers
+        opentelemetry::global::set_text_map_propagator(opentelemetry::sdk::propagation::TextMapCompositePropagator::new(vec![
+            Box::new(opentelemetry::sdk::propagation::TraceContextPropagator::new()),
+            Box::new(opentelemetry::sdk::propagation::BaggagePropagator::new()),
+        ]));
         let protocol = std::env::var(OTEL_EXPORTER_OTLP_PROTOCOL).ok().map(|s| s.parse::<OtlpProtocol>().unwrap_or_default()).unwrap_or_default();
         let mut tracer = opentelemetry_otlp::new_pipeline().tracing();
         match protocol {
@@ -272,6 +545,11 @@ impl TardisTracing<LogConfig> {
                 tracer = tracer.with_exporter(exporter)
             }
         };
+        if let Some(conf) = conf {
+            let attributes = conf.resource_attributes.iter().map(|(key, value)| KeyValue::new(key.clone(), value.clone())).collect::<Vec<_>>();
+            tracer = tracer
+                .with_trace_config(opentelemetry::sdk::trace::config().with_sampler(TardisSampler::new(conf)).with_resource(Resource::default().merge(&Resource::new(attributes))));
+        }
         tracing::debug!("[Tardis.Tracing] Batch installing tracer. If you are blocked here, try running tokio in multithread.");
         let tracer = tracer.install_batch(opentelemetry::runtime::Tokio).expect("fail to install otlp tracer");
         tracing::debug!("[Tardis.Tracing] Initialized otlp tracer");
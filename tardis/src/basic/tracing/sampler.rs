@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use opentelemetry::sdk::trace::{Sampler, ShouldSample};
+use opentelemetry::trace::{Link, SamplingDecision, SamplingResult, SpanKind, TraceContextExt, TraceId};
+use opentelemetry::{Context, KeyValue};
+
+use crate::config::config_dto::{TracingConfig, TracingSampler};
+
+/// Tardis trace sampler / Tardis链路采样器
+///
+/// 1. If `parent_based` is enabled, follow the sampling decision of the parent span.
+/// 1. If the root span matches a route of `route_rate_limits` (by the `http.target` attribute or the span name), sample by the rate limit of the route.
+/// 1. Otherwise, sample by the `sampler`.
+///
+/// 1. 若启用 `parent_based` ，遵循父span的采样决策.
+/// 1. 若根span匹配 `route_rate_limits` 中的路由（根据 `http.target` 属性或span名称），按该路由的速率限制采样.
+/// 1. 否则按 `sampler` 采样.
+#[derive(Debug, Clone)]
+pub struct TardisSampler {
+    sampler: Sampler,
+    parent_based: bool,
+    // sorted by the prefix length in descending order
+    route_limiters: Arc<Vec<(String, Mutex<RateLimiter>)>>,
+}
+
+impl TardisSampler {
+    pub fn new(config: &TracingConfig) -> Self {
+        let sampler = match config.sampler {
+            TracingSampler::AlwaysOn => Sampler::AlwaysOn,
+            TracingSampler::AlwaysOff => Sampler::AlwaysOff,
+            TracingSampler::TraceIdRatio(ratio) => Sampler::TraceIdRatioBased(if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) }),
+        };
+        let mut route_limiters = config.route_rate_limits.iter().map(|(route, rate)| (route.clone(), Mutex::new(RateLimiter::new(*rate)))).collect::<Vec<_>>();
+        route_limiters.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        TardisSampler {
+            sampler,
+            parent_based: config.parent_based,
+            route_limiters: Arc::new(route_limiters),
+        }
+    }
+}
+
+impl ShouldSample for TardisSampler {
+    fn should_sample(&self, parent_context: Option<&Context>, trace_id: TraceId, name: &str, span_kind: &SpanKind, attributes: &[KeyValue], links: &[Link]) -> SamplingResult {
+        let parent_context = parent_context.filter(|cx| cx.has_active_span());
+        if self.parent_based {
+            if let Some(parent_context) = parent_context {
+                let span = parent_context.span();
+                let span_context = span.span_context();
+                return SamplingResult {
+                    decision: if span_context.is_sampled() {
+                        SamplingDecision::RecordAndSample
+                    } else {
+                        SamplingDecision::Drop
+                    },
+                    attributes: Vec::new(),
+                    trace_state: span_context.trace_state().clone(),
+                };
+            }
+        }
+        if parent_context.is_none() && !self.route_limiters.is_empty() {
+            let target = attributes.iter().find(|kv| kv.key.as_str() == "http.target").map(|kv| kv.value.as_str());
+            let route = target.as_deref().unwrap_or(name);
+            if let Some((_, limiter)) = self.route_limiters.iter().find(|(prefix, _)| route.starts_with(prefix.as_str())) {
+                let sampled = limiter.lock().map(|mut limiter| limiter.try_acquire()).unwrap_or(false);
+                return SamplingResult {
+                    decision: if sampled { SamplingDecision::RecordAndSample } else { SamplingDecision::Drop },
+                    attributes: Vec::new(),
+                    trace_state: Default::default(),
+                };
+            }
+        }
+        self.sampler.should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
+}
+
+/// Token bucket, allows `rate` acquisitions per second with a burst of `max(rate, 1)`
+#[derive(Debug)]
+struct RateLimiter {
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: f64) -> Self {
+        let rate = if rate.is_nan() { 0.0 } else { rate.max(0.0) };
+        RateLimiter {
+            rate,
+            tokens: if rate > 0.0 { rate.max(1.0) } else { 0.0 },
+            last: Instant::now(),
+        }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        self.tokens = (self.tokens + now.duration_since(self.last).as_secs_f64() * self.rate).min(self.rate.max(1.0));
+        self.last = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Declarative validation / 声明式校验
+//!
+//! The rules are declared on the fields by the `#[validate(..)]` attributes of the [`TardisValidate`](crate::TardisValidate) derive macro,
+//! the web server rejects the invalid bodies of [`ValidJson`](crate::web::web_validation::ValidJson) before the handlers run.
+//!
+//! 规则通过 [`TardisValidate`](crate::TardisValidate) 派生宏的 `#[validate(..)]` 属性声明在字段上，
+//! Web服务在处理函数执行前拒绝 [`ValidJson`](crate::web::web_validation::ValidJson) 的无效请求体.
+//!
+//! The messages are localized by [`TardisLocale`] with the codes of the rules, e.g. `config/locale/zh-cn` :
+//!
+//! 消息以规则的错误码通过 [`TardisLocale`] 本地化，如 `config/locale/zh-cn` :
+//!
+//! ```text
+//! 400-tardis-validation-length    长度必须在{1}到{2}之间   between (\d+) and (\d+)
+//! 400-tardis-validation-one-of    不在可选值范围内
+//! ```
+//!
+//! # Examples
+//! ```ignore
+//! #[derive(TardisValidate)]
+//! struct TodoAddReq {
+//!     #[validate(length(min = 1, max = 64), regex = "^[a-z0-9-]+$")]
+//!     code: String,
+//!     #[validate(range(min = 1, max = 5))]
+//!     priority: i32,
+//!     #[validate(one_of = ["todo", "done"])]
+//!     status: String,
+//!     #[validate(custom = "check_description")]
+//!     description: Option<String>,
+//!     #[validate(nested)]
+//!     tags: Vec<TagReq>,
+//! }
+//!
+//! fn check_description(description: &String) -> TardisResult<()> {
+//!     ...
+//! }
+//! ```
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::sync::{Mutex, OnceLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::basic::error::TardisError;
+use crate::basic::locale::TardisLocale;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+pub const VALIDATION_FAILED_CODE: &str = "400-tardis-validation-failed";
+pub const VALIDATION_LENGTH_CODE: &str = "400-tardis-validation-length";
+pub const VALIDATION_REGEX_CODE: &str = "400-tardis-validation-regex";
+pub const VALIDATION_RANGE_CODE: &str = "400-tardis-validation-range";
+pub const VALIDATION_ONE_OF_CODE: &str = "400-tardis-validation-one-of";
+
+/// Validation of the fields / 字段校验
+///
+/// It's implemented by the [`TardisValidate`](crate::TardisValidate) derive macro.
+///
+/// 由 [`TardisValidate`](crate::TardisValidate) 派生宏实现.
+pub trait TardisValidate {
+    /// Add the errors of the fields to `errors` , the field names are prefixed by `prefix` (e.g. `items[0]` )
+    /// / 将字段的错误添加到 `errors` ，字段名以 `prefix` （如 `items[0]` ）为前缀
+    fn validate_fields(&self, errors: &mut TardisValidationErrors, prefix: &str);
+
+    fn validate(&self) -> Result<(), TardisValidationErrors> {
+        let mut errors = TardisValidationErrors::default();
+        self.validate_fields(&mut errors, "");
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<T: TardisValidate> TardisValidate for Option<T> {
+    fn validate_fields(&self, errors: &mut TardisValidationErrors, prefix: &str) {
+        if let Some(value) = self {
+            value.validate_fields(errors, prefix);
+        }
+    }
+}
+
+impl<T: TardisValidate> TardisValidate for Vec<T> {
+    fn validate_fields(&self, errors: &mut TardisValidationErrors, prefix: &str) {
+        for (index, value) in self.iter().enumerate() {
+            value.validate_fields(errors, &format!("{prefix}[{index}]"));
+        }
+    }
+}
+
+impl<T: TardisValidate> TardisValidate for Box<T> {
+    fn validate_fields(&self, errors: &mut TardisValidationErrors, prefix: &str) {
+        self.as_ref().validate_fields(errors, prefix);
+    }
+}
+
+/// Error of a field / 字段错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisFieldError {
+    /// Path of the field, e.g. `tags[0].name` / 字段路径，如 `tags[0].name`
+    pub field: String,
+    /// Code of the violated rule, also the key of the localized message / 违反规则的错误码，同时为本地化消息的键
+    pub code: String,
+    pub message: String,
+}
+
+/// Errors of the fields / 字段错误集合
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TardisValidationErrors(pub Vec<TardisFieldError>);
+
+impl TardisValidationErrors {
+    pub fn add(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.0.push(TardisFieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Convert to the 400 error with the localized messages of the fields in the extensions
+    /// / 转换为400错误，扩展中包含字段的本地化消息
+    ///
+    /// The extensions are `{field: localized message}` and `{field}.code: rule code`, the first error of each field is kept.
+    /// The messages are localized in `lang` or the default language.
+    ///
+    /// 扩展为 `{字段: 本地化消息}` 及 `{字段}.code: 规则错误码` ，每个字段保留第一个错误. 消息以 `lang` 或默认语言本地化.
+    pub fn into_error(self, lang: Option<&str>) -> TardisError {
+        let lang = lang.map(str::to_string).or_else(|| TardisFuns::fw_config_opt().and_then(|fw_config| fw_config.app.default_lang.clone()));
+        let mut fields = BTreeMap::new();
+        for error in self.0 {
+            fields.entry(error.field.clone()).or_insert(error);
+        }
+        let message = fields.values().map(|error| format!("{}: {}", error.field, error.message)).collect::<Vec<_>>().join("; ");
+        let mut tardis_error = TardisError::bad_request(&format!("[Tardis.Validation] {message}"), VALIDATION_FAILED_CODE);
+        for (field, error) in fields {
+            let message = match &lang {
+                Some(lang) => TardisLocale::get_message(&error.code, &error.message, lang).unwrap_or(error.message),
+                None => error.message,
+            };
+            tardis_error = tardis_error.with_extension(format!("{field}.code"), error.code).with_extension(field, message);
+        }
+        tardis_error
+    }
+}
+
+impl Display for TardisValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = self.0.iter().map(|error| format!("{}: {}", error.field, error.message)).collect::<Vec<_>>().join("; ");
+        f.write_str(&message)
+    }
+}
+
+impl From<TardisValidationErrors> for TardisError {
+    fn from(errors: TardisValidationErrors) -> Self {
+        errors.into_error(None)
+    }
+}
+
+/// Path of the field with the prefix / 带前缀的字段路径
+pub fn field_path(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{prefix}.{field}")
+    }
+}
+
+/// Length of the value for the `length` rule, the strings are counted by chars
+/// / `length` 规则的值长度，字符串按字符计数
+pub trait ValidateLength {
+    fn validate_length(&self) -> usize;
+}
+
+impl ValidateLength for String {
+    fn validate_length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl ValidateLength for &str {
+    fn validate_length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl<T> ValidateLength for Vec<T> {
+    fn validate_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> ValidateLength for HashMap<K, V, S> {
+    fn validate_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> ValidateLength for HashSet<T, S> {
+    fn validate_length(&self) -> usize {
+        self.len()
+    }
+}
+
+pub fn check_length<T: ValidateLength + ?Sized>(errors: &mut TardisValidationErrors, field: &str, value: &T, min: Option<usize>, max: Option<usize>) {
+    let length = value.validate_length();
+    if min.map(|min| length < min).unwrap_or(false) || max.map(|max| length > max).unwrap_or(false) {
+        let message = match (min, max) {
+            (Some(min), Some(max)) => format!("length must be between {min} and {max}"),
+            (Some(min), None) => format!("length must be at least {min}"),
+            (None, Some(max)) => format!("length must be at most {max}"),
+            (None, None) => unreachable!(),
+        };
+        errors.add(field, VALIDATION_LENGTH_CODE, message);
+    }
+}
+
+pub fn check_regex<T: AsRef<str> + ?Sized>(errors: &mut TardisValidationErrors, field: &str, value: &T, pattern: &'static str) {
+    static REGEXES: OnceLock<Mutex<HashMap<&'static str, Regex>>> = OnceLock::new();
+    let mut regexes = REGEXES.get_or_init(Default::default).lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !regexes.contains_key(pattern) {
+        match Regex::new(pattern) {
+            Ok(regex) => {
+                regexes.insert(pattern, regex);
+            }
+            Err(error) => {
+                errors.add(field, VALIDATION_REGEX_CODE, format!("invalid pattern {pattern}: {error}"));
+                return;
+            }
+        }
+    }
+    if !regexes[pattern].is_match(value.as_ref()) {
+        errors.add(field, VALIDATION_REGEX_CODE, format!("must match the pattern {pattern}"));
+    }
+}
+
+pub fn check_range<T: PartialOrd + Display>(errors: &mut TardisValidationErrors, field: &str, value: &T, min: Option<T>, max: Option<T>) {
+    if min.as_ref().map(|min| value < min).unwrap_or(false) || max.as_ref().map(|max| value > max).unwrap_or(false) {
+        let message = match (min, max) {
+            (Some(min), Some(max)) => format!("must be between {min} and {max}"),
+            (Some(min), None) => format!("must be at least {min}"),
+            (None, Some(max)) => format!("must be at most {max}"),
+            (None, None) => unreachable!(),
+        };
+        errors.add(field, VALIDATION_RANGE_CODE, message);
+    }
+}
+
+pub fn check_one_of<T: PartialEq<A>, A: Display>(errors: &mut TardisValidationErrors, field: &str, value: &T, allowed: &[A]) {
+    if !allowed.iter().any(|allowed| value == allowed) {
+        let allowed = allowed.iter().map(|allowed| allowed.to_string()).collect::<Vec<_>>().join(", ");
+        errors.add(field, VALIDATION_ONE_OF_CODE, format!("must be one of [{allowed}]"));
+    }
+}
+
+/// The result of the custom validator, the code and the message of the error are used
+/// / 自定义校验器的结果，使用错误的错误码及消息
+pub fn check_custom(errors: &mut TardisValidationErrors, field: &str, result: TardisResult<()>) {
+    if let Err(error) = result {
+        errors.add(field, &error.code, error.message);
+    }
+}
@@ -1,2 +1,4 @@
 pub use redis::*;
 pub mod cache_client;
+pub mod cache_timer;
+pub mod rate_limiter;
@@ -1,12 +1,25 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
 use deadpool_redis::{Config, Connection, Pool, Runtime};
+use futures_util::StreamExt;
+use redis::aio::PubSub;
 use redis::{AsyncCommands, ErrorKind, RedisError, RedisResult};
-use tracing::{error, info, trace};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tracing::{error, info, trace, warn};
 
 use crate::basic::error::TardisError;
+use crate::basic::metrics::observe_client;
 use crate::basic::result::TardisResult;
-use crate::config::config_dto::component::cache::CacheModuleConfig;
+use crate::cache::cache_timer::TardisTimer;
+use crate::cache::rate_limiter::TardisRateLimiter;
+use crate::config::config_dto::component::cache::{CacheCodec, CacheKind, CacheModuleConfig, RateLimiterConfig, TimerConfig};
+use crate::TardisFuns;
 
 use crate::utils::initializer::InitBy;
 
@@ -29,9 +42,53 @@ use crate::utils::initializer::InitBy;
 /// assert!(TardisFuns::cache().set_nx("test_key2", "测试2").await.unwrap());
 /// assert!(!TardisFuns::cache().set_nx("test_key2", "测试2").await.unwrap());
 /// ```
+#[derive(Clone)]
 pub struct TardisCacheClient {
-    pool: Pool,
+    backend: CacheBackend,
+    codec: CacheCodec,
+    rate_limiter: RateLimiterConfig,
+    timer: TimerConfig,
 }
+
+#[derive(Clone)]
+enum CacheBackend {
+    Redis {
+        pool: Pool,
+        // the pub/sub subscriptions use dedicated connections out of the pool
+        client: redis::Client,
+    },
+    #[cfg(feature = "test")]
+    Memory(Arc<crate::test::memory_cache::TardisMemoryCache>),
+}
+
+/// Run the operation on the backend of the client, the single dispatch point of the backends
+///
+/// The redis operation gets a connection from the pool and is observed by the client metrics,
+/// the in-memory operation runs on the store directly.
+macro_rules! dispatch {
+    ($client:expr, $op:literal, |$memory:ident| $on_memory:expr, |$conn:ident| $on_redis:expr) => {
+        match &$client.backend {
+            CacheBackend::Redis { .. } => {
+                observe_client("cache", $op, async {
+                    let mut $conn = $client.get_connection().await?;
+                    $on_redis
+                })
+                .await
+            }
+            #[cfg(feature = "test")]
+            CacheBackend::Memory($memory) => $on_memory,
+        }
+    };
+    // not supported by the in-memory client, which fails to get the connection
+    ($client:expr, $op:literal, |$conn:ident| $on_redis:expr) => {
+        observe_client("cache", $op, async {
+            let mut $conn = $client.get_connection().await?;
+            $on_redis
+        })
+        .await
+    };
+}
+
 #[async_trait::async_trait]
 impl InitBy<CacheModuleConfig> for TardisCacheClient {
     async fn init_by(config: &CacheModuleConfig) -> TardisResult<Self> {
@@ -41,7 +98,28 @@ impl InitBy<CacheModuleConfig> for TardisCacheClient {
 
 impl TardisCacheClient {
     /// Initialize configuration / 初始化配置
-    pub async fn init(CacheModuleConfig { url }: &CacheModuleConfig) -> TardisResult<TardisCacheClient> {
+    ///
+    /// The url `mem://` or the kind `memory` creates an in-memory client for tests, requires the `test` feature.
+    ///
+    /// url为 `mem://` 或类型为 `memory` 时创建用于测试的内存客户端，需启用 `test` 特性.
+    pub async fn init(config: &CacheModuleConfig) -> TardisResult<TardisCacheClient> {
+        let CacheModuleConfig {
+            url, codec, rate_limiter, timer, ..
+        } = config;
+        if config.kind() == CacheKind::Memory {
+            #[cfg(feature = "test")]
+            return Ok(TardisCacheClient {
+                codec: *codec,
+                rate_limiter: rate_limiter.clone(),
+                timer: timer.clone(),
+                ..Self::memory()
+            });
+            #[cfg(not(feature = "test"))]
+            return Err(TardisError::not_implemented(
+                "[Tardis.CacheClient] The in-memory store isn't enabled, see the test feature",
+                "501-tardis-cache-kind-error",
+            ));
+        }
         info!(
             "[Tardis.CacheClient] Initializing, host:{}, port:{}, db:{}",
             url.host_str().unwrap_or(""),
@@ -52,52 +130,111 @@ impl TardisCacheClient {
         let pool = cfg
             .create_pool(Some(Runtime::Tokio1))
             .map_err(|e| TardisError::format_error(&format!("[Tardis.CacheClient] Create pool error: {e}"), "500-tardis-cache-pool-error"))?;
+        let client =
+            redis::Client::open(url.as_str()).map_err(|e| TardisError::format_error(&format!("[Tardis.CacheClient] Create client error: {e}"), "500-tardis-cache-client-error"))?;
         info!(
             "[Tardis.CacheClient] Initialized, host:{}, port:{}, db:{}",
             url.host_str().unwrap_or(""),
             url.port().unwrap_or(0),
             if url.path().is_empty() { "" } else { &url.path()[1..] },
         );
-        Ok(TardisCacheClient { pool })
+        Ok(TardisCacheClient {
+            backend: CacheBackend::Redis { pool, client },
+            codec: *codec,
+            rate_limiter: rate_limiter.clone(),
+            timer: timer.clone(),
+        })
+    }
+
+    /// Create an in-memory client for tests, with an empty store / 创建用于测试的内存客户端，存储为空
+    ///
+    /// @see [TardisMemoryCache](crate::test::memory_cache::TardisMemoryCache)
+    #[cfg(feature = "test")]
+    pub fn memory() -> TardisCacheClient {
+        info!("[Tardis.CacheClient] Initialized in memory");
+        TardisCacheClient {
+            backend: CacheBackend::Memory(Arc::new(crate::test::memory_cache::TardisMemoryCache::new())),
+            codec: CacheCodec::default(),
+            rate_limiter: RateLimiterConfig::default(),
+            timer: TimerConfig::default(),
+        }
+    }
+
+    #[cfg(feature = "test")]
+    pub(crate) fn memory_store(&self) -> Option<&crate::test::memory_cache::TardisMemoryCache> {
+        match &self.backend {
+            CacheBackend::Memory(memory) => Some(memory),
+            _ => None,
+        }
+    }
+
+    /// Check the connectivity by `PING` / 通过 `PING` 检查连通性
+    pub async fn health_check(&self) -> TardisResult<()> {
+        dispatch!(self, "health_check", |_memory| Ok(()), |conn| {
+            redis::cmd("PING").query_async::<_, String>(&mut conn).await.map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// Create a rate limiter with the configured [`RateLimiterConfig`] / 使用所配置的 [`RateLimiterConfig`] 创建限流器
+    ///
+    /// @see [TardisRateLimiter]
+    pub fn rate_limiter(&self) -> TardisRateLimiter {
+        TardisRateLimiter::new(self.clone(), self.rate_limiter.clone())
+    }
+
+    /// Create a delayed task queue with the configured [`TimerConfig`] / 使用所配置的 [`TimerConfig`] 创建延时任务队列
+    ///
+    /// @see [TardisTimer]
+    pub fn timer(&self) -> TardisTimer {
+        TardisTimer::new(self.clone(), self.timer.clone())
     }
 
     async fn get_connection(&self) -> RedisResult<Connection> {
-        self.pool.get().await.map_err(|error| RedisError::from((ErrorKind::IoError, "Get connection error", error.to_string())))
+        match &self.backend {
+            CacheBackend::Redis { pool, .. } => pool.get().await.map_err(|error| RedisError::from((ErrorKind::IoError, "Get connection error", error.to_string()))),
+            #[cfg(feature = "test")]
+            CacheBackend::Memory(_) => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Get connection error",
+                "not supported by the in-memory client".to_string(),
+            ))),
+        }
     }
 
     pub async fn set(&self, key: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] set, key:{}, value:{}", key, value);
-        self.get_connection().await?.set(key, value).await
+        dispatch!(self, "set", |memory| memory.set(key, value), |conn| conn.set(key, value).await)
     }
 
     pub async fn set_ex(&self, key: &str, value: &str, ex_sec: usize) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] set_ex, key:{}, value:{}, ex_sec:{}", key, value, ex_sec);
-        self.get_connection().await?.set_ex(key, value, ex_sec).await
+        dispatch!(self, "set_ex", |memory| memory.set_ex(key, value, ex_sec), |conn| conn.set_ex(key, value, ex_sec).await)
     }
 
     pub async fn set_nx(&self, key: &str, value: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] set_nx, key:{}, value:{}", key, value);
-        self.get_connection().await?.set_nx(key, value).await
+        dispatch!(self, "set_nx", |memory| memory.set_nx(key, value), |conn| conn.set_nx(key, value).await)
     }
 
     pub async fn get(&self, key: &str) -> RedisResult<Option<String>> {
         trace!("[Tardis.CacheClient] get, key:{}", key);
-        self.get_connection().await?.get(key).await
+        dispatch!(self, "get", |memory| memory.get(key), |conn| conn.get(key).await)
     }
 
     pub async fn getset(&self, key: &str, value: &str) -> RedisResult<Option<String>> {
         trace!("[Tardis.CacheClient] getset, key:{}, value:{}", key, value);
-        self.get_connection().await?.getset(key, value).await
+        dispatch!(self, "getset", |memory| memory.getset(key, value), |conn| conn.getset(key, value).await)
     }
 
     pub async fn incr(&self, key: &str, delta: isize) -> RedisResult<isize> {
         trace!("[Tardis.CacheClient] incr, key:{}, delta:{}", key, delta);
-        self.get_connection().await?.incr(key, delta).await
+        dispatch!(self, "incr", |memory| memory.incr(key, delta), |conn| conn.incr(key, delta).await)
     }
 
     pub async fn del(&self, key: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] del, key:{}", key);
-        self.get_connection().await?.del(key).await
+        dispatch!(self, "del", |memory| memory.del(key), |conn| conn.del(key).await)
     }
 
     pub async fn del_confirm(&self, key: &str) -> RedisResult<()> {
@@ -118,86 +255,92 @@ impl TardisCacheClient {
 
     pub async fn exists(&self, key: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] exists, key:{}", key);
-        self.get_connection().await?.exists(key).await
+        dispatch!(self, "exists", |memory| memory.exists(key), |conn| conn.exists(key).await)
     }
 
     pub async fn expire(&self, key: &str, ex_sec: usize) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] expire, key:{}, ex_sec:{}", key, ex_sec);
-        self.get_connection().await?.expire(key, ex_sec).await
+        dispatch!(self, "expire", |memory| memory.expire(key, ex_sec), |conn| conn.expire(key, ex_sec).await)
     }
 
     pub async fn expire_at(&self, key: &str, timestamp_sec: usize) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] expire_at, key:{}, timestamp_sec:{}", key, timestamp_sec);
-        self.get_connection().await?.expire_at(key, timestamp_sec).await
+        dispatch!(self, "expire_at", |memory| memory.expire_at(key, timestamp_sec), |conn| {
+            conn.expire_at(key, timestamp_sec).await
+        })
     }
 
     pub async fn ttl(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] ttl, key:{}", key);
-        self.get_connection().await?.ttl(key).await
+        dispatch!(self, "ttl", |memory| memory.ttl(key), |conn| conn.ttl(key).await)
     }
 
     // list operations
 
     pub async fn lpush(&self, key: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] lpush, key:{}, value:{}", key, value);
-        self.get_connection().await?.lpush(key, value).await
+        dispatch!(self, "lpush", |memory| memory.lpush(key, value), |conn| conn.lpush(key, value).await)
     }
 
     pub async fn rpush(&self, key: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] rpush, key:{}, value:{}", key, value);
-        self.get_connection().await?.rpush(key, value).await
+        dispatch!(self, "rpush", |memory| memory.rpush(key, value), |conn| conn.rpush(key, value).await)
     }
 
     pub async fn lrangeall(&self, key: &str) -> RedisResult<Vec<String>> {
         trace!("[Tardis.CacheClient] lrangeall, key:{}", key);
-        self.get_connection().await?.lrange(key, 0, -1).await
+        dispatch!(self, "lrangeall", |memory| memory.lrangeall(key), |conn| conn.lrange(key, 0, -1).await)
     }
 
     pub async fn llen(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] llen, key:{}", key);
-        self.get_connection().await?.llen(key).await
+        dispatch!(self, "llen", |memory| memory.llen(key), |conn| conn.llen(key).await)
     }
 
     pub async fn lrem(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] lrem, key:{}", key);
-        self.get_connection().await?.lrem(key, count, value).await
+        dispatch!(self, "lrem", |memory| memory.lrem(key, count, value), |conn| conn.lrem(key, count, value).await)
     }
 
     pub async fn linsert_after(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] linsert_after, key:{}", key);
-        self.get_connection().await?.linsert_after(key, count, value).await
+        dispatch!(self, "linsert_after", |memory| memory.linsert_after(key, count, value), |conn| {
+            conn.linsert_after(key, count, value).await
+        })
     }
 
     pub async fn linsert_before(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] linsert_before, key:{}", key);
-        self.get_connection().await?.linsert_before(key, count, value).await
+        dispatch!(self, "linsert_before", |memory| memory.linsert_before(key, count, value), |conn| {
+            conn.linsert_before(key, count, value).await
+        })
     }
 
     pub async fn lset(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] lset, key:{}", key);
-        self.get_connection().await?.lset(key, count, value).await
+        dispatch!(self, "lset", |memory| memory.lset(key, count, value), |conn| conn.lset(key, count, value).await)
     }
 
     // hash operations
 
     pub async fn hget(&self, key: &str, field: &str) -> RedisResult<Option<String>> {
         trace!("[Tardis.CacheClient] hget, key:{}, field:{}", key, field);
-        self.get_connection().await?.hget(key, field).await
+        dispatch!(self, "hget", |memory| memory.hget(key, field), |conn| conn.hget(key, field).await)
     }
 
     pub async fn hset(&self, key: &str, field: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] hset, key:{}, field:{}, value:{}", key, field, value);
-        self.get_connection().await?.hset(key, field, value).await
+        dispatch!(self, "hset", |memory| memory.hset(key, field, value), |conn| conn.hset(key, field, value).await)
     }
 
     pub async fn hset_nx(&self, key: &str, field: &str, value: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] hset_nx, key:{}, field:{}, value:{}", key, field, value);
-        self.get_connection().await?.hset_nx(key, field, value).await
+        dispatch!(self, "hset_nx", |memory| memory.hset_nx(key, field, value), |conn| conn.hset_nx(key, field, value).await)
     }
 
     pub async fn hdel(&self, key: &str, field: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] hdel, key:{}, field:{}", key, field);
-        self.get_connection().await?.hdel(key, field).await
+        dispatch!(self, "hdel", |memory| memory.hdel(key, field), |conn| conn.hdel(key, field).await)
     }
 
     pub async fn hdel_confirm(&self, key: &str, field: &str) -> RedisResult<()> {
@@ -218,81 +361,726 @@ impl TardisCacheClient {
 
     pub async fn hincr(&self, key: &str, field: &str, delta: isize) -> RedisResult<isize> {
         trace!("[Tardis.CacheClient] hincr, key:{}, field:{}, delta:{}", key, field, delta);
-        self.get_connection().await?.hincr(key, field, delta).await
+        dispatch!(self, "hincr", |memory| memory.hincr(key, field, delta), |conn| conn.hincr(key, field, delta).await)
     }
 
     pub async fn hexists(&self, key: &str, field: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] hexists, key:{}, field:{}", key, field);
-        self.get_connection().await?.hexists(key, field).await
+        dispatch!(self, "hexists", |memory| memory.hexists(key, field), |conn| conn.hexists(key, field).await)
     }
 
     pub async fn hkeys(&self, key: &str) -> RedisResult<Vec<String>> {
         trace!("[Tardis.CacheClient] hkeys, key:{}", key);
-        self.get_connection().await?.hkeys(key).await
+        dispatch!(self, "hkeys", |memory| memory.hkeys(key), |conn| conn.hkeys(key).await)
     }
 
     pub async fn hvals(&self, key: &str) -> RedisResult<Vec<String>> {
         trace!("[Tardis.CacheClient] hvals, key:{}", key);
-        self.get_connection().await?.hvals(key).await
+        dispatch!(self, "hvals", |memory| memory.hvals(key), |conn| conn.hvals(key).await)
     }
 
     pub async fn hgetall(&self, key: &str) -> RedisResult<HashMap<String, String>> {
         trace!("[Tardis.CacheClient] hgetall, key:{}", key);
-        self.get_connection().await?.hgetall(key).await
+        dispatch!(self, "hgetall", |memory| memory.hgetall(key), |conn| conn.hgetall(key).await)
     }
 
     pub async fn hlen(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] hlen, key:{}", key);
-        self.get_connection().await?.hlen(key).await
+        dispatch!(self, "hlen", |memory| memory.hlen(key), |conn| conn.hlen(key).await)
+    }
+
+    // sorted set operations
+
+    /// Add the member or update its score, returns `true` if the member is new / 添加成员或更新其分数，成员为新增时返回 `true`
+    pub async fn zadd(&self, key: &str, member: &str, score: f64) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] zadd, key:{}, member:{}, score:{}", key, member, score);
+        dispatch!(self, "zadd", |memory| memory.zadd(key, member, score), |conn| conn.zadd(key, member, score).await)
+    }
+
+    /// Add the `(score, member)` pairs, returns the number of the new members / 添加 `(分数, 成员)` 对，返回新增成员的数量
+    pub async fn zadd_members(&self, key: &str, members: &[(f64, &str)]) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] zadd_members, key:{}, members:{:?}", key, members);
+        dispatch!(self, "zadd_members", |memory| memory.zadd_members(key, members), |conn| {
+            conn.zadd_multiple(key, members).await
+        })
+    }
+
+    /// Increase the score of the member (`ZINCRBY`), returns the new score / 增加成员的分数（ `ZINCRBY` ），返回新的分数
+    pub async fn zincr(&self, key: &str, member: &str, delta: f64) -> RedisResult<f64> {
+        trace!("[Tardis.CacheClient] zincr, key:{}, member:{}, delta:{}", key, member, delta);
+        dispatch!(self, "zincr", |memory| memory.zincr(key, member, delta), |conn| conn.zincr(key, member, delta).await)
+    }
+
+    pub async fn zrem(&self, key: &str, member: &str) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] zrem, key:{}, member:{}", key, member);
+        dispatch!(self, "zrem", |memory| memory.zrem(key, member), |conn| conn.zrem(key, member).await)
+    }
+
+    /// Move the member to the `destination` sorted set with the score atomically, returns `false` if it isn't in the `source` sorted set
+    /// / 以指定分数将成员原子地移动到 `destination` 有序集合，不在 `source` 有序集合中时返回 `false`
+    pub async fn zmove(&self, source: &str, destination: &str, member: &str, score: f64) -> RedisResult<bool> {
+        trace!(
+            "[Tardis.CacheClient] zmove, source:{}, destination:{}, member:{}, score:{}",
+            source,
+            destination,
+            member,
+            score
+        );
+        dispatch!(self, "zmove", |memory| memory.zmove(source, destination, member, score), |conn| {
+            let moved: usize = redis::Script::new(ZMOVE_SCRIPT).key(source).key(destination).arg(member).arg(score).invoke_async(&mut conn).await?;
+            Ok(moved > 0)
+        })
+    }
+
+    pub async fn zcard(&self, key: &str) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] zcard, key:{}", key);
+        dispatch!(self, "zcard", |memory| memory.zcard(key), |conn| conn.zcard(key).await)
+    }
+
+    pub async fn zscore(&self, key: &str, member: &str) -> RedisResult<Option<f64>> {
+        trace!("[Tardis.CacheClient] zscore, key:{}, member:{}", key, member);
+        dispatch!(self, "zscore", |memory| memory.zscore(key, member), |conn| conn.zscore(key, member).await)
+    }
+
+    /// Get the 0-based rank of the member ordered by score ascending / 获取成员按分数升序的排名（从0开始）
+    pub async fn zrank(&self, key: &str, member: &str) -> RedisResult<Option<usize>> {
+        trace!("[Tardis.CacheClient] zrank, key:{}, member:{}", key, member);
+        dispatch!(self, "zrank", |memory| memory.zrank(key, member), |conn| conn.zrank(key, member).await)
+    }
+
+    /// Get the 0-based rank of the member ordered by score descending / 获取成员按分数降序的排名（从0开始）
+    pub async fn zrevrank(&self, key: &str, member: &str) -> RedisResult<Option<usize>> {
+        trace!("[Tardis.CacheClient] zrevrank, key:{}, member:{}", key, member);
+        dispatch!(self, "zrevrank", |memory| memory.zrevrank(key, member), |conn| conn.zrevrank(key, member).await)
+    }
+
+    /// Get the members in the rank range (inclusive, negative index counts from the end) ordered by score ascending / 获取排名范围内（闭区间，负数索引从末尾计数）按分数升序的成员
+    pub async fn zrange(&self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<String>> {
+        trace!("[Tardis.CacheClient] zrange, key:{}, start:{}, stop:{}", key, start, stop);
+        dispatch!(
+            self,
+            "zrange",
+            |memory| Ok(memory.zrange_withscores(key, start, stop)?.into_iter().map(|(member, _)| member).collect()),
+            |conn| conn.zrange(key, start, stop).await
+        )
+    }
+
+    pub async fn zrange_withscores(&self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<(String, f64)>> {
+        trace!("[Tardis.CacheClient] zrange_withscores, key:{}, start:{}, stop:{}", key, start, stop);
+        dispatch!(self, "zrange_withscores", |memory| memory.zrange_withscores(key, start, stop), |conn| {
+            conn.zrange_withscores(key, start, stop).await
+        })
+    }
+
+    /// Get the members in the rank range ordered by score descending, e.g. the top 10 of a leaderboard is `zrevrange(key, 0, 9)` / 获取排名范围内按分数降序的成员，如排行榜前10名为 `zrevrange(key, 0, 9)`
+    pub async fn zrevrange(&self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<String>> {
+        trace!("[Tardis.CacheClient] zrevrange, key:{}, start:{}, stop:{}", key, start, stop);
+        dispatch!(
+            self,
+            "zrevrange",
+            |memory| Ok(memory.zrevrange_withscores(key, start, stop)?.into_iter().map(|(member, _)| member).collect()),
+            |conn| conn.zrevrange(key, start, stop).await
+        )
+    }
+
+    pub async fn zrevrange_withscores(&self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<(String, f64)>> {
+        trace!("[Tardis.CacheClient] zrevrange_withscores, key:{}, start:{}, stop:{}", key, start, stop);
+        dispatch!(self, "zrevrange_withscores", |memory| memory.zrevrange_withscores(key, start, stop), |conn| {
+            conn.zrevrange_withscores(key, start, stop).await
+        })
+    }
+
+    /// Get the members with the score in `[min, max]` ordered by score ascending, use `f64::NEG_INFINITY` / `f64::INFINITY` for the unbounded ranges / 获取分数在 `[min, max]` 内按分数升序的成员，无界范围使用 `f64::NEG_INFINITY` / `f64::INFINITY`
+    pub async fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> RedisResult<Vec<String>> {
+        trace!("[Tardis.CacheClient] zrangebyscore, key:{}, min:{}, max:{}", key, min, max);
+        dispatch!(
+            self,
+            "zrangebyscore",
+            |memory| Ok(memory.zrangebyscore_withscores(key, min, max, None)?.into_iter().map(|(member, _)| member).collect()),
+            |conn| conn.zrangebyscore(key, min, max).await
+        )
+    }
+
+    pub async fn zrangebyscore_withscores(&self, key: &str, min: f64, max: f64) -> RedisResult<Vec<(String, f64)>> {
+        trace!("[Tardis.CacheClient] zrangebyscore_withscores, key:{}, min:{}, max:{}", key, min, max);
+        dispatch!(self, "zrangebyscore_withscores", |memory| memory.zrangebyscore_withscores(key, min, max, None), |conn| {
+            conn.zrangebyscore_withscores(key, min, max).await
+        })
+    }
+
+    /// Same as [`zrangebyscore`](Self::zrangebyscore) with the pagination, a negative `count` returns all the members after the `offset` / 与 [`zrangebyscore`](Self::zrangebyscore) 相同并分页，`count` 为负数时返回 `offset` 之后的全部成员
+    pub async fn zrangebyscore_limit(&self, key: &str, min: f64, max: f64, offset: isize, count: isize) -> RedisResult<Vec<String>> {
+        trace!(
+            "[Tardis.CacheClient] zrangebyscore_limit, key:{}, min:{}, max:{}, offset:{}, count:{}",
+            key,
+            min,
+            max,
+            offset,
+            count
+        );
+        dispatch!(
+            self,
+            "zrangebyscore_limit",
+            |memory| Ok(memory.zrangebyscore_withscores(key, min, max, Some((offset, count)))?.into_iter().map(|(member, _)| member).collect()),
+            |conn| conn.zrangebyscore_limit(key, min, max, offset, count).await
+        )
+    }
+
+    /// Remove the members with the score in `[min, max]`, returns the number of the removed members / 删除分数在 `[min, max]` 内的成员，返回被删除成员的数量
+    pub async fn zremrangebyscore(&self, key: &str, min: f64, max: f64) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] zremrangebyscore, key:{}, min:{}, max:{}", key, min, max);
+        dispatch!(self, "zremrangebyscore", |memory| memory.zremrangebyscore(key, min, max), |conn| {
+            conn.zrembyscore(key, min, max).await
+        })
+    }
+
+    pub async fn zcount(&self, key: &str, min: f64, max: f64) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] zcount, key:{}, min:{}, max:{}", key, min, max);
+        dispatch!(self, "zcount", |memory| memory.zcount(key, min, max), |conn| conn.zcount(key, min, max).await)
     }
 
     // bitmap operations
 
     pub async fn setbit(&self, key: &str, offset: usize, value: bool) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] setbit, key:{}, offset:{}, value:{}", key, offset, value);
-        self.get_connection().await?.setbit(key, offset, value).await
+        dispatch!(self, "setbit", |memory| memory.setbit(key, offset, value), |conn| conn.setbit(key, offset, value).await)
     }
 
     pub async fn getbit(&self, key: &str, offset: usize) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] getbit, key:{}, offset:{}", key, offset);
-        self.get_connection().await?.getbit(key, offset).await
+        dispatch!(self, "getbit", |memory| memory.getbit(key, offset), |conn| conn.getbit(key, offset).await)
     }
 
     pub async fn bitcount(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] bitcount, key:{}", key);
-        self.get_connection().await?.bitcount(key).await
+        dispatch!(self, "bitcount", |memory| memory.bitcount(key), |conn| conn.bitcount(key).await)
     }
 
     pub async fn bitcount_range_by_byte(&self, key: &str, start: usize, end: usize) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] bitcount_range_by_byte, key:{}, start:{}, end:{}", key, start, end);
-        self.get_connection().await?.bitcount_range(key, start, end).await
+        dispatch!(self, "bitcount_range_by_byte", |memory| memory.bitcount_range_by_byte(key, start, end), |conn| {
+            conn.bitcount_range(key, start, end).await
+        })
     }
 
     /// Supported from version redis 7.0.0
     pub async fn bitcount_range_by_bit(&self, key: &str, start: usize, end: usize) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] bitcount_range_by_bit, key:{}, start:{}, end:{}", key, start, end);
-        match redis::cmd("BITCOUNT").arg(key).arg(start).arg(end).arg("BIT").query_async(&mut self.get_connection().await?).await {
-            Ok(count) => Ok(count),
+        dispatch!(self, "bitcount_range_by_bit", |memory| memory.bitcount_range_by_bit(key, start, end), |conn| {
+            redis::cmd("BITCOUNT").arg(key).arg(start).arg(end).arg("BIT").query_async(&mut conn).await
+        })
+    }
+
+    // pipeline operations
+
+    /// Create a pipeline, the queued commands are sent in one round trip / 创建管道，队列中的命令在一次往返中发送
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let (count, _): (isize, bool) = TardisFuns::cache().pipeline().incr("rate:u1", 1).expire("rate:u1", 60).query().await?;
+    /// ```
+    pub fn pipeline(&self) -> CachePipeline<'_> {
+        CachePipeline {
+            client: self,
+            atomic: false,
+            ops: Vec::new(),
+        }
+    }
+
+    /// Create a pipeline executed atomically in a `MULTI`/`EXEC` transaction / 创建在 `MULTI`/`EXEC` 事务中原子执行的管道
+    ///
+    /// The in-memory client executes the commands in order without isolation.
+    ///
+    /// 内存客户端按顺序执行命令，无隔离性.
+    pub fn atomic(&self) -> CachePipeline<'_> {
+        CachePipeline {
+            client: self,
+            atomic: true,
+            ops: Vec::new(),
+        }
+    }
+
+    // typed operations
+
+    /// Set the value encoded by the configured [`CacheCodec`] / 设置以所配置 [`CacheCodec`] 编码的值
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::cache().set_obj("user:1", &user).await?;
+    /// let user: Option<User> = TardisFuns::cache().get_obj("user:1").await?;
+    /// ```
+    pub async fn set_obj<T: Serialize>(&self, key: &str, value: &T) -> TardisResult<()> {
+        trace!("[Tardis.CacheClient] set_obj, key:{}", key);
+        let value = self.encode(value)?;
+        dispatch!(self, "set_obj", |memory| memory.set_bytes(key, value, None), |conn| conn.set::<_, _, ()>(key, value).await)?;
+        Ok(())
+    }
+
+    pub async fn set_obj_ex<T: Serialize>(&self, key: &str, value: &T, ex_sec: usize) -> TardisResult<()> {
+        trace!("[Tardis.CacheClient] set_obj_ex, key:{}, ex_sec:{}", key, ex_sec);
+        let value = self.encode(value)?;
+        dispatch!(self, "set_obj_ex", |memory| memory.set_bytes(key, value, Some(ex_sec)), |conn| {
+            conn.set_ex::<_, _, ()>(key, value, ex_sec).await
+        })?;
+        Ok(())
+    }
+
+    pub async fn get_obj<T: DeserializeOwned>(&self, key: &str) -> TardisResult<Option<T>> {
+        trace!("[Tardis.CacheClient] get_obj, key:{}", key);
+        let value: Option<Vec<u8>> = dispatch!(self, "get_obj", |memory| memory.get_bytes(key), |conn| conn.get(key).await)?;
+        value.map(|value| self.decode(&value)).transpose()
+    }
+
+    pub async fn hset_obj<T: Serialize>(&self, key: &str, field: &str, value: &T) -> TardisResult<()> {
+        trace!("[Tardis.CacheClient] hset_obj, key:{}, field:{}", key, field);
+        let value = self.encode(value)?;
+        dispatch!(self, "hset_obj", |memory| memory.hset_bytes(key, field, value), |conn| {
+            conn.hset::<_, _, _, ()>(key, field, value).await
+        })?;
+        Ok(())
+    }
+
+    pub async fn hget_obj<T: DeserializeOwned>(&self, key: &str, field: &str) -> TardisResult<Option<T>> {
+        trace!("[Tardis.CacheClient] hget_obj, key:{}, field:{}", key, field);
+        let value: Option<Vec<u8>> = dispatch!(self, "hget_obj", |memory| memory.hget_bytes(key, field), |conn| conn.hget(key, field).await)?;
+        value.map(|value| self.decode(&value)).transpose()
+    }
+
+    pub async fn hgetall_obj<T: DeserializeOwned>(&self, key: &str) -> TardisResult<HashMap<String, T>> {
+        trace!("[Tardis.CacheClient] hgetall_obj, key:{}", key);
+        let values: HashMap<String, Vec<u8>> = dispatch!(self, "hgetall_obj", |memory| memory.hgetall_bytes(key), |conn| conn.hgetall(key).await)?;
+        values.into_iter().map(|(field, value)| Ok((field, self.decode(&value)?))).collect()
+    }
+
+    fn encode<T: Serialize>(&self, value: &T) -> TardisResult<Vec<u8>> {
+        match self.codec {
+            CacheCodec::Json => Ok(TardisFuns::json.obj_to_string(value)?.into_bytes()),
+            CacheCodec::MessagePack => msgpack_encode(value),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, value: &[u8]) -> TardisResult<T> {
+        match self.codec {
+            CacheCodec::Json => TardisFuns::json.reader_to_obj(value),
+            CacheCodec::MessagePack => msgpack_decode(value),
+        }
+    }
+
+    // lock operations
+
+    /// Try to acquire the distributed lock of the key, returns `None` if it's held by others
+    /// / 尝试获取该键的分布式锁，被其他持有者持有时返回 `None`
+    ///
+    /// The lock is a `SET key <token> NX PX <ttl>`, it's released (only if it's still held by the token) when the guard is unlocked or dropped,
+    /// and expires after `ttl` if the holder crashed, use [`CacheLockGuard::auto_renew`] for the jobs that may take longer than `ttl`.
+    ///
+    /// 锁通过 `SET key <token> NX PX <ttl>` 实现，在守卫解锁或被drop时释放（仅当仍由该token持有时），持有者崩溃时在 `ttl` 后过期，
+    /// 对于可能超过 `ttl` 的任务可使用 [`CacheLockGuard::auto_renew`] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// if let Some(guard) = TardisFuns::cache().lock("job:sync", Duration::from_secs(10)).await? {
+    ///     let guard = guard.auto_renew();
+    ///     // do the job exclusively
+    ///     guard.unlock().await?;
+    /// }
+    /// ```
+    pub async fn lock(&self, key: &str, ttl: Duration) -> RedisResult<Option<CacheLockGuard>> {
+        trace!("[Tardis.CacheClient] lock, key:{}, ttl:{:?}", key, ttl);
+        let token = TardisFuns::field.nanoid();
+        let ttl_ms = ttl.as_millis() as u64;
+        let acquired = dispatch!(self, "lock", |memory| memory.set_nx_px(key, &token, ttl_ms), |conn| {
+            let result: Option<String> = redis::cmd("SET").arg(key).arg(&token).arg("NX").arg("PX").arg(ttl_ms).query_async(&mut conn).await?;
+            Ok(result.is_some())
+        })?;
+        Ok(acquired.then(|| CacheLockGuard {
+            client: self.clone(),
+            key: key.to_string(),
+            token,
+            ttl,
+            renewal: None,
+            released: false,
+        }))
+    }
+
+    /// Acquire the distributed lock of the key, retrying until `timeout`, returns `None` if timed out
+    /// / 获取该键的分布式锁，重试直到 `timeout` ，超时返回 `None`
+    ///
+    /// @see [lock](Self::lock)
+    pub async fn lock_wait(&self, key: &str, ttl: Duration, timeout: Duration) -> RedisResult<Option<CacheLockGuard>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(guard) = self.lock(key, ttl).await? {
+                return Ok(Some(guard));
+            }
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(LOCK_RETRY_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    async fn release_lock(&self, key: &str, token: &str) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] unlock, key:{}", key);
+        dispatch!(self, "unlock", |memory| memory.del_if_eq(key, token), |conn| {
+            let deleted: usize = redis::Script::new(UNLOCK_SCRIPT).key(key).arg(token).invoke_async(&mut conn).await?;
+            Ok(deleted > 0)
+        })
+    }
+
+    async fn extend_lock(&self, key: &str, token: &str, ttl_ms: u64) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] extend lock, key:{}, ttl_ms:{}", key, ttl_ms);
+        dispatch!(self, "extend_lock", |memory| memory.pexpire_if_eq(key, token, ttl_ms), |conn| {
+            let extended: usize = redis::Script::new(EXTEND_LOCK_SCRIPT).key(key).arg(token).arg(ttl_ms).invoke_async(&mut conn).await?;
+            Ok(extended > 0)
+        })
+    }
+
+    // pub/sub operations
+
+    /// Publish the message to the channel, returns the number of the subscribers that received it
+    /// / 发布消息到频道，返回接收到消息的订阅者数量
+    pub async fn publish(&self, channel: &str, message: &str) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] publish, channel:{}, message:{}", channel, message);
+        dispatch!(self, "publish", |memory| Ok(memory.publish(channel, message).await), |conn| {
+            conn.publish(channel, message).await
+        })
+    }
+
+    /// Subscribe the channel / 订阅频道
+    ///
+    /// The subscription uses a dedicated connection and reconnects (and resubscribes) automatically when disconnected,
+    /// the messages are dispatched to `fun` with `(channel, message)` in order, the errors of `fun` are logged.
+    ///
+    /// 订阅使用独立的连接，断开时会自动重连（并重新订阅），消息以 `(频道, 消息)` 按顺序分发给 `fun` ， `fun` 的错误仅记录日志.
+    ///
+    /// The messages published while reconnecting are lost, like the Redis pub/sub.
+    ///
+    /// 与Redis的发布订阅相同，重连期间发布的消息会丢失.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let subscription = TardisFuns::cache().subscribe("news", |(channel, message)| async move {
+    ///     println!("{channel}: {message}");
+    ///     Ok(())
+    /// }).await?;
+    /// TardisFuns::cache().publish("news", "hello").await?;
+    /// subscription.unsubscribe();
+    /// ```
+    pub async fn subscribe<F, T>(&self, channel: &str, fun: F) -> TardisResult<CacheSubscription>
+    where
+        F: Fn((String, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        trace!("[Tardis.CacheClient] subscribe, channel:{}", channel);
+        self.do_subscribe(CacheTopic::Channel(channel.to_string()), Arc::new(move |message| Box::pin(fun(message)))).await
+    }
+
+    /// Subscribe the channels matching the glob-style pattern, e.g. `news.*` / 订阅匹配glob风格模式的频道，如 `news.*`
+    ///
+    /// @see [subscribe](Self::subscribe)
+    pub async fn psubscribe<F, T>(&self, pattern: &str, fun: F) -> TardisResult<CacheSubscription>
+    where
+        F: Fn((String, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        trace!("[Tardis.CacheClient] psubscribe, pattern:{}", pattern);
+        self.do_subscribe(CacheTopic::Pattern(pattern.to_string()), Arc::new(move |message| Box::pin(fun(message)))).await
+    }
+
+    async fn do_subscribe(&self, topic: CacheTopic, handler: CacheMessageHandler) -> TardisResult<CacheSubscription> {
+        let client = match &self.backend {
+            CacheBackend::Redis { client, .. } => client.clone(),
+            #[cfg(feature = "test")]
+            CacheBackend::Memory(memory) => {
+                return Ok(CacheSubscription {
+                    inner: SubscriptionInner::Memory(memory.subscribe(topic, handler)),
+                })
+            }
+        };
+        // fail fast if the first subscription failed
+        let mut pubsub = Self::connect_pubsub(&client, &topic).await?;
+        let task = tokio::spawn(async move {
+            loop {
+                {
+                    let mut messages = pubsub.on_message();
+                    while let Some(message) = messages.next().await {
+                        let channel = message.get_channel_name().to_string();
+                        let payload = match message.get_payload::<String>() {
+                            Ok(payload) => payload,
+                            Err(error) => {
+                                warn!("[Tardis.CacheClient] Subscription of {topic} received an invalid message from {channel}: {error}");
+                                continue;
+                            }
+                        };
+                        trace!("[Tardis.CacheClient] Receive, channel:{}, message:{}", channel, payload);
+                        if let Err(error) = handler((channel.clone(), payload.clone())).await {
+                            error!("[Tardis.CacheClient] Receive process error, channel:{channel}, message:{payload} | {error}");
+                        }
+                    }
+                }
+                warn!("[Tardis.CacheClient] Subscription of {topic} disconnected, reconnecting");
+                let mut delay = SUBSCRIPTION_RECONNECT_MIN_DELAY;
+                pubsub = loop {
+                    tokio::time::sleep(delay).await;
+                    match Self::connect_pubsub(&client, &topic).await {
+                        Ok(pubsub) => {
+                            info!("[Tardis.CacheClient] Subscription of {topic} reconnected");
+                            break pubsub;
+                        }
+                        Err(error) => {
+                            warn!("[Tardis.CacheClient] Subscription of {topic} reconnect error, retry after {delay:?}: {error}");
+                            delay = (delay * 2).min(SUBSCRIPTION_RECONNECT_MAX_DELAY);
+                        }
+                    }
+                };
+            }
+        });
+        Ok(CacheSubscription {
+            inner: SubscriptionInner::Redis(task),
+        })
+    }
+
+    async fn connect_pubsub(client: &redis::Client, topic: &CacheTopic) -> RedisResult<PubSub> {
+        let mut pubsub = client.get_async_connection().await?.into_pubsub();
+        match topic {
+            CacheTopic::Channel(channel) => pubsub.subscribe(channel).await?,
+            CacheTopic::Pattern(pattern) => pubsub.psubscribe(pattern).await?,
+        }
+        Ok(pubsub)
+    }
+
+    // stream operations
+
+    /// Append the entry to the stream with an auto-generated id, returns the id / 以自动生成的id追加条目到流，返回id
+    ///
+    /// The stream operations are not supported by the in-memory client.
+    ///
+    /// 内存客户端不支持流操作.
+    pub async fn xadd(&self, key: &str, fields: &[(&str, &str)]) -> RedisResult<String> {
+        trace!("[Tardis.CacheClient] xadd, key:{}, fields:{:?}", key, fields);
+        dispatch!(self, "xadd", |conn| redis::cmd("XADD").arg(key).arg("*").arg(fields).query_async(&mut conn).await)
+    }
+
+    /// Append the entry and trim the stream to about `max_len` entries / 追加条目并将流修剪到约 `max_len` 个条目
+    pub async fn xadd_maxlen(&self, key: &str, max_len: usize, fields: &[(&str, &str)]) -> RedisResult<String> {
+        trace!("[Tardis.CacheClient] xadd_maxlen, key:{}, max_len:{}, fields:{:?}", key, max_len, fields);
+        dispatch!(self, "xadd_maxlen", |conn| {
+            redis::cmd("XADD").arg(key).arg("MAXLEN").arg("~").arg(max_len).arg("*").arg(fields).query_async(&mut conn).await
+        })
+    }
+
+    pub async fn xlen(&self, key: &str) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] xlen, key:{}", key);
+        dispatch!(self, "xlen", |conn| redis::cmd("XLEN").arg(key).query_async(&mut conn).await)
+    }
+
+    pub async fn xdel(&self, key: &str, ids: &[&str]) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] xdel, key:{}, ids:{:?}", key, ids);
+        dispatch!(self, "xdel", |conn| redis::cmd("XDEL").arg(key).arg(ids).query_async(&mut conn).await)
+    }
+
+    /// Read the entries after the ids from the streams, returns `(stream key, entries)` of the streams having new entries
+    /// / 从流中读取id之后的条目，返回有新条目的流的 `(流key, 条目)`
+    ///
+    /// `streams` are `(stream key, id)` pairs, use `$` as the id to read only the entries added after the call,
+    /// `block` waits for the new entries when none is available (`Duration::ZERO` waits forever).
+    ///
+    /// `streams` 为 `(流key, id)` 对，id使用 `$` 表示只读取调用之后添加的条目， `block` 在无可用条目时等待新条目（ `Duration::ZERO` 表示永久等待）.
+    pub async fn xread(&self, streams: &[(&str, &str)], count: usize, block: Option<Duration>) -> RedisResult<Vec<(String, Vec<CacheStreamEntry>)>> {
+        trace!("[Tardis.CacheClient] xread, streams:{:?}, count:{}", streams, count);
+        dispatch!(self, "xread", |conn| {
+            let mut cmd = redis::cmd("XREAD");
+            cmd.arg("COUNT").arg(count);
+            if let Some(block) = block {
+                cmd.arg("BLOCK").arg(block.as_millis() as u64);
+            }
+            cmd.arg("STREAMS").arg(streams.iter().map(|(key, _)| *key).collect::<Vec<_>>()).arg(streams.iter().map(|(_, id)| *id).collect::<Vec<_>>());
+            let result: Option<Vec<(String, Vec<CacheStreamEntry>)>> = cmd.query_async(&mut conn).await?;
+            Ok(result.unwrap_or_default())
+        })
+    }
+
+    /// Create the consumer group starting from the id (`$` for the new entries only, `0` for all), the stream is created if not exists,
+    /// returns `false` if the group already exists
+    /// / 创建从该id（ `$` 表示只消费新条目， `0` 表示全部）开始消费的消费组，流不存在时会被创建，消费组已存在时返回 `false`
+    pub async fn xgroup_create(&self, key: &str, group: &str, id: &str) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] xgroup_create, key:{}, group:{}, id:{}", key, group, id);
+        let result = dispatch!(self, "xgroup_create", |conn| {
+            redis::cmd("XGROUP").arg("CREATE").arg(key).arg(group).arg(id).arg("MKSTREAM").query_async::<_, ()>(&mut conn).await
+        });
+        match result {
+            Ok(()) => Ok(true),
+            Err(error) if error.code() == Some("BUSYGROUP") => Ok(false),
             Err(error) => Err(error),
         }
     }
 
+    pub async fn xgroup_destroy(&self, key: &str, group: &str) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] xgroup_destroy, key:{}, group:{}", key, group);
+        dispatch!(self, "xgroup_destroy", |conn| {
+            redis::cmd("XGROUP").arg("DESTROY").arg(key).arg(group).query_async(&mut conn).await
+        })
+    }
+
+    /// Read the entries never delivered to the other consumers of the group / 读取从未投递给消费组其他消费者的条目
+    ///
+    /// The entries are pending until acknowledged by [`xack`](Self::xack).
+    ///
+    /// 条目在被 [`xack`](Self::xack) 确认前处于待处理状态.
+    pub async fn xreadgroup(&self, key: &str, group: &str, consumer: &str, count: usize, block: Option<Duration>) -> RedisResult<Vec<CacheStreamEntry>> {
+        trace!("[Tardis.CacheClient] xreadgroup, key:{}, group:{}, consumer:{}, count:{}", key, group, consumer, count);
+        dispatch!(self, "xreadgroup", |conn| {
+            let mut cmd = redis::cmd("XREADGROUP");
+            cmd.arg("GROUP").arg(group).arg(consumer).arg("COUNT").arg(count);
+            if let Some(block) = block {
+                cmd.arg("BLOCK").arg(block.as_millis() as u64);
+            }
+            cmd.arg("STREAMS").arg(key).arg(">");
+            let result: Option<Vec<(String, Vec<Option<CacheStreamEntry>>)>> = cmd.query_async(&mut conn).await?;
+            Ok(result.unwrap_or_default().into_iter().flat_map(|(_, entries)| entries.into_iter().flatten()).collect())
+        })
+    }
+
+    /// Acknowledge the entries, returns the number of the entries acknowledged / 确认条目，返回被确认的条目数量
+    pub async fn xack(&self, key: &str, group: &str, ids: &[&str]) -> RedisResult<usize> {
+        trace!("[Tardis.CacheClient] xack, key:{}, group:{}, ids:{:?}", key, group, ids);
+        dispatch!(self, "xack", |conn| redis::cmd("XACK").arg(key).arg(group).arg(ids).query_async(&mut conn).await)
+    }
+
+    /// Transfer the entries pending longer than `min_idle` to the consumer, starting from the id `start` (`0-0` for the beginning),
+    /// returns the next start id (`0-0` if the scan is complete) and the entries (requires Redis 6.2+)
+    /// / 将待处理超过 `min_idle` 的条目从 `start` （ `0-0` 表示起始处）开始转移给该消费者，返回下次的起始id（扫描完成时为 `0-0` ）及条目（需要Redis 6.2+）
+    pub async fn xautoclaim(&self, key: &str, group: &str, consumer: &str, min_idle: Duration, start: &str, count: usize) -> RedisResult<(String, Vec<CacheStreamEntry>)> {
+        trace!("[Tardis.CacheClient] xautoclaim, key:{}, group:{}, consumer:{}, start:{}", key, group, consumer, start);
+        dispatch!(self, "xautoclaim", |conn| {
+            // Redis 7 appends the deleted ids as the third element
+            let result: Vec<redis::Value> =
+                redis::cmd("XAUTOCLAIM").arg(key).arg(group).arg(consumer).arg(min_idle.as_millis() as u64).arg(start).arg("COUNT").arg(count).query_async(&mut conn).await?;
+            let mut result = result.iter();
+            let next = match result.next() {
+                Some(next) => redis::from_redis_value(next)?,
+                None => "0-0".to_string(),
+            };
+            let entries: Vec<Option<CacheStreamEntry>> = match result.next() {
+                Some(entries) => redis::from_redis_value(entries)?,
+                None => Vec::new(),
+            };
+            Ok((next, entries.into_iter().flatten().collect()))
+        })
+    }
+
+    /// Consume the stream as a member of the consumer group / 作为消费组成员消费流
+    ///
+    /// The group is created (from the new entries) if not exists, the entries are dispatched to `fun` in order and acknowledged when `fun` succeeds.
+    /// The failed entries and the entries left by the crashed consumers stay pending and are reclaimed (and redelivered)
+    /// after being idle for 60 seconds, so `fun` should be idempotent. Disconnections are retried with backoff (requires Redis 6.2+).
+    ///
+    /// 消费组不存在时会被创建（从新条目开始），条目按顺序分发给 `fun` 并在 `fun` 成功时确认.
+    /// 失败的条目及崩溃消费者遗留的条目保持待处理状态，空闲60秒后被回收（并重新投递），因此 `fun` 应当幂等. 断开时按退避重试（需要Redis 6.2+）.
+    ///
+    /// The consumer holds a connection of the pool while waiting for the new entries.
+    ///
+    /// 消费者在等待新条目时占用连接池的一个连接.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let subscription = TardisFuns::cache().stream_consume("orders", "billing", "node-1", |entry| async move {
+    ///     println!("{}: {:?}", entry.id, entry.get("order_id"));
+    ///     Ok(())
+    /// }).await?;
+    /// TardisFuns::cache().xadd("orders", &[("order_id", "1")]).await?;
+    /// subscription.unsubscribe();
+    /// ```
+    pub async fn stream_consume<F, T>(&self, key: &str, group: &str, consumer: &str, fun: F) -> TardisResult<CacheSubscription>
+    where
+        F: Fn(CacheStreamEntry) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        trace!("[Tardis.CacheClient] stream_consume, key:{}, group:{}, consumer:{}", key, group, consumer);
+        let handler: CacheStreamHandler = Arc::new(move |entry| Box::pin(fun(entry)));
+        // fail fast if the group creation failed
+        self.xgroup_create(key, group, "$").await?;
+        let client = self.clone();
+        let (key, group, consumer) = (key.to_string(), group.to_string(), consumer.to_string());
+        let task = tokio::spawn(async move {
+            let mut delay = SUBSCRIPTION_RECONNECT_MIN_DELAY;
+            let mut last_reclaim: Option<std::time::Instant> = None;
+            loop {
+                match client.do_stream_consume(&key, &group, &consumer, &handler, &mut last_reclaim).await {
+                    Ok(()) => delay = SUBSCRIPTION_RECONNECT_MIN_DELAY,
+                    Err(error) => {
+                        warn!("[Tardis.CacheClient] Stream consumer {consumer} of {key}/{group} error, retry after {delay:?}: {error}");
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(SUBSCRIPTION_RECONNECT_MAX_DELAY);
+                        // the stream (and the group) was deleted
+                        if error.code() == Some("NOGROUP") {
+                            if let Err(error) = client.xgroup_create(&key, &group, "$").await {
+                                warn!("[Tardis.CacheClient] Stream consumer {consumer} of {key}/{group} recreate group error: {error}");
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Ok(CacheSubscription {
+            inner: SubscriptionInner::Redis(task),
+        })
+    }
+
+    async fn do_stream_consume(&self, key: &str, group: &str, consumer: &str, handler: &CacheStreamHandler, last_reclaim: &mut Option<std::time::Instant>) -> RedisResult<()> {
+        if !matches!(last_reclaim, Some(last_reclaim) if last_reclaim.elapsed() < STREAM_RECLAIM_MIN_IDLE) {
+            let mut start = "0-0".to_string();
+            loop {
+                let (next, entries) = self.xautoclaim(key, group, consumer, STREAM_RECLAIM_MIN_IDLE, &start, STREAM_CONSUME_COUNT).await?;
+                if !entries.is_empty() {
+                    info!(
+                        "[Tardis.CacheClient] Stream consumer {consumer} of {key}/{group} reclaimed {} pending entries",
+                        entries.len()
+                    );
+                }
+                self.handle_stream_entries(key, group, handler, entries).await?;
+                if next == "0-0" {
+                    break;
+                }
+                start = next;
+            }
+            *last_reclaim = Some(std::time::Instant::now());
+        }
+        let entries = self.xreadgroup(key, group, consumer, STREAM_CONSUME_COUNT, Some(STREAM_CONSUME_BLOCK)).await?;
+        self.handle_stream_entries(key, group, handler, entries).await
+    }
+
+    async fn handle_stream_entries(&self, key: &str, group: &str, handler: &CacheStreamHandler, entries: Vec<CacheStreamEntry>) -> RedisResult<()> {
+        for entry in entries {
+            let id = entry.id.clone();
+            trace!("[Tardis.CacheClient] Receive, stream:{}, id:{}", key, id);
+            match handler(entry).await {
+                Ok(()) => {
+                    self.xack(key, group, &[&id]).await?;
+                }
+                Err(error) => error!("[Tardis.CacheClient] Stream process error, stream:{key}, group:{group}, id:{id} | {error}"),
+            }
+        }
+        Ok(())
+    }
+
     // other operations
 
     pub async fn flushdb(&self) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] flushdb");
-        match redis::cmd("FLUSHDB").query_async(&mut self.get_connection().await?).await {
-            Ok(()) => Ok(()),
-            Err(error) => Err(error),
-        }
+        dispatch!(self, "flushdb", |memory| memory.flushdb(), |conn| redis::cmd("FLUSHDB").query_async(&mut conn).await)
     }
 
     pub async fn flushall(&self) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] flushall");
-        match redis::cmd("FLUSHALL").query_async(&mut self.get_connection().await?).await {
-            Ok(()) => Ok(()),
-            Err(error) => Err(error),
-        }
+        dispatch!(self, "flushall", |memory| memory.flushdb(), |conn| redis::cmd("FLUSHALL").query_async(&mut conn).await)
     }
 
     // custom
@@ -301,6 +1089,430 @@ impl TardisCacheClient {
     }
 }
 
+enum PipelineOp {
+    Set { key: String, value: String, ex_sec: Option<usize> },
+    Get { key: String },
+    Del { key: String },
+    Incr { key: String, delta: isize },
+    Expire { key: String, ex_sec: usize },
+    HSet { key: String, field: String, value: String },
+    HGet { key: String, field: String },
+    HDel { key: String, field: String },
+    HIncr { key: String, field: String, delta: isize },
+    LPush { key: String, value: String },
+    RPush { key: String, value: String },
+    Cmd(redis::Cmd),
+}
+
+/// Pipeline of the cache commands / 缓存命令管道
+///
+/// The results of the commands (except the ignored ones) are returned as a tuple or a `Vec` by [`query`](Self::query).
+///
+/// 命令的结果（被忽略的除外）由 [`query`](Self::query) 以元组或 `Vec` 返回.
+pub struct CachePipeline<'a> {
+    client: &'a TardisCacheClient,
+    atomic: bool,
+    // (op, ignored)
+    ops: Vec<(PipelineOp, bool)>,
+}
+
+impl CachePipeline<'_> {
+    fn push(mut self, op: PipelineOp) -> Self {
+        self.ops.push((op, false));
+        self
+    }
+
+    /// Ignore the result of the last command / 忽略上一条命令的结果
+    pub fn ignore(mut self) -> Self {
+        if let Some((_, ignored)) = self.ops.last_mut() {
+            *ignored = true;
+        }
+        self
+    }
+
+    pub fn set(self, key: &str, value: &str) -> Self {
+        self.push(PipelineOp::Set {
+            key: key.to_string(),
+            value: value.to_string(),
+            ex_sec: None,
+        })
+    }
+
+    pub fn set_ex(self, key: &str, value: &str, ex_sec: usize) -> Self {
+        self.push(PipelineOp::Set {
+            key: key.to_string(),
+            value: value.to_string(),
+            ex_sec: Some(ex_sec),
+        })
+    }
+
+    pub fn get(self, key: &str) -> Self {
+        self.push(PipelineOp::Get { key: key.to_string() })
+    }
+
+    pub fn del(self, key: &str) -> Self {
+        self.push(PipelineOp::Del { key: key.to_string() })
+    }
+
+    pub fn incr(self, key: &str, delta: isize) -> Self {
+        self.push(PipelineOp::Incr { key: key.to_string(), delta })
+    }
+
+    pub fn expire(self, key: &str, ex_sec: usize) -> Self {
+        self.push(PipelineOp::Expire { key: key.to_string(), ex_sec })
+    }
+
+    pub fn hset(self, key: &str, field: &str, value: &str) -> Self {
+        self.push(PipelineOp::HSet {
+            key: key.to_string(),
+            field: field.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    pub fn hget(self, key: &str, field: &str) -> Self {
+        self.push(PipelineOp::HGet {
+            key: key.to_string(),
+            field: field.to_string(),
+        })
+    }
+
+    pub fn hdel(self, key: &str, field: &str) -> Self {
+        self.push(PipelineOp::HDel {
+            key: key.to_string(),
+            field: field.to_string(),
+        })
+    }
+
+    pub fn hincr(self, key: &str, field: &str, delta: isize) -> Self {
+        self.push(PipelineOp::HIncr {
+            key: key.to_string(),
+            field: field.to_string(),
+            delta,
+        })
+    }
+
+    pub fn lpush(self, key: &str, value: &str) -> Self {
+        self.push(PipelineOp::LPush {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    pub fn rpush(self, key: &str, value: &str) -> Self {
+        self.push(PipelineOp::RPush {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Add a custom command, not supported by the in-memory client / 添加自定义命令，内存客户端不支持
+    pub fn cmd(self, cmd: redis::Cmd) -> Self {
+        self.push(PipelineOp::Cmd(cmd))
+    }
+
+    /// Execute the commands and return the results / 执行命令并返回结果
+    pub async fn query<T: redis::FromRedisValue>(self) -> RedisResult<T> {
+        trace!("[Tardis.CacheClient] pipeline, commands:{}, atomic:{}", self.ops.len(), self.atomic);
+        #[cfg(feature = "test")]
+        if let CacheBackend::Memory(memory) = &self.client.backend {
+            let mut results = Vec::new();
+            for (op, ignored) in &self.ops {
+                let result = Self::apply_memory(memory, op)?;
+                if !ignored {
+                    results.push(result);
+                }
+            }
+            return T::from_redis_value(&redis::Value::Bulk(results));
+        }
+        let mut pipe = redis::pipe();
+        if self.atomic {
+            pipe.atomic();
+        }
+        for (op, ignored) in self.ops {
+            match op {
+                PipelineOp::Set { key, value, ex_sec: None } => pipe.set(key, value),
+                PipelineOp::Set { key, value, ex_sec: Some(ex_sec) } => pipe.set_ex(key, value, ex_sec),
+                PipelineOp::Get { key } => pipe.get(key),
+                PipelineOp::Del { key } => pipe.del(key),
+                PipelineOp::Incr { key, delta } => pipe.incr(key, delta),
+                PipelineOp::Expire { key, ex_sec } => pipe.expire(key, ex_sec),
+                PipelineOp::HSet { key, field, value } => pipe.hset(key, field, value),
+                PipelineOp::HGet { key, field } => pipe.hget(key, field),
+                PipelineOp::HDel { key, field } => pipe.hdel(key, field),
+                PipelineOp::HIncr { key, field, delta } => pipe.hincr(key, field, delta),
+                PipelineOp::LPush { key, value } => pipe.lpush(key, value),
+                PipelineOp::RPush { key, value } => pipe.rpush(key, value),
+                PipelineOp::Cmd(cmd) => pipe.add_command(cmd),
+            };
+            if ignored {
+                pipe.ignore();
+            }
+        }
+        observe_client("cache", "pipeline", async { pipe.query_async(&mut self.client.get_connection().await?).await }).await
+    }
+
+    /// Execute the commands and discard the results / 执行命令并丢弃结果
+    pub async fn execute(self) -> RedisResult<()> {
+        self.query::<redis::Value>().await.map(|_| ())
+    }
+
+    #[cfg(feature = "test")]
+    fn apply_memory(memory: &crate::test::memory_cache::TardisMemoryCache, op: &PipelineOp) -> RedisResult<redis::Value> {
+        let to_data = |value: Option<String>| value.map(|value| redis::Value::Data(value.into_bytes())).unwrap_or(redis::Value::Nil);
+        Ok(match op {
+            PipelineOp::Set { key, value, ex_sec: None } => memory.set(key, value).map(|_| redis::Value::Okay)?,
+            PipelineOp::Set { key, value, ex_sec: Some(ex_sec) } => memory.set_ex(key, value, *ex_sec).map(|_| redis::Value::Okay)?,
+            PipelineOp::Get { key } => to_data(memory.get(key)?),
+            PipelineOp::Del { key } => {
+                let exists = memory.exists(key)?;
+                memory.del(key)?;
+                redis::Value::Int(exists as i64)
+            }
+            PipelineOp::Incr { key, delta } => redis::Value::Int(memory.incr(key, *delta)? as i64),
+            PipelineOp::Expire { key, ex_sec } => {
+                let exists = memory.exists(key)?;
+                memory.expire(key, *ex_sec)?;
+                redis::Value::Int(exists as i64)
+            }
+            PipelineOp::HSet { key, field, value } => {
+                let exists = memory.hexists(key, field)?;
+                memory.hset(key, field, value)?;
+                redis::Value::Int(!exists as i64)
+            }
+            PipelineOp::HGet { key, field } => to_data(memory.hget(key, field)?),
+            PipelineOp::HDel { key, field } => {
+                let exists = memory.hexists(key, field)?;
+                memory.hdel(key, field)?;
+                redis::Value::Int(exists as i64)
+            }
+            PipelineOp::HIncr { key, field, delta } => redis::Value::Int(memory.hincr(key, field, *delta)? as i64),
+            PipelineOp::LPush { key, value } => {
+                memory.lpush(key, value)?;
+                redis::Value::Int(memory.llen(key)? as i64)
+            }
+            PipelineOp::RPush { key, value } => {
+                memory.rpush(key, value)?;
+                redis::Value::Int(memory.llen(key)? as i64)
+            }
+            PipelineOp::Cmd(_) => {
+                return Err(RedisError::from((
+                    ErrorKind::ClientError,
+                    "Pipeline error",
+                    "custom commands are not supported by the in-memory client".to_string(),
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(feature = "cache-msgpack")]
+fn msgpack_encode<T: Serialize>(value: &T) -> TardisResult<Vec<u8>> {
+    rmp_serde::to_vec_named(value).map_err(|error| TardisError::format_error(&format!("[Tardis.CacheClient] MessagePack encode error: {error}"), "406-tardis-cache-codec-error"))
+}
+
+#[cfg(feature = "cache-msgpack")]
+fn msgpack_decode<T: DeserializeOwned>(value: &[u8]) -> TardisResult<T> {
+    rmp_serde::from_slice(value).map_err(|error| TardisError::format_error(&format!("[Tardis.CacheClient] MessagePack decode error: {error}"), "406-tardis-cache-codec-error"))
+}
+
+#[cfg(not(feature = "cache-msgpack"))]
+fn msgpack_encode<T: Serialize>(_: &T) -> TardisResult<Vec<u8>> {
+    Err(msgpack_unsupported())
+}
+
+#[cfg(not(feature = "cache-msgpack"))]
+fn msgpack_decode<T: DeserializeOwned>(_: &[u8]) -> TardisResult<T> {
+    Err(msgpack_unsupported())
+}
+
+#[cfg(not(feature = "cache-msgpack"))]
+fn msgpack_unsupported() -> TardisError {
+    TardisError::not_implemented(
+        "[Tardis.CacheClient] MessagePack codec requires the cache-msgpack feature",
+        "501-tardis-cache-codec-not-supported",
+    )
+}
+
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+const UNLOCK_SCRIPT: &str = r#"if redis.call("GET", KEYS[1]) == ARGV[1] then return redis.call("DEL", KEYS[1]) else return 0 end"#;
+const EXTEND_LOCK_SCRIPT: &str = r#"if redis.call("GET", KEYS[1]) == ARGV[1] then return redis.call("PEXPIRE", KEYS[1], ARGV[2]) else return 0 end"#;
+const ZMOVE_SCRIPT: &str = r#"if redis.call("ZREM", KEYS[1], ARGV[1]) == 1 then redis.call("ZADD", KEYS[2], ARGV[2], ARGV[1]) return 1 else return 0 end"#;
+
+/// Guard of the distributed lock, the lock is released when dropped / 分布式锁的守卫，drop时释放锁
+///
+/// Dropping releases the lock in a spawned task, call [`unlock`](Self::unlock) to release it immediately and check the result.
+///
+/// drop时在新建的任务中释放锁，调用 [`unlock`](Self::unlock) 可立即释放并检查结果.
+pub struct CacheLockGuard {
+    client: TardisCacheClient,
+    key: String,
+    token: String,
+    ttl: Duration,
+    renewal: Option<JoinHandle<()>>,
+    released: bool,
+}
+
+impl CacheLockGuard {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The unique token of the holder / 持有者的唯一token
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Renew the lock every third of the ttl while the guard is alive / 在守卫存活期间每隔三分之一ttl续期锁
+    pub fn auto_renew(mut self) -> Self {
+        let client = self.client.clone();
+        let key = self.key.clone();
+        let token = self.token.clone();
+        let ttl = self.ttl;
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        self.renewal = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval((ttl / 3).max(Duration::from_millis(1)));
+            // the first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match client.extend_lock(&key, &token, ttl.as_millis() as u64).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        warn!("[Tardis.CacheClient] Lock {key} is lost, stop renewing");
+                        break;
+                    }
+                    Err(error) => warn!("[Tardis.CacheClient] Renew lock {key} error: {error}"),
+                }
+            }
+        }));
+        self
+    }
+
+    /// Reset the ttl of the lock, returns `false` if the lock is lost (expired or held by others)
+    /// / 重置锁的ttl，锁已丢失（已过期或被其他持有者持有）时返回 `false`
+    pub async fn extend(&self, ttl: Duration) -> RedisResult<bool> {
+        self.client.extend_lock(&self.key, &self.token, ttl.as_millis() as u64).await
+    }
+
+    /// Release the lock, returns `false` if the lock is lost (expired or held by others)
+    /// / 释放锁，锁已丢失（已过期或被其他持有者持有）时返回 `false`
+    pub async fn unlock(mut self) -> RedisResult<bool> {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        self.released = true;
+        self.client.release_lock(&self.key, &self.token).await
+    }
+
+    /// Stop renewing and keep the lock until it expires / 停止续期并保留锁直到过期
+    pub fn detach(mut self) {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        self.released = true;
+    }
+}
+
+impl Drop for CacheLockGuard {
+    fn drop(&mut self) {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        if self.released {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            warn!("[Tardis.CacheClient] Lock {} is dropped out of the runtime, it will be released after the ttl", self.key);
+            return;
+        };
+        let client = self.client.clone();
+        let key = std::mem::take(&mut self.key);
+        let token = std::mem::take(&mut self.token);
+        handle.spawn(async move {
+            if let Err(error) = client.release_lock(&key, &token).await {
+                warn!("[Tardis.CacheClient] Release lock {key} error: {error}");
+            }
+        });
+    }
+}
+
+const SUBSCRIPTION_RECONNECT_MIN_DELAY: Duration = Duration::from_millis(500);
+const SUBSCRIPTION_RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+pub(crate) type CacheMessageHandler = Arc<dyn Fn((String, String)) -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send>> + Send + Sync>;
+
+const STREAM_CONSUME_COUNT: usize = 10;
+const STREAM_CONSUME_BLOCK: Duration = Duration::from_secs(5);
+const STREAM_RECLAIM_MIN_IDLE: Duration = Duration::from_secs(60);
+
+type CacheStreamHandler = Arc<dyn Fn(CacheStreamEntry) -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send>> + Send + Sync>;
+
+/// Channel or pattern of the subscription / 订阅的频道或模式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CacheTopic {
+    Channel(String),
+    Pattern(String),
+}
+
+impl std::fmt::Display for CacheTopic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheTopic::Channel(channel) => write!(f, "channel {channel}"),
+            CacheTopic::Pattern(pattern) => write!(f, "pattern {pattern}"),
+        }
+    }
+}
+
+/// Subscription of the cache pub/sub, keeps receiving until [`unsubscribe`](Self::unsubscribe) is called (dropping it doesn't unsubscribe)
+/// / 缓存发布订阅的订阅，在调用 [`unsubscribe`](Self::unsubscribe) 之前持续接收（drop不会取消订阅）
+pub struct CacheSubscription {
+    inner: SubscriptionInner,
+}
+
+enum SubscriptionInner {
+    Redis(JoinHandle<()>),
+    #[cfg(feature = "test")]
+    Memory(crate::test::memory_cache::MemorySubscription),
+}
+
+impl CacheSubscription {
+    /// Stop receiving and close the connection / 停止接收并关闭连接
+    pub fn unsubscribe(self) {
+        match self.inner {
+            SubscriptionInner::Redis(task) => task.abort(),
+            #[cfg(feature = "test")]
+            SubscriptionInner::Memory(subscription) => subscription.unsubscribe(),
+        }
+    }
+}
+
+/// Entry of the stream / 流的条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStreamEntry {
+    pub id: String,
+    pub fields: HashMap<String, String>,
+}
+
+impl CacheStreamEntry {
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(String::as_str)
+    }
+}
+
+impl redis::FromRedisValue for CacheStreamEntry {
+    fn from_redis_value(value: &redis::Value) -> RedisResult<Self> {
+        // the fields of the deleted entries are nil
+        let (id, fields): (String, Option<HashMap<String, String>>) = redis::from_redis_value(value)?;
+        Ok(CacheStreamEntry {
+            id,
+            fields: fields.unwrap_or_default(),
+        })
+    }
+}
+
 impl From<RedisError> for TardisError {
     fn from(error: RedisError) -> Self {
         error!("[Tardis.CacheClient] [{}]{}", error.code().unwrap_or(""), error.detail().unwrap_or(""));
@@ -0,0 +1,300 @@
+//! Delayed task queue / 延时任务队列
+//!
+//! The tasks are persisted in the cache, so they survive the restarts and are shared by all the nodes using the same cache:
+//!
+//! 任务持久化在缓存中，因此重启后仍然存在，并由使用同一缓存的所有节点共享：
+//!
+//! * `{key_prefix}{queue}:tasks` : hash of the tasks by id / 按id存储任务的hash
+//! * `{key_prefix}{queue}:due` : sorted set of the pending tasks scored by the due time / 按到期时间排序的待处理任务
+//! * `{key_prefix}{queue}:processing` : sorted set of the claimed tasks scored by the lease deadline / 按租约截止时间排序的已认领任务
+//! * `{key_prefix}{queue}:dead` : sorted set of the tasks failed after the retries / 重试后仍失败的任务
+//!
+//! A due task is claimed by moving it from the pending set to the claimed set atomically, which succeeds on only one consumer of the cluster,
+//! so each due task is delivered to exactly one consumer. The failed tasks are retried with the exponential backoff,
+//! and the tasks whose consumer crashed are delivered again after the lease, see [`TimerConfig`].
+//! The task is moved back only by the consumer still holding its lease, so no task is lost or revived by a late consumer.
+//! The times are taken from [`TardisFuns::clock`].
+//!
+//! 到期任务通过从待处理集合原子地移动到已认领集合来认领，集群中只有一个消费者能移动成功，因此每个到期任务只投递给一个消费者.
+//! 失败的任务以指数退避重试，消费者崩溃的任务在租期后再次投递，见 [`TimerConfig`] .
+//! 任务只由仍持有其租约的消费者移回，因此任务不会丢失，也不会被迟到的消费者恢复. 时间取自 [`TardisFuns::clock`] .
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::TardisFuns;
+//! let timer = TardisFuns::timer().queue("order_timeout");
+//! let id = timer.schedule_after(Duration::from_secs(1800), &order_id).await?;
+//! // the order is paid
+//! timer.cancel(&id).await?;
+//! // on each node
+//! timer.subscribe(|task: TardisDelayedTask<String>| async move { cancel_order(&task.payload).await });
+//! ```
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures_util::FutureExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing::{error, info, trace, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::cache::cache_client::TardisCacheClient;
+use crate::config::config_dto::component::cache::TimerConfig;
+use crate::TardisFuns;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TimerRecord {
+    payload: Value,
+    due_at: i64,
+    attempts: u32,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+/// Delayed task / 延时任务
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TardisDelayedTask<T> {
+    pub id: String,
+    pub queue: String,
+    pub payload: T,
+    /// The scheduled time / 计划时间
+    pub due_at: DateTime<Utc>,
+    /// Failed deliveries before / 此前失败的投递次数
+    pub attempts: u32,
+    /// Error of the last failed delivery / 上次失败投递的错误
+    pub last_error: Option<String>,
+}
+
+/// Delayed task queue / 延时任务队列
+#[derive(Clone)]
+pub struct TardisTimer {
+    client: TardisCacheClient,
+    config: TimerConfig,
+}
+
+impl TardisTimer {
+    pub fn new(client: TardisCacheClient, config: TimerConfig) -> TardisTimer {
+        TardisTimer { client, config }
+    }
+
+    pub fn config(&self) -> &TimerConfig {
+        &self.config
+    }
+
+    /// The queue of the name with the same options / 使用相同选项的指定名称队列
+    pub fn queue(&self, queue: &str) -> TardisTimer {
+        TardisTimer {
+            client: self.client.clone(),
+            config: TimerConfig {
+                queue: queue.to_string(),
+                ..self.config.clone()
+            },
+        }
+    }
+
+    fn key(&self, suffix: &str) -> String {
+        format!("{}{}:{}", self.config.key_prefix, self.config.queue, suffix)
+    }
+
+    /// Schedule the task after the delay, returns the id of the task / 在延迟后调度任务，返回任务id
+    pub async fn schedule_after<T: Serialize>(&self, delay: Duration, payload: &T) -> TardisResult<String> {
+        let delay =
+            chrono::Duration::from_std(delay).map_err(|error| TardisError::bad_request(&format!("[Tardis.Timer] Invalid delay: {error}"), "400-tardis-timer-delay-invalid"))?;
+        self.schedule_at(TardisFuns::clock().now() + delay, payload).await
+    }
+
+    /// Schedule the task at the time, the past time is due at once, returns the id of the task / 在指定时间调度任务，过去的时间立即到期，返回任务id
+    pub async fn schedule_at<T: Serialize>(&self, due_at: DateTime<Utc>, payload: &T) -> TardisResult<String> {
+        let id = TardisFuns::field.nanoid();
+        let record = TimerRecord {
+            payload: TardisFuns::json.obj_to_json(payload)?,
+            due_at: due_at.timestamp_millis(),
+            attempts: 0,
+            last_error: None,
+        };
+        trace!("[Tardis.Timer] schedule, queue:{}, id:{}, due_at:{}", self.config.queue, id, due_at);
+        // the task is stored before it's visible to the consumers
+        self.client.hset(&self.key("tasks"), &id, &TardisFuns::json.obj_to_string(&record)?).await?;
+        self.client.zadd(&self.key("due"), &id, record.due_at as f64).await?;
+        Ok(id)
+    }
+
+    /// Cancel the pending task, returns `false` if it doesn't exist or it's being delivered / 取消待处理的任务，任务不存在或正在投递时返回 `false`
+    pub async fn cancel(&self, id: &str) -> TardisResult<bool> {
+        trace!("[Tardis.Timer] cancel, queue:{}, id:{}", self.config.queue, id);
+        if !self.client.zrem(&self.key("due"), id).await? {
+            return Ok(false);
+        }
+        self.client.hdel(&self.key("tasks"), id).await?;
+        Ok(true)
+    }
+
+    /// Get the task that isn't delivered successfully yet / 获取尚未成功投递的任务
+    pub async fn get<T: DeserializeOwned>(&self, id: &str) -> TardisResult<Option<TardisDelayedTask<T>>> {
+        let Some(raw) = self.client.hget(&self.key("tasks"), id).await? else {
+            return Ok(None);
+        };
+        let record = TardisFuns::json.str_to_obj::<TimerRecord>(&raw)?;
+        Ok(Some(self.to_task(id, TardisFuns::json.json_to_obj(record.payload.clone())?, &record)))
+    }
+
+    /// Number of the pending tasks, including the ones waiting for a retry / 待处理任务的数量，包括等待重试的任务
+    pub async fn pending(&self) -> TardisResult<usize> {
+        Ok(self.client.zcard(&self.key("due")).await?)
+    }
+
+    /// The tasks failed after the retries, ordered by the failed time / 重试后仍失败的任务，按失败时间排序
+    pub async fn dead_letters<T: DeserializeOwned>(&self) -> TardisResult<Vec<TardisDelayedTask<T>>> {
+        let mut tasks = Vec::new();
+        for id in self.client.zrange(&self.key("dead"), 0, -1).await? {
+            if let Some(task) = self.get(&id).await? {
+                tasks.push(task);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Remove the task failed after the retries, returns `false` if it doesn't exist / 删除重试后仍失败的任务，不存在时返回 `false`
+    pub async fn remove_dead_letter(&self, id: &str) -> TardisResult<bool> {
+        if !self.client.zrem(&self.key("dead"), id).await? {
+            return Ok(false);
+        }
+        self.client.hdel(&self.key("tasks"), id).await?;
+        Ok(true)
+    }
+
+    /// Claim and deliver a batch of the due tasks, returns the number of the delivered tasks / 认领并投递一批到期任务，返回已投递的任务数
+    ///
+    /// The task is removed if the handler returns `Ok`, otherwise (an error or a panic) it's retried `max_retries` times and then moved to the dead letters.
+    ///
+    /// 处理函数返回 `Ok` 时删除任务，否则（返回错误或panic）重试 `max_retries` 次，之后移入死信.
+    pub async fn poll_once<T, F, Fut>(&self, handler: &F) -> TardisResult<usize>
+    where
+        T: DeserializeOwned,
+        F: Fn(TardisDelayedTask<T>) -> Fut,
+        Fut: Future<Output = TardisResult<()>>,
+    {
+        let now = TardisFuns::clock().timestamp_millis();
+        let batch_size = self.config.batch_size.max(1).min(isize::MAX as u64) as isize;
+        // the tasks whose consumer crashed
+        for id in self.client.zrangebyscore_limit(&self.key("processing"), f64::NEG_INFINITY, now as f64, 0, batch_size).await? {
+            if self.client.zmove(&self.key("processing"), &self.key("due"), &id, now as f64).await? {
+                warn!("[Tardis.Timer] The lease of task {id} expired, deliver it again, queue:{}", self.config.queue);
+            }
+        }
+        let mut delivered = 0;
+        for id in self.client.zrangebyscore_limit(&self.key("due"), f64::NEG_INFINITY, now as f64, 0, batch_size).await? {
+            let lease_deadline = now.saturating_add(self.config.lease_ms.min(i64::MAX as u64) as i64);
+            // only one consumer moves the task successfully
+            if !self.client.zmove(&self.key("due"), &self.key("processing"), &id, lease_deadline as f64).await? {
+                continue;
+            }
+            self.deliver(&id, handler).await?;
+            delivered += 1;
+        }
+        Ok(delivered)
+    }
+
+    async fn deliver<T, F, Fut>(&self, id: &str, handler: &F) -> TardisResult<()>
+    where
+        T: DeserializeOwned,
+        F: Fn(TardisDelayedTask<T>) -> Fut,
+        Fut: Future<Output = TardisResult<()>>,
+    {
+        let Some(raw) = self.client.hget(&self.key("tasks"), id).await? else {
+            warn!("[Tardis.Timer] Task {id} doesn't exist, queue:{}", self.config.queue);
+            self.client.zrem(&self.key("processing"), id).await?;
+            return Ok(());
+        };
+        let mut record = TardisFuns::json.str_to_obj::<TimerRecord>(&raw)?;
+        // the payload that can't be deserialized is never delivered successfully
+        let (result, retryable) = match TardisFuns::json.json_to_obj::<T>(record.payload.clone()) {
+            Ok(payload) => {
+                let task = self.to_task(id, payload, &record);
+                let result = match AssertUnwindSafe(handler(task)).catch_unwind().await {
+                    Ok(result) => result,
+                    Err(_) => Err(TardisError::internal_error(
+                        &format!("[Tardis.Timer] Handler of task {id} panicked"),
+                        "500-tardis-timer-handler-panic",
+                    )),
+                };
+                (result, true)
+            }
+            Err(error) => (Err(error), false),
+        };
+        match result {
+            Ok(()) => {
+                trace!("[Tardis.Timer] Task {id} delivered, queue:{}", self.config.queue);
+                self.client.hdel(&self.key("tasks"), id).await?;
+                self.client.zrem(&self.key("processing"), id).await?;
+                // delivered again after the lease expired meanwhile
+                self.client.zrem(&self.key("due"), id).await?;
+            }
+            Err(error) => {
+                let now = TardisFuns::clock().timestamp_millis();
+                record.attempts += 1;
+                record.last_error = Some(error.to_string());
+                let (destination, score) = if retryable && record.attempts <= self.config.max_retries {
+                    let backoff = self.config.backoff(record.attempts - 1);
+                    warn!("[Tardis.Timer] Task {id} failed, retry after {backoff:?}, queue:{} | {error}", self.config.queue);
+                    ("due", now.saturating_add(backoff.as_millis() as i64))
+                } else {
+                    error!("[Tardis.Timer] Task {id} failed, move it to the dead letters, queue:{} | {error}", self.config.queue);
+                    ("dead", now)
+                };
+                // the lease expired meanwhile, the task belongs to the consumer delivering it again,
+                // and the record is updated after the move so it isn't revived once that consumer succeeded
+                if !self.client.zmove(&self.key("processing"), &self.key(destination), id, score as f64).await? {
+                    warn!("[Tardis.Timer] The lease of task {id} expired before it failed, queue:{}", self.config.queue);
+                    return Ok(());
+                }
+                self.client.hset(&self.key("tasks"), id, &TardisFuns::json.obj_to_string(&record)?).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Poll and deliver the due tasks until the handle is aborted / 轮询并投递到期任务，直到句柄被中止
+    ///
+    /// @see [poll_once](Self::poll_once)
+    pub fn subscribe<T, F, Fut>(&self, handler: F) -> JoinHandle<()>
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(TardisDelayedTask<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let timer = self.clone();
+        info!("[Tardis.Timer] Subscribed, queue:{}", timer.config.queue);
+        tokio::spawn(async move {
+            loop {
+                let delivered = match timer.poll_once(&handler).await {
+                    Ok(delivered) => delivered,
+                    Err(error) => {
+                        error!("[Tardis.Timer] Poll error, queue:{} | {error}", timer.config.queue);
+                        0
+                    }
+                };
+                // the next batch may be due
+                if (delivered as u64) < timer.config.batch_size {
+                    tokio::time::sleep(Duration::from_millis(timer.config.poll_interval_ms)).await;
+                }
+            }
+        })
+    }
+
+    fn to_task<T>(&self, id: &str, payload: T, record: &TimerRecord) -> TardisDelayedTask<T> {
+        TardisDelayedTask {
+            id: id.to_string(),
+            queue: self.config.queue.clone(),
+            payload,
+            due_at: Utc.timestamp_millis_opt(record.due_at).single().unwrap_or_default(),
+            attempts: record.attempts,
+            last_error: record.last_error.clone(),
+        }
+    }
+}
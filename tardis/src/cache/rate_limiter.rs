@@ -0,0 +1,226 @@
+//! Rate limiter / 限流器
+//!
+//! Counts the requests of each key in the cache, so the limit is shared by all the nodes using the same cache.
+//! The strategies are executed atomically by Lua scripts on Redis, the current time is taken from [`TardisFuns::clock`].
+//!
+//! 在缓存中统计每个key的请求，从而限制由使用同一缓存的所有节点共享. 策略在Redis上由Lua脚本原子执行，当前时间取自 [`TardisFuns::clock`] .
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::TardisFuns;
+//! let decision = TardisFuns::cache().rate_limiter().acquire("login:u1").await?;
+//! if !decision.allowed {
+//!     println!("retry after {:?}", decision.retry_after);
+//! }
+//! // or fail with a 429 error
+//! TardisFuns::cache().rate_limiter().check("login:u1").await?;
+//! ```
+use std::time::Duration;
+
+use tracing::trace;
+
+use crate::basic::error::TardisError;
+use crate::basic::metrics::observe_client;
+use crate::basic::result::TardisResult;
+use crate::cache::cache_client::TardisCacheClient;
+use crate::config::config_dto::component::cache::{RateLimitStrategy, RateLimiterConfig};
+use crate::TardisFuns;
+
+// KEYS[1]: key, ARGV: limit, window_ms, now_ms
+const FIXED_WINDOW_SCRIPT: &str = r#"
+local limit = tonumber(ARGV[1])
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+if count <= limit then
+    return {1, limit - count}
+end
+return {0, 0}
+"#;
+
+// KEYS[1]: key, ARGV: limit, window_ms, now_ms, member
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+local limit = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+local count = redis.call('ZCARD', KEYS[1])
+if count < limit then
+    redis.call('ZADD', KEYS[1], now, ARGV[4])
+    redis.call('PEXPIRE', KEYS[1], window)
+    return {1, limit - count - 1, 0}
+end
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+return {0, 0, tonumber(oldest[2]) + window - now}
+"#;
+
+// KEYS[1]: key, ARGV: limit, window_ms, now_ms
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local limit = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local rate = limit / window
+local tokens = limit
+local state = redis.call('GET', KEYS[1])
+if state then
+    local sep = string.find(state, ':')
+    local ts = tonumber(string.sub(state, sep + 1))
+    tokens = math.min(limit, tonumber(string.sub(state, 1, sep - 1)) + math.max(0, now - ts) * rate)
+end
+local allowed = 0
+local retry = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+else
+    retry = math.ceil((1 - tokens) / rate)
+end
+redis.call('SET', KEYS[1], tokens .. ':' .. now, 'PX', window)
+return {allowed, math.floor(tokens), retry}
+"#;
+
+/// Result of a rate limit acquisition / 限流获取的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u64,
+    /// Requests still allowed in the current window / 当前窗口内仍允许的请求数
+    pub remaining: u64,
+    /// Time to wait before the next request is allowed, zero if allowed / 下一个请求被允许前需等待的时间，允许时为零
+    pub retry_after: Duration,
+}
+
+/// Rate limiter / 限流器
+#[derive(Clone)]
+pub struct TardisRateLimiter {
+    client: TardisCacheClient,
+    config: RateLimiterConfig,
+}
+
+impl TardisRateLimiter {
+    pub fn new(client: TardisCacheClient, config: RateLimiterConfig) -> TardisRateLimiter {
+        TardisRateLimiter { client, config }
+    }
+
+    pub fn config(&self) -> &RateLimiterConfig {
+        &self.config
+    }
+
+    /// Count a request of the key, returns whether it is allowed / 统计该key的一个请求，返回是否允许
+    ///
+    /// The denied requests are counted too by the fixed window strategy.
+    ///
+    /// 固定窗口策略也会统计被拒绝的请求.
+    pub async fn acquire(&self, key: &str) -> TardisResult<RateLimitDecision> {
+        trace!("[Tardis.RateLimiter] acquire, key:{}, strategy:{:?}", key, self.config.strategy);
+        let limit = self.config.limit;
+        let window = self.config.window_ms.max(1);
+        let now = TardisFuns::clock().timestamp_millis().max(0) as u64;
+        let key = format!("{}{}", self.config.key_prefix, key);
+        let (allowed, remaining, retry_after) = match self.config.strategy {
+            RateLimitStrategy::FixedWindow => {
+                let window_idx = now / window;
+                let (allowed, remaining) = self.fixed_window(&format!("{key}:{window_idx}"), limit, window).await?;
+                (allowed, remaining, if allowed { 0 } else { (window_idx + 1) * window - now })
+            }
+            RateLimitStrategy::SlidingWindow => self.sliding_window(&key, limit, window, now).await?,
+            RateLimitStrategy::TokenBucket => self.token_bucket(&key, limit, window, now).await?,
+        };
+        Ok(RateLimitDecision {
+            allowed,
+            limit,
+            remaining,
+            retry_after: Duration::from_millis(retry_after),
+        })
+    }
+
+    /// Same as [`acquire`](Self::acquire), returns a `429-tardis-rate-limited` error if the request is denied / 与 [`acquire`](Self::acquire) 相同，请求被拒绝时返回 `429-tardis-rate-limited` 错误
+    pub async fn check(&self, key: &str) -> TardisResult<RateLimitDecision> {
+        let decision = self.acquire(key).await?;
+        if !decision.allowed {
+            return Err(rate_limited(&decision));
+        }
+        Ok(decision)
+    }
+
+    async fn fixed_window(&self, key: &str, limit: u64, window: u64) -> TardisResult<(bool, u64)> {
+        #[cfg(feature = "test")]
+        if let Some(memory) = self.client.memory_store() {
+            return Ok(memory.update_bytes(key, window, |state| {
+                let count = parse_state(state).and_then(|state| state.parse::<u64>().ok()).unwrap_or(0) + 1;
+                (count.to_string().into_bytes(), (count <= limit, limit.saturating_sub(count)))
+            })?);
+        }
+        let (allowed, remaining): (u64, u64) = self.invoke("fixed_window", FIXED_WINDOW_SCRIPT, key, &[limit, window]).await?;
+        Ok((allowed == 1, remaining))
+    }
+
+    async fn sliding_window(&self, key: &str, limit: u64, window: u64, now: u64) -> TardisResult<(bool, u64, u64)> {
+        #[cfg(feature = "test")]
+        if let Some(memory) = self.client.memory_store() {
+            // the timestamps of the allowed requests separated by commas
+            return Ok(memory.update_bytes(key, window, |state| {
+                let mut timestamps =
+                    parse_state(state).map(|state| state.split(',').filter_map(|ts| ts.parse::<u64>().ok()).filter(|ts| ts + window > now).collect::<Vec<_>>()).unwrap_or_default();
+                let result = if (timestamps.len() as u64) < limit {
+                    timestamps.push(now);
+                    (true, limit - timestamps.len() as u64, 0)
+                } else {
+                    (false, 0, timestamps.first().map(|oldest| oldest + window - now).unwrap_or(window))
+                };
+                (timestamps.iter().map(u64::to_string).collect::<Vec<_>>().join(",").into_bytes(), result)
+            })?);
+        }
+        let member = format!("{now}-{}", TardisFuns::field.nanoid_len(8));
+        let (allowed, remaining, retry_after): (u64, u64, u64) = observe_client("cache", "rate_limit_sliding_window", async {
+            redis::Script::new(SLIDING_WINDOW_SCRIPT).key(key).arg(limit).arg(window).arg(now).arg(member).invoke_async(&mut self.client.cmd().await?).await
+        })
+        .await?;
+        Ok((allowed == 1, remaining, retry_after))
+    }
+
+    async fn token_bucket(&self, key: &str, limit: u64, window: u64, now: u64) -> TardisResult<(bool, u64, u64)> {
+        #[cfg(feature = "test")]
+        if let Some(memory) = self.client.memory_store() {
+            // `tokens:timestamp`, same as the script
+            return Ok(memory.update_bytes(key, window, |state| {
+                let rate = limit as f64 / window as f64;
+                let mut tokens = parse_state(state)
+                    .and_then(|state| state.split_once(':').and_then(|(tokens, ts)| Some((tokens.parse::<f64>().ok()?, ts.parse::<f64>().ok()?))))
+                    .map(|(tokens, ts)| (tokens + (now as f64 - ts).max(0.0) * rate).min(limit as f64))
+                    .unwrap_or(limit as f64);
+                let result = if tokens >= 1.0 {
+                    tokens -= 1.0;
+                    (true, tokens.floor() as u64, 0)
+                } else {
+                    (false, 0, ((1.0 - tokens) / rate).ceil() as u64)
+                };
+                (format!("{tokens}:{now}").into_bytes(), result)
+            })?);
+        }
+        let (allowed, remaining, retry_after): (u64, u64, u64) = self.invoke("token_bucket", TOKEN_BUCKET_SCRIPT, key, &[limit, window, now]).await?;
+        Ok((allowed == 1, remaining, retry_after))
+    }
+
+    async fn invoke<T: redis::FromRedisValue>(&self, strategy: &str, script: &str, key: &str, args: &[u64]) -> TardisResult<T> {
+        Ok(observe_client("cache", &format!("rate_limit_{strategy}"), async {
+            redis::Script::new(script).key(key).arg(args).invoke_async(&mut self.client.cmd().await?).await
+        })
+        .await?)
+    }
+}
+
+#[cfg(feature = "test")]
+/// The `429-tardis-rate-limited` error of the denied request / 被拒绝请求的 `429-tardis-rate-limited` 错误
+pub(crate) fn rate_limited(decision: &RateLimitDecision) -> TardisError {
+    TardisError::custom(
+        "429-tardis-rate-limited",
+        &format!("[Tardis.RateLimiter] Too many requests, retry after {}ms", decision.retry_after.as_millis()),
+        "",
+    )
+}
+
+fn parse_state(state: Option<&[u8]>) -> Option<&str> {
+    state.and_then(|state| std::str::from_utf8(state).ok())
+}
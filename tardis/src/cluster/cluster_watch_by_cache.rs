@@ -1,6 +1,5 @@
 use std::{collections::HashSet, net::SocketAddr, time::Duration};
 
-use chrono::Utc;
 use tokio::time;
 use tracing::{error, trace};
 
@@ -27,7 +26,7 @@ pub async fn init(cluster_config: &ClusterConfig, web_server_config: &WebServerC
         loop {
             {
                 trace!("[Tardis.Cluster] [Client] heartbeat...");
-                if let Err(error) = client.hset(CACHE_NODE_INFO_KEY, &access_addr.to_string(), &Utc::now().timestamp().to_string()).await {
+                if let Err(error) = client.hset(CACHE_NODE_INFO_KEY, &access_addr.to_string(), &TardisFuns::clock().timestamp().to_string()).await {
                     error!("[Tardis.Cluster] [Client] heartbeat error: {}", error);
                 }
             }
@@ -52,7 +51,7 @@ pub async fn init(cluster_config: &ClusterConfig, web_server_config: &WebServerC
 async fn watch(client: &TardisCacheClient, cache_check_interval_sec: i32) -> TardisResult<()> {
     trace!("[Tardis.Cluster] [Client] watching");
     let all_nodes = client.hgetall(CACHE_NODE_INFO_KEY).await?;
-    let active_ts = Utc::now().timestamp() - cache_check_interval_sec as i64 * CACHE_NODE_ALIVE_CHECK_DELAYED_TIMES as i64 - 1;
+    let active_ts = TardisFuns::clock().timestamp() - cache_check_interval_sec as i64 * CACHE_NODE_ALIVE_CHECK_DELAYED_TIMES as i64 - 1;
     let active_nodes = all_nodes
         .iter()
         .filter_map(|(active_node_key, active_node_ts)| (active_node_ts.parse::<i64>().unwrap_or(i64::MIN) > active_ts).then_some(active_node_key))
@@ -1,5 +1,12 @@
+#[cfg(feature = "conf-remote")]
+pub mod config_consul;
 pub mod config_dto;
 #[cfg(feature = "conf-remote")]
+pub mod config_etcd;
+#[cfg(feature = "conf-remote")]
 pub mod config_nacos;
+pub mod config_patch;
 pub mod config_processor;
+pub mod config_secret;
 pub(crate) mod config_utils;
+pub mod config_watcher;
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use config::ConfigError;
+use tracing::{debug, trace, warn};
+
+use super::{config_dto::ConfCenterConfig, config_processor::ConfCenterProcess};
+use crate::basic::result::TardisResult;
+use crate::config::config_utils::config_foreign_err;
+use crate::config::config_watcher;
+pub mod consul_client;
+
+/// consul recommends rate limiting the blocking queries
+const MIN_QUERY_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+/// Config from Consul KV,
+/// A source corresponding to a remote config
+pub(crate) struct ConfConsulConfigSource<F: config::Format> {
+    key: String,
+    /// consul client
+    consul_client: Arc<consul_client::ConsulClient>,
+    format: Arc<F>,
+    /// modify index of the key
+    index: Arc<tokio::sync::Mutex<Option<u64>>>,
+}
+
+impl<F: config::Format> std::fmt::Display for ConfConsulConfigSource<F>
+where
+    F: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ConfConsulConfigSource {{ key: {}, consul_client: {}, format: {:?} }}",
+            self.key, self.consul_client, self.format,
+        )
+    }
+}
+
+impl<F: config::Format> Clone for ConfConsulConfigSource<F> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            consul_client: self.consul_client.clone(),
+            format: self.format.clone(),
+            index: self.index.clone(),
+        }
+    }
+}
+
+impl<F: config::Format> ConfConsulConfigSource<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    /// create a new config source, the key is `[<namespace>/]<group>/<app_id>-<profile>`
+    fn new(profile: Option<&str>, app_id: &str, namespace: Option<&str>, group: &str, format: Arc<F>, consul_client: &Arc<consul_client::ConsulClient>) -> Self {
+        let data_id = format!("{}-{}", app_id, profile.unwrap_or("default"));
+        let key = match namespace {
+            Some(namespace) => format!("{namespace}/{group}/{data_id}"),
+            None => format!("{group}/{data_id}"),
+        };
+        Self {
+            key,
+            consul_client: consul_client.clone(),
+            format,
+            index: Arc::new(tokio::sync::Mutex::new(None)),
+        }
+    }
+
+    fn listen_update(self, update_notifier: tokio::sync::mpsc::Sender<()>) {
+        let task = async move {
+            debug!("[Tardis.config] Consul Remote listener start for {:?}", &self);
+            let mut last_index = *self.index.lock().await;
+            loop {
+                let started = Instant::now();
+                // blocking query, returns when the index changed or the wait time elapsed
+                match self.consul_client.get_kv(&self.key, Some(last_index.unwrap_or(0))).await {
+                    Ok((_, index)) => {
+                        // the index could go backwards when consul resets it, reload in this case too
+                        let updated = last_index.map(|last_index| last_index != index).unwrap_or(false);
+                        last_index = Some(index);
+                        if updated {
+                            match update_notifier.send(()).await {
+                                Ok(_) => {
+                                    debug!("[Tardis.config] Consul Remote config updated, send update notifier")
+                                }
+                                Err(e) => {
+                                    // if receiver dropped, stop watching, since tardis wont be reloaded anyway
+                                    warn!("[Tardis.config] Consul Remote config updated, but no receiver found, stop watching, error: {e}");
+                                    break;
+                                }
+                            }
+                        }
+                        let elapsed = started.elapsed();
+                        if elapsed < MIN_QUERY_INTERVAL {
+                            tokio::time::sleep(MIN_QUERY_INTERVAL - elapsed).await;
+                        }
+                    }
+                    Err(e) => {
+                        // if request failed, wait for next poll
+                        warn!("[Tardis.config] Consul Remote listen failed, error: {e}");
+                        tokio::time::sleep(self.consul_client.poll_period).await;
+                    }
+                }
+            }
+        };
+        config_watcher::add_task(tokio::spawn(task));
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: config::Format> config::AsyncSource for ConfConsulConfigSource<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn collect(&self) -> Result<config::Map<String, config::Value>, ConfigError> {
+        debug!("[Tardis.config] Consul Remote config server request: {}", &self);
+        let (config_text, index) = self.consul_client.get_kv(&self.key, None).await.map_err(config_foreign_err)?;
+        self.index.lock().await.replace(index);
+        match config_text {
+            Some(config_text) => {
+                trace!("[Tardis.config] Consul Remote config server response: {}", config_text);
+                self.format.parse(None, &config_text).map_err(ConfigError::Foreign)
+            }
+            None => {
+                warn!("[Tardis.config] Consul Remote config not found, config: {}", &self);
+                Ok(config::Map::new())
+            }
+        }
+    }
+}
+
+/// # Consul config processor
+/// implement ConfProcess for Consul KV
+#[derive(Debug)]
+pub(crate) struct ConfConsulProcessor<F: config::Format>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    /// *-default config source
+    pub(crate) default_config_source: ConfConsulConfigSource<F>,
+    /// *-{profile} config source, it could be none
+    pub(crate) config_source: Option<ConfConsulConfigSource<F>>,
+}
+
+impl<F: config::Format> ConfConsulProcessor<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    /// create a new consul config processor, the password is used as the acl token
+    pub async fn init(config: &ConfCenterConfig, profile: &str, app_id: &str, format: &Arc<F>) -> TardisResult<ConfConsulProcessor<F>> {
+        let mut client = consul_client::ConsulClient::new(config.url.as_str());
+        // set blocking query wait time, default to 5s
+        client.poll_period = Duration::from_millis(config.config_change_polling_interval.unwrap_or(5000));
+        if !config.password.is_empty() {
+            client = client.with_token(&config.password);
+        }
+        let consul_client = Arc::new(client);
+        let group = config.group.as_deref().unwrap_or("default");
+        let namespace = config.namespace.as_deref();
+        // there are two config source, *-{profile} could be empty
+        let default_config_source = ConfConsulConfigSource::new(None, app_id, namespace, group, format.clone(), &consul_client);
+        let config_source = if !profile.is_empty() {
+            Some(ConfConsulConfigSource::new(Some(profile), app_id, namespace, group, format.clone(), &consul_client))
+        } else {
+            None
+        };
+        Ok(Self {
+            default_config_source,
+            config_source,
+        })
+    }
+}
+
+impl<F: config::Format> ConfCenterProcess for ConfConsulProcessor<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+        self.default_config_source.clone().listen_update(reload_notifier.clone());
+        if let Some(h) = self.config_source.clone() {
+            h.listen_update(reload_notifier.clone())
+        }
+    }
+    fn register_to_config(&self, mut conf: config::ConfigBuilder<config::builder::AsyncState>) -> config::ConfigBuilder<config::builder::AsyncState> {
+        conf = conf.add_async_source(self.default_config_source.clone());
+        if let Some(config_source) = self.config_source.as_ref() {
+            conf = conf.add_async_source(config_source.clone());
+        }
+        conf
+    }
+}
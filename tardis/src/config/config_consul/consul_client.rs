@@ -0,0 +1,85 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use tracing::trace;
+
+use crate::basic::result::TardisResult;
+
+const CONSUL_INDEX_HEADER: &str = "X-Consul-Index";
+const CONSUL_TOKEN_HEADER: &str = "X-Consul-Token";
+
+/// for request consul kv api, see https://developer.hashicorp.com/consul/api-docs/kv
+#[derive(Debug, Clone)]
+pub struct ConsulClient {
+    pub base_url: String,
+    /// max wait time of the blocking query, default 5s
+    pub poll_period: Duration,
+    /// acl token
+    token: Option<String>,
+    pub reqwest_client: reqwest::Client,
+}
+
+impl Display for ConsulClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConsulClient {{ base_url: {}, poll_period: {:?} }}", self.base_url, self.poll_period)
+    }
+}
+
+impl Default for ConsulClient {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:8500".to_owned(),
+            poll_period: Duration::from_secs(5),
+            token: None,
+            reqwest_client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl ConsulClient {
+    /// create a new consul client
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            ..Default::default()
+        }
+    }
+
+    /// set the acl token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// get the raw value and the modify index of a key, the value is none if the key doesn't exist
+    ///
+    /// if `index` is set, it's a blocking query, which returns when the index changed or `poll_period` elapsed
+    pub async fn get_kv(&self, key: &str, index: Option<u64>) -> TardisResult<(Option<String>, u64)> {
+        let url = format!("{}/v1/kv/{}", self.base_url.trim_end_matches('/'), key.trim_start_matches('/'));
+        let mut request = self.reqwest_client.get(&url).query(&[("raw", "true")]);
+        if let Some(index) = index {
+            request = request.query(&[("index", index.to_string()), ("wait", format!("{}ms", self.poll_period.as_millis()))]);
+        }
+        if let Some(token) = &self.token {
+            request = request.header(CONSUL_TOKEN_HEADER, token);
+        }
+        let resp = request.send().await?;
+        let index = resp.headers().get(CONSUL_INDEX_HEADER).and_then(|index| index.to_str().ok()).and_then(|index| index.parse::<u64>().ok()).unwrap_or(0);
+        trace!("[Tardis.Config] Consul get_kv key: {}, status: {}, index: {}", key, resp.status(), index);
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok((None, index));
+        }
+        let text = resp.error_for_status()?.text().await?;
+        Ok((Some(text), index))
+    }
+
+    /// put the value of a key
+    pub async fn put_kv(&self, key: &str, value: impl Into<String>) -> TardisResult<bool> {
+        let url = format!("{}/v1/kv/{}", self.base_url.trim_end_matches('/'), key.trim_start_matches('/'));
+        let mut request = self.reqwest_client.put(&url).body(value.into());
+        if let Some(token) = &self.token {
+            request = request.header(CONSUL_TOKEN_HEADER, token);
+        }
+        Ok(request.send().await?.error_for_status()?.json::<bool>().await?)
+    }
+}
@@ -40,6 +40,8 @@ pub struct FrameworkConfig {
     #[builder(!default, default = Some(WebClientConfig::default()))]
     /// Web client configuration / Web客户端配置
     pub web_client: Option<WebClientConfig>,
+    /// gRPC client configuration / gRPC客户端配置
+    pub grpc_client: Option<GrpcClientConfig>,
     /// Distributed cache configuration / 分布式缓存配置
     pub cache: Option<CacheConfig>,
     /// Message queue configuration / 消息队列配置
@@ -58,6 +60,14 @@ pub struct FrameworkConfig {
     pub log: Option<LogConfig>,
     /// Cluster configuration / 集群配置
     pub cluster: Option<ClusterConfig>,
+    /// Service discovery configuration / 服务发现配置
+    #[cfg(feature = "discovery")]
+    pub discovery: Option<DiscoveryConfig>,
+    /// Job scheduler configuration / 任务调度器配置
+    pub scheduler: Option<SchedulerConfig>,
+    /// Sentry error reporting configuration / Sentry错误上报配置
+    #[cfg(feature = "sentry")]
+    pub sentry: Option<SentryConfig>,
 }
 
 impl Default for FrameworkConfig {
@@ -85,6 +95,12 @@ impl FrameworkConfig {
     pub fn web_client(&self) -> &WebClientConfig {
         self.web_client.as_ref().expect("missing component config of web_client")
     }
+    /// Get grpc_client config
+    /// # Panic
+    /// If the config of grpc_client is none, this will be panic.
+    pub fn grpc_client(&self) -> &GrpcClientConfig {
+        self.grpc_client.as_ref().expect("missing component config of grpc_client")
+    }
     /// Get cache config
     /// # Panic
     /// If the config of cache is none, this will be panic.
@@ -121,12 +137,25 @@ impl FrameworkConfig {
     pub fn log(&self) -> &LogConfig {
         self.log.as_ref().expect("missing component config of log")
     }
+    /// Get discovery config
+    /// # Panic
+    /// If the config of discovery is none, this will be panic.
+    #[cfg(feature = "discovery")]
+    pub fn discovery(&self) -> &DiscoveryConfig {
+        self.discovery.as_ref().expect("missing component config of discovery")
+    }
     /// Get cluster config
     /// # Panic
     /// If the config of cluster is none, this will be panic.
     pub fn cluster(&self) -> &ClusterConfig {
         self.cluster.as_ref().expect("missing component config of cluster")
     }
+    /// Get scheduler config
+    /// # Panic
+    /// If the config of scheduler is none, this will be panic.
+    pub fn scheduler(&self) -> &SchedulerConfig {
+        self.scheduler.as_ref().expect("missing component config of scheduler")
+    }
 }
 
 /// Application configuration / 应用配置
@@ -185,6 +214,13 @@ pub struct AppConfig {
     /// Application default language / 应用默认语言
     /// https://www.andiamo.co.uk/resources/iso-language-codes/
     pub default_lang: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    /// Application business timezone, the offset from UTC, E.g. `+08:00` / 应用业务时区，相对UTC的偏移，如 `+08:00`
+    ///
+    /// Used by [`TardisTime`](crate::basic::time::TardisTime), UTC is used when not configured.
+    ///
+    /// 用于 [`TardisTime`](crate::basic::time::TardisTime) ，未配置时使用UTC.
+    pub timezone: Option<String>,
 }
 
 impl Default for AppConfig {
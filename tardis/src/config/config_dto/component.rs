@@ -9,6 +9,8 @@ pub(crate) mod web_server;
 pub use web_server::*;
 pub(crate) mod web_client;
 pub use web_client::*;
+pub(crate) mod grpc_client;
+pub use grpc_client::*;
 pub(crate) mod cache;
 pub use cache::*;
 pub(crate) mod mq;
@@ -19,6 +21,8 @@ pub(crate) mod mail;
 pub use mail::*;
 pub(crate) mod os;
 pub use os::*;
+pub(crate) mod scheduler;
+pub use scheduler::*;
 
 /// # Tardis Component Configuration
 ///
@@ -89,6 +93,8 @@ pub type WebServerConfig = TardisComponentConfig<web_server::WebServerModuleConf
 
 pub type WebClientConfig = TardisComponentConfig<web_client::WebClientModuleConfig>;
 
+pub type GrpcClientConfig = TardisComponentConfig<grpc_client::GrpcClientModuleConfig>;
+
 pub type MQConfig = TardisComponentConfig<mq::MQModuleConfig>;
 
 pub type SearchConfig = TardisComponentConfig<search::SearchModuleConfig>;
@@ -119,15 +125,112 @@ pub struct AdvConfig {
     /// . Click `Encrypt` to wrap the generated value in `ENC(xx)` to replace the original value
     #[builder(default)]
     pub salt: String,
+
+    /// Interval for polling the local configuration files, in milliseconds / 轮询本地配置文件的间隔，单位毫秒
+    ///
+    /// When set, the configuration is reloaded and the changed components are re-initialized in place if the local files change.
+    ///
+    /// 设置后，本地文件变更时会重新加载配置并原地重新初始化变更的组件.
+    #[builder(default)]
+    pub config_watch_interval_ms: Option<u64>,
+
+    /// Initialization of the components on startup / 启动时组件的初始化
+    #[builder(default)]
+    pub startup: StartupConfig,
 }
 
+/// Startup configuration / 启动配置
+///
+/// The components (`db`, `cache`, `mq`, `search`, `mail`, `os`, `web_client`, `grpc_client`, `discovery`) are initialized in parallel,
+/// except the dependencies, e.g. `mq` waits for `db` to start the outbox relay.
+/// Each attempt is limited by the timeout, and retried with the exponential backoff on failure, e.g. Redis is not yet up in k8s.
+///
+/// 组件（ `db` 、 `cache` 、 `mq` 、 `search` 、 `mail` 、 `os` 、 `web_client` 、 `grpc_client` 、 `discovery` ）并行初始化，
+/// 依赖除外，如 `mq` 等待 `db` 以启动发件箱中继. 每次尝试受超时限制，失败时以指数退避重试，如k8s中Redis尚未就绪时.
+///
+/// ```toml
+/// [fw.adv.startup]
+/// timeout_ms = 10000
+/// retries = 5
+///
+/// [fw.adv.startup.components.search]
+/// background = true
+///
+/// [fw.adv.startup.components.mail]
+/// optional = true
+/// timeout_ms = 3000
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// Timeout of each attempt in milliseconds, `None` means no timeout / 每次尝试的超时时间（毫秒）， `None` 表示不超时
+    #[builder(default, setter(strip_option))]
+    pub timeout_ms: Option<u64>,
+    /// Retries after the first attempt / 首次尝试后的重试次数
+    #[builder(default)]
+    pub retries: u32,
+    /// Initial delay of the exponential backoff in milliseconds / 指数退避的初始等待时间（毫秒）
+    #[builder(default = 500)]
+    pub initial_backoff_ms: u64,
+    /// Max delay of the exponential backoff in milliseconds / 指数退避的最大等待时间（毫秒）
+    #[builder(default = 10000)]
+    pub max_backoff_ms: u64,
+    /// Options of the components by the name, e.g. `cache` / 按名称配置的组件选项，如 `cache`
+    #[builder(default)]
+    pub components: HashMap<String, StartupComponentConfig>,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl StartupConfig {
+    /// Options of the component / 组件的选项
+    pub fn component(&self, name: &str) -> StartupComponentConfig {
+        self.components.get(name).cloned().unwrap_or_default()
+    }
+}
+
+/// Startup options of a component / 组件的启动选项
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct StartupComponentConfig {
+    /// Override the timeout of each attempt in milliseconds / 覆盖每次尝试的超时时间（毫秒）
+    #[builder(default, setter(strip_option))]
+    pub timeout_ms: Option<u64>,
+    /// Override the retries / 覆盖重试次数
+    #[builder(default, setter(strip_option))]
+    pub retries: Option<u32>,
+    /// Start initializing on startup in the background without blocking it, wait for it by [`TardisFuns::wait_component`](crate::TardisFuns::wait_component).
+    /// The component isn't available until it's ready, it's not deferred to the first access,
+    /// so access it by the `try_*` accessors, e.g. [`TardisFuns::try_cache`](crate::TardisFuns::try_cache), which fail instead of panicking before it's ready.
+    ///
+    /// 启动时在后台开始初始化而不阻塞启动，可通过 [`TardisFuns::wait_component`](crate::TardisFuns::wait_component) 等待.
+    /// 组件就绪前不可用，初始化不会推迟到首次访问，因此请通过 `try_*` 访问函数（如 [`TardisFuns::try_cache`](crate::TardisFuns::try_cache) ）访问，其在就绪前失败而不是panic.
+    #[builder(default)]
+    pub background: bool,
+    /// Log the failure instead of failing the startup / 记录失败而不是使启动失败
+    #[builder(default)]
+    pub optional: bool,
+}
+
+/// Config center configuration / 配置中心配置
+///
+/// The remote configurations are `<app_id>-default` and `<app_id>-<profile>`,
+/// they are stored as the data ids of the `group` in nacos, and as the keys `[<namespace>/]<group>/<app_id>-<profile>` in consul / etcd.
+///
+/// 远程配置为 `<app_id>-default` 及 `<app_id>-<profile>` ，在nacos中为 `group` 下的data id，在consul / etcd中为 `[<namespace>/]<group>/<app_id>-<profile>` 的key.
 #[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
 pub struct ConfCenterConfig {
+    /// kind of the config center, supports `nacos` `consul` `etcd` / 配置中心类型，支持 `nacos` `consul` `etcd`
     #[builder(default = "nacos".to_string())]
     pub kind: String,
     pub url: Url,
     #[builder(default)]
     pub username: String,
+    /// password, used as the acl token in consul / 密码，在consul中作为acl token
     #[builder(default)]
     pub password: String,
     #[builder(default = Some("default".to_string()))]
@@ -140,3 +243,118 @@ pub struct ConfCenterConfig {
     /// config change polling interval, in milliseconds, default is 30000ms / 配置变更轮询间隔，单位毫秒, 默认30000ms
     pub config_change_polling_interval: Option<u64>,
 }
+
+/// Service discovery configuration / 服务发现配置
+///
+/// The web server instance is registered as the service `service_name` (default is `fw.app.id`) when it starts,
+/// kept alive by heartbeats and deregistered when it shuts down. Only `nacos` is supported now.
+///
+/// web服务实例在启动时注册为 `service_name` （默认为 `fw.app.id` ）服务，通过心跳保活并在关闭时注销. 目前仅支持 `nacos` .
+///
+/// ## Example
+/// ```toml
+/// [fw.discovery]
+/// url = "http://127.0.0.1:8848/nacos"
+/// metadata = { zone = "a" }
+/// ```
+#[cfg(feature = "discovery")]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TypedBuilder)]
+pub struct DiscoveryConfig {
+    /// kind of the registry, supports `nacos` / 注册中心类型，支持 `nacos`
+    #[builder(default = "nacos".to_string())]
+    #[serde(default = "default_discovery_kind")]
+    pub kind: String,
+    pub url: Url,
+    #[builder(default)]
+    #[serde(default)]
+    pub username: String,
+    #[builder(default)]
+    #[serde(default)]
+    pub password: String,
+    #[builder(default)]
+    pub namespace: Option<String>,
+    /// group of the services, default is `DEFAULT_GROUP` / 服务的分组，默认为 `DEFAULT_GROUP`
+    #[builder(default)]
+    pub group: Option<String>,
+    /// service name of the current application, default is `fw.app.id` / 当前应用的服务名，默认为 `fw.app.id`
+    #[builder(default, setter(strip_option, into))]
+    pub service_name: Option<String>,
+    /// registered ip, default is the `access_host` / `host` of the web server, or the local ip if it's unspecified
+    /// / 注册的ip，默认为web服务的 `access_host` / `host` ，未指定时为本机ip
+    #[builder(default, setter(strip_option))]
+    pub ip: Option<std::net::IpAddr>,
+    /// registered port, default is the `access_port` / `port` of the web server / 注册的端口，默认为web服务的 `access_port` / `port`
+    #[builder(default, setter(strip_option))]
+    pub port: Option<u16>,
+    #[builder(default = 1.0)]
+    #[serde(default = "default_discovery_weight")]
+    pub weight: f64,
+    #[builder(default)]
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// heartbeat interval, in seconds, default is 5s / 心跳间隔，单位秒，默认5秒
+    #[builder(default = 5)]
+    #[serde(default = "default_discovery_heartbeat_interval_sec")]
+    pub heartbeat_interval_sec: u64,
+    /// how long the instances of a service are cached on selecting, in seconds, default is 10s / 选择实例时服务实例的缓存时长，单位秒，默认10秒
+    #[builder(default = 10)]
+    #[serde(default = "default_discovery_instances_cache_sec")]
+    pub instances_cache_sec: u64,
+}
+
+#[cfg(feature = "discovery")]
+fn default_discovery_kind() -> String {
+    "nacos".to_string()
+}
+
+#[cfg(feature = "discovery")]
+fn default_discovery_weight() -> f64 {
+    1.0
+}
+
+#[cfg(feature = "discovery")]
+fn default_discovery_heartbeat_interval_sec() -> u64 {
+    5
+}
+
+#[cfg(feature = "discovery")]
+fn default_discovery_instances_cache_sec() -> u64 {
+    10
+}
+
+/// # Sentry configure / Sentry配置
+///
+/// Report the [`TardisError`](crate::basic::error::TardisError)s whose code is not less than `min_code` to Sentry-compatible endpoints.
+///
+/// 将错误码不小于 `min_code` 的 [`TardisError`](crate::basic::error::TardisError) 上报到兼容Sentry的端点.
+///
+/// ## Example
+/// ```toml
+/// [fw.sentry]
+/// dsn = "https://public@sentry.example.com/1"
+/// min_code = 500
+/// environment = "prod"
+/// ```
+#[cfg(feature = "sentry")]
+#[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+pub struct SentryConfig {
+    #[builder(setter(into))]
+    pub dsn: String,
+    /// minimum error code to report, the codes not starting with a number (e.g. `-1`) are not reported
+    #[builder(default = 500)]
+    #[serde(default = "default_sentry_min_code")]
+    pub min_code: u16,
+    /// release name, default to `<app.id>@<app.version>`
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub release: Option<String>,
+    /// environment name, default to the profile
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub environment: Option<String>,
+}
+
+#[cfg(feature = "sentry")]
+fn default_sentry_min_code() -> u16 {
+    500
+}
@@ -15,8 +15,162 @@ use url::Url;
 ///    ..Default::default()
 ///};
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+///
+/// The in-memory store is used if the url scheme is `mem` or the `kind` is `memory`, requires the `test` feature.
+///
+/// url scheme为 `mem` 或 `kind` 为 `memory` 时使用内存存储，需启用 `test` 特性.
+/// ```toml
+/// [fw.cache]
+/// url = "redis://127.0.0.1:6379"
+/// kind = "memory"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 pub struct CacheModuleConfig {
     /// Cache access Url, Url with permission information / 缓存访问Url，Url带权限信息
     pub url: Url,
+    /// Kind of the store, inferred by the url scheme if it's `None` / 存储类型，为 `None` 时由url scheme推断
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub kind: Option<CacheKind>,
+    /// Codec of the typed values, e.g. `set_obj` / 类型化值的编解码方式，如 `set_obj`
+    #[builder(default)]
+    #[serde(default)]
+    pub codec: CacheCodec,
+    /// Default configuration of [`TardisCacheClient::rate_limiter`](crate::cache::cache_client::TardisCacheClient::rate_limiter)
+    /// / [`TardisCacheClient::rate_limiter`](crate::cache::cache_client::TardisCacheClient::rate_limiter) 的默认配置
+    #[builder(default)]
+    #[serde(default)]
+    pub rate_limiter: RateLimiterConfig,
+    /// Default configuration of [`TardisCacheClient::timer`](crate::cache::cache_client::TardisCacheClient::timer)
+    /// / [`TardisCacheClient::timer`](crate::cache::cache_client::TardisCacheClient::timer) 的默认配置
+    #[builder(default)]
+    #[serde(default)]
+    pub timer: TimerConfig,
+}
+
+impl CacheModuleConfig {
+    /// The kind of the store, by the `kind` or the url scheme / 存储类型，取自 `kind` 或url scheme
+    pub fn kind(&self) -> CacheKind {
+        self.kind.unwrap_or(match self.url.scheme() {
+            "mem" => CacheKind::Memory,
+            _ => CacheKind::Redis,
+        })
+    }
+}
+
+/// Kind of the cache store / 缓存存储类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheKind {
+    #[default]
+    Redis,
+    /// In-memory store for the tests without Redis, requires the `test` feature / 用于无Redis测试的内存存储，需启用 `test` 特性
+    Memory,
+}
+
+/// Codec of the typed cache values / 类型化缓存值的编解码方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheCodec {
+    /// JSON by `TardisFuns::json` / 通过 `TardisFuns::json` 编解码为JSON
+    #[default]
+    Json,
+    /// MessagePack, requires the `cache-msgpack` feature / MessagePack，需启用 `cache-msgpack` 特性
+    MessagePack,
+}
+
+/// Rate limiter configuration / 限流器配置
+///
+/// # Examples
+/// ```ignore
+/// use tardis::config::config_dto::{RateLimiterConfig, RateLimitStrategy};
+/// // 10 requests per second with bursts up to 10
+/// let config = RateLimiterConfig::builder().strategy(RateLimitStrategy::TokenBucket).limit(10).window_ms(1000).build();
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct RateLimiterConfig {
+    #[builder(default)]
+    pub strategy: RateLimitStrategy,
+    /// Max requests in a window / 每个窗口的最大请求数
+    #[builder(default = 100)]
+    pub limit: u64,
+    /// Window in milliseconds / 窗口（毫秒）
+    #[builder(default = 1000)]
+    pub window_ms: u64,
+    /// Prefix of the cache keys / 缓存key的前缀
+    #[builder(default = "tardis:rate_limit:".to_string(), setter(into))]
+    pub key_prefix: String,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Rate limit strategy / 限流策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitStrategy {
+    /// At most `limit` requests in each aligned window, allows bursts at the window boundaries
+    /// / 每个对齐的窗口内最多 `limit` 个请求，窗口边界处允许突发
+    #[default]
+    FixedWindow,
+    /// At most `limit` requests in any window ending now, stores a timestamp per request
+    /// / 任意截止于当前的窗口内最多 `limit` 个请求，每个请求存储一个时间戳
+    SlidingWindow,
+    /// Bucket of `limit` tokens refilled at `limit` per window, allows bursts up to `limit`
+    /// / 容量为 `limit` 的令牌桶，每个窗口补充 `limit` 个令牌，允许最多 `limit` 的突发
+    TokenBucket,
+}
+
+/// Delayed task queue configuration / 延时任务队列配置
+///
+/// # Examples
+/// ```ignore
+/// use tardis::config::config_dto::TimerConfig;
+/// let config = TimerConfig::builder().queue("order_timeout").max_retries(5).build();
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct TimerConfig {
+    /// Name of the queue / 队列名
+    #[builder(default = "default".to_string(), setter(into))]
+    pub queue: String,
+    /// Prefix of the cache keys / 缓存key的前缀
+    #[builder(default = "tardis:timer:".to_string(), setter(into))]
+    pub key_prefix: String,
+    /// Interval between the polls of the consumer in milliseconds / 消费者轮询间隔（毫秒）
+    #[builder(default = 1000)]
+    pub poll_interval_ms: u64,
+    /// Max tasks claimed by each poll / 每次轮询认领的最大任务数
+    #[builder(default = 100)]
+    pub batch_size: u64,
+    /// The claimed task is delivered again after the lease in milliseconds, e.g. the consumer crashed,
+    /// it should be longer than the handling / 认领的任务在租期（毫秒）后再次投递，如消费者崩溃时，应长于处理时长
+    #[builder(default = 60000)]
+    pub lease_ms: u64,
+    /// Times to retry the failed task / 重试失败任务的次数
+    #[builder(default = 3)]
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds, doubled for each retry / 首次重试前的延迟（毫秒），每次重试翻倍
+    #[builder(default = 1000)]
+    pub initial_backoff_ms: u64,
+    /// Max delay between the retries in milliseconds / 重试间的最大延迟（毫秒）
+    #[builder(default = 60000)]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl TimerConfig {
+    /// Delay before the retry of the attempt (0-based) / 第 `attempt` 次（从0开始）重试前的延迟
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        std::time::Duration::from_millis(self.initial_backoff_ms.saturating_mul(1u64 << attempt.min(32)).min(self.max_backoff_ms))
+    }
 }
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// gRPC client configuration / gRPC客户端配置
+///
+/// gRPC client operation needs to be enabled ```#[cfg(feature = "web-client-grpc")]``` .
+///
+/// gRPC客户端操作需要启用 ```#[cfg(feature = "web-client-grpc")]``` .
+///
+/// # Examples
+/// ```toml
+/// [fw.grpc_client.modules.user]
+/// uris = ["https://user-service:9090"]
+/// request_timeout_sec = 10
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct GrpcClientModuleConfig {
+    /// Endpoints of the remote service, the requests are balanced among them, the `https` ones use TLS
+    /// / 远程服务的端点，请求在端点间负载均衡， `https` 端点使用TLS
+    #[builder(default, setter(into))]
+    pub uris: Vec<String>,
+    #[builder(default = 60, setter(into))]
+    /// Request timeout / 请求超时时间
+    pub request_timeout_sec: u64,
+    /// Whether to carry the [`TardisContext`](crate::basic::dto::TardisContext) of the current task in the metadata of the requests,
+    /// disabled by default, enable it only for the clients calling the trusted internal services
+    /// / 是否在请求的元数据中携带当前task的 [`TardisContext`](crate::basic::dto::TardisContext) ，默认禁用，仅应对调用受信内部服务的客户端启用
+    #[builder(default)]
+    pub propagate_context: bool,
+}
+
+impl Default for GrpcClientModuleConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
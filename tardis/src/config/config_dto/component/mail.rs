@@ -4,9 +4,25 @@ use typed_builder::TypedBuilder;
 
 /// Mail module configuration / 邮件模块配置
 ///
-#[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+/// The transport is selected by the `kind` / 传输方式通过 `kind` 选择:
+///
+/// | Kind       | Transport                       | Fields                                   | Feature    |
+/// |------------|---------------------------------|------------------------------------------|------------|
+/// | `smtp`     | SMTP                            | `smtp_*` , `starttls`                    | `mail`     |
+/// | `sendgrid` | SendGrid v3 API                 | `api_key`                                | `mail-api` |
+/// | `mailgun`  | Mailgun MIME API                | `api_key` , `api_domain`                 | `mail-api` |
+/// | `aliyun`   | Aliyun DirectMail SingleSendMail| `api_key` , `api_secret`                 | `mail-api` |
+/// | `mock`     | Recorded in memory for tests    | -                                        | `mail`     |
+///
+/// The `api_endpoint` overrides the default endpoint of the provider, e.g. `https://api.eu.mailgun.net` or `https://dm.ap-southeast-1.aliyuncs.com` .
+///
+/// `api_endpoint` 覆盖服务商的默认端点，如 `https://api.eu.mailgun.net` 或 `https://dm.ap-southeast-1.aliyuncs.com` .
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 #[serde(default)]
 pub struct MailModuleConfig {
+    /// Transport kind, default by `smtp` / 传输方式，默认为 `smtp`
+    #[builder(setter(into), default = "smtp".to_string())]
+    pub kind: String,
     /// SMTP host
     #[builder(setter(into), default)]
     pub smtp_host: String,
@@ -25,6 +41,18 @@ pub struct MailModuleConfig {
     /// weather to use STARTTLS, default by false
     #[builder(default = false)]
     pub starttls: bool,
+    /// Endpoint of the HTTP API, empty for the default endpoint of the provider / HTTP API的端点，为空时使用服务商的默认端点
+    #[builder(setter(into), default)]
+    pub api_endpoint: String,
+    /// API key of SendGrid and Mailgun, AccessKey ID of Aliyun / SendGrid及Mailgun的API key，阿里云的AccessKey ID
+    #[builder(setter(into), default)]
+    pub api_key: String,
+    /// AccessKey secret of Aliyun / 阿里云的AccessKey secret
+    #[builder(setter(into), default)]
+    pub api_secret: String,
+    /// Sending domain of Mailgun / Mailgun的发信域名
+    #[builder(setter(into), default)]
+    pub api_domain: String,
 }
 
 impl Default for MailModuleConfig {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::Url;
@@ -16,8 +18,419 @@ use url::Url;
 ///    ..Default::default()
 ///};
 /// ```
+///
+/// The Kafka broker is used if the url scheme is `kafka` or the `kind` is `kafka`, requires the `mq-kafka` feature.
+///
+/// url scheme为 `kafka` 或 `kind` 为 `kafka` 时使用Kafka，需启用 `mq-kafka` 特性.
+/// ```toml
+/// [fw.mq]
+/// url = "kafka://127.0.0.1:9092?security.protocol=plaintext"
+/// kafka = { group_id = "order-service", commit = "sync" }
+/// ```
+///
+/// Likewise, the NATS broker is used for the `nats` url scheme or the `nats` kind, requires the `mq-nats` feature,
+/// the Redis Streams are used for the `redis`/`rediss` url schemes or the `redis_stream` kind, requires the `mq-redis` feature.
+///
+/// 同样地，url scheme为 `nats` 或 `kind` 为 `nats` 时使用NATS，需启用 `mq-nats` 特性，
+/// url scheme为 `redis`/`rediss` 或 `kind` 为 `redis_stream` 时使用Redis Streams，需启用 `mq-redis` 特性.
+///
+/// The in-memory broker is used for the `mem` url scheme or the `memory` kind, requires the `test` feature.
+///
+/// url scheme为 `mem` 或 `kind` 为 `memory` 时使用内存代理，需启用 `test` 特性.
+///
+/// The topology can also be configured, e.g.
+///
+/// 也可以配置拓扑，如
+/// ```toml
+/// [fw.mq.topology]
+/// exchanges = [{ name = "order", kind = "topic" }, { name = "order.dlx", kind = "fanout" }]
+///
+/// [[fw.mq.topology.queues]]
+/// name = "order.created"
+/// message_ttl_ms = 60000
+/// dead_letter_exchange = "order.dlx"
+/// bindings = [{ exchange = "order", routing_key = "order.*.created" }]
+/// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 pub struct MQModuleConfig {
     /// Message queue access Url, Url with permission information / 消息队列访问Url，Url带权限信息
     pub url: Url,
+    /// Kind of the broker, inferred by the url scheme if it's `None` / 消息代理类型，为 `None` 时由url scheme推断
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub kind: Option<MQKind>,
+    /// Options of the Kafka broker / Kafka代理的选项
+    #[builder(default)]
+    #[serde(default)]
+    pub kafka: MQKafkaConfig,
+    /// Options of the Redis Streams / Redis Streams的选项
+    #[builder(default)]
+    #[serde(default)]
+    pub redis_stream: MQRedisStreamConfig,
+    /// Default reliability options of the consumers / 消费者的默认可靠性选项
+    #[builder(default)]
+    #[serde(default)]
+    pub consumer: MQConsumerConfig,
+    /// Transactional outbox, requires the `reldb-core` feature / 事务性发件箱，需启用 `reldb-core` 特性
+    #[builder(default)]
+    #[serde(default)]
+    pub outbox: MQOutboxConfig,
+    /// Exchanges and queues declared on initialization / 初始化时声明的交换机及队列
+    #[builder(default)]
+    #[serde(default)]
+    pub topology: MQTopologyConfig,
+}
+
+impl MQModuleConfig {
+    /// The kind of the broker, by the `kind` or the url scheme / 消息代理类型，取自 `kind` 或url scheme
+    pub fn kind(&self) -> MQKind {
+        self.kind.unwrap_or(match self.url.scheme() {
+            "kafka" => MQKind::Kafka,
+            "nats" | "tls" => MQKind::Nats,
+            "redis" | "rediss" => MQKind::RedisStream,
+            "mem" => MQKind::Memory,
+            _ => MQKind::Amqp,
+        })
+    }
+}
+
+/// Reliability options of the consumers / 消费者的可靠性选项
+///
+/// The message is acknowledged if the handler returns `Ok`, otherwise (an error or a panic) it's retried `max_retries` times
+/// with the exponential backoff, then it's sent to the dead-letter queue if `dead_letter` is enabled, or rejected.
+///
+/// 处理函数返回 `Ok` 时确认消息，否则（返回错误或panic）以指数退避重试 `max_retries` 次，之后若启用了 `dead_letter` 则发送到死信队列，否则拒绝该消息.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::config::config_dto::MQConsumerConfig;
+/// let options = MQConsumerConfig::builder().max_retries(5).dead_letter(true).prefetch(10).concurrency(4).build();
+/// TardisFuns::mq().response_with("order.created", &options, |(header, msg)| async move { Ok(()) }).await?;
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct MQConsumerConfig {
+    /// Times to retry the failed message / 重试失败消息的次数
+    #[builder(default)]
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds, doubled for each retry / 首次重试前的延迟（毫秒），每次重试翻倍
+    #[builder(default = 1000)]
+    pub initial_backoff_ms: u64,
+    /// Max delay between the retries in milliseconds / 重试之间的最大延迟（毫秒）
+    #[builder(default = 60000)]
+    pub max_backoff_ms: u64,
+    /// Send the messages still failing after the retries to the dead-letter queue / 将重试后仍失败的消息发送到死信队列
+    ///
+    /// The dead-letter queue is named `{address or topic}{dead_letter_suffix}`, the original header is kept with the
+    /// `x-tardis-dead-letter-origin` and `x-tardis-dead-letter-error` entries added.
+    ///
+    /// 死信队列名为 `{地址或主题}{dead_letter_suffix}` ，保留原消息头并添加 `x-tardis-dead-letter-origin` 及 `x-tardis-dead-letter-error` 条目.
+    #[builder(default)]
+    pub dead_letter: bool,
+    /// Suffix of the dead-letter queue / 死信队列的后缀
+    #[builder(default = ".dlq".to_string(), setter(into))]
+    pub dead_letter_suffix: String,
+    /// Max unacknowledged messages delivered to the consumer, only for the amqp client / 投递给消费者的最大未确认消息数，仅用于AMQP客户端
+    #[builder(default = 1)]
+    pub prefetch: u16,
+    /// Max messages processed concurrently by the consumer, only for the amqp and nats clients
+    /// / 消费者并发处理的最大消息数，仅用于AMQP及NATS客户端
+    #[builder(default = 1)]
+    pub concurrency: usize,
+}
+
+impl Default for MQConsumerConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl MQConsumerConfig {
+    /// The dead-letter queue of the address or topic, `None` if it isn't enabled / 该地址或主题的死信队列，未启用时为 `None`
+    pub fn dead_letter_queue(&self, destination: &str) -> Option<String> {
+        self.dead_letter.then(|| format!("{destination}{}", self.dead_letter_suffix))
+    }
+
+    /// The delay before the retry, the `attempt` starts from 0 / 重试前的延迟， `attempt` 从0开始
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        std::time::Duration::from_millis(self.initial_backoff_ms.saturating_mul(1u64 << attempt.min(32)).min(self.max_backoff_ms))
+    }
+}
+
+/// Kind of the message broker / 消息代理类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MQKind {
+    /// RabbitMQ or other AMQP 0.9.1 brokers / RabbitMQ或其它AMQP 0.9.1代理
+    #[default]
+    Amqp,
+    /// Kafka, requires the `mq-kafka` feature / Kafka，需启用 `mq-kafka` 特性
+    Kafka,
+    /// NATS core, requires the `mq-nats` feature / NATS core，需启用 `mq-nats` 特性
+    Nats,
+    /// Redis Streams, requires the `mq-redis` feature / Redis Streams，需启用 `mq-redis` 特性
+    RedisStream,
+    /// In-memory broker for the tests without a real broker, requires the `test` feature / 用于无真实代理测试的内存代理，需启用 `test` 特性
+    Memory,
+}
+
+/// Kafka configuration / Kafka配置
+///
+/// The bootstrap server is the host and port of the url, the query parameters of the url and the `properties` are passed to librdkafka,
+/// e.g. `kafka://127.0.0.1:9092?bootstrap.servers=127.0.0.1:9092,127.0.0.2:9092&security.protocol=sasl_ssl` .
+///
+/// 引导服务器为url的主机及端口，url的查询参数及 `properties` 会传给librdkafka，
+/// 如 `kafka://127.0.0.1:9092?bootstrap.servers=127.0.0.1:9092,127.0.0.2:9092&security.protocol=sasl_ssl` .
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct MQKafkaConfig {
+    /// Consumer group of `response`, each `subscribe` uses an unique group / `response` 的消费者组，每个 `subscribe` 使用唯一的组
+    #[builder(default = "tardis".to_string(), setter(into))]
+    pub group_id: String,
+    #[builder(default)]
+    pub commit: MQKafkaCommitStrategy,
+    /// Where to start if the group has no committed offset / 消费者组没有已提交的偏移量时从何处开始
+    #[builder(default)]
+    pub offset_reset: MQKafkaOffsetReset,
+    /// Timeout of sending a message in milliseconds / 发送消息的超时时间（毫秒）
+    #[builder(default = 5000)]
+    pub send_timeout_ms: u64,
+    /// Extra librdkafka properties, e.g. `sasl.mechanism` / 额外的librdkafka属性，如 `sasl.mechanism`
+    #[builder(default, setter(into))]
+    pub properties: HashMap<String, String>,
+}
+
+impl Default for MQKafkaConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Redis Streams configuration / Redis Streams配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct MQRedisStreamConfig {
+    /// Consumer group of `response` / `response` 的消费者组
+    #[builder(default = "tardis".to_string(), setter(into))]
+    pub group: String,
+    /// Approximate max length of each stream, the oldest entries are trimmed on adding, unlimited if it's `None`
+    /// / 每个流的近似最大长度，添加时裁剪最早的条目，为 `None` 时不限制
+    #[builder(default = Some(10000), setter(strip_option))]
+    pub max_len: Option<usize>,
+    /// Timeout of each blocking read in milliseconds / 每次阻塞读取的超时时间（毫秒）
+    #[builder(default = 5000)]
+    pub block_ms: usize,
+}
+
+impl Default for MQRedisStreamConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Transactional outbox configuration / 事务性发件箱配置
+///
+/// The messages published by `publish_transactional` are written into the outbox table in the caller's transaction,
+/// the relay claims the committed rows, publishes them and then deletes them, so the messages are published at least once.
+///
+/// `publish_transactional` 发布的消息在调用方的事务中写入发件箱表，中继认领已提交的行，发布后删除，因此消息至少发布一次.
+///
+/// ```toml
+/// [fw.mq.outbox]
+/// enabled = true
+/// poll_interval_ms = 500
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct MQOutboxConfig {
+    /// Start the relay on initialization, the table is created if it doesn't exist / 初始化时启动中继，表不存在时创建
+    #[builder(default)]
+    pub enabled: bool,
+    /// Module code of the database, the default module if it doesn't exist / 数据库的模块编码，不存在时使用默认模块
+    #[builder(default, setter(into))]
+    pub db_module: String,
+    /// Name of the outbox table / 发件箱表名
+    #[builder(default = "tardis_mq_outbox".to_string(), setter(into))]
+    pub table: String,
+    /// Interval between the polls of the relay in milliseconds / 中继轮询间隔（毫秒）
+    #[builder(default = 1000)]
+    pub poll_interval_ms: u64,
+    /// Max rows claimed by each poll, must be greater than 0 / 每次轮询认领的最大行数，必须大于0
+    #[builder(default = 100)]
+    pub batch_size: u64,
+    /// The claimed row is claimable again after the timeout in milliseconds, e.g. the relay crashed
+    /// / 认领的行在超时（毫秒）后可再次认领，如中继崩溃时
+    #[builder(default = 30000)]
+    pub claim_timeout_ms: u64,
+}
+
+impl Default for MQOutboxConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Offset commit strategy of the Kafka consumers / Kafka消费者的偏移量提交策略
+///
+/// Kafka can't skip a single message, so the offset of a failed message is committed along with the next processed one.
+///
+/// Kafka无法跳过单条消息，因此失败消息的偏移量会随下一条处理成功的消息一起提交.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MQKafkaCommitStrategy {
+    /// Committed periodically by librdkafka, the received but unprocessed messages may be lost on crash
+    /// / 由librdkafka定期提交，崩溃时已接收但未处理的消息可能丢失
+    Auto,
+    /// Committed synchronously after each message is processed successfully / 每条消息处理成功后同步提交
+    #[default]
+    Sync,
+    /// Committed asynchronously after each message is processed successfully / 每条消息处理成功后异步提交
+    Async,
+}
+
+/// Initial offset of the Kafka consumer groups / Kafka消费者组的初始偏移量
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MQKafkaOffsetReset {
+    Earliest,
+    #[default]
+    Latest,
+}
+
+/// Message queue topology / 消息队列拓扑
+///
+/// The declaration is idempotent, but redeclaring an existing exchange or queue with different options fails.
+///
+/// 声明是幂等的，但以不同的选项重复声明已存在的交换机或队列会失败.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::config::config_dto::{MQExchangeConfig, MQExchangeKind, MQQueueConfig, MQTopologyConfig};
+/// let topology = MQTopologyConfig::default()
+///     .exchange(MQExchangeConfig::builder().name("order").kind(MQExchangeKind::Topic).build())
+///     .exchange(MQExchangeConfig::builder().name("order.dlx").kind(MQExchangeKind::Fanout).build())
+///     .queue(MQQueueConfig::builder().name("order.created").message_ttl_ms(60000).dead_letter_exchange("order.dlx").build().bind("order", "order.*.created"));
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct MQTopologyConfig {
+    #[builder(default, setter(into))]
+    pub exchanges: Vec<MQExchangeConfig>,
+    #[builder(default, setter(into))]
+    pub queues: Vec<MQQueueConfig>,
+}
+
+impl MQTopologyConfig {
+    pub fn exchange(mut self, exchange: MQExchangeConfig) -> Self {
+        self.exchanges.push(exchange);
+        self
+    }
+
+    pub fn queue(mut self, queue: MQQueueConfig) -> Self {
+        self.queues.push(queue);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exchanges.is_empty() && self.queues.is_empty()
+    }
+}
+
+/// Exchange declaration / 交换机声明
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct MQExchangeConfig {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(default)]
+    #[serde(default)]
+    pub kind: MQExchangeKind,
+    #[builder(default = true)]
+    #[serde(default = "default_true")]
+    pub durable: bool,
+    /// Delete the exchange when the last queue is unbound / 最后一个队列解绑时删除该交换机
+    #[builder(default)]
+    #[serde(default)]
+    pub auto_delete: bool,
+}
+
+/// Exchange kind / 交换机类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MQExchangeKind {
+    /// Routed to the queues whose binding key equals the routing key / 路由到绑定键与路由键相同的队列
+    Direct,
+    /// Routed to all the bound queues / 路由到所有绑定的队列
+    Fanout,
+    /// Routed by the binding patterns, `*` matches one word and `#` matches zero or more words
+    /// / 按绑定模式路由， `*` 匹配一个单词， `#` 匹配零或多个单词
+    #[default]
+    Topic,
+    /// Routed by the message headers, not supported by the in-memory client / 按消息头路由，内存客户端不支持
+    Headers,
+}
+
+/// Queue declaration / 队列声明
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct MQQueueConfig {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(default = true)]
+    #[serde(default = "default_true")]
+    pub durable: bool,
+    /// Delete the queue when the last consumer is cancelled / 最后一个消费者取消时删除该队列
+    #[builder(default)]
+    #[serde(default)]
+    pub auto_delete: bool,
+    /// Time to live of the messages in milliseconds, the expired messages are dead-lettered (`x-message-ttl`)
+    /// / 消息的存活时间（毫秒），过期的消息成为死信（ `x-message-ttl` ）
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub message_ttl_ms: Option<u64>,
+    /// The unused queue is deleted after the milliseconds (`x-expires`) / 未使用的队列在该毫秒数后删除（ `x-expires` ）
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub expires_ms: Option<u64>,
+    /// Max number of the ready messages, the oldest ones are dropped or dead-lettered (`x-max-length`)
+    /// / 就绪消息的最大数量，最早的消息被丢弃或成为死信（ `x-max-length` ）
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub max_length: Option<u64>,
+    /// Exchange of the rejected or expired messages (`x-dead-letter-exchange`) / 被拒绝或过期消息的交换机（ `x-dead-letter-exchange` ）
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub dead_letter_exchange: Option<String>,
+    /// Routing key of the dead-lettered messages, the original one is kept if it's `None` (`x-dead-letter-routing-key`)
+    /// / 死信消息的路由键，为 `None` 时保留原路由键（ `x-dead-letter-routing-key` ）
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub dead_letter_routing_key: Option<String>,
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub bindings: Vec<MQBindingConfig>,
+}
+
+impl MQQueueConfig {
+    /// Bind the queue to the exchange / 将队列绑定到交换机
+    pub fn bind(mut self, exchange: impl Into<String>, routing_key: impl Into<String>) -> Self {
+        self.bindings.push(MQBindingConfig {
+            exchange: exchange.into(),
+            routing_key: routing_key.into(),
+        });
+        self
+    }
+}
+
+/// Queue binding / 队列绑定
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct MQBindingConfig {
+    #[builder(setter(into))]
+    pub exchange: String,
+    /// Binding key, a pattern for the topic exchanges, ignored by the fanout exchanges / 绑定键，主题交换机时为模式，扇出交换机忽略该值
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub routing_key: String,
+}
+
+fn default_true() -> bool {
+    true
 }
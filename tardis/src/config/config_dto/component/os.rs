@@ -2,22 +2,38 @@ use serde::{Deserialize, Serialize};
 
 use typed_builder::TypedBuilder;
 
-#[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 #[serde(default)]
 pub struct OSModuleConfig {
-    /// s3/oss/obs, Support amazon s3 / aliyun oss / huaweicloud obs
+    /// s3/fs/azure, s3 supports amazon s3 / aliyun oss / huaweicloud obs / minio,
+    /// fs is the local filesystem for the development and the tests, azure is the Azure Blob Storage and requires the `os-azure` feature
+    /// / s3/fs/azure，s3支持amazon s3 / aliyun oss / huaweicloud obs / minio，fs为用于开发及测试的本地文件系统，azure为Azure Blob Storage，需启用 `os-azure` 特性
     #[builder(default = "s3".to_string(), setter(into))]
     pub kind: String,
+    /// The root directory for fs, e.g. `fs:///var/tardis/os` , defaults to `https://{ak}.blob.core.windows.net` for azure
+    /// / fs的根目录，如 `fs:///var/tardis/os` ，azure默认为 `https://{ak}.blob.core.windows.net`
     #[builder(default, setter(into))]
     pub endpoint: String,
+    /// The account name for azure / azure的账户名
     #[builder(default, setter(into))]
     pub ak: String,
+    /// The account key for azure / azure的账户密钥
     #[builder(default, setter(into))]
     pub sk: String,
     #[builder(default, setter(into))]
     pub region: String,
     #[builder(default, setter(into))]
     pub default_bucket: String,
+    /// Expiry of the presigned urls requested with `expire_sec` 0, at most 604800 (7 days)
+    /// / `expire_sec` 为0时预签名url的有效期，最大604800（7天）
+    #[builder(default = 3600)]
+    pub presign_expire_sec: u32,
+    /// Size of the parts of the multipart uploads, at least 5 MiB except the last part / 分片上传的分片大小，除最后一个分片外至少5 MiB
+    #[builder(default = 8 * 1024 * 1024)]
+    pub multipart_part_size: usize,
+    /// Number of the parts uploaded in parallel / 并行上传的分片数
+    #[builder(default = 4)]
+    pub multipart_concurrency: usize,
 }
 
 impl Default for OSModuleConfig {
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use typed_builder::TypedBuilder;
+
+/// Scheduler configuration / 调度器配置
+///
+/// The scheduler is started on initialization if it's `enabled`, the jobs registered later are started once registered.
+/// Set `enabled = false` on the instances that shouldn't run any job.
+///
+/// 若 `enabled` ，初始化时启动调度器，之后注册的任务在注册时启动. 不应运行任何任务的实例可设置 `enabled = false` .
+///
+/// ## Example
+/// ```toml
+/// [fw.scheduler]
+/// lock_ttl_sec = 60
+/// [fw.scheduler.jobs.clean_expired]
+/// cron = "0 0 3 * * *"
+/// [fw.scheduler.jobs.sync_stock]
+/// enabled = false
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Run the jobs on this instance, default by true / 在本实例运行任务，默认为true
+    #[builder(default = true)]
+    pub enabled: bool,
+    /// Module code of the cache of the distributed locks, the default module if it doesn't exist
+    /// / 分布式锁所用缓存的模块编码，不存在时使用默认模块
+    #[builder(default, setter(into))]
+    pub cache_module: String,
+    /// Key prefix of the distributed locks, followed by the application id and the job name
+    /// / 分布式锁的键前缀，其后为应用id及任务名
+    #[builder(default = "tardis:scheduler:".to_string(), setter(into))]
+    pub lock_prefix: String,
+    /// TTL of the distributed locks in seconds, renewed while the job is running / 分布式锁的TTL（秒），任务运行期间续期
+    #[builder(default = 30)]
+    pub lock_ttl_sec: u64,
+    /// The distributed lock is held at least this long after the scheduled time in milliseconds,
+    /// so the instances with the skewed clocks don't run the same occurrence again
+    /// / 分布式锁在计划时间后至少持有的时长（毫秒），使时钟有偏差的实例不会再次运行同一次调度
+    #[builder(default = 1000)]
+    pub lock_min_hold_ms: u64,
+    /// Overrides of the registered jobs by name / 按名称覆盖已注册任务的配置
+    #[builder(default)]
+    pub jobs: HashMap<String, SchedulerJobConfig>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig::builder().build()
+    }
+}
+
+/// Overrides of a scheduled job / 调度任务的覆盖配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct SchedulerJobConfig {
+    /// Run the job, default by true / 运行该任务，默认为true
+    #[builder(default = true)]
+    pub enabled: bool,
+    /// Cron expression replacing the registered schedule / 替换已注册调度的cron表达式
+    #[builder(default, setter(strip_option, into))]
+    pub cron: Option<String>,
+    /// Fixed interval in seconds replacing the registered schedule / 替换已注册调度的固定间隔（秒）
+    #[builder(default, setter(strip_option))]
+    pub interval_sec: Option<u64>,
+}
+
+impl Default for SchedulerJobConfig {
+    fn default() -> Self {
+        SchedulerJobConfig::builder().build()
+    }
+}
@@ -15,11 +15,91 @@ use url::Url;
 ///    ..Default::default()
 ///};
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+///
+/// Secured cluster, the PEM contents can be read from files by `${file:...}` references / 安全集群，PEM内容可通过 `${file:...}` 引用从文件读取
+/// ```toml
+/// [fw.search]
+/// url = "https://search-xxx.es.amazonaws.com"
+/// engine = "opensearch"
+/// username = "admin"
+/// password = "${env:SEARCH_PASSWORD}"
+///
+/// [fw.search.tls]
+/// ca_cert = "${file:/etc/search/ca.pem}"
+/// accept_invalid_certs = false
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 pub struct SearchModuleConfig {
     /// Search access Url, Url with permission information / 搜索访问Url，Url带权限信息
     pub url: Url,
     #[builder(default = 60)]
     /// Timeout / 操作超时时间
     pub timeout_sec: u64,
+    #[builder(default = 500)]
+    #[serde(default = "default_bulk_chunk_size")]
+    /// Max number of records per `_bulk` request of the bulk operations / 批量操作每个 `_bulk` 请求的最大记录数
+    pub bulk_chunk_size: usize,
+    #[builder(default)]
+    #[serde(default)]
+    /// Kind of the search engine / 搜索引擎类型
+    pub engine: SearchEngineKind,
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    /// Username of the basic auth, takes precedence over the credentials in the url / 基础认证的用户名，优先于Url中的认证信息
+    pub username: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    /// Password of the basic auth / 基础认证的密码
+    pub password: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    /// Api key, the base64 encoded `id:api_key`, sent as `Authorization: ApiKey <api_key>`, takes precedence over the basic auth
+    /// / Api key，即base64编码的 `id:api_key` ，以 `Authorization: ApiKey <api_key>` 发送，优先于基础认证
+    pub api_key: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    /// TLS configuration / TLS配置
+    pub tls: SearchTlsConfig,
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    /// The major version of the Elasticsearch REST API compatibility headers, E.g. `7` to use the 7.x API on 8.x clusters, ignored by OpenSearch
+    /// / Elasticsearch REST API兼容请求头的主版本，如 `7` 表示在8.x集群上使用7.x的API，OpenSearch忽略此配置
+    pub compatible_with: Option<u8>,
+}
+
+fn default_bulk_chunk_size() -> usize {
+    500
+}
+
+/// Kind of the search engine / 搜索引擎类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEngineKind {
+    #[default]
+    Elasticsearch,
+    OpenSearch,
+}
+
+/// TLS configuration of the search client / 搜索客户端的TLS配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct SearchTlsConfig {
+    #[builder(default, setter(strip_option, into))]
+    /// PEM content of the CA certificate to trust / 信任的CA证书的PEM内容
+    pub ca_cert: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    /// PEM content of the client certificate, requires `client_key` / 客户端证书的PEM内容，需同时配置 `client_key`
+    pub client_cert: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    /// PEM content of the PKCS#8 client private key / PKCS#8客户端私钥的PEM内容
+    pub client_key: Option<String>,
+    #[builder(default = true)]
+    /// Whether to accept invalid server certificates, enabled by default for compatibility / 是否接受无效的服务端证书，为兼容默认启用
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for SearchTlsConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
 }
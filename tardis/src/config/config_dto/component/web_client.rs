@@ -6,15 +6,45 @@ use typed_builder::TypedBuilder;
 /// Web client operation needs to be enabled ```#[cfg(feature = "web-client")]``` .
 ///
 /// Web客户端操作需要启用 ```#[cfg(feature = "web-client")]``` .
-#[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 #[serde(default)]
 pub struct WebClientModuleConfig {
+    /// Base url of the remote API, used by the typed API clients of the module,
+    /// see [`TardisApiClient::from_module`](crate::web::web_api_client::TardisApiClient::from_module)
+    /// / 远程API的基础地址，供该模块的类型化API客户端使用
+    #[builder(default, setter(strip_option, into))]
+    pub base_url: Option<String>,
     #[builder(default = 60, setter(into))]
     /// Connection timeout / 连接超时时间
     pub connect_timeout_sec: u64,
     #[builder(default = 60, setter(into))]
-    /// Request timeout / 请求超时时间
+    /// Request timeout, can be overridden per call by [`TardisWebClient::with_timeout`](crate::web::web_client::TardisWebClient::with_timeout)
+    /// / 请求超时时间，可通过 [`TardisWebClient::with_timeout`](crate::web::web_client::TardisWebClient::with_timeout) 按调用覆盖
     pub request_timeout_sec: u64,
+    /// Retry configuration / 重试配置
+    #[builder(default)]
+    pub retry: WebClientRetryConfig,
+    /// Circuit breaker configuration / 熔断器配置
+    #[builder(default)]
+    pub circuit_breaker: WebClientCircuitBreakerConfig,
+    /// Proxy configuration, the system proxy environment variables are used if it's not set
+    /// / 代理配置，未设置时使用系统代理环境变量
+    #[builder(default, setter(strip_option))]
+    pub proxy: Option<WebClientProxyConfig>,
+    /// TLS configuration / TLS配置
+    #[builder(default)]
+    pub tls: WebClientTlsConfig,
+    /// Response cache configuration / 响应缓存配置
+    #[builder(default)]
+    pub cache: WebClientCacheConfig,
+    /// WebSocket client configuration / WebSocket客户端配置
+    #[builder(default)]
+    pub ws: WSClientConfig,
+    /// Whether to carry the [`TardisContext`](crate::basic::dto::TardisContext) of the current task in the context header of the requests,
+    /// disabled by default, enable it only for the clients calling the trusted internal services
+    /// / 是否在请求的上下文请求头中携带当前task的 [`TardisContext`](crate::basic::dto::TardisContext) ，默认禁用，仅应对调用受信内部服务的客户端启用
+    #[builder(default)]
+    pub propagate_context: bool,
 }
 
 impl Default for WebClientModuleConfig {
@@ -22,3 +52,217 @@ impl Default for WebClientModuleConfig {
         Self::builder().build()
     }
 }
+
+/// Web client retry configuration / Web客户端重试配置
+///
+/// Only the idempotent methods (`GET` `HEAD` `PUT` `DELETE` `OPTIONS` `TRACE`) are retried,
+/// on the connection errors, the timeouts and the responses with the status in `retry_on_status`.
+///
+/// 只重试幂等方法（ `GET` `HEAD` `PUT` `DELETE` `OPTIONS` `TRACE` ），在连接错误、超时及响应状态码属于 `retry_on_status` 时重试.
+///
+/// # Examples
+/// ```toml
+/// [fw.web_client.retry]
+/// max_retries = 3
+/// initial_backoff_ms = 200
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WebClientRetryConfig {
+    /// Max retries, not including the first attempt, `0` disables the retry / 最大重试次数，不包含首次请求，`0` 表示不重试
+    #[builder(default = 0)]
+    pub max_retries: u32,
+    /// Initial backoff, doubled after each retry with full jitter / 初始退避时长，每次重试后翻倍并全抖动
+    #[builder(default = 100)]
+    pub initial_backoff_ms: u64,
+    #[builder(default = 5000)]
+    pub max_backoff_ms: u64,
+    #[builder(default = vec![502, 503, 504])]
+    pub retry_on_status: Vec<u16>,
+}
+
+impl Default for WebClientRetryConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Web client circuit breaker configuration / Web客户端熔断器配置
+///
+/// The breakers are keyed by the host, the connection errors, the timeouts and the `5xx` responses are counted as failures,
+/// the requests to an open host fail fast with the `503-tardis-webclient-circuit-open` error.
+///
+/// 熔断器按主机区分，连接错误、超时及 `5xx` 响应计为失败，对已熔断主机的请求以 `503-tardis-webclient-circuit-open` 错误快速失败.
+///
+/// # Examples
+/// ```toml
+/// [fw.web_client.circuit_breaker]
+/// enabled = true
+/// failure_threshold = 5
+/// open_sec = 30
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WebClientCircuitBreakerConfig {
+    #[builder(default = false)]
+    pub enabled: bool,
+    /// Consecutive failures to open the breaker / 打开熔断器的连续失败次数
+    #[builder(default = 5)]
+    pub failure_threshold: u32,
+    /// Duration of the open state before a trial request is allowed / 打开状态持续时长，之后允许一次试探请求
+    #[builder(default = 30)]
+    pub open_sec: u64,
+}
+
+impl Default for WebClientCircuitBreakerConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Web client proxy configuration / Web客户端代理配置
+///
+/// The `socks5://` and `socks5h://` proxies need to be enabled ```#[cfg(feature = "web-client-socks")]``` .
+///
+/// `socks5://` 及 `socks5h://` 代理需要启用 ```#[cfg(feature = "web-client-socks")]``` .
+///
+/// # Examples
+/// ```toml
+/// [fw.web_client.proxy]
+/// url = "http://proxy.corp.example.com:3128"
+/// username = "svc-gateway"
+/// password = "******"
+/// no_proxy = "localhost,127.0.0.1,.svc.cluster.local"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WebClientProxyConfig {
+    /// Proxy url of all the requests, e.g. `http://host:3128` or `socks5://host:1080` / 所有请求的代理地址，如 `http://host:3128` 或 `socks5://host:1080`
+    #[builder(setter(into))]
+    pub url: String,
+    /// Basic authentication of the proxy / 代理的基础认证
+    #[builder(default, setter(strip_option, into))]
+    pub username: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    pub password: Option<String>,
+    /// Comma separated hosts, domains (`.example.com`) and CIDRs bypassing the proxy / 以逗号分隔的不走代理的主机、域名（ `.example.com` ）及CIDR
+    #[builder(default, setter(strip_option, into))]
+    pub no_proxy: Option<String>,
+}
+
+impl Default for WebClientProxyConfig {
+    fn default() -> Self {
+        Self::builder().url("").build()
+    }
+}
+
+/// Web client TLS configuration / Web客户端TLS配置
+///
+/// # Examples
+/// ```toml
+/// [fw.web_client.tls]
+/// accept_invalid_certs = false
+/// ca_cert_paths = ["/etc/pki/corp-root-ca.pem"]
+/// client_cert_path = "/etc/pki/gateway.crt"
+/// client_key_path = "/etc/pki/gateway.key"
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WebClientTlsConfig {
+    /// Skip the verification of the server certificates, it's enabled by default for compatibility, disable it in production
+    /// / 跳过服务端证书校验，为兼容默认开启，生产环境中请关闭
+    #[builder(default = true)]
+    pub accept_invalid_certs: bool,
+    /// Additional trusted CA certificates, the PEM files may contain multiple certificates / 额外信任的CA证书，PEM文件可包含多个证书
+    #[builder(default)]
+    pub ca_cert_paths: Vec<String>,
+    /// Trust only the `ca_cert_paths` rather than the built-in root certificates / 只信任 `ca_cert_paths` 而不信任内置根证书
+    #[builder(default = false)]
+    pub disable_built_in_root_certs: bool,
+    /// Client certificate chain (PEM) for the mutual TLS / 双向TLS的客户端证书链（PEM）
+    #[builder(default, setter(strip_option, into))]
+    pub client_cert_path: Option<String>,
+    /// Client private key (PKCS#8 PEM) for the mutual TLS / 双向TLS的客户端私钥（PKCS#8 PEM）
+    #[builder(default, setter(strip_option, into))]
+    pub client_key_path: Option<String>,
+}
+
+impl Default for WebClientTlsConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Web client response cache configuration / Web客户端响应缓存配置
+///
+/// The `GET` responses are cached as the `Cache-Control` and `ETag`/`Last-Modified` response headers say,
+/// the shared cache by the cache client needs to be enabled ```#[cfg(feature = "cache")]``` .
+///
+/// 按 `Cache-Control` 及 `ETag`/`Last-Modified` 响应头缓存 `GET` 响应，通过缓存客户端共享的缓存需要启用 ```#[cfg(feature = "cache")]``` .
+///
+/// # Examples
+/// ```toml
+/// [fw.web_client.cache]
+/// enabled = true
+/// cache_module = ""
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WebClientCacheConfig {
+    #[builder(default = false)]
+    pub enabled: bool,
+    /// Max responses of the in-memory cache / 内存缓存的最大响应数
+    #[builder(default = 1000)]
+    pub max_entries: usize,
+    /// Use the cache client of the module (`""` is the default one) rather than the in-memory cache
+    /// / 使用该模块（ `""` 为默认模块）的缓存客户端而不是内存缓存
+    #[builder(default, setter(strip_option, into))]
+    pub cache_module: Option<String>,
+}
+
+impl Default for WebClientCacheConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// WebSocket client configuration / WebSocket客户端配置
+///
+/// WebSocket client operation needs to be enabled ```#[cfg(feature = "ws-client")]``` .
+///
+/// WebSocket客户端操作需要启用 ```#[cfg(feature = "ws-client")]``` .
+///
+/// # Examples
+/// ```toml
+/// [fw.web_client.ws]
+/// reconnect_interval_ms = 500
+/// ping_interval_sec = 10
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WSClientConfig {
+    /// Reconnect automatically when the connection is lost, except it's closed by the server / 连接断开时自动重连，服务端主动关闭的除外
+    #[builder(default = true)]
+    pub auto_reconnect: bool,
+    /// Initial reconnect interval, doubled after each failure / 初始重连间隔，每次失败后翻倍
+    #[builder(default = 1000)]
+    pub reconnect_interval_ms: u64,
+    #[builder(default = 30000)]
+    pub max_reconnect_interval_ms: u64,
+    /// Max reconnect attempts of a disconnection, `0` means unlimited / 单次断开的最大重连次数，`0` 表示不限
+    #[builder(default = 0)]
+    pub max_reconnect_attempts: u32,
+    /// Interval of the ping frames, `0` disables the keepalive / ping帧的发送间隔，`0` 表示关闭保活
+    #[builder(default = 30)]
+    pub ping_interval_sec: u64,
+    /// The connection is considered broken if nothing is received within the ping interval plus this timeout
+    /// / 在ping间隔加上该超时时间内未收到任何消息时视为连接断开
+    #[builder(default = 10)]
+    pub pong_timeout_sec: u64,
+}
+
+impl Default for WSClientConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr};
 
 use serde::{Deserialize, Serialize};
@@ -47,6 +48,10 @@ pub struct WebServerCommonConfig {
     #[builder(default, setter(strip_option))]
     /// Directly accessible port, same as port by default / 可直接访问的端口，默认与port相同
     pub access_port: Option<u16>,
+    #[builder(default, setter(strip_option))]
+    /// Port of the separate gRPC listener, the gRPC modules are served on the [port](Self::port) if it's not set
+    /// / 独立gRPC监听端口，未设置时gRPC模块在 [port](Self::port) 上提供服务
+    pub grpc_port: Option<u16>,
     #[builder(default = String::from("*"), setter(into))]
     /// Allowed cross-domain sources, default is `*` / 允许的跨域来源，默认为 `*`
     pub allowed_origin: String,
@@ -61,6 +66,62 @@ pub struct WebServerCommonConfig {
     pub context_conf: WebServerContextConfig,
     #[builder(default = false)]
     pub security_hide_err_msg: bool,
+    #[builder(default = 10)]
+    /// Max time to drain the in-flight requests on shutdown, in seconds, default is `10` / 关闭时排空在途请求的最长时间，单位秒，默认为 `10`
+    ///
+    /// Keep it shorter than the termination grace period of the deployment, e.g. `terminationGracePeriodSeconds` of Kubernetes.
+    ///
+    /// 应短于部署环境的终止宽限期，如Kubernetes的 `terminationGracePeriodSeconds` .
+    pub shutdown_timeout_sec: u64,
+    #[builder(default)]
+    /// Bearer token authentication configuration / Bearer令牌认证配置
+    pub auth: WebServerAuthConfig,
+}
+
+/// Bearer token authentication configuration / Bearer令牌认证配置
+///
+/// The JWTs are verified by the keys of [jwks_url](Self::jwks_url) (e.g. the OAuth2 / OIDC provider) or by the HMAC [secret](Self::secret).
+///
+/// JWT由 [jwks_url](Self::jwks_url) 的密钥（如OAuth2 / OIDC提供方）或HMAC [secret](Self::secret) 验证.
+///
+/// # Examples
+/// ```toml
+/// [fw.web_server.auth]
+/// jwks_url = "https://idp.example.com/.well-known/jwks.json"
+/// issuer = "https://idp.example.com"
+/// audience = ["todo"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct WebServerAuthConfig {
+    #[builder(default, setter(strip_option, into))]
+    /// JSON Web Key Set url, the keys are refetched when an unknown `kid` is met / JSON Web Key Set地址，遇到未知的 `kid` 时重新获取密钥
+    pub jwks_url: Option<String>,
+    #[builder(default = 3600)]
+    /// Max age of the fetched keys, in seconds, default is `3600` / 已获取密钥的最长有效期，单位秒，默认为 `3600`
+    pub jwks_refresh_sec: u64,
+    #[builder(default = 10)]
+    /// Min interval of refetching the keys for the unknown `kid`, in seconds, default is `10` / 因未知 `kid` 重新获取密钥的最小间隔，单位秒，默认为 `10`
+    pub jwks_min_refresh_sec: u64,
+    #[builder(default, setter(strip_option, into))]
+    /// HMAC secret of the `HS256` / `HS384` / `HS512` tokens / `HS256` / `HS384` / `HS512` 令牌的HMAC密钥
+    pub secret: Option<String>,
+    #[builder(default, setter(strip_option, into))]
+    /// Expected `iss` claim / 期望的 `iss` 声明
+    pub issuer: Option<String>,
+    #[builder(default, setter(into))]
+    /// Accepted `aud` claims, not checked if empty / 接受的 `aud` 声明，为空时不检查
+    pub audience: Vec<String>,
+    #[builder(default = 60)]
+    /// Clock skew tolerance of the `exp` and `nbf` claims, in seconds, default is `60` / `exp` 及 `nbf` 声明的时钟偏差容忍度，单位秒，默认为 `60`
+    pub leeway_sec: u64,
+    #[builder(default = String::from("roles"), setter(into))]
+    /// Claim of the roles, default is `roles` / 角色的声明，默认为 `roles`
+    pub roles_claim: String,
+    #[builder(default = String::from("scope"), setter(into))]
+    /// Claim of the permissions, an array or a space-separated string like the OAuth2 `scope`, default is `scope`
+    /// / 权限的声明，数组或与OAuth2 `scope` 一样以空格分隔的字符串，默认为 `scope`
+    pub permissions_claim: String,
 }
 
 /// Tardis context configuration / Tardis上下文配置
@@ -133,16 +194,40 @@ pub struct WebServerModuleConfig {
     /// Formatted as ``[(header name, header description)]`` / 格式为 ``[（请求头名称，请求头说明）]``
     pub req_headers: Vec<(String, String)>,
     #[builder(default = None, setter(strip_option, into))]
+    /// Module description for ``OpenAPI`` / 模块描述，用于 ``OpenAPI``
+    pub description: Option<String>,
+    #[builder(default, setter(into))]
+    /// Security schemes for ``OpenAPI`` , required by all the operations of the module
+    /// / 安全方案，用于 ``OpenAPI`` ，模块的所有操作均需满足
+    ///
+    /// Formatted as ``{scheme name: OpenAPI security scheme object}`` , e.g. ``{"bearer": {"type": "http", "scheme": "bearer"}}`` .
+    /// They are added to the spec documents served by ``spec_path`` and exported by ``export_openapi`` .
+    ///
+    /// 格式为 ``{方案名称: OpenAPI安全方案对象}`` ，如 ``{"bearer": {"type": "http", "scheme": "bearer"}}`` .
+    /// 会添加到由 ``spec_path`` 提供及由 ``export_openapi`` 导出的规范文档中.
+    pub security_schemes: HashMap<String, serde_json::Value>,
+    #[builder(default = None, setter(strip_option, into))]
     /// ``OpenAPI`` UI path / 模``OpenAPI`` UI路径
     pub ui_path: Option<String>,
     #[builder(default = None, setter(strip_option, into))]
-    /// ``OpenAPI`` information path / ``OpenAPI`` 信息路径
+    /// ``OpenAPI`` information path, the document is in JSON if the path ends with ``.json`` , otherwise in YAML
+    /// / ``OpenAPI`` 信息路径，路径以 ``.json`` 结尾时文档为JSON格式，否则为YAML格式
     pub spec_path: Option<String>,
     #[builder(default = true)]
     /// Enable `UniformError` middleware / 启用 `UniformError` 中间件
     ///
     /// It's enabled by default. In some cases like running a mocker server, this may be supposed to be closed
     pub uniform_error: bool,
+    #[builder(default, setter(strip_option, into))]
+    /// Allowed cross-domain sources of this module, overrides the `allowed_origin` of the web server
+    /// / 该模块允许的跨域来源，覆盖Web服务的 `allowed_origin`
+    pub allowed_origin: Option<String>,
+}
+
+impl Default for WebServerAuthConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
 }
 
 impl Default for WebServerContextConfig {
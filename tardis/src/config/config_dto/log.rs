@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
-use tracing_subscriber::filter::Directive;
+use tracing_subscriber::filter::{Directive, LevelFilter};
 use typed_builder::TypedBuilder;
 
+#[cfg(any(feature = "log-loki", feature = "log-fluentd"))]
+mod log_shipping;
+#[cfg(any(feature = "log-loki", feature = "log-fluentd"))]
+pub use log_shipping::*;
+mod slow_operation;
+pub use slow_operation::*;
 #[cfg(feature = "tracing")]
 mod tracing;
 #[cfg(feature = "tracing")]
@@ -17,11 +23,21 @@ pub use tracing_appender::*;
 ///
 /// - level: global log level, default to `info`
 /// - directives: log level with targets and modules, e.g. `tardis=debug,sqlx=info`
+/// - targets: log level of targets, e.g. `"sqlx::query" = "warn"`, the directives take precedence over it
+/// - slow_operation: slow operation warning thresholds of the built-in clients
+/// - module_appenders: file appenders of the modules, requires the `tracing-appender` feature
 /// ## Example
 /// ```toml
 /// [fw.log]
 /// level = "info"
 /// directives = ["tardis=debug", "sqlx=info"]
+/// [fw.log.targets]
+/// "sqlx::query" = "warn"
+/// "my_app::payment" = "debug"
+/// [fw.log.module_appenders."my_app::payment"]
+/// dir = "./logs"
+/// filename = "payment.log"
+/// rotation = "daily"
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
 #[serde(default)]
@@ -34,6 +50,10 @@ pub struct LogConfig {
     #[serde(deserialize_with = "deserialize_directives", serialize_with = "serialize_directives")]
     /// tracing filtering directive, e.g. `tardis=debug,sqlx=off`
     pub directives: Vec<Directive>,
+    #[builder(default, setter(into))]
+    #[serde(deserialize_with = "deserialize_targets", serialize_with = "serialize_targets")]
+    /// log level of targets, e.g. `"sqlx::query" = "warn"`
+    pub targets: BTreeMap<String, LevelFilter>,
     #[cfg(feature = "tracing")]
     #[builder(default)]
     /// open telemetry tracing config
@@ -43,6 +63,24 @@ pub struct LogConfig {
     /// tracing appender config
     /// a `None` value means no file output
     pub tracing_appender: Option<TracingAppenderConfig>,
+    #[cfg(feature = "tracing-appender")]
+    #[builder(default)]
+    /// file appenders of the modules by the target prefix, e.g. `tardis::cache`,
+    /// the events of the module are written to the module file as well as the file of `tracing_appender`
+    pub module_appenders: BTreeMap<String, TracingAppenderConfig>,
+    #[cfg(feature = "log-loki")]
+    #[builder(default)]
+    /// loki log shipping config
+    /// a `None` value means no shipping to loki
+    pub loki: Option<LokiConfig>,
+    #[cfg(feature = "log-fluentd")]
+    #[builder(default)]
+    /// fluentd log shipping config
+    /// a `None` value means no shipping to fluentd
+    pub fluentd: Option<FluentdConfig>,
+    #[builder(default)]
+    /// slow operation warning config
+    pub slow_operation: SlowOperationConfig,
     /// extension config for custom tracing layers
     #[builder(default)]
     pub ext: HashMap<String, crate::serde_json::Value>,
@@ -77,6 +115,22 @@ where
     directives.into_iter().map(|d| d.parse::<Directive>().map_err(serde::de::Error::custom)).collect()
 }
 
+fn serialize_targets<S>(value: &BTreeMap<String, LevelFilter>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let targets: BTreeMap<&str, String> = value.iter().map(|(target, level)| (target.as_str(), level.to_string().to_lowercase())).collect();
+    targets.serialize(serializer)
+}
+
+fn deserialize_targets<'de, D>(deserializer: D) -> Result<BTreeMap<String, LevelFilter>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let targets: BTreeMap<String, String> = BTreeMap::deserialize(deserializer)?;
+    targets.into_iter().map(|(target, level)| level.parse::<LevelFilter>().map(|level| (target, level)).map_err(serde::de::Error::custom)).collect()
+}
+
 impl Default for LogConfig {
     fn default() -> Self {
         LogConfig::builder().build()
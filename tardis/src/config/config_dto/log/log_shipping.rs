@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// # Loki log shipping configure
+///
+/// Push log events to [Grafana Loki](https://grafana.com/oss/loki/) by the HTTP push api in batches.
+///
+/// ## Example
+/// ```toml
+/// [fw.log.loki]
+/// url = "http://localhost:3100"
+/// labels = { app = "tardis", env = "prod" }
+/// ```
+#[cfg(feature = "log-loki")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+pub struct LokiConfig {
+    /// base url of loki, events are pushed to `<url>/loki/api/v1/push`
+    #[builder(setter(into))]
+    pub url: String,
+    /// stream labels, the `level` label is always added
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// tenant id, sent as the `X-Scope-OrgID` header
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub tenant: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    pub batch: LogShippingBatchConfig,
+}
+
+/// # Fluentd log shipping configure
+///
+/// Send log events to Fluentd or fluent-bit by the [forward protocol](https://github.com/fluent/fluentd/wiki/Forward-Protocol-Specification-v1) in batches.
+///
+/// ## Example
+/// ```toml
+/// [fw.log.fluentd]
+/// addr = "127.0.0.1:24224"
+/// tag = "tardis.app"
+/// ```
+#[cfg(feature = "log-fluentd")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+pub struct FluentdConfig {
+    /// address of the forward input, e.g. `127.0.0.1:24224`
+    #[builder(setter(into))]
+    pub addr: String,
+    /// tag of the events
+    #[builder(default = "tardis".to_string(), setter(into))]
+    #[serde(default = "default_fluentd_tag")]
+    pub tag: String,
+    #[builder(default)]
+    #[serde(default)]
+    pub batch: LogShippingBatchConfig,
+}
+
+#[cfg(feature = "log-fluentd")]
+fn default_fluentd_tag() -> String {
+    "tardis".to_string()
+}
+
+/// # Log shipping batch configure
+///
+/// Events are sent when the batch is full or the interval elapsed,
+/// new events are dropped when the queue is full, so that the application is never blocked by the log backend.
+///
+/// ## Example
+/// ```toml
+/// [fw.log.loki.batch]
+/// size = 100
+/// interval_ms = 1000
+/// queue_size = 10000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[serde(default)]
+pub struct LogShippingBatchConfig {
+    /// maximum number of events in a batch
+    #[builder(default = 100)]
+    pub size: usize,
+    /// maximum time in milliseconds to wait before sending a batch
+    #[builder(default = 1000)]
+    pub interval_ms: u64,
+    /// maximum number of events waiting to be sent
+    #[builder(default = 10000)]
+    pub queue_size: usize,
+}
+
+impl Default for LogShippingBatchConfig {
+    fn default() -> Self {
+        LogShippingBatchConfig::builder().build()
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// # Slow operation warning configure
+///
+/// Emit a `WARN` event (and increase the `tardis_client_slow_requests_total` metric if the `metrics` feature is enabled)
+/// when a client operation takes longer than the threshold.
+///
+/// The component names are `reldb`, `cache`, `search`, `web_client`, `mq`, `os` and `mail`.
+///
+/// ## Example
+/// ```toml
+/// [fw.log.slow_operation]
+/// threshold_ms = 1000
+/// [fw.log.slow_operation.components]
+/// reldb = 500
+/// cache = 50
+/// web_client = 3000
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, TypedBuilder)]
+#[serde(default)]
+pub struct SlowOperationConfig {
+    /// default threshold in milliseconds, `None` means disabled for the components without their own thresholds
+    #[builder(default, setter(strip_option))]
+    pub threshold_ms: Option<u64>,
+    /// thresholds in milliseconds of the components
+    #[builder(default, setter(into))]
+    pub components: HashMap<String, u64>,
+}
+
+impl SlowOperationConfig {
+    /// Get the threshold of the component / 获取组件的阈值
+    pub fn threshold(&self, component: &str) -> Option<Duration> {
+        self.components.get(component).copied().or(self.threshold_ms).map(Duration::from_millis)
+    }
+}
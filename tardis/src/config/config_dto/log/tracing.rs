@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
@@ -47,7 +48,43 @@ impl<'de> Deserialize<'de> for OtlpProtocol {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TypedBuilder)]
+/// Trace sampler / 链路采样器
+///
+/// Used for the root spans, or all spans if `parent_based` of [`TracingConfig`] is disabled.
+///
+/// 用于根span，若 [`TracingConfig`] 的 `parent_based` 被禁用则用于所有span.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "ratio")]
+pub enum TracingSampler {
+    #[default]
+    AlwaysOn,
+    AlwaysOff,
+    /// Sample a ratio of traces, E.g. `0.1` for 10% / 按比例采样，如 `0.1` 表示10%
+    TraceIdRatio(f64),
+}
+
+// Only used for comparing configs, a NaN ratio is treated as `0` when applied.
+impl Eq for TracingSampler {}
+
+/// # Open telemetry tracing configure / OpenTelemetry链路配置
+///
+/// ## Example
+/// ```toml
+/// [fw.log.tracing]
+/// endpoint = "http://localhost:4317"
+/// protocol = "grpc"
+/// server_name = "todo-service"
+/// headers = "authorization=Basic xxx"
+/// sampler = { type = "trace_id_ratio", ratio = 0.1 }
+/// parent_based = true
+/// [fw.log.tracing.resource_attributes]
+/// "deployment.environment" = "prod"
+/// [fw.log.tracing.route_rate_limits]
+/// "/health" = 0.1
+/// "/api/orders" = 50
+/// ```
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypedBuilder)]
+#[serde(default)]
 pub struct TracingConfig {
     #[cfg(feature = "tracing")]
     #[builder(default = "http://localhost:4317".to_string(), setter(into))]
@@ -60,9 +97,28 @@ pub struct TracingConfig {
     pub server_name: String,
     #[cfg(feature = "tracing")]
     #[builder(default, setter(into, strip_option))]
+    /// exporter headers, e.g. `key1=value1,key2=value2`
     pub headers: Option<String>,
+    #[cfg(feature = "tracing")]
+    #[builder(default)]
+    /// extra resource attributes, e.g. `deployment.environment`
+    pub resource_attributes: HashMap<String, String>,
+    #[cfg(feature = "tracing")]
+    #[builder(default)]
+    pub sampler: TracingSampler,
+    #[cfg(feature = "tracing")]
+    #[builder(default = true)]
+    /// follow the sampling decision of the parent span (e.g. from the `traceparent` header)
+    pub parent_based: bool,
+    #[cfg(feature = "tracing")]
+    #[builder(default)]
+    /// maximum sampled root traces per second of the routes, keyed by the path prefix, the longest matched prefix is used
+    pub route_rate_limits: HashMap<String, f64>,
 }
 
+// Only used for comparing configs, a NaN rate is treated as `0` when applied.
+impl Eq for TracingConfig {}
+
 impl Default for TracingConfig {
     fn default() -> Self {
         Self::builder().build()
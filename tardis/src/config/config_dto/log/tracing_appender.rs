@@ -3,12 +3,42 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use tracing_appender::rolling::Rotation;
 use typed_builder::TypedBuilder;
+
+/// # Log file configure
+///
+/// The active log file is `dir/filename`, it's rotated to `dir/filename.<time>` when the period changes or the size exceeds `max_size`.
+///
+/// ## Example
+/// ```toml
+/// [fw.log.tracing_appender]
+/// dir = "./logs"
+/// filename = "app.log"
+/// rotation = "daily"
+/// max_size = 104857600
+/// max_files = 30
+/// compress = true
+/// ```
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TypedBuilder, Default)]
 pub struct TracingAppenderConfig {
     #[builder(default, setter(into))]
+    #[serde(default)]
     pub rotation: TracingAppenderRotation,
+    #[builder(setter(into))]
     pub dir: PathBuf,
+    #[builder(setter(into))]
     pub filename: PathBuf,
+    /// rotate when the file size exceeds the bytes, `None` means no size limit
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    /// maximum number of rotated files to keep, `None` means keep all
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    /// compress rotated files with gzip
+    #[builder(default)]
+    #[serde(default)]
+    pub compress: bool,
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
@@ -21,6 +51,18 @@ pub enum TracingAppenderRotation {
     Daily,
 }
 
+impl TracingAppenderRotation {
+    /// Time format of the rotation period, `None` means never rotate by time
+    pub fn period_format(&self) -> Option<&'static str> {
+        match self {
+            TracingAppenderRotation::Never => None,
+            TracingAppenderRotation::Minutely => Some("%Y-%m-%d-%H-%M"),
+            TracingAppenderRotation::Hourly => Some("%Y-%m-%d-%H"),
+            TracingAppenderRotation::Daily => Some("%Y-%m-%d"),
+        }
+    }
+}
+
 impl From<TracingAppenderRotation> for Rotation {
     fn from(val: TracingAppenderRotation) -> Self {
         match val {
@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use config::ConfigError;
+use tracing::{debug, trace, warn};
+
+use super::{config_dto::ConfCenterConfig, config_processor::ConfCenterProcess};
+use crate::basic::result::TardisResult;
+use crate::config::config_utils::config_foreign_err;
+use crate::config::config_watcher;
+pub mod etcd_client;
+
+#[derive(Debug)]
+/// Config from etcd,
+/// A source corresponding to a remote config
+pub(crate) struct ConfEtcdConfigSource<F: config::Format> {
+    key: String,
+    /// etcd client
+    etcd_client: Arc<etcd_client::EtcdClient>,
+    format: Arc<F>,
+    /// revision of the last read
+    revision: Arc<tokio::sync::Mutex<Option<i64>>>,
+}
+
+impl<F: config::Format> std::fmt::Display for ConfEtcdConfigSource<F>
+where
+    F: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ConfEtcdConfigSource {{ key: {}, etcd_client: {}, format: {:?} }}",
+            self.key, self.etcd_client, self.format,
+        )
+    }
+}
+
+impl<F: config::Format> Clone for ConfEtcdConfigSource<F> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            etcd_client: self.etcd_client.clone(),
+            format: self.format.clone(),
+            revision: self.revision.clone(),
+        }
+    }
+}
+
+impl<F: config::Format> ConfEtcdConfigSource<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    /// create a new config source, the key is `[<namespace>/]<group>/<app_id>-<profile>`
+    fn new(profile: Option<&str>, app_id: &str, namespace: Option<&str>, group: &str, format: Arc<F>, etcd_client: &Arc<etcd_client::EtcdClient>) -> Self {
+        let data_id = format!("{}-{}", app_id, profile.unwrap_or("default"));
+        let key = match namespace {
+            Some(namespace) => format!("{namespace}/{group}/{data_id}"),
+            None => format!("{group}/{data_id}"),
+        };
+        Self {
+            key,
+            etcd_client: etcd_client.clone(),
+            format,
+            revision: Arc::new(tokio::sync::Mutex::new(None)),
+        }
+    }
+
+    fn listen_update(self, update_notifier: tokio::sync::mpsc::Sender<()>) {
+        let task = async move {
+            debug!("[Tardis.config] Etcd Remote listener start for {:?}", &self);
+            loop {
+                // watch the changes after the last read, 0 means from now on
+                let start_revision = self.revision.lock().await.map(|revision| revision + 1).unwrap_or(0);
+                match self.etcd_client.watch(&self.key, start_revision).await {
+                    Ok(Some(revision)) => {
+                        self.revision.lock().await.replace(revision);
+                        match update_notifier.send(()).await {
+                            Ok(_) => {
+                                debug!("[Tardis.config] Etcd Remote config updated, send update notifier")
+                            }
+                            Err(e) => {
+                                // if receiver dropped, stop watching, since tardis wont be reloaded anyway
+                                warn!("[Tardis.config] Etcd Remote config updated, but no receiver found, stop watching, error: {e}");
+                                break;
+                            }
+                        }
+                    }
+                    // if the stream closed or request failed, wait for next watch
+                    Ok(None) => {
+                        tokio::time::sleep(self.etcd_client.poll_period).await;
+                    }
+                    Err(e) => {
+                        warn!("[Tardis.config] Etcd Remote watch failed, error: {e}");
+                        tokio::time::sleep(self.etcd_client.poll_period).await;
+                    }
+                }
+            }
+        };
+        config_watcher::add_task(tokio::spawn(task));
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: config::Format> config::AsyncSource for ConfEtcdConfigSource<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn collect(&self) -> Result<config::Map<String, config::Value>, ConfigError> {
+        debug!("[Tardis.config] Etcd Remote config server request: {}", &self);
+        let (config_text, revision) = self.etcd_client.get(&self.key).await.map_err(config_foreign_err)?;
+        self.revision.lock().await.replace(revision);
+        match config_text {
+            Some(config_text) => {
+                trace!("[Tardis.config] Etcd Remote config server response: {}", config_text);
+                self.format.parse(None, &config_text).map_err(ConfigError::Foreign)
+            }
+            None => {
+                warn!("[Tardis.config] Etcd Remote config not found, config: {}", &self);
+                Ok(config::Map::new())
+            }
+        }
+    }
+}
+
+/// # Etcd config processor
+/// implement ConfProcess for etcd
+#[derive(Debug)]
+pub(crate) struct ConfEtcdProcessor<F: config::Format>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    /// *-default config source
+    pub(crate) default_config_source: ConfEtcdConfigSource<F>,
+    /// *-{profile} config source, it could be none
+    pub(crate) config_source: Option<ConfEtcdConfigSource<F>>,
+}
+
+impl<F: config::Format> ConfEtcdProcessor<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    /// create a new etcd config processor
+    pub async fn init(config: &ConfCenterConfig, profile: &str, app_id: &str, format: &Arc<F>) -> TardisResult<ConfEtcdProcessor<F>> {
+        let mut client = etcd_client::EtcdClient::new(config.url.as_str());
+        // set retry interval, default to 5s
+        client.poll_period = std::time::Duration::from_millis(config.config_change_polling_interval.unwrap_or(5000));
+        if !config.username.is_empty() {
+            client.login(&config.username, &config.password).await?;
+        }
+        let etcd_client = Arc::new(client);
+        let group = config.group.as_deref().unwrap_or("default");
+        let namespace = config.namespace.as_deref();
+        // there are two config source, *-{profile} could be empty
+        let default_config_source = ConfEtcdConfigSource::new(None, app_id, namespace, group, format.clone(), &etcd_client);
+        let config_source = if !profile.is_empty() {
+            Some(ConfEtcdConfigSource::new(Some(profile), app_id, namespace, group, format.clone(), &etcd_client))
+        } else {
+            None
+        };
+        Ok(Self {
+            default_config_source,
+            config_source,
+        })
+    }
+}
+
+impl<F: config::Format> ConfCenterProcess for ConfEtcdProcessor<F>
+where
+    F: Send + Sync + std::fmt::Debug + 'static,
+{
+    fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+        self.default_config_source.clone().listen_update(reload_notifier.clone());
+        if let Some(h) = self.config_source.clone() {
+            h.listen_update(reload_notifier.clone())
+        }
+    }
+    fn register_to_config(&self, mut conf: config::ConfigBuilder<config::builder::AsyncState>) -> config::ConfigBuilder<config::builder::AsyncState> {
+        conf = conf.add_async_source(self.default_config_source.clone());
+        if let Some(config_source) = self.config_source.as_ref() {
+            conf = conf.add_async_source(config_source.clone());
+        }
+        conf
+    }
+}
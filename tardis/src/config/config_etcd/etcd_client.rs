@@ -0,0 +1,154 @@
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tracing::{debug, trace};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+const TOKEN_HEADER: &str = "Authorization";
+
+/// for request etcd v3 json gateway, see https://etcd.io/docs/v3.5/dev-guide/api_grpc_gateway/
+#[derive(Debug, Clone)]
+pub struct EtcdClient {
+    pub base_url: String,
+    /// retry period when watching failed, default 5s
+    pub poll_period: Duration,
+    token: Arc<Mutex<Option<String>>>,
+    pub reqwest_client: reqwest::Client,
+    auth: Option<(String, String)>,
+}
+
+impl Display for EtcdClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EtcdClient {{ base_url: {}, poll_period: {:?} }}", self.base_url, self.poll_period)
+    }
+}
+
+impl Default for EtcdClient {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:2379".to_owned(),
+            poll_period: Duration::from_secs(5),
+            token: Default::default(),
+            reqwest_client: reqwest::Client::new(),
+            auth: None,
+        }
+    }
+}
+
+impl EtcdClient {
+    /// create a new etcd client
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            ..Default::default()
+        }
+    }
+
+    /// authenticate with username and password
+    pub async fn login(&mut self, username: &str, password: &str) -> TardisResult<&mut Self> {
+        self.auth = Some((username.to_string(), password.to_string()));
+        self.relogin().await?;
+        Ok(self)
+    }
+
+    async fn relogin(&self) -> TardisResult<()> {
+        let Some((username, password)) = &self.auth else {
+            return Ok(());
+        };
+        debug!("[Tardis.Config] Trying to login etcd");
+        let url = format!("{}/v3/auth/authenticate", self.base_url.trim_end_matches('/'));
+        let resp = self.reqwest_client.post(&url).json(&json!({"name": username, "password": password})).send().await?.error_for_status()?.json::<Value>().await?;
+        let token = resp.get("token").and_then(Value::as_str).ok_or_else(|| TardisError::format_error("[Tardis.Config] Etcd authenticate response missing token", ""))?;
+        *self.token.lock().await = Some(token.to_string());
+        Ok(())
+    }
+
+    /// post to the gateway, re-login once if the token expired
+    async fn post(&self, path: &str, body: &Value) -> TardisResult<reqwest::Response> {
+        let url = format!("{}{}", self.base_url.trim_end_matches('/'), path);
+        let mut resp = self.post_with_token(&url, body).await?;
+        if resp.status() == reqwest::StatusCode::UNAUTHORIZED && self.auth.is_some() {
+            self.relogin().await?;
+            resp = self.post_with_token(&url, body).await?;
+        }
+        Ok(resp.error_for_status()?)
+    }
+
+    async fn post_with_token(&self, url: &str, body: &Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.reqwest_client.post(url).json(body);
+        if let Some(token) = self.token.lock().await.as_deref() {
+            request = request.header(TOKEN_HEADER, token);
+        }
+        request.send().await
+    }
+
+    /// get the value and the revision of a key, the value is none if the key doesn't exist
+    pub async fn get(&self, key: &str) -> TardisResult<(Option<String>, i64)> {
+        let resp = self.post("/v3/kv/range", &json!({"key": TardisFuns::crypto.base64.encode(key)})).await?.json::<Value>().await?;
+        trace!("[Tardis.Config] Etcd range key: {}, response: {}", key, resp);
+        let revision = resp.get("header").and_then(|header| header.get("revision")).map(as_i64).unwrap_or(0);
+        let value = match resp.get("kvs").and_then(|kvs| kvs.get(0)).and_then(|kv| kv.get("value")).and_then(Value::as_str) {
+            Some(value) => Some(TardisFuns::crypto.base64.decode_to_string(value)?),
+            None => None,
+        };
+        Ok((value, revision))
+    }
+
+    /// put the value of a key
+    pub async fn put(&self, key: &str, value: &str) -> TardisResult<()> {
+        self.post(
+            "/v3/kv/put",
+            &json!({"key": TardisFuns::crypto.base64.encode(key), "value": TardisFuns::crypto.base64.encode(value)}),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// watch a key from the revision, returns the revision of the first change, or none if the watch stream closed without changes
+    pub async fn watch(&self, key: &str, start_revision: i64) -> TardisResult<Option<i64>> {
+        let mut resp = self
+            .post(
+                "/v3/watch",
+                &json!({"create_request": {"key": TardisFuns::crypto.base64.encode(key), "start_revision": start_revision}}),
+            )
+            .await?;
+        // the gateway streams a json object per line
+        let mut buf = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            buf.extend_from_slice(&chunk);
+            while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+                let line = buf.drain(..=pos).collect::<Vec<_>>();
+                if let Some(revision) = changed_revision(&line)? {
+                    return Ok(Some(revision));
+                }
+            }
+        }
+        changed_revision(&buf)
+    }
+}
+
+/// the revision of the change in a watch response, none if it's not a change (e.g. the creation response)
+fn changed_revision(line: &[u8]) -> TardisResult<Option<i64>> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    let resp = TardisFuns::json.str_to_obj::<Value>(&String::from_utf8_lossy(line))?;
+    trace!("[Tardis.Config] Etcd watch response: {}", resp);
+    let result = resp.get("result").unwrap_or(&resp);
+    let has_events = result.get("events").and_then(Value::as_array).map(|events| !events.is_empty()).unwrap_or(false);
+    if !has_events {
+        return Ok(None);
+    }
+    Ok(Some(result.get("header").and_then(|header| header.get("revision")).map(as_i64).unwrap_or(0)))
+}
+
+/// int64 fields are encoded as strings by the gateway
+fn as_i64(value: &Value) -> i64 {
+    value.as_i64().or_else(|| value.as_str().and_then(|value| value.parse().ok())).unwrap_or(0)
+}
@@ -8,6 +8,7 @@ use self::nacos_client::NacosClientError;
 use super::{config_dto::ConfCenterConfig, config_processor::ConfCenterProcess};
 use crate::basic::result::TardisResult;
 use crate::config::config_utils::config_foreign_err;
+use crate::config::config_watcher;
 pub mod nacos_client;
 #[derive(Debug)]
 /// Config from Nacos,
@@ -86,22 +87,24 @@ where
                 if !updated.unwrap_or(false) {
                     tokio::time::sleep(self.nacos_client.poll_period).await;
                     continue;
-                } else {
-                    match update_notifier.send(()).await {
-                        Ok(_) => {
-                            // tardis will be reboot, stop watching
-                            debug!("[Tardis.config] Nacos Remote config updated, send update notifier")
-                        }
-                        Err(e) => {
-                            // if receiver dropped, stop watching, since tardis wont be reboot anyway
-                            warn!("[Tardis.config] Nacos Remote config updated, but no receiver found, stop watching, error: {e}");
-                        }
+                }
+                // refresh the md5, otherwise the same update will be notified again
+                if let Err(e) = self.nacos_client.get_config(&self.get_nacos_config_descriptor()).await {
+                    warn!("[Tardis.config] Nacos Remote config updated, but fetch failed, error: {e}");
+                }
+                match update_notifier.send(()).await {
+                    Ok(_) => {
+                        debug!("[Tardis.config] Nacos Remote config updated, send update notifier")
+                    }
+                    Err(e) => {
+                        // if receiver dropped, stop watching, since tardis wont be reloaded anyway
+                        warn!("[Tardis.config] Nacos Remote config updated, but no receiver found, stop watching, error: {e}");
+                        break;
                     }
-                    break;
                 }
             }
         };
-        tokio::spawn(task);
+        config_watcher::add_task(tokio::spawn(task));
     }
 }
 
@@ -233,6 +233,123 @@ impl NacosClient {
     }
 }
 
+impl NacosClient {
+    /// request the naming openapi, with access_token if client has one, re-login once if the token is expired
+    async fn naming_request(&self, method: reqwest::Method, path: &str, params: &[(&str, String)]) -> Result<reqwest::Response, NacosClientError> {
+        let url = format!("{}{path}", self.base_url);
+        let mut resp = self.reqwest_client.request(method.clone(), &url).query(params).query(&self.access_token_as_query().await).send().await?;
+        if resp.status() == reqwest::StatusCode::FORBIDDEN {
+            self.relogin().await?;
+            resp = self.reqwest_client.request(method, &url).query(params).query(&self.access_token_as_query().await).send().await?;
+        }
+        Ok(resp.error_for_status()?)
+    }
+
+    /// register an instance of the service
+    pub async fn register_instance(&self, service: &NacosServiceDescriptor<'_>, instance: &NacosInstance) -> Result<bool, NacosClientError> {
+        let mut params = service.as_params();
+        params.extend(instance.as_params());
+        let resp = self.naming_request(reqwest::Method::POST, "/v1/ns/instance", &params).await?;
+        Ok(resp.text().await? == "ok")
+    }
+
+    /// deregister an instance of the service
+    pub async fn deregister_instance(&self, service: &NacosServiceDescriptor<'_>, instance: &NacosInstance) -> Result<bool, NacosClientError> {
+        let mut params = service.as_params();
+        params.extend(instance.as_params());
+        let resp = self.naming_request(reqwest::Method::DELETE, "/v1/ns/instance", &params).await?;
+        Ok(resp.text().await? == "ok")
+    }
+
+    /// send heartbeat of an instance, return Ok(false) if the instance is not registered (e.g. expired)
+    pub async fn send_instance_beat(&self, service: &NacosServiceDescriptor<'_>, instance: &NacosInstance) -> Result<bool, NacosClientError> {
+        let beat = serde_json::json!({
+            "serviceName": format!("{}@@{}", service.group_name, service.service_name),
+            "ip": instance.ip,
+            "port": instance.port,
+            "weight": instance.weight,
+            "cluster": instance.cluster_name,
+            "metadata": instance.metadata,
+            "scheduled": true,
+        });
+        let mut params = service.as_params();
+        params.push(("ephemeral", instance.ephemeral.to_string()));
+        params.push(("beat", beat.to_string()));
+        let resp = self.naming_request(reqwest::Method::PUT, "/v1/ns/instance/beat", &params).await?.json::<NacosBeatResponse>().await?;
+        Ok(resp.code != NACOS_RESOURCE_NOT_FOUND)
+    }
+
+    /// list the instances of the service
+    pub async fn list_instances(&self, service: &NacosServiceDescriptor<'_>, healthy_only: bool) -> Result<Vec<NacosInstance>, NacosClientError> {
+        let mut params = service.as_params();
+        params.push(("healthyOnly", healthy_only.to_string()));
+        let resp = self.naming_request(reqwest::Method::GET, "/v1/ns/instance/list", &params).await?.json::<NacosInstanceListResponse>().await?;
+        Ok(resp.hosts)
+    }
+}
+
+/// # Nacos service descriptor
+/// it's a descriptor corresponding to a service in nacos
+#[derive(Debug, Clone)]
+pub struct NacosServiceDescriptor<'a> {
+    pub service_name: &'a str,
+    pub group_name: &'a str,
+    pub namespace_id: Option<&'a str>,
+}
+
+impl<'a> NacosServiceDescriptor<'a> {
+    fn as_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("serviceName", self.service_name.to_string()), ("groupName", self.group_name.to_string())];
+        params.extend(self.namespace_id.map(|namespace_id| ("namespaceId", namespace_id.to_string())));
+        params
+    }
+}
+
+/// # Nacos service instance
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NacosInstance {
+    pub ip: String,
+    pub port: u16,
+    pub weight: f64,
+    pub healthy: bool,
+    pub enabled: bool,
+    pub ephemeral: bool,
+    pub cluster_name: String,
+    pub metadata: HashMap<String, String>,
+}
+
+impl NacosInstance {
+    fn as_params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("ip", self.ip.clone()),
+            ("port", self.port.to_string()),
+            ("weight", self.weight.to_string()),
+            ("enabled", self.enabled.to_string()),
+            ("healthy", self.healthy.to_string()),
+            ("ephemeral", self.ephemeral.to_string()),
+            ("clusterName", self.cluster_name.clone()),
+            // metadata should be a json string
+            ("metadata", serde_json::to_string(&self.metadata).unwrap_or_default()),
+        ]
+    }
+}
+
+/// code of the beat response when the instance is not found
+const NACOS_RESOURCE_NOT_FOUND: i32 = 20404;
+
+#[derive(Deserialize)]
+struct NacosBeatResponse {
+    #[serde(default)]
+    code: i32,
+}
+
+#[derive(Deserialize)]
+struct NacosInstanceListResponse {
+    #[serde(default)]
+    hosts: Vec<NacosInstance>,
+}
+
 /// # Nacos config descriptor
 /// it's a descriptor corresponding to a config in nacos, it stores content's md5 value
 #[derive(Serialize, Debug, Clone)]
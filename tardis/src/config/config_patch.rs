@@ -0,0 +1,134 @@
+//! Runtime configuration override / 运行时配置覆盖
+//!
+//! The overrides are merged on top of the loaded configuration, they are kept when the configuration is reloaded by the watchers
+//! (e.g. the config center changed) and cleared by [`TardisFuns::init`](crate::TardisFuns::init).
+//!
+//! 覆盖项合并在已加载的配置之上，在配置被监听重新加载（如配置中心变更）时保留，由 [`TardisFuns::init`](crate::TardisFuns::init) 清除.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::config::config_patch::TardisConfigPatch;
+//! use tardis::TardisFuns;
+//! TardisFuns::override_config("fw.log.level", "debug").await?;
+//! TardisFuns::patch_config(TardisConfigPatch::new().set("csm.todo.feature_x", true).set("cs.project_name", "todo")).await?;
+//! ```
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use serde_json::{Map, Value};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::TardisConfig;
+use crate::tardis_static;
+use crate::TardisFuns;
+
+tardis_static! {
+    config_overrides: RwLock<Vec<(String, Value)>>;
+}
+
+/// Overrides of the configuration / 配置的覆盖项
+///
+/// The paths are the same as the configuration files, separated by `.`:
+/// `fw.<...>` for the framework configuration, `cs.<...>` for the default custom configuration, `csm.<code>.<...>` for the custom configuration of a module.
+///
+/// 路径与配置文件相同，以 `.` 分隔: `fw.<...>` 为框架配置， `cs.<...>` 为默认的自定义配置， `csm.<code>.<...>` 为模块的自定义配置.
+#[derive(Debug, Clone, Default)]
+pub struct TardisConfigPatch {
+    overrides: Vec<(String, Value)>,
+}
+
+impl TardisConfigPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of the path, the later one wins / 设置路径的值，后设置的生效
+    pub fn set(mut self, path: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.overrides.push((path.into(), value.into()));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// Apply to the configuration / 应用到配置
+    pub fn apply_to(&self, config: TardisConfig) -> TardisResult<TardisConfig> {
+        apply(config, &self.overrides)
+    }
+}
+
+/// Keep the patch to apply it on reloading / 保存覆盖项以在重新加载时应用
+pub(crate) fn save(patch: &TardisConfigPatch) -> TardisResult<()> {
+    let mut overrides = config_overrides().write().map_err(|error| TardisError::internal_error(&format!("[Tardis.Config] {error:?}"), ""))?;
+    overrides.extend(patch.overrides.iter().cloned());
+    Ok(())
+}
+
+pub(crate) fn clear() {
+    if let Ok(mut overrides) = config_overrides().write() {
+        overrides.clear();
+    }
+}
+
+/// Apply the saved overrides / 应用已保存的覆盖项
+pub(crate) fn apply_saved(config: TardisConfig) -> TardisResult<TardisConfig> {
+    let overrides = config_overrides().read().map_err(|error| TardisError::internal_error(&format!("[Tardis.Config] {error:?}"), ""))?.clone();
+    if overrides.is_empty() {
+        return Ok(config);
+    }
+    apply(config, &overrides)
+}
+
+fn apply(config: TardisConfig, overrides: &[(String, Value)]) -> TardisResult<TardisConfig> {
+    let mut cs = config.cs;
+    let mut doc = Map::new();
+    doc.insert("fw".to_string(), TardisFuns::json.obj_to_json(&config.fw)?);
+    doc.insert("cs".to_string(), cs.remove("").unwrap_or_else(|| Value::Object(Map::new())));
+    doc.insert("csm".to_string(), Value::Object(cs.into_iter().collect()));
+    let mut doc = Value::Object(doc);
+    for (path, value) in overrides {
+        let segments = path.split('.').map(str::trim).collect::<Vec<_>>();
+        if !matches!(segments.first(), Some(&"fw") | Some(&"cs") | Some(&"csm")) || segments.len() < 2 || segments.iter().any(|segment| segment.is_empty()) {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.Config] Invalid override path [{path}], it should start with [fw] [cs] or [csm]"),
+                "406-tardis-config-override-invalid",
+            ));
+        }
+        set_path(&mut doc, &segments, value.clone());
+    }
+    let Value::Object(mut doc) = doc else {
+        return Err(TardisError::internal_error("[Tardis.Config] Override document is not an object", ""));
+    };
+    let fw = TardisFuns::json.json_to_obj(doc.remove("fw").unwrap_or(Value::Null))?;
+    let mut cs = match doc.remove("csm") {
+        Some(Value::Object(csm)) => csm.into_iter().collect::<HashMap<_, _>>(),
+        _ => HashMap::new(),
+    };
+    if let Some(default_cs) = doc.remove("cs").filter(|default_cs| !matches!(default_cs, Value::Object(fields) if fields.is_empty())) {
+        cs.insert("".to_string(), default_cs);
+    }
+    Ok(TardisConfig { cs, fw })
+}
+
+/// Set the value of the path, the missing or non-object parents are replaced by objects / 设置路径的值，缺失或非对象的父级被替换为对象
+fn set_path(doc: &mut Value, segments: &[&str], value: Value) {
+    let mut current = doc;
+    for segment in segments {
+        current = child(current, segment);
+    }
+    *current = value;
+}
+
+/// The element of an array by index, or the field of an object / 数组中按索引的元素，或对象的字段
+fn child<'a>(current: &'a mut Value, segment: &str) -> &'a mut Value {
+    let idx = segment.parse::<usize>().ok().filter(|idx| current.as_array().map(|items| *idx < items.len()).unwrap_or(false));
+    if let Some(idx) = idx {
+        return &mut current.as_array_mut().expect("ensured array")[idx];
+    }
+    if !current.is_object() {
+        *current = Value::Object(Map::new());
+    }
+    current.as_object_mut().expect("ensured object").entry(segment.to_string()).or_insert(Value::Null)
+}
@@ -1,17 +1,26 @@
 use config::builder::AsyncState;
-use config::{ConfigBuilder, ConfigError, Environment, File};
+use config::{Config, ConfigBuilder, ConfigError, Environment, File};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::env;
 use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
 #[cfg(feature = "conf-remote")]
-use {config::FileFormat, std::sync::Arc};
+use {
+    crate::tardis_static,
+    config::FileFormat,
+    std::future::Future,
+    std::pin::Pin,
+    std::sync::{Arc, RwLock},
+};
 
 use crate::basic::error::TardisError;
 use crate::basic::fetch_profile;
 use crate::basic::locale::TardisLocale;
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::FrameworkConfig;
+use crate::config::{config_patch, config_secret, config_watcher};
 use tracing::{debug, info};
 
 use super::config_dto::{ConfCenterConfig, TardisConfig};
@@ -32,6 +41,21 @@ use super::config_dto::{ConfCenterConfig, TardisConfig};
 /// 1. Remote file: <fw.app.id>-<profile>
 /// 1. Environment variables starting with TARDIS
 ///
+/// ## Include local files
+///
+/// A local file can include other local files in the same path by `include = ["conf-base-db", "conf-base-mq"]`,
+/// the included files are loaded (recursively) before the including file, so the including file wins.
+///
+/// 本地文件可通过 `include = ["conf-base-db", "conf-base-mq"]` 包含同一路径下的其它本地文件，
+/// 被包含的文件（递归地）在包含它的文件之前加载，即包含它的文件优先.
+///
+/// ## Secret references
+///
+/// The string values can reference the secrets by `${env:NAME}` `${file:/path}` `${vault:path#field}`,
+/// see [`config_secret`](crate::config::config_secret) for details.
+///
+/// 字符串值可通过 `${env:NAME}` `${file:/path}` `${vault:path#field}` 引用密钥，详见 [`config_secret`](crate::config::config_secret) .
+///
 impl TardisConfig {
     pub(crate) async fn init(relative_path: Option<&str>) -> TardisResult<TardisConfig> {
         let profile = fetch_profile();
@@ -41,7 +65,42 @@ impl TardisConfig {
             parent_path, relative_path, profile
         );
 
-        let config = TardisConfig::do_init(relative_path, &profile, None).await?;
+        // replace the watchers of the previous initialization
+        config_watcher::stop();
+        config_patch::clear();
+        let reload_notifier = config_watcher::start(relative_path);
+        let config = TardisConfig::load(relative_path, &profile, Some(&reload_notifier)).await?;
+        if let (Some(relative_path), Some(interval)) = (relative_path, config.fw.adv.config_watch_interval_ms) {
+            config_watcher::watch_local_files(relative_path, &profile, Duration::from_millis(interval), reload_notifier.clone());
+        }
+
+        info!(
+            "[Tardis.Config] Initialized, base path:{:?}, relative path:{:?}, profile:{}",
+            parent_path, relative_path, profile
+        );
+        debug!("=====[Tardis.Config] Content=====\n{:#?}\n=====", &config.fw);
+
+        if let Some(relative_path) = relative_path {
+            TardisLocale::init(Path::new(relative_path))?;
+        }
+        Ok(config)
+    }
+
+    /// Reload the configuration with the runtime overrides, without starting the watchers / 重新加载配置并应用运行时覆盖项，不启动监听
+    pub(crate) async fn reload(relative_path: Option<&str>) -> TardisResult<TardisConfig> {
+        let config = TardisConfig::load(relative_path, &fetch_profile(), None).await?;
+        // keep the runtime overrides
+        let config = config_patch::apply_saved(config)?;
+        if let Some(relative_path) = relative_path {
+            TardisLocale::init(Path::new(relative_path))?;
+        }
+        Ok(config)
+    }
+
+    /// Load the local configuration, then the remote configuration if the config center is enabled
+    /// / 加载本地配置，启用配置中心时再加载远程配置
+    async fn load(relative_path: Option<&str>, profile: &str, _reload_notifier: Option<&mpsc::Sender<()>>) -> TardisResult<TardisConfig> {
+        let config = TardisConfig::do_init(relative_path, profile, None, None).await?;
 
         #[cfg(feature = "conf-remote")]
         let config = if let Some(conf_center) = &config.fw.conf_center {
@@ -51,34 +110,26 @@ impl TardisConfig {
                     "",
                 ));
             }
-            TardisConfig::do_init(relative_path, &profile, Some((conf_center, &config.fw.app.id))).await?
+            TardisConfig::do_init(relative_path, profile, Some((conf_center, &config.fw.app.id)), _reload_notifier).await?
         } else {
             config
         };
-
-        info!(
-            "[Tardis.Config] Initialized, base path:{:?}, relative path:{:?}, profile:{}",
-            parent_path, relative_path, profile
-        );
-        debug!("=====[Tardis.Config] Content=====\n{:#?}\n=====", &config.fw);
-
-        if let Some(relative_path) = relative_path {
-            TardisLocale::init(Path::new(relative_path))?;
-        }
         Ok(config)
     }
 
-    async fn do_init(relative_path: Option<&str>, profile: &str, _conf_center: Option<(&ConfCenterConfig, &str)>) -> TardisResult<TardisConfig> {
+    async fn do_init(
+        relative_path: Option<&str>,
+        profile: &str,
+        _conf_center: Option<(&ConfCenterConfig, &str)>,
+        _reload_notifier: Option<&mpsc::Sender<()>>,
+    ) -> TardisResult<TardisConfig> {
         let mut conf = ConfigBuilder::<AsyncState>::default();
 
         // Fetch from local file
         if relative_path.is_some() {
             let path = Path::new(relative_path.unwrap_or(""));
-            let file = path.join("conf-default");
-            debug!("[Tardis.Config] Fetch local file: {:?}", file);
-            conf = conf.add_source(File::from(file).required(true));
-            if !profile.is_empty() {
-                let file = path.join(format!("conf-{profile}").as_str());
+            for name in local_config_files(path, profile)? {
+                let file = path.join(name);
                 debug!("[Tardis.Config] Fetch local file: {:?}", file);
                 conf = conf.add_source(File::from(file).required(true));
             }
@@ -103,15 +154,26 @@ impl TardisConfig {
                     "[Tardis.Config] Enabled config center: [{}] {} , start refetching configuration",
                     conf_center.kind, conf_center.url
                 );
-                // listen reload signal
-                let reload_notifier = conf_center.reload_on_remote_config_change(relative_path);
-                let processor: Box<dyn ConfCenterProcess> = match conf_center.kind.to_lowercase().as_str() {
-                    "nacos" => Box::new(crate::config::config_nacos::ConfNacosProcessor::init(conf_center, profile, app_id, &Arc::new(format)).await?),
-                    _ => return Err(TardisError::format_error("[Tardis.Config] The kind of config center only supports [nacos]", "")),
+                let kind = conf_center.kind.to_lowercase();
+                let factory = conf_center_factories().read().map_err(|error| TardisError::internal_error(&format!("[Tardis.Config] {error:?}"), ""))?.get(&kind).cloned();
+                let Some(factory) = factory else {
+                    return Err(TardisError::format_error(
+                        &format!("[Tardis.Config] The kind of config center [{kind}] is not registered, supports [nacos,consul,etcd] and the registered kinds"),
+                        "",
+                    ));
                 };
+                let processor = factory(ConfCenterContext {
+                    config: conf_center.clone(),
+                    profile: profile.to_string(),
+                    app_id: app_id.to_string(),
+                    format,
+                })
+                .await?;
                 conf = processor.register_to_config(conf);
                 // listen update, if update, send reload signal
-                processor.listen_update(&reload_notifier);
+                if let Some(reload_notifier) = _reload_notifier {
+                    processor.listen_update(reload_notifier);
+                }
             }
         }
 
@@ -119,6 +181,8 @@ impl TardisConfig {
         debug!("[Tardis.Config] Fetch env with prefix: TARDIS");
         conf = conf.add_source(Environment::with_prefix("TARDIS"));
         let conf = conf.build().await?;
+        // resolve the secret references, e.g. `${env:DB_PASSWORD}`
+        let conf = config_secret::resolve(conf).await?;
 
         let mut workspace_config: HashMap<String, Value> = Default::default();
         match conf.get::<Value>("cs") {
@@ -182,46 +246,163 @@ impl TardisConfig {
     }
 }
 
+/// The local configuration files (without extension) in loading order, the included files are placed before the including file
+/// / 按加载顺序排列的本地配置文件（不含扩展名），被包含的文件排在包含它的文件之前
+pub(crate) fn local_config_files(path: &Path, profile: &str) -> TardisResult<Vec<String>> {
+    let mut files = Vec::new();
+    collect_local_config_files(path, "conf-default", &mut Vec::new(), &mut files)?;
+    if !profile.is_empty() {
+        collect_local_config_files(path, &format!("conf-{profile}"), &mut Vec::new(), &mut files)?;
+    }
+    Ok(files)
+}
+
+/// `chain` is the include path from the root file, used for cycle detection and error messages
+/// / `chain` 为从根文件开始的包含路径，用于循环检测及错误信息
+fn collect_local_config_files(path: &Path, name: &str, chain: &mut Vec<String>, files: &mut Vec<String>) -> TardisResult<()> {
+    if chain.iter().any(|included| included == name) {
+        return Err(TardisError::format_error(
+            &format!("[Tardis.Config] Circular include of local files: {} -> {name}", chain.join(" -> ")),
+            "406-tardis-config-include-circular",
+        ));
+    }
+    let include = match Config::builder().add_source(File::from(path.join(name)).required(true)).build() {
+        Ok(conf) => match conf.get::<Vec<String>>("include") {
+            Ok(include) => include,
+            Err(ConfigError::NotFound(_)) => Vec::new(),
+            Err(error) => return Err(include_error(chain, name, error)),
+        },
+        // keep the original error of the root files
+        Err(error) if chain.is_empty() => return Err(error.into()),
+        Err(error) => return Err(include_error(chain, name, error)),
+    };
+    chain.push(name.to_string());
+    for included in include {
+        collect_local_config_files(path, included.trim(), chain, files)?;
+    }
+    chain.pop();
+    files.push(name.to_string());
+    Ok(())
+}
+
+fn include_error(chain: &[String], name: &str, error: ConfigError) -> TardisError {
+    TardisError::format_error(
+        &format!("[Tardis.Config] Include local file error: {} -> {name}: {error}", chain.join(" -> ")),
+        "406-tardis-config-include-invalid",
+    )
+}
+
+/// Config center processor / 配置中心处理器
+///
+/// Implement it and register it by [`TardisConfig::register_conf_center`] to use a custom config center.
+///
+/// 实现该trait并通过 [`TardisConfig::register_conf_center`] 注册以使用自定义的配置中心.
 #[cfg(feature = "conf-remote")]
 // temporarily don't need async_trait
 // #[async_trait]
-pub(crate) trait ConfCenterProcess: Sync + Send + std::fmt::Debug {
-    /// listen the config-center processor change
-    fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>);
+pub trait ConfCenterProcess: Sync + Send + std::fmt::Debug {
+    /// listen the config-center processor change, send to `reload_notifier` when the remote config changed
+    fn listen_update(&self, reload_notifier: &mpsc::Sender<()>);
     /// Add all sources to config
     fn register_to_config(&self, conf: ConfigBuilder<AsyncState>) -> ConfigBuilder<AsyncState>;
 }
 
+/// Context for creating a [`ConfCenterProcess`] / 创建 [`ConfCenterProcess`] 的上下文
+#[cfg(feature = "conf-remote")]
+#[derive(Debug, Clone)]
+pub struct ConfCenterContext {
+    pub config: ConfCenterConfig,
+    pub profile: String,
+    pub app_id: String,
+    /// format of the remote config / 远程配置的格式
+    pub format: FileFormat,
+}
+
+#[cfg(feature = "conf-remote")]
+type ConfCenterProcessFuture = Pin<Box<dyn Future<Output = TardisResult<Box<dyn ConfCenterProcess>>> + Send>>;
+
+#[cfg(feature = "conf-remote")]
+type ConfCenterProcessFactory = Arc<dyn Fn(ConfCenterContext) -> ConfCenterProcessFuture + Send + Sync>;
+
+#[cfg(feature = "conf-remote")]
+fn boxed_conf_center_factory<F, Fut>(factory: F) -> ConfCenterProcessFactory
+where
+    F: Fn(ConfCenterContext) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TardisResult<Box<dyn ConfCenterProcess>>> + Send + 'static,
+{
+    Arc::new(move |ctx| -> ConfCenterProcessFuture { Box::pin(factory(ctx)) })
+}
+
+#[cfg(feature = "conf-remote")]
+tardis_static! {
+    conf_center_factories: RwLock<HashMap<String, ConfCenterProcessFactory>> = RwLock::new(builtin_conf_center_factories());
+}
+
+#[cfg(feature = "conf-remote")]
+fn builtin_conf_center_factories() -> HashMap<String, ConfCenterProcessFactory> {
+    let mut factories: HashMap<String, ConfCenterProcessFactory> = HashMap::new();
+    factories.insert(
+        "nacos".to_string(),
+        boxed_conf_center_factory(|ctx| async move {
+            let processor = crate::config::config_nacos::ConfNacosProcessor::init(&ctx.config, &ctx.profile, &ctx.app_id, &Arc::new(ctx.format)).await?;
+            Ok(Box::new(processor) as Box<dyn ConfCenterProcess>)
+        }),
+    );
+    factories.insert(
+        "consul".to_string(),
+        boxed_conf_center_factory(|ctx| async move {
+            let processor = crate::config::config_consul::ConfConsulProcessor::init(&ctx.config, &ctx.profile, &ctx.app_id, &Arc::new(ctx.format)).await?;
+            Ok(Box::new(processor) as Box<dyn ConfCenterProcess>)
+        }),
+    );
+    factories.insert(
+        "etcd".to_string(),
+        boxed_conf_center_factory(|ctx| async move {
+            let processor = crate::config::config_etcd::ConfEtcdProcessor::init(&ctx.config, &ctx.profile, &ctx.app_id, &Arc::new(ctx.format)).await?;
+            Ok(Box::new(processor) as Box<dyn ConfCenterProcess>)
+        }),
+    );
+    factories
+}
+
+#[cfg(feature = "conf-remote")]
+impl TardisConfig {
+    /// Register a config center, selected by `fw.conf_center.kind` (case insensitive) / 注册配置中心，由 `fw.conf_center.kind` （不区分大小写）选择
+    ///
+    /// The built-in `nacos` `consul` `etcd` can be replaced too. It should be called before [`TardisFuns::init`](crate::TardisFuns::init).
+    ///
+    /// 内置的 `nacos` `consul` `etcd` 也可被替换. 应在 [`TardisFuns::init`](crate::TardisFuns::init) 之前调用.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::config::config_dto::TardisConfig;
+    /// use tardis::config::config_processor::ConfCenterProcess;
+    /// TardisConfig::register_conf_center("apollo", |ctx| async move {
+    ///     let processor = ApolloProcessor::init(&ctx.config, &ctx.profile, &ctx.app_id, ctx.format).await?;
+    ///     Ok(Box::new(processor) as Box<dyn ConfCenterProcess>)
+    /// });
+    /// ```
+    pub fn register_conf_center<F, Fut>(kind: &str, factory: F)
+    where
+        F: Fn(ConfCenterContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<Box<dyn ConfCenterProcess>>> + Send + 'static,
+    {
+        if let Ok(mut factories) = conf_center_factories().write() {
+            factories.insert(kind.to_lowercase(), boxed_conf_center_factory(factory));
+        }
+    }
+}
+
 #[cfg(feature = "conf-remote")]
 impl ConfCenterConfig {
     /// Reload configuration on remote configuration change / 远程配置变更时重新加载配置
+    ///
+    /// The configuration is reloaded and hot reloaded each time the returned notifier is signaled.
+    ///
+    /// 每次返回的通知器收到信号时重新加载配置并热重载.
     #[must_use]
-    pub fn reload_on_remote_config_change(&self, relative_path: Option<&str>) -> tokio::sync::mpsc::Sender<()> {
-        let (tx, mut rx) = tokio::sync::mpsc::channel::<()>(1);
-        let relative_path = relative_path.map(str::to_string);
-        tokio::spawn(async move {
-            match rx.recv().await {
-                Some(_) => {}
-                None => {
-                    tracing::debug!("[Tardis.config] Configuration update channel closed");
-                    return;
-                }
-            };
-            if let Ok(config) = TardisConfig::init(relative_path.as_deref()).await {
-                match crate::TardisFuns::hot_reload(config).await {
-                    Ok(_) => {
-                        tracing::info!("[Tardis.config] Tardis hot reloaded");
-                    }
-                    Err(e) => {
-                        tracing::error!("[Tardis.config] Tardis shutdown with error {}", e);
-                    }
-                }
-            } else {
-                tracing::error!("[Tardis.config] Configuration update failed: Failed to load configuration");
-            }
-            tracing::debug!("[Tardis.config] Configuration update listener closed")
-        });
-        tx
+    pub fn reload_on_remote_config_change(&self, relative_path: Option<&str>) -> mpsc::Sender<()> {
+        config_watcher::start(relative_path)
     }
 }
 
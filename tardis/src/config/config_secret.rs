@@ -0,0 +1,133 @@
+//! Secret references in configuration values / 配置值中的密钥引用
+//!
+//! The string values can reference the secrets stored outside the configuration, the references are resolved while loading the configuration:
+//!
+//! 字符串值可引用存储在配置之外的密钥，引用在加载配置时被解析:
+//!
+//! * `${env:NAME}` the environment variable / 环境变量
+//! * `${file:/run/secrets/db}` the file content, the trailing newline is trimmed / 文件内容，去除末尾换行
+//! * `${vault:secret/data/db#password}` the field of the HashiCorp Vault secret, the address and the token are taken from the `VAULT_ADDR` `VAULT_TOKEN` (and optional `VAULT_NAMESPACE`) environment variables,
+//!   both KV v1 and v2 are supported. ``Requires [web-client] feature``
+//!   / HashiCorp Vault密钥的字段，地址及token取自 `VAULT_ADDR` `VAULT_TOKEN` （及可选的 `VAULT_NAMESPACE` ）环境变量，支持KV v1及v2
+//!
+//! A reference can be the whole value or a part of it, e.g. `postgres://app:${file:/run/secrets/db}@db:5432/app`.
+//!
+//! 引用可以是整个值或其一部分，如 `postgres://app:${file:/run/secrets/db}@db:5432/app` .
+use std::collections::HashMap;
+use std::env;
+
+use config::Config;
+use regex::Regex;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::tardis_static;
+
+tardis_static! {
+    secret_ref_regex: Regex = Regex::new(r"\$\{(env|file|vault):([^}]+)\}").expect("[Tardis.Config] Invalid secret reference regex");
+}
+
+/// Resolve the secret references of the configuration / 解析配置中的密钥引用
+pub(crate) async fn resolve(conf: Config) -> TardisResult<Config> {
+    let value = conf.clone().try_deserialize::<Value>()?;
+    let mut refs = Vec::new();
+    collect_refs(&value, String::new(), &mut refs);
+    if refs.is_empty() {
+        return Ok(conf);
+    }
+    let mut resolver = SecretResolver::default();
+    let mut builder = Config::builder().add_source(conf);
+    for (path, text) in refs {
+        debug!("[Tardis.Config] Resolve secret references of {}", path);
+        builder = builder.set_override(path, resolver.resolve_str(&text).await?)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// The paths and the strings containing secret references / 包含密钥引用的路径及字符串
+fn collect_refs(value: &Value, path: String, refs: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(text) if secret_ref_regex().is_match(text) => refs.push((path, text.clone())),
+        Value::Array(items) => items.iter().enumerate().for_each(|(idx, item)| collect_refs(item, format!("{path}[{idx}]"), refs)),
+        Value::Object(fields) => fields.iter().for_each(|(key, field)| collect_refs(field, if path.is_empty() { key.clone() } else { format!("{path}.{key}") }, refs)),
+        _ => {}
+    }
+}
+
+#[derive(Default)]
+struct SecretResolver {
+    /// vault secrets by path, each path is fetched once / 按路径缓存的vault密钥，每个路径只获取一次
+    #[allow(dead_code)]
+    vault_secrets: HashMap<String, Value>,
+}
+
+impl SecretResolver {
+    async fn resolve_str(&mut self, text: &str) -> TardisResult<String> {
+        let mut resolved = String::with_capacity(text.len());
+        let mut last = 0;
+        for captures in secret_ref_regex().captures_iter(text) {
+            let (Some(reference), Some(kind), Some(key)) = (captures.get(0), captures.get(1), captures.get(2)) else {
+                continue;
+            };
+            resolved.push_str(&text[last..reference.start()]);
+            let key = key.as_str().trim();
+            let secret = match kind.as_str() {
+                "env" => env::var(key).map_err(|_| not_found(&format!("environment variable [{key}] doesn't exist")))?,
+                "file" => std::fs::read_to_string(key).map_err(|error| not_found(&format!("file [{key}] read error: {error}")))?.trim_end_matches(['\r', '\n']).to_string(),
+                _ => self.resolve_vault(key).await?,
+            };
+            resolved.push_str(&secret);
+            last = reference.end();
+        }
+        resolved.push_str(&text[last..]);
+        Ok(resolved)
+    }
+
+    #[cfg(feature = "web-client")]
+    async fn resolve_vault(&mut self, key: &str) -> TardisResult<String> {
+        let Some((path, field)) = key.split_once('#') else {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.Config] Vault secret reference [{key}] must be in the form of <path>#<field>"),
+                "406-tardis-config-secret-invalid",
+            ));
+        };
+        if !self.vault_secrets.contains_key(path) {
+            let addr = env::var("VAULT_ADDR").map_err(|_| not_found("environment variable [VAULT_ADDR] is required to resolve the vault secrets"))?;
+            let mut request = reqwest::Client::new().get(format!("{}/v1/{}", addr.trim_end_matches('/'), path.trim_start_matches('/')));
+            if let Ok(token) = env::var("VAULT_TOKEN") {
+                request = request.header("X-Vault-Token", token);
+            }
+            if let Ok(namespace) = env::var("VAULT_NAMESPACE") {
+                request = request.header("X-Vault-Namespace", namespace);
+            }
+            let resp = request.send().await?;
+            if resp.status() == reqwest::StatusCode::NOT_FOUND {
+                return Err(not_found(&format!("vault secret [{path}] doesn't exist")));
+            }
+            let secret = resp.error_for_status()?.json::<Value>().await?;
+            self.vault_secrets.insert(path.to_string(), secret);
+        }
+        let secret = self.vault_secrets.get(path).and_then(|secret| secret.get("data"));
+        // kv v2 nests the fields in `data.data`, kv v1 in `data`
+        let value = secret.and_then(|data| data.get("data")).and_then(|data| data.get(field)).or_else(|| secret.and_then(|data| data.get(field)));
+        match value {
+            Some(Value::String(value)) => Ok(value.clone()),
+            Some(value) => Ok(value.to_string()),
+            None => Err(not_found(&format!("field [{field}] of vault secret [{path}] doesn't exist"))),
+        }
+    }
+
+    #[cfg(not(feature = "web-client"))]
+    async fn resolve_vault(&mut self, _key: &str) -> TardisResult<String> {
+        Err(TardisError::format_error(
+            "[Tardis.Config] Vault secret references must depend on the web-client feature",
+            "406-tardis-config-secret-invalid",
+        ))
+    }
+}
+
+fn not_found(msg: &str) -> TardisError {
+    TardisError::not_found(&format!("[Tardis.Config] Secret reference resolve error: {msg}"), "404-tardis-config-secret-not-found")
+}
@@ -0,0 +1,148 @@
+//! Configuration watcher / 配置监听
+//!
+//! Reloads the configuration when the remote (config center) or the local configuration files change,
+//! the changed components are re-initialized in place by [`TardisFuns::hot_reload`] and a [`TardisConfigChanged`] event is broadcast.
+//!
+//! 当远程（配置中心）或本地配置文件变更时重新加载配置，变更的组件由 [`TardisFuns::hot_reload`] 原地重新初始化并广播 [`TardisConfigChanged`] 事件.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::TardisFuns;
+//! let mut changes = TardisFuns::subscribe_config_changed();
+//! tokio::spawn(async move {
+//!     while let Ok(changed) = changes.recv().await {
+//!         if changed.custom.iter().any(|code| code == "todo") {
+//!             println!("todo config changed: {:?}", TardisFuns::cs_config::<TodoConfig>("todo"));
+//!         }
+//!     }
+//! });
+//! ```
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::config::config_dto::TardisConfig;
+use crate::config::config_processor;
+use crate::tardis_static;
+use crate::TardisFuns;
+
+/// Event of the configuration change / 配置变更事件
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TardisConfigChanged {
+    /// The re-initialized components, e.g. `db` `cache` `web_server` / 被重新初始化的组件，如 `db` `cache` `web_server`
+    pub components: Vec<String>,
+    /// The codes of the changed custom configurations / 变更的自定义配置的code
+    pub custom: Vec<String>,
+}
+
+impl TardisConfigChanged {
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.custom.is_empty()
+    }
+}
+
+tardis_static! {
+    config_changed_sender: broadcast::Sender<TardisConfigChanged> = broadcast::channel(64).0;
+    watch_tasks: Mutex<Vec<JoinHandle<()>>>;
+}
+
+pub(crate) fn subscribe() -> broadcast::Receiver<TardisConfigChanged> {
+    config_changed_sender().subscribe()
+}
+
+pub(crate) fn publish(changed: TardisConfigChanged) {
+    debug!("[Tardis.Config] Configuration changed: {:?}", changed);
+    // no subscriber is not an error
+    let _ = config_changed_sender().send(changed);
+}
+
+/// Track a watch task, it is aborted by [`stop`] / 记录监听任务，由 [`stop`] 终止
+pub(crate) fn add_task(task: JoinHandle<()>) {
+    if let Ok(mut tasks) = watch_tasks().lock() {
+        tasks.push(task);
+    }
+}
+
+/// Stop watching / 停止监听
+pub(crate) fn stop() {
+    if let Ok(mut tasks) = watch_tasks().lock() {
+        tasks.drain(..).for_each(|task| task.abort());
+    }
+}
+
+/// Start the reload loop, the configuration is reloaded when the returned notifier is signaled,
+/// the loop stops when all the notifiers are dropped
+/// / 启动重新加载循环，返回的通知器收到信号时重新加载配置，所有通知器被drop后循环停止
+pub(crate) fn start(relative_path: Option<&str>) -> mpsc::Sender<()> {
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    let relative_path = relative_path.map(str::to_string);
+    add_task(tokio::spawn(async move {
+        while rx.recv().await.is_some() {
+            // merge the notifications received while reloading
+            while rx.try_recv().is_ok() {}
+            info!("[Tardis.Config] Configuration changed, reloading");
+            let config = match TardisConfig::reload(relative_path.as_deref()).await {
+                Ok(config) => config,
+                Err(error) => {
+                    error!("[Tardis.Config] Configuration reload error: {error}");
+                    continue;
+                }
+            };
+            match TardisFuns::hot_reload(config).await {
+                Ok(()) => info!("[Tardis.Config] Tardis hot reloaded"),
+                Err(error) => error!("[Tardis.Config] Tardis hot reload error: {error}"),
+            }
+        }
+        debug!("[Tardis.Config] Configuration reload loop closed");
+    }));
+    tx
+}
+
+/// Poll the modified time of the local configuration files / 轮询本地配置文件的修改时间
+pub(crate) fn watch_local_files(relative_path: &str, profile: &str, interval: Duration, notifier: mpsc::Sender<()>) {
+    let dir = PathBuf::from(relative_path);
+    let profile = profile.to_string();
+    info!("[Tardis.Config] Watching local configuration files in {:?}, interval:{:?}", dir, interval);
+    add_task(tokio::spawn(async move {
+        let mut stems = vec![];
+        let mut last = modified_times(&dir, &profile, &mut stems);
+        loop {
+            tokio::time::sleep(interval).await;
+            let current = modified_times(&dir, &profile, &mut stems);
+            if current != last {
+                last = current;
+                debug!("[Tardis.Config] Local configuration files changed");
+                if notifier.send(()).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }));
+}
+
+/// The files (with any extension) and their modified time, the included files are watched too
+/// / 文件（任意扩展名）及其修改时间，被包含的文件也被监听
+fn modified_times(dir: &Path, profile: &str, stems: &mut Vec<String>) -> Vec<(PathBuf, Option<SystemTime>)> {
+    // keep the previous files if the includes are invalid while editing
+    if let Ok(files) = config_processor::local_config_files(dir, profile) {
+        *stems = files;
+    }
+    let mut files = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stems.iter().any(|s| s == stem)).unwrap_or(false))
+                .map(|path| {
+                    let modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+                    (path, modified)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
@@ -6,9 +6,13 @@ pub mod crypto_base64;
 pub mod crypto_digest;
 #[cfg(feature = "crypto")]
 pub mod crypto_hex;
+#[cfg(feature = "crypto-jwt")]
+pub mod crypto_jwt;
 #[cfg(feature = "crypto")]
 pub mod crypto_key;
 #[cfg(feature = "crypto")]
+pub mod crypto_keyring;
+#[cfg(feature = "crypto")]
 pub mod crypto_main;
 #[cfg(feature = "rsa")]
 pub mod crypto_rsa;
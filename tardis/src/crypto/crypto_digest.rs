@@ -20,6 +20,7 @@ pub mod algorithm {
     pub type HmacSha1 = Hmac<Sha1>;
     pub type HmacSha256 = Hmac<Sha256>;
     pub type HmacSha512 = Hmac<Sha512>;
+    pub type HmacSm3 = Hmac<Sm3>;
 }
 
 pub mod output {
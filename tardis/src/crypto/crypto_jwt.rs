@@ -0,0 +1,405 @@
+//! JWT / JWS / JWE / JWT、JWS及JWE
+//!
+//! The JWTs are signed by `HS256` , `HS384` , `HS512` , `RS256` or `ES256` , and optionally encrypted as the nested JWE by `dir` + `A256GCM` .
+//! The keys of the other algorithms can be imported from the JWKs to verify only.
+//!
+//! JWT以 `HS256` 、 `HS384` 、 `HS512` 、 `RS256` 或 `ES256` 签名，并可选地以 `dir` + `A256GCM` 加密为嵌套的JWE. 其他算法的密钥可从JWK导入，仅用于验证.
+//!
+//! # Examples
+//! ```ignore
+//! let key = TardisJwtKey::rs256(&private_key_pem)?.kid("2023-10");
+//! let token = TardisFuns::crypto.jwt.sign(&json!({ "sub": "u1", "exp": TardisFuns::crypto.jwt.expire_at(3600) }), &key)?;
+//! let claims: Value = TardisFuns::crypto.jwt.verify(&token, &[key.clone()], &TardisJwtValidation::builder().issuer("tardis").build())?;
+//! // published at /.well-known/jwks.json
+//! let jwks = TardisFuns::crypto.jwt.jwks(&[key]);
+//! ```
+use aes_gcm::Aes256Gcm;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::traits::PublicKeyParts;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use typed_builder::TypedBuilder;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+const JWE_ALG: &str = "dir";
+const JWE_ENC: &str = "A256GCM";
+const JWE_KEY_SIZE: usize = 32;
+const JWE_TAG_SIZE: usize = 16;
+/// The DER prefix of the uncompressed P-256 public key / 未压缩P-256公钥的DER前缀
+const EC_POINT_PREFIX: [u8; 4] = [0x03, 0x42, 0x00, 0x04];
+
+/// Signing algorithm / 签名算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TardisJwtAlgorithm {
+    HS256,
+    HS384,
+    HS512,
+    RS256,
+    RS384,
+    RS512,
+    PS256,
+    PS384,
+    PS512,
+    ES256,
+    ES384,
+    EdDSA,
+}
+
+impl TardisJwtAlgorithm {
+    fn algorithm(&self) -> Algorithm {
+        match self {
+            TardisJwtAlgorithm::HS256 => Algorithm::HS256,
+            TardisJwtAlgorithm::HS384 => Algorithm::HS384,
+            TardisJwtAlgorithm::HS512 => Algorithm::HS512,
+            TardisJwtAlgorithm::RS256 => Algorithm::RS256,
+            TardisJwtAlgorithm::RS384 => Algorithm::RS384,
+            TardisJwtAlgorithm::RS512 => Algorithm::RS512,
+            TardisJwtAlgorithm::PS256 => Algorithm::PS256,
+            TardisJwtAlgorithm::PS384 => Algorithm::PS384,
+            TardisJwtAlgorithm::PS512 => Algorithm::PS512,
+            TardisJwtAlgorithm::ES256 => Algorithm::ES256,
+            TardisJwtAlgorithm::ES384 => Algorithm::ES384,
+            TardisJwtAlgorithm::EdDSA => Algorithm::EdDSA,
+        }
+    }
+
+    fn from_algorithm(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::HS256 => TardisJwtAlgorithm::HS256,
+            Algorithm::HS384 => TardisJwtAlgorithm::HS384,
+            Algorithm::HS512 => TardisJwtAlgorithm::HS512,
+            Algorithm::RS256 => TardisJwtAlgorithm::RS256,
+            Algorithm::RS384 => TardisJwtAlgorithm::RS384,
+            Algorithm::RS512 => TardisJwtAlgorithm::RS512,
+            Algorithm::PS256 => TardisJwtAlgorithm::PS256,
+            Algorithm::PS384 => TardisJwtAlgorithm::PS384,
+            Algorithm::PS512 => TardisJwtAlgorithm::PS512,
+            Algorithm::ES256 => TardisJwtAlgorithm::ES256,
+            Algorithm::ES384 => TardisJwtAlgorithm::ES384,
+            Algorithm::EdDSA => TardisJwtAlgorithm::EdDSA,
+        }
+    }
+}
+
+/// Signing and verification key / 签名及验证密钥
+///
+/// The keys constructed by the public keys can only verify.
+///
+/// 由公钥构造的密钥只能验证.
+#[derive(Clone)]
+pub struct TardisJwtKey {
+    kid: Option<String>,
+    alg: TardisJwtAlgorithm,
+    encoding_key: Option<EncodingKey>,
+    decoding_key: DecodingKey,
+    /// The public parameters of the JWK, `None` for the HMAC keys / JWK的公开参数，HMAC密钥为 `None`
+    jwk: Option<Value>,
+}
+
+impl std::fmt::Debug for TardisJwtKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TardisJwtKey").field("kid", &self.kid).field("alg", &self.alg).field("can_sign", &self.encoding_key.is_some()).finish()
+    }
+}
+
+impl TardisJwtKey {
+    pub fn hs256(secret: impl AsRef<[u8]>) -> Self {
+        Self::hmac(TardisJwtAlgorithm::HS256, secret.as_ref())
+    }
+
+    pub fn hs384(secret: impl AsRef<[u8]>) -> Self {
+        Self::hmac(TardisJwtAlgorithm::HS384, secret.as_ref())
+    }
+
+    pub fn hs512(secret: impl AsRef<[u8]>) -> Self {
+        Self::hmac(TardisJwtAlgorithm::HS512, secret.as_ref())
+    }
+
+    fn hmac(alg: TardisJwtAlgorithm, secret: &[u8]) -> Self {
+        TardisJwtKey {
+            kid: None,
+            alg,
+            encoding_key: Some(EncodingKey::from_secret(secret)),
+            decoding_key: DecodingKey::from_secret(secret),
+            jwk: None,
+        }
+    }
+
+    /// From the JWK, e.g. of the JWKS of the identity provider, to verify only
+    /// / 来自JWK（如身份提供方的JWKS），仅用于验证
+    ///
+    /// The algorithm is inferred by the key type if the `alg` is absent, e.g. `RS256` for the RSA keys.
+    ///
+    /// 未声明 `alg` 时根据密钥类型推断算法，如RSA密钥为 `RS256` .
+    pub fn from_jwk(jwk: &Jwk) -> TardisResult<Self> {
+        let alg = match &jwk.common.key_algorithm {
+            Some(alg) => format!("{alg:?}").parse::<Algorithm>().map_err(|_| key_invalid(&format!("the algorithm {alg:?} isn't a signing algorithm")))?,
+            None => match &jwk.algorithm {
+                AlgorithmParameters::RSA(_) => Algorithm::RS256,
+                AlgorithmParameters::EllipticCurve(params) if params.curve == EllipticCurve::P256 => Algorithm::ES256,
+                AlgorithmParameters::EllipticCurve(params) if params.curve == EllipticCurve::P384 => Algorithm::ES384,
+                AlgorithmParameters::EllipticCurve(_) => return Err(key_invalid("the curve isn't supported")),
+                AlgorithmParameters::OctetKey(_) => Algorithm::HS256,
+                AlgorithmParameters::OctetKeyPair(_) => Algorithm::EdDSA,
+            },
+        };
+        Ok(TardisJwtKey {
+            kid: jwk.common.key_id.clone(),
+            alg: TardisJwtAlgorithm::from_algorithm(alg),
+            encoding_key: None,
+            decoding_key: DecodingKey::from_jwk(jwk).map_err(|error| key_invalid(&error.to_string()))?,
+            // the symmetric keys are never published
+            jwk: match &jwk.algorithm {
+                AlgorithmParameters::OctetKey(_) => None,
+                _ => serde_json::to_value(&jwk.algorithm).ok(),
+            },
+        })
+    }
+
+    /// From the PKCS#8 or PKCS#1 PEM of the RSA private key / 来自RSA私钥的PKCS#8或PKCS#1 PEM
+    pub fn rs256(private_key_pem: &str) -> TardisResult<Self> {
+        let private_key = rsa::RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+            .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(private_key_pem))
+            .map_err(|error| key_invalid(&error.to_string()))?;
+        let mut key = Self::rs256_components(private_key.n().to_bytes_be(), private_key.e().to_bytes_be())?;
+        key.encoding_key = Some(EncodingKey::from_rsa_pem(private_key_pem.as_bytes()).map_err(|error| key_invalid(&error.to_string()))?);
+        Ok(key)
+    }
+
+    /// From the PEM of the RSA public key, to verify only / 来自RSA公钥的PEM，仅用于验证
+    pub fn rs256_public(public_key_pem: &str) -> TardisResult<Self> {
+        let public_key = rsa::RsaPublicKey::from_public_key_pem(public_key_pem).map_err(|error| key_invalid(&error.to_string()))?;
+        Self::rs256_components(public_key.n().to_bytes_be(), public_key.e().to_bytes_be())
+    }
+
+    fn rs256_components(n: Vec<u8>, e: Vec<u8>) -> TardisResult<Self> {
+        let (n, e) = (URL_SAFE_NO_PAD.encode(n), URL_SAFE_NO_PAD.encode(e));
+        Ok(TardisJwtKey {
+            kid: None,
+            alg: TardisJwtAlgorithm::RS256,
+            encoding_key: None,
+            decoding_key: DecodingKey::from_rsa_components(&n, &e).map_err(|error| key_invalid(&error.to_string()))?,
+            jwk: Some(json!({ "kty": "RSA", "n": n, "e": e })),
+        })
+    }
+
+    /// From the PKCS#8 PEM of the P-256 private key, which contains the public key, e.g. generated by OpenSSL
+    /// / 来自包含公钥的P-256私钥的PKCS#8 PEM，如由OpenSSL生成
+    pub fn es256(private_key_pem: &str) -> TardisResult<Self> {
+        let mut key = Self::es256_point(&pem_to_der(private_key_pem)?)?;
+        key.encoding_key = Some(EncodingKey::from_ec_pem(private_key_pem.as_bytes()).map_err(|error| key_invalid(&error.to_string()))?);
+        Ok(key)
+    }
+
+    /// From the PEM of the P-256 public key, to verify only / 来自P-256公钥的PEM，仅用于验证
+    pub fn es256_public(public_key_pem: &str) -> TardisResult<Self> {
+        Self::es256_point(&pem_to_der(public_key_pem)?)
+    }
+
+    /// The uncompressed point is the trailing bit string of both the PKCS#8 private key and the SPKI public key
+    /// / 未压缩的点是PKCS#8私钥及SPKI公钥末尾的位串
+    fn es256_point(der: &[u8]) -> TardisResult<Self> {
+        let point_start = der.len().checked_sub(64 + EC_POINT_PREFIX.len()).filter(|start| der[*start..*start + EC_POINT_PREFIX.len()] == EC_POINT_PREFIX);
+        let Some(point_start) = point_start.map(|start| start + EC_POINT_PREFIX.len()) else {
+            return Err(key_invalid("the P-256 public key is missing"));
+        };
+        let x = URL_SAFE_NO_PAD.encode(&der[point_start..point_start + 32]);
+        let y = URL_SAFE_NO_PAD.encode(&der[point_start + 32..]);
+        Ok(TardisJwtKey {
+            kid: None,
+            alg: TardisJwtAlgorithm::ES256,
+            encoding_key: None,
+            decoding_key: DecodingKey::from_ec_components(&x, &y).map_err(|error| key_invalid(&error.to_string()))?,
+            jwk: Some(json!({ "kty": "EC", "crv": "P-256", "x": x, "y": y })),
+        })
+    }
+
+    /// Set the key id, which is the `kid` of the header and the JWK / 设置密钥id，即头部及JWK的 `kid`
+    pub fn kid(mut self, kid: impl Into<String>) -> Self {
+        self.kid = Some(kid.into());
+        self
+    }
+
+    pub fn key_id(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    pub fn algorithm(&self) -> TardisJwtAlgorithm {
+        self.alg
+    }
+
+    /// The public JWK, `None` for the HMAC keys / 公开的JWK，HMAC密钥为 `None`
+    pub fn to_jwk(&self) -> Option<Value> {
+        let mut jwk = self.jwk.clone()?;
+        jwk["use"] = json!("sig");
+        jwk["alg"] = json!(format!("{:?}", self.alg));
+        if let Some(kid) = &self.kid {
+            jwk["kid"] = json!(kid);
+        }
+        Some(jwk)
+    }
+}
+
+/// Validation of the JWT / JWT的校验
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct TardisJwtValidation {
+    /// Allowed clock skew of the `exp` and `nbf` , default by 60 seconds / `exp` 及 `nbf` 允许的时钟偏差，默认为60秒
+    #[builder(default = 60)]
+    pub leeway_sec: u64,
+    /// Expected `iss` / 期望的 `iss`
+    #[builder(default, setter(into, strip_option))]
+    pub issuer: Option<String>,
+    /// Accepted `aud` , not validated if empty / 接受的 `aud` ，为空时不校验
+    #[builder(default)]
+    pub audience: Vec<String>,
+    /// The claims must be present, default by `exp` / 必须存在的声明，默认为 `exp`
+    #[builder(default = vec!["exp".to_string()])]
+    pub required_claims: Vec<String>,
+    /// Whether to validate the `nbf` , default by true / 是否校验 `nbf` ，默认为true
+    #[builder(default = true)]
+    pub validate_nbf: bool,
+}
+
+impl Default for TardisJwtValidation {
+    fn default() -> Self {
+        TardisJwtValidation::builder().build()
+    }
+}
+
+/// JWT handle / JWT处理
+pub struct TardisCryptoJwt;
+
+impl TardisCryptoJwt {
+    /// The `exp` the seconds later / 若干秒后的 `exp`
+    pub fn expire_at(&self, expire_sec: i64) -> i64 {
+        jsonwebtoken::get_current_timestamp() as i64 + expire_sec
+    }
+
+    pub fn sign<T: Serialize>(&self, claims: &T, key: &TardisJwtKey) -> TardisResult<String> {
+        let Some(encoding_key) = &key.encoding_key else {
+            return Err(key_invalid("the public key can't sign"));
+        };
+        let mut header = Header::new(key.alg.algorithm());
+        header.kid = key.kid.clone();
+        jsonwebtoken::encode(&header, claims, encoding_key)
+            .map_err(|error| TardisError::internal_error(&format!("[Tardis.Crypto] Failed to sign the JWT: {error}"), "500-tardis-crypto-jwt-sign-failed"))
+    }
+
+    /// Verify the JWT by the key of the `kid` , or by the keys of the algorithm if the `kid` is absent
+    /// / 通过 `kid` 对应的密钥验证JWT，无 `kid` 时通过该算法的密钥验证
+    ///
+    /// The algorithm of the header must be the algorithm of the key, it's also used by the web authentication.
+    ///
+    /// 头部的算法必须为密钥的算法，Web认证也使用此方法.
+    pub fn verify<T: DeserializeOwned>(&self, token: &str, keys: &[TardisJwtKey], validation: &TardisJwtValidation) -> TardisResult<T> {
+        let header = jsonwebtoken::decode_header(token).map_err(|error| token_invalid(&error.to_string()))?;
+        let mut jwt_validation = Validation::new(header.alg);
+        jwt_validation.leeway = validation.leeway_sec;
+        jwt_validation.validate_nbf = validation.validate_nbf;
+        jwt_validation.set_required_spec_claims(&validation.required_claims);
+        if let Some(issuer) = &validation.issuer {
+            jwt_validation.set_issuer(&[issuer]);
+        }
+        if validation.audience.is_empty() {
+            jwt_validation.validate_aud = false;
+        } else {
+            jwt_validation.set_audience(&validation.audience);
+        }
+        let mut last_error = token_invalid(&format!("no key of the algorithm {:?} and the key id {:?}", header.alg, header.kid));
+        for key in keys.iter().filter(|key| key.alg.algorithm() == header.alg && (header.kid.is_none() || key.kid == header.kid)) {
+            match jsonwebtoken::decode::<T>(token, &key.decoding_key, &jwt_validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(error) => last_error = token_invalid(&error.to_string()),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// The JWKS of the public keys, the HMAC keys are skipped / 公钥的JWKS，跳过HMAC密钥
+    pub fn jwks(&self, keys: &[TardisJwtKey]) -> Value {
+        json!({ "keys": keys.iter().filter_map(TardisJwtKey::to_jwk).collect::<Vec<_>>() })
+    }
+
+    /// Encrypt the payload as the compact JWE by `dir` + `A256GCM` with the 32 bytes key
+    /// / 以32字节的密钥通过 `dir` + `A256GCM` 将负载加密为紧凑格式的JWE
+    pub fn encrypt(&self, payload: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> TardisResult<String> {
+        jwe_encrypt(payload.as_ref(), key.as_ref(), None)
+    }
+
+    pub fn decrypt(&self, jwe: &str, key: impl AsRef<[u8]>) -> TardisResult<Vec<u8>> {
+        jwe_decrypt(jwe, key.as_ref())
+    }
+
+    /// Sign the claims and encrypt the JWT as the nested JWE / 签名声明并将JWT加密为嵌套的JWE
+    pub fn sign_and_encrypt<T: Serialize>(&self, claims: &T, key: &TardisJwtKey, encryption_key: impl AsRef<[u8]>) -> TardisResult<String> {
+        jwe_encrypt(self.sign(claims, key)?.as_bytes(), encryption_key.as_ref(), Some("JWT"))
+    }
+
+    pub fn decrypt_and_verify<T: DeserializeOwned>(&self, jwe: &str, encryption_key: impl AsRef<[u8]>, keys: &[TardisJwtKey], validation: &TardisJwtValidation) -> TardisResult<T> {
+        let token = String::from_utf8(jwe_decrypt(jwe, encryption_key.as_ref())?).map_err(|error| token_invalid(&error.to_string()))?;
+        self.verify(&token, keys, validation)
+    }
+}
+
+fn jwe_encrypt(payload: &[u8], key: &[u8], cty: Option<&str>) -> TardisResult<String> {
+    if key.len() != JWE_KEY_SIZE {
+        return Err(key_invalid(&format!("the encryption key must be {JWE_KEY_SIZE} bytes")));
+    }
+    let mut header = json!({ "alg": JWE_ALG, "enc": JWE_ENC });
+    if let Some(cty) = cty {
+        header["cty"] = json!(cty);
+    }
+    let header = URL_SAFE_NO_PAD.encode(TardisFuns::json.obj_to_string(&header)?);
+    let iv = TardisFuns::crypto.aead.random_nonce::<Aes256Gcm>();
+    let (mut ciphertext, _) = TardisFuns::crypto.aead.encrypt::<Aes256Gcm>(key, header.as_bytes(), &iv, payload)?;
+    let tag = ciphertext.split_off(ciphertext.len() - JWE_TAG_SIZE);
+    // the encrypted key is empty for the direct encryption
+    Ok(format!(
+        "{header}..{}.{}.{}",
+        URL_SAFE_NO_PAD.encode(iv),
+        URL_SAFE_NO_PAD.encode(ciphertext),
+        URL_SAFE_NO_PAD.encode(tag)
+    ))
+}
+
+fn jwe_decrypt(jwe: &str, key: &[u8]) -> TardisResult<Vec<u8>> {
+    if key.len() != JWE_KEY_SIZE {
+        return Err(key_invalid(&format!("the encryption key must be {JWE_KEY_SIZE} bytes")));
+    }
+    let [header, encrypted_key, iv, ciphertext, tag] = jwe.split('.').collect::<Vec<_>>()[..] else {
+        return Err(token_invalid("the JWE must have 5 parts"));
+    };
+    let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|error| token_invalid(&error.to_string()));
+    let header_value: Value = serde_json::from_slice(&decode(header)?).map_err(|error| token_invalid(&error.to_string()))?;
+    if header_value["alg"] != JWE_ALG || header_value["enc"] != JWE_ENC || !encrypted_key.is_empty() {
+        return Err(token_invalid(&format!("only the {JWE_ALG} + {JWE_ENC} JWE is supported")));
+    }
+    let (iv, mut ciphertext) = (decode(iv)?, decode(ciphertext)?);
+    ciphertext.extend(decode(tag)?);
+    if iv.len() != 12 {
+        return Err(token_invalid("the IV must be 12 bytes"));
+    }
+    TardisFuns::crypto.aead.decrypt::<Aes256Gcm>(key, header.as_bytes(), iv, ciphertext).map_err(|_| token_invalid("failed to decrypt the JWE"))
+}
+
+fn pem_to_der(pem: &str) -> TardisResult<Vec<u8>> {
+    let body = pem.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with("-----")).collect::<String>();
+    STANDARD.decode(body).map_err(|error| key_invalid(&error.to_string()))
+}
+
+fn key_invalid(reason: &str) -> TardisError {
+    TardisError::format_error(&format!("[Tardis.Crypto] The JWT key is invalid: {reason}"), "406-tardis-crypto-jwt-key-invalid")
+}
+
+fn token_invalid(reason: &str) -> TardisError {
+    TardisError::custom("401-tardis-crypto-jwt-invalid", &format!("[Tardis.Crypto] The JWT is invalid: {reason}"), "")
+}
@@ -0,0 +1,286 @@
+//! Keyring with key rotation and envelope encryption / 支持密钥轮换及信封加密的密钥环
+//!
+//! Each message is encrypted by a random data key with AES-256-GCM, and the data key is wrapped by the current key of the keyring,
+//! so the rotation only re-wraps the data keys of the stored blobs, and the historical keys are kept to decrypt the blobs not rotated yet.
+//!
+//! 每条消息通过随机的数据密钥以AES-256-GCM加密，数据密钥由密钥环的当前密钥包装，
+//! 因此轮换时只需重新包装已存储数据的数据密钥，保留的历史密钥用于解密尚未轮换的数据.
+//!
+//! # Examples
+//! ```ignore
+//! let mut keyring = TardisCryptoKeyring::from_config(&TardisCryptoKeyringConfig::builder().keys(HashMap::from([(1, key_v1)])).build())?;
+//! let blob = keyring.encrypt_to_base64("secret", "user-1")?;
+//! // rotation
+//! keyring.add_key(2, key_v2)?;
+//! keyring.set_current_version(2)?;
+//! if let Some(rotated) = keyring.re_encrypt_base64(&blob)? {
+//!     // persist the rotated blob
+//! }
+//! assert_eq!(keyring.decrypt_base64_to_string(&blob, "user-1")?, "secret");
+//! ```
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::Path;
+
+use aes_gcm::Aes256Gcm;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+const BLOB_FORMAT: u8 = 1;
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+/// The data key wrapped by AES-256-GCM, with the tag / 以AES-256-GCM包装的数据密钥，含tag
+const WRAPPED_KEY_SIZE: usize = KEY_SIZE + 16;
+const HEADER_SIZE: usize = 1 + 4 + NONCE_SIZE + WRAPPED_KEY_SIZE + NONCE_SIZE;
+
+/// Keyring configuration / 密钥环配置
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, TypedBuilder)]
+#[serde(default)]
+pub struct TardisCryptoKeyringConfig {
+    /// The version of the key to encrypt, the latest version if not set / 用于加密的密钥版本，未设置时为最新版本
+    #[builder(default, setter(strip_option))]
+    pub current_version: Option<u32>,
+    /// The base64 encoded 32 bytes keys by version / 按版本的base64编码的32字节密钥
+    #[builder(default)]
+    pub keys: HashMap<u32, String>,
+}
+
+/// Keyring of the versioned symmetric keys / 版本化对称密钥的密钥环
+///
+/// The blob layout is `format(1) | key version(4) | key nonce(12) | wrapped data key(48) | data nonce(12) | ciphertext` .
+///
+/// 数据格式为 `格式(1) | 密钥版本(4) | 密钥nonce(12) | 包装后的数据密钥(48) | 数据nonce(12) | 密文` .
+#[derive(Clone, Default)]
+pub struct TardisCryptoKeyring {
+    keys: BTreeMap<u32, Vec<u8>>,
+    current_version: u32,
+}
+
+impl std::fmt::Debug for TardisCryptoKeyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TardisCryptoKeyring").field("versions", &self.versions()).field("current_version", &self.current_version).finish()
+    }
+}
+
+impl TardisCryptoKeyring {
+    pub fn from_config(config: &TardisCryptoKeyringConfig) -> TardisResult<Self> {
+        let mut keyring = TardisCryptoKeyring::default();
+        for (version, key) in &config.keys {
+            keyring.add_key(*version, TardisFuns::crypto.base64.decode(key)?)?;
+        }
+        if let Some(current_version) = config.current_version {
+            keyring.set_current_version(current_version)?;
+        }
+        Ok(keyring)
+    }
+
+    /// Load from the JSON file of [`TardisCryptoKeyringConfig`] / 从 [`TardisCryptoKeyringConfig`] 的JSON文件加载
+    pub fn from_file(path: impl AsRef<Path>) -> TardisResult<Self> {
+        let config = std::fs::read_to_string(path)?;
+        Self::from_config(&TardisFuns::json.str_to_obj(&config)?)
+    }
+
+    /// Load the keys by the callback, e.g. fetched from the KMS, the latest version is the current one
+    /// / 通过回调加载密钥，如从KMS获取，最新版本为当前版本
+    pub async fn from_loader<F, T>(loader: F) -> TardisResult<Self>
+    where
+        F: FnOnce() -> T,
+        T: Future<Output = TardisResult<Vec<(u32, Vec<u8>)>>>,
+    {
+        let mut keyring = TardisCryptoKeyring::default();
+        for (version, key) in loader().await? {
+            keyring.add_key(version, key)?;
+        }
+        Ok(keyring)
+    }
+
+    /// Add the key of the version, the key becomes the current one if its version is the latest
+    /// / 添加该版本的密钥，版本为最新时成为当前密钥
+    pub fn add_key(&mut self, version: u32, key: impl AsRef<[u8]>) -> TardisResult<()> {
+        let key = key.as_ref();
+        if key.len() != KEY_SIZE {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.Crypto] The key of version {version} must be {KEY_SIZE} bytes"),
+                "406-tardis-crypto-keyring-key-invalid",
+            ));
+        }
+        if self.keys.get(&version).is_some_and(|exist| exist.as_slice() != key) {
+            return Err(TardisError::conflict(
+                &format!("[Tardis.Crypto] The key of version {version} already exists"),
+                "409-tardis-crypto-keyring-key-exist",
+            ));
+        }
+        if self.keys.keys().next_back().map_or(true, |latest| version > *latest) {
+            self.current_version = version;
+        }
+        self.keys.insert(version, key.to_vec());
+        Ok(())
+    }
+
+    /// Switch the key to encrypt / 切换用于加密的密钥
+    pub fn set_current_version(&mut self, version: u32) -> TardisResult<()> {
+        self.key(version)?;
+        self.current_version = version;
+        Ok(())
+    }
+
+    /// Remove the retired key, the blobs of the version can't be decrypted anymore / 移除停用的密钥，该版本的数据将无法解密
+    pub fn remove_key(&mut self, version: u32) -> TardisResult<()> {
+        if version == self.current_version {
+            return Err(TardisError::conflict(
+                &format!("[Tardis.Crypto] The current key of version {version} can't be removed"),
+                "409-tardis-crypto-keyring-key-in-use",
+            ));
+        }
+        self.keys.remove(&version);
+        Ok(())
+    }
+
+    pub fn current_version(&self) -> u32 {
+        self.current_version
+    }
+
+    pub fn versions(&self) -> Vec<u32> {
+        self.keys.keys().copied().collect()
+    }
+
+    /// The version of the key wrapping the blob / 包装该数据的密钥版本
+    pub fn version_of(&self, blob: &[u8]) -> TardisResult<u32> {
+        if blob.len() < HEADER_SIZE || blob[0] != BLOB_FORMAT {
+            return Err(TardisError::format_error("[Tardis.Crypto] Invalid keyring blob", "406-tardis-crypto-keyring-blob-invalid"));
+        }
+        Ok(u32::from_be_bytes([blob[1], blob[2], blob[3], blob[4]]))
+    }
+
+    /// Whether the blob is wrapped by a key other than the current one / 数据是否由非当前密钥包装
+    pub fn needs_rotation(&self, blob: &[u8]) -> TardisResult<bool> {
+        Ok(self.version_of(blob)? != self.current_version)
+    }
+
+    /// Encrypt the message, the `aad` is the associated data bound to the blob, e.g. the id of the record
+    /// / 加密消息， `aad` 为绑定到该数据的关联数据，如记录的id
+    pub fn encrypt(&self, message: impl AsRef<[u8]>, aad: impl AsRef<[u8]>) -> TardisResult<Vec<u8>> {
+        let data_key = TardisFuns::crypto.key.rand_32_bytes();
+        let data_nonce = TardisFuns::crypto.aead.random_nonce::<Aes256Gcm>();
+        let (ciphertext, _) = TardisFuns::crypto.aead.encrypt::<Aes256Gcm>(data_key, aad, &data_nonce, message)?;
+        let mut blob = self.wrap_data_key(&data_key)?;
+        blob.extend_from_slice(&data_nonce);
+        blob.extend_from_slice(&ciphertext);
+        Ok(blob)
+    }
+
+    pub fn decrypt(&self, blob: &[u8], aad: impl AsRef<[u8]>) -> TardisResult<Vec<u8>> {
+        let data_key = self.unwrap_data_key(blob)?;
+        let data_nonce = &blob[HEADER_SIZE - NONCE_SIZE..HEADER_SIZE];
+        TardisFuns::crypto
+            .aead
+            .decrypt::<Aes256Gcm>(data_key, aad, data_nonce, &blob[HEADER_SIZE..])
+            .map_err(|_| TardisError::format_error("[Tardis.Crypto] Failed to decrypt the keyring blob", "406-tardis-crypto-keyring-decrypt-failed"))
+    }
+
+    /// Re-wrap the data key of the blob by the current key, `None` if the blob is already wrapped by the current key
+    /// / 以当前密钥重新包装数据的数据密钥，已由当前密钥包装时为 `None`
+    ///
+    /// The ciphertext of the message is kept, so the rotation is cheap and doesn't need the `aad` .
+    ///
+    /// 消息的密文保持不变，因此轮换开销小且不需要 `aad` .
+    pub fn re_encrypt(&self, blob: &[u8]) -> TardisResult<Option<Vec<u8>>> {
+        if !self.needs_rotation(blob)? {
+            return Ok(None);
+        }
+        let data_key = self.unwrap_data_key(blob)?;
+        let mut rotated = self.wrap_data_key(&data_key)?;
+        rotated.extend_from_slice(&blob[HEADER_SIZE - NONCE_SIZE..]);
+        Ok(Some(rotated))
+    }
+
+    pub fn encrypt_to_base64(&self, message: impl AsRef<[u8]>, aad: impl AsRef<[u8]>) -> TardisResult<String> {
+        Ok(TardisFuns::crypto.base64.encode(self.encrypt(message, aad)?))
+    }
+
+    pub fn decrypt_base64(&self, blob: &str, aad: impl AsRef<[u8]>) -> TardisResult<Vec<u8>> {
+        self.decrypt(&TardisFuns::crypto.base64.decode(blob)?, aad)
+    }
+
+    pub fn decrypt_base64_to_string(&self, blob: &str, aad: impl AsRef<[u8]>) -> TardisResult<String> {
+        Ok(String::from_utf8(self.decrypt_base64(blob, aad)?)?)
+    }
+
+    pub fn re_encrypt_base64(&self, blob: &str) -> TardisResult<Option<String>> {
+        Ok(self.re_encrypt(&TardisFuns::crypto.base64.decode(blob)?)?.map(|rotated| TardisFuns::crypto.base64.encode(rotated)))
+    }
+
+    /// Re-encrypt the stored blobs in batches, e.g. the rows of a table, until `fetch` returns an empty batch
+    /// / 分批重新加密已存储的数据，如表中的行，直到 `fetch` 返回空批次
+    ///
+    /// `fetch` returns the next batch of `(id, blob)` after the last id ( `None` for the first batch),
+    /// `save` persists the rotated blobs, and the number of the rotated blobs is returned.
+    ///
+    /// `fetch` 返回上一个id（首批为 `None` ）之后的下一批 `(id, 数据)` ， `save` 持久化轮换后的数据，返回轮换的数据数.
+    pub async fn rotate<K, F, FT, S, ST>(&self, mut fetch: F, mut save: S) -> TardisResult<usize>
+    where
+        K: Clone,
+        F: FnMut(Option<K>) -> FT,
+        FT: Future<Output = TardisResult<Vec<(K, Vec<u8>)>>>,
+        S: FnMut(Vec<(K, Vec<u8>)>) -> ST,
+        ST: Future<Output = TardisResult<()>>,
+    {
+        let mut rotated_count = 0;
+        let mut last_id = None;
+        loop {
+            let batch = fetch(last_id.clone()).await?;
+            let Some((id, _)) = batch.last() else {
+                return Ok(rotated_count);
+            };
+            last_id = Some(id.clone());
+            let mut rotated = Vec::new();
+            for (id, blob) in batch {
+                if let Some(blob) = self.re_encrypt(&blob)? {
+                    rotated.push((id, blob));
+                }
+            }
+            if !rotated.is_empty() {
+                rotated_count += rotated.len();
+                save(rotated).await?;
+            }
+        }
+    }
+
+    fn key(&self, version: u32) -> TardisResult<&[u8]> {
+        self.keys.get(&version).map(Vec::as_slice).ok_or_else(|| {
+            TardisError::not_found(
+                &format!("[Tardis.Crypto] The key of version {version} doesn't exist"),
+                "404-tardis-crypto-keyring-key-not-exist",
+            )
+        })
+    }
+
+    /// The header without the data nonce / 不含数据nonce的头部
+    fn wrap_data_key(&self, data_key: &[u8]) -> TardisResult<Vec<u8>> {
+        let key = self.key(self.current_version)?;
+        let mut header = vec![BLOB_FORMAT];
+        header.extend_from_slice(&self.current_version.to_be_bytes());
+        let key_nonce = TardisFuns::crypto.aead.random_nonce::<Aes256Gcm>();
+        // the format and the version are bound to the wrapped data key
+        let (wrapped_key, _) = TardisFuns::crypto.aead.encrypt::<Aes256Gcm>(key, &header, &key_nonce, data_key)?;
+        header.extend_from_slice(&key_nonce);
+        header.extend_from_slice(&wrapped_key);
+        Ok(header)
+    }
+
+    fn unwrap_data_key(&self, blob: &[u8]) -> TardisResult<Vec<u8>> {
+        let key = self.key(self.version_of(blob)?)?;
+        let key_nonce = &blob[5..5 + NONCE_SIZE];
+        let wrapped_key = &blob[5 + NONCE_SIZE..5 + NONCE_SIZE + WRAPPED_KEY_SIZE];
+        TardisFuns::crypto.aead.decrypt::<Aes256Gcm>(key, &blob[..5], key_nonce, wrapped_key).map_err(|_| {
+            TardisError::format_error(
+                "[Tardis.Crypto] Failed to unwrap the data key of the keyring blob",
+                "406-tardis-crypto-keyring-decrypt-failed",
+            )
+        })
+    }
+}
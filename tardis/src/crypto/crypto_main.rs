@@ -18,8 +18,12 @@ pub struct TardisCrypto {
     pub aead: TardisCryptoAead,
     pub rsa: TardisCryptoRsa,
     pub digest: TardisCryptoDigest,
+    #[cfg(feature = "crypto-jwt")]
+    pub jwt: super::crypto_jwt::TardisCryptoJwt,
     #[cfg(feature = "crypto-with-sm")]
     pub sm4: super::crypto_sm2_4::TardisCryptoSm4,
     #[cfg(feature = "crypto-with-sm")]
     pub sm2: super::crypto_sm2_4::TardisCryptoSm2,
+    #[cfg(feature = "crypto-with-sm")]
+    pub sm3: super::crypto_sm2_4::TardisCryptoSm3,
 }
@@ -1,6 +1,7 @@
 use libsm::{
     sm2::ecc::Point,
     sm2::encrypt::{DecryptCtx, EncryptCtx},
+    sm2::exchange::{ExchangeCtxA, ExchangeCtxB},
     sm2::signature::{SigCtx, Signature},
     sm4::{Cipher, Mode},
 };
@@ -11,6 +12,8 @@ use crate::basic::result::TardisResult;
 
 pub struct TardisCryptoSm4;
 
+pub struct TardisCryptoSm3;
+
 pub struct TardisCryptoSm2;
 
 pub struct TardisCryptoSm2PrivateKey {
@@ -26,8 +29,8 @@ pub struct TardisCryptoSm2PublicKey {
 /// # Examples
 /// ```ignore
 /// use tardis::TardisFuns;
-/// let private_key = TardisFuns::crypto.rsa.new_private_key().unwrap();
-/// let public_key = TardisFuns::crypto.rsa.new_public_key(&private_key).unwrap();
+/// let private_key = TardisFuns::crypto.sm2.new_private_key().unwrap();
+/// let public_key = TardisFuns::crypto.sm2.new_public_key(&private_key).unwrap();
 ///
 /// let signed_data = private_key.sign("测试").unwrap();
 /// public_key.verify("测试", &signed_data).unwrap();
@@ -56,6 +59,137 @@ impl TardisCryptoSm2 {
     pub fn new_public_key_from_private_key(&self, private_key: &str) -> TardisResult<TardisCryptoSm2PublicKey> {
         TardisCryptoSm2PublicKey::from_private_key_str(private_key)
     }
+
+    /// Start the key exchange as the initiator A, see [`TardisCryptoSm2KeyExchangeInitiator`] / 作为发起方A开始密钥交换，见 [`TardisCryptoSm2KeyExchangeInitiator`]
+    pub fn new_key_exchange_initiator(
+        &self,
+        key_len: usize,
+        id_a: &str,
+        id_b: &str,
+        private_key_a: &TardisCryptoSm2PrivateKey,
+        public_key_b: &TardisCryptoSm2PublicKey,
+    ) -> TardisResult<TardisCryptoSm2KeyExchangeInitiator> {
+        let public_key_a = TardisCryptoSm2PublicKey::from_private_key(private_key_a)?;
+        Ok(TardisCryptoSm2KeyExchangeInitiator {
+            ctx: ExchangeCtxA::new(key_len, id_a, id_b, public_key_a.pub_key, public_key_b.pub_key, private_key_a.pri_key.clone()),
+        })
+    }
+
+    /// Start the key exchange as the responder B, see [`TardisCryptoSm2KeyExchangeInitiator`] / 作为响应方B开始密钥交换，见 [`TardisCryptoSm2KeyExchangeInitiator`]
+    pub fn new_key_exchange_responder(
+        &self,
+        key_len: usize,
+        id_a: &str,
+        id_b: &str,
+        public_key_a: &TardisCryptoSm2PublicKey,
+        private_key_b: &TardisCryptoSm2PrivateKey,
+    ) -> TardisResult<TardisCryptoSm2KeyExchangeResponder> {
+        let public_key_b = TardisCryptoSm2PublicKey::from_private_key(private_key_b)?;
+        Ok(TardisCryptoSm2KeyExchangeResponder {
+            ctx: ExchangeCtxB::new(key_len, id_a, id_b, public_key_a.pub_key, public_key_b.pub_key, private_key_b.pri_key.clone()),
+            r_a: None,
+        })
+    }
+}
+
+/// SM2 key exchange of the initiator A (GM/T 0003.3) / 发起方A的SM2密钥交换（GM/T 0003.3）
+///
+/// The messages between A and B are hex strings / A与B之间的消息为十六进制字符串:
+///
+/// 1. A: `r_a = exchange1()` , sends `r_a` to B / 发送 `r_a` 给B
+/// 2. B: `(r_b, s_b) = exchange2(r_a)` , sends `r_b` and `s_b` to A / 发送 `r_b` 及 `s_b` 给A
+/// 3. A: `s_a = exchange3(r_b, s_b)` , sends `s_a` to B, the key of A is agreed / 发送 `s_a` 给B，A的密钥已协商
+/// 4. B: `exchange4(s_a)` , the key of B is agreed / B的密钥已协商
+///
+/// # Examples
+/// ```ignore
+/// use tardis::TardisFuns;
+/// let mut a = TardisFuns::crypto.sm2.new_key_exchange_initiator(16, "alice", "bob", &private_key_a, &public_key_b)?;
+/// let mut b = TardisFuns::crypto.sm2.new_key_exchange_responder(16, "alice", "bob", &public_key_a, &private_key_b)?;
+/// let r_a = a.exchange1()?;
+/// let (r_b, s_b) = b.exchange2(&r_a)?;
+/// let s_a = a.exchange3(&r_b, &s_b)?;
+/// b.exchange4(&s_a)?;
+/// assert_eq!(a.key(), b.key());
+/// ```
+pub struct TardisCryptoSm2KeyExchangeInitiator {
+    ctx: ExchangeCtxA,
+}
+
+/// SM2 key exchange of the responder B, see [`TardisCryptoSm2KeyExchangeInitiator`] / 响应方B的SM2密钥交换，见 [`TardisCryptoSm2KeyExchangeInitiator`]
+pub struct TardisCryptoSm2KeyExchangeResponder {
+    ctx: ExchangeCtxB,
+    r_a: Option<Point>,
+}
+
+#[cfg(feature = "crypto-with-sm")]
+impl TardisCryptoSm2KeyExchangeInitiator {
+    /// Generate the random point `R_A` / 生成随机点 `R_A`
+    pub fn exchange1(&mut self) -> TardisResult<String> {
+        let r_a = self.ctx.exchange1().map_err(|error| exchange_error(&error.to_string()))?;
+        serialize_point(&r_a)
+    }
+
+    /// Verify `S_B` of B, and generate `S_A` / 验证B的 `S_B` ，并生成 `S_A`
+    pub fn exchange3(&mut self, r_b: &str, s_b: &str) -> TardisResult<String> {
+        let r_b = load_point(r_b)?;
+        let s_a = self.ctx.exchange3(&r_b, load_hash(s_b)?).map_err(|error| exchange_error(&error.to_string()))?;
+        Ok(hex::encode(s_a))
+    }
+
+    /// The agreed key in hex / 十六进制的协商密钥
+    pub fn key(&self) -> String {
+        hex::encode(&self.ctx.k)
+    }
+}
+
+#[cfg(feature = "crypto-with-sm")]
+impl TardisCryptoSm2KeyExchangeResponder {
+    /// Agree the key by `R_A` of A, and generate `R_B` and `S_B` / 通过A的 `R_A` 协商密钥，并生成 `R_B` 及 `S_B`
+    pub fn exchange2(&mut self, r_a: &str) -> TardisResult<(String, String)> {
+        let r_a = load_point(r_a)?;
+        let (r_b, s_b) = self.ctx.exchange2(&r_a).map_err(|error| exchange_error(&error.to_string()))?;
+        self.r_a = Some(r_a);
+        Ok((serialize_point(&r_b)?, hex::encode(s_b)))
+    }
+
+    /// Verify `S_A` of A / 验证A的 `S_A`
+    pub fn exchange4(&self, s_a: &str) -> TardisResult<()> {
+        let Some(r_a) = &self.r_a else {
+            return Err(exchange_error("exchange2 isn't completed"));
+        };
+        if self.ctx.exchange4(load_hash(s_a)?, r_a) {
+            Ok(())
+        } else {
+            Err(exchange_error("S_A doesn't match"))
+        }
+    }
+
+    /// The agreed key in hex / 十六进制的协商密钥
+    pub fn key(&self) -> String {
+        hex::encode(&self.ctx.k)
+    }
+}
+
+fn serialize_point(point: &Point) -> TardisResult<String> {
+    let point = SigCtx::new()
+        .serialize_pubkey(point, true)
+        .map_err(|error| TardisError::format_error(&format!("[Tardis.Crypto] SM2 serialize point error:{error}"), "406-tardis-crypto-sm2-exchange-error"))?;
+    Ok(hex::encode(point))
+}
+
+fn load_point(point: &str) -> TardisResult<Point> {
+    SigCtx::new()
+        .load_pubkey(&hex::decode(point)?)
+        .map_err(|error| TardisError::format_error(&format!("[Tardis.Crypto] SM2 load point error:{error}"), "406-tardis-crypto-sm2-exchange-error"))
+}
+
+fn load_hash(hash: &str) -> TardisResult<[u8; 32]> {
+    hex::decode(hash)?.try_into().map_err(|_| exchange_error("the hash must be 32 bytes"))
+}
+
+fn exchange_error(reason: &str) -> TardisError {
+    TardisError::format_error(&format!("[Tardis.Crypto] SM2 key exchange error:{reason}"), "406-tardis-crypto-sm2-exchange-error")
 }
 
 #[cfg(feature = "crypto-with-sm")]
@@ -151,6 +285,27 @@ impl TardisCryptoSm2PublicKey {
     }
 }
 
+/// SM3 handle / SM3处理
+///
+/// # Examples
+/// ```ignore
+/// use tardis::TardisFuns;
+/// let digest = TardisFuns::crypto.sm3.digest("测试").unwrap();
+/// let hmac = TardisFuns::crypto.sm3.hmac("测试", "pwd").unwrap();
+/// ```
+#[cfg(feature = "crypto-with-sm")]
+impl TardisCryptoSm3 {
+    /// The hex digest / 十六进制摘要
+    pub fn digest(&self, data: impl AsRef<[u8]>) -> TardisResult<String> {
+        crate::TardisFuns::crypto.digest.sm3(data)
+    }
+
+    /// The hex HMAC-SM3 / 十六进制的HMAC-SM3
+    pub fn hmac(&self, data: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> TardisResult<String> {
+        crate::TardisFuns::crypto.digest.digest_hmac::<crate::crypto::crypto_digest::algorithm::HmacSm3>(data, key)
+    }
+}
+
 /// SM4 handle / SM4处理
 ///
 /// # Examples
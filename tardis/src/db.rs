@@ -1,3 +1,5 @@
 pub use sea_orm;
+pub mod dialect;
 pub mod domain;
+pub mod migration;
 pub mod reldb_client;
@@ -0,0 +1,193 @@
+//! Database dialects / 数据库方言
+//!
+//! The dialect of each DB module is selected by the scheme of its url:
+//!
+//! 每个DB模块的方言通过其url的scheme选择：
+//!
+//! | Scheme                     | Dialect     | Feature          |
+//! |----------------------------|-------------|------------------|
+//! | `mysql`                    | MySQL       | `reldb-mysql`    |
+//! | `postgres` , `postgresql`  | PostgreSQL  | `reldb-postgres` |
+//! | `sqlite`                   | SQLite      | `reldb-sqlite`   |
+//! | `mssql` , `sqlserver`      | SQL Server  | -                |
+//!
+//! SQLite fits the local development and the tests without docker, e.g. `sqlite::memory:` or `sqlite://data/dev.db?mode=rwc` ,
+//! the in-memory database is kept in a single connection, since each connection opens its own in-memory database.
+//!
+//! SQLite适用于本地开发及不依赖docker的测试，如 `sqlite::memory:` 或 `sqlite://data/dev.db?mode=rwc` ，
+//! 由于每个连接会打开各自的内存数据库，内存数据库保持在单个连接中.
+//!
+//! The SQL generation of SQL Server is provided, but the connection isn't supported yet since the driver of SeaORM doesn't support it,
+//! so the SQL Server urls are rejected on initialization.
+//!
+//! 提供了SQL Server的SQL生成，但由于SeaORM的驱动尚不支持，暂不支持连接，因此初始化时会拒绝SQL Server的url.
+use sea_orm::DbBackend;
+use sqlparser::dialect::{Dialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::db::CompatibleType;
+
+/// Database dialect / 数据库方言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TardisDBDialect {
+    MySql,
+    Postgres,
+    Sqlite,
+    SqlServer,
+}
+
+impl From<DbBackend> for TardisDBDialect {
+    fn from(backend: DbBackend) -> Self {
+        match backend {
+            DbBackend::MySql => TardisDBDialect::MySql,
+            DbBackend::Postgres => TardisDBDialect::Postgres,
+            DbBackend::Sqlite => TardisDBDialect::Sqlite,
+        }
+    }
+}
+
+impl TardisDBDialect {
+    /// Select the dialect by the scheme of the url / 通过url的scheme选择方言
+    pub fn from_url(url: &str) -> TardisResult<Self> {
+        let scheme = url.split_once(':').map(|(scheme, _)| scheme.to_lowercase()).unwrap_or_default();
+        match scheme.as_str() {
+            "mysql" => Ok(TardisDBDialect::MySql),
+            "postgres" | "postgresql" => Ok(TardisDBDialect::Postgres),
+            "sqlite" => Ok(TardisDBDialect::Sqlite),
+            "mssql" | "sqlserver" => Ok(TardisDBDialect::SqlServer),
+            _ => Err(TardisError::format_error(
+                &format!("[Tardis.RelDBClient] Unsupported database scheme {scheme}"),
+                "406-tardis-reldb-url-error",
+            )),
+        }
+    }
+
+    /// The backend of SeaORM / SeaORM的后端
+    pub fn backend(&self) -> TardisResult<DbBackend> {
+        match self {
+            TardisDBDialect::MySql => Ok(DbBackend::MySql),
+            TardisDBDialect::Postgres => Ok(DbBackend::Postgres),
+            TardisDBDialect::Sqlite => Ok(DbBackend::Sqlite),
+            TardisDBDialect::SqlServer => Err(TardisError::not_implemented(
+                "[Tardis.RelDBClient] SQL Server isn't supported by the driver yet",
+                "501-tardis-reldb-dialect-unsupported",
+            )),
+        }
+    }
+
+    /// Whether the url is an in-memory SQLite database / url是否为SQLite内存数据库
+    pub fn is_in_memory(&self, url: &str) -> bool {
+        *self == TardisDBDialect::Sqlite && (url.contains(":memory:") || url.contains("mode=memory"))
+    }
+
+    /// Quote the identifier / 引用标识符
+    pub fn quote(&self, identifier: &str) -> String {
+        match self {
+            TardisDBDialect::MySql => format!("`{}`", identifier.replace('`', "``")),
+            TardisDBDialect::Postgres | TardisDBDialect::Sqlite => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            TardisDBDialect::SqlServer => format!("[{}]", identifier.replace(']', "]]")),
+        }
+    }
+
+    /// The placeholder of the parameter, the index starts from 1 / 参数的占位符，序号从1开始
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            TardisDBDialect::Postgres => format!("${index}"),
+            TardisDBDialect::SqlServer => format!("@P{index}"),
+            TardisDBDialect::MySql | TardisDBDialect::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// The comma separated placeholders of the parameters / 以逗号分隔的参数占位符
+    pub fn placeholders(&self, count: usize) -> String {
+        (1..=count).map(|index| self.placeholder(index)).collect::<Vec<_>>().join(",")
+    }
+
+    /// Append the paging clause to the query / 为查询追加分页子句
+    ///
+    /// SQL Server requires the query to be ordered.
+    ///
+    /// SQL Server要求查询已排序.
+    pub fn paginate(&self, sql: &str, limit: u64, offset: u64) -> String {
+        match self {
+            TardisDBDialect::SqlServer => format!("{sql} OFFSET {offset} ROWS FETCH NEXT {limit} ROWS ONLY"),
+            _ => format!("{sql} LIMIT {limit} OFFSET {offset}"),
+        }
+    }
+
+    /// The SQLs updating the time field automatically on update / 在更新时自动更新时间字段的SQL
+    ///
+    /// MySQL uses `ON UPDATE CURRENT_TIMESTAMP` of the column instead, and SQL Server isn't supported.
+    ///
+    /// MySQL使用列的 `ON UPDATE CURRENT_TIMESTAMP` 代替，不支持SQL Server.
+    pub fn auto_update_time_sqls(&self, table_name: &str, update_time_field: &str, compatible_type: CompatibleType) -> Vec<String> {
+        match (self, compatible_type) {
+            (TardisDBDialect::Postgres, CompatibleType::None) => vec![
+                format!(
+                    r###"CREATE OR REPLACE FUNCTION TARDIS_AUTO_UPDATE_TIME_{}()
+        RETURNS TRIGGER AS $$
+        BEGIN
+            NEW.{} = now();
+            RETURN NEW;
+        END;
+        $$ language 'plpgsql';"###,
+                    update_time_field.replace('-', "_"),
+                    update_time_field
+                ),
+                format!(
+                    r###"CREATE OR REPLACE TRIGGER TARDIS_AUTO_UPDATE_TIME_ON
+            BEFORE UPDATE
+            ON
+                {}
+            FOR EACH ROW
+        EXECUTE PROCEDURE TARDIS_AUTO_UPDATE_TIME_{}();"###,
+                    table_name,
+                    update_time_field.replace('-', "_")
+                ),
+            ],
+            (TardisDBDialect::Postgres, CompatibleType::Oracle) => vec![format!(
+                r###"CREATE OR REPLACE TRIGGER TARDIS_AUTO_UPDATE_TIME_ON
+            BEFORE UPDATE
+            ON
+                {}
+            FOR EACH ROW
+            BEGIN
+                NEW.{}= now();
+                RETURN NEW;
+            END;"###,
+                table_name, update_time_field
+            )],
+            // the explicitly updated time is kept
+            (TardisDBDialect::Sqlite, _) => vec![format!(
+                r###"CREATE TRIGGER IF NOT EXISTS TARDIS_AUTO_UPDATE_TIME_{}_{}
+            AFTER UPDATE
+            ON
+                {}
+            FOR EACH ROW
+            WHEN NEW.{} = OLD.{}
+            BEGIN
+                UPDATE {} SET {} = CURRENT_TIMESTAMP WHERE rowid = NEW.rowid;
+            END;"###,
+                table_name.replace('-', "_"),
+                update_time_field.replace('-', "_"),
+                table_name,
+                update_time_field,
+                update_time_field,
+                table_name,
+                update_time_field
+            )],
+            (TardisDBDialect::MySql, _) | (TardisDBDialect::SqlServer, _) => vec![],
+        }
+    }
+
+    /// The dialect of the SQL parser / SQL解析器的方言
+    pub(crate) fn sql_parser_dialect(&self) -> Box<dyn Dialect> {
+        match self {
+            TardisDBDialect::MySql => Box::new(MySqlDialect {}),
+            TardisDBDialect::Postgres => Box::new(PostgreSqlDialect {}),
+            TardisDBDialect::Sqlite => Box::new(SQLiteDialect {}),
+            TardisDBDialect::SqlServer => Box::new(MsSqlDialect {}),
+        }
+    }
+}
@@ -8,7 +8,7 @@ use crate::db::reldb_client::{TardisActiveModel, TardisRelDBlConnection};
 use crate::db::sea_orm::sea_query::{ColumnDef, Table, TableCreateStatement};
 use crate::db::sea_orm::ActiveValue::Set;
 use crate::db::sea_orm::{ActiveModelBehavior, DbBackend};
-use crate::TardisResult;
+use crate::{TardisFuns, TardisResult};
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "tardis_config")]
@@ -60,7 +60,7 @@ impl TardisActiveModel for ActiveModel {
                 .col(ColumnDef::new(Column::Creator).not_null().string())
                 .col(ColumnDef::new(Column::Updater).not_null().string())
                 .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
-                .col(ColumnDef::new(Column::UpdateTime).extra("DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP".to_string()).timestamp())
+                .col(ColumnDef::new(Column::UpdateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
                 .to_owned(),
         }
     }
@@ -94,12 +94,14 @@ impl TardisDataDict {
 
     pub async fn add(&self, key: &str, value: &str, creator: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
         trace!("[Tardis.RelDBClient] [db_config] add key: {}, value: {}", key, value);
+        let now = TardisFuns::clock().now();
         let model = tardis_db_config::ActiveModel {
             k: Set(key.to_string()),
             v: Set(value.to_string()),
             creator: Set(creator.to_string()),
             updater: Set(creator.to_string()),
-            update_time: Set(Utc::now()),
+            create_time: Set(now),
+            update_time: Set(now),
             ..Default::default()
         };
         if db.has_tx() {
@@ -116,6 +118,7 @@ impl TardisDataDict {
             k: Set(key.to_string()),
             v: Set(value.to_string()),
             updater: Set(updater.to_string()),
+            update_time: Set(TardisFuns::clock().now()),
             ..Default::default()
         };
         if db.has_tx() {
@@ -0,0 +1,311 @@
+//! Database migrations / 数据库迁移
+//!
+//! The migrations are applied in the order of the versions, each in a transaction, and recorded in the schema history table,
+//! the instances migrating at the same time are serialized by a database lock
+//! (`pg_advisory_xact_lock` of PostgreSQL, `GET_LOCK` of MySQL, the write lock of SQLite).
+//!
+//! 迁移按版本顺序应用，每个迁移在一个事务中执行，并记录到结构历史表中，
+//! 同时迁移的多个实例通过数据库锁串行化（PostgreSQL的 `pg_advisory_xact_lock` ，MySQL的 `GET_LOCK` ，SQLite的写锁）.
+//!
+//! The SQL migrations are the files named `V{version}__{name}.sql`, e.g. `V1__init.sql`, `V2__add_order_remark.sql` ,
+//! loaded from the directory or embedded by [`tardis_migrations!`](crate::tardis_migrations).
+//! The checksum of the applied SQL migration is verified, so don't modify the applied migrations, add a new one instead.
+//! The code migrations are written with the connection, e.g. with the sea-query statements.
+//!
+//! SQL迁移为名为 `V{版本}__{名称}.sql` 的文件，如 `V1__init.sql` 、 `V2__add_order_remark.sql` ，
+//! 从目录加载或通过 [`tardis_migrations!`](crate::tardis_migrations) 嵌入. 会校验已应用SQL迁移的校验和，因此请勿修改已应用的迁移，而应添加新的迁移.
+//! 代码迁移使用连接编写，如使用sea-query语句.
+//!
+//! MySQL commits implicitly after the DDL statements, so a failed MySQL migration may be applied partially.
+//!
+//! MySQL在DDL语句后隐式提交，因此失败的MySQL迁移可能被部分应用.
+//!
+//! # Examples
+//! ```ignore
+//! // applied on initialization with `run_migrations = true`, or explicitly
+//! let migrations = tardis_migrations!("migrations", ["V1__init.sql", "V2__add_order_remark.sql"])?;
+//! TardisFuns::reldb().migrate(&migrations).await?;
+//! ```
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use sea_orm::sea_query::{Alias, ColumnDef, Expr, Query, Table};
+use sea_orm::{DbBackend, FromQueryResult};
+use tracing::info;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::db::reldb_client::{TardisRelDBClient, TardisRelDBlConnection};
+use crate::TardisFuns;
+
+/// Key of the PostgreSQL advisory lock and the name of the MySQL lock / PostgreSQL咨询锁的key及MySQL锁的名称
+const LOCK_KEY: i64 = 7_450_017;
+const LOCK_NAME: &str = "tardis_migration";
+const LOCK_TIMEOUT_SEC: u32 = 300;
+
+type TardisMigrationFn = Arc<dyn for<'a> Fn(&'a TardisRelDBlConnection) -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send + 'a>> + Send + Sync>;
+
+#[derive(Clone)]
+enum TardisMigrationKind {
+    Sql(String),
+    Code(TardisMigrationFn),
+}
+
+/// Versioned migration / 版本化迁移
+#[derive(Clone)]
+pub struct TardisMigration {
+    pub version: u64,
+    pub name: String,
+    kind: TardisMigrationKind,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct TardisMigrationRecord {
+    version: i64,
+    checksum: String,
+}
+
+impl TardisMigration {
+    /// SQL migration, the statements are separated by `;` / SQL迁移，语句以 `;` 分隔
+    pub fn sql(version: u64, name: impl Into<String>, sql: impl Into<String>) -> Self {
+        TardisMigration {
+            version,
+            name: name.into(),
+            kind: TardisMigrationKind::Sql(sql.into()),
+        }
+    }
+
+    /// Code migration / 代码迁移
+    ///
+    /// # Examples
+    /// ```ignore
+    /// TardisMigration::code(3, "backfill_order_remark", |conn| Box::pin(async move {
+    ///     conn.execute(&Query::update().table(Order::Table).value(Order::Remark, "").to_owned()).await?;
+    ///     Ok(())
+    /// }))
+    /// ```
+    pub fn code<F>(version: u64, name: impl Into<String>, fun: F) -> Self
+    where
+        F: for<'a> Fn(&'a TardisRelDBlConnection) -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send + 'a>> + Send + Sync + 'static,
+    {
+        TardisMigration {
+            version,
+            name: name.into(),
+            kind: TardisMigrationKind::Code(Arc::new(fun)),
+        }
+    }
+
+    /// Parse the SQL migration by the file name `V{version}__{name}.sql` / 按文件名 `V{版本}__{名称}.sql` 解析SQL迁移
+    pub fn from_file(file_name: &str, sql: impl Into<String>) -> TardisResult<Self> {
+        let invalid = || {
+            TardisError::format_error(
+                &format!("[Tardis.RelDBClient] Invalid migration file name {file_name}, expected V{{version}}__{{name}}.sql"),
+                "406-tardis-reldb-migration-name-error",
+            )
+        };
+        let (version, name) = file_name.strip_prefix('V').and_then(|name| name.strip_suffix(".sql")).and_then(|name| name.split_once("__")).ok_or_else(invalid)?;
+        Ok(Self::sql(version.parse().map_err(|_| invalid())?, name, sql))
+    }
+
+    /// Load the SQL migrations of the directory, the other files are ignored / 加载目录中的SQL迁移，忽略其它文件
+    pub fn from_dir(dir: impl AsRef<Path>) -> TardisResult<Vec<Self>> {
+        let mut migrations = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|file_name| file_name.to_str()) else {
+                continue;
+            };
+            if path.is_file() && file_name.starts_with('V') && file_name.ends_with(".sql") {
+                migrations.push(Self::from_file(file_name, std::fs::read_to_string(&path)?)?);
+            }
+        }
+        Ok(migrations)
+    }
+
+    /// Checksum of the SQL, the code migrations aren't verified / SQL的校验和，不校验代码迁移
+    fn checksum(&self) -> String {
+        match &self.kind {
+            // FNV-1a, stable across the platforms and the versions
+            TardisMigrationKind::Sql(sql) => format!(
+                "{:016x}",
+                sql.replace("\r\n", "\n").trim().bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+            ),
+            TardisMigrationKind::Code(_) => String::new(),
+        }
+    }
+}
+
+/// Embed the SQL migration files of the directory relative to the crate root / 嵌入相对于crate根目录的目录中的SQL迁移文件
+///
+/// Returns `TardisResult<Vec<TardisMigration>>` .
+///
+/// # Examples
+/// ```ignore
+/// let migrations = tardis_migrations!("migrations", ["V1__init.sql", "V2__add_order_remark.sql"])?;
+/// ```
+#[macro_export]
+macro_rules! tardis_migrations {
+    ($dir:literal, [$($file:literal),* $(,)?]) => {
+        $crate::basic::result::TardisResult::<Vec<$crate::db::migration::TardisMigration>>::Ok(vec![
+            $($crate::db::migration::TardisMigration::from_file($file, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $dir, "/", $file)))?,)*
+        ])
+    };
+}
+
+impl TardisRelDBClient {
+    /// Apply the pending migrations in the order of the versions, returns the versions applied
+    /// / 按版本顺序应用待执行的迁移，返回已应用的版本
+    ///
+    /// Fails if the checksum of an applied SQL migration is changed, or the versions are duplicated.
+    ///
+    /// 已应用SQL迁移的校验和发生变化，或版本重复时失败.
+    pub async fn migrate(&self, migrations: &[TardisMigration]) -> TardisResult<Vec<u64>> {
+        let mut migrations = migrations.iter().collect::<Vec<_>>();
+        migrations.sort_by_key(|migration| migration.version);
+        if let Some(duplicated) = migrations.windows(2).find(|pair| pair[0].version == pair[1].version) {
+            return Err(TardisError::conflict(
+                &format!("[Tardis.RelDBClient] Duplicated migration version {}", duplicated[0].version),
+                "409-tardis-reldb-migration-conflict",
+            ));
+        }
+        let table = Alias::new(self.migration_table());
+        self.conn()
+            .create_table(
+                &Table::create()
+                    .table(table.clone())
+                    .if_not_exists()
+                    .col(ColumnDef::new(Alias::new("version")).not_null().big_integer().primary_key())
+                    .col(ColumnDef::new(Alias::new("name")).not_null().string())
+                    .col(ColumnDef::new(Alias::new("checksum")).not_null().string())
+                    .col(ColumnDef::new(Alias::new("execution_ms")).not_null().big_integer())
+                    .col(ColumnDef::new(Alias::new("applied_time")).not_null().big_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut conn = self.conn();
+        conn.begin().await?;
+        match self.backend() {
+            DbBackend::Postgres => {
+                conn.execute_one(&format!("SELECT pg_advisory_xact_lock({LOCK_KEY})"), vec![]).await?;
+            }
+            DbBackend::MySql => {
+                let locked = conn.query_one(&format!("SELECT GET_LOCK('{LOCK_NAME}', {LOCK_TIMEOUT_SEC}) AS locked"), vec![]).await?;
+                if locked.and_then(|locked| locked.try_get::<Option<i64>>("", "locked").ok().flatten()) != Some(1) {
+                    return Err(TardisError::conflict(
+                        "[Tardis.RelDBClient] Timeout waiting for the migration lock",
+                        "409-tardis-reldb-migration-locked",
+                    ));
+                }
+            }
+            // locked by the first write
+            DbBackend::Sqlite => {}
+        }
+        let result = apply(&conn, &table, &migrations).await;
+        if self.backend() == DbBackend::MySql {
+            conn.execute_one(&format!("SELECT RELEASE_LOCK('{LOCK_NAME}')"), vec![]).await?;
+        }
+        match result {
+            Ok(applied) => {
+                conn.commit().await?;
+                Ok(applied)
+            }
+            Err(error) => {
+                conn.rollback().await?;
+                Err(error)
+            }
+        }
+    }
+}
+
+async fn apply(conn: &TardisRelDBlConnection, table: &Alias, migrations: &[&TardisMigration]) -> TardisResult<Vec<u64>> {
+    let records: Vec<TardisMigrationRecord> = conn.find_dtos(&Query::select().columns([Alias::new("version"), Alias::new("checksum")]).from(table.clone()).to_owned()).await?;
+    let mut applied = Vec::new();
+    for migration in migrations {
+        let checksum = migration.checksum();
+        if let Some(record) = records.iter().find(|record| record.version as u64 == migration.version) {
+            if !checksum.is_empty() && !record.checksum.is_empty() && record.checksum != checksum {
+                return Err(TardisError::conflict(
+                    &format!(
+                        "[Tardis.RelDBClient] The applied migration V{}__{} is modified, checksum:{} , expected:{}",
+                        migration.version, migration.name, checksum, record.checksum
+                    ),
+                    "409-tardis-reldb-migration-conflict",
+                ));
+            }
+            continue;
+        }
+        info!("[Tardis.RelDBClient] Applying migration V{}__{}", migration.version, migration.name);
+        let started = Instant::now();
+        match &migration.kind {
+            TardisMigrationKind::Sql(sql) => {
+                for statement in split_sql(sql) {
+                    conn.execute_one(&statement, vec![]).await?;
+                }
+            }
+            TardisMigrationKind::Code(fun) => fun(conn).await?,
+        }
+        conn.execute(
+            &Query::insert()
+                .into_table(table.clone())
+                .columns([
+                    Alias::new("version"),
+                    Alias::new("name"),
+                    Alias::new("checksum"),
+                    Alias::new("execution_ms"),
+                    Alias::new("applied_time"),
+                ])
+                .values_panic([
+                    Expr::val(migration.version as i64).into(),
+                    migration.name.as_str().into(),
+                    checksum.into(),
+                    (started.elapsed().as_millis() as i64).into(),
+                    TardisFuns::clock().now().timestamp_millis().into(),
+                ])
+                .to_owned(),
+        )
+        .await?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// Split the statements by `;`, ignoring those in quotes and comments / 以 `;` 拆分语句，忽略引号及注释中的 `;`
+pub(crate) fn split_sql(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '\'' || c == '"' || c == '`' => {
+                current.push(c);
+                quote = Some(c);
+            }
+            None if c == '-' && chars.peek() == Some(&'-') => {
+                // skip the line comment
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        current.push(c);
+                        break;
+                    }
+                }
+            }
+            None if c == ';' => {
+                statements.push(std::mem::take(&mut current));
+            }
+            None => current.push(c),
+        }
+    }
+    statements.push(current);
+    statements.into_iter().map(|statement| statement.trim().to_string()).filter(|statement| !statement.is_empty()).collect()
+}
@@ -1,27 +1,34 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
 use sea_orm::sea_query::TableCreateStatement;
+use sea_orm::sea_query::{Alias, Expr};
 use sea_orm::sea_query::{IndexCreateStatement, SelectStatement, UpdateStatement};
 use sea_orm::ActiveValue::Set;
 use sea_orm::*;
 use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, EntityTrait, ExecResult, QueryTrait, Schema, Select, Statement};
 use sqlparser::ast;
 use sqlparser::ast::{SetExpr, TableFactor};
-use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect, SQLiteDialect};
 use sqlparser::parser::{Parser, ParserError};
 use sqlx::Executor;
-use tracing::{error, info, trace};
+use tokio::task::JoinHandle;
+use tracing::{error, info, trace, warn};
 use url::Url;
 
 use crate::basic::dto::TardisContext;
 use crate::basic::error::TardisError;
+use crate::basic::metrics::observe_client;
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::component::db::CompatibleType;
 use crate::config::config_dto::component::db::DBModuleConfig;
+use crate::db::dialect::TardisDBDialect;
 use crate::db::domain::{tardis_db_config, tardis_db_del_record};
+use crate::db::migration::TardisMigration;
 use crate::serde::{Deserialize, Serialize};
 use crate::utils::initializer::InitBy;
 use crate::TardisFuns;
@@ -130,9 +137,31 @@ use crate::TardisFuns;
 /// ```
 pub struct TardisRelDBClient {
     con: Arc<DatabaseConnection>,
+    dialect: TardisDBDialect,
     compatible_type: CompatibleType,
+    replicas: Arc<Vec<TardisRelDBReplica>>,
+    next_replica: AtomicUsize,
+    replica_checker: Option<JoinHandle<()>>,
+    migration_table: String,
 }
 
+struct TardisRelDBReplica {
+    host: String,
+    con: Arc<DatabaseConnection>,
+    healthy: AtomicBool,
+}
+
+impl Drop for TardisRelDBClient {
+    fn drop(&mut self) {
+        if let Some(replica_checker) = &self.replica_checker {
+            replica_checker.abort();
+        }
+    }
+}
+
+/// Column of the total number of records in [`TardisRelDBlConnection::paginate`] / [`TardisRelDBlConnection::paginate`] 中总记录数的列
+const PAGINATE_TOTAL_COLUMN: &str = "_tardis_total";
+
 #[async_trait::async_trait]
 impl InitBy<DBModuleConfig> for TardisRelDBClient {
     async fn init_by(config: &DBModuleConfig) -> TardisResult<Self> {
@@ -142,17 +171,59 @@ impl InitBy<DBModuleConfig> for TardisRelDBClient {
 
 impl TardisRelDBClient {
     /// Initialize configuration / 初始化配置
-    pub async fn init(
+    ///
+    /// The replicas failed to connect are skipped with an error log, so that the reads fall back to the primary.
+    ///
+    /// 连接失败的副本会被跳过并记录错误日志，从而读操作回退到主库.
+    ///
+    /// The migrations of `migrations_dir` are applied if `run_migrations` is enabled, see [`TardisRelDBClient::migrate`].
+    ///
+    /// 若启用了 `run_migrations` ，则应用 `migrations_dir` 中的迁移，见 [`TardisRelDBClient::migrate`] .
+    pub async fn init(config: &DBModuleConfig) -> TardisResult<TardisRelDBClient> {
+        let con = Self::connect(&config.url, config).await?;
+        let mut replicas = Vec::new();
+        for replica_url in &config.replicas {
+            let host = Url::parse(replica_url).ok().and_then(|url| url.host_str().map(|host| format!("{host}:{}", url.port().unwrap_or(0)))).unwrap_or_default();
+            match Self::connect(replica_url, config).await {
+                Ok(con) => replicas.push(TardisRelDBReplica {
+                    host,
+                    con: Arc::new(con),
+                    healthy: AtomicBool::new(true),
+                }),
+                Err(error) => error!("[Tardis.RelDBClient] Replica {host} is skipped | {error}"),
+            }
+        }
+        let replicas = Arc::new(replicas);
+        let replica_checker = (!replicas.is_empty()).then(|| Self::check_replicas(replicas.clone(), Duration::from_secs(config.replica_check_interval_sec.max(1))));
+        let client = TardisRelDBClient {
+            con: Arc::new(con),
+            dialect: TardisDBDialect::from_url(&config.url)?,
+            compatible_type: config.compatible_type,
+            replicas,
+            next_replica: AtomicUsize::new(0),
+            replica_checker,
+            migration_table: config.migration_table.clone(),
+        };
+        if config.run_migrations {
+            let applied = client.migrate(&TardisMigration::from_dir(&config.migrations_dir)?).await?;
+            info!("[Tardis.RelDBClient] Migrations applied, versions:{applied:?}");
+        }
+        Ok(client)
+    }
+
+    async fn connect(
+        str_url: &str,
         DBModuleConfig {
-            url: str_url,
             max_connections,
             min_connections,
             connect_timeout_sec,
             idle_timeout_sec,
-            compatible_type,
+            ..
         }: &DBModuleConfig,
-    ) -> TardisResult<TardisRelDBClient> {
+    ) -> TardisResult<DatabaseConnection> {
         let url = Url::parse(str_url).map_err(|_| TardisError::format_error(&format!("[Tardis.RelDBClient] Invalid url {str_url}"), "406-tardis-reldb-url-error"))?;
+        let dialect = TardisDBDialect::from_url(str_url)?;
+        dialect.backend()?;
         info!(
             "[Tardis.RelDBClient] Initializing, host:{}, port:{}, max_connections:{}",
             url.host_str().unwrap_or(""),
@@ -167,6 +238,10 @@ impl TardisRelDBClient {
         if let Some(idle_timeout_sec) = idle_timeout_sec {
             opt.idle_timeout(Duration::from_secs(*idle_timeout_sec));
         }
+        if dialect.is_in_memory(str_url) {
+            // each connection opens its own in-memory database
+            opt.max_connections(1).min_connections(1);
+        }
         let con = if let Some(timezone) = url.query_pairs().find(|x| x.0.to_lowercase() == "timezone").map(|x| x.1.to_string()) {
             match url.scheme().to_lowercase().as_str() {
                 #[cfg(feature = "reldb-mysql")]
@@ -241,9 +316,27 @@ impl TardisRelDBClient {
             url.port().unwrap_or(0),
             min_connections
         );
-        Ok(TardisRelDBClient {
-            con: Arc::new(con),
-            compatible_type: *compatible_type,
+        Ok(con)
+    }
+
+    /// Check the replicas periodically by `SELECT 1` / 通过 `SELECT 1` 定期检查副本
+    fn check_replicas(replicas: Arc<Vec<TardisRelDBReplica>>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                for replica in replicas.iter() {
+                    let healthy = match TardisRelDBClient::query_one_inner("SELECT 1", Vec::new(), replica.con.as_ref()).await {
+                        Ok(_) => true,
+                        Err(error) => {
+                            warn!("[Tardis.RelDBClient] Replica {} is unhealthy | {error}", replica.host);
+                            false
+                        }
+                    };
+                    if replica.healthy.swap(healthy, Ordering::Relaxed) != healthy && healthy {
+                        info!("[Tardis.RelDBClient] Replica {} is recovered", replica.host);
+                    }
+                }
+            }
         })
     }
 
@@ -258,6 +351,16 @@ impl TardisRelDBClient {
         self.compatible_type
     }
 
+    /// Get the dialect selected by the url / 获取通过url选择的方言
+    pub fn dialect(&self) -> TardisDBDialect {
+        self.dialect
+    }
+
+    /// Get the schema history table of the migrations / 获取迁移的结构历史表
+    pub fn migration_table(&self) -> &str {
+        &self.migration_table
+    }
+
     /// Get database connection
     ///
     /// 获取数据库操作连接
@@ -265,6 +368,47 @@ impl TardisRelDBClient {
         TardisRelDBlConnection { conn: self.con.clone(), tx: None }
     }
 
+    /// Get a database connection for reads, the healthy replicas are used in turn, or the primary if there isn't any
+    /// / 获取用于读操作的数据库连接，轮流使用健康的副本，没有时使用主库
+    ///
+    /// The replicas may lag behind the primary, so read by [`Self::conn`] right after writing.
+    ///
+    /// 副本可能落后于主库，因此写入后立即读取请使用 [`Self::conn`] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let report = TardisFuns::reldb().read().find_dtos::<ReportResp>(&query).await?;
+    /// ```
+    pub fn read(&self) -> TardisRelDBlConnection {
+        let len = self.replicas.len();
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..len {
+            let replica = &self.replicas[(start + offset) % len];
+            if replica.healthy.load(Ordering::Relaxed) {
+                return TardisRelDBlConnection {
+                    conn: replica.con.clone(),
+                    tx: None,
+                };
+            }
+        }
+        if len > 0 {
+            trace!("[Tardis.RelDBClient] No healthy replica, read from the primary");
+        }
+        self.conn()
+    }
+
+    /// Number of the healthy replicas / 健康副本的数量
+    pub fn healthy_replicas(&self) -> usize {
+        self.replicas.iter().filter(|replica| replica.healthy.load(Ordering::Relaxed)).count()
+    }
+
+    /// Check the connectivity by `SELECT 1` / 通过 `SELECT 1` 检查连通性
+    pub async fn health_check(&self) -> TardisResult<()> {
+        self.conn().query_one("SELECT 1", Vec::new()).await?;
+        Ok(())
+    }
+
     /// Initialize basic tables / 初始化基础表
     pub async fn init_basic_tables(&self) -> TardisResult<()> {
         trace!("[Tardis.RelDBClient] Initializing basic tables");
@@ -332,7 +476,7 @@ impl TardisRelDBClient {
     where
         C: ConnectionTrait,
     {
-        let result = db.execute(statement).await;
+        let result = observe_client("reldb", "execute", db.execute(statement)).await;
         match result {
             Ok(ok) => TardisResult::Ok(ok),
             Err(error) => TardisResult::Err(TardisError::from(error)),
@@ -345,7 +489,7 @@ impl TardisRelDBClient {
     {
         trace!("[Tardis.RelDBClient] Querying one sql {}, params:{:?}", sql, params);
         let query_stmt = Statement::from_sql_and_values(db.get_database_backend(), sql, params);
-        let result = db.query_one(query_stmt).await;
+        let result = observe_client("reldb", "query_one", db.query_one(query_stmt)).await;
         match result {
             Ok(ok) => TardisResult::Ok(ok),
             Err(error) => TardisResult::Err(TardisError::from(error)),
@@ -358,7 +502,7 @@ impl TardisRelDBClient {
     {
         trace!("[Tardis.RelDBClient] Querying all sql {}, params:{:?}", sql, params);
         let query_stmt = Statement::from_sql_and_values(db.get_database_backend(), sql, params);
-        let result = db.query_all(query_stmt).await;
+        let result = observe_client("reldb", "query_all", db.query_all(query_stmt)).await;
         match result {
             Ok(ok) => TardisResult::Ok(ok),
             Err(error) => TardisResult::Err(TardisError::from(error)),
@@ -386,7 +530,7 @@ impl TardisRelDBClient {
         C: ConnectionTrait,
         D: FromQueryResult,
     {
-        let result = D::find_by_statement(select_statement).one(db).await;
+        let result = observe_client("reldb", "get_dto", D::find_by_statement(select_statement).one(db)).await;
         match result {
             Ok(r) => TardisResult::Ok(r),
             Err(error) => TardisResult::Err(TardisError::from(error)),
@@ -414,7 +558,7 @@ impl TardisRelDBClient {
         C: ConnectionTrait,
         D: FromQueryResult,
     {
-        let result = D::find_by_statement(select_statement).all(db).await;
+        let result = observe_client("reldb", "find_dtos", D::find_by_statement(select_statement).all(db)).await;
         match result {
             Ok(r) => TardisResult::Ok(r),
             Err(error) => TardisResult::Err(TardisError::from(error)),
@@ -442,17 +586,48 @@ impl TardisRelDBClient {
         C: ConnectionTrait,
         D: FromQueryResult,
     {
-        let select_sql = format!("{} LIMIT {} OFFSET {}", select_statement.sql, page_size, (page_number - 1) * page_size);
+        let select_sql = TardisDBDialect::from(select_statement.db_backend).paginate(&select_statement.sql, page_size, (page_number - 1) * page_size);
         let query_statement = Statement {
             sql: select_sql,
             values: select_statement.values.clone(),
             db_backend: select_statement.db_backend,
         };
-        let query_result = D::find_by_statement(query_statement).all(db).await?;
+        let query_result = observe_client("reldb", "paginate_dtos", D::find_by_statement(query_statement).all(db)).await?;
         let count_result = TardisRelDBClient::do_count_inner(select_statement, db).await?;
         Ok((query_result, count_result))
     }
 
+    pub(self) async fn paginate_inner<C, D>(select_statement: &SelectStatement, page_number: u64, page_size: u64, db: &C) -> TardisResult<(u64, Vec<D>)>
+    where
+        C: ConnectionTrait,
+        D: FromQueryResult,
+    {
+        let page_statement = select_statement
+            .clone()
+            .expr_as(Expr::cust("COUNT(1) OVER()"), Alias::new(PAGINATE_TOTAL_COLUMN))
+            .limit(page_size)
+            .offset((page_number.max(1) - 1) * page_size)
+            .to_owned();
+        let rows = observe_client("reldb", "paginate", db.query_all(db.get_database_backend().build(&page_statement))).await?;
+        let Some(first) = rows.first() else {
+            // the total is unknown beyond the last page
+            return Ok((Self::count_inner(select_statement, db).await?, Vec::new()));
+        };
+        let total = first.try_get::<i64>("", PAGINATE_TOTAL_COLUMN)? as u64;
+        let records = rows.iter().map(|row| D::from_query_result(row, "")).collect::<Result<Vec<_>, _>>()?;
+        Ok((total, records))
+    }
+
+    pub(self) async fn stream_query_inner<'a, C, D>(select_statement: Statement, db: &'a C) -> TardisResult<BoxStream<'a, TardisResult<D>>>
+    where
+        C: ConnectionTrait + StreamTrait + Send,
+        D: FromQueryResult + Send + 'a,
+    {
+        trace!("[Tardis.RelDBClient] Streaming sql {}, params:{:?}", select_statement.sql, select_statement.values);
+        let stream = D::find_by_statement(select_statement).stream(db).await?;
+        Ok(stream.map(|row| row.map_err(TardisError::from)).boxed())
+    }
+
     pub(self) async fn count_inner<C>(select_statement: &SelectStatement, db: &C) -> TardisResult<u64>
     where
         C: ConnectionTrait,
@@ -481,7 +656,7 @@ impl TardisRelDBClient {
             values: select_statement.values,
             db_backend: select_statement.db_backend,
         };
-        let count_result = CountResp::find_by_statement(count_statement).one(db).await?;
+        let count_result = observe_client("reldb", "count", CountResp::find_by_statement(count_statement).one(db)).await?;
         match count_result {
             Some(r) => TardisResult::Ok(r.count as u64),
             None => TardisResult::Err(TardisError::internal_error(
@@ -498,7 +673,7 @@ impl TardisRelDBClient {
     {
         trace!("[Tardis.RelDBClient] Inserting one model");
         model.fill_ctx(ctx, true);
-        let result = EntityTrait::insert(model).exec(db).await?;
+        let result = observe_client("reldb", "insert_one", EntityTrait::insert(model).exec(db)).await?;
         Ok(result)
     }
 
@@ -509,7 +684,7 @@ impl TardisRelDBClient {
     {
         trace!("[Tardis.RelDBClient] Inserting many models");
         models.iter_mut().for_each(|m| m.fill_ctx(ctx, true));
-        EntityTrait::insert_many(models).exec(db).await?;
+        observe_client("reldb", "insert_many", EntityTrait::insert_many(models).exec(db)).await?;
         Ok(())
     }
 
@@ -564,6 +739,20 @@ impl TardisRelDBClient {
         trace!("[Tardis.RelDBClient] Soft deleting custom");
         select.soft_delete_custom(custom_pk_field, db).await
     }
+
+    pub(self) async fn mark_deleted_inner<E, C>(condition: Condition, deleted: bool, db: &C) -> TardisResult<u64>
+    where
+        C: ConnectionTrait,
+        E: TardisSoftDeleteEntity,
+    {
+        trace!("[Tardis.RelDBClient] Marking deleted:{deleted}");
+        let mut update = E::update_many().col_expr(E::deleted_column(), Expr::value(deleted)).filter(condition).filter(E::deleted_column().eq(!deleted));
+        if let Some(update_time_column) = E::update_time_column() {
+            update = update.col_expr(update_time_column, Expr::value(TardisFuns::clock().now()));
+        }
+        let result = observe_client("reldb", "mark_deleted", update.exec(db)).await?;
+        Ok(result.rows_affected)
+    }
 }
 
 /// Database operation connection object / 数据库操作连接对象
@@ -896,6 +1085,91 @@ impl TardisRelDBlConnection {
         }
     }
 
+    /// Paging to get the total number of records and the records in one query / 在一次查询中分页获取总记录数及记录
+    ///
+    /// The total is counted by the window function `COUNT(1) OVER()` , so it's not suitable for the `DISTINCT` queries, use [`Self::paginate_dtos`] instead.
+    /// The page beyond the last page is empty, and its total is counted by another query.
+    ///
+    /// 总记录数通过窗口函数 `COUNT(1) OVER()` 统计，因此不适用于 `DISTINCT` 查询，请改用 [`Self::paginate_dtos`] .
+    /// 超出最后一页的页为空，其总记录数通过另一次查询统计.
+    ///
+    /// # Arguments
+    ///
+    ///  * `select_statement` - Statement of the query / 查询的Statement
+    ///  * `page_number` -  Current page number, starting from 1 / 当前页码，从1开始
+    ///  * `page_size` -  Number of records per page / 每页记录数
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::db::sea_orm::sea_query::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::TardisFuns;
+    /// let (total, records) = TardisFuns::reldb().conn().paginate::<IdResp>(&Query::select()
+    ///     .column(tardis_db_config::Column::Id)
+    ///     .from(tardis_db_config::Entity)
+    ///     .order_by(tardis_db_config::Column::Id, Order::Asc)
+    ///     .to_owned(),
+    ///     1,10
+    /// ).await.unwrap();
+    /// ```
+    pub async fn paginate<D>(&self, select_statement: &SelectStatement, page_number: u64, page_size: u64) -> TardisResult<(u64, Vec<D>)>
+    where
+        D: FromQueryResult,
+    {
+        if let Some(tx) = &self.tx {
+            TardisRelDBClient::paginate_inner(select_statement, page_number, page_size, tx).await
+        } else {
+            TardisRelDBClient::paginate_inner(select_statement, page_number, page_size, self.conn.as_ref()).await
+        }
+    }
+
+    /// Stream the records, returning a custom structure / 流式获取记录，返回自定义结构体
+    ///
+    /// The rows are fetched from the database as the stream is polled, so the large results, e.g. exports, aren't loaded into memory at once.
+    /// The connection is held until the stream is dropped.
+    ///
+    /// 行随流被轮询从数据库获取，因此大的结果集（如导出）不会被一次性加载到内存中. 连接会被持有直到流被drop.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::futures::StreamExt;
+    /// use tardis::db::sea_orm::sea_query::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let mut records = conn.stream_query::<IdResp>(&Query::select().column(tardis_db_config::Column::Id).from(tardis_db_config::Entity).to_owned()).await.unwrap();
+    /// while let Some(record) = records.next().await {
+    ///     println!("{}", record.unwrap().id);
+    /// }
+    /// ```
+    pub async fn stream_query<D>(&self, select_statement: &SelectStatement) -> TardisResult<BoxStream<'_, TardisResult<D>>>
+    where
+        D: FromQueryResult + Send + 'static,
+    {
+        if let Some(tx) = &self.tx {
+            TardisRelDBClient::stream_query_inner(tx.get_database_backend().build(select_statement), tx).await
+        } else {
+            TardisRelDBClient::stream_query_inner(self.conn.get_database_backend().build(select_statement), self.conn.as_ref()).await
+        }
+    }
+
+    /// Stream the records by the SQL, returning a custom structure / 通过SQL流式获取记录，返回自定义结构体
+    ///
+    /// # Arguments
+    ///
+    ///  * `sql` - sql of the query / 查询SQL
+    ///  * `params` - params of the query / 查询参数
+    pub async fn stream_query_by_sql<D>(&self, sql: &str, params: Vec<Value>) -> TardisResult<BoxStream<'_, TardisResult<D>>>
+    where
+        D: FromQueryResult + Send + 'static,
+    {
+        if let Some(tx) = &self.tx {
+            TardisRelDBClient::stream_query_inner(Statement::from_sql_and_values(tx.get_database_backend(), sql, params), tx).await
+        } else {
+            TardisRelDBClient::stream_query_inner(Statement::from_sql_and_values(self.conn.get_database_backend(), sql, params), self.conn.as_ref()).await
+        }
+    }
+
     /// Get number of records / 获取记录数量
     ///
     /// # Arguments
@@ -1089,6 +1363,38 @@ impl TardisRelDBlConnection {
         }
     }
 
+    /// Insert a record with the current context, see [`TardisContext::current`] / 以当前上下文插入一条记录，见 [`TardisContext::current`]
+    ///
+    /// An unauthorized error is returned if there is no current context.
+    ///
+    /// 没有当前上下文时返回未授权错误.
+    pub async fn insert_one_with_current_ctx<T>(&self, model: T) -> TardisResult<InsertResult<T>>
+    where
+        T: TardisActiveModel,
+    {
+        self.insert_one(model, &Self::current_ctx()?).await
+    }
+
+    /// Insert multiple records with the current context, see [`TardisContext::current`] / 以当前上下文插入多条记录，见 [`TardisContext::current`]
+    pub async fn insert_many_with_current_ctx<T>(&self, models: Vec<T>) -> TardisResult<()>
+    where
+        T: TardisActiveModel,
+    {
+        self.insert_many(models, &Self::current_ctx()?).await
+    }
+
+    /// Update a record with the current context, see [`TardisContext::current`] / 以当前上下文更新一条记录，见 [`TardisContext::current`]
+    pub async fn update_one_with_current_ctx<T>(&self, model: T) -> TardisResult<()>
+    where
+        T: TardisActiveModel,
+    {
+        self.update_one(model, &Self::current_ctx()?).await
+    }
+
+    fn current_ctx() -> TardisResult<TardisContext> {
+        TardisContext::current().ok_or_else(|| TardisError::unauthorized("[Tardis.RelDBClient] No context in the current task", "401-tardis-reldb-context-not-found"))
+    }
+
     /// Update multiple records / 更新多条记录
     ///
     /// # Arguments
@@ -1174,6 +1480,74 @@ impl TardisRelDBlConnection {
             TardisRelDBClient::soft_delete_custom_inner(select, custom_pk_field, self.conn.as_ref()).await
         }
     }
+
+    /// Mark the records deleted by the flag, returns the number of the records marked / 通过标记将记录标记为已删除，返回被标记的记录数
+    ///
+    /// # Arguments
+    ///
+    ///  * `condition` -  Condition of the records / 记录的条件
+    pub async fn mark_deleted<E>(&self, condition: Condition) -> TardisResult<u64>
+    where
+        E: TardisSoftDeleteEntity,
+    {
+        if let Some(tx) = &self.tx {
+            TardisRelDBClient::mark_deleted_inner::<E, _>(condition, true, tx).await
+        } else {
+            TardisRelDBClient::mark_deleted_inner::<E, _>(condition, true, self.conn.as_ref()).await
+        }
+    }
+
+    /// Restore the records marked deleted, returns the number of the records restored / 恢复被标记为已删除的记录，返回被恢复的记录数
+    ///
+    /// # Arguments
+    ///
+    ///  * `condition` -  Condition of the records / 记录的条件
+    pub async fn restore_deleted<E>(&self, condition: Condition) -> TardisResult<u64>
+    where
+        E: TardisSoftDeleteEntity,
+    {
+        if let Some(tx) = &self.tx {
+            TardisRelDBClient::mark_deleted_inner::<E, _>(condition, false, tx).await
+        } else {
+            TardisRelDBClient::mark_deleted_inner::<E, _>(condition, false, self.conn.as_ref()).await
+        }
+    }
+}
+
+/// Entity soft-deleted by the `deleted` flag / 通过 `deleted` 标记软删除的实体
+///
+/// Different from [`TardisSeaORMExtend::soft_delete`] which moves the records into the `tardis_del_record` table,
+/// the records are kept in the table with the flag, so the queries should start with [`Self::find_alive`] instead of `find` to exclude them.
+/// It's implemented by [`TardisCreateEntity`](crate::TardisCreateEntity) for the field with `#[fill_ctx(deleted)]` .
+///
+/// 与将记录移入 `tardis_del_record` 表的 [`TardisSeaORMExtend::soft_delete`] 不同，记录带标记保留在表中，
+/// 因此查询应使用 [`Self::find_alive`] 而非 `find` 以排除已删除记录. [`TardisCreateEntity`](crate::TardisCreateEntity) 会为带 `#[fill_ctx(deleted)]` 的字段实现该trait.
+///
+/// # Examples
+/// ```ignore
+/// let alive = todos::Entity::find_alive().filter(todos::Column::Done.eq(false)).all(conn.raw_conn()).await?;
+/// // include the deleted records explicitly
+/// let all = todos::Entity::find_with_deleted().all(conn.raw_conn()).await?;
+/// conn.mark_deleted::<todos::Entity>(Condition::all().add(todos::Column::Id.eq(1))).await?;
+/// ```
+pub trait TardisSoftDeleteEntity: EntityTrait {
+    /// The boolean column of the flag / 标记的布尔列
+    fn deleted_column() -> Self::Column;
+
+    /// The column updated on deletion and restoration / 删除及恢复时更新的列
+    fn update_time_column() -> Option<Self::Column> {
+        None
+    }
+
+    /// Find the records not deleted / 查找未删除的记录
+    fn find_alive() -> Select<Self> {
+        Self::find().filter(Self::deleted_column().eq(false))
+    }
+
+    /// Find the records including the deleted / 查找包括已删除在内的记录
+    fn find_with_deleted() -> Select<Self> {
+        Self::find()
+    }
 }
 
 #[async_trait]
@@ -1215,6 +1589,7 @@ where
                 record_id: Set(delete_entity.record_id.to_string()),
                 content: Set(delete_entity.content),
                 creator: Set(delete_user.to_string()),
+                create_time: Set(TardisFuns::clock().now()),
                 ..Default::default()
             }
             .insert(db)
@@ -1230,16 +1605,8 @@ where
         let db_backend: DbBackend = db.get_database_backend();
 
         let sql = self.build(db_backend).sql.replace('?', "''");
-        let ast = match Parser::parse_sql(
-            match db.get_database_backend() {
-                DatabaseBackend::MySql => &MySqlDialect {},
-                DatabaseBackend::Postgres => &PostgreSqlDialect {},
-                DatabaseBackend::Sqlite => &SQLiteDialect {},
-            },
-            &sql,
-        )?
-        .pop()
-        {
+        let dialect = TardisDBDialect::from(db_backend);
+        let ast = match Parser::parse_sql(dialect.sql_parser_dialect().as_ref(), &sql)?.pop() {
             Some(ast) => ast,
             None => {
                 return Err(TardisError::format_error(
@@ -1305,21 +1672,7 @@ where
         }
         let statement = Statement::from_sql_and_values(
             db_backend,
-            match db_backend {
-                DatabaseBackend::Postgres => format!(
-                    "DELETE FROM {} WHERE {} IN ({})",
-                    table_name,
-                    custom_pk_field,
-                    ids.iter().enumerate().map(|(idx, _)| format!("${}", idx + 1)).collect::<Vec<String>>().join(",")
-                ),
-                _ => format!(
-                    "DELETE FROM {} WHERE {} IN ({})",
-                    table_name,
-                    custom_pk_field,
-                    ids.iter().map(|_| "?").collect::<Vec<&str>>().join(",")
-                ),
-            }
-            .as_str(),
+            &format!("DELETE FROM {} WHERE {} IN ({})", table_name, custom_pk_field, dialect.placeholders(ids.len())),
             ids,
         );
         let result = db.execute(statement).await;
@@ -1335,6 +1688,12 @@ where
 pub trait TardisActiveModel: ActiveModelBehavior {
     /// Fill TardisContext / 填充TardisContext
     ///
+    /// It's called on [`TardisRelDBlConnection::insert_one`] , [`TardisRelDBlConnection::insert_many`] and [`TardisRelDBlConnection::update_one`] ,
+    /// and generated by [`TardisCreateEntity`](crate::TardisCreateEntity) for the fields with `#[fill_ctx(..)]` .
+    ///
+    /// 在 [`TardisRelDBlConnection::insert_one`] 、 [`TardisRelDBlConnection::insert_many`] 及 [`TardisRelDBlConnection::update_one`] 时调用，
+    /// [`TardisCreateEntity`](crate::TardisCreateEntity) 会为带 `#[fill_ctx(..)]` 的字段生成.
+    ///
     /// # Arguments
     ///
     ///  * `ctx` -  TardisContext
@@ -1454,54 +1813,14 @@ pub trait TardisActiveModel: ActiveModelBehavior {
 
     /// Create functions / 创建函数
     fn create_function_sqls(db: DbBackend, table_name: &str, update_time_field: Option<&str>, compatible_type: CompatibleType) -> Vec<String> {
-        if db == DbBackend::Postgres {
-            if let Some(update_time_field) = update_time_field {
-                return Self::create_function_postgresql_auto_update_time(table_name, update_time_field, compatible_type);
-            }
+        match update_time_field {
+            Some(update_time_field) => TardisDBDialect::from(db).auto_update_time_sqls(table_name, update_time_field, compatible_type),
+            None => vec![],
         }
-        vec![]
     }
 
     fn create_function_postgresql_auto_update_time(table_name: &str, update_time_field: &str, compatible_type: CompatibleType) -> Vec<String> {
-        match compatible_type {
-            CompatibleType::None => {
-                vec![
-                    format!(
-                        r###"CREATE OR REPLACE FUNCTION TARDIS_AUTO_UPDATE_TIME_{}()
-        RETURNS TRIGGER AS $$
-        BEGIN
-            NEW.{} = now();
-            RETURN NEW;
-        END;
-        $$ language 'plpgsql';"###,
-                        update_time_field.replace('-', "_"),
-                        update_time_field
-                    ),
-                    format!(
-                        r###"CREATE OR REPLACE TRIGGER TARDIS_AUTO_UPDATE_TIME_ON
-            BEFORE UPDATE
-            ON
-                {}
-            FOR EACH ROW
-        EXECUTE PROCEDURE TARDIS_AUTO_UPDATE_TIME_{}();"###,
-                        table_name,
-                        update_time_field.replace('-', "_")
-                    ),
-                ]
-            }
-            CompatibleType::Oracle => vec![format!(
-                r###"CREATE OR REPLACE TRIGGER TARDIS_AUTO_UPDATE_TIME_ON
-            BEFORE UPDATE
-            ON
-                {}
-            FOR EACH ROW
-            BEGIN
-                NEW.{}= now();
-                RETURN NEW;
-            END;"###,
-                table_name, update_time_field
-            )],
-        }
+        TardisDBDialect::Postgres.auto_update_time_sqls(table_name, update_time_field, compatible_type)
     }
 }
 
@@ -0,0 +1 @@
+pub mod discovery_client;
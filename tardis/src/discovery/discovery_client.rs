@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::{AppConfig, DiscoveryConfig};
+use crate::config::config_nacos::nacos_client::{NacosClient, NacosClientError, NacosInstance, NacosServiceDescriptor};
+
+const DEFAULT_GROUP: &str = "DEFAULT_GROUP";
+const DEFAULT_CLUSTER: &str = "DEFAULT";
+
+/// Service discovery client / 服务发现客户端
+///
+/// # Examples
+/// ```ignore
+/// use tardis::TardisFuns;
+/// let instance = TardisFuns::discovery().select_instance("todo").await?;
+/// let resp = TardisFuns::web_client().get_to_str(&format!("{}/todo/1", instance.url()), None).await?;
+/// ```
+pub struct TardisDiscoveryClient {
+    config: DiscoveryConfig,
+    service_name: String,
+    nacos_client: NacosClient,
+    /// the registered instance and its heartbeat task / 已注册的实例及其心跳任务
+    registered: Mutex<Option<(NacosInstance, JoinHandle<()>)>>,
+    /// instances by service name, with the fetched time / 按服务名缓存的实例及获取时间
+    instances: RwLock<HashMap<String, (Instant, Vec<TardisServiceInstance>)>>,
+    next: AtomicUsize,
+}
+
+/// Instance of a service / 服务实例
+#[derive(Debug, Clone, PartialEq)]
+pub struct TardisServiceInstance {
+    pub ip: String,
+    pub port: u16,
+    pub weight: f64,
+    pub metadata: HashMap<String, String>,
+}
+
+impl TardisServiceInstance {
+    /// Base url of the instance, `https` is used if the metadata `secure` is `true` / 实例的基础地址，元数据 `secure` 为 `true` 时使用 `https`
+    pub fn url(&self) -> String {
+        let protocol = if self.metadata.get("secure").map(|secure| secure == "true").unwrap_or(false) {
+            "https"
+        } else {
+            "http"
+        };
+        format!("{protocol}://{}:{}", self.ip, self.port)
+    }
+}
+
+impl TardisDiscoveryClient {
+    pub async fn init(config: &DiscoveryConfig, app: &AppConfig) -> TardisResult<TardisDiscoveryClient> {
+        info!("[Tardis.Discovery] Initializing, kind:{}, url:{}", config.kind, config.url);
+        if !config.kind.eq_ignore_ascii_case("nacos") {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.Discovery] The kind of discovery [{}] is not supported, supports [nacos]", config.kind),
+                "406-tardis-discovery-kind-not-supported",
+            ));
+        }
+        let service_name = config.service_name.clone().unwrap_or_else(|| app.id.clone());
+        if service_name.is_empty() {
+            return Err(TardisError::format_error(
+                "[Tardis.Discovery] The [fw.discovery.service_name] or [fw.app.id] must be set",
+                "406-tardis-discovery-service-name-missing",
+            ));
+        }
+        let mut nacos_client = NacosClient::new(config.url.as_str().trim_end_matches('/'));
+        if !config.username.is_empty() {
+            nacos_client.login(&config.username, &config.password).await.map_err(|error| nacos_error(NacosClientError::from(error)))?;
+        }
+        info!("[Tardis.Discovery] Initialized, service name:{}", service_name);
+        Ok(TardisDiscoveryClient {
+            config: config.clone(),
+            service_name,
+            nacos_client,
+            registered: Mutex::new(None),
+            instances: RwLock::new(HashMap::new()),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// The service name of the current application / 当前应用的服务名
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    fn service_descriptor<'a>(&'a self, service_name: &'a str) -> NacosServiceDescriptor<'a> {
+        NacosServiceDescriptor {
+            service_name,
+            group_name: self.config.group.as_deref().unwrap_or(DEFAULT_GROUP),
+            namespace_id: self.config.namespace.as_deref(),
+        }
+    }
+
+    /// Register the current application instance and keep it alive by heartbeats, the previous registered instance is deregistered
+    /// / 注册当前应用实例并通过心跳保活，之前注册的实例会被注销
+    ///
+    /// The heartbeat re-registers the instance if it's not found in the registry, even if this registration fails.
+    ///
+    /// 即使本次注册失败，心跳在注册中心中找不到实例时也会重新注册.
+    pub async fn register(&self, ip: IpAddr, port: u16, metadata: HashMap<String, String>) -> TardisResult<()> {
+        if let Err(error) = self.deregister().await {
+            warn!("[Tardis.Discovery] Deregister the previous instance error: {error}");
+        }
+        let mut instance_metadata = self.config.metadata.clone();
+        instance_metadata.extend(metadata);
+        let instance = NacosInstance {
+            ip: ip.to_string(),
+            port,
+            weight: self.config.weight,
+            healthy: true,
+            enabled: true,
+            ephemeral: true,
+            cluster_name: DEFAULT_CLUSTER.to_string(),
+            metadata: instance_metadata,
+        };
+        info!("[Tardis.Discovery] Register instance {}:{} of service {}", instance.ip, instance.port, self.service_name);
+        let heartbeat = self.start_heartbeat(instance.clone());
+        *self.registered.lock().await = Some((instance.clone(), heartbeat));
+        self.nacos_client.register_instance(&self.service_descriptor(&self.service_name), &instance).await.map_err(nacos_error)?;
+        Ok(())
+    }
+
+    /// Register the web server, the ip and port are taken from the discovery config or the web server config
+    /// / 注册web服务，ip及端口取自服务发现配置或web服务配置
+    #[cfg(feature = "web-server")]
+    pub(crate) async fn register_web_server(&self, config: &crate::config::config_dto::WebServerCommonConfig) -> TardisResult<()> {
+        let ip = match self.config.ip.or(config.access_host).unwrap_or(config.host) {
+            ip if ip.is_unspecified() => self.local_ip()?,
+            ip => ip,
+        };
+        let port = self.config.port.or(config.access_port).unwrap_or(config.port);
+        let mut metadata = HashMap::new();
+        if config.tls_key.is_some() {
+            metadata.insert("secure".to_string(), "true".to_string());
+        }
+        self.register(ip, port, metadata).await
+    }
+
+    /// The local ip used to connect the registry / 用于连接注册中心的本机ip
+    #[cfg(feature = "web-server")]
+    fn local_ip(&self) -> TardisResult<IpAddr> {
+        let registry = self.config.url.socket_addrs(|| None)?.into_iter().next().ok_or_else(|| {
+            TardisError::format_error(
+                &format!("[Tardis.Discovery] Can't resolve the registry address {}", self.config.url),
+                "406-tardis-discovery-ip-missing",
+            )
+        })?;
+        let socket = std::net::UdpSocket::bind(if registry.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" })?;
+        socket.connect(registry)?;
+        Ok(socket.local_addr()?.ip())
+    }
+
+    fn start_heartbeat(&self, instance: NacosInstance) -> JoinHandle<()> {
+        let nacos_client = self.nacos_client.clone();
+        let service_name = self.service_name.clone();
+        let group_name = self.config.group.clone().unwrap_or_else(|| DEFAULT_GROUP.to_string());
+        let namespace_id = self.config.namespace.clone();
+        let interval = Duration::from_secs(self.config.heartbeat_interval_sec.max(1));
+        tokio::spawn(async move {
+            let service = NacosServiceDescriptor {
+                service_name: &service_name,
+                group_name: &group_name,
+                namespace_id: namespace_id.as_deref(),
+            };
+            loop {
+                tokio::time::sleep(interval).await;
+                match nacos_client.send_instance_beat(&service, &instance).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        warn!(
+                            "[Tardis.Discovery] Instance {}:{} of service {} not found, re-registering",
+                            instance.ip, instance.port, service_name
+                        );
+                        if let Err(error) = nacos_client.register_instance(&service, &instance).await {
+                            warn!("[Tardis.Discovery] Re-register instance error: {error}");
+                        }
+                    }
+                    Err(error) => warn!("[Tardis.Discovery] Send heartbeat error: {error}"),
+                }
+            }
+        })
+    }
+
+    /// Deregister the current application instance if it's registered / 注销当前应用实例（如已注册）
+    pub async fn deregister(&self) -> TardisResult<()> {
+        let Some((instance, heartbeat)) = self.registered.lock().await.take() else {
+            return Ok(());
+        };
+        heartbeat.abort();
+        info!("[Tardis.Discovery] Deregister instance {}:{} of service {}", instance.ip, instance.port, self.service_name);
+        self.nacos_client.deregister_instance(&self.service_descriptor(&self.service_name), &instance).await.map_err(nacos_error)?;
+        Ok(())
+    }
+
+    /// The healthy and enabled instances of the service, cached for `instances_cache_sec` / 服务的健康且启用的实例，缓存 `instances_cache_sec`
+    pub async fn instances(&self, service_name: &str) -> TardisResult<Vec<TardisServiceInstance>> {
+        if let Some((fetched_at, instances)) = self.instances.read().map_err(lock_error)?.get(service_name) {
+            if fetched_at.elapsed() < Duration::from_secs(self.config.instances_cache_sec) {
+                return Ok(instances.clone());
+            }
+        }
+        debug!("[Tardis.Discovery] Fetch instances of service {}", service_name);
+        let instances = self
+            .nacos_client
+            .list_instances(&self.service_descriptor(service_name), true)
+            .await
+            .map_err(nacos_error)?
+            .into_iter()
+            .filter(|instance| instance.healthy && instance.enabled)
+            .map(|instance| TardisServiceInstance {
+                ip: instance.ip,
+                port: instance.port,
+                weight: instance.weight,
+                metadata: instance.metadata,
+            })
+            .collect::<Vec<_>>();
+        self.instances.write().map_err(lock_error)?.insert(service_name.to_string(), (Instant::now(), instances.clone()));
+        Ok(instances)
+    }
+
+    /// Select an instance of the service by round robin, the instances whose weight is not positive are skipped
+    /// / 轮询选择服务的一个实例，跳过权重不为正数的实例
+    pub async fn select_instance(&self, service_name: &str) -> TardisResult<TardisServiceInstance> {
+        let instances = self.instances(service_name).await?.into_iter().filter(|instance| instance.weight > 0.0).collect::<Vec<_>>();
+        if instances.is_empty() {
+            return Err(TardisError::not_found(
+                &format!("[Tardis.Discovery] No available instance of service {service_name}"),
+                "404-tardis-discovery-instance-not-exist",
+            ));
+        }
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % instances.len();
+        Ok(instances[idx].clone())
+    }
+}
+
+impl Drop for TardisDiscoveryClient {
+    fn drop(&mut self) {
+        if let Some((_, heartbeat)) = self.registered.get_mut().take() {
+            heartbeat.abort();
+        }
+    }
+}
+
+fn nacos_error(error: NacosClientError) -> TardisError {
+    TardisError::wrap(&format!("[Tardis.Discovery] Nacos request error: {error}"), "-1-tardis-discovery-error")
+}
+
+fn lock_error<T>(error: std::sync::PoisonError<T>) -> TardisError {
+    TardisError::internal_error(&format!("[Tardis.Discovery] {error}"), "")
+}
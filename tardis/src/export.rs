@@ -0,0 +1,148 @@
+//! CSV / Excel import and export / CSV / Excel导入导出
+//!
+//! [`TardisExporter`](exporter::TardisExporter) converts the serializable rows into CSV or XLSX files or web responses,
+//! [`TardisImporter`](importer::TardisImporter) deserializes the uploaded files into typed rows with the errors of each row.
+//! The columns map the fields to the headers, which are localized by [`TardisLocale`](crate::basic::locale::TardisLocale) .
+//!
+//! [`TardisExporter`](exporter::TardisExporter) 将可序列化的行转换为CSV或XLSX文件或Web响应，
+//! [`TardisImporter`](importer::TardisImporter) 将上传的文件反序列化为类型化的行，并附带每行的错误.
+//! 列将字段映射到表头，表头通过 [`TardisLocale`](crate::basic::locale::TardisLocale) 本地化.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::export::{exporter::TardisExporter, importer::TardisImporter, TardisExportColumn};
+//! let columns = [TardisExportColumn::new("code", "Code").locale_code("todo-export-code"), TardisExportColumn::new("priority", "Priority")];
+//! let resp = TardisExporter::new().columns(columns.clone()).lang("zh-cn").csv_response("todos.csv", todos)?;
+//! let todos = TardisImporter::new().columns(columns).parse_upload::<TodoAddReq>(upload).await?.into_rows()?;
+//! ```
+#[cfg(not(feature = "export-xlsx"))]
+use crate::basic::error::TardisError;
+use crate::basic::locale::TardisLocale;
+use crate::TardisFuns;
+
+pub mod exporter;
+pub mod importer;
+
+pub const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+pub const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Format of the file / 文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TardisExportFormat {
+    Csv,
+    /// Requires the `export-xlsx` feature / 需启用 `export-xlsx` 特性
+    Xlsx,
+}
+
+impl TardisExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TardisExportFormat::Csv => CSV_CONTENT_TYPE,
+            TardisExportFormat::Xlsx => XLSX_CONTENT_TYPE,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TardisExportFormat::Csv => "csv",
+            TardisExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    /// Detect the format by the extension of the file name / 根据文件名的扩展名识别格式
+    pub fn from_file_name(file_name: &str) -> Option<TardisExportFormat> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_lowercase().as_str() {
+            "csv" => Some(TardisExportFormat::Csv),
+            "xlsx" => Some(TardisExportFormat::Xlsx),
+            _ => None,
+        }
+    }
+
+    /// Detect the format by the content type, the parameters are ignored / 根据内容类型识别格式，忽略参数
+    pub fn from_content_type(content_type: &str) -> Option<TardisExportFormat> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        if mime == "text/csv" {
+            Some(TardisExportFormat::Csv)
+        } else if mime == XLSX_CONTENT_TYPE {
+            Some(TardisExportFormat::Xlsx)
+        } else {
+            None
+        }
+    }
+}
+
+/// Mapping of a field to a column / 字段到列的映射
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TardisExportColumn {
+    /// Name of the field, the nested fields are separated by `.` in the export, e.g. `owner.name`
+    /// / 字段名，导出时嵌套字段以 `.` 分隔，如 `owner.name`
+    pub field: String,
+    /// Header of the column, also the default message of the localization / 列的表头，同时为本地化的默认消息
+    pub header: String,
+    /// Code to localize the header / 用于本地化表头的错误码
+    pub locale_code: Option<String>,
+}
+
+impl TardisExportColumn {
+    pub fn new(field: impl Into<String>, header: impl Into<String>) -> Self {
+        TardisExportColumn {
+            field: field.into(),
+            header: header.into(),
+            locale_code: None,
+        }
+    }
+
+    pub fn locale_code(mut self, locale_code: impl Into<String>) -> Self {
+        self.locale_code = Some(locale_code.into());
+        self
+    }
+
+    /// The header localized in `lang` or the default language / 以 `lang` 或默认语言本地化的表头
+    pub fn localized_header(&self, lang: Option<&str>) -> String {
+        let Some(locale_code) = &self.locale_code else {
+            return self.header.clone();
+        };
+        let lang = lang.map(str::to_string).or_else(|| TardisFuns::fw_config_opt().and_then(|fw_config| fw_config.app.default_lang.clone()));
+        match lang {
+            Some(lang) => TardisLocale::get_message(locale_code, &self.header, &lang).unwrap_or_else(|_| self.header.clone()),
+            None => self.header.clone(),
+        }
+    }
+}
+
+impl<F: Into<String>, H: Into<String>> From<(F, H)> for TardisExportColumn {
+    fn from((field, header): (F, H)) -> Self {
+        TardisExportColumn::new(field, header)
+    }
+}
+
+#[cfg(not(feature = "export-xlsx"))]
+pub(crate) fn xlsx_not_enabled() -> TardisError {
+    TardisError::custom(
+        "501-tardis-export-format-not-supported",
+        "[Tardis.Export] The XLSX format isn't enabled, see the export-xlsx feature",
+        "",
+    )
+}
+
+// the leading characters of the spreadsheet formulas
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Prefix the text starting like a formula by `'` , so the spreadsheets don't evaluate it (the CSV injection)
+/// / 以 `'` 前缀类似公式开头的文本，使电子表格不对其求值（CSV注入）
+pub(crate) fn escape_formula(text: String) -> String {
+    if text.starts_with(&FORMULA_PREFIXES[..]) {
+        format!("'{text}")
+    } else {
+        text
+    }
+}
+
+/// Reverse [`escape_formula`] / [`escape_formula`] 的逆操作
+pub(crate) fn unescape_formula(text: &str) -> &str {
+    match text.strip_prefix('\'') {
+        Some(formula) if formula.starts_with(&FORMULA_PREFIXES[..]) => formula,
+        _ => text,
+    }
+}
@@ -0,0 +1,378 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::export::{escape_formula, TardisExportColumn};
+use crate::TardisFuns;
+
+const CSV_BOM: &[u8] = b"\xEF\xBB\xBF";
+/// Default rows of a chunk / 默认每个分块的行数
+pub const EXPORT_DEFAULT_CHUNK_SIZE: usize = 1000;
+// the worksheet has 1048576 rows at most, including the header
+#[cfg(feature = "export-xlsx")]
+const XLSX_MAX_ROWS: usize = 1048575;
+
+/// Exporter of the rows / 行导出器
+///
+/// The columns are the fields of the first row if not configured, the nested values are exported as JSON.
+/// The strings starting with `=` , `+` , `-` , `@` , tab or carriage return are prefixed by `'` so they are not evaluated as formulas,
+/// and [`TardisImporter`](crate::export::importer::TardisImporter) removes the prefix.
+/// The CSV files start with the UTF-8 BOM by default, so Excel detects the encoding of the non-ASCII headers.
+///
+/// 未配置列时使用第一行的字段，嵌套的值导出为JSON. 以 `=` 、 `+` 、 `-` 、 `@` 、制表符或回车开头的字符串以 `'` 为前缀，避免被作为公式求值，
+/// [`TardisImporter`](crate::export::importer::TardisImporter) 会移除该前缀. CSV文件默认以UTF-8 BOM开头，以便Excel识别非ASCII表头的编码.
+#[derive(Debug, Clone)]
+pub struct TardisExporter {
+    columns: Vec<TardisExportColumn>,
+    lang: Option<String>,
+    chunk_size: usize,
+    bom: bool,
+    #[cfg(feature = "export-xlsx")]
+    sheet_name: Option<String>,
+}
+
+impl Default for TardisExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TardisExporter {
+    pub fn new() -> Self {
+        TardisExporter {
+            columns: Vec::new(),
+            lang: None,
+            chunk_size: EXPORT_DEFAULT_CHUNK_SIZE,
+            bom: true,
+            #[cfg(feature = "export-xlsx")]
+            sheet_name: None,
+        }
+    }
+
+    /// Export the field as the column / 将字段导出为列
+    pub fn column(mut self, field: impl Into<String>, header: impl Into<String>) -> Self {
+        self.columns.push(TardisExportColumn::new(field, header));
+        self
+    }
+
+    pub fn columns(mut self, columns: impl IntoIterator<Item = impl Into<TardisExportColumn>>) -> Self {
+        self.columns.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Language of the headers, the default language is used if it's not set / 表头的语言，未设置时使用默认语言
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Language of the headers by the `Accept-Language` header of the request / 根据请求的 `Accept-Language` 头设置表头的语言
+    #[cfg(feature = "web-server")]
+    pub fn accept_language(mut self, accept_language: &str) -> Self {
+        if let Some(lang) = crate::web::uniform_error_mw::preferred_lang(accept_language) {
+            self.lang = Some(lang);
+        }
+        self
+    }
+
+    /// Rows generated and sent at a time by the streaming responses / 流式响应每次生成并发送的行数
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Whether the CSV files start with the UTF-8 BOM / CSV文件是否以UTF-8 BOM开头
+    pub fn bom(mut self, bom: bool) -> Self {
+        self.bom = bom;
+        self
+    }
+
+    /// Name of the worksheet / 工作表名称
+    #[cfg(feature = "export-xlsx")]
+    pub fn sheet_name(mut self, sheet_name: impl Into<String>) -> Self {
+        self.sheet_name = Some(sheet_name.into());
+        self
+    }
+
+    /// Export to CSV / 导出为CSV
+    pub fn csv<T: Serialize>(&self, rows: &[T]) -> TardisResult<Vec<u8>> {
+        let mut columns = None;
+        let mut data = Vec::new();
+        for chunk in rows.chunks(self.chunk_size) {
+            data.extend(self.csv_chunk(&mut columns, chunk)?);
+        }
+        if columns.is_none() {
+            data.extend(self.csv_chunk::<T>(&mut columns, &[])?);
+        }
+        Ok(data)
+    }
+
+    /// Export a chunk of the rows to CSV, the BOM and the header are written before the first chunk
+    /// / 将一块行导出为CSV，在第一块前写入BOM及表头
+    fn csv_chunk<T: Serialize>(&self, columns: &mut Option<Vec<TardisExportColumn>>, rows: &[T]) -> TardisResult<Vec<u8>> {
+        let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+        let mut data = Vec::new();
+        if columns.is_none() {
+            let resolved = self.resolve_columns(rows.first())?;
+            if self.bom {
+                data.extend_from_slice(CSV_BOM);
+            }
+            // the csv writer writes `""` for an empty record
+            if !resolved.is_empty() {
+                writer.write_record(resolved.iter().map(|column| column.localized_header(self.lang.as_deref())))?;
+            }
+            *columns = Some(resolved);
+        }
+        for row in rows {
+            let row = to_json(row)?;
+            writer.write_record(columns.iter().flatten().map(|column| cell_text(cell(&row, &column.field))))?;
+        }
+        data.extend(writer.into_inner().map_err(|error| error.into_error())?);
+        Ok(data)
+    }
+
+    /// Export to XLSX / 导出为XLSX
+    #[cfg(feature = "export-xlsx")]
+    pub fn xlsx<T: Serialize>(&self, rows: &[T]) -> TardisResult<Vec<u8>> {
+        use rust_xlsxwriter::{Format, Workbook};
+        if rows.len() > XLSX_MAX_ROWS {
+            return Err(TardisError::custom(
+                "400-tardis-export-too-many-rows",
+                &format!("[Tardis.Export] The worksheet supports {XLSX_MAX_ROWS} rows at most, got {}", rows.len()),
+                "",
+            ));
+        }
+        let columns = self.resolve_columns(rows.first())?;
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        if let Some(sheet_name) = &self.sheet_name {
+            worksheet.set_name(sheet_name)?;
+        }
+        let header_format = Format::new().set_bold();
+        for (col, column) in columns.iter().enumerate() {
+            worksheet.write_string_with_format(0, col as u16, column.localized_header(self.lang.as_deref()), &header_format)?;
+        }
+        for (index, row) in rows.iter().enumerate() {
+            let row = to_json(row)?;
+            let index = index as u32 + 1;
+            for (col, column) in columns.iter().enumerate() {
+                let col = col as u16;
+                match cell(&row, &column.field) {
+                    None | Some(Value::Null) => continue,
+                    Some(Value::Bool(value)) => worksheet.write_boolean(index, col, *value)?,
+                    // the large integers (e.g. the ids) lose the precision as the numbers of Excel
+                    Some(Value::Number(value)) => match value.as_f64() {
+                        Some(number) if value.is_f64() || number.abs() < 1e15 => worksheet.write_number(index, col, number)?,
+                        _ => worksheet.write_string(index, col, value.to_string())?,
+                    },
+                    Some(Value::String(value)) => worksheet.write_string(index, col, escape_formula(value.clone()))?,
+                    Some(value) => worksheet.write_string(index, col, value.to_string())?,
+                };
+            }
+        }
+        Ok(workbook.save_to_buffer()?)
+    }
+
+    fn resolve_columns<T: Serialize>(&self, first: Option<&T>) -> TardisResult<Vec<TardisExportColumn>> {
+        if !self.columns.is_empty() {
+            return Ok(self.columns.clone());
+        }
+        let Some(first) = first else {
+            return Ok(Vec::new());
+        };
+        // the csv serializer keeps the order of the struct fields, but doesn't support the nested values
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        if writer.serialize(first).is_ok() {
+            if let Ok(data) = writer.into_inner() {
+                if let Ok(headers) = csv::Reader::from_reader(data.as_slice()).headers() {
+                    return Ok(headers.iter().map(|field| TardisExportColumn::new(field, field)).collect());
+                }
+            }
+        }
+        Ok(to_json(first)?.as_object().map(|row| row.keys().map(|field| TardisExportColumn::new(field, field)).collect()).unwrap_or_default())
+    }
+}
+
+fn to_json<T: Serialize>(row: &T) -> TardisResult<Value> {
+    let row = TardisFuns::json.obj_to_json(row)?;
+    if !row.is_object() {
+        return Err(TardisError::custom(
+            "406-tardis-export-row-not-object",
+            "[Tardis.Export] The row must be serialized as an object",
+            "",
+        ));
+    }
+    Ok(row)
+}
+
+fn cell<'a>(row: &'a Value, field: &str) -> Option<&'a Value> {
+    row.get(field).or_else(|| {
+        if field.contains('.') {
+            row.pointer(&format!("/{}", field.replace('.', "/")))
+        } else {
+            None
+        }
+    })
+}
+
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(value)) => escape_formula(value.clone()),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[cfg(feature = "web-server")]
+mod web {
+    use futures::{Stream, StreamExt};
+    use poem::http::StatusCode;
+    use poem::{Body, IntoResponse, Response};
+    use poem_openapi::payload::{Attachment, AttachmentType};
+    use serde::Serialize;
+    use tracing::error;
+
+    use super::TardisExporter;
+    #[cfg(feature = "export-xlsx")]
+    use crate::basic::error::TardisError;
+    use crate::basic::result::TardisResult;
+    use crate::export::TardisExportFormat;
+
+    /// Response of the exported file / 导出文件的响应
+    ///
+    /// Return it from the raw handlers, or convert it by [`TardisExportResponse::into_attachment`] for the OpenAPI handlers.
+    ///
+    /// 从原始处理函数返回，或通过 [`TardisExportResponse::into_attachment`] 转换后用于OpenAPI处理函数.
+    pub struct TardisExportResponse {
+        file_name: String,
+        format: TardisExportFormat,
+        body: Body,
+    }
+
+    impl TardisExportResponse {
+        pub fn new(file_name: impl Into<String>, format: TardisExportFormat, body: Body) -> Self {
+            TardisExportResponse {
+                file_name: file_name.into(),
+                format,
+                body,
+            }
+        }
+
+        pub fn file_name(&self) -> &str {
+            &self.file_name
+        }
+
+        pub fn format(&self) -> TardisExportFormat {
+            self.format
+        }
+
+        /// Convert to the attachment of the OpenAPI handlers, whose content type is `application/octet-stream`
+        /// / 转换为OpenAPI处理函数的附件，其内容类型为 `application/octet-stream`
+        pub fn into_attachment(self) -> Attachment<Body> {
+            Attachment::new(self.body).attachment_type(AttachmentType::Attachment).filename(self.file_name)
+        }
+    }
+
+    impl IntoResponse for TardisExportResponse {
+        fn into_response(self) -> Response {
+            Response::builder().status(StatusCode::OK).content_type(self.format.content_type()).header("Content-Disposition", content_disposition(&self.file_name)).body(self.body)
+        }
+    }
+
+    /// The ASCII fallback and the UTF-8 encoded (RFC 6266) file names / ASCII的备用文件名及UTF-8编码（RFC 6266）的文件名
+    fn content_disposition(file_name: &str) -> String {
+        let fallback = file_name.chars().map(|c| if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' { c } else { '_' }).collect::<String>();
+        let encoded = file_name
+            .bytes()
+            .map(|b| {
+                if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                    (b as char).to_string()
+                } else {
+                    format!("%{b:02X}")
+                }
+            })
+            .collect::<String>();
+        format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+    }
+
+    impl TardisExporter {
+        /// Stream the rows as CSV in chunks / 以分块的方式将行流式输出为CSV
+        pub fn csv_response<T: Serialize + Send + 'static>(&self, file_name: impl Into<String>, rows: Vec<T>) -> TardisExportResponse {
+            self.csv_stream_response(file_name, futures::stream::iter(rows.into_iter().map(Ok)))
+        }
+
+        /// Stream the rows of the stream as CSV in chunks, e.g. the pages of a large table
+        /// / 以分块的方式将流中的行流式输出为CSV，如大表的分页
+        ///
+        /// The response is aborted if the stream fails after the header has been sent.
+        ///
+        /// 表头发送后流失败时中止响应.
+        pub fn csv_stream_response<T, S>(&self, file_name: impl Into<String>, rows: S) -> TardisExportResponse
+        where
+            T: Serialize + Send + 'static,
+            S: Stream<Item = TardisResult<T>> + Send + 'static,
+        {
+            let exporter = self.clone();
+            let body = async_stream::stream! {
+                let mut rows = Box::pin(rows);
+                let mut columns = None;
+                let mut chunk = Vec::with_capacity(exporter.chunk_size);
+                loop {
+                    let row = rows.next().await;
+                    let end = row.is_none();
+                    match row {
+                        Some(Ok(row)) => chunk.push(row),
+                        Some(Err(error)) => {
+                            error!("[Tardis.Export] Read the rows error: {error}");
+                            yield Err(std::io::Error::new(std::io::ErrorKind::Other, error.to_string()));
+                            return;
+                        }
+                        None => {}
+                    }
+                    // the header is sent even if there is no row
+                    if chunk.len() >= exporter.chunk_size || (end && (!chunk.is_empty() || columns.is_none())) {
+                        match exporter.csv_chunk(&mut columns, &chunk) {
+                            Ok(data) => yield Ok(data),
+                            Err(error) => {
+                                error!("[Tardis.Export] Export the rows error: {error}");
+                                yield Err(std::io::Error::new(std::io::ErrorKind::Other, error.to_string()));
+                                return;
+                            }
+                        }
+                        chunk.clear();
+                    }
+                    if end {
+                        break;
+                    }
+                }
+            };
+            TardisExportResponse::new(file_name, TardisExportFormat::Csv, Body::from_bytes_stream(body))
+        }
+
+        /// Export the rows as XLSX, generated in a blocking thread / 将行导出为XLSX，在阻塞线程中生成
+        #[cfg(feature = "export-xlsx")]
+        pub async fn xlsx_response<T: Serialize + Send + 'static>(&self, file_name: impl Into<String>, rows: Vec<T>) -> TardisResult<TardisExportResponse> {
+            let exporter = self.clone();
+            let data = tokio::task::spawn_blocking(move || exporter.xlsx(&rows))
+                .await
+                .map_err(|error| TardisError::custom("500-tardis-export-xlsx-error", &format!("[Tardis.Export] Generate XLSX error: {error}"), ""))??;
+            Ok(TardisExportResponse::new(file_name, TardisExportFormat::Xlsx, Body::from(data)))
+        }
+
+        /// Export the rows in the format, e.g. selected by a query parameter / 以指定格式导出行，如由查询参数选择
+        #[allow(unused_variables)]
+        pub async fn response<T: Serialize + Send + 'static>(&self, format: TardisExportFormat, file_name: impl Into<String>, rows: Vec<T>) -> TardisResult<TardisExportResponse> {
+            match format {
+                TardisExportFormat::Csv => Ok(self.csv_response(file_name, rows)),
+                #[cfg(feature = "export-xlsx")]
+                TardisExportFormat::Xlsx => self.xlsx_response(file_name, rows).await,
+                #[cfg(not(feature = "export-xlsx"))]
+                TardisExportFormat::Xlsx => Err(crate::export::xlsx_not_enabled()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "web-server")]
+pub use web::TardisExportResponse;
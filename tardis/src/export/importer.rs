@@ -0,0 +1,228 @@
+use csv::StringRecord;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::export::{unescape_formula, TardisExportColumn, TardisExportFormat};
+
+/// Error of a row / 行错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisImportRowError {
+    /// Row number in the file starting from 1, including the header / 文件中的行号，从1开始，包含表头
+    pub row: usize,
+    /// The field of the cell if known / 单元格对应的字段（如已知）
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Imported rows / 导入的行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TardisImportResult<T> {
+    /// The valid rows / 有效的行
+    pub rows: Vec<T>,
+    pub errors: Vec<TardisImportRowError>,
+}
+
+impl<T> TardisImportResult<T> {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The rows if all are valid, otherwise the 400 error with the errors of the rows in the extensions
+    /// / 全部有效时返回行，否则返回400错误，扩展中包含各行的错误
+    ///
+    /// The extensions are `row.{row}: {field}: {message}` , the first error of each row is kept.
+    ///
+    /// 扩展为 `row.{行号}: {字段}: {消息}` ，每行保留第一个错误.
+    pub fn into_rows(self) -> TardisResult<Vec<T>> {
+        if self.errors.is_empty() {
+            return Ok(self.rows);
+        }
+        let message = self.errors.iter().map(|error| format!("row {}: {}", error.row, row_error_message(error))).collect::<Vec<_>>().join("; ");
+        let mut tardis_error = TardisError::custom("400-tardis-import-invalid-rows", &format!("[Tardis.Import] {message}"), "");
+        for error in &self.errors {
+            let key = format!("row.{}", error.row);
+            if !tardis_error.extensions.contains_key(&key) {
+                tardis_error = tardis_error.with_extension(key, row_error_message(error));
+            }
+        }
+        Err(tardis_error)
+    }
+}
+
+fn row_error_message(error: &TardisImportRowError) -> String {
+    match &error.field {
+        Some(field) => format!("{field}: {}", error.message),
+        None => error.message.clone(),
+    }
+}
+
+/// Importer of the rows / 行导入器
+///
+/// The headers are mapped to the fields by the columns, matching the header, the localized header or the field name,
+/// the unmatched headers are used as the field names. The blank rows are skipped,
+/// the `'` prefix of the formula-like values added by [`TardisExporter`](crate::export::exporter::TardisExporter) is removed.
+///
+/// 表头通过列映射到字段，匹配表头、本地化的表头或字段名，未匹配的表头作为字段名使用. 跳过空行，
+/// 移除 [`TardisExporter`](crate::export::exporter::TardisExporter) 为类似公式的值添加的 `'` 前缀.
+#[derive(Debug, Clone, Default)]
+pub struct TardisImporter {
+    columns: Vec<TardisExportColumn>,
+    lang: Option<String>,
+    max_rows: Option<usize>,
+}
+
+impl TardisImporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Import the column as the field / 将列导入为字段
+    pub fn column(mut self, field: impl Into<String>, header: impl Into<String>) -> Self {
+        self.columns.push(TardisExportColumn::new(field, header));
+        self
+    }
+
+    pub fn columns(mut self, columns: impl IntoIterator<Item = impl Into<TardisExportColumn>>) -> Self {
+        self.columns.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Language of the localized headers / 本地化表头的语言
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Fail with the `400-tardis-import-too-many-rows` error if the file has more rows
+    /// / 文件的行数超过时以 `400-tardis-import-too-many-rows` 错误失败
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Parse the file / 解析文件
+    pub fn parse<T: DeserializeOwned>(&self, format: TardisExportFormat, data: &[u8]) -> TardisResult<TardisImportResult<T>> {
+        match format {
+            TardisExportFormat::Csv => self.parse_csv(data),
+            #[cfg(feature = "export-xlsx")]
+            TardisExportFormat::Xlsx => self.parse_xlsx(data),
+            #[cfg(not(feature = "export-xlsx"))]
+            TardisExportFormat::Xlsx => Err(super::xlsx_not_enabled()),
+        }
+    }
+
+    /// Parse the CSV file / 解析CSV文件
+    pub fn parse_csv<T: DeserializeOwned>(&self, data: &[u8]) -> TardisResult<TardisImportResult<T>> {
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        let mut reader = csv::ReaderBuilder::new().has_headers(false).flexible(true).trim(csv::Trim::All).from_reader(data);
+        // the line where the record starts, the blank lines are skipped by the reader
+        self.deserialize(reader.records().enumerate().map(|(index, record)| {
+            let position = match &record {
+                Ok(record) => record.position(),
+                Err(error) => error.position(),
+            };
+            (position.map(|position| position.line() as usize).unwrap_or(index + 1), record)
+        }))
+    }
+
+    /// Parse the first worksheet of the XLSX file / 解析XLSX文件的第一个工作表
+    #[cfg(feature = "export-xlsx")]
+    pub fn parse_xlsx<T: DeserializeOwned>(&self, data: &[u8]) -> TardisResult<TardisImportResult<T>> {
+        use calamine::{open_workbook_from_rs, Reader, Xlsx};
+        let mut workbook: Xlsx<_> = open_workbook_from_rs(std::io::Cursor::new(data))?;
+        let range =
+            workbook.worksheet_range_at(0).ok_or_else(|| TardisError::custom("400-tardis-import-worksheet-empty", "[Tardis.Import] The workbook has no worksheet", ""))??;
+        // the range starts from the first non-empty row
+        let header_row = range.start().map(|(row, _)| row as usize).unwrap_or(0) + 1;
+        self.deserialize(range.rows().enumerate().map(|(index, row)| (header_row + index, Ok(StringRecord::from(row.iter().map(xlsx_cell_text).collect::<Vec<_>>())))))
+    }
+
+    /// Parse the uploaded file, the format is detected by the file name or the content type
+    /// / 解析上传的文件，格式根据文件名或内容类型识别
+    #[cfg(feature = "web-server")]
+    pub async fn parse_upload<T: DeserializeOwned>(&self, upload: poem_openapi::types::multipart::Upload) -> TardisResult<TardisImportResult<T>> {
+        let format =
+            upload.file_name().and_then(TardisExportFormat::from_file_name).or_else(|| upload.content_type().and_then(TardisExportFormat::from_content_type)).ok_or_else(|| {
+                TardisError::custom(
+                    "400-tardis-import-format-not-supported",
+                    &format!("[Tardis.Import] The format of the file {} is not supported", upload.file_name().unwrap_or_default()),
+                    "",
+                )
+            })?;
+        let data = upload.into_vec().await?;
+        self.parse(format, &data)
+    }
+
+    /// The records are paired with the row numbers, the first record is the header / 记录与行号配对，第一条记录为表头
+    fn deserialize<T: DeserializeOwned>(&self, mut records: impl Iterator<Item = (usize, csv::Result<StringRecord>)>) -> TardisResult<TardisImportResult<T>> {
+        let mut result = TardisImportResult {
+            rows: Vec::new(),
+            errors: Vec::new(),
+        };
+        let Some((_, headers)) = records.next() else {
+            return Ok(result);
+        };
+        let fields = StringRecord::from(headers?.iter().map(|header| self.field_of(header)).collect::<Vec<_>>());
+        let mut count = 0;
+        for (row, record) in records {
+            let record = match record {
+                Ok(record) => record,
+                Err(error) => {
+                    result.errors.push(TardisImportRowError {
+                        row,
+                        field: None,
+                        message: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            count += 1;
+            let record = StringRecord::from(record.iter().map(unescape_formula).collect::<Vec<_>>());
+            if let Some(max_rows) = self.max_rows {
+                if count > max_rows {
+                    return Err(TardisError::custom(
+                        "400-tardis-import-too-many-rows",
+                        &format!("[Tardis.Import] The file has more than {max_rows} rows"),
+                        "",
+                    ));
+                }
+            }
+            match record.deserialize::<T>(Some(&fields)) {
+                Ok(value) => result.rows.push(value),
+                Err(error) => {
+                    let (field, message) = match error.kind() {
+                        csv::ErrorKind::Deserialize { err, .. } => (err.field().and_then(|index| fields.get(index as usize)).map(str::to_string), err.kind().to_string()),
+                        _ => (None, error.to_string()),
+                    };
+                    result.errors.push(TardisImportRowError { row, field, message });
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn field_of(&self, header: &str) -> String {
+        self.columns
+            .iter()
+            .find(|column| column.header == header || column.field == header || (column.locale_code.is_some() && column.localized_header(self.lang.as_deref()) == header))
+            .map(|column| column.field.clone())
+            .unwrap_or_else(|| header.to_string())
+    }
+}
+
+#[cfg(feature = "export-xlsx")]
+fn xlsx_cell_text(cell: &calamine::Data) -> String {
+    use calamine::{Data, DataType};
+    match cell {
+        Data::Empty => String::new(),
+        // the integers are stored as the floats
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => (*value as i64).to_string(),
+        Data::DateTime(_) => cell.as_datetime().map(|value| value.format("%Y-%m-%dT%H:%M:%S").to_string()).unwrap_or_else(|| cell.to_string()),
+        _ => cell.to_string().trim().to_string(),
+    }
+}
@@ -22,8 +22,9 @@
 //! ## ⚙️Key Features
 //!
 //! * ``conf-remote`` enable the unified configuration center
-//! * ``crypto`` encryption, decryption and digest operations
-//! * ``crypto-with-sm`` encryption, decryption and digest with SM.x operations
+//! * ``crypto`` encryption, decryption and digest operations, and the keyring with key rotation and envelope encryption
+//! * ``crypto-with-sm`` encryption, decryption, digest and key exchange with SM.x operations
+//! * ``crypto-jwt`` JWT signing and verification, JWKS and JWE operations
 //! * ``future`` asynchronous operations
 //! * ``reldb-core`` relational database core operations(based on [SeaORM](https://github.com/SeaQL/sea-orm))
 //! * ``reldb-postgres`` relational database with postgres driver
@@ -31,13 +32,27 @@
 //! * ``reldb-sqlite`` relational database with sqlite driver
 //! * ``reldb`` relational database with postgres/mysql/sqlite drivers
 //! * ``web-server`` web service operations(based on [Poem](https://github.com/poem-web/poem))
+//! * ``web-server-grpc`` grpc web service based on [Poem](https://github.com/poem-web/poem), optionally on a separate listener
+//! * ``web-server-graphql`` GraphQL web service based on [async-graphql](https://github.com/async-graphql/async-graphql)
 //! * ``web-client`` web client operations
+//! * ``web-client-grpc`` grpc client operations based on [Poem](https://github.com/poem-web/poem)
 //! * ``ws-client`` webscoket client operations
-//! * ``cache`` cache operations
-//! * ``mq`` message queue operations
+//! * ``cache`` cache operations, the distributed lock, the rate limiter and the delayed task queue
+//! * ``cache-msgpack`` MessagePack codec of the typed cache values
+//! * ``mq`` message queue operations and the bridging of the in-process events
+//! * ``mq-kafka`` message queue operations with the Kafka broker(based on [rust-rdkafka](https://github.com/fede1024/rust-rdkafka))
+//! * ``mq-nats`` message queue operations with the NATS broker(based on [async-nats](https://github.com/nats-io/nats.rs))
+//! * ``mq-redis`` message queue operations with the Redis Streams
 //! * ``mail`` mail send operations
+//! * ``mail-api`` mail send operations by the HTTP APIs of SendGrid, Mailgun and Aliyun DirectMail
+//! * ``mail-template`` mail templates with i18n(based on [handlebars](https://github.com/sunng87/handlebars-rust))
 //! * ``os`` object Storage operations
-//! * ``test`` unit test operations
+//! * ``os-azure`` object Storage operations with the Azure Blob Storage
+//! * ``test`` unit test operations (test harness, mock clock, test containers, database fixtures, in-process HTTP mock server and web test client, JSON snapshots, in-memory cache and MQ)
+//! * ``decimal`` money and decimal arithmetic operations(based on [rust_decimal](https://github.com/paupino/rust-decimal))
+//! * ``metrics`` prometheus metrics of the built-in clients and the web server(based on [prometheus](https://github.com/tikv/rust-prometheus))
+//! * ``sentry`` report errors to Sentry-compatible endpoints(based on [sentry](https://github.com/getsentry/sentry-rust))
+//! * ``scheduler`` background jobs scheduled by cron expressions or fixed intervals, optionally run once per cluster with the ``cache`` feature
 //!
 //! ## 🚀 Quick start
 //!
@@ -132,16 +147,22 @@ pub use async_stream;
 #[cfg(feature = "future")]
 pub use async_trait;
 pub use chrono;
+// the `config` crate, used to implement the custom config center processors
+pub use ::config as config_rs;
 pub use derive_more;
 #[cfg(feature = "future")]
 pub use futures;
 #[cfg(feature = "future")]
 pub use futures_util;
 pub use lru;
-#[cfg(feature = "web-server-grpc")]
+#[cfg(any(feature = "web-server-grpc", feature = "web-client-grpc"))]
 pub use poem_grpc;
+#[cfg(feature = "metrics")]
+pub use prometheus;
 pub use rand;
 pub use regex;
+#[cfg(feature = "decimal")]
+pub use rust_decimal;
 pub use serde;
 use serde::Deserialize;
 pub use serde_json;
@@ -156,6 +177,12 @@ use basic::error::TardisErrorWithExt;
 use basic::result::TardisResult;
 use basic::tracing::TardisTracing;
 pub use paste;
+#[cfg(all(feature = "tardis-macros", feature = "web-client"))]
+pub use tardis_macros::tardis_client;
+#[cfg(feature = "tardis-macros")]
+pub use tardis_macros::TardisMap;
+#[cfg(feature = "tardis-macros")]
+pub use tardis_macros::TardisValidate;
 #[cfg(feature = "tardis-macros")]
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql"))]
 pub use tardis_macros::{TardisCreateEntity, TardisCreateIndex, TardisCreateTable, TardisEmptyBehavior, TardisEmptyRelation};
@@ -164,6 +191,7 @@ pub use url;
 
 use crate::basic::field::TardisField;
 use crate::basic::json::TardisJson;
+use crate::basic::time::TardisTime;
 use crate::basic::uri::TardisUri;
 #[cfg(feature = "cache")]
 use crate::cache::cache_client::TardisCacheClient;
@@ -172,15 +200,21 @@ use crate::config::config_dto::{FrameworkConfig, TardisConfig};
 use crate::db::domain::tardis_db_config::TardisDataDict;
 #[cfg(feature = "reldb-core")]
 use crate::db::reldb_client::TardisRelDBClient;
+#[cfg(feature = "discovery")]
+use crate::discovery::discovery_client::TardisDiscoveryClient;
 #[cfg(feature = "mail")]
 use crate::mail::mail_client::TardisMailClient;
 #[cfg(feature = "mq")]
 use crate::mq::mq_client::TardisMQClient;
 #[cfg(feature = "os")]
 use crate::os::os_client::TardisOSClient;
+#[cfg(feature = "scheduler")]
+use crate::scheduler::scheduler_client::TardisScheduler;
 #[cfg(feature = "web-client")]
 use crate::search::search_client::TardisSearchClient;
 use crate::utils::*;
+#[cfg(feature = "web-client-grpc")]
+use crate::web::grpc_client::TardisGrpcClient;
 #[cfg(feature = "web-client")]
 use crate::web::web_client::TardisWebClient;
 #[cfg(feature = "web-server")]
@@ -274,6 +308,7 @@ use crate::web::web_server::TardisWebServer;
 /// TardisFuns::field;
 /// TardisFuns::json;
 /// TardisFuns::uri;  
+/// TardisFuns::time;
 /// TardisFuns::crypto;   
 /// TardisFuns::reldb();    
 /// TardisFuns::web_server();  
@@ -287,21 +322,27 @@ pub struct TardisFuns {
     framework_config: TardisComponent<FrameworkConfig>,
     pub(crate) tracing: TardisComponent<TardisTracing>,
     #[cfg(feature = "reldb-core")]
-    reldb: TardisComponentMap<TardisRelDBClient>,
+    pub(crate) reldb: TardisComponentMap<TardisRelDBClient>,
     #[cfg(feature = "web-server")]
     web_server: TardisComponent<TardisWebServer>,
     #[cfg(feature = "web-client")]
     web_client: TardisComponentMap<TardisWebClient>,
+    #[cfg(feature = "web-client-grpc")]
+    grpc_client: TardisComponentMap<TardisGrpcClient>,
     #[cfg(feature = "cache")]
-    cache: TardisComponentMap<TardisCacheClient>,
+    pub(crate) cache: TardisComponentMap<TardisCacheClient>,
     #[cfg(feature = "mq")]
-    mq: TardisComponentMap<TardisMQClient>,
+    pub(crate) mq: TardisComponentMap<TardisMQClient>,
     #[cfg(feature = "web-client")]
-    search: TardisComponentMap<TardisSearchClient>,
+    pub(crate) search: TardisComponentMap<TardisSearchClient>,
     #[cfg(feature = "mail")]
     mail: TardisComponentMap<TardisMailClient>,
     #[cfg(feature = "os")]
-    os: TardisComponentMap<TardisOSClient>,
+    pub(crate) os: TardisComponentMap<TardisOSClient>,
+    #[cfg(feature = "discovery")]
+    discovery: TardisComponentMap<TardisDiscoveryClient>,
+    #[cfg(feature = "scheduler")]
+    scheduler: TardisComponent<TardisScheduler>,
 }
 
 static TARDIS_INST: TardisFuns = TardisFuns {
@@ -314,6 +355,8 @@ static TARDIS_INST: TardisFuns = TardisFuns {
     web_server: TardisComponent::new(),
     #[cfg(feature = "web-client")]
     web_client: TardisComponentMap::new(),
+    #[cfg(feature = "web-client-grpc")]
+    grpc_client: TardisComponentMap::new(),
     #[cfg(feature = "cache")]
     cache: TardisComponentMap::new(),
     #[cfg(feature = "mq")]
@@ -324,6 +367,10 @@ static TARDIS_INST: TardisFuns = TardisFuns {
     mail: TardisComponentMap::new(),
     #[cfg(feature = "os")]
     os: TardisComponentMap::new(),
+    #[cfg(feature = "discovery")]
+    discovery: TardisComponentMap::new(),
+    #[cfg(feature = "scheduler")]
+    scheduler: TardisComponent::new(),
 };
 
 #[allow(unsafe_code)]
@@ -392,6 +439,7 @@ impl TardisFuns {
     /// .await?;
     /// ```
     pub async fn init_conf(conf: TardisConfig) -> TardisResult<()> {
+        TardisFuns::time.validate_business_timezone(conf.fw.app.timezone.as_deref())?;
         let custom_config = conf.cs.iter().map(|(k, v)| (k.clone(), CachedJsonValue::new(v.clone()))).collect::<HashMap<_, _>>();
         TARDIS_INST.custom_config.replace_inner(custom_config);
         TARDIS_INST.framework_config.set(conf.fw);
@@ -400,63 +448,150 @@ impl TardisFuns {
         if let Some(log_config) = &fw_conf.log {
             TARDIS_INST.tracing.get().update_config(log_config)?;
         }
+        #[cfg(feature = "sentry")]
+        {
+            if let Some(sentry_config) = &fw_conf.sentry {
+                basic::error_report::init(sentry_config, &fw_conf.app);
+            }
+        }
+        // the other components are initialized in parallel, see `fw.adv.startup`
+        #[allow(unused_mut)]
+        let mut components: Vec<utils::startup::StartupComponent> = Vec::new();
         #[cfg(feature = "reldb-core")]
         {
             if let Some(db_config) = &fw_conf.db {
-                TARDIS_INST.reldb.init_by(db_config).await?;
+                let db_config = db_config.clone();
+                components.push(utils::startup::StartupComponent::new("db", move || {
+                    let db_config = db_config.clone();
+                    async move { TARDIS_INST.reldb.init_by(&db_config).await.map(|_| ()) }
+                }));
             }
         }
-        #[cfg(feature = "web-server")]
+        #[cfg(feature = "discovery")]
         {
-            if let Some(_web_server_config) = &fw_conf.web_server {
-                let web_server = TardisWebServer::init_by_conf(&fw_conf)?;
-                // take out previous webserver first, because TARDIS_INST is not send and can't live cross an `await` point
-                let inherit = TARDIS_INST.web_server.get();
-                if inherit.is_running().await {
-                    // 1. should always shutdown first
-                    let _ = inherit.shutdown().await;
-                    // 2. load initializers
-                    web_server.load_initializer(inherit).await;
-                    // 3. restart webserver
-                    web_server.start().await?;
-                }
-                TARDIS_INST.web_server.set(web_server)
+            if let Some(discovery_config) = &fw_conf.discovery {
+                let (discovery_config, app_config) = (discovery_config.clone(), fw_conf.app.clone());
+                components.push(utils::startup::StartupComponent::new("discovery", move || {
+                    let (discovery_config, app_config) = (discovery_config.clone(), app_config.clone());
+                    async move {
+                        let discovery = TardisDiscoveryClient::init(&discovery_config, &app_config).await?;
+                        if let Some(previous) = TARDIS_INST.discovery.insert("", discovery) {
+                            if let Err(e) = previous.deregister().await {
+                                tracing::error!("[Tardis] Encounter an error while deregistering the previous instance: {}", e);
+                            }
+                        }
+                        Ok(())
+                    }
+                }));
             }
         }
         #[cfg(feature = "web-client")]
         {
             if let Some(web_client_config) = &fw_conf.web_client {
-                TARDIS_INST.web_client.init_by(web_client_config).await?;
+                let web_client_config = web_client_config.clone();
+                components.push(utils::startup::StartupComponent::new("web_client", move || {
+                    let web_client_config = web_client_config.clone();
+                    async move { TARDIS_INST.web_client.init_by(&web_client_config).await.map(|_| ()) }
+                }));
+            }
+        }
+        #[cfg(feature = "web-client-grpc")]
+        {
+            if let Some(grpc_client_config) = &fw_conf.grpc_client {
+                let grpc_client_config = grpc_client_config.clone();
+                components.push(utils::startup::StartupComponent::new("grpc_client", move || {
+                    let grpc_client_config = grpc_client_config.clone();
+                    async move { TARDIS_INST.grpc_client.init_by(&grpc_client_config).await.map(|_| ()) }
+                }));
             }
         }
         #[cfg(feature = "cache")]
         {
             if let Some(cache_config) = &fw_conf.cache {
-                TARDIS_INST.cache.init_by(cache_config).await?;
+                let cache_config = cache_config.clone();
+                components.push(utils::startup::StartupComponent::new("cache", move || {
+                    let cache_config = cache_config.clone();
+                    async move { TARDIS_INST.cache.init_by(&cache_config).await.map(|_| ()) }
+                }));
             }
         }
         #[cfg(feature = "mq")]
         {
             if let Some(mq_config) = &fw_conf.mq {
-                TARDIS_INST.mq.init_by(mq_config).await?;
+                #[cfg(feature = "reldb-core")]
+                let outbox_enabled = std::iter::once(&mq_config.default).chain(mq_config.modules.values()).any(|module_config| module_config.outbox.enabled);
+                let mq_config = mq_config.clone();
+                #[allow(unused_mut)]
+                let mut component = utils::startup::StartupComponent::new("mq", move || {
+                    let mq_config = mq_config.clone();
+                    async move {
+                        TARDIS_INST.mq.init_by(&mq_config).await?;
+                        #[cfg(feature = "reldb-core")]
+                        mq::mq_outbox::TardisMQOutbox::start_configured(&mq_config).await?;
+                        Ok(())
+                    }
+                });
+                // the outbox relay requires the db
+                #[cfg(feature = "reldb-core")]
+                if outbox_enabled {
+                    component = component.depends_on("db");
+                }
+                components.push(component);
             }
         }
         #[cfg(feature = "web-client")]
         {
             if let Some(search_config) = &fw_conf.search {
-                TARDIS_INST.search.init_by(search_config).await?;
+                let search_config = search_config.clone();
+                components.push(utils::startup::StartupComponent::new("search", move || {
+                    let search_config = search_config.clone();
+                    async move { TARDIS_INST.search.init_by(&search_config).await.map(|_| ()) }
+                }));
             }
         }
         #[cfg(feature = "mail")]
         {
             if let Some(mail_config) = &fw_conf.mail {
-                TARDIS_INST.mail.init_by(mail_config).await?;
+                let mail_config = mail_config.clone();
+                components.push(utils::startup::StartupComponent::new("mail", move || {
+                    let mail_config = mail_config.clone();
+                    async move { TARDIS_INST.mail.init_by(&mail_config).await.map(|_| ()) }
+                }));
             }
         }
         #[cfg(feature = "os")]
         {
             if let Some(os_config) = &fw_conf.os {
-                TARDIS_INST.os.init_by(os_config).await?;
+                let os_config = os_config.clone();
+                components.push(utils::startup::StartupComponent::new("os", move || {
+                    let os_config = os_config.clone();
+                    async move { TARDIS_INST.os.init_by(&os_config).await.map(|_| ()) }
+                }));
+            }
+        }
+        utils::startup::run(components, &fw_conf.adv.startup).await?;
+        #[cfg(feature = "web-server")]
+        {
+            if let Some(_web_server_config) = &fw_conf.web_server {
+                let web_server = TardisWebServer::init_by_conf(&fw_conf)?;
+                // take out previous webserver first, because TARDIS_INST is not send and can't live cross an `await` point
+                let inherit = TARDIS_INST.web_server.get();
+                if inherit.is_running().await {
+                    // 1. should always shutdown first
+                    let _ = inherit.shutdown_for_restart().await;
+                    // 2. load initializers
+                    web_server.load_initializer(inherit).await;
+                    // 3. restart webserver
+                    web_server.start().await?;
+                }
+                TARDIS_INST.web_server.set(web_server)
+            }
+        }
+        // start the jobs after the components they may use
+        #[cfg(feature = "scheduler")]
+        {
+            if let Some(scheduler_config) = &fw_conf.scheduler {
+                TARDIS_INST.scheduler.get().reload(scheduler_config.clone()).await?;
             }
         }
         Ok(())
@@ -530,6 +665,66 @@ impl TardisFuns {
         Some(TARDIS_INST.framework_config.get())
     }
 
+    /// Subscribe to the configuration changes / 订阅配置变更
+    ///
+    /// An event is received after each [`hot_reload`](Self::hot_reload) that changed the configuration,
+    /// e.g. when the config center or the watched local files changed.
+    ///
+    /// 每次变更了配置的 [`hot_reload`](Self::hot_reload) 后会收到事件，如配置中心或被监听的本地文件变更时.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let mut changes = TardisFuns::subscribe_config_changed();
+    /// while let Ok(changed) = changes.recv().await {
+    ///     println!("components:{:?}, custom:{:?}", changed.components, changed.custom);
+    /// }
+    /// ```
+    pub fn subscribe_config_changed() -> tokio::sync::broadcast::Receiver<config::config_watcher::TardisConfigChanged> {
+        config::config_watcher::subscribe()
+    }
+
+    /// Override a configuration value at runtime / 运行时覆盖配置值
+    ///
+    /// Same as [`patch_config`](Self::patch_config) with a single override.
+    ///
+    /// 与只有一个覆盖项的 [`patch_config`](Self::patch_config) 相同.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::override_config("fw.log.level", "debug").await?;
+    /// ```
+    pub async fn override_config(path: &str, value: impl Into<serde_json::Value>) -> TardisResult<()> {
+        Self::patch_config(config::config_patch::TardisConfigPatch::new().set(path, value)).await
+    }
+
+    /// Merge the overrides on top of the current configuration and [`hot_reload`](Self::hot_reload) it / 将覆盖项合并到当前配置之上并 [`hot_reload`](Self::hot_reload)
+    ///
+    /// The overrides are kept when the configuration is reloaded by the watchers, see [`config_patch`](config::config_patch).
+    ///
+    /// 覆盖项在配置被监听重新加载时保留，详见 [`config_patch`](config::config_patch) .
+    pub async fn patch_config(patch: config::config_patch::TardisConfigPatch) -> TardisResult<()> {
+        if patch.is_empty() {
+            return Ok(());
+        }
+        let cs = TARDIS_INST
+            .custom_config
+            .inner
+            .read()
+            .map_err(|error| basic::error::TardisError::internal_error(&format!("[Tardis.Config] {error:?}"), ""))?
+            .iter()
+            .map(|(code, value)| (code.clone(), value.raw().clone()))
+            .collect();
+        let current = TardisConfig {
+            cs,
+            fw: TardisFuns::fw_config().as_ref().clone(),
+        };
+        let patched = patch.apply_to(current)?;
+        config::config_patch::save(&patch)?;
+        TardisFuns::hot_reload(patched).await
+    }
+
     /// Using the field feature / 使用字段功能
     ///
     /// # Examples
@@ -574,6 +769,18 @@ impl TardisFuns {
     #[allow(non_upper_case_globals)]
     pub const uri: TardisUri = TardisUri {};
 
+    /// Using the time feature / 使用时间功能
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let dt = TardisFuns::time.parse("2023/08/01 10:00:00").unwrap();
+    /// let week_start = TardisFuns::time.truncate_to_week(dt);
+    /// let local = TardisFuns::time.to_business(week_start);
+    /// ```
+    #[allow(non_upper_case_globals)]
+    pub const time: TardisTime = TardisTime {};
+
     /// Use of encryption/decryption/digest features / 使用加解密/摘要功能
     ///
     /// Supported algorithms: base64/md5/sha/mac/aes/rsa/sm2/sm3/sm4.
@@ -603,13 +810,89 @@ impl TardisFuns {
         sm4: crypto::crypto_sm2_4::TardisCryptoSm4 {},
         #[cfg(feature = "crypto-with-sm")]
         sm2: crypto::crypto_sm2_4::TardisCryptoSm2 {},
+        #[cfg(feature = "crypto-with-sm")]
+        sm3: crypto::crypto_sm2_4::TardisCryptoSm3 {},
         digest: crypto::crypto_digest::TardisCryptoDigest {},
+        #[cfg(feature = "crypto-jwt")]
+        jwt: crypto::crypto_jwt::TardisCryptoJwt {},
     };
 
     pub fn tracing() -> Arc<TardisTracing> {
         TARDIS_INST.tracing.get()
     }
 
+    /// Reload the log filter at runtime / 运行时重新加载日志过滤器
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::log_reload("info,tardis::cache=trace")?;
+    /// ```
+    ///
+    /// @see [`TardisTracing::reload_filter`]
+    pub fn log_reload(filter: &str) -> TardisResult<()> {
+        TardisFuns::tracing().reload_filter(filter)
+    }
+
+    /// Wait for the component to be initialized / 等待组件初始化完成
+    ///
+    /// Used for the background components, e.g. `fw.adv.startup.components.search.background = true` ,
+    /// returns an error if the component is not configured or failed to initialize.
+    ///
+    /// 用于后台组件，如 `fw.adv.startup.components.search.background = true` ，组件未配置或初始化失败时返回错误.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::wait_component("search").await?;
+    /// let search = TardisFuns::search();
+    /// ```
+    pub async fn wait_component(name: &str) -> TardisResult<()> {
+        utils::startup::wait(name).await
+    }
+
+    /// Use the framework clock / 使用框架时钟
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let now = TardisFuns::clock().now();
+    /// ```
+    pub fn clock() -> &'static basic::clock::TardisClock {
+        static CLOCK: std::sync::OnceLock<basic::clock::TardisClock> = std::sync::OnceLock::new();
+        CLOCK.get_or_init(basic::clock::TardisClock::new)
+    }
+
+    /// Use the in-process event bus / 使用进程内事件总线
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::event().subscribe(|event: UserCreated| async move { Ok(()) });
+    /// TardisFuns::event().publish(UserCreated { id: "u1".to_string() }).await?;
+    /// ```
+    pub fn event() -> &'static utils::event_bus::TardisEventBus {
+        static EVENT_BUS: std::sync::OnceLock<utils::event_bus::TardisEventBus> = std::sync::OnceLock::new();
+        EVENT_BUS.get_or_init(utils::event_bus::TardisEventBus::new)
+    }
+
+    /// Use the metrics feature / 使用监控指标功能
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "metrics")] .
+    ///
+    /// 本功能需要启用 #[cfg(feature = "metrics")] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let text = TardisFuns::metrics().export()?;
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn metrics() -> &'static basic::metrics::TardisMetrics {
+        static METRICS: std::sync::OnceLock<basic::metrics::TardisMetrics> = std::sync::OnceLock::new();
+        METRICS.get_or_init(basic::metrics::TardisMetrics::new)
+    }
+
     /// Use the relational database feature / 使用关系型数据库功能
     ///
     /// This feature needs to be enabled #[cfg(feature = "reldb/reldb-postgres/reldb-mysql/reldb-sqlite")] .
@@ -690,6 +973,20 @@ impl TardisFuns {
         TARDIS_INST.reldb.get(code).unwrap_or_else(|| panic!("[Tardis.Config] RelDB {code} instance doesn't exist"))
     }
 
+    /// Same as [`reldb`](Self::reldb), but fails with the `503-tardis-startup-component-not-ready` error instead of panicking if the instance doesn't exist,
+    /// e.g. the background component is still initializing, see [`wait_component`](Self::wait_component)
+    /// / 与 [`reldb`](Self::reldb) 相同，但实例不存在（如后台组件仍在初始化）时以 `503-tardis-startup-component-not-ready` 错误失败而不是panic，见 [`wait_component`](Self::wait_component)
+    #[cfg(feature = "reldb-core")]
+    pub fn try_reldb() -> TardisResult<Arc<TardisRelDBClient>> {
+        Self::try_reldb_by_module("")
+    }
+
+    #[cfg(feature = "reldb-core")]
+    pub fn try_reldb_by_module(code: &str) -> TardisResult<Arc<TardisRelDBClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.reldb.get(&code).ok_or_else(|| utils::startup::not_ready("db", &code))
+    }
+
     #[cfg(feature = "reldb-core")]
     pub fn reldb_by_module_or_default(code: &str) -> Arc<TardisRelDBClient> {
         let code = code.to_lowercase();
@@ -787,6 +1084,18 @@ impl TardisFuns {
         TARDIS_INST.web_client.get(code).unwrap_or_else(|| panic!("[Tardis.Config] Web Client {code} instance doesn't exist"))
     }
 
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "web-client")]
+    pub fn try_web_client() -> TardisResult<Arc<TardisWebClient>> {
+        Self::try_web_client_by_module("")
+    }
+
+    #[cfg(feature = "web-client")]
+    pub fn try_web_client_by_module(code: &str) -> TardisResult<Arc<TardisWebClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.web_client.get(&code).ok_or_else(|| utils::startup::not_ready("web_client", &code))
+    }
+
     #[cfg(feature = "web-client")]
     pub fn web_client_by_module_or_default(code: &str) -> Arc<TardisWebClient> {
         let code = code.to_lowercase();
@@ -794,6 +1103,47 @@ impl TardisFuns {
         TARDIS_INST.web_client.get(code).unwrap_or_else(Self::web_client)
     }
 
+    /// Use the gRPC client feature / 使用gRPC客户端功能
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "web-client-grpc")] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let grpc = TardisFuns::grpc_client_by_module("user");
+    /// let client = grpc.client(UserClient::new)?.with(grpc.propagation());
+    /// ```
+    #[cfg(feature = "web-client-grpc")]
+    pub fn grpc_client() -> Arc<TardisGrpcClient> {
+        Self::grpc_client_by_module("")
+    }
+
+    #[cfg(feature = "web-client-grpc")]
+    pub fn grpc_client_by_module(code: &str) -> Arc<TardisGrpcClient> {
+        let code = code.to_lowercase();
+        let code = code.as_str();
+        TARDIS_INST.grpc_client.get(code).unwrap_or_else(|| panic!("[Tardis.Config] gRPC Client {code} instance doesn't exist"))
+    }
+
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "web-client-grpc")]
+    pub fn try_grpc_client() -> TardisResult<Arc<TardisGrpcClient>> {
+        Self::try_grpc_client_by_module("")
+    }
+
+    #[cfg(feature = "web-client-grpc")]
+    pub fn try_grpc_client_by_module(code: &str) -> TardisResult<Arc<TardisGrpcClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.grpc_client.get(&code).ok_or_else(|| utils::startup::not_ready("grpc_client", &code))
+    }
+
+    #[cfg(feature = "web-client-grpc")]
+    pub fn grpc_client_by_module_or_default(code: &str) -> Arc<TardisGrpcClient> {
+        let code = code.to_lowercase();
+        let code = code.as_str();
+        TARDIS_INST.grpc_client.get(code).unwrap_or_else(Self::grpc_client)
+    }
+
     #[cfg(feature = "ws-client")]
     pub async fn ws_client<F, T>(str_url: &str, on_message: F) -> TardisResult<web::ws_client::TardisWSClient>
     where
@@ -834,6 +1184,18 @@ impl TardisFuns {
         TARDIS_INST.cache.get(code).unwrap_or_else(|| panic!("[Tardis.Config] Cache {code} instance doesn't exist"))
     }
 
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "cache")]
+    pub fn try_cache() -> TardisResult<Arc<TardisCacheClient>> {
+        Self::try_cache_by_module("")
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn try_cache_by_module(code: &str) -> TardisResult<Arc<TardisCacheClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.cache.get(&code).ok_or_else(|| utils::startup::not_ready("cache", &code))
+    }
+
     #[cfg(feature = "cache")]
     pub fn cache_by_module_or_default(code: &str) -> Arc<TardisCacheClient> {
         let code = code.to_lowercase();
@@ -841,6 +1203,24 @@ impl TardisFuns {
         TARDIS_INST.cache.get(code).unwrap_or_else(Self::cache)
     }
 
+    /// Use the delayed task queue of the default cache / 使用默认缓存的延时任务队列
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "cache")] .
+    ///
+    /// 本功能需要启用 #[cfg(feature = "cache")] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::timer().queue("order_timeout").schedule_after(Duration::from_secs(1800), &order_id).await?;
+    /// ```
+    ///
+    /// @see [TardisTimer](cache::cache_timer::TardisTimer)
+    #[cfg(feature = "cache")]
+    pub fn timer() -> cache::cache_timer::TardisTimer {
+        Self::cache().timer()
+    }
+
     /// Use the message queue feature / 使用消息队列功能
     ///
     /// This feature needs to be enabled #[cfg(feature = "mq")] .
@@ -871,6 +1251,18 @@ impl TardisFuns {
         TARDIS_INST.mq.get(code).unwrap_or_else(|| panic!("[Tardis.Config] MQ {code} instance doesn't exist"))
     }
 
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "mq")]
+    pub fn try_mq() -> TardisResult<Arc<TardisMQClient>> {
+        Self::try_mq_by_module("")
+    }
+
+    #[cfg(feature = "mq")]
+    pub fn try_mq_by_module(code: &str) -> TardisResult<Arc<TardisMQClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.mq.get(&code).ok_or_else(|| utils::startup::not_ready("mq", &code))
+    }
+
     #[cfg(feature = "mq")]
     pub fn mq_by_module_or_default(code: &str) -> Arc<TardisMQClient> {
         let code = code.to_lowercase();
@@ -908,6 +1300,18 @@ impl TardisFuns {
         TARDIS_INST.search.get(code).unwrap_or_else(|| panic!("[Tardis.Config] Search {code} instance doesn't exist"))
     }
 
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "web-client")]
+    pub fn try_search() -> TardisResult<Arc<TardisSearchClient>> {
+        Self::try_search_by_module("")
+    }
+
+    #[cfg(feature = "web-client")]
+    pub fn try_search_by_module(code: &str) -> TardisResult<Arc<TardisSearchClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.search.get(&code).ok_or_else(|| utils::startup::not_ready("search", &code))
+    }
+
     #[cfg(feature = "web-client")]
     pub fn search_by_module_or_default(code: &str) -> Arc<TardisSearchClient> {
         let code = code.to_lowercase();
@@ -927,6 +1331,18 @@ impl TardisFuns {
         TARDIS_INST.mail.get(code).unwrap_or_else(|| panic!("[Tardis.Config] Mail {code} instance doesn't exist"))
     }
 
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "mail")]
+    pub fn try_mail() -> TardisResult<Arc<TardisMailClient>> {
+        Self::try_mail_by_module("")
+    }
+
+    #[cfg(feature = "mail")]
+    pub fn try_mail_by_module(code: &str) -> TardisResult<Arc<TardisMailClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.mail.get(&code).ok_or_else(|| utils::startup::not_ready("mail", &code))
+    }
+
     #[cfg(feature = "mail")]
     pub fn mail_by_module_or_default(code: &str) -> Arc<TardisMailClient> {
         let code = code.to_lowercase();
@@ -946,6 +1362,18 @@ impl TardisFuns {
         TARDIS_INST.os.get(code).unwrap_or_else(|| panic!("[Tardis.Config] Os {code} instance doesn't exist"))
     }
 
+    /// @see [try_reldb](Self::try_reldb)
+    #[cfg(feature = "os")]
+    pub fn try_os() -> TardisResult<Arc<TardisOSClient>> {
+        Self::try_os_by_module("")
+    }
+
+    #[cfg(feature = "os")]
+    pub fn try_os_by_module(code: &str) -> TardisResult<Arc<TardisOSClient>> {
+        let code = code.to_lowercase();
+        TARDIS_INST.os.get(&code).ok_or_else(|| utils::startup::not_ready("os", &code))
+    }
+
     #[cfg(feature = "os")]
     pub fn os_by_module_or_default(code: &str) -> Arc<TardisOSClient> {
         let code = code.to_lowercase();
@@ -953,6 +1381,46 @@ impl TardisFuns {
         TARDIS_INST.os.get(code).unwrap_or_else(Self::os)
     }
 
+    /// Use the service discovery feature / 使用服务发现功能
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "discovery")] .
+    ///
+    /// 本功能需要启用 #[cfg(feature = "discovery")] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let instance = TardisFuns::discovery().select_instance("todo").await?;
+    /// TardisFuns::web_client().get_to_str(&format!("{}/todo/1", instance.url()), None).await?;
+    /// ```
+    #[cfg(feature = "discovery")]
+    pub fn discovery() -> Arc<TardisDiscoveryClient> {
+        TARDIS_INST.discovery.get("").expect("[Tardis.Config] Discovery instance doesn't exist")
+    }
+
+    #[cfg(feature = "discovery")]
+    pub fn discovery_opt() -> Option<Arc<TardisDiscoveryClient>> {
+        TARDIS_INST.discovery.get("")
+    }
+
+    /// Use the job scheduler feature / 使用任务调度功能
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "scheduler")] .
+    ///
+    /// 本功能需要启用 #[cfg(feature = "scheduler")] .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::scheduler().register_cron("clean_expired", "0 0 3 * * *", || async { Ok(()) })?;
+    /// // started on initialization if `fw.scheduler` is configured
+    /// TardisFuns::scheduler().start()?;
+    /// ```
+    #[cfg(feature = "scheduler")]
+    pub fn scheduler() -> Arc<TardisScheduler> {
+        TARDIS_INST.scheduler.get()
+    }
+
     #[cfg(feature = "cluster")]
     pub async fn cluster_subscribe_event_boxed(subscriber: Box<dyn cluster::cluster_processor::TardisClusterSubscriber>) {
         cluster::cluster_processor::subscribe_boxed(subscriber).await;
@@ -986,9 +1454,26 @@ impl TardisFuns {
     /// - `clean: bool`: if use clean mode, it will cleanup all user setted configs like webserver modules
     async fn shutdown_internal(#[allow(unused_variables)] clean: bool) -> TardisResult<()> {
         tracing::info!("[Tardis] Shutdown...");
+        config::config_watcher::stop();
+        utils::startup::abort_background_tasks();
+        // drain the in-flight requests first, they may still use the other components
+        #[cfg(feature = "web-server")]
+        {
+            let web_server = TARDIS_INST.web_server.get();
+            if web_server.is_running().await {
+                if let Err(e) = web_server.shutdown().await {
+                    tracing::error!("[Tardis] Encounter an error while shutting down webserver: {}", e);
+                }
+            }
+        }
+        // the jobs may also use the other components
+        #[cfg(feature = "scheduler")]
+        TARDIS_INST.scheduler.get().stop().await;
         // using a join set to collect async task, because `&TARDIS_INST` is not `Send`
         #[cfg(feature = "web-client")]
         TARDIS_INST.web_client.clear();
+        #[cfg(feature = "web-client-grpc")]
+        TARDIS_INST.grpc_client.clear();
         #[cfg(feature = "cache")]
         TARDIS_INST.cache.clear();
         #[cfg(feature = "mail")]
@@ -1009,12 +1494,12 @@ impl TardisFuns {
                 }
             }
         }
-        #[cfg(feature = "web-server")]
+        #[cfg(feature = "discovery")]
         {
-            let web_server = TARDIS_INST.web_server.get();
-            if web_server.is_running().await {
-                if let Err(e) = web_server.shutdown().await {
-                    tracing::error!("[Tardis] Encounter an error while shutting down webserver: {}", e);
+            let discovery = TARDIS_INST.discovery.drain();
+            for (_, client) in discovery {
+                if let Err(e) = client.deregister().await {
+                    tracing::error!("[Tardis] Encounter an error while deregistering the instance: {}", e);
                 }
             }
         }
@@ -1029,6 +1514,9 @@ impl TardisFuns {
 
     /// hot reload tardis instance by a new [`TardisConfig`].
     ///
+    /// only the components whose configuration changed are re-initialized, then a [`TardisConfigChanged`](config::config_watcher::TardisConfigChanged) event
+    /// is broadcast to the subscribers of [`subscribe_config_changed`](Self::subscribe_config_changed) if anything changed.
+    ///
     /// there should have only one hot reload task at the same time. If it's called when other reload task is running,
     /// it will wait until the other task finished.
     pub async fn hot_reload(conf: TardisConfig) -> TardisResult<()> {
@@ -1037,20 +1525,22 @@ impl TardisFuns {
             tardis_load_semaphore: Semaphore = Semaphore::new(1);
         }
         let _sync = tardis_load_semaphore().acquire().await.expect("reload_semaphore is static so it shouldn't be closed.");
+        TardisFuns::time.validate_business_timezone(conf.fw.app.timezone.as_deref())?;
         let new_custom_config = conf.cs.iter().map(|(k, v)| (k.clone(), CachedJsonValue::new(v.clone()))).collect::<HashMap<_, _>>();
         let new_framework_config = conf.fw;
-        #[allow(unused_variables)]
         let old_custom_config = TARDIS_INST.custom_config.replace_inner(new_custom_config);
         #[allow(unused_variables)]
         let old_framework_config = TARDIS_INST.framework_config.replace(new_framework_config);
 
         #[allow(unused_variables)]
         let fw_config = TardisFuns::fw_config();
+        let mut components = Vec::new();
 
         if fw_config.log != old_framework_config.log {
             if let Some(log_config) = &fw_config.log {
                 TARDIS_INST.tracing.get().update_config(log_config)?;
             }
+            components.push("log".to_string());
         }
 
         #[cfg(feature = "reldb-core")]
@@ -1059,6 +1549,29 @@ impl TardisFuns {
                 if let Some(db_config) = &fw_config.db {
                     TARDIS_INST.reldb.init_by(db_config).await?;
                 }
+                components.push("db".to_string());
+            }
+        }
+        #[cfg(feature = "discovery")]
+        {
+            if fw_config.discovery != old_framework_config.discovery {
+                for (_, client) in TARDIS_INST.discovery.drain() {
+                    if let Err(e) = client.deregister().await {
+                        tracing::error!("[Tardis] Encounter an error while deregistering the instance: {}", e);
+                    }
+                }
+                if let Some(discovery_config) = &fw_config.discovery {
+                    let discovery = TardisDiscoveryClient::init(discovery_config, &fw_config.app).await?;
+                    #[cfg(feature = "web-server")]
+                    {
+                        let web_server = TARDIS_INST.web_server.get();
+                        if web_server.is_running().await {
+                            discovery.register_web_server(fw_config.web_server()).await?;
+                        }
+                    }
+                    TARDIS_INST.discovery.insert("", discovery);
+                }
+                components.push("discovery".to_string());
             }
         }
         #[cfg(feature = "web-server")]
@@ -1069,25 +1582,41 @@ impl TardisFuns {
                 // if there's some inherit webserver
                 if old_server.is_running().await {
                     // 1. shutdown webserver
-                    old_server.shutdown().await?;
+                    old_server.shutdown_for_restart().await?;
                     // 2. load initializers
                     web_server.load_initializer(old_server).await;
                     // 3. restart webserver
                     web_server.start().await?;
                 }
-                TARDIS_INST.web_server.set(web_server)
+                TARDIS_INST.web_server.set(web_server);
+                components.push("web_server".to_string());
             }
         }
         #[cfg(feature = "web-client")]
         {
-            if let Some(web_client_config) = &fw_config.web_client {
-                TARDIS_INST.web_client.init_by(web_client_config).await?;
+            if fw_config.web_client != old_framework_config.web_client {
+                if let Some(web_client_config) = &fw_config.web_client {
+                    TARDIS_INST.web_client.init_by(web_client_config).await?;
+                }
+                components.push("web_client".to_string());
+            }
+        }
+        #[cfg(feature = "web-client-grpc")]
+        {
+            if fw_config.grpc_client != old_framework_config.grpc_client {
+                if let Some(grpc_client_config) = &fw_config.grpc_client {
+                    TARDIS_INST.grpc_client.init_by(grpc_client_config).await?;
+                }
+                components.push("grpc_client".to_string());
             }
         }
         #[cfg(feature = "cache")]
         {
-            if let Some(cache_config) = &fw_config.cache {
-                TARDIS_INST.cache.init_by(cache_config).await?;
+            if fw_config.cache != old_framework_config.cache {
+                if let Some(cache_config) = &fw_config.cache {
+                    TARDIS_INST.cache.init_by(cache_config).await?;
+                }
+                components.push("cache".to_string());
             }
         }
         #[cfg(feature = "mq")]
@@ -1100,21 +1629,63 @@ impl TardisFuns {
                             tracing::error!("[Tardis] Encounter an error while shutting down MQClient [{code}]: {}", e);
                         }
                     }
+                    #[cfg(feature = "reldb-core")]
+                    mq::mq_outbox::TardisMQOutbox::start_configured(mq_config).await?;
+                }
+                components.push("mq".to_string());
+            }
+        }
+        #[cfg(feature = "web-client")]
+        {
+            if fw_config.search != old_framework_config.search {
+                if let Some(search_config) = &fw_config.search {
+                    TARDIS_INST.search.init_by(search_config).await?;
                 }
+                components.push("search".to_string());
             }
         }
         #[cfg(feature = "mail")]
         {
-            if let Some(mail_config) = &fw_config.mail {
-                TARDIS_INST.mail.init_by(mail_config).await?;
+            if fw_config.mail != old_framework_config.mail {
+                if let Some(mail_config) = &fw_config.mail {
+                    TARDIS_INST.mail.init_by(mail_config).await?;
+                }
+                components.push("mail".to_string());
             }
         }
         #[cfg(feature = "os")]
         {
-            if let Some(os_config) = &fw_config.os {
-                TARDIS_INST.os.init_by(os_config).await?;
+            if fw_config.os != old_framework_config.os {
+                if let Some(os_config) = &fw_config.os {
+                    TARDIS_INST.os.init_by(os_config).await?;
+                }
+                components.push("os".to_string());
+            }
+        }
+        #[cfg(feature = "scheduler")]
+        {
+            if fw_config.scheduler != old_framework_config.scheduler {
+                let scheduler = TARDIS_INST.scheduler.get();
+                match &fw_config.scheduler {
+                    Some(scheduler_config) => scheduler.reload(scheduler_config.clone()).await?,
+                    None => scheduler.stop().await,
+                }
+                components.push("scheduler".to_string());
             }
         }
+
+        let mut custom = conf
+            .cs
+            .iter()
+            .filter(|(code, value)| old_custom_config.get(*code).map(|old| old.raw() != *value).unwrap_or(true))
+            .map(|(code, _)| code.clone())
+            .chain(old_custom_config.keys().filter(|code| !conf.cs.contains_key(*code)).cloned())
+            .collect::<Vec<_>>();
+        custom.sort();
+        let changed = config::config_watcher::TardisConfigChanged { components, custom };
+        if !changed.is_empty() {
+            config::config_watcher::publish(changed);
+        }
         Ok(())
     }
 }
@@ -1254,6 +1825,12 @@ pub mod crypto;
 #[cfg(feature = "reldb-core")]
 #[cfg_attr(docsrs, doc(cfg(feature = "reldb-core")))]
 pub mod db;
+#[cfg(feature = "discovery")]
+#[cfg_attr(docsrs, doc(cfg(feature = "discovery")))]
+pub mod discovery;
+#[cfg(feature = "export")]
+#[cfg_attr(docsrs, doc(cfg(feature = "export")))]
+pub mod export;
 #[cfg(feature = "mail")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mail")))]
 pub mod mail;
@@ -1263,6 +1840,9 @@ pub mod mq;
 #[cfg(feature = "os")]
 #[cfg_attr(docsrs, doc(cfg(feature = "os")))]
 pub mod os;
+#[cfg(feature = "scheduler")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scheduler")))]
+pub mod scheduler;
 #[cfg(feature = "web-client")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-client")))]
 pub mod search;
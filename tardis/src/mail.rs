@@ -1 +1,6 @@
+#[cfg(feature = "mail-api")]
+pub(crate) mod mail_api;
 pub mod mail_client;
+pub(crate) mod mail_mock;
+#[cfg(feature = "mail-template")]
+pub mod mail_template;
@@ -0,0 +1,259 @@
+//! HTTP API transports of the mail providers / 邮件服务商的HTTP API传输方式
+//!
+//! For the environments that block the outbound SMTP.
+//!
+//! 用于禁止对外SMTP的环境.
+//!
+//! * `sendgrid` : [Mail Send v3](https://docs.sendgrid.com/api-reference/mail-send/mail-send)
+//! * `mailgun` : [Messages MIME](https://documentation.mailgun.com/en/latest/api-sending.html#sending) , the MIME message is sent as is
+//!   / MIME消息原样发送
+//! * `aliyun` : [SingleSendMail](https://help.aliyun.com/document_detail/29444.html) , without the CC, BCC and attachments
+//!   / 不支持抄送、密送及附件
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use lettre::message::Mailbox;
+use lettre::Message;
+use serde_json::{json, Value};
+use sha1::Sha1;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::mail::MailModuleConfig;
+use crate::mail::mail_client::{TardisMailSendReq, TardisMailTransport};
+use crate::TardisFuns;
+
+const SENDGRID_ENDPOINT: &str = "https://api.sendgrid.com";
+const MAILGUN_ENDPOINT: &str = "https://api.mailgun.net";
+const ALIYUN_ENDPOINT: &str = "https://dm.aliyuncs.com";
+const ALIYUN_DEFAULT_REGION: &str = "cn-hangzhou";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TardisMailApiProvider {
+    SendGrid,
+    Mailgun,
+    Aliyun,
+}
+
+pub(crate) struct TardisMailApiTransport {
+    provider: TardisMailApiProvider,
+    endpoint: String,
+    api_key: String,
+    api_secret: String,
+    api_domain: String,
+    http_client: reqwest::Client,
+}
+
+impl TardisMailApiTransport {
+    pub(crate) fn init(
+        MailModuleConfig {
+            kind,
+            api_endpoint,
+            api_key,
+            api_secret,
+            api_domain,
+            ..
+        }: &MailModuleConfig,
+    ) -> TardisResult<Self> {
+        let (provider, default_endpoint) = match kind.as_str() {
+            "sendgrid" => (TardisMailApiProvider::SendGrid, SENDGRID_ENDPOINT),
+            "mailgun" => (TardisMailApiProvider::Mailgun, MAILGUN_ENDPOINT),
+            "aliyun" => (TardisMailApiProvider::Aliyun, ALIYUN_ENDPOINT),
+            _ => {
+                return Err(TardisError::not_implemented(
+                    &format!("[Tardis.MailClient] Unsupported mail kind {kind}"),
+                    "501-tardis-mail-kind-error",
+                ))
+            }
+        };
+        if api_key.is_empty() {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.MailClient] The api_key is required by the {kind} transport"),
+                "406-tardis-mail-config-error",
+            ));
+        }
+        if provider == TardisMailApiProvider::Mailgun && api_domain.is_empty() {
+            return Err(TardisError::format_error(
+                "[Tardis.MailClient] The api_domain is required by the mailgun transport",
+                "406-tardis-mail-config-error",
+            ));
+        }
+        if provider == TardisMailApiProvider::Aliyun && api_secret.is_empty() {
+            return Err(TardisError::format_error(
+                "[Tardis.MailClient] The api_secret is required by the aliyun transport",
+                "406-tardis-mail-config-error",
+            ));
+        }
+        let endpoint = if api_endpoint.is_empty() { default_endpoint } else { api_endpoint.as_str() };
+        Ok(TardisMailApiTransport {
+            provider,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            api_domain: api_domain.to_string(),
+            http_client: reqwest::Client::new(),
+        })
+    }
+
+    async fn send_by_sendgrid(&self, req: &TardisMailSendReq, from: &str) -> TardisResult<()> {
+        let addresses = |addresses: &[String]| addresses.iter().map(|address| sendgrid_address(address)).collect::<TardisResult<Vec<_>>>();
+        let mut personalization = json!({ "to": addresses(&req.to)? });
+        if !req.cc.is_empty() {
+            personalization["cc"] = Value::Array(addresses(&req.cc)?);
+        }
+        if !req.bcc.is_empty() {
+            personalization["bcc"] = Value::Array(addresses(&req.bcc)?);
+        }
+        // the empty content is rejected by SendGrid
+        let mut content = Vec::new();
+        if !req.txt_body.is_empty() || req.html_body.is_none() {
+            content.push(json!({ "type": "text/plain", "value": req.txt_body }));
+        }
+        if let Some(html_body) = &req.html_body {
+            content.push(json!({ "type": "text/html", "value": html_body }));
+        }
+        let mut body = json!({
+            "personalizations": [personalization],
+            "from": sendgrid_address(from)?,
+            "subject": req.subject,
+            "content": content,
+        });
+        match req.reply_to.as_slice() {
+            [] => {}
+            [reply_to] => body["reply_to"] = sendgrid_address(reply_to)?,
+            reply_to => body["reply_to_list"] = Value::Array(addresses(reply_to)?),
+        }
+        if !req.attachments.is_empty() {
+            body["attachments"] = Value::Array(
+                req.attachments
+                    .iter()
+                    .map(|attachment| {
+                        let mut value = json!({
+                            "content": STANDARD.encode(&attachment.content),
+                            "type": attachment.content_type,
+                            "filename": attachment.filename,
+                            "disposition": if attachment.content_id.is_some() { "inline" } else { "attachment" },
+                        });
+                        if let Some(content_id) = &attachment.content_id {
+                            value["content_id"] = Value::String(content_id.to_string());
+                        }
+                        value
+                    })
+                    .collect(),
+            );
+        }
+        let response = self.http_client.post(format!("{}/v3/mail/send", self.endpoint)).bearer_auth(&self.api_key).json(&body).send().await;
+        check_response(response).await
+    }
+
+    async fn send_by_mailgun(&self, message: Message) -> TardisResult<()> {
+        // the envelope contains the BCC recipients, which are removed from the formatted message
+        let to = message.envelope().to().iter().map(|address| address.to_string()).collect::<Vec<_>>().join(",");
+        let form = reqwest::multipart::Form::new().text("to", to).part(
+            "message",
+            reqwest::multipart::Part::bytes(message.formatted())
+                .file_name("message.mime")
+                .mime_str("message/rfc822")
+                .map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Could not build the Mailgun request: {error}"), "-1-tardis-mail-error"))?,
+        );
+        let response = self.http_client.post(format!("{}/v3/{}/messages.mime", self.endpoint, self.api_domain)).basic_auth("api", Some(&self.api_key)).multipart(form).send().await;
+        check_response(response).await
+    }
+
+    async fn send_by_aliyun(&self, req: &TardisMailSendReq, from: &str) -> TardisResult<()> {
+        if !req.cc.is_empty() || !req.bcc.is_empty() || !req.attachments.is_empty() {
+            return Err(TardisError::not_implemented(
+                "[Tardis.MailClient] The CC, BCC and attachments aren't supported by the aliyun transport",
+                "501-tardis-mail-unsupported",
+            ));
+        }
+        let from: Mailbox = from.parse()?;
+        let to = req.to.iter().map(|to| to.parse::<Mailbox>().map(|to| to.email.to_string())).collect::<Result<Vec<_>, _>>()?.join(",");
+        let mut params = vec![
+            ("AccessKeyId", self.api_key.clone()),
+            ("AccountName", from.email.to_string()),
+            ("Action", "SingleSendMail".to_string()),
+            ("AddressType", "1".to_string()),
+            ("Format", "JSON".to_string()),
+            ("RegionId", aliyun_region(&self.endpoint)),
+            ("ReplyToAddress", "false".to_string()),
+            ("SignatureMethod", "HMAC-SHA1".to_string()),
+            ("SignatureNonce", TardisFuns::field.nanoid()),
+            ("SignatureVersion", "1.0".to_string()),
+            ("Subject", req.subject.clone()),
+            ("TextBody", req.txt_body.clone()),
+            ("Timestamp", Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            ("ToAddress", to),
+            ("Version", "2015-11-23".to_string()),
+        ];
+        if let Some(name) = &from.name {
+            params.push(("FromAlias", name.to_string()));
+        }
+        if let Some(html_body) = &req.html_body {
+            params.push(("HtmlBody", html_body.to_string()));
+        }
+        if let Some(reply_to) = req.reply_to.first() {
+            params.push(("ReplyAddress", reply_to.parse::<Mailbox>()?.email.to_string()));
+        }
+        let signature = aliyun_signature(&self.api_secret, &params)?;
+        params.push(("Signature", signature));
+        let response = self.http_client.post(format!("{}/", self.endpoint)).form(&params).send().await;
+        check_response(response).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TardisMailTransport for TardisMailApiTransport {
+    async fn send(&self, req: &TardisMailSendReq, from: &str, message: Message) -> TardisResult<()> {
+        match self.provider {
+            TardisMailApiProvider::SendGrid => self.send_by_sendgrid(req, from).await,
+            TardisMailApiProvider::Mailgun => self.send_by_mailgun(message).await,
+            TardisMailApiProvider::Aliyun => self.send_by_aliyun(req, from).await,
+        }
+    }
+}
+
+fn sendgrid_address(address: &str) -> TardisResult<Value> {
+    let mailbox: Mailbox = address.parse()?;
+    Ok(match mailbox.name {
+        Some(name) => json!({ "email": mailbox.email.to_string(), "name": name }),
+        None => json!({ "email": mailbox.email.to_string() }),
+    })
+}
+
+/// The region in the endpoint, e.g. `dm.ap-southeast-1.aliyuncs.com` / 端点中的区域，如 `dm.ap-southeast-1.aliyuncs.com`
+fn aliyun_region(endpoint: &str) -> String {
+    let host = endpoint.split_once("://").map(|(_, host)| host).unwrap_or(endpoint);
+    host.strip_prefix("dm.").and_then(|host| host.strip_suffix(".aliyuncs.com")).filter(|region| !region.contains('.')).unwrap_or(ALIYUN_DEFAULT_REGION).to_string()
+}
+
+/// The percent encoding of Aliyun, i.e. RFC 3986 / 阿里云的百分号编码，即RFC 3986
+fn aliyun_encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>().replace('+', "%20").replace('*', "%2A").replace("%7E", "~")
+}
+
+/// The RPC signature of Aliyun / 阿里云的RPC签名
+fn aliyun_signature(secret: &str, params: &[(&str, String)]) -> TardisResult<String> {
+    let mut params = params.iter().map(|(key, value)| (aliyun_encode(key), aliyun_encode(value))).collect::<Vec<_>>();
+    params.sort();
+    let query = params.iter().map(|(key, value)| format!("{key}={value}")).collect::<Vec<_>>().join("&");
+    let string_to_sign = format!("POST&{}&{}", aliyun_encode("/"), aliyun_encode(&query));
+    let mut mac = Hmac::<Sha1>::new_from_slice(format!("{secret}&").as_bytes())
+        .map_err(|error| TardisError::format_error(&format!("[Tardis.MailClient] Invalid api_secret: {error}"), "406-tardis-mail-config-error"))?;
+    mac.update(string_to_sign.as_bytes());
+    Ok(STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+async fn check_response(response: Result<reqwest::Response, reqwest::Error>) -> TardisResult<()> {
+    let response = response.map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Could not send email: {error}"), "-1-tardis-mail-error"))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(TardisError::internal_error(
+        &format!("[Tardis.MailClient] Could not send email: [{status}] {body}"),
+        "-1-tardis-mail-error",
+    ))
+}
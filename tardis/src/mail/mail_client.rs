@@ -1,17 +1,59 @@
-use lettre::message::{header, MultiPart, SinglePart};
+use std::path::Path;
+
+use lettre::message::{header, Attachment, MultiPart, SinglePart};
 use lettre::transport::smtp::client::{Tls, TlsParametersBuilder, TlsVersion};
 use lettre::{address, error, transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
 use tracing::{error, info, trace, warn};
 use typed_builder::TypedBuilder;
 
 use crate::basic::error::TardisError;
+use crate::basic::metrics::observe_client;
 use crate::config::config_dto::component::mail::MailModuleConfig;
+use crate::mail::mail_mock::TardisMailMockTransport;
+#[cfg(feature = "mail-template")]
+use crate::mail::mail_template::{TardisMailContent, TardisMailTemplate, TardisMailTemplates};
 use crate::utils::initializer::InitBy;
 use crate::{TardisFuns, TardisResult};
 
 pub struct TardisMailClient {
-    client: AsyncSmtpTransport<Tokio1Executor>,
+    transport: Box<dyn TardisMailTransport + Sync + Send>,
     default_from: String,
+    #[cfg(feature = "mail-template")]
+    templates: std::sync::RwLock<TardisMailTemplates>,
+}
+
+/// The sent mail recorded by the `mock` transport / `mock` 传输方式记录的已发送邮件
+#[derive(Debug, Clone)]
+pub struct TardisMailSentMessage {
+    /// The request with the resolved sender / 已解析发件人的请求
+    pub req: TardisMailSendReq,
+    /// The formatted MIME message / 格式化后的MIME消息
+    pub raw: String,
+}
+
+#[async_trait::async_trait]
+pub(crate) trait TardisMailTransport {
+    /// Send the mail, `from` is the resolved sender and `message` is the MIME message of the request
+    /// / 发送邮件， `from` 为解析后的发件人， `message` 为请求的MIME消息
+    async fn send(&self, req: &TardisMailSendReq, from: &str, message: Message) -> TardisResult<()>;
+
+    fn sent_messages(&self) -> TardisResult<Vec<TardisMailSentMessage>> {
+        Err(TardisError::not_implemented(
+            "[Tardis.MailClient] The sent messages are only recorded by the mock transport",
+            "501-tardis-mail-mock-only",
+        ))
+    }
+
+    fn clear_sent_messages(&self) -> TardisResult<()> {
+        Err(TardisError::not_implemented(
+            "[Tardis.MailClient] The sent messages are only recorded by the mock transport",
+            "501-tardis-mail-mock-only",
+        ))
+    }
+}
+
+struct TardisMailSmtpTransport {
+    client: AsyncSmtpTransport<Tokio1Executor>,
 }
 
 #[async_trait::async_trait]
@@ -22,44 +64,36 @@ impl InitBy<MailModuleConfig> for TardisMailClient {
 }
 
 impl TardisMailClient {
-    pub fn init(
-        MailModuleConfig {
-            smtp_host,
-            smtp_port,
-            smtp_username,
-            smtp_password,
-            default_from,
-            starttls,
-        }: &MailModuleConfig,
-    ) -> TardisResult<TardisMailClient> {
-        info!("[Tardis.MailClient] Initializing");
-        let creds = Credentials::new(smtp_username.to_string(), smtp_password.to_string());
-        let tls = TlsParametersBuilder::new(smtp_host.to_string())
-            .dangerous_accept_invalid_certs(true)
-            .dangerous_accept_invalid_hostnames(true)
-            .set_min_tls_version(TlsVersion::Tlsv10)
-            .build()
-            .map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Tls build error: {error}"), "500-tardis-mail-init-error"))?;
-        let (client, tls) = if *starttls {
-            info!("[Tardis.MailClient] Using STARTTLS");
-            (AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host), Tls::Opportunistic(tls))
-        } else {
-            (AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host), Tls::Wrapper(tls))
+    pub fn init(config: &MailModuleConfig) -> TardisResult<TardisMailClient> {
+        info!("[Tardis.MailClient] Initializing for {}", config.kind);
+        let transport: Box<dyn TardisMailTransport + Sync + Send> = match config.kind.as_str() {
+            "smtp" => Box::new(TardisMailSmtpTransport::init(config)?),
+            "mock" => Box::new(TardisMailMockTransport::new()),
+            #[cfg(feature = "mail-api")]
+            "sendgrid" | "mailgun" | "aliyun" => Box::new(crate::mail::mail_api::TardisMailApiTransport::init(config)?),
+            kind => {
+                return Err(TardisError::not_implemented(
+                    &format!("[Tardis.MailClient] Unsupported mail kind {kind}, the HTTP API kinds require the mail-api feature"),
+                    "501-tardis-mail-kind-error",
+                ))
+            }
         };
-        let client = client
-            .map_err(|_| TardisError::internal_error(&format!("[Tardis.MailClient] Failed to create SMTP client: {smtp_host}"), "500-tardis-mail-init-error"))?
-            .credentials(creds)
-            .tls(tls)
-            .port(*smtp_port)
-            .build();
         info!("[Tardis.MailClient] Initialized");
         TardisResult::Ok(TardisMailClient {
-            client,
-            default_from: default_from.to_string(),
+            transport,
+            default_from: config.default_from.to_string(),
+            #[cfg(feature = "mail-template")]
+            templates: std::sync::RwLock::new(TardisMailTemplates::new()),
         })
     }
 
-    pub async fn send(&self, req: &TardisMailSendReq) -> TardisResult<()> {
+    /// Build the MIME message of the request / 构建请求的MIME消息
+    ///
+    /// The message is `multipart/mixed` with the attachments, the body is `multipart/related` with the inline attachments,
+    /// and the plain text and the HTML bodies are `multipart/alternative` .
+    ///
+    /// 有附件时消息为 `multipart/mixed` ，有内联附件时正文为 `multipart/related` ，纯文本及HTML正文为 `multipart/alternative` .
+    pub fn build_message(&self, req: &TardisMailSendReq) -> TardisResult<Message> {
         let mut email = Message::builder();
         email = if let Some(from) = &req.from {
             email.from(from.parse()?)
@@ -79,28 +113,100 @@ impl TardisMailClient {
             email = email.bcc(t.parse()?)
         }
         email = email.subject(&req.subject);
-        let email = if let Some(html_body) = &req.html_body {
-            email.multipart(
-                MultiPart::alternative()
-                    .singlepart(SinglePart::builder().header(header::ContentType::TEXT_PLAIN).body(req.txt_body.clone()))
-                    .singlepart(SinglePart::builder().header(header::ContentType::TEXT_HTML).body(html_body.to_string())),
-            )?
-        } else {
-            email.header(header::ContentType::TEXT_PLAIN).body(req.txt_body.clone())?
+        if req.attachments.is_empty() {
+            let email = if let Some(html_body) = &req.html_body {
+                email.multipart(
+                    MultiPart::alternative()
+                        .singlepart(SinglePart::builder().header(header::ContentType::TEXT_PLAIN).body(req.txt_body.clone()))
+                        .singlepart(SinglePart::builder().header(header::ContentType::TEXT_HTML).body(html_body.to_string())),
+                )?
+            } else {
+                email.header(header::ContentType::TEXT_PLAIN).body(req.txt_body.clone())?
+            };
+            return Ok(email);
+        }
+        let text = SinglePart::builder().header(header::ContentType::TEXT_PLAIN).body(req.txt_body.clone());
+        let mut mixed = match &req.html_body {
+            Some(html_body) => {
+                let alternative = MultiPart::alternative().singlepart(text).singlepart(SinglePart::builder().header(header::ContentType::TEXT_HTML).body(html_body.to_string()));
+                let inlines = req.attachments.iter().filter(|attachment| attachment.content_id.is_some()).collect::<Vec<_>>();
+                if inlines.is_empty() {
+                    MultiPart::mixed().multipart(alternative)
+                } else {
+                    let mut related = MultiPart::related().multipart(alternative);
+                    for attachment in inlines {
+                        related = related.singlepart(attachment.to_part()?);
+                    }
+                    MultiPart::mixed().multipart(related)
+                }
+            }
+            None => MultiPart::mixed().singlepart(text),
         };
-        trace!(
-            "[Tardis.MailClient] Sending email:{}, from: {}, to: {}",
-            req.subject,
-            req.from.as_ref().unwrap_or(&self.default_from.clone()),
-            req.to.join(",")
-        );
-        match self.client.send(email).await {
-            Ok(_) => Ok(()),
-            Err(error) => Err(TardisError::internal_error(
-                &format!("[Tardis.MailClient] Could not send email: {error}"),
-                "-1-tardis-mail-error",
-            )),
+        // the inline attachments are referenced by the HTML body only
+        for attachment in req.attachments.iter().filter(|attachment| attachment.content_id.is_none() || req.html_body.is_none()) {
+            mixed = mixed.singlepart(attachment.to_part()?);
         }
+        Ok(email.multipart(mixed)?)
+    }
+
+    pub async fn send(&self, req: &TardisMailSendReq) -> TardisResult<()> {
+        observe_client("mail", "send", async {
+            let email = self.build_message(req)?;
+            trace!(
+                "[Tardis.MailClient] Sending email:{}, from: {}, to: {}",
+                req.subject,
+                req.from.as_deref().unwrap_or(&self.default_from),
+                req.to.join(",")
+            );
+            let from = req.from.as_deref().unwrap_or(&self.default_from);
+            self.transport.send(req, from, email).await
+        })
+        .await
+    }
+
+    /// Register the template of the language, `None` for the fallback of all the languages
+    /// / 注册该语言的模板， `None` 为所有语言的兜底
+    ///
+    /// The template with the same name and language is replaced.
+    ///
+    /// 替换相同名称及语言的模板.
+    #[cfg(feature = "mail-template")]
+    pub fn register_template(&self, name: &str, lang: Option<&str>, template: &TardisMailTemplate) -> TardisResult<()> {
+        trace!("[Tardis.MailClient] Registering template {}:{}", name, lang.unwrap_or(""));
+        self.templates
+            .write()
+            .map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Templates lock error: {error:?}"), "500-tardis-mail-template-error"))?
+            .register(name, lang, template)
+    }
+
+    /// Render the template of the language, see [`mail_template`](crate::mail::mail_template) / 渲染该语言的模板，见 [`mail_template`](crate::mail::mail_template)
+    #[cfg(feature = "mail-template")]
+    pub fn render_template<T: serde::Serialize>(&self, name: &str, lang: Option<&str>, vars: &T) -> TardisResult<TardisMailContent> {
+        self.templates
+            .read()
+            .map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Templates lock error: {error:?}"), "500-tardis-mail-template-error"))?
+            .render(name, lang, vars)
+    }
+
+    /// Send the mail rendered by the template, the subject and the bodies of the request are replaced
+    /// / 发送由模板渲染的邮件，请求的主题及正文会被替换
+    #[cfg(feature = "mail-template")]
+    pub async fn send_by_template<T: serde::Serialize>(&self, name: &str, lang: Option<&str>, vars: &T, mut req: TardisMailSendReq) -> TardisResult<()> {
+        let content = self.render_template(name, lang, vars)?;
+        req.subject = content.subject;
+        req.txt_body = content.txt_body;
+        req.html_body = content.html_body;
+        self.send(&req).await
+    }
+
+    /// The mails sent by the `mock` transport, in the sending order / `mock` 传输方式发送的邮件，按发送顺序
+    pub fn sent_messages(&self) -> TardisResult<Vec<TardisMailSentMessage>> {
+        self.transport.sent_messages()
+    }
+
+    /// Clear the mails recorded by the `mock` transport / 清空 `mock` 传输方式记录的邮件
+    pub fn clear_sent_messages(&self) -> TardisResult<()> {
+        self.transport.clear_sent_messages()
     }
 
     pub fn send_quiet(module_code: String, req: TardisMailSendReq) -> TardisResult<()> {
@@ -115,6 +221,53 @@ impl TardisMailClient {
     }
 }
 
+impl TardisMailSmtpTransport {
+    fn init(
+        MailModuleConfig {
+            smtp_host,
+            smtp_port,
+            smtp_username,
+            smtp_password,
+            starttls,
+            ..
+        }: &MailModuleConfig,
+    ) -> TardisResult<Self> {
+        let creds = Credentials::new(smtp_username.to_string(), smtp_password.to_string());
+        let tls = TlsParametersBuilder::new(smtp_host.to_string())
+            .dangerous_accept_invalid_certs(true)
+            .dangerous_accept_invalid_hostnames(true)
+            .set_min_tls_version(TlsVersion::Tlsv10)
+            .build()
+            .map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Tls build error: {error}"), "500-tardis-mail-init-error"))?;
+        let (client, tls) = if *starttls {
+            info!("[Tardis.MailClient] Using STARTTLS");
+            (AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host), Tls::Opportunistic(tls))
+        } else {
+            (AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host), Tls::Wrapper(tls))
+        };
+        let client = client
+            .map_err(|_| TardisError::internal_error(&format!("[Tardis.MailClient] Failed to create SMTP client: {smtp_host}"), "500-tardis-mail-init-error"))?
+            .credentials(creds)
+            .tls(tls)
+            .port(*smtp_port)
+            .build();
+        Ok(TardisMailSmtpTransport { client })
+    }
+}
+
+#[async_trait::async_trait]
+impl TardisMailTransport for TardisMailSmtpTransport {
+    async fn send(&self, _: &TardisMailSendReq, _: &str, message: Message) -> TardisResult<()> {
+        match self.client.send(message).await {
+            Ok(_) => Ok(()),
+            Err(error) => Err(TardisError::internal_error(
+                &format!("[Tardis.MailClient] Could not send email: {error}"),
+                "-1-tardis-mail-error",
+            )),
+        }
+    }
+}
+
 /// # TardisMailSendReq
 /// The mail send request.
 #[derive(Debug, Clone, TypedBuilder)]
@@ -143,6 +296,58 @@ pub struct TardisMailSendReq {
     /// Email sender.
     #[builder(default, setter(into, strip_option))]
     pub from: Option<String>,
+    /// Attachments, the inline ones are referenced by `cid:<content_id>` in the HTML body.
+    #[builder(default, setter(into))]
+    pub attachments: Vec<TardisMailAttachment>,
+}
+
+/// # TardisMailAttachment
+/// The attachment of the mail / 邮件附件
+#[derive(Debug, Clone)]
+pub struct TardisMailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+    /// The content id of the inline attachment, e.g. the images in the HTML body / 内联附件的内容id，如HTML正文中的图片
+    pub content_id: Option<String>,
+}
+
+impl TardisMailAttachment {
+    pub fn new(filename: impl Into<String>, content_type: impl Into<String>, content: Vec<u8>) -> Self {
+        TardisMailAttachment {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            content,
+            content_id: None,
+        }
+    }
+
+    /// Read the attachment from the file, named by the file name / 从文件读取附件，以文件名命名
+    pub fn from_file(path: impl AsRef<Path>, content_type: impl Into<String>) -> TardisResult<Self> {
+        let path = path.as_ref();
+        let filename = path.file_name().map(|filename| filename.to_string_lossy().to_string()).unwrap_or_default();
+        Ok(Self::new(filename, content_type, std::fs::read(path)?))
+    }
+
+    /// Reference the attachment by `cid:<content_id>` in the HTML body / 在HTML正文中通过 `cid:<content_id>` 引用该附件
+    pub fn inline(mut self, content_id: impl Into<String>) -> Self {
+        self.content_id = Some(content_id.into());
+        self
+    }
+
+    fn to_part(&self) -> TardisResult<SinglePart> {
+        let content_type = header::ContentType::parse(&self.content_type).map_err(|error| {
+            TardisError::format_error(
+                &format!("[Tardis.MailClient] Invalid content type {} of {}: {error}", self.content_type, self.filename),
+                "406-tardis-mail-attachment-error",
+            )
+        })?;
+        let attachment = match &self.content_id {
+            Some(content_id) => Attachment::new_inline(content_id.clone()),
+            None => Attachment::new(self.filename.clone()),
+        };
+        Ok(attachment.body(self.content.clone(), content_type))
+    }
 }
 
 impl From<address::AddressError> for TardisError {
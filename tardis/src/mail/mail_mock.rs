@@ -0,0 +1,42 @@
+use std::sync::Mutex;
+
+use lettre::Message;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::mail::mail_client::{TardisMailSendReq, TardisMailSentMessage, TardisMailTransport};
+
+/// Mock transport, the mails are recorded in memory instead of being sent / 模拟传输方式，邮件记录在内存中而不发送
+pub(crate) struct TardisMailMockTransport {
+    sent: Mutex<Vec<TardisMailSentMessage>>,
+}
+
+impl TardisMailMockTransport {
+    pub(crate) fn new() -> Self {
+        TardisMailMockTransport { sent: Mutex::new(Vec::new()) }
+    }
+
+    fn lock(&self) -> TardisResult<std::sync::MutexGuard<'_, Vec<TardisMailSentMessage>>> {
+        self.sent.lock().map_err(|error| TardisError::internal_error(&format!("[Tardis.MailClient] Mock lock error: {error:?}"), "500-tardis-mail-mock-error"))
+    }
+}
+
+#[async_trait::async_trait]
+impl TardisMailTransport for TardisMailMockTransport {
+    async fn send(&self, req: &TardisMailSendReq, from: &str, message: Message) -> TardisResult<()> {
+        let mut req = req.clone();
+        req.from = Some(from.to_string());
+        let raw = String::from_utf8_lossy(&message.formatted()).to_string();
+        self.lock()?.push(TardisMailSentMessage { req, raw });
+        Ok(())
+    }
+
+    fn sent_messages(&self) -> TardisResult<Vec<TardisMailSentMessage>> {
+        Ok(self.lock()?.clone())
+    }
+
+    fn clear_sent_messages(&self) -> TardisResult<()> {
+        self.lock()?.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Mail templates / 邮件模板
+//!
+//! The templates are registered by name with the optional language, and rendered by [handlebars](https://handlebarsjs.com/guide/) in the strict mode,
+//! so the missing variables are reported instead of being rendered as empty:
+//!
+//! 模板按名称及可选的语言注册，通过 [handlebars](https://handlebarsjs.com/guide/) 以严格模式渲染，因此缺失的变量会报错而不是渲染为空：
+//!
+//! * the variant of the language is resolved by the exact language, the primary language, the default language of the application and then the variant without language,
+//!   e.g. `zh-cn` , `zh` , `en` , none / 语言变体依次按完整语言、主语言、应用的默认语言、无语言的变体解析，如 `zh-cn` 、 `zh` 、 `en` 、无
+//! * the HTML body is escaped, the subject and the plain text body are not / HTML正文会被转义，主题及纯文本正文不会
+//! * `{{t "code" "default message"}}` renders the message of [`TardisLocale`] in the language / `{{t "code" "默认消息"}}` 渲染该语言的 [`TardisLocale`] 消息
+//! * `@root._lang` is the resolved language / `@root._lang` 为解析后的语言
+//!
+//! # Examples
+//! ```ignore
+//! TardisFuns::mail().register_template(
+//!     "welcome",
+//!     Some("zh-cn"),
+//!     &TardisMailTemplate::new("欢迎 {{name}}", "你好 {{name}}").html_body("<h1>你好 {{name}}</h1>"),
+//! )?;
+//! TardisFuns::mail()
+//!     .send_by_template("welcome", Some("zh-CN"), &json!({ "name": "tardis" }), TardisMailSendReq::builder().to(["a@example.com".to_string()]).build())
+//!     .await?;
+//! ```
+use std::collections::HashSet;
+
+use handlebars::{no_escape, Context, Handlebars, Helper, HelperResult, Output, RenderContext};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::basic::error::TardisError;
+use crate::basic::locale::TardisLocale;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+const LANG_VAR: &str = "_lang";
+
+/// Mail template / 邮件模板
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TardisMailTemplate {
+    pub subject: String,
+    pub txt_body: String,
+    pub html_body: Option<String>,
+}
+
+impl TardisMailTemplate {
+    pub fn new(subject: impl Into<String>, txt_body: impl Into<String>) -> Self {
+        TardisMailTemplate {
+            subject: subject.into(),
+            txt_body: txt_body.into(),
+            html_body: None,
+        }
+    }
+
+    pub fn html_body(mut self, html_body: impl Into<String>) -> Self {
+        self.html_body = Some(html_body.into());
+        self
+    }
+}
+
+/// Rendered content of the template / 模板渲染后的内容
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TardisMailContent {
+    pub subject: String,
+    pub txt_body: String,
+    pub html_body: Option<String>,
+    /// The resolved language, empty for the variant without language / 解析后的语言，无语言的变体为空
+    pub lang: String,
+}
+
+pub(crate) struct TardisMailTemplates {
+    text: Handlebars<'static>,
+    html: Handlebars<'static>,
+    /// name and language of the registered templates / 已注册模板的名称及语言
+    registered: HashSet<(String, String)>,
+}
+
+impl TardisMailTemplates {
+    pub(crate) fn new() -> Self {
+        let mut text = Handlebars::new();
+        text.set_strict_mode(true);
+        text.register_escape_fn(no_escape);
+        text.register_helper("t", Box::new(locale_helper));
+        let mut html = Handlebars::new();
+        html.set_strict_mode(true);
+        html.register_helper("t", Box::new(locale_helper));
+        TardisMailTemplates {
+            text,
+            html,
+            registered: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn register(&mut self, name: &str, lang: Option<&str>, template: &TardisMailTemplate) -> TardisResult<()> {
+        let lang = lang.unwrap_or("").to_lowercase();
+        let register = |registry: &mut Handlebars<'static>, part: &str, content: &str| {
+            registry.register_template_string(&template_key(name, &lang, part), content).map_err(|error| {
+                TardisError::format_error(
+                    &format!("[Tardis.MailClient] Invalid {part} of the template {name}:{lang}: {error}"),
+                    "406-tardis-mail-template-error",
+                )
+            })
+        };
+        register(&mut self.text, "subject", &template.subject)?;
+        register(&mut self.text, "txt", &template.txt_body)?;
+        match &template.html_body {
+            Some(html_body) => register(&mut self.html, "html", html_body)?,
+            None => {
+                self.html.unregister_template(&template_key(name, &lang, "html"));
+            }
+        }
+        self.registered.insert((name.to_string(), lang));
+        Ok(())
+    }
+
+    pub(crate) fn render<T: Serialize>(&self, name: &str, lang: Option<&str>, vars: &T) -> TardisResult<TardisMailContent> {
+        let lang = self
+            .resolve_lang(name, lang)
+            .ok_or_else(|| TardisError::not_found(&format!("[Tardis.MailClient] Template {name} isn't registered"), "404-tardis-mail-template-not-exist"))?;
+        let mut data = TardisFuns::json.obj_to_json(vars)?;
+        if let Value::Object(data) = &mut data {
+            data.insert(LANG_VAR.to_string(), Value::String(lang.clone()));
+        }
+        let render = |registry: &Handlebars<'static>, part: &str| {
+            registry.render(&template_key(name, &lang, part), &data).map_err(|error| {
+                TardisError::format_error(
+                    &format!("[Tardis.MailClient] Failed to render {part} of the template {name}:{lang}: {error}"),
+                    "406-tardis-mail-template-error",
+                )
+            })
+        };
+        let html_key = template_key(name, &lang, "html");
+        Ok(TardisMailContent {
+            subject: render(&self.text, "subject")?,
+            txt_body: render(&self.text, "txt")?,
+            html_body: if self.html.has_template(&html_key) { Some(render(&self.html, "html")?) } else { None },
+            lang,
+        })
+    }
+
+    fn resolve_lang(&self, name: &str, lang: Option<&str>) -> Option<String> {
+        let lang = lang.map(str::to_lowercase);
+        let primary_lang = lang.as_deref().and_then(|lang| lang.split_once('-')).map(|(primary, _)| primary.to_string());
+        let default_lang = TardisFuns::fw_config_opt().and_then(|config| config.app.default_lang.clone()).map(|lang| lang.to_lowercase());
+        [lang, primary_lang, default_lang, Some(String::new())].into_iter().flatten().find(|lang| self.registered.contains(&(name.to_string(), lang.clone())))
+    }
+}
+
+fn template_key(name: &str, lang: &str, part: &str) -> String {
+    format!("{name}:{lang}:{part}")
+}
+
+fn locale_helper(helper: &Helper, _: &Handlebars, context: &Context, _: &mut RenderContext, out: &mut dyn Output) -> HelperResult {
+    let code = helper.param(0).and_then(|param| param.value().as_str()).unwrap_or("");
+    let default_message = helper.param(1).and_then(|param| param.value().as_str()).unwrap_or(code);
+    let lang = context.data().get(LANG_VAR).and_then(Value::as_str).unwrap_or("");
+    let message = TardisLocale::get_message(code, default_message, lang).unwrap_or_else(|_| default_message.to_string());
+    out.write(&message)?;
+    Ok(())
+}
@@ -1 +1,10 @@
 pub mod mq_client;
+#[cfg(feature = "mq-kafka")]
+pub(crate) mod mq_kafka;
+pub mod mq_message;
+#[cfg(feature = "mq-nats")]
+pub(crate) mod mq_nats;
+#[cfg(feature = "reldb-core")]
+pub mod mq_outbox;
+#[cfg(feature = "mq-redis")]
+pub(crate) mod mq_redis;
@@ -1,20 +1,47 @@
 use std::collections::HashMap;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
 
 use amq_protocol_types::{AMQPValue, LongString, ShortString};
 use futures_util::lock::Mutex;
 use futures_util::stream::StreamExt;
+use futures_util::FutureExt;
+use lapin::message::Delivery;
 use lapin::{options::*, types::FieldTable, BasicProperties, Channel, Connection, ConnectionProperties, Consumer, ExchangeKind};
 
+use crate::basic::dto::TardisContext;
+use crate::basic::metrics::observe_client;
 use crate::basic::result::TardisResult;
-use crate::config::config_dto::component::mq::MQModuleConfig;
+use crate::basic::tracing::TardisTracing;
+use crate::config::config_dto::component::mq::{MQConsumerConfig, MQExchangeKind, MQKind, MQModuleConfig, MQTopologyConfig};
+use crate::mq::mq_message::{TardisMQMessage, TardisMQPayload};
 
 use crate::{basic::error::TardisError, utils::initializer::InitBy};
-use tracing::{error, info, trace};
+use tracing::{error, info, trace, warn, Instrument};
 
 pub struct TardisMQClient {
-    con: Connection,
-    channels: Mutex<Vec<Channel>>,
+    backend: MQBackend,
+    consumer: MQConsumerConfig,
+    #[cfg(feature = "reldb-core")]
+    outbox: crate::config::config_dto::component::mq::MQOutboxConfig,
+    #[cfg(feature = "reldb-core")]
+    outbox_relay: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+enum MQBackend {
+    Amqp {
+        con: Connection,
+        channels: Mutex<Vec<Channel>>,
+    },
+    #[cfg(feature = "mq-kafka")]
+    Kafka(crate::mq::mq_kafka::TardisKafkaMQ),
+    #[cfg(feature = "mq-nats")]
+    Nats(crate::mq::mq_nats::TardisNatsMQ),
+    #[cfg(feature = "mq-redis")]
+    RedisStream(crate::mq::mq_redis::TardisRedisStreamMQ),
+    #[cfg(feature = "test")]
+    Memory(crate::test::memory_mq::TardisMemoryMQ),
 }
 
 #[async_trait::async_trait]
@@ -25,73 +52,280 @@ impl InitBy<MQModuleConfig> for TardisMQClient {
 }
 
 impl TardisMQClient {
-    pub async fn init(MQModuleConfig { url }: &MQModuleConfig) -> TardisResult<TardisMQClient> {
-        info!("[Tardis.MQClient] Initializing, host:{}, port:{}", url.host_str().unwrap_or(""), url.port().unwrap_or(0));
-        let con = Connection::connect(url.as_str(), ConnectionProperties::default().with_connection_name("tardis".into())).await?;
-        info!("[Tardis.MQClient] Initialized, host:{}, port:{}", url.host_str().unwrap_or(""), url.port().unwrap_or(0));
-        Ok(TardisMQClient {
-            con,
-            channels: Mutex::new(Vec::new()),
-        })
+    /// The url `mem://` or the kind `memory` creates an in-memory client for tests, requires the `test` feature.
+    /// The broker is selected by the `kind` or the url scheme, see [`MQModuleConfig`].
+    /// The configured topology is declared after connected.
+    ///
+    /// url为 `mem://` 或类型为 `memory` 时创建用于测试的内存客户端，需启用 `test` 特性. 消息代理由 `kind` 或url scheme选择，见 [`MQModuleConfig`] .
+    /// 连接后声明配置的拓扑.
+    pub async fn init(config: &MQModuleConfig) -> TardisResult<TardisMQClient> {
+        let MQModuleConfig { url, topology, consumer, .. } = config;
+        #[cfg(feature = "test")]
+        if config.kind() == MQKind::Memory {
+            let client = TardisMQClient {
+                consumer: consumer.clone(),
+                #[cfg(feature = "reldb-core")]
+                outbox: config.outbox.clone(),
+                ..Self::memory()
+            };
+            client.declare_topology(topology).await?;
+            return Ok(client);
+        }
+        let kind = config.kind();
+        info!(
+            "[Tardis.MQClient] Initializing, kind:{:?}, host:{}, port:{}",
+            kind,
+            url.host_str().unwrap_or(""),
+            url.port().unwrap_or(0)
+        );
+        let backend = match kind {
+            MQKind::Amqp => MQBackend::Amqp {
+                con: Connection::connect(url.as_str(), ConnectionProperties::default().with_connection_name("tardis".into())).await?,
+                channels: Mutex::new(Vec::new()),
+            },
+            #[cfg(feature = "mq-kafka")]
+            MQKind::Kafka => MQBackend::Kafka(crate::mq::mq_kafka::TardisKafkaMQ::init(url, &config.kafka)?),
+            #[cfg(feature = "mq-nats")]
+            MQKind::Nats => MQBackend::Nats(crate::mq::mq_nats::TardisNatsMQ::init(url).await?),
+            #[cfg(feature = "mq-redis")]
+            MQKind::RedisStream => MQBackend::RedisStream(crate::mq::mq_redis::TardisRedisStreamMQ::init(url, &config.redis_stream).await?),
+            #[allow(unreachable_patterns)]
+            kind => {
+                return Err(TardisError::not_implemented(
+                    &format!("[Tardis.MQClient] The {kind:?} broker isn't enabled, see the mq-kafka, mq-nats, mq-redis and test features"),
+                    "501-tardis-mq-kind-error",
+                ))
+            }
+        };
+        let client = TardisMQClient {
+            backend,
+            consumer: consumer.clone(),
+            #[cfg(feature = "reldb-core")]
+            outbox: config.outbox.clone(),
+            #[cfg(feature = "reldb-core")]
+            outbox_relay: std::sync::Mutex::new(None),
+        };
+        client.declare_topology(topology).await?;
+        info!(
+            "[Tardis.MQClient] Initialized, kind:{:?}, host:{}, port:{}",
+            kind,
+            url.host_str().unwrap_or(""),
+            url.port().unwrap_or(0)
+        );
+        Ok(client)
+    }
+
+    /// Create an in-memory client for tests, with an empty broker / 创建用于测试的内存客户端，代理为空
+    ///
+    /// @see [TardisMemoryMQ](crate::test::memory_mq::TardisMemoryMQ)
+    #[cfg(feature = "test")]
+    pub fn memory() -> TardisMQClient {
+        info!("[Tardis.MQClient] Initialized in memory");
+        TardisMQClient {
+            backend: MQBackend::Memory(crate::test::memory_mq::TardisMemoryMQ::new()),
+            consumer: MQConsumerConfig::default(),
+            #[cfg(feature = "reldb-core")]
+            outbox: crate::config::config_dto::component::mq::MQOutboxConfig::default(),
+            #[cfg(feature = "reldb-core")]
+            outbox_relay: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Get the in-memory broker, e.g. to inject failures or check the messages / 获取内存代理，如用于注入失败或检查消息
+    #[cfg(feature = "test")]
+    pub fn as_memory(&self) -> Option<&crate::test::memory_mq::TardisMemoryMQ> {
+        match &self.backend {
+            MQBackend::Memory(memory) => Some(memory),
+            _ => None,
+        }
+    }
+
+    fn amqp(&self) -> TardisResult<(&Connection, &Mutex<Vec<Channel>>)> {
+        match &self.backend {
+            MQBackend::Amqp { con, channels } => Ok((con, channels)),
+            #[cfg(feature = "mq-kafka")]
+            MQBackend::Kafka(_) => Err(TardisError::not_implemented(
+                "[Tardis.MQClient] Not supported by the kafka client",
+                "501-tardis-mq-kafka-error",
+            )),
+            #[cfg(feature = "mq-nats")]
+            MQBackend::Nats(_) => Err(TardisError::not_implemented(
+                "[Tardis.MQClient] Not supported by the nats client",
+                "501-tardis-mq-nats-error",
+            )),
+            #[cfg(feature = "mq-redis")]
+            MQBackend::RedisStream(_) => Err(TardisError::not_implemented(
+                "[Tardis.MQClient] Not supported by the redis stream client",
+                "501-tardis-mq-redis-error",
+            )),
+            #[cfg(feature = "test")]
+            MQBackend::Memory(_) => Err(TardisError::not_implemented(
+                "[Tardis.MQClient] Not supported by the in-memory client",
+                "501-tardis-mq-memory-error",
+            )),
+        }
+    }
+
+    #[cfg(feature = "mq-kafka")]
+    fn kafka(&self) -> TardisResult<&crate::mq::mq_kafka::TardisKafkaMQ> {
+        match &self.backend {
+            MQBackend::Kafka(kafka) => Ok(kafka),
+            _ => Err(TardisError::not_implemented(
+                "[Tardis.MQClient] Only supported by the kafka client",
+                "501-tardis-mq-kafka-error",
+            )),
+        }
+    }
+
+    /// Check the status of the connection / 检查连接状态
+    pub async fn health_check(&self) -> TardisResult<()> {
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(_) = &self.backend {
+            return Ok(());
+        }
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return kafka.health_check().await;
+        }
+        #[cfg(feature = "mq-nats")]
+        if let MQBackend::Nats(nats) = &self.backend {
+            return nats.health_check().await;
+        }
+        #[cfg(feature = "mq-redis")]
+        if let MQBackend::RedisStream(redis) = &self.backend {
+            return redis.health_check().await;
+        }
+        let (con, _) = self.amqp()?;
+        if con.status().connected() {
+            Ok(())
+        } else {
+            Err(TardisError::io_error(
+                &format!("[Tardis.MQClient] Connection is {:?}", con.status().state()),
+                "503-tardis-mq-disconnected",
+            ))
+        }
     }
 
     pub async fn close(&self) -> TardisResult<()> {
         info!("[Tardis.MQClient] Shutdown...");
-        let channels = self.channels.lock().await;
+        #[cfg(feature = "reldb-core")]
+        if let Some(relay) = self.outbox_relay()?.take() {
+            relay.abort();
+        }
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(_) = &self.backend {
+            return Ok(());
+        }
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return kafka.close().await;
+        }
+        #[cfg(feature = "mq-nats")]
+        if let MQBackend::Nats(nats) = &self.backend {
+            return nats.close().await;
+        }
+        #[cfg(feature = "mq-redis")]
+        if let MQBackend::RedisStream(redis) = &self.backend {
+            return redis.close().await;
+        }
+        let (con, channels) = self.amqp()?;
+        let channels = channels.lock().await;
         for channel in channels.iter() {
             channel.close(0u16, "Shutdown AMQP Channel").await?;
         }
-        self.con.close(0u16, "Shutdown AMQP Connection").await?;
+        con.close(0u16, "Shutdown AMQP Connection").await?;
         Ok(())
     }
 
     pub async fn request(&self, address: &str, message: String, header: &HashMap<String, String>) -> TardisResult<()> {
         trace!("[Tardis.MQClient] Request, queue:{}, message:{}", address, message);
-        let channel = self.con.create_channel().await?;
-        channel.confirm_select(ConfirmSelectOptions::default()).await?;
-        let mut mq_header = FieldTable::default();
-        for (k, v) in header {
-            mq_header.insert(ShortString::from(k.to_string()), AMQPValue::from(LongString::from(v.to_string())));
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            let mut header = header.clone();
+            TardisTracing::inject_context(&mut header);
+            TardisContext::inject(&mut header);
+            let result = memory.request(address, message, header).await;
+            record_message(address, "publish", result.is_ok());
+            return result;
         }
-        let confirm = channel
-            .basic_publish(
-                "",
-                address,
-                BasicPublishOptions::default(),
-                message.as_bytes(),
-                BasicProperties::default().with_headers(mq_header).with_delivery_mode(2),
-            )
-            .await?
-            .await?;
-        if confirm.is_ack() {
-            channel.close(200u16, "").await?;
-            Ok(())
-        } else {
-            Err(TardisError::internal_error("MQ request confirmation error", "500-tardis-mq-confirm-error"))
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return observe_send("request", address, header, |header| kafka.send(address, None, None, message, header)).await;
+        }
+        #[cfg(feature = "mq-nats")]
+        if let MQBackend::Nats(nats) = &self.backend {
+            return observe_send("request", address, header, |header| nats.send(address, message, header)).await;
         }
+        #[cfg(feature = "mq-redis")]
+        if let MQBackend::RedisStream(redis) = &self.backend {
+            return observe_send("request", address, header, |header| redis.send(address, message, header)).await;
+        }
+        let result = observe_client("mq", "request", self.basic_publish("", address, message, header)).await;
+        record_message(address, "publish", result.is_ok());
+        result
     }
 
+    /// Process the messages of the queue with the configured consumer options / 以配置的消费者选项处理队列中的消息
+    ///
+    /// The message is acknowledged if the handler returns `Ok`,
+    /// otherwise (returned an error or panicked) it's retried and then dead-lettered or rejected, see [`MQConsumerConfig`].
+    ///
+    /// 处理函数返回 `Ok` 时确认消息，否则（返回错误或panic）重试后转入死信队列或拒绝，见 [`MQConsumerConfig`] .
     pub async fn response<F, T>(&self, address: &str, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.response_with(address, &self.consumer, fun).await
+    }
+
+    /// Process the messages of the queue with the consumer options of this subscription / 以本订阅的消费者选项处理队列中的消息
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let options = MQConsumerConfig::builder().max_retries(3).dead_letter(true).concurrency(8).prefetch(8).build();
+    /// client.response_with("order", &options, |(header, msg)| async move { Ok(()) }).await?;
+    /// ```
+    pub async fn response_with<F, T>(&self, address: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
     where
         F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
         T: Future<Output = TardisResult<()>> + Send + 'static,
     {
         info!("[Tardis.MQClient] Response, queue:{}", address);
-        let channel = self.con.create_channel().await?;
-        channel
-            .queue_declare(
-                address,
-                QueueDeclareOptions {
-                    passive: false,
-                    durable: true,
-                    exclusive: false,
-                    auto_delete: false,
-                    nowait: false,
-                },
-                FieldTable::default(),
-            )
-            .await?;
-        channel.basic_qos(1, BasicQosOptions::default()).await?;
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            return memory.response(address, options, fun).await;
+        }
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return kafka.consume_group(address, None, options, fun);
+        }
+        #[cfg(feature = "mq-nats")]
+        if let MQBackend::Nats(nats) = &self.backend {
+            return nats.response(address, options, fun).await;
+        }
+        #[cfg(feature = "mq-redis")]
+        if let MQBackend::RedisStream(redis) = &self.backend {
+            return redis.response(address, options, fun).await;
+        }
+        let (con, channels) = self.amqp()?;
+        let channel = con.create_channel().await?;
+        for queue in [Some(address.to_string()), options.dead_letter_queue(address)].into_iter().flatten() {
+            channel
+                .queue_declare(
+                    &queue,
+                    QueueDeclareOptions {
+                        passive: false,
+                        durable: true,
+                        exclusive: false,
+                        auto_delete: false,
+                        nowait: false,
+                    },
+                    FieldTable::default(),
+                )
+                .await?;
+        }
+        channel.basic_qos(options.prefetch, BasicQosOptions::default()).await?;
         let consumer = channel
             .basic_consume(
                 address,
@@ -105,22 +339,123 @@ impl TardisMQClient {
                 FieldTable::default(),
             )
             .await?;
-        self.channels.lock().await.push(channel);
-        self.process(address.to_string(), consumer, fun).await
+        channels.lock().await.push(channel.clone());
+        self.process(address.to_string(), channel, consumer, options, fun).await
     }
 
     pub async fn publish(&self, topic: &str, message: String, header: &HashMap<String, String>) -> TardisResult<()> {
         trace!("[Tardis.MQClient] Publish, queue:{}, message:{}", topic, message);
-        let channel = self.con.create_channel().await?;
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            let mut header = header.clone();
+            TardisTracing::inject_context(&mut header);
+            TardisContext::inject(&mut header);
+            let result = memory.publish(topic, message, header).await;
+            record_message(topic, "publish", result.is_ok());
+            return result;
+        }
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return observe_send("publish", topic, header, |header| kafka.send(topic, None, None, message, header)).await;
+        }
+        #[cfg(feature = "mq-nats")]
+        if let MQBackend::Nats(nats) = &self.backend {
+            return observe_send("publish", topic, header, |header| nats.send(topic, message, header)).await;
+        }
+        #[cfg(feature = "mq-redis")]
+        if let MQBackend::RedisStream(redis) = &self.backend {
+            return observe_send("publish", topic, header, |header| redis.send(topic, message, header)).await;
+        }
+        let result = observe_client("mq", "publish", self.basic_publish(topic, "", message, header)).await;
+        record_message(topic, "publish", result.is_ok());
+        result
+    }
+
+    /// Write the message into the outbox table in the transaction of the connection, it's published by the outbox relay after committed
+    /// / 在连接的事务中将消息写入发件箱表，提交后由发件箱中继发布
+    ///
+    /// The outbox table is created by the relay, see [`MQOutboxConfig`](crate::config::config_dto::MQOutboxConfig) and [`TardisMQOutbox`](crate::mq::mq_outbox::TardisMQOutbox).
+    ///
+    /// 发件箱表由中继创建，见 [`MQOutboxConfig`](crate::config::config_dto::MQOutboxConfig) 及 [`TardisMQOutbox`](crate::mq::mq_outbox::TardisMQOutbox) .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut conn = TardisFuns::reldb().conn();
+    /// conn.begin().await?;
+    /// conn.insert_one(order, &ctx).await?;
+    /// TardisFuns::mq().publish_transactional(&conn, "order.created", msg, &header).await?;
+    /// conn.commit().await?;
+    /// ```
+    #[cfg(feature = "reldb-core")]
+    pub async fn publish_transactional(
+        &self,
+        conn: &crate::db::reldb_client::TardisRelDBlConnection,
+        topic: &str,
+        message: String,
+        header: &HashMap<String, String>,
+    ) -> TardisResult<()> {
+        trace!("[Tardis.MQClient] Publish transactional, queue:{}, message:{}", topic, message);
+        if !conn.has_tx() {
+            return Err(TardisError::bad_request(
+                "[Tardis.MQClient] The transactional publishing requires a transaction, call begin first",
+                "400-tardis-mq-outbox-tx-empty",
+            ));
+        }
+        crate::mq::mq_outbox::write(conn, &self.outbox.table, topic, message, header).await
+    }
+
+    #[cfg(feature = "reldb-core")]
+    pub(crate) fn set_outbox_relay(&self, relay: tokio::task::JoinHandle<()>) -> TardisResult<()> {
+        if let Some(previous) = self.outbox_relay()?.replace(relay) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "reldb-core")]
+    fn outbox_relay(&self) -> TardisResult<std::sync::MutexGuard<'_, Option<tokio::task::JoinHandle<()>>>> {
+        self.outbox_relay.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] Outbox relay lock error: {error}"), ""))
+    }
+
+    /// Publish the message to the exchange with the routing key / 以路由键将消息发布到交换机
+    ///
+    /// The exchange isn't declared, declare it by the topology or [`Self::subscribe_topic`] first.
+    ///
+    /// 不会声明交换机，请先通过拓扑或 [`Self::subscribe_topic`] 声明.
+    pub async fn publish_topic(&self, exchange: &str, routing_key: &str, message: String, header: &HashMap<String, String>) -> TardisResult<()> {
+        trace!("[Tardis.MQClient] Publish, exchange:{}, routing key:{}, message:{}", exchange, routing_key, message);
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            let mut header = header.clone();
+            TardisTracing::inject_context(&mut header);
+            TardisContext::inject(&mut header);
+            let result = memory.publish_topic(exchange, routing_key, message, header).await;
+            record_message(exchange, "publish", result.is_ok());
+            return result;
+        }
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return observe_send("publish", exchange, header, |header| kafka.send(exchange, Some(routing_key), None, message, header)).await;
+        }
+        let result = observe_client("mq", "publish", self.basic_publish(exchange, routing_key, message, header)).await;
+        record_message(exchange, "publish", result.is_ok());
+        result
+    }
+
+    async fn basic_publish(&self, exchange: &str, routing_key: &str, message: String, header: &HashMap<String, String>) -> TardisResult<()> {
+        let channel = self.amqp()?.0.create_channel().await?;
         channel.confirm_select(ConfirmSelectOptions::default()).await?;
+        let mut header = header.clone();
+        TardisTracing::inject_context(&mut header);
+        TardisContext::inject(&mut header);
         let mut mq_header = FieldTable::default();
         for (k, v) in header {
             mq_header.insert(ShortString::from(k.to_string()), AMQPValue::from(LongString::from(v.to_string())));
         }
         let confirm = channel
             .basic_publish(
-                topic,
-                "",
+                exchange,
+                routing_key,
                 BasicPublishOptions::default(),
                 message.as_bytes(),
                 BasicProperties::default().with_headers(mq_header).with_delivery_mode(2),
@@ -135,14 +470,146 @@ impl TardisMQClient {
         }
     }
 
+    /// Subscribe the topic with the configured consumer options / 以配置的消费者选项订阅主题
     pub async fn subscribe<F, T>(&self, topic: &str, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.subscribe_with(topic, &self.consumer, fun).await
+    }
+
+    /// Subscribe the topic with the consumer options of this subscription / 以本订阅的消费者选项订阅主题
+    pub async fn subscribe_with<F, T>(&self, topic: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
     where
         F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
         T: Future<Output = TardisResult<()>> + Send + 'static,
     {
         info!("[Tardis.MQClient] Subscribe, queue:{}", topic);
-        let channel = self.con.create_channel().await?;
-        self.declare_exchange(&channel, topic).await?;
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            return memory.subscribe(topic, options, fun);
+        }
+        #[cfg(feature = "mq-kafka")]
+        if let MQBackend::Kafka(kafka) = &self.backend {
+            return kafka.consume_all(topic, options, fun);
+        }
+        #[cfg(feature = "mq-nats")]
+        if let MQBackend::Nats(nats) = &self.backend {
+            return nats.subscribe(topic, options, fun).await;
+        }
+        #[cfg(feature = "mq-redis")]
+        if let MQBackend::RedisStream(redis) = &self.backend {
+            return redis.subscribe(topic, options, fun).await;
+        }
+        self.subscribe_temp_queue(topic, ExchangeKind::Fanout, "", topic.to_string(), options, fun).await
+    }
+
+    /// Request by the typed message, see [`TardisMQMessage`] / 以类型化消息请求，见 [`TardisMQMessage`]
+    pub async fn request_message<P: TardisMQPayload>(&self, address: &str, message: &TardisMQMessage<P>) -> TardisResult<()> {
+        let (header, message) = message.encode()?;
+        self.request(address, message, &header).await
+    }
+
+    /// Respond to the typed messages, the messages failed to decode are handled as the failed messages
+    /// / 响应类型化消息，解码失败的消息按处理失败的消息处理
+    pub async fn response_message<P, F, T>(&self, address: &str, fun: F) -> TardisResult<()>
+    where
+        P: TardisMQPayload + Send + 'static,
+        F: Fn(TardisMQMessage<P>) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.response(address, typed_handler(fun)).await
+    }
+
+    /// Publish the typed message, see [`TardisMQMessage`] / 发布类型化消息，见 [`TardisMQMessage`]
+    pub async fn publish_message<P: TardisMQPayload>(&self, topic: &str, message: &TardisMQMessage<P>) -> TardisResult<()> {
+        let (header, message) = message.encode()?;
+        self.publish(topic, message, &header).await
+    }
+
+    /// Subscribe the typed messages, the messages failed to decode are handled as the failed messages
+    /// / 订阅类型化消息，解码失败的消息按处理失败的消息处理
+    pub async fn subscribe_message<P, F, T>(&self, topic: &str, fun: F) -> TardisResult<()>
+    where
+        P: TardisMQPayload + Send + 'static,
+        F: Fn(TardisMQMessage<P>) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.subscribe(topic, typed_handler(fun)).await
+    }
+
+    /// Publish the message to the Kafka topic with the key and the partition, only supported by the kafka client
+    /// / 以key及分区将消息发布到Kafka主题，仅Kafka客户端支持
+    ///
+    /// The messages with the same key are sent to the same partition if the partition isn't specified.
+    ///
+    /// 未指定分区时相同key的消息发送到同一分区.
+    #[cfg(feature = "mq-kafka")]
+    pub async fn publish_with_key(&self, topic: &str, key: Option<&str>, partition: Option<i32>, message: String, header: &HashMap<String, String>) -> TardisResult<()> {
+        trace!("[Tardis.MQClient] Publish, topic:{}, key:{:?}, partition:{:?}, message:{}", topic, key, partition, message);
+        let kafka = self.kafka()?;
+        observe_send("publish", topic, header, |header| kafka.send(topic, key, partition, message, header)).await
+    }
+
+    /// Subscribe the Kafka topic by the consumer group, each message is processed by one of the subscribers of the group,
+    /// the offsets are committed by the configured strategy, only supported by the kafka client
+    /// / 以消费者组订阅Kafka主题，每条消息只由组内其中一个订阅者处理，按配置的策略提交偏移量，仅Kafka客户端支持
+    #[cfg(feature = "mq-kafka")]
+    pub async fn subscribe_group<F, T>(&self, topic: &str, group_id: &str, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        info!("[Tardis.MQClient] Subscribe, topic:{}, group:{}", topic, group_id);
+        self.kafka()?.consume_group(topic, Some(group_id), &self.consumer, fun)
+    }
+
+    /// Subscribe the messages of the topic exchange matching the routing key pattern, `*` matches one word and `#` matches zero or more words
+    /// / 订阅主题交换机中匹配路由键模式的消息， `*` 匹配一个单词， `#` 匹配零或多个单词
+    ///
+    /// The exchange is declared as a durable topic exchange, each subscription receives all the matching messages by its temporary queue.
+    ///
+    /// 交换机声明为持久化的主题交换机，每个订阅通过其临时队列接收所有匹配的消息.
+    ///
+    /// The Kafka keys aren't routed by patterns, so it isn't supported by the kafka client.
+    ///
+    /// Kafka的key不按模式路由，因此Kafka客户端不支持.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// client.subscribe_topic("order", "order.*.created", |(header, msg)| async move { Ok(()) }).await?;
+    /// client.publish_topic("order", "order.vip.created", msg, &header).await?;
+    /// ```
+    pub async fn subscribe_topic<F, T>(&self, exchange: &str, routing_key_pattern: &str, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        info!("[Tardis.MQClient] Subscribe, exchange:{}, routing key:{}", exchange, routing_key_pattern);
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            return memory.subscribe_topic(exchange, routing_key_pattern, &self.consumer, fun);
+        }
+        self.subscribe_temp_queue(
+            exchange,
+            ExchangeKind::Topic,
+            routing_key_pattern,
+            format!("{exchange}:{routing_key_pattern}"),
+            &self.consumer,
+            fun,
+        )
+        .await
+    }
+
+    async fn subscribe_temp_queue<F, T>(&self, exchange: &str, kind: ExchangeKind, routing_key: &str, destination: String, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let (con, channels) = self.amqp()?;
+        let channel = con.create_channel().await?;
+        self.declare_exchange(&channel, exchange, kind, true, false).await?;
         let temp_queue_name = channel
             .queue_declare(
                 "",
@@ -158,8 +625,23 @@ impl TardisMQClient {
             .await?
             .name()
             .to_string();
-        channel.queue_bind(&temp_queue_name, topic, "", QueueBindOptions::default(), FieldTable::default()).await?;
-        channel.basic_qos(1, BasicQosOptions::default()).await?;
+        channel.queue_bind(&temp_queue_name, exchange, routing_key, QueueBindOptions::default(), FieldTable::default()).await?;
+        if let Some(dead_letter_queue) = options.dead_letter_queue(&destination) {
+            channel
+                .queue_declare(
+                    &dead_letter_queue,
+                    QueueDeclareOptions {
+                        passive: false,
+                        durable: true,
+                        exclusive: false,
+                        auto_delete: false,
+                        nowait: false,
+                    },
+                    FieldTable::default(),
+                )
+                .await?;
+        }
+        channel.basic_qos(options.prefetch, BasicQosOptions::default()).await?;
         let consumer = channel
             .basic_consume(
                 &temp_queue_name,
@@ -173,19 +655,80 @@ impl TardisMQClient {
                 FieldTable::default(),
             )
             .await?;
-        self.channels.lock().await.push(channel);
-        self.process(topic.to_string(), consumer, fun).await
+        channels.lock().await.push(channel.clone());
+        self.process(destination, channel, consumer, options, fun).await
     }
 
-    async fn declare_exchange(&self, channel: &Channel, topic: &str) -> TardisResult<()> {
+    /// Declare the exchanges, the queues and the bindings, it's called by [`Self::init`] with the configured topology
+    /// / 声明交换机、队列及绑定， [`Self::init`] 时会以配置的拓扑调用
+    pub async fn declare_topology(&self, topology: &MQTopologyConfig) -> TardisResult<()> {
+        if topology.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "[Tardis.MQClient] Declare topology, exchanges:{}, queues:{}",
+            topology.exchanges.iter().map(|exchange| exchange.name.as_str()).collect::<Vec<_>>().join(","),
+            topology.queues.iter().map(|queue| queue.name.as_str()).collect::<Vec<_>>().join(",")
+        );
+        #[cfg(feature = "test")]
+        if let MQBackend::Memory(memory) = &self.backend {
+            return memory.declare_topology(topology);
+        }
+        let channel = self.amqp()?.0.create_channel().await?;
+        for exchange in &topology.exchanges {
+            let kind = match exchange.kind {
+                MQExchangeKind::Direct => ExchangeKind::Direct,
+                MQExchangeKind::Fanout => ExchangeKind::Fanout,
+                MQExchangeKind::Topic => ExchangeKind::Topic,
+                MQExchangeKind::Headers => ExchangeKind::Headers,
+            };
+            self.declare_exchange(&channel, &exchange.name, kind, exchange.durable, exchange.auto_delete).await?;
+        }
+        for queue in &topology.queues {
+            let mut arguments = FieldTable::default();
+            for (key, value) in [("x-message-ttl", queue.message_ttl_ms), ("x-expires", queue.expires_ms), ("x-max-length", queue.max_length)] {
+                if let Some(value) = value {
+                    arguments.insert(ShortString::from(key), AMQPValue::LongLongInt(value as i64));
+                }
+            }
+            for (key, value) in [
+                ("x-dead-letter-exchange", &queue.dead_letter_exchange),
+                ("x-dead-letter-routing-key", &queue.dead_letter_routing_key),
+            ] {
+                if let Some(value) = value {
+                    arguments.insert(ShortString::from(key), AMQPValue::from(LongString::from(value.to_string())));
+                }
+            }
+            channel
+                .queue_declare(
+                    &queue.name,
+                    QueueDeclareOptions {
+                        passive: false,
+                        durable: queue.durable,
+                        exclusive: false,
+                        auto_delete: queue.auto_delete,
+                        nowait: false,
+                    },
+                    arguments,
+                )
+                .await?;
+            for binding in &queue.bindings {
+                channel.queue_bind(&queue.name, &binding.exchange, &binding.routing_key, QueueBindOptions::default(), FieldTable::default()).await?;
+            }
+        }
+        channel.close(200u16, "").await?;
+        Ok(())
+    }
+
+    async fn declare_exchange(&self, channel: &Channel, exchange: &str, kind: ExchangeKind, durable: bool, auto_delete: bool) -> TardisResult<()> {
         channel
             .exchange_declare(
-                topic,
-                ExchangeKind::Fanout,
+                exchange,
+                kind,
                 ExchangeDeclareOptions {
                     passive: false,
-                    durable: true,
-                    auto_delete: false,
+                    durable,
+                    auto_delete,
                     internal: false,
                     nowait: false,
                 },
@@ -195,45 +738,33 @@ impl TardisMQClient {
         Ok(())
     }
 
-    async fn process<F, T>(&self, topic_or_address: String, mut consumer: Consumer, fun: F) -> TardisResult<()>
+    /// Process the deliveries, at most `concurrency` deliveries are processed at the same time
+    /// / 处理投递的消息，同时最多处理 `concurrency` 条
+    async fn process<F, T>(&self, topic_or_address: String, channel: Channel, mut consumer: Consumer, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
     where
         F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
         T: Future<Output = TardisResult<()>> + Send + 'static,
     {
+        let fun = Arc::new(fun);
+        let options = options.clone();
+        let permits = Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
         async_global_executor::spawn(async move {
             while let Some(delivery) = consumer.next().await {
                 match delivery {
-                    Ok(d) => match std::str::from_utf8(d.data.as_slice()) {
-                        Ok(msg) => {
-                            trace!("[Tardis.MQClient] Receive, queue:{}, message:{}", topic_or_address, msg);
-                            let mut resp_header: HashMap<String, String> = HashMap::default();
-                            let _ = d.properties.headers().as_ref().map(|header| {
-                                for (k, v) in header.into_iter() {
-                                    let value = if let AMQPValue::LongString(val) = v {
-                                        val.to_string()
-                                    } else {
-                                        error!("[Tardis.MQClient] Receive, queue:{topic_or_address}, message:{msg} | MQ Header only supports string types");
-                                        panic!("[Tardis.MQClient] Receive, queue:{topic_or_address}, message:{msg} | MQ Header only supports string types")
-                                    };
-                                    resp_header.insert(k.to_string(), value);
-                                }
-                            });
-                            match fun((resp_header, msg.to_string())).await {
-                                Ok(_) => match d.ack(BasicAckOptions::default()).await {
-                                    Ok(_) => (),
-                                    Err(error) => {
-                                        error!("[Tardis.MQClient] Receive ack error, queue:{topic_or_address}, message:{msg} | {error}");
-                                    }
-                                },
-                                Err(error) => {
-                                    error!("[Tardis.MQClient] Receive process error, queue:{topic_or_address}, message:{msg} | {error}");
-                                }
-                            }
-                        }
-                        Err(error) => {
-                            error!("[Tardis.MQClient] Receive delivery error, queue:{topic_or_address} | {error}");
-                        }
-                    },
+                    Ok(delivery) => {
+                        let Ok(permit) = permits.clone().acquire_owned().await else {
+                            break;
+                        };
+                        let topic_or_address = topic_or_address.clone();
+                        let channel = channel.clone();
+                        let options = options.clone();
+                        let fun = fun.clone();
+                        async_global_executor::spawn(async move {
+                            handle_delivery(&topic_or_address, &channel, delivery, &options, fun.as_ref()).await;
+                            drop(permit);
+                        })
+                        .detach();
+                    }
                     Err(error) => {
                         error!("[Tardis.MQClient] Receive connection error, queue:{topic_or_address} | {error}");
                     }
@@ -245,6 +776,179 @@ impl TardisMQClient {
     }
 }
 
+/// Process the delivery, then acknowledge it, move it to the dead letter queue or reject it
+/// / 处理投递的消息，然后确认、转入死信队列或拒绝
+async fn handle_delivery<F, T>(destination: &str, channel: &Channel, delivery: Delivery, options: &MQConsumerConfig, fun: &F)
+where
+    F: Fn((HashMap<String, String>, String)) -> T,
+    T: Future<Output = TardisResult<()>>,
+{
+    let msg = match std::str::from_utf8(delivery.data.as_slice()) {
+        Ok(msg) => msg.to_string(),
+        Err(error) => {
+            error!("[Tardis.MQClient] Receive delivery error, queue:{destination} | {error}");
+            if let Err(error) = delivery.nack(BasicNackOptions { multiple: false, requeue: false }).await {
+                error!("[Tardis.MQClient] Receive nack error, queue:{destination} | {error}");
+            }
+            return;
+        }
+    };
+    let mut header: HashMap<String, String> = HashMap::default();
+    if let Some(headers) = delivery.properties.headers() {
+        for (k, v) in headers.into_iter() {
+            if let AMQPValue::LongString(value) = v {
+                header.insert(k.to_string(), value.to_string());
+            } else {
+                warn!("[Tardis.MQClient] Receive, queue:{destination}, header:{k} | MQ Header only supports string types, ignored");
+            }
+        }
+    }
+    let error = match process_message(destination, header.clone(), msg.clone(), fun, options).await {
+        Ok(()) => {
+            if let Err(error) = delivery.ack(BasicAckOptions::default()).await {
+                error!("[Tardis.MQClient] Receive ack error, queue:{destination}, message:{msg} | {error}");
+            }
+            return;
+        }
+        Err(error) => error,
+    };
+    let requeue = match options.dead_letter_queue(destination) {
+        Some(dead_letter_queue) => {
+            let mut mq_header = FieldTable::default();
+            for (k, v) in dead_letter_header(header, destination, &error) {
+                mq_header.insert(ShortString::from(k), AMQPValue::from(LongString::from(v)));
+            }
+            let published = channel
+                .basic_publish(
+                    "",
+                    &dead_letter_queue,
+                    BasicPublishOptions::default(),
+                    msg.as_bytes(),
+                    BasicProperties::default().with_headers(mq_header).with_delivery_mode(2),
+                )
+                .await;
+            match published {
+                Ok(_) => {
+                    record_message(&dead_letter_queue, "dead_letter", true);
+                    if let Err(error) = delivery.ack(BasicAckOptions::default()).await {
+                        error!("[Tardis.MQClient] Receive ack error, queue:{destination}, message:{msg} | {error}");
+                    }
+                    return;
+                }
+                Err(error) => {
+                    error!("[Tardis.MQClient] Dead letter error, queue:{dead_letter_queue}, message:{msg} | {error}");
+                    record_message(&dead_letter_queue, "dead_letter", false);
+                    // keep the message rather than losing it
+                    true
+                }
+            }
+        }
+        None => false,
+    };
+    if let Err(error) = delivery.nack(BasicNackOptions { multiple: false, requeue }).await {
+        error!("[Tardis.MQClient] Receive nack error, queue:{destination}, message:{msg} | {error}");
+    }
+}
+
+/// Wrap the typed handler into the string handler / 将类型化处理函数包装为字符串处理函数
+fn typed_handler<P, F, T>(
+    fun: F,
+) -> impl Fn((HashMap<String, String>, String)) -> futures_util::future::Either<T, futures_util::future::Ready<TardisResult<()>>> + Send + Sync + 'static
+where
+    P: TardisMQPayload + Send + 'static,
+    F: Fn(TardisMQMessage<P>) -> T + Send + Sync + 'static,
+    T: Future<Output = TardisResult<()>> + Send + 'static,
+{
+    move |(header, message)| match TardisMQMessage::<P>::decode(header, message) {
+        Ok(message) => futures_util::future::Either::Left(fun(message)),
+        Err(error) => futures_util::future::Either::Right(futures_util::future::ready(Err(error))),
+    }
+}
+
+/// Send the message with the tracing context injected into the header / 发送消息，并将追踪上下文注入消息头
+#[cfg(any(feature = "mq-kafka", feature = "mq-nats", feature = "mq-redis"))]
+async fn observe_send<F, Fut>(operation: &str, destination: &str, header: &HashMap<String, String>, send: F) -> TardisResult<()>
+where
+    F: FnOnce(HashMap<String, String>) -> Fut,
+    Fut: Future<Output = TardisResult<()>>,
+{
+    let result = observe_client("mq", operation, async {
+        let mut header = header.clone();
+        TardisTracing::inject_context(&mut header);
+        TardisContext::inject(&mut header);
+        send(header).await
+    })
+    .await;
+    record_message(destination, "publish", result.is_ok());
+    result
+}
+
+/// Process the received message by the handler in the consumer span, the failed (returned an error or panicked) message is retried by the options,
+/// returns the error of the last attempt if it still fails
+/// / 在消费者span中以处理函数处理收到的消息，失败（返回错误或panic）的消息按选项重试，仍失败时返回最后一次的错误
+pub(crate) async fn process_message<F, T>(destination: &str, header: HashMap<String, String>, message: String, fun: &F, options: &MQConsumerConfig) -> TardisResult<()>
+where
+    F: Fn((HashMap<String, String>, String)) -> T,
+    T: Future<Output = TardisResult<()>>,
+{
+    trace!("[Tardis.MQClient] Receive, queue:{}, message:{}", destination, message);
+    let mut attempt = 0;
+    loop {
+        let span = tracing::info_span!(
+            "mq_process",
+            otel.name = format!("{destination} process"),
+            otel.kind = "consumer",
+            messaging.destination = destination,
+            messaging.attempt = attempt,
+        );
+        TardisTracing::set_parent_from(&span, &header);
+        let process = AssertUnwindSafe(async { fun((header.clone(), message.clone())).await }).catch_unwind().instrument(span);
+        // the handler runs with the context carried by the message
+        let process = match TardisContext::extract(&header) {
+            Some(ctx) => ctx.scope(process).await,
+            None => process.await,
+        };
+        let result = match process {
+            Ok(result) => result,
+            Err(panic) => Err(TardisError::internal_error(
+                &format!("[Tardis.MQClient] Handler panicked: {}", panic_message(panic.as_ref())),
+                "500-tardis-mq-handler-panic",
+            )),
+        };
+        record_message(destination, "consume", result.is_ok());
+        match result {
+            Ok(()) => return Ok(()),
+            Err(error) if attempt < options.max_retries => {
+                let backoff = options.backoff(attempt);
+                warn!("[Tardis.MQClient] Receive process error, retry after {backoff:?}, queue:{destination}, message:{message} | {error}");
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(error) => {
+                error!("[Tardis.MQClient] Receive process error, queue:{destination}, message:{message} | {error}");
+                return Err(error);
+            }
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> &str {
+    panic.downcast_ref::<&str>().copied().or_else(|| panic.downcast_ref::<String>().map(String::as_str)).unwrap_or("unknown")
+}
+
+/// The header of the dead-lettered message / 死信消息的消息头
+pub(crate) fn dead_letter_header(mut header: HashMap<String, String>, destination: &str, error: &TardisError) -> HashMap<String, String> {
+    header.insert("x-tardis-dead-letter-origin".to_string(), destination.to_string());
+    header.insert("x-tardis-dead-letter-error".to_string(), error.to_string());
+    header
+}
+
+#[allow(unused_variables)]
+pub(crate) fn record_message(topic: &str, direction: &str, success: bool) {
+    #[cfg(feature = "metrics")]
+    crate::TardisFuns::metrics().record_mq_message(topic, direction, success);
+}
+
 impl From<lapin::Error> for TardisError {
     fn from(error: lapin::Error) -> Self {
         error!("[Tardis.MQClient] Error: {}", error.to_string());
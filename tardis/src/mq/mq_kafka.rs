@@ -0,0 +1,228 @@
+//! Kafka backend of the MQ client / MQ客户端的Kafka后端
+//!
+//! The backend of [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) when the url scheme or the `kind` is `kafka`,
+//! the operations are mapped as follows:
+//!
+//! 当url scheme或 `kind` 为 `kafka` 时 [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) 使用的后端，操作映射如下：
+//!
+//! * `request` / `publish`: send the message to the topic of the address / 将消息发送到与地址同名的主题
+//! * `response`: consume by the configured `group_id`, so each message is processed by one of the responders
+//!   / 以配置的 `group_id` 消费，因此每条消息只由其中一个响应者处理
+//! * `subscribe`: consume by an unique group from the latest offset, so each subscriber receives all the messages
+//!   / 以唯一的消费者组从最新偏移量消费，因此每个订阅者都会收到所有消息
+//! * `publish_topic`: send the message to the topic of the exchange with the routing key as the message key
+//!   / 将消息发送到与交换机同名的主题，路由键作为消息key
+//! * `publish_with_key` / `subscribe_group`: send with the key and the partition, consume by the consumer group
+//!   / 以key及分区发送，以消费者组消费
+//!
+//! The messages are processed one by one in the order of the partition, the offset is committed after the message is processed successfully
+//! or sent to the dead-letter topic, so the failed message is redelivered after the consumer restarts if the dead-letter isn't enabled.
+//!
+//! 消息按分区顺序逐条处理，消息处理成功或发送到死信主题后提交偏移量，因此未启用死信时失败的消息会在消费者重启后重新投递.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use tokio::task::JoinHandle;
+use tracing::error;
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::mq::{MQConsumerConfig, MQKafkaCommitStrategy, MQKafkaConfig, MQKafkaOffsetReset};
+use crate::mq::mq_client::{dead_letter_header, process_message, record_message};
+use crate::TardisFuns;
+
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub(crate) struct TardisKafkaMQ {
+    producer: FutureProducer,
+    client_config: ClientConfig,
+    config: MQKafkaConfig,
+    consumers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl TardisKafkaMQ {
+    pub(crate) fn init(url: &Url, config: &MQKafkaConfig) -> TardisResult<Self> {
+        let host = url.host_str().ok_or_else(|| TardisError::format_error(&format!("[Tardis.MQClient] Invalid kafka url {url}"), "406-tardis-mq-url-error"))?;
+        let mut client_config = ClientConfig::new();
+        client_config.set("bootstrap.servers", format!("{host}:{}", url.port().unwrap_or(9092)));
+        if !url.username().is_empty() {
+            client_config.set("sasl.username", url.username());
+            client_config.set("sasl.password", url.password().unwrap_or_default());
+        }
+        for (key, value) in url.query_pairs() {
+            client_config.set(key, value);
+        }
+        for (key, value) in &config.properties {
+            client_config.set(key, value);
+        }
+        let producer = client_config.clone().set("message.timeout.ms", config.send_timeout_ms.to_string()).create()?;
+        Ok(TardisKafkaMQ {
+            producer,
+            client_config,
+            config: config.clone(),
+            consumers: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub(crate) async fn health_check(&self) -> TardisResult<()> {
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || producer.client().fetch_metadata(None, METADATA_TIMEOUT).map(|_| ()))
+            .await
+            .map_err(|error| TardisError::internal_error(&format!("[Tardis.MQClient] Health check error: {error}"), "500-tardis-mq-error"))?
+            .map_err(|error| TardisError::io_error(&format!("[Tardis.MQClient] Kafka is unavailable: {error}"), "503-tardis-mq-disconnected"))?;
+        Ok(())
+    }
+
+    pub(crate) async fn close(&self) -> TardisResult<()> {
+        for consumer in self.consumers.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] Kafka consumers lock error: {error}"), ""))?.drain(..) {
+            consumer.abort();
+        }
+        let producer = self.producer.clone();
+        let timeout = Duration::from_millis(self.config.send_timeout_ms);
+        tokio::task::spawn_blocking(move || producer.flush(timeout))
+            .await
+            .map_err(|error| TardisError::internal_error(&format!("[Tardis.MQClient] Flush error: {error}"), "500-tardis-mq-error"))??;
+        Ok(())
+    }
+
+    pub(crate) async fn send(&self, topic: &str, key: Option<&str>, partition: Option<i32>, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+        send(&self.producer, self.config.send_timeout_ms, topic, key, partition, message, header).await
+    }
+
+    /// Consume by the consumer group, the configured group is used if `group_id` is `None` / 以消费者组消费， `group_id` 为 `None` 时使用配置的组
+    pub(crate) fn consume_group<F, T>(&self, topic: &str, group_id: Option<&str>, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.consume(topic, group_id.unwrap_or(&self.config.group_id), self.config.offset_reset, options, fun)
+    }
+
+    /// Consume all the messages from now on by an unique group / 以唯一的消费者组消费此后的所有消息
+    pub(crate) fn consume_all<F, T>(&self, topic: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let group_id = format!("{}-{}", self.config.group_id, TardisFuns::field.nanoid());
+        self.consume(topic, &group_id, MQKafkaOffsetReset::Latest, options, fun)
+    }
+
+    fn consume<F, T>(&self, topic: &str, group_id: &str, offset_reset: MQKafkaOffsetReset, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let commit_mode = match self.config.commit {
+            MQKafkaCommitStrategy::Auto => None,
+            MQKafkaCommitStrategy::Sync => Some(CommitMode::Sync),
+            MQKafkaCommitStrategy::Async => Some(CommitMode::Async),
+        };
+        let consumer: StreamConsumer = self
+            .client_config
+            .clone()
+            .set("group.id", group_id)
+            .set("enable.auto.commit", commit_mode.is_none().to_string())
+            .set(
+                "auto.offset.reset",
+                match offset_reset {
+                    MQKafkaOffsetReset::Earliest => "earliest",
+                    MQKafkaOffsetReset::Latest => "latest",
+                },
+            )
+            .create()?;
+        consumer.subscribe(&[topic])?;
+        let topic = topic.to_string();
+        let options = options.clone();
+        let producer = self.producer.clone();
+        let send_timeout_ms = self.config.send_timeout_ms;
+        let handle = tokio::spawn(async move {
+            loop {
+                let delivery = match consumer.recv().await {
+                    Ok(delivery) => delivery,
+                    Err(error) => {
+                        error!("[Tardis.MQClient] Receive connection error, topic:{topic} | {error}");
+                        continue;
+                    }
+                };
+                let msg = match delivery.payload_view::<str>() {
+                    Some(Ok(msg)) => msg.to_string(),
+                    None => String::new(),
+                    Some(Err(error)) => {
+                        error!("[Tardis.MQClient] Receive delivery error, topic:{topic} | {error}");
+                        continue;
+                    }
+                };
+                let mut header = HashMap::new();
+                if let Some(headers) = delivery.headers() {
+                    for item in headers.iter() {
+                        header.insert(item.key.to_string(), item.value.map(|value| String::from_utf8_lossy(value).to_string()).unwrap_or_default());
+                    }
+                }
+                let processed = match process_message(&topic, header.clone(), msg.clone(), &fun, &options).await {
+                    Ok(()) => true,
+                    Err(error) => match options.dead_letter_queue(&topic) {
+                        Some(dead_letter_topic) => {
+                            let key = delivery.key_view::<str>().and_then(Result::ok);
+                            let result = send(&producer, send_timeout_ms, &dead_letter_topic, key, None, msg, dead_letter_header(header, &topic, &error)).await;
+                            record_message(&dead_letter_topic, "dead_letter", result.is_ok());
+                            result.is_ok()
+                        }
+                        None => false,
+                    },
+                };
+                if processed {
+                    if let Some(commit_mode) = commit_mode {
+                        if let Err(error) = consumer.commit_message(&delivery, commit_mode) {
+                            error!("[Tardis.MQClient] Receive commit error, topic:{topic} | {error}");
+                        }
+                    }
+                }
+            }
+        });
+        self.consumers.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] Kafka consumers lock error: {error}"), ""))?.push(handle);
+        Ok(())
+    }
+}
+
+async fn send(
+    producer: &FutureProducer,
+    send_timeout_ms: u64,
+    topic: &str,
+    key: Option<&str>,
+    partition: Option<i32>,
+    message: String,
+    header: HashMap<String, String>,
+) -> TardisResult<()> {
+    let mut headers = OwnedHeaders::new_with_capacity(header.len());
+    for (name, value) in &header {
+        headers = headers.insert(Header {
+            key: name.as_str(),
+            value: Some(value.as_str()),
+        });
+    }
+    let mut record = FutureRecord::<str, str>::to(topic).payload(&message).headers(headers);
+    if let Some(key) = key {
+        record = record.key(key);
+    }
+    if let Some(partition) = partition {
+        record = record.partition(partition);
+    }
+    producer.send(record, Duration::from_millis(send_timeout_ms)).await.map_err(|(error, _)| TardisError::from(error))?;
+    Ok(())
+}
+
+impl From<KafkaError> for TardisError {
+    fn from(error: KafkaError) -> Self {
+        error!("[Tardis.MQClient] Error: {}", error.to_string());
+        TardisError::wrap(&format!("[Tardis.MQClient] {error:?}"), "-1-tardis-mq-error")
+    }
+}
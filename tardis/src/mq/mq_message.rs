@@ -0,0 +1,167 @@
+//! Typed message envelope / 类型化消息信封
+//!
+//! [`TardisMQMessage`] carries the payload with the metadata, which is sent as the message headers,
+//! so the producers and the consumers share the payload types instead of the ad-hoc strings:
+//!
+//! [`TardisMQMessage`] 携带载荷及元数据，元数据作为消息头发送，因此生产者与消费者共享载荷类型，而非临时约定的字符串：
+//!
+//! * `x-tardis-message-id`: unique id of the message, e.g. for the idempotent consumers / 消息的唯一id，如用于幂等消费
+//! * `x-tardis-timestamp`: creation time in RFC 3339 / 创建时间，RFC 3339格式
+//! * `content-type`: encoding of the payload / 载荷的编码
+//! * the trace context injected by the client, e.g. `traceparent` / 客户端注入的追踪上下文，如 `traceparent`
+//!
+//! The payloads are encoded as JSON by serde, or as base64 by [`TardisMQRawPayload`] for the raw bytes.
+//!
+//! 载荷通过serde编码为JSON，原始字节可使用 [`TardisMQRawPayload`] 编码为base64.
+//!
+//! # Examples
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct OrderCreated { id: String }
+//!
+//! client.subscribe_message("order.created", |message: TardisMQMessage<OrderCreated>| async move {
+//!     println!("{} created at {}", message.payload.id, message.timestamp);
+//!     Ok(())
+//! }).await?;
+//! client.publish_message("order.created", &TardisMQMessage::new(OrderCreated { id: "o1".to_string() })).await?;
+//! ```
+use std::collections::HashMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+pub const HEADER_MESSAGE_ID: &str = "x-tardis-message-id";
+pub const HEADER_TIMESTAMP: &str = "x-tardis-timestamp";
+pub const HEADER_CONTENT_TYPE: &str = "content-type";
+const HEADER_TRACE_PARENT: &str = "traceparent";
+
+/// Encoding of the message payloads / 消息载荷的编码
+///
+/// It's implemented for all the serde types as JSON.
+///
+/// 所有serde类型均以JSON实现.
+pub trait TardisMQPayload: Sized {
+    /// The `content-type` header of the encoded payloads / 编码后载荷的 `content-type` 消息头
+    fn content_type() -> &'static str;
+
+    fn encode(&self) -> TardisResult<String>;
+
+    fn decode(payload: String) -> TardisResult<Self>;
+}
+
+impl<T: Serialize + DeserializeOwned> TardisMQPayload for T {
+    fn content_type() -> &'static str {
+        "application/json"
+    }
+
+    fn encode(&self) -> TardisResult<String> {
+        TardisFuns::json.obj_to_string(self)
+    }
+
+    fn decode(payload: String) -> TardisResult<Self> {
+        TardisFuns::json.str_to_obj(&payload)
+    }
+}
+
+/// Raw bytes payload, encoded as base64 / 原始字节载荷，编码为base64
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TardisMQRawPayload(pub Vec<u8>);
+
+impl TardisMQPayload for TardisMQRawPayload {
+    fn content_type() -> &'static str {
+        "application/octet-stream"
+    }
+
+    fn encode(&self) -> TardisResult<String> {
+        Ok(STANDARD.encode(&self.0))
+    }
+
+    fn decode(payload: String) -> TardisResult<Self> {
+        STANDARD
+            .decode(payload)
+            .map(TardisMQRawPayload)
+            .map_err(|error| TardisError::format_error(&format!("[Tardis.MQClient] Invalid base64 payload: {error}"), "406-tardis-mq-payload-error"))
+    }
+}
+
+/// Message envelope / 消息信封
+#[derive(Debug, Clone, PartialEq)]
+pub struct TardisMQMessage<T> {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// The headers without the metadata of the envelope / 不含信封元数据的消息头
+    pub headers: HashMap<String, String>,
+    pub payload: T,
+}
+
+impl<T: TardisMQPayload> TardisMQMessage<T> {
+    /// Create a message with an unique id and the current time / 创建消息，具有唯一id及当前时间
+    pub fn new(payload: T) -> Self {
+        TardisMQMessage {
+            id: TardisFuns::field.nanoid(),
+            timestamp: TardisFuns::clock().now(),
+            headers: HashMap::new(),
+            payload,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// The `content-type` of the payload / 载荷的 `content-type`
+    pub fn content_type(&self) -> &'static str {
+        T::content_type()
+    }
+
+    /// The W3C trace context of the producer, if the tracing is enabled / 生产者的W3C追踪上下文（如果启用了追踪）
+    pub fn trace_parent(&self) -> Option<&str> {
+        self.headers.get(HEADER_TRACE_PARENT).map(String::as_str)
+    }
+
+    /// Encode into the headers and the message body / 编码为消息头及消息体
+    pub fn encode(&self) -> TardisResult<(HashMap<String, String>, String)> {
+        let mut headers = self.headers.clone();
+        headers.insert(HEADER_MESSAGE_ID.to_string(), self.id.clone());
+        headers.insert(HEADER_TIMESTAMP.to_string(), self.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true));
+        headers.insert(HEADER_CONTENT_TYPE.to_string(), T::content_type().to_string());
+        Ok((headers, self.payload.encode()?))
+    }
+
+    /// Decode from the received headers and message body / 从收到的消息头及消息体解码
+    ///
+    /// The messages without the envelope metadata, e.g. sent by the string API, are accepted with a generated id and the current time,
+    /// but the mismatched `content-type` is rejected.
+    ///
+    /// 接受不含信封元数据的消息（如通过字符串API发送），使用生成的id及当前时间，但拒绝不匹配的 `content-type` .
+    pub fn decode(mut headers: HashMap<String, String>, message: String) -> TardisResult<Self> {
+        if let Some(content_type) = headers.remove(HEADER_CONTENT_TYPE) {
+            if content_type != T::content_type() {
+                return Err(TardisError::format_error(
+                    &format!("[Tardis.MQClient] Mismatched content type {content_type}, expected {}", T::content_type()),
+                    "406-tardis-mq-payload-error",
+                ));
+            }
+        }
+        let timestamp = match headers.remove(HEADER_TIMESTAMP) {
+            Some(timestamp) => DateTime::parse_from_rfc3339(&timestamp)
+                .map_err(|error| TardisError::format_error(&format!("[Tardis.MQClient] Invalid timestamp {timestamp}: {error}"), "406-tardis-mq-payload-error"))?
+                .with_timezone(&Utc),
+            None => TardisFuns::clock().now(),
+        };
+        Ok(TardisMQMessage {
+            id: headers.remove(HEADER_MESSAGE_ID).unwrap_or_else(|| TardisFuns::field.nanoid()),
+            timestamp,
+            payload: T::decode(message)?,
+            headers,
+        })
+    }
+}
@@ -0,0 +1,144 @@
+//! NATS backend of the MQ client / MQ客户端的NATS后端
+//!
+//! The backend of [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) when the url scheme or the `kind` is `nats`,
+//! the addresses and the topics are the NATS subjects:
+//!
+//! 当url scheme或 `kind` 为 `nats` 时 [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) 使用的后端，地址及主题即为NATS的subject：
+//!
+//! * `request` / `publish`: publish the message to the subject / 将消息发布到该subject
+//! * `response`: subscribe by the queue group named by the address, so each message is processed by one of the responders
+//!   / 以与地址同名的队列组订阅，因此每条消息只由其中一个响应者处理
+//! * `subscribe`: subscribe the subject, so each subscriber receives all the messages / 订阅该subject，因此每个订阅者都会收到所有消息
+//!
+//! NATS core is at-most-once, the messages without any responder or subscriber are dropped,
+//! the failed messages are retried in the process and then published to the dead-letter subject if it's enabled.
+//!
+//! NATS core为至多一次投递，没有响应者或订阅者的消息会被丢弃，处理失败的消息在进程内重试，之后若启用死信则发布到死信subject.
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_nats::connection::State;
+use async_nats::{Client, HeaderMap, Subscriber};
+use futures_util::StreamExt;
+use tokio::task::JoinHandle;
+use tracing::error;
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::mq::MQConsumerConfig;
+use crate::mq::mq_client::{dead_letter_header, process_message, record_message};
+
+pub(crate) struct TardisNatsMQ {
+    client: Client,
+    consumers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl TardisNatsMQ {
+    pub(crate) async fn init(url: &Url) -> TardisResult<Self> {
+        let client = async_nats::connect(url.as_str()).await.map_err(nats_error)?;
+        Ok(TardisNatsMQ {
+            client,
+            consumers: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub(crate) async fn health_check(&self) -> TardisResult<()> {
+        match self.client.connection_state() {
+            State::Connected => Ok(()),
+            state => Err(TardisError::io_error(&format!("[Tardis.MQClient] Connection is {state:?}"), "503-tardis-mq-disconnected")),
+        }
+    }
+
+    pub(crate) async fn close(&self) -> TardisResult<()> {
+        for consumer in self.consumers.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] NATS consumers lock error: {error}"), ""))?.drain(..) {
+            consumer.abort();
+        }
+        self.client.flush().await.map_err(nats_error)
+    }
+
+    /// Publish and flush, so that the connection errors are returned / 发布并刷新，从而返回连接错误
+    pub(crate) async fn send(&self, subject: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+        send(&self.client, subject, message, header).await
+    }
+
+    pub(crate) async fn response<F, T>(&self, address: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let subscriber = self.client.queue_subscribe(address.to_string(), address.to_string()).await.map_err(nats_error)?;
+        self.process(address.to_string(), subscriber, options, fun)
+    }
+
+    pub(crate) async fn subscribe<F, T>(&self, topic: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let subscriber = self.client.subscribe(topic.to_string()).await.map_err(nats_error)?;
+        self.process(topic.to_string(), subscriber, options, fun)
+    }
+
+    /// Process the messages, at most `concurrency` messages are processed at the same time / 处理消息，同时最多处理 `concurrency` 条
+    fn process<F, T>(&self, subject: String, mut subscriber: Subscriber, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let client = self.client.clone();
+        let options = Arc::new(options.clone());
+        let fun = Arc::new(fun);
+        let permits = Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
+        let handle = tokio::spawn(async move {
+            while let Some(message) = subscriber.next().await {
+                let msg = match String::from_utf8(message.payload.to_vec()) {
+                    Ok(msg) => msg,
+                    Err(error) => {
+                        error!("[Tardis.MQClient] Receive delivery error, subject:{subject} | {error}");
+                        continue;
+                    }
+                };
+                let mut header = HashMap::new();
+                if let Some(headers) = &message.headers {
+                    for (name, values) in headers.iter() {
+                        if let Some(value) = values.first() {
+                            header.insert(name.to_string(), value.as_str().to_string());
+                        }
+                    }
+                }
+                let Ok(permit) = permits.clone().acquire_owned().await else {
+                    break;
+                };
+                let (client, subject, options, fun) = (client.clone(), subject.clone(), options.clone(), fun.clone());
+                tokio::spawn(async move {
+                    if let Err(error) = process_message(&subject, header.clone(), msg.clone(), fun.as_ref(), &options).await {
+                        if let Some(dead_letter_subject) = options.dead_letter_queue(&subject) {
+                            let result = send(&client, &dead_letter_subject, msg, dead_letter_header(header, &subject, &error)).await;
+                            record_message(&dead_letter_subject, "dead_letter", result.is_ok());
+                        }
+                    }
+                    drop(permit);
+                });
+            }
+        });
+        self.consumers.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] NATS consumers lock error: {error}"), ""))?.push(handle);
+        Ok(())
+    }
+}
+
+async fn send(client: &Client, subject: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &header {
+        headers.insert(name.as_str(), value.as_str());
+    }
+    client.publish_with_headers(subject.to_string(), headers, message.into_bytes().into()).await.map_err(nats_error)?;
+    client.flush().await.map_err(nats_error)
+}
+
+fn nats_error(error: impl Debug) -> TardisError {
+    error!("[Tardis.MQClient] Error: {:?}", error);
+    TardisError::wrap(&format!("[Tardis.MQClient] {error:?}"), "-1-tardis-mq-error")
+}
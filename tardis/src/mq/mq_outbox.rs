@@ -0,0 +1,234 @@
+//! Transactional outbox / 事务性发件箱
+//!
+//! [`TardisMQClient::publish_transactional`] writes the message into the outbox table in the caller's transaction,
+//! so the message is published if and only if the transaction is committed.
+//! The relay polls the outbox table, claims the rows, publishes them by [`TardisMQClient::publish`] and then deletes them.
+//!
+//! [`TardisMQClient::publish_transactional`] 在调用方的事务中将消息写入发件箱表，因此当且仅当事务提交时才会发布消息.
+//! 中继轮询发件箱表，认领行后通过 [`TardisMQClient::publish`] 发布，之后删除.
+//!
+//! The messages are published at least once: a row is claimed by updating it conditionally, so the relays of multiple instances
+//! don't publish the same row at the same time, but a row published by a relay crashed before deleting it is published again
+//! after the claim timeout, as well as the row failed to publish. The consumers should be idempotent.
+//!
+//! 消息至少发布一次：通过条件更新认领行，因此多个实例的中继不会同时发布同一行，
+//! 但中继在发布后删除前崩溃的行，以及发布失败的行，会在认领超时后再次发布. 消费者应保证幂等.
+//!
+//! The relay is started on initialization if the `outbox` of the MQ module is enabled, see [`MQOutboxConfig`].
+//!
+//! 若MQ模块启用了 `outbox` ，初始化时启动中继，见 [`MQOutboxConfig`] .
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sea_orm::sea_query::{Alias, ColumnDef, Expr, Order, Query, Table, TableCreateStatement};
+use sea_orm::FromQueryResult;
+use tokio::task::JoinHandle;
+use tracing::{error, info, trace, warn, Instrument};
+
+use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::basic::tracing::TardisTracing;
+use crate::config::config_dto::component::mq::MQOutboxConfig;
+use crate::config::config_dto::MQConfig;
+use crate::db::reldb_client::{TardisRelDBClient, TardisRelDBlConnection};
+use crate::mq::mq_client::TardisMQClient;
+use crate::TardisFuns;
+
+const COLUMN_ID: &str = "id";
+const COLUMN_TOPIC: &str = "topic";
+const COLUMN_MESSAGE: &str = "message";
+const COLUMN_HEADER: &str = "header";
+const COLUMN_ATTEMPTS: &str = "attempts";
+const COLUMN_CLAIMED_BY: &str = "claimed_by";
+const COLUMN_CLAIMED_UNTIL: &str = "claimed_until";
+const COLUMN_CREATE_TIME: &str = "create_time";
+
+#[derive(Debug, FromQueryResult)]
+struct OutboxRecord {
+    id: String,
+    topic: String,
+    message: String,
+    header: String,
+}
+
+/// Relay of the transactional outbox / 事务性发件箱的中继
+///
+/// # Examples
+/// ```ignore
+/// let outbox = TardisMQOutbox::new(TardisFuns::mq(), TardisFuns::reldb(), &MQOutboxConfig::default());
+/// outbox.init_table().await?;
+/// // publish the committed messages once, e.g. in tests
+/// outbox.relay_once().await?;
+/// ```
+pub struct TardisMQOutbox {
+    mq: Arc<TardisMQClient>,
+    db: Arc<TardisRelDBClient>,
+    config: MQOutboxConfig,
+    relay_id: String,
+}
+
+impl TardisMQOutbox {
+    pub fn new(mq: Arc<TardisMQClient>, db: Arc<TardisRelDBClient>, config: &MQOutboxConfig) -> Self {
+        TardisMQOutbox {
+            mq,
+            db,
+            config: config.clone(),
+            relay_id: TardisFuns::field.nanoid(),
+        }
+    }
+
+    /// Start the relays of the MQ modules whose outbox is enabled / 启动启用了发件箱的MQ模块的中继
+    pub(crate) async fn start_configured(mq_config: &MQConfig) -> TardisResult<()> {
+        for (code, module_config) in std::iter::once((&String::new(), &mq_config.default)).chain(mq_config.modules.iter()) {
+            if !module_config.outbox.enabled {
+                continue;
+            }
+            if module_config.outbox.batch_size == 0 {
+                return Err(TardisError::bad_request(
+                    &format!("[Tardis.MQClient] The outbox batch size of MQ {code} must be greater than 0"),
+                    "400-tardis-mq-outbox-batch-size-invalid",
+                ));
+            }
+            let db_module = &module_config.outbox.db_module;
+            if crate::TARDIS_INST.reldb.get(db_module).or_else(|| crate::TARDIS_INST.reldb.get("")).is_none() {
+                return Err(TardisError::not_found(
+                    &format!("[Tardis.MQClient] The outbox of MQ {code} requires the RelDB {db_module}"),
+                    "404-tardis-mq-outbox-db-empty",
+                ));
+            }
+            let mq = TardisFuns::mq_by_module(code);
+            let outbox = TardisMQOutbox::new(mq.clone(), TardisFuns::reldb_by_module_or_default(db_module), &module_config.outbox);
+            outbox.init_table().await?;
+            mq.set_outbox_relay(outbox.start())?;
+        }
+        Ok(())
+    }
+
+    /// Create the outbox table if it doesn't exist / 创建发件箱表（如果不存在）
+    pub async fn init_table(&self) -> TardisResult<()> {
+        self.db.conn().create_table(&create_table_statement(&self.config.table)).await
+    }
+
+    /// Start the relay, it's stopped when the handle is aborted / 启动中继，中止句柄时停止
+    pub fn start(self) -> JoinHandle<()> {
+        info!("[Tardis.MQClient] Outbox relay started, table:{}, relay:{}", self.config.table, self.relay_id);
+        tokio::spawn(async move {
+            loop {
+                let relayed = match self.relay_once().await {
+                    Ok(relayed) => relayed,
+                    Err(error) => {
+                        error!("[Tardis.MQClient] Outbox relay error, table:{} | {error}", self.config.table);
+                        0
+                    }
+                };
+                // the next batch may be ready unless nothing was relayed
+                if relayed == 0 || (relayed as u64) < self.config.batch_size {
+                    tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+                }
+            }
+        })
+    }
+
+    /// Claim and publish a batch of the rows, returns the number of the published messages / 认领并发布一批行，返回已发布的消息数
+    pub async fn relay_once(&self) -> TardisResult<usize> {
+        let table = Alias::new(&self.config.table);
+        let conn = self.db.conn();
+        let now = TardisFuns::clock().now().timestamp_millis();
+        let records: Vec<OutboxRecord> = conn
+            .find_dtos(
+                &Query::select()
+                    .columns([Alias::new(COLUMN_ID), Alias::new(COLUMN_TOPIC), Alias::new(COLUMN_MESSAGE), Alias::new(COLUMN_HEADER)])
+                    .from(table.clone())
+                    .and_where(Expr::col(Alias::new(COLUMN_CLAIMED_UNTIL)).lt(now))
+                    .order_by(Alias::new(COLUMN_CREATE_TIME), Order::Asc)
+                    .limit(self.config.batch_size)
+                    .to_owned(),
+            )
+            .await?;
+        let mut published = 0;
+        for record in records {
+            let claimed = conn
+                .execute(
+                    &Query::update()
+                        .table(table.clone())
+                        .value(Alias::new(COLUMN_CLAIMED_BY), self.relay_id.clone())
+                        .value(Alias::new(COLUMN_CLAIMED_UNTIL), now + self.config.claim_timeout_ms as i64)
+                        .value(Alias::new(COLUMN_ATTEMPTS), Expr::col(Alias::new(COLUMN_ATTEMPTS)).add(1))
+                        .and_where(Expr::col(Alias::new(COLUMN_ID)).eq(record.id.as_str()))
+                        .and_where(Expr::col(Alias::new(COLUMN_CLAIMED_UNTIL)).lt(now))
+                        .to_owned(),
+                )
+                .await?;
+            if claimed.rows_affected() != 1 {
+                trace!("[Tardis.MQClient] Outbox row is claimed by another relay, table:{}, id:{}", self.config.table, record.id);
+                continue;
+            }
+            let header = TardisFuns::json.str_to_obj::<HashMap<String, String>>(&record.header).unwrap_or_default();
+            let span = tracing::info_span!("mq_outbox_relay", otel.name = format!("{} relay", record.topic), otel.kind = "producer");
+            TardisTracing::set_parent_from(&span, &header);
+            if let Err(error) = self.mq.publish(&record.topic, record.message, &header).instrument(span).await {
+                // retried after the claim timeout
+                warn!(
+                    "[Tardis.MQClient] Outbox publish error, table:{}, id:{}, topic:{} | {error}",
+                    self.config.table, record.id, record.topic
+                );
+                continue;
+            }
+            conn.execute(
+                &Query::delete()
+                    .from_table(table.clone())
+                    .and_where(Expr::col(Alias::new(COLUMN_ID)).eq(record.id.as_str()))
+                    .and_where(Expr::col(Alias::new(COLUMN_CLAIMED_BY)).eq(self.relay_id.as_str()))
+                    .to_owned(),
+            )
+            .await?;
+            published += 1;
+        }
+        Ok(published)
+    }
+}
+
+/// Write the message into the outbox table by the connection / 通过连接将消息写入发件箱表
+pub(crate) async fn write(conn: &TardisRelDBlConnection, table: &str, topic: &str, message: String, header: &HashMap<String, String>) -> TardisResult<()> {
+    let mut header = header.clone();
+    TardisTracing::inject_context(&mut header);
+    TardisContext::inject(&mut header);
+    conn.execute(
+        &Query::insert()
+            .into_table(Alias::new(table))
+            .columns([
+                Alias::new(COLUMN_ID),
+                Alias::new(COLUMN_TOPIC),
+                Alias::new(COLUMN_MESSAGE),
+                Alias::new(COLUMN_HEADER),
+                Alias::new(COLUMN_CREATE_TIME),
+            ])
+            .values_panic([
+                TardisFuns::field.nanoid().into(),
+                topic.into(),
+                message.into(),
+                TardisFuns::json.obj_to_string(&header)?.into(),
+                TardisFuns::clock().now().timestamp_millis().into(),
+            ])
+            .to_owned(),
+    )
+    .await?;
+    Ok(())
+}
+
+fn create_table_statement(table: &str) -> TableCreateStatement {
+    Table::create()
+        .table(Alias::new(table))
+        .if_not_exists()
+        .col(ColumnDef::new(Alias::new(COLUMN_ID)).not_null().string().primary_key())
+        .col(ColumnDef::new(Alias::new(COLUMN_TOPIC)).not_null().string())
+        .col(ColumnDef::new(Alias::new(COLUMN_MESSAGE)).not_null().text())
+        .col(ColumnDef::new(Alias::new(COLUMN_HEADER)).not_null().text())
+        .col(ColumnDef::new(Alias::new(COLUMN_ATTEMPTS)).not_null().integer().default(0))
+        .col(ColumnDef::new(Alias::new(COLUMN_CLAIMED_BY)).not_null().string().default(""))
+        .col(ColumnDef::new(Alias::new(COLUMN_CLAIMED_UNTIL)).not_null().big_integer().default(0))
+        .col(ColumnDef::new(Alias::new(COLUMN_CREATE_TIME)).not_null().big_integer())
+        .to_owned()
+}
@@ -0,0 +1,203 @@
+//! Redis Streams backend of the MQ client / MQ客户端的Redis Streams后端
+//!
+//! The backend of [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) when the url scheme is `redis`/`rediss` or the `kind` is `redis_stream`,
+//! each address or topic is a stream whose entries have the `message` and the `header` (JSON) fields:
+//!
+//! 当url scheme为 `redis`/`rediss` 或 `kind` 为 `redis_stream` 时 [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) 使用的后端，
+//! 每个地址或主题为一个流，其条目包含 `message` 及 `header` （JSON）字段：
+//!
+//! * `request` / `publish`: add the entry to the stream, trimmed to the approximate `max_len` / 向流中添加条目，并裁剪到近似的 `max_len`
+//! * `response`: read by the configured consumer group, the group starts from the beginning of the stream,
+//!   so the messages sent before any responder is registered are kept, the entry is acknowledged after processed successfully
+//!   / 以配置的消费者组读取，该组从流的开头开始，因此保留在注册响应者之前发送的消息，条目处理成功后确认
+//! * `subscribe`: read the entries added after subscribed, so each subscriber receives all the messages
+//!   / 读取订阅后添加的条目，因此每个订阅者都会收到所有消息
+//!
+//! The failed entries are retried in the process and then added to the dead-letter stream if it's enabled,
+//! otherwise the failed entries of `response` stay in the pending list of the group, e.g. for `XAUTOCLAIM` .
+//!
+//! 处理失败的条目在进程内重试，之后若启用死信则添加到死信流，否则 `response` 的失败条目保留在消费者组的待处理列表中，如用于 `XAUTOCLAIM` .
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamMaxlen, StreamRangeReply, StreamReadOptions, StreamReadReply};
+use redis::{AsyncCommands, Client, RedisResult};
+use tokio::task::JoinHandle;
+use tracing::error;
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::mq::{MQConsumerConfig, MQRedisStreamConfig};
+use crate::mq::mq_client::{dead_letter_header, process_message, record_message};
+use crate::TardisFuns;
+
+const FIELD_MESSAGE: &str = "message";
+const FIELD_HEADER: &str = "header";
+
+pub(crate) struct TardisRedisStreamMQ {
+    client: Client,
+    con: MultiplexedConnection,
+    config: MQRedisStreamConfig,
+    consumers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl TardisRedisStreamMQ {
+    pub(crate) async fn init(url: &Url, config: &MQRedisStreamConfig) -> TardisResult<Self> {
+        let client = Client::open(url.as_str())?;
+        let con = client.get_multiplexed_tokio_connection().await?;
+        Ok(TardisRedisStreamMQ {
+            client,
+            con,
+            config: config.clone(),
+            consumers: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub(crate) async fn health_check(&self) -> TardisResult<()> {
+        redis::cmd("PING")
+            .query_async::<_, String>(&mut self.con.clone())
+            .await
+            .map_err(|error| TardisError::io_error(&format!("[Tardis.MQClient] Redis is unavailable: {error}"), "503-tardis-mq-disconnected"))?;
+        Ok(())
+    }
+
+    pub(crate) async fn close(&self) -> TardisResult<()> {
+        for consumer in self.consumers()?.drain(..) {
+            consumer.abort();
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn send(&self, stream: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+        send(&mut self.con.clone(), self.config.max_len, stream, message, header).await
+    }
+
+    pub(crate) async fn response<F, T>(&self, address: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let group = self.config.group.clone();
+        let created: RedisResult<()> = self.con.clone().xgroup_create_mkstream(address, &group, "0").await;
+        if let Err(error) = created {
+            // the group already exists
+            if error.code() != Some("BUSYGROUP") {
+                return Err(error.into());
+            }
+        }
+        let mut con = self.client.get_async_connection().await?;
+        let stream = address.to_string();
+        let read_options = StreamReadOptions::default().group(&group, TardisFuns::field.nanoid()).count(1).block(self.config.block_ms);
+        let options = options.clone();
+        let max_len = self.config.max_len;
+        self.spawn(async move {
+            loop {
+                let reply: RedisResult<Option<StreamReadReply>> = con.xread_options(&[&stream], &[">"], &read_options).await;
+                let entries = match reply {
+                    Ok(reply) => reply.map(|reply| reply.keys.into_iter().flat_map(|key| key.ids).collect::<Vec<_>>()).unwrap_or_default(),
+                    Err(error) => {
+                        error!("[Tardis.MQClient] Receive connection error, stream:{stream} | {error}");
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                for entry in entries {
+                    let (header, msg) = decode_entry(&entry.map);
+                    let processed = match process_message(&stream, header.clone(), msg.clone(), &fun, &options).await {
+                        Ok(()) => true,
+                        Err(error) => dead_letter(&mut con, max_len, &options, &stream, msg, header, &error).await,
+                    };
+                    if processed {
+                        let acked: RedisResult<()> = con.xack(&stream, &group, &[&entry.id]).await;
+                        if let Err(error) = acked {
+                            error!("[Tardis.MQClient] Receive ack error, stream:{stream}, id:{} | {error}", entry.id);
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    pub(crate) async fn subscribe<F, T>(&self, topic: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let mut con = self.client.get_async_connection().await?;
+        let stream = topic.to_string();
+        // the id of the last entry, so that the entries added during the processing aren't missed
+        let latest: StreamRangeReply = con.xrevrange_count(&stream, "+", "-", 1).await?;
+        let mut last_id = latest.ids.first().map(|entry| entry.id.clone()).unwrap_or_else(|| "0".to_string());
+        let read_options = StreamReadOptions::default().count(100).block(self.config.block_ms);
+        let options = options.clone();
+        let max_len = self.config.max_len;
+        self.spawn(async move {
+            loop {
+                let reply: RedisResult<Option<StreamReadReply>> = con.xread_options(&[&stream], &[&last_id], &read_options).await;
+                let entries = match reply {
+                    Ok(reply) => reply.map(|reply| reply.keys.into_iter().flat_map(|key| key.ids).collect::<Vec<_>>()).unwrap_or_default(),
+                    Err(error) => {
+                        error!("[Tardis.MQClient] Receive connection error, stream:{stream} | {error}");
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                for entry in entries {
+                    let (header, msg) = decode_entry(&entry.map);
+                    if let Err(error) = process_message(&stream, header.clone(), msg.clone(), &fun, &options).await {
+                        dead_letter(&mut con, max_len, &options, &stream, msg, header, &error).await;
+                    }
+                    last_id = entry.id;
+                }
+            }
+        })
+    }
+
+    fn spawn(&self, consumer: impl Future<Output = ()> + Send + 'static) -> TardisResult<()> {
+        self.consumers()?.push(tokio::spawn(consumer));
+        Ok(())
+    }
+
+    fn consumers(&self) -> TardisResult<std::sync::MutexGuard<'_, Vec<JoinHandle<()>>>> {
+        self.consumers.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] Redis stream consumers lock error: {error}"), ""))
+    }
+}
+
+async fn send<C: AsyncCommands>(con: &mut C, max_len: Option<usize>, stream: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+    let fields = [(FIELD_MESSAGE, message), (FIELD_HEADER, TardisFuns::json.obj_to_string(&header)?)];
+    let _: String = match max_len {
+        Some(max_len) => con.xadd_maxlen(stream, StreamMaxlen::Approx(max_len), "*", &fields).await?,
+        None => con.xadd(stream, "*", &fields).await?,
+    };
+    Ok(())
+}
+
+/// Add the failed entry to the dead-letter stream, returns whether it's added / 将失败的条目添加到死信流，返回是否已添加
+async fn dead_letter<C: AsyncCommands>(
+    con: &mut C,
+    max_len: Option<usize>,
+    options: &MQConsumerConfig,
+    stream: &str,
+    message: String,
+    header: HashMap<String, String>,
+    error: &TardisError,
+) -> bool {
+    let Some(dead_letter_stream) = options.dead_letter_queue(stream) else {
+        return false;
+    };
+    let result = send(con, max_len, &dead_letter_stream, message, dead_letter_header(header, stream, error)).await;
+    record_message(&dead_letter_stream, "dead_letter", result.is_ok());
+    if let Err(error) = &result {
+        error!("[Tardis.MQClient] Dead letter error, stream:{dead_letter_stream} | {error}");
+    }
+    result.is_ok()
+}
+
+fn decode_entry(fields: &HashMap<String, redis::Value>) -> (HashMap<String, String>, String) {
+    let field = |name: &str| fields.get(name).and_then(|value| redis::from_redis_value::<String>(value).ok());
+    let header = field(FIELD_HEADER).and_then(|header| TardisFuns::json.str_to_obj::<HashMap<String, String>>(&header).ok()).unwrap_or_default();
+    (header, field(FIELD_MESSAGE).unwrap_or_default())
+}
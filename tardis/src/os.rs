@@ -1 +1,4 @@
+#[cfg(feature = "os-azure")]
+pub(crate) mod os_azure;
 pub mod os_client;
+pub(crate) mod os_fs;
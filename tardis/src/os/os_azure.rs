@@ -0,0 +1,498 @@
+//! Azure Blob Storage backend of the OS client / OS客户端的Azure Blob Storage后端
+//!
+//! The backend of [`TardisOSClient`](crate::os::os_client::TardisOSClient) when the `kind` is `azure`, requires the `os-azure` feature.
+//! The buckets are the containers, the `ak` is the storage account name and the `sk` is the account key,
+//! the `endpoint` defaults to `https://{account}.blob.core.windows.net` , e.g. `http://127.0.0.1:10000/devstoreaccount1` for Azurite.
+//!
+//! 当 `kind` 为 `azure` 时 [`TardisOSClient`](crate::os::os_client::TardisOSClient) 使用的后端，需启用 `os-azure` 特性.
+//! 桶即为容器， `ak` 为存储账户名， `sk` 为账户密钥， `endpoint` 默认为 `https://{account}.blob.core.windows.net` ，Azurite可使用如 `http://127.0.0.1:10000/devstoreaccount1` .
+//!
+//! The requests are signed by the Shared Key, and the presigned urls are the service SAS of the blobs.
+//! The multipart uploads are the block blobs, the parts are the uncommitted blocks, which are discarded by the service
+//! after a week if not committed, so aborting the upload doesn't request the service.
+//!
+//! 请求使用共享密钥签名，预签名url为blob的服务SAS.
+//! 分片上传即为块blob，分片为未提交的块，未提交的块一周后由服务端丢弃，因此中止上传不会请求服务端.
+//!
+//! The lifecycle rules aren't supported, since the lifecycle management policies of Azure are configured for the storage accounts
+//! by the management API.
+//!
+//! 不支持生命周期规则，因为Azure的生命周期管理策略通过管理API配置于存储账户.
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method};
+use sha2::Sha256;
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::os::os_client::{xml_escape, xml_text, TardisOSLifecycleRule, TardisOSObject, TardisOSObjectMeta, TardisOSObjectPage, TardisOSOperations, TardisOSUploadedPart};
+use crate::TardisFuns;
+
+const API_VERSION: &str = "2021-08-06";
+const META_HEADER_PREFIX: &str = "x-ms-meta-";
+
+pub(crate) struct TardisOSAzureClient {
+    client: Client,
+    endpoint: Url,
+    account: String,
+    key: Vec<u8>,
+    default_container: Option<String>,
+}
+
+/// Response of the Blob service / Blob服务的响应
+struct AzureResponse {
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl TardisOSAzureClient {
+    pub(crate) fn init(endpoint: &str, account: &str, key: &str, default_container: &str) -> TardisResult<Self> {
+        let endpoint = if endpoint.is_empty() {
+            format!("https://{account}.blob.core.windows.net")
+        } else {
+            endpoint.trim_end_matches('/').to_string()
+        };
+        let endpoint =
+            Url::parse(&endpoint).map_err(|error| TardisError::format_error(&format!("[Tardis.OSClient] Invalid endpoint {endpoint}: {error}"), "406-tardis-os-endpoint-error"))?;
+        let key =
+            STANDARD.decode(key).map_err(|error| TardisError::format_error(&format!("[Tardis.OSClient] Invalid account key of {account}: {error}"), "406-tardis-os-key-error"))?;
+        Ok(TardisOSAzureClient {
+            client: Client::new(),
+            endpoint,
+            account: account.to_string(),
+            key,
+            default_container: if default_container.is_empty() { None } else { Some(default_container.to_string()) },
+        })
+    }
+
+    fn get_container<'a>(&'a self, bucket_name: Option<&'a str>) -> TardisResult<&'a str> {
+        bucket_name
+            .or(self.default_container.as_deref())
+            .ok_or_else(|| TardisError::not_found("[Tardis.OSClient] No default bucket configured", "404-tardis-os-default-bucket-not-exist"))
+    }
+
+    fn resource_url(&self, container: &str, path: Option<&str>) -> TardisResult<Url> {
+        let mut url = self.endpoint.clone();
+        {
+            let mut segments =
+                url.path_segments_mut().map_err(|_| TardisError::format_error(&format!("[Tardis.OSClient] Invalid endpoint {}", self.endpoint), "406-tardis-os-endpoint-error"))?;
+            segments.pop_if_empty().push(container);
+            if let Some(path) = path {
+                segments.extend(path.split('/'));
+            }
+        }
+        Ok(url)
+    }
+
+    fn sign(&self, string_to_sign: &str) -> TardisResult<String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key)
+            .map_err(|error| TardisError::format_error(&format!("[Tardis.OSClient] Invalid account key: {error}"), "406-tardis-os-key-error"))?;
+        mac.update(string_to_sign.as_bytes());
+        Ok(STANDARD.encode(mac.finalize().into_bytes()))
+    }
+
+    /// Send the request signed by the Shared Key / 发送使用共享密钥签名的请求
+    ///
+    /// The header names are lowercase.
+    ///
+    /// 请求头名称为小写.
+    #[allow(clippy::too_many_arguments)]
+    async fn send(
+        &self,
+        method: Method,
+        container: &str,
+        path: Option<&str>,
+        query: &[(&str, &str)],
+        mut headers: Vec<(String, String)>,
+        body: Vec<u8>,
+        operation: &str,
+        locale_code: &str,
+    ) -> TardisResult<AzureResponse> {
+        let mut url = self.resource_url(container, path)?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        headers.push(("x-ms-date".to_string(), Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string()));
+        headers.push(("x-ms-version".to_string(), API_VERSION.to_string()));
+        let header = |name: &str| headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str()).unwrap_or("");
+        let mut ms_headers = headers.iter().filter(|(key, _)| key.starts_with("x-ms-")).collect::<Vec<_>>();
+        ms_headers.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        let canonicalized_headers = ms_headers.iter().map(|(key, value)| format!("{key}:{}\n", value.trim())).collect::<String>();
+        let mut canonicalized_query = query.iter().map(|(key, value)| format!("\n{}:{value}", key.to_lowercase())).collect::<Vec<_>>();
+        canonicalized_query.sort();
+        let string_to_sign = format!(
+            "{}\n\n\n{}\n\n{}\n\n\n\n\n\n\n{}/{}{}{}",
+            method.as_str(),
+            if body.is_empty() { String::new() } else { body.len().to_string() },
+            header("content-type"),
+            canonicalized_headers,
+            self.account,
+            url.path(),
+            canonicalized_query.concat()
+        );
+        let signature = self.sign(&string_to_sign)?;
+        let mut request = self.client.request(method, url).header("authorization", format!("SharedKey {}:{signature}", self.account));
+        for (key, value) in &headers {
+            request = request.header(key, value);
+        }
+        let response = request.body(body).send().await?;
+        let status_code = response.status().as_u16();
+        let response_headers = response.headers().clone();
+        let response_body = response.bytes().await?.to_vec();
+        if (200..300).contains(&status_code) {
+            Ok(AzureResponse {
+                headers: response_headers,
+                body: response_body,
+            })
+        } else {
+            Err(TardisError::custom(
+                &status_code.to_string(),
+                &format!(
+                    "[Tardis.OSClient] Failed to {} {}:{} with error [{}]",
+                    operation,
+                    container,
+                    path.unwrap_or(""),
+                    String::from_utf8_lossy(&response_body)
+                ),
+                locale_code,
+            ))
+        }
+    }
+
+    /// The url with the service SAS of the blob / 带有blob服务SAS的url
+    fn presign(&self, path: &str, permissions: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        let container = self.get_container(bucket_name)?;
+        let expiry = (Utc::now() + Duration::seconds(expire_sec as i64)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let canonicalized_resource = format!("/blob/{}/{container}/{path}", self.account);
+        // permissions, start, expiry, resource, identifier, ip, protocol, version, resource type, snapshot time, encryption scope, response headers
+        let string_to_sign = [permissions, "", &expiry, &canonicalized_resource, "", "", "", API_VERSION, "b", "", "", "", "", "", "", ""].join("\n");
+        let signature = self.sign(&string_to_sign)?;
+        let mut url = self.resource_url(container, Some(path))?;
+        url.query_pairs_mut().append_pair("sv", API_VERSION).append_pair("se", &expiry).append_pair("sr", "b").append_pair("sp", permissions).append_pair("sig", &signature);
+        Ok(url.to_string())
+    }
+
+    fn lifecycle_unsupported() -> TardisError {
+        TardisError::not_implemented(
+            "[Tardis.OSClient] The lifecycle of Azure Blob Storage is managed by the storage account, not by the containers",
+            "501-tardis-os-lifecycle-unsupported",
+        )
+    }
+
+    fn block_id(upload_id: &str, part_number: u32) -> String {
+        // the block ids of a blob must be the same length
+        STANDARD.encode(format!("{upload_id}-{part_number:05}"))
+    }
+}
+
+#[async_trait]
+impl TardisOSOperations for TardisOSAzureClient {
+    async fn health_check(&self) -> TardisResult<()> {
+        let Some(container) = &self.default_container else {
+            return Ok(());
+        };
+        self.send(
+            Method::GET,
+            container,
+            None,
+            &[("restype", "container"), ("comp", "list"), ("maxresults", "1")],
+            vec![],
+            vec![],
+            "list blobs of",
+            "-1-tardis-os-health-check-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn bucket_create_simple(&self, bucket_name: &str, is_private: bool) -> TardisResult<()> {
+        let headers = if is_private {
+            vec![]
+        } else {
+            vec![("x-ms-blob-public-access".to_string(), "blob".to_string())]
+        };
+        self.send(
+            Method::PUT,
+            bucket_name,
+            None,
+            &[("restype", "container")],
+            headers,
+            vec![],
+            "create bucket",
+            "-1-tardis-os-create-bucket-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn bucket_delete(&self, bucket_name: &str) -> TardisResult<()> {
+        self.send(
+            Method::DELETE,
+            bucket_name,
+            None,
+            &[("restype", "container")],
+            vec![],
+            vec![],
+            "delete bucket",
+            "-1-tardis-os-delete-bucket-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn object_create(
+        &self,
+        path: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+        metadata: Option<&HashMap<String, String>>,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<()> {
+        let container = self.get_container(bucket_name)?;
+        let mut headers = vec![("x-ms-blob-type".to_string(), "BlockBlob".to_string())];
+        if let Some(content_type) = content_type {
+            headers.push(("content-type".to_string(), content_type.to_string()));
+        }
+        headers.extend(metadata.into_iter().flatten().map(|(key, value)| (format!("{META_HEADER_PREFIX}{}", key.to_lowercase()), value.clone())));
+        self.send(
+            Method::PUT,
+            container,
+            Some(path),
+            &[],
+            headers,
+            content.to_vec(),
+            "create object",
+            "-1-tardis-os-create-object-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn object_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<Vec<u8>> {
+        let container = self.get_container(bucket_name)?;
+        Ok(self.send(Method::GET, container, Some(path), &[], vec![], vec![], "get object", "-1-tardis-os-get-object-error").await?.body)
+    }
+
+    async fn object_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        let container = self.get_container(bucket_name)?;
+        self.send(
+            Method::DELETE,
+            container,
+            Some(path),
+            &[],
+            vec![],
+            vec![],
+            "delete object",
+            "-1-tardis-os-delete-object-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn object_create_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.presign(path, "cw", expire_sec, bucket_name)
+    }
+
+    fn object_get_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.presign(path, "r", expire_sec, bucket_name)
+    }
+
+    fn object_delete_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.presign(path, "d", expire_sec, bucket_name)
+    }
+
+    async fn object_head(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectMeta> {
+        let container = self.get_container(bucket_name)?;
+        let response = self.send(Method::HEAD, container, Some(path), &[], vec![], vec![], "head object", "-1-tardis-os-head-object-error").await?;
+        let header = |name: &str| response.headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        Ok(TardisOSObjectMeta {
+            content_length: header("content-length").and_then(|content_length| content_length.parse().ok()),
+            content_type: header("content-type"),
+            etag: header("etag"),
+            last_modified: header("last-modified"),
+            metadata: response
+                .headers
+                .iter()
+                .filter_map(|(key, value)| Some((key.as_str().strip_prefix(META_HEADER_PREFIX)?.to_string(), value.to_str().ok()?.to_string())))
+                .collect(),
+        })
+    }
+
+    async fn object_tags_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<HashMap<String, String>> {
+        let container = self.get_container(bucket_name)?;
+        let response = self
+            .send(
+                Method::GET,
+                container,
+                Some(path),
+                &[("comp", "tags")],
+                vec![],
+                vec![],
+                "get object tags",
+                "-1-tardis-os-get-tags-error",
+            )
+            .await?;
+        let body = String::from_utf8_lossy(&response.body);
+        Ok(body.split("<Tag>").skip(1).filter_map(|tag| Some((xml_text(tag, "Key")?, xml_text(tag, "Value")?))).collect())
+    }
+
+    async fn object_tags_put(&self, path: &str, tags: &HashMap<String, String>, bucket_name: Option<&str>) -> TardisResult<()> {
+        let container = self.get_container(bucket_name)?;
+        let tags = tags.iter().map(|(key, value)| format!("<Tag><Key>{}</Key><Value>{}</Value></Tag>", xml_escape(key), xml_escape(value))).collect::<String>();
+        let body = format!(r#"<?xml version="1.0" encoding="utf-8"?><Tags><TagSet>{tags}</TagSet></Tags>"#);
+        self.send(
+            Method::PUT,
+            container,
+            Some(path),
+            &[("comp", "tags")],
+            vec![("content-type".to_string(), "application/xml".to_string())],
+            body.into_bytes(),
+            "put object tags",
+            "-1-tardis-os-put-tags-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn object_tags_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        self.object_tags_put(path, &HashMap::new(), bucket_name).await
+    }
+
+    async fn object_list(&self, prefix: &str, continuation_token: Option<&str>, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectPage> {
+        let container = self.get_container(bucket_name)?;
+        let mut query = vec![("restype", "container"), ("comp", "list"), ("prefix", prefix)];
+        if let Some(continuation_token) = continuation_token {
+            query.push(("marker", continuation_token));
+        }
+        let response = self.send(Method::GET, container, None, &query, vec![], vec![], "list objects of", "-1-tardis-os-list-objects-error").await?;
+        let body = String::from_utf8_lossy(&response.body);
+        Ok(TardisOSObjectPage {
+            objects: body
+                .split("<Blob>")
+                .skip(1)
+                .filter_map(|blob| {
+                    Some(TardisOSObject {
+                        path: xml_text(blob, "Name")?,
+                        size: xml_text(blob, "Content-Length").and_then(|size| size.parse().ok()).unwrap_or_default(),
+                        etag: xml_text(blob, "Etag"),
+                        last_modified: xml_text(blob, "Last-Modified"),
+                    })
+                })
+                .collect(),
+            next_continuation_token: xml_text(&body, "NextMarker").filter(|marker| !marker.is_empty()),
+        })
+    }
+
+    async fn object_copy(&self, from: &str, to: &str, from_bucket_name: Option<&str>, to_bucket_name: Option<&str>) -> TardisResult<()> {
+        let from_container = self.get_container(from_bucket_name)?;
+        let to_container = self.get_container(to_bucket_name)?;
+        let source = self.resource_url(from_container, Some(from))?.to_string();
+        let mut response = self
+            .send(
+                Method::PUT,
+                to_container,
+                Some(to),
+                &[],
+                vec![("x-ms-copy-source".to_string(), source)],
+                vec![],
+                "copy object to",
+                "-1-tardis-os-copy-object-error",
+            )
+            .await?;
+        // the copy in the same account is usually synchronous, otherwise wait for it
+        loop {
+            match response.headers.get("x-ms-copy-status").and_then(|status| status.to_str().ok()) {
+                Some("pending") => {
+                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                    response = self.send(Method::HEAD, to_container, Some(to), &[], vec![], vec![], "head object", "-1-tardis-os-copy-object-error").await?;
+                }
+                Some("success") | None => return Ok(()),
+                Some(status) => {
+                    return Err(TardisError::custom(
+                        "500",
+                        &format!("[Tardis.OSClient] Failed to copy object {from_container}:{from} to {to_container}:{to} with status {status}"),
+                        "-1-tardis-os-copy-object-error",
+                    ))
+                }
+            }
+        }
+    }
+
+    async fn bucket_lifecycle_get(&self, _bucket_name: Option<&str>) -> TardisResult<Vec<TardisOSLifecycleRule>> {
+        Err(Self::lifecycle_unsupported())
+    }
+
+    async fn bucket_lifecycle_put(&self, _rules: &[TardisOSLifecycleRule], _bucket_name: Option<&str>) -> TardisResult<()> {
+        Err(Self::lifecycle_unsupported())
+    }
+
+    async fn bucket_lifecycle_delete(&self, _bucket_name: Option<&str>) -> TardisResult<()> {
+        Err(Self::lifecycle_unsupported())
+    }
+
+    async fn object_multipart_init(&self, _path: &str, _content_type: &str, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.get_container(bucket_name)?;
+        Ok(TardisFuns::field.nanoid())
+    }
+
+    async fn object_multipart_put_part(
+        &self,
+        path: &str,
+        upload_id: &str,
+        part_number: u32,
+        content: Vec<u8>,
+        _content_type: &str,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<String> {
+        let container = self.get_container(bucket_name)?;
+        let block_id = Self::block_id(upload_id, part_number);
+        self.send(
+            Method::PUT,
+            container,
+            Some(path),
+            &[("comp", "block"), ("blockid", &block_id)],
+            vec![],
+            content,
+            "put part of",
+            "-1-tardis-os-put-part-error",
+        )
+        .await?;
+        Ok(block_id)
+    }
+
+    async fn object_multipart_complete(&self, path: &str, _upload_id: &str, parts: &[TardisOSUploadedPart], content_type: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        let container = self.get_container(bucket_name)?;
+        let mut parts = parts.to_vec();
+        parts.sort_by_key(|part| part.part_number);
+        let blocks = parts.iter().map(|part| format!("<Latest>{}</Latest>", xml_escape(&part.etag))).collect::<String>();
+        let body = format!(r#"<?xml version="1.0" encoding="utf-8"?><BlockList>{blocks}</BlockList>"#);
+        self.send(
+            Method::PUT,
+            container,
+            Some(path),
+            &[("comp", "blocklist")],
+            vec![
+                ("content-type".to_string(), "application/xml".to_string()),
+                ("x-ms-blob-content-type".to_string(), content_type.to_string()),
+            ],
+            body.into_bytes(),
+            "complete multipart upload",
+            "-1-tardis-os-complete-multipart-error",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn object_multipart_abort(&self, _path: &str, _upload_id: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        // the uncommitted blocks are discarded by the service
+        self.get_container(bucket_name)?;
+        Ok(())
+    }
+}
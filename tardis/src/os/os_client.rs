@@ -1,23 +1,106 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
 use std::ops::Deref;
+use std::path::Path;
 
 use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use futures_util::StreamExt;
+use hmac::{Hmac, Mac};
+use md5::Md5;
 use s3::creds::Credentials;
+use s3::serde_types::Part;
 use s3::{Bucket, BucketConfiguration, Region};
-use tracing::{error, info, trace};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{error, info, trace, warn};
+use url::{Position, Url};
 
 use crate::basic::error::{TardisError, ERROR_DEFAULT_CODE};
+use crate::basic::metrics::observe_client;
 use crate::config::config_dto::component::os::OSModuleConfig;
+use crate::os::os_fs::TardisOSFsClient;
 use crate::utils::initializer::InitBy;
 use crate::TardisResult;
 
 pub struct TardisOSClient {
     client: Box<dyn TardisOSOperations + Sync + Send>,
+    presign_expire_sec: u32,
+    multipart_part_size: usize,
+    multipart_concurrency: usize,
+}
+
+/// State of the multipart upload, persist it to resume the upload after a failure or a restart
+/// / 分片上传的状态，持久化后可在失败或重启后恢复上传
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisOSMultipartUpload {
+    pub path: String,
+    pub bucket_name: Option<String>,
+    pub upload_id: String,
+    pub content_type: String,
+    pub part_size: usize,
+    /// The uploaded parts / 已上传的分片
+    pub parts: Vec<TardisOSUploadedPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisOSUploadedPart {
+    pub part_number: u32,
+    pub etag: String,
+}
+
+/// Metadata of the object / 对象的元数据
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TardisOSObjectMeta {
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// The user-defined metadata, i.e. the `x-amz-meta-*` headers without the prefix / 用户自定义元数据，即去掉前缀的 `x-amz-meta-*` 头
+    pub metadata: HashMap<String, String>,
+}
+
+/// Object in the listing / 列举出的对象
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TardisOSObject {
+    pub path: String,
+    pub size: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Page of the listed objects / 列举对象的分页
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TardisOSObjectPage {
+    pub objects: Vec<TardisOSObject>,
+    /// The token to list the next page, `None` for the last page / 列举下一页的令牌，最后一页为 `None`
+    pub next_continuation_token: Option<String>,
+}
+
+/// Lifecycle rule of the bucket / 桶的生命周期规则
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TardisOSLifecycleRule {
+    pub id: String,
+    /// The rule applies to the objects with the prefix, all the objects if empty / 规则作用于具有该前缀的对象，为空时作用于所有对象
+    pub prefix: String,
+    pub enabled: bool,
+    /// Delete the objects the days after the creation / 对象创建后的该天数删除对象
+    pub expiration_days: Option<u32>,
+    /// Move the objects to the `transition_storage_class` the days after the creation, e.g. for archiving
+    /// / 对象创建后的该天数将对象转移到 `transition_storage_class` ，如用于归档
+    pub transition_days: Option<u32>,
+    pub transition_storage_class: Option<String>,
+    /// Abort the incomplete multipart uploads the days after the initiation / 分片上传开始后的该天数中止未完成的上传
+    pub abort_incomplete_multipart_days: Option<u32>,
 }
 
 struct TardisOSS3Client {
     region: Region,
     credentials: Credentials,
     default_bucket: Option<Bucket>,
+    http_client: reqwest::Client,
 }
 
 #[async_trait::async_trait]
@@ -36,10 +119,13 @@ impl TardisOSClient {
             sk,
             region,
             default_bucket,
+            presign_expire_sec,
+            multipart_part_size,
+            multipart_concurrency,
         }: &OSModuleConfig,
     ) -> TardisResult<TardisOSClient> {
         info!("[Tardis.OSClient] Initializing for {}", kind);
-        match kind.as_str() {
+        let client: Box<dyn TardisOSOperations + Sync + Send> = match kind.as_str() {
             "s3" => {
                 let region = Region::Custom {
                     region: region.to_string(),
@@ -57,73 +143,327 @@ impl TardisOSClient {
                 } else {
                     None
                 };
-                let s3 = TardisOSS3Client {
+                Box::new(TardisOSS3Client {
                     region,
                     credentials,
                     default_bucket,
-                };
-                info!("[Tardis.OSClient] Initialized");
-                Ok(TardisOSClient { client: Box::new(s3) })
+                    http_client: reqwest::Client::new(),
+                })
             }
-            _ => Err(TardisError::not_implemented(
-                &format!("[Tardis.OSClient] Unsupported OS kind {kind}"),
-                "501-tardis-os-kind-error",
-            )),
-        }
+            "fs" => Box::new(TardisOSFsClient::init(endpoint, default_bucket)?),
+            #[cfg(feature = "os-azure")]
+            "azure" => Box::new(crate::os::os_azure::TardisOSAzureClient::init(endpoint, ak, sk, default_bucket)?),
+            _ => {
+                return Err(TardisError::not_implemented(
+                    &format!("[Tardis.OSClient] Unsupported OS kind {kind}, the azure kind requires the os-azure feature"),
+                    "501-tardis-os-kind-error",
+                ))
+            }
+        };
+        info!("[Tardis.OSClient] Initialized");
+        Ok(TardisOSClient {
+            client,
+            presign_expire_sec: *presign_expire_sec,
+            multipart_part_size: *multipart_part_size,
+            multipart_concurrency: (*multipart_concurrency).max(1),
+        })
     }
 
     fn get_client(&self) -> &(dyn TardisOSOperations + Sync + Send) {
         self.client.deref()
     }
 
+    /// Check the access of the default bucket / 检查默认桶的访问
+    pub async fn health_check(&self) -> TardisResult<()> {
+        self.get_client().health_check().await
+    }
+
     pub async fn bucket_create_simple(&self, bucket_name: &str, is_private: bool) -> TardisResult<()> {
         trace!("[Tardis.OSClient] Creating bucket {}", bucket_name);
-        self.get_client().bucket_create_simple(bucket_name, is_private).await
+        observe_client("os", "bucket_create_simple", self.get_client().bucket_create_simple(bucket_name, is_private)).await
     }
 
     pub async fn bucket_delete(&self, bucket_name: &str) -> TardisResult<()> {
         trace!("[Tardis.OSClient] Deleting bucket {}", bucket_name);
-        self.get_client().bucket_delete(bucket_name).await
+        observe_client("os", "bucket_delete", self.get_client().bucket_delete(bucket_name)).await
     }
 
     pub async fn object_create(&self, path: &str, content: &[u8], content_type: Option<&str>, bucket_name: Option<&str>) -> TardisResult<()> {
         trace!("[Tardis.OSClient] Creating object {}", path);
-        self.get_client().object_create(path, content, content_type, bucket_name).await
+        observe_client("os", "object_create", self.get_client().object_create(path, content, content_type, None, bucket_name)).await
+    }
+
+    /// Create the object with the user-defined metadata / 创建带用户自定义元数据的对象
+    pub async fn object_create_with_meta(
+        &self,
+        path: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+        metadata: &HashMap<String, String>,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Creating object {} with metadata", path);
+        observe_client(
+            "os",
+            "object_create",
+            self.get_client().object_create(path, content, content_type, Some(metadata), bucket_name),
+        )
+        .await
     }
 
     pub async fn object_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<Vec<u8>> {
         trace!("[Tardis.OSClient] Getting object {}", path);
-        self.get_client().object_get(path, bucket_name).await
+        observe_client("os", "object_get", self.get_client().object_get(path, bucket_name)).await
     }
 
     pub async fn object_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
         trace!("[Tardis.OSClient] Deleting object {}", path);
-        self.get_client().object_delete(path, bucket_name).await
+        observe_client("os", "object_delete", self.get_client().object_delete(path, bucket_name)).await
     }
 
+    /// Presigned url to create the object by `PUT` / 通过 `PUT` 创建对象的预签名url
+    ///
+    /// The `presign_expire_sec` of the config is used if `expire_sec` is 0.
+    ///
+    /// `expire_sec` 为0时使用配置的 `presign_expire_sec` .
     pub fn object_create_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
         trace!("[Tardis.OSClient] Creating object url {}", path);
-        self.get_client().object_create_url(path, expire_sec, bucket_name)
+        self.get_client().object_create_url(path, self.presign_expire_sec(expire_sec), bucket_name)
     }
 
+    /// Presigned url to get the object by `GET` / 通过 `GET` 获取对象的预签名url
     pub fn object_get_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
         trace!("[Tardis.OSClient] Getting object url {}", path);
-        self.get_client().object_get_url(path, expire_sec, bucket_name)
+        self.get_client().object_get_url(path, self.presign_expire_sec(expire_sec), bucket_name)
     }
 
+    /// Presigned url to delete the object by `DELETE` / 通过 `DELETE` 删除对象的预签名url
     pub fn object_delete_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
         trace!("[Tardis.OSClient] Deleting object url {}", path);
-        self.get_client().object_delete_url(path, expire_sec, bucket_name)
+        self.get_client().object_delete_url(path, self.presign_expire_sec(expire_sec), bucket_name)
+    }
+
+    fn presign_expire_sec(&self, expire_sec: u32) -> u32 {
+        if expire_sec == 0 {
+            self.presign_expire_sec
+        } else {
+            expire_sec
+        }
+    }
+
+    /// Get the metadata of the object / 获取对象的元数据
+    pub async fn object_head(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectMeta> {
+        trace!("[Tardis.OSClient] Getting object metadata {}", path);
+        observe_client("os", "object_head", self.get_client().object_head(path, bucket_name)).await
+    }
+
+    pub async fn object_tags_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<HashMap<String, String>> {
+        trace!("[Tardis.OSClient] Getting object tags {}", path);
+        observe_client("os", "object_tags_get", self.get_client().object_tags_get(path, bucket_name)).await
+    }
+
+    /// Replace the tags of the object / 替换对象的标签
+    pub async fn object_tags_put(&self, path: &str, tags: &HashMap<String, String>, bucket_name: Option<&str>) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Putting object tags {}", path);
+        observe_client("os", "object_tags_put", self.get_client().object_tags_put(path, tags, bucket_name)).await
+    }
+
+    pub async fn object_tags_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Deleting object tags {}", path);
+        observe_client("os", "object_tags_delete", self.get_client().object_tags_delete(path, bucket_name)).await
+    }
+
+    /// List the objects with the prefix by pages / 分页列举具有该前缀的对象
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut continuation_token = None;
+    /// loop {
+    ///     let page = TardisFuns::os().list_objects("tmp/", continuation_token.as_deref(), None).await?;
+    ///     // process page.objects
+    ///     continuation_token = page.next_continuation_token;
+    ///     if continuation_token.is_none() {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub async fn list_objects(&self, prefix: &str, continuation_token: Option<&str>, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectPage> {
+        trace!("[Tardis.OSClient] Listing objects {}", prefix);
+        observe_client("os", "list_objects", self.get_client().object_list(prefix, continuation_token, bucket_name)).await
+    }
+
+    /// Copy the object by the service without downloading it / 由服务端复制对象，无需下载
+    pub async fn copy_object(&self, from: &str, to: &str, from_bucket_name: Option<&str>, to_bucket_name: Option<&str>) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Copying object {} to {}", from, to);
+        observe_client("os", "copy_object", self.get_client().object_copy(from, to, from_bucket_name, to_bucket_name)).await
+    }
+
+    /// Delete the objects in batches, returns the paths failed to delete with the errors
+    /// / 批量删除对象，返回删除失败的路径及错误
+    pub async fn delete_objects(&self, paths: &[&str], bucket_name: Option<&str>) -> TardisResult<Vec<(String, TardisError)>> {
+        trace!("[Tardis.OSClient] Deleting {} objects", paths.len());
+        observe_client("os", "delete_objects", self.get_client().object_delete_batch(paths, bucket_name)).await
+    }
+
+    /// Get the lifecycle rules of the bucket, empty if not configured / 获取桶的生命周期规则，未配置时为空
+    pub async fn bucket_lifecycle_get(&self, bucket_name: Option<&str>) -> TardisResult<Vec<TardisOSLifecycleRule>> {
+        trace!("[Tardis.OSClient] Getting bucket lifecycle");
+        observe_client("os", "bucket_lifecycle_get", self.get_client().bucket_lifecycle_get(bucket_name)).await
+    }
+
+    /// Replace the lifecycle rules of the bucket / 替换桶的生命周期规则
+    pub async fn bucket_lifecycle_put(&self, rules: &[TardisOSLifecycleRule], bucket_name: Option<&str>) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Putting bucket lifecycle");
+        observe_client("os", "bucket_lifecycle_put", self.get_client().bucket_lifecycle_put(rules, bucket_name)).await
+    }
+
+    pub async fn bucket_lifecycle_delete(&self, bucket_name: Option<&str>) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Deleting bucket lifecycle");
+        observe_client("os", "bucket_lifecycle_delete", self.get_client().bucket_lifecycle_delete(bucket_name)).await
+    }
+
+    /// Create the large object from the file by the multipart upload / 通过分片上传从文件创建大对象
+    ///
+    /// The upload is aborted on failure, use the `object_multipart_*` steps to resume the failed uploads instead.
+    ///
+    /// 失败时中止上传，如需恢复失败的上传请改用 `object_multipart_*` 步骤.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// // resumable upload
+    /// let mut upload = TardisFuns::os().object_multipart_init("videos/1.mp4", Some("video/mp4"), None).await?;
+    /// while let Err(error) = TardisFuns::os().object_multipart_upload_file(&mut upload, "/data/1.mp4").await {
+    ///     // the uploaded parts are kept in the state, persist it to resume after a restart
+    ///     warn!("upload error: {error}");
+    /// }
+    /// TardisFuns::os().object_multipart_complete(&upload).await?;
+    /// ```
+    pub async fn object_create_multipart(&self, path: &str, file_path: impl AsRef<Path>, content_type: Option<&str>, bucket_name: Option<&str>) -> TardisResult<()> {
+        let mut upload = self.object_multipart_init(path, content_type, bucket_name).await?;
+        let result = match self.object_multipart_upload_file(&mut upload, file_path).await {
+            Ok(_) => self.object_multipart_complete(&upload).await,
+            Err(error) => Err(error),
+        };
+        if result.is_err() {
+            if let Err(error) = self.object_multipart_abort(&upload).await {
+                warn!("[Tardis.OSClient] Failed to abort the multipart upload {} of {} | {error}", upload.upload_id, path);
+            }
+        }
+        result
+    }
+
+    /// Start the multipart upload / 开始分片上传
+    pub async fn object_multipart_init(&self, path: &str, content_type: Option<&str>, bucket_name: Option<&str>) -> TardisResult<TardisOSMultipartUpload> {
+        trace!("[Tardis.OSClient] Initiating multipart upload {}", path);
+        let content_type = content_type.unwrap_or("application/octet-stream");
+        let upload_id = observe_client("os", "object_multipart_init", self.get_client().object_multipart_init(path, content_type, bucket_name)).await?;
+        Ok(TardisOSMultipartUpload {
+            path: path.to_string(),
+            bucket_name: bucket_name.map(str::to_string),
+            upload_id,
+            content_type: content_type.to_string(),
+            part_size: self.multipart_part_size,
+            parts: Vec::new(),
+        })
+    }
+
+    /// Upload the parts of the file not uploaded yet in parallel / 并行上传文件中尚未上传的分片
+    ///
+    /// The uploaded parts are recorded in the state even if the others fail, so calling it again resumes the upload.
+    ///
+    /// 即使其它分片失败，已上传的分片也会记录在状态中，因此再次调用即可恢复上传.
+    pub async fn object_multipart_upload_file(&self, upload: &mut TardisOSMultipartUpload, file_path: impl AsRef<Path>) -> TardisResult<()> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let file_size = std::fs::metadata(&file_path)?.len();
+        let part_size = upload.part_size.max(1) as u64;
+        let part_count = ((file_size + part_size - 1) / part_size).max(1) as u32;
+        let pending = (1..=part_count).filter(|part_number| upload.parts.iter().all(|part| part.part_number != *part_number)).collect::<Vec<_>>();
+        trace!("[Tardis.OSClient] Uploading {} of {} parts of {}", pending.len(), part_count, upload.path);
+        let state = &*upload;
+        let results = futures_util::stream::iter(pending)
+            .map(|part_number| {
+                let file_path = file_path.clone();
+                async move {
+                    let offset = (part_number - 1) as u64 * part_size;
+                    let content = tokio::task::spawn_blocking(move || -> TardisResult<Vec<u8>> {
+                        let mut file = std::fs::File::open(file_path)?;
+                        file.seek(SeekFrom::Start(offset))?;
+                        let mut content = vec![0; part_size.min(file_size - offset) as usize];
+                        file.read_exact(&mut content)?;
+                        Ok(content)
+                    })
+                    .await
+                    .map_err(|error| {
+                        TardisError::internal_error(
+                            &format!("[Tardis.OSClient] Failed to read the part {part_number}: {error}"),
+                            "500-tardis-os-read-part-error",
+                        )
+                    })??;
+                    let etag = observe_client(
+                        "os",
+                        "object_multipart_put_part",
+                        self.get_client().object_multipart_put_part(&state.path, &state.upload_id, part_number, content, &state.content_type, state.bucket_name.as_deref()),
+                    )
+                    .await?;
+                    Ok(TardisOSUploadedPart { part_number, etag })
+                }
+            })
+            .buffer_unordered(self.multipart_concurrency)
+            .collect::<Vec<TardisResult<TardisOSUploadedPart>>>()
+            .await;
+        let mut first_error = None;
+        for result in results {
+            match result {
+                Ok(part) => upload.parts.push(part),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        upload.parts.sort_by_key(|part| part.part_number);
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Complete the multipart upload with the uploaded parts / 使用已上传的分片完成分片上传
+    pub async fn object_multipart_complete(&self, upload: &TardisOSMultipartUpload) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Completing multipart upload {}", upload.path);
+        observe_client(
+            "os",
+            "object_multipart_complete",
+            self.get_client().object_multipart_complete(&upload.path, &upload.upload_id, &upload.parts, &upload.content_type, upload.bucket_name.as_deref()),
+        )
+        .await
+    }
+
+    /// Abort the multipart upload and discard the uploaded parts / 中止分片上传并丢弃已上传的分片
+    pub async fn object_multipart_abort(&self, upload: &TardisOSMultipartUpload) -> TardisResult<()> {
+        trace!("[Tardis.OSClient] Aborting multipart upload {}", upload.path);
+        observe_client(
+            "os",
+            "object_multipart_abort",
+            self.get_client().object_multipart_abort(&upload.path, &upload.upload_id, upload.bucket_name.as_deref()),
+        )
+        .await
     }
 }
 
+/// Operations of the object storage backends / 对象存储后端的操作
 #[async_trait]
-trait TardisOSOperations {
+pub(crate) trait TardisOSOperations {
+    async fn health_check(&self) -> TardisResult<()>;
+
     async fn bucket_create_simple(&self, bucket_name: &str, is_private: bool) -> TardisResult<()>;
 
     async fn bucket_delete(&self, bucket_name: &str) -> TardisResult<()>;
 
-    async fn object_create(&self, path: &str, content: &[u8], content_type: Option<&str>, bucket_name: Option<&str>) -> TardisResult<()>;
+    async fn object_create(
+        &self,
+        path: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+        metadata: Option<&HashMap<String, String>>,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<()>;
 
     async fn object_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<Vec<u8>>;
 
@@ -134,10 +474,66 @@ trait TardisOSOperations {
     fn object_get_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String>;
 
     fn object_delete_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String>;
+
+    async fn object_head(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectMeta>;
+
+    async fn object_tags_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<HashMap<String, String>>;
+
+    async fn object_tags_put(&self, path: &str, tags: &HashMap<String, String>, bucket_name: Option<&str>) -> TardisResult<()>;
+
+    async fn object_tags_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()>;
+
+    async fn object_list(&self, prefix: &str, continuation_token: Option<&str>, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectPage>;
+
+    async fn object_copy(&self, from: &str, to: &str, from_bucket_name: Option<&str>, to_bucket_name: Option<&str>) -> TardisResult<()>;
+
+    /// Returns the paths failed to delete with the errors, deletes the objects one by one by default
+    /// / 返回删除失败的路径及错误，默认逐个删除对象
+    async fn object_delete_batch(&self, paths: &[&str], bucket_name: Option<&str>) -> TardisResult<Vec<(String, TardisError)>> {
+        let mut failed = Vec::new();
+        for path in paths {
+            if let Err(error) = self.object_delete(path, bucket_name).await {
+                failed.push((path.to_string(), error));
+            }
+        }
+        Ok(failed)
+    }
+
+    async fn bucket_lifecycle_get(&self, bucket_name: Option<&str>) -> TardisResult<Vec<TardisOSLifecycleRule>>;
+
+    async fn bucket_lifecycle_put(&self, rules: &[TardisOSLifecycleRule], bucket_name: Option<&str>) -> TardisResult<()>;
+
+    async fn bucket_lifecycle_delete(&self, bucket_name: Option<&str>) -> TardisResult<()>;
+
+    /// Returns the upload id / 返回上传id
+    async fn object_multipart_init(&self, path: &str, content_type: &str, bucket_name: Option<&str>) -> TardisResult<String>;
+
+    /// Returns the etag of the part / 返回分片的etag
+    async fn object_multipart_put_part(
+        &self,
+        path: &str,
+        upload_id: &str,
+        part_number: u32,
+        content: Vec<u8>,
+        content_type: &str,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<String>;
+
+    async fn object_multipart_complete(&self, path: &str, upload_id: &str, parts: &[TardisOSUploadedPart], content_type: &str, bucket_name: Option<&str>) -> TardisResult<()>;
+
+    async fn object_multipart_abort(&self, path: &str, upload_id: &str, bucket_name: Option<&str>) -> TardisResult<()>;
 }
 
 #[async_trait]
 impl TardisOSOperations for TardisOSS3Client {
+    async fn health_check(&self) -> TardisResult<()> {
+        let Some(bucket) = &self.default_bucket else {
+            return Ok(());
+        };
+        bucket.list_page(String::new(), None, None, None, Some(1)).await?;
+        Ok(())
+    }
+
     async fn bucket_create_simple(&self, bucket_name: &str, is_private: bool) -> TardisResult<()> {
         let resp = Bucket::create_with_path_style(
             bucket_name,
@@ -174,8 +570,18 @@ impl TardisOSOperations for TardisOSS3Client {
         }
     }
 
-    async fn object_create(&self, path: &str, content: &[u8], content_type: Option<&str>, bucket_name: Option<&str>) -> TardisResult<()> {
-        let bucket = self.get_bucket(bucket_name)?;
+    async fn object_create(
+        &self,
+        path: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+        metadata: Option<&HashMap<String, String>>,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<()> {
+        let mut bucket = self.get_bucket(bucket_name)?;
+        for (key, value) in metadata.into_iter().flatten() {
+            bucket.add_header(&format!("x-amz-meta-{key}"), value);
+        }
         let response_data = if let Some(content_type) = content_type {
             bucket.put_object_with_content_type(path, content, content_type).await?
         } else {
@@ -246,9 +652,313 @@ impl TardisOSOperations for TardisOSS3Client {
     fn object_delete_url(&self, path: &str, expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
         Ok(self.get_bucket(bucket_name)?.presign_delete(path, expire_sec)?)
     }
+
+    async fn object_head(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectMeta> {
+        let (head, _) = self.get_bucket(bucket_name)?.head_object(path).await?;
+        Ok(TardisOSObjectMeta {
+            content_length: head.content_length.map(|content_length| content_length as u64),
+            content_type: head.content_type,
+            etag: head.e_tag,
+            last_modified: head.last_modified,
+            metadata: head.metadata.unwrap_or_default(),
+        })
+    }
+
+    async fn object_tags_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<HashMap<String, String>> {
+        let (tags, _) = self.get_bucket(bucket_name)?.get_object_tagging(path).await?;
+        Ok(tags.into_iter().map(|tag| (tag.key(), tag.value())).collect())
+    }
+
+    async fn object_tags_put(&self, path: &str, tags: &HashMap<String, String>, bucket_name: Option<&str>) -> TardisResult<()> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let tags = tags.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect::<Vec<_>>();
+        let response_data = bucket.put_object_tagging(path, &tags).await?;
+        Self::check_response(
+            &bucket,
+            path,
+            response_data.status_code(),
+            response_data.bytes(),
+            "put object tags",
+            "-1-tardis-os-put-tags-error",
+        )
+    }
+
+    async fn object_tags_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let response_data = bucket.delete_object_tagging(path).await?;
+        Self::check_response(
+            &bucket,
+            path,
+            response_data.status_code(),
+            response_data.bytes(),
+            "delete object tags",
+            "-1-tardis-os-delete-tags-error",
+        )
+    }
+
+    async fn object_list(&self, prefix: &str, continuation_token: Option<&str>, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectPage> {
+        let (result, _) = self.get_bucket(bucket_name)?.list_page(prefix.to_string(), None, continuation_token.map(str::to_string), None, None).await?;
+        Ok(TardisOSObjectPage {
+            objects: result
+                .contents
+                .into_iter()
+                .map(|object| TardisOSObject {
+                    path: object.key,
+                    size: object.size,
+                    etag: object.e_tag,
+                    last_modified: Some(object.last_modified),
+                })
+                .collect(),
+            next_continuation_token: if result.is_truncated { result.next_continuation_token } else { None },
+        })
+    }
+
+    async fn object_copy(&self, from: &str, to: &str, from_bucket_name: Option<&str>, to_bucket_name: Option<&str>) -> TardisResult<()> {
+        let from_bucket = self.get_bucket(from_bucket_name)?;
+        let to_bucket = self.get_bucket(to_bucket_name)?;
+        let source = format!("/{}/{}", from_bucket.name, uri_encode(from.trim_start_matches('/')));
+        let (status_code, body) = self.send_signed(reqwest::Method::PUT, &to_bucket.name, Some(to), None, vec![("x-amz-copy-source", source)], Vec::new()).await?;
+        // the copy may fail with the status code 200
+        let status_code = if String::from_utf8_lossy(&body).contains("<Error>") { 500 } else { status_code };
+        Self::check_response(&to_bucket, to, status_code, &body, "copy object to", "-1-tardis-os-copy-object-error")
+    }
+
+    async fn object_delete_batch(&self, paths: &[&str], bucket_name: Option<&str>) -> TardisResult<Vec<(String, TardisError)>> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let mut failed = Vec::new();
+        // at most 1000 objects per request
+        for paths in paths.chunks(1000) {
+            let objects = paths.iter().map(|path| format!("<Object><Key>{}</Key></Object>", xml_escape(path))).collect::<String>();
+            let (status_code, body) = self
+                .send_signed(
+                    reqwest::Method::POST,
+                    &bucket.name,
+                    None,
+                    Some("delete"),
+                    vec![("content-type", "application/xml".to_string())],
+                    format!("<Delete><Quiet>true</Quiet>{objects}</Delete>").into_bytes(),
+                )
+                .await?;
+            Self::check_response(&bucket, "", status_code, &body, "delete objects of", "-1-tardis-os-delete-objects-error")?;
+            for error in String::from_utf8_lossy(&body).split("<Error>").skip(1) {
+                let path = xml_text(error, "Key").unwrap_or_default();
+                let message = format!(
+                    "[Tardis.OSClient] Failed to delete object {}:{} with error [{}]{}",
+                    bucket.name,
+                    path,
+                    xml_text(error, "Code").unwrap_or_default(),
+                    xml_text(error, "Message").unwrap_or_default()
+                );
+                failed.push((path, TardisError::custom("500", &message, "-1-tardis-os-delete-object-error")));
+            }
+        }
+        Ok(failed)
+    }
+
+    async fn bucket_lifecycle_get(&self, bucket_name: Option<&str>) -> TardisResult<Vec<TardisOSLifecycleRule>> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let (status_code, body) = self.send_signed(reqwest::Method::GET, &bucket.name, None, Some("lifecycle"), vec![], Vec::new()).await?;
+        if status_code == 404 {
+            return Ok(Vec::new());
+        }
+        Self::check_response(&bucket, "", status_code, &body, "get lifecycle of", "-1-tardis-os-get-lifecycle-error")?;
+        let days = |rule: &str, element: &str, field: &str| xml_text(rule, element).and_then(|element| xml_text(&element, field)).and_then(|days| days.parse().ok());
+        Ok(String::from_utf8_lossy(&body)
+            .split("<Rule>")
+            .skip(1)
+            .map(|rule| TardisOSLifecycleRule {
+                id: xml_text(rule, "ID").unwrap_or_default(),
+                prefix: xml_text(rule, "Prefix").unwrap_or_default(),
+                enabled: xml_text(rule, "Status").as_deref() == Some("Enabled"),
+                expiration_days: days(rule, "Expiration", "Days"),
+                transition_days: days(rule, "Transition", "Days"),
+                transition_storage_class: xml_text(rule, "Transition").and_then(|transition| xml_text(&transition, "StorageClass")),
+                abort_incomplete_multipart_days: days(rule, "AbortIncompleteMultipartUpload", "DaysAfterInitiation"),
+            })
+            .collect())
+    }
+
+    async fn bucket_lifecycle_put(&self, rules: &[TardisOSLifecycleRule], bucket_name: Option<&str>) -> TardisResult<()> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let mut xml = format!(
+                    "<Rule><ID>{}</ID><Filter><Prefix>{}</Prefix></Filter><Status>{}</Status>",
+                    xml_escape(&rule.id),
+                    xml_escape(&rule.prefix),
+                    if rule.enabled { "Enabled" } else { "Disabled" }
+                );
+                if let Some(days) = rule.expiration_days {
+                    xml.push_str(&format!("<Expiration><Days>{days}</Days></Expiration>"));
+                }
+                if let (Some(days), Some(storage_class)) = (rule.transition_days, &rule.transition_storage_class) {
+                    xml.push_str(&format!(
+                        "<Transition><Days>{days}</Days><StorageClass>{}</StorageClass></Transition>",
+                        xml_escape(storage_class)
+                    ));
+                }
+                if let Some(days) = rule.abort_incomplete_multipart_days {
+                    xml.push_str(&format!(
+                        "<AbortIncompleteMultipartUpload><DaysAfterInitiation>{days}</DaysAfterInitiation></AbortIncompleteMultipartUpload>"
+                    ));
+                }
+                xml.push_str("</Rule>");
+                xml
+            })
+            .collect::<String>();
+        let (status_code, body) = self
+            .send_signed(
+                reqwest::Method::PUT,
+                &bucket.name,
+                None,
+                Some("lifecycle"),
+                vec![("content-type", "application/xml".to_string())],
+                format!("<LifecycleConfiguration>{rules}</LifecycleConfiguration>").into_bytes(),
+            )
+            .await?;
+        Self::check_response(&bucket, "", status_code, &body, "put lifecycle of", "-1-tardis-os-put-lifecycle-error")
+    }
+
+    async fn bucket_lifecycle_delete(&self, bucket_name: Option<&str>) -> TardisResult<()> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let (status_code, body) = self.send_signed(reqwest::Method::DELETE, &bucket.name, None, Some("lifecycle"), vec![], Vec::new()).await?;
+        Self::check_response(&bucket, "", status_code, &body, "delete lifecycle of", "-1-tardis-os-delete-lifecycle-error")
+    }
+
+    async fn object_multipart_init(&self, path: &str, content_type: &str, bucket_name: Option<&str>) -> TardisResult<String> {
+        Ok(self.get_bucket(bucket_name)?.initiate_multipart_upload(path, content_type).await?.upload_id)
+    }
+
+    async fn object_multipart_put_part(
+        &self,
+        path: &str,
+        upload_id: &str,
+        part_number: u32,
+        content: Vec<u8>,
+        content_type: &str,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<String> {
+        Ok(self.get_bucket(bucket_name)?.put_multipart_chunk(content, path, part_number, upload_id, content_type).await?.etag)
+    }
+
+    async fn object_multipart_complete(&self, path: &str, upload_id: &str, parts: &[TardisOSUploadedPart], _content_type: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        let bucket = self.get_bucket(bucket_name)?;
+        let parts = parts
+            .iter()
+            .map(|part| Part {
+                part_number: part.part_number,
+                etag: part.etag.clone(),
+            })
+            .collect();
+        let response_data = bucket.complete_multipart_upload(path, upload_id, parts).await?;
+        Self::check_response(
+            &bucket,
+            path,
+            response_data.status_code(),
+            response_data.bytes(),
+            "complete multipart upload",
+            "-1-tardis-os-complete-multipart-error",
+        )
+    }
+
+    async fn object_multipart_abort(&self, path: &str, upload_id: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        self.get_bucket(bucket_name)?.abort_upload(path, upload_id).await?;
+        Ok(())
+    }
 }
 
 impl TardisOSS3Client {
+    /// Send the request signed by the AWS Signature Version 4, for the operations not provided by the S3 client
+    /// / 发送使用AWS签名V4签名的请求，用于S3客户端未提供的操作
+    ///
+    /// Returns the status code and the body.
+    ///
+    /// 返回状态码及响应体.
+    async fn send_signed(
+        &self,
+        method: reqwest::Method,
+        bucket_name: &str,
+        path: Option<&str>,
+        sub_resource: Option<&str>,
+        extra_headers: Vec<(&str, String)>,
+        body: Vec<u8>,
+    ) -> TardisResult<(u16, Vec<u8>)> {
+        let endpoint = self.region.endpoint();
+        let endpoint = endpoint.trim_end_matches('/');
+        let uri = match path {
+            Some(path) => format!("/{bucket_name}/{}", uri_encode(path.trim_start_matches('/'))),
+            None => format!("/{bucket_name}"),
+        };
+        let url = Url::parse(&format!("{endpoint}{uri}"))
+            .map_err(|error| TardisError::format_error(&format!("[Tardis.OSClient] Invalid endpoint {endpoint}: {error}"), "406-tardis-os-endpoint-error"))?;
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let region = self.region.to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let mut headers = extra_headers.into_iter().map(|(key, value)| (key.to_string(), value)).collect::<Vec<_>>();
+        headers.push(("content-md5".to_string(), STANDARD.encode(Md5::digest(&body))));
+        headers.push(("host".to_string(), url[Position::BeforeHost..Position::AfterPort].to_string()));
+        headers.push(("x-amz-content-sha256".to_string(), payload_hash.clone()));
+        headers.push(("x-amz-date".to_string(), amz_date.clone()));
+        if let Some(token) = self.credentials.security_token.as_ref().or(self.credentials.session_token.as_ref()) {
+            headers.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        headers.sort();
+        let signed_headers = headers.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>().join(";");
+        let canonical_headers = headers.iter().map(|(key, value)| format!("{key}:{}\n", value.trim())).collect::<String>();
+        let canonical_request = format!(
+            "{}\n{uri}\n{}\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+            method.as_str(),
+            sub_resource.map(|sub_resource| format!("{sub_resource}=")).unwrap_or_default()
+        );
+        let scope = format!("{date}/{region}/s3/aws4_request");
+        let string_to_sign = format!("AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}", hex::encode(Sha256::digest(canonical_request.as_bytes())));
+        let mut key = format!("AWS4{}", self.credentials.secret_key.as_deref().unwrap_or_default()).into_bytes();
+        for part in [date.as_str(), region.as_str(), "s3", "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes())?;
+        }
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes())?);
+        let url = match sub_resource {
+            Some(sub_resource) => format!("{url}?{sub_resource}"),
+            None => url.to_string(),
+        };
+        let mut request = self.http_client.request(method, url).header(
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                self.credentials.access_key.as_deref().unwrap_or_default()
+            ),
+        );
+        for (key, value) in headers.iter().filter(|(key, _)| key != "host") {
+            request = request.header(key, value);
+        }
+        let response =
+            request.body(body).send().await.map_err(|error| TardisError::custom(ERROR_DEFAULT_CODE, &format!("[Tardis.OSClient] Error: {error}"), "-1-tardis-os-error"))?;
+        let status_code = response.status().as_u16();
+        let body = response.bytes().await.map_err(|error| TardisError::custom(ERROR_DEFAULT_CODE, &format!("[Tardis.OSClient] Error: {error}"), "-1-tardis-os-error"))?;
+        Ok((status_code, body.to_vec()))
+    }
+
+    fn check_response(bucket: &Bucket, path: &str, status_code: u16, body: &[u8], operation: &str, locale_code: &str) -> TardisResult<()> {
+        if (200..300).contains(&status_code) {
+            Ok(())
+        } else {
+            Err(TardisError::custom(
+                &status_code.to_string(),
+                &format!(
+                    "[Tardis.OSClient] Failed to {} {}:{} with error [{}]",
+                    operation,
+                    bucket.name,
+                    path,
+                    String::from_utf8_lossy(body)
+                ),
+                locale_code,
+            ))
+        }
+    }
+
     fn get_bucket(&self, bucket_name: Option<&str>) -> TardisResult<Bucket> {
         if let Some(bucket_name) = bucket_name {
             Ok(Bucket::new(bucket_name, self.region.clone(), self.credentials.clone())?.with_path_style())
@@ -269,3 +979,30 @@ impl From<s3::error::S3Error> for TardisError {
         }
     }
 }
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> TardisResult<Vec<u8>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|error| TardisError::format_error(&format!("[Tardis.OSClient] Invalid key: {error}"), "406-tardis-os-key-error"))?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// Percent-encode the path except the unreserved characters and `/` / 对路径进行百分号编码，保留非保留字符及 `/`
+fn uri_encode(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+/// The text of the first element, without parsing the whole document / 第一个该元素的文本，不解析整个文档
+pub(crate) fn xml_text(xml: &str, element: &str) -> Option<String> {
+    let start = xml.find(&format!("<{element}>"))? + element.len() + 2;
+    let end = start + xml[start..].find(&format!("</{element}>"))?;
+    Some(xml[start..end].replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&"))
+}
@@ -0,0 +1,422 @@
+//! Local filesystem backend of the OS client / OS客户端的本地文件系统后端
+//!
+//! The backend of [`TardisOSClient`](crate::os::os_client::TardisOSClient) when the `kind` is `fs`, for the development and the tests.
+//! The `endpoint` is the root directory, e.g. `fs:///var/tardis/os` or `data/os`, and the buckets are the directories in it:
+//!
+//! 当 `kind` 为 `fs` 时 [`TardisOSClient`](crate::os::os_client::TardisOSClient) 使用的后端，用于开发及测试.
+//! `endpoint` 为根目录，如 `fs:///var/tardis/os` 或 `data/os` ，桶即为其中的目录：
+//!
+//! * `{root}/{bucket}/{path}`: content of the object / 对象的内容
+//! * `{root}/.tardis/meta/{bucket}/{path}.json`: content type, user-defined metadata and tags of the object / 对象的内容类型、用户自定义元数据及标签
+//! * `{root}/.tardis/multipart/{upload_id}/{part_number}`: parts of the multipart upload / 分片上传的分片
+//!
+//! The presigned urls are the `file://` urls of the objects without any expiry, since there is no server to verify them.
+//! Likewise, the lifecycle rules are stored in `{root}/.tardis/lifecycle/{bucket}.json` but not applied.
+//!
+//! 由于没有服务端校验，预签名url为对象的 `file://` url，没有有效期.
+//! 同样地，生命周期规则存储于 `{root}/.tardis/lifecycle/{bucket}.json` ，但不会被执行.
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::os::os_client::{TardisOSLifecycleRule, TardisOSObject, TardisOSObjectMeta, TardisOSObjectPage, TardisOSOperations, TardisOSUploadedPart};
+use crate::TardisFuns;
+
+const INTERNAL_DIR: &str = ".tardis";
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+const LIST_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct FsObjectMeta {
+    content_type: Option<String>,
+    metadata: HashMap<String, String>,
+    tags: HashMap<String, String>,
+}
+
+pub(crate) struct TardisOSFsClient {
+    root: PathBuf,
+    default_bucket: Option<String>,
+}
+
+impl TardisOSFsClient {
+    pub(crate) fn init(endpoint: &str, default_bucket: &str) -> TardisResult<Self> {
+        let root = endpoint.strip_prefix("fs://").unwrap_or(endpoint);
+        if root.is_empty() {
+            return Err(TardisError::format_error(
+                "[Tardis.OSClient] The fs kind requires the root directory as the endpoint",
+                "406-tardis-os-fs-root-error",
+            ));
+        }
+        let root = PathBuf::from(root);
+        let root = if root.is_absolute() { root } else { std::env::current_dir()?.join(root) };
+        let default_bucket = if default_bucket.is_empty() { None } else { Some(default_bucket.to_string()) };
+        if let Some(bucket_name) = &default_bucket {
+            Self::check_bucket_name(bucket_name)?;
+        }
+        Ok(TardisOSFsClient { root, default_bucket })
+    }
+
+    fn check_bucket_name(bucket_name: &str) -> TardisResult<()> {
+        if bucket_name.is_empty() || bucket_name.starts_with('.') || bucket_name.contains(['/', '\\']) {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.OSClient] Invalid bucket name {bucket_name}"),
+                "400-tardis-os-path-error",
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_path(path: &str) -> TardisResult<()> {
+        if path.is_empty() || !Path::new(path).components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.OSClient] Invalid object path {path}"),
+                "400-tardis-os-path-error",
+            ));
+        }
+        Ok(())
+    }
+
+    fn get_bucket<'a>(&'a self, bucket_name: Option<&'a str>) -> TardisResult<&'a str> {
+        let bucket_name = bucket_name
+            .or(self.default_bucket.as_deref())
+            .ok_or_else(|| TardisError::not_found("[Tardis.OSClient] No default bucket configured", "404-tardis-os-default-bucket-not-exist"))?;
+        Self::check_bucket_name(bucket_name)?;
+        Ok(bucket_name)
+    }
+
+    /// The paths of the object and its metadata / 对象及其元数据的路径
+    fn object_paths(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<(PathBuf, PathBuf)> {
+        let bucket_name = self.get_bucket(bucket_name)?;
+        Self::check_path(path)?;
+        Ok((
+            self.root.join(bucket_name).join(path),
+            self.root.join(INTERNAL_DIR).join("meta").join(bucket_name).join(format!("{path}.json")),
+        ))
+    }
+
+    fn multipart_dir(&self, upload_id: &str) -> TardisResult<PathBuf> {
+        if upload_id.is_empty() || !upload_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(TardisError::bad_request(
+                &format!("[Tardis.OSClient] Invalid upload id {upload_id}"),
+                "400-tardis-os-path-error",
+            ));
+        }
+        Ok(self.root.join(INTERNAL_DIR).join("multipart").join(upload_id))
+    }
+
+    fn io_error(error: std::io::Error, operation: &str, target: &str) -> TardisError {
+        match error.kind() {
+            ErrorKind::NotFound => TardisError::not_found(&format!("[Tardis.OSClient] Failed to {operation} {target}, not exist"), "404-tardis-os-not-exist"),
+            ErrorKind::AlreadyExists => TardisError::conflict(&format!("[Tardis.OSClient] Failed to {operation} {target}, already exists"), "409-tardis-os-conflict"),
+            _ => TardisError::internal_error(&format!("[Tardis.OSClient] Failed to {operation} {target} with error [{error}]"), "500-tardis-os-fs-error"),
+        }
+    }
+
+    async fn write_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, content).await
+    }
+
+    async fn read_meta(meta_path: &Path) -> TardisResult<FsObjectMeta> {
+        match tokio::fs::read_to_string(meta_path).await {
+            Ok(meta) => TardisFuns::json.str_to_obj(&meta),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(FsObjectMeta::default()),
+            Err(error) => Err(Self::io_error(error, "read metadata", &meta_path.to_string_lossy())),
+        }
+    }
+
+    async fn write_meta(meta_path: &Path, meta: &FsObjectMeta) -> TardisResult<()> {
+        Self::write_file(meta_path, TardisFuns::json.obj_to_string(meta)?.as_bytes()).await.map_err(|error| Self::io_error(error, "write metadata", &meta_path.to_string_lossy()))
+    }
+
+    /// Update the metadata of the existing object / 更新已存在对象的元数据
+    async fn update_meta(&self, path: &str, bucket_name: Option<&str>, update: impl FnOnce(&mut FsObjectMeta)) -> TardisResult<()> {
+        let (object_path, meta_path) = self.object_paths(path, bucket_name)?;
+        tokio::fs::metadata(&object_path).await.map_err(|error| Self::io_error(error, "get object", path))?;
+        let mut meta = Self::read_meta(&meta_path).await?;
+        update(&mut meta);
+        Self::write_meta(&meta_path, &meta).await
+    }
+
+    /// The etag and the last modified time of the file / 文件的etag及最后修改时间
+    fn file_version(file_meta: &std::fs::Metadata) -> (Option<String>, Option<String>) {
+        let modified = file_meta.modified().ok();
+        (
+            modified.and_then(|modified| modified.duration_since(UNIX_EPOCH).ok()).map(|modified| format!("\"{:x}-{:x}\"", file_meta.len(), modified.as_nanos())),
+            modified.map(|modified| DateTime::<Utc>::from(modified).format("%a, %d %b %Y %H:%M:%S GMT").to_string()),
+        )
+    }
+
+    /// List the files in the directory recursively / 递归列举目录中的文件
+    fn walk(dir: &Path, relative_dir: &str, objects: &mut Vec<TardisOSObject>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let relative_path = format!("{relative_dir}{}", entry.file_name().to_string_lossy());
+            let file_meta = entry.metadata()?;
+            if file_meta.is_dir() {
+                Self::walk(&entry.path(), &format!("{relative_path}/"), objects)?;
+            } else {
+                let (etag, last_modified) = Self::file_version(&file_meta);
+                objects.push(TardisOSObject {
+                    path: relative_path,
+                    size: file_meta.len(),
+                    etag,
+                    last_modified,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn lifecycle_path(&self, bucket_name: Option<&str>) -> TardisResult<PathBuf> {
+        Ok(self.root.join(INTERNAL_DIR).join("lifecycle").join(format!("{}.json", self.get_bucket(bucket_name)?)))
+    }
+
+    fn object_url(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<String> {
+        let (object_path, _) = self.object_paths(path, bucket_name)?;
+        Url::from_file_path(&object_path)
+            .map(|url| url.to_string())
+            .map_err(|_| TardisError::format_error(&format!("[Tardis.OSClient] Invalid object path {path}"), "406-tardis-os-path-error"))
+    }
+}
+
+#[async_trait]
+impl TardisOSOperations for TardisOSFsClient {
+    async fn health_check(&self) -> TardisResult<()> {
+        let dir = match &self.default_bucket {
+            Some(bucket_name) => self.root.join(bucket_name),
+            None => self.root.clone(),
+        };
+        if tokio::fs::metadata(&dir).await.map_err(|error| Self::io_error(error, "access", &dir.to_string_lossy()))?.is_dir() {
+            Ok(())
+        } else {
+            Err(TardisError::not_found(
+                &format!("[Tardis.OSClient] {} isn't a directory", dir.to_string_lossy()),
+                "404-tardis-os-not-exist",
+            ))
+        }
+    }
+
+    async fn bucket_create_simple(&self, bucket_name: &str, _is_private: bool) -> TardisResult<()> {
+        Self::check_bucket_name(bucket_name)?;
+        tokio::fs::create_dir_all(&self.root).await.map_err(|error| Self::io_error(error, "create root directory", &self.root.to_string_lossy()))?;
+        tokio::fs::create_dir(self.root.join(bucket_name)).await.map_err(|error| Self::io_error(error, "create bucket", bucket_name))
+    }
+
+    async fn bucket_delete(&self, bucket_name: &str) -> TardisResult<()> {
+        Self::check_bucket_name(bucket_name)?;
+        // only the empty buckets are deleted, the same as the object storage services
+        tokio::fs::remove_dir(self.root.join(bucket_name)).await.map_err(|error| Self::io_error(error, "delete bucket", bucket_name))?;
+        match tokio::fs::remove_dir_all(self.root.join(INTERNAL_DIR).join("meta").join(bucket_name)).await {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(Self::io_error(error, "delete bucket metadata", bucket_name)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn object_create(
+        &self,
+        path: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+        metadata: Option<&HashMap<String, String>>,
+        bucket_name: Option<&str>,
+    ) -> TardisResult<()> {
+        let (object_path, meta_path) = self.object_paths(path, bucket_name)?;
+        Self::write_file(&object_path, content).await.map_err(|error| Self::io_error(error, "create object", path))?;
+        // the tags are reset by overwriting, the same as the object storage services
+        let meta = FsObjectMeta {
+            content_type: content_type.map(str::to_string),
+            metadata: metadata.cloned().unwrap_or_default(),
+            tags: HashMap::new(),
+        };
+        Self::write_meta(&meta_path, &meta).await
+    }
+
+    async fn object_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<Vec<u8>> {
+        let (object_path, _) = self.object_paths(path, bucket_name)?;
+        tokio::fs::read(&object_path).await.map_err(|error| Self::io_error(error, "get object", path))
+    }
+
+    async fn object_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        let (object_path, meta_path) = self.object_paths(path, bucket_name)?;
+        for file_path in [object_path, meta_path] {
+            match tokio::fs::remove_file(&file_path).await {
+                Err(error) if error.kind() != ErrorKind::NotFound => return Err(Self::io_error(error, "delete object", path)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn object_create_url(&self, path: &str, _expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.object_url(path, bucket_name)
+    }
+
+    fn object_get_url(&self, path: &str, _expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.object_url(path, bucket_name)
+    }
+
+    fn object_delete_url(&self, path: &str, _expire_sec: u32, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.object_url(path, bucket_name)
+    }
+
+    async fn object_head(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectMeta> {
+        let (object_path, meta_path) = self.object_paths(path, bucket_name)?;
+        let file_meta = tokio::fs::metadata(&object_path).await.map_err(|error| Self::io_error(error, "get object", path))?;
+        let meta = Self::read_meta(&meta_path).await?;
+        let (etag, last_modified) = Self::file_version(&file_meta);
+        Ok(TardisOSObjectMeta {
+            content_length: Some(file_meta.len()),
+            content_type: Some(meta.content_type.unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string())),
+            etag,
+            last_modified,
+            metadata: meta.metadata,
+        })
+    }
+
+    async fn object_tags_get(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<HashMap<String, String>> {
+        let (object_path, meta_path) = self.object_paths(path, bucket_name)?;
+        tokio::fs::metadata(&object_path).await.map_err(|error| Self::io_error(error, "get object", path))?;
+        Ok(Self::read_meta(&meta_path).await?.tags)
+    }
+
+    async fn object_tags_put(&self, path: &str, tags: &HashMap<String, String>, bucket_name: Option<&str>) -> TardisResult<()> {
+        self.update_meta(path, bucket_name, |meta| meta.tags = tags.clone()).await
+    }
+
+    async fn object_tags_delete(&self, path: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        self.update_meta(path, bucket_name, |meta| meta.tags.clear()).await
+    }
+
+    async fn object_list(&self, prefix: &str, continuation_token: Option<&str>, bucket_name: Option<&str>) -> TardisResult<TardisOSObjectPage> {
+        let bucket_name = self.get_bucket(bucket_name)?.to_string();
+        let dir = self.root.join(&bucket_name);
+        let mut objects = tokio::task::spawn_blocking(move || {
+            let mut objects = Vec::new();
+            Self::walk(&dir, "", &mut objects).map(|_| objects)
+        })
+        .await
+        .map_err(|error| TardisError::internal_error(&format!("[Tardis.OSClient] Failed to list objects: {error}"), "500-tardis-os-fs-error"))?
+        .map_err(|error| Self::io_error(error, "list objects of", &bucket_name))?;
+        // the continuation token is the last path of the previous page
+        objects.retain(|object| object.path.starts_with(prefix) && continuation_token.map_or(true, |token| object.path.as_str() > token));
+        objects.sort_by(|object1, object2| object1.path.cmp(&object2.path));
+        let next_continuation_token = if objects.len() > LIST_PAGE_SIZE {
+            objects.truncate(LIST_PAGE_SIZE);
+            objects.last().map(|object| object.path.clone())
+        } else {
+            None
+        };
+        Ok(TardisOSObjectPage { objects, next_continuation_token })
+    }
+
+    async fn object_copy(&self, from: &str, to: &str, from_bucket_name: Option<&str>, to_bucket_name: Option<&str>) -> TardisResult<()> {
+        let (from_path, from_meta_path) = self.object_paths(from, from_bucket_name)?;
+        let (to_path, to_meta_path) = self.object_paths(to, to_bucket_name)?;
+        if let Some(parent) = to_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|error| Self::io_error(error, "copy object to", to))?;
+        }
+        tokio::fs::copy(&from_path, &to_path).await.map_err(|error| Self::io_error(error, "copy object", from))?;
+        Self::write_meta(&to_meta_path, &Self::read_meta(&from_meta_path).await?).await
+    }
+
+    async fn bucket_lifecycle_get(&self, bucket_name: Option<&str>) -> TardisResult<Vec<TardisOSLifecycleRule>> {
+        let lifecycle_path = self.lifecycle_path(bucket_name)?;
+        match tokio::fs::read_to_string(&lifecycle_path).await {
+            Ok(rules) => TardisFuns::json.str_to_obj(&rules),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(Self::io_error(error, "read lifecycle", &lifecycle_path.to_string_lossy())),
+        }
+    }
+
+    async fn bucket_lifecycle_put(&self, rules: &[TardisOSLifecycleRule], bucket_name: Option<&str>) -> TardisResult<()> {
+        let lifecycle_path = self.lifecycle_path(bucket_name)?;
+        Self::write_file(&lifecycle_path, TardisFuns::json.obj_to_string(&rules)?.as_bytes())
+            .await
+            .map_err(|error| Self::io_error(error, "write lifecycle", &lifecycle_path.to_string_lossy()))
+    }
+
+    async fn bucket_lifecycle_delete(&self, bucket_name: Option<&str>) -> TardisResult<()> {
+        let lifecycle_path = self.lifecycle_path(bucket_name)?;
+        match tokio::fs::remove_file(&lifecycle_path).await {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(Self::io_error(error, "delete lifecycle", &lifecycle_path.to_string_lossy())),
+            _ => Ok(()),
+        }
+    }
+
+    async fn object_multipart_init(&self, path: &str, _content_type: &str, bucket_name: Option<&str>) -> TardisResult<String> {
+        self.object_paths(path, bucket_name)?;
+        let upload_id = TardisFuns::field.nanoid();
+        let dir = self.multipart_dir(&upload_id)?;
+        tokio::fs::create_dir_all(&dir).await.map_err(|error| Self::io_error(error, "init multipart upload", path))?;
+        Ok(upload_id)
+    }
+
+    async fn object_multipart_put_part(
+        &self,
+        path: &str,
+        upload_id: &str,
+        part_number: u32,
+        content: Vec<u8>,
+        _content_type: &str,
+        _bucket_name: Option<&str>,
+    ) -> TardisResult<String> {
+        let dir = self.multipart_dir(upload_id)?;
+        tokio::fs::metadata(&dir).await.map_err(|error| Self::io_error(error, "get multipart upload", upload_id))?;
+        tokio::fs::write(dir.join(part_number.to_string()), &content).await.map_err(|error| Self::io_error(error, "put part of", path))?;
+        Ok(format!("{part_number}-{:x}", content.len()))
+    }
+
+    async fn object_multipart_complete(&self, path: &str, upload_id: &str, parts: &[TardisOSUploadedPart], content_type: &str, bucket_name: Option<&str>) -> TardisResult<()> {
+        let (object_path, meta_path) = self.object_paths(path, bucket_name)?;
+        let dir = self.multipart_dir(upload_id)?;
+        tokio::fs::metadata(&dir).await.map_err(|error| Self::io_error(error, "get multipart upload", upload_id))?;
+        let mut parts = parts.to_vec();
+        parts.sort_by_key(|part| part.part_number);
+        // merged in the upload directory and then moved, so the object is replaced atomically
+        let merged_path = dir.join("merged");
+        let mut merged = tokio::fs::File::create(&merged_path).await.map_err(|error| Self::io_error(error, "complete multipart upload", path))?;
+        for part in &parts {
+            let content = tokio::fs::read(dir.join(part.part_number.to_string())).await.map_err(|error| Self::io_error(error, "get part of", path))?;
+            if part.etag != format!("{}-{:x}", part.part_number, content.len()) {
+                return Err(TardisError::bad_request(
+                    &format!("[Tardis.OSClient] Mismatched etag of the part {} of {path}", part.part_number),
+                    "400-tardis-os-part-error",
+                ));
+            }
+            merged.write_all(&content).await.map_err(|error| Self::io_error(error, "complete multipart upload", path))?;
+        }
+        merged.flush().await.map_err(|error| Self::io_error(error, "complete multipart upload", path))?;
+        drop(merged);
+        if let Some(parent) = object_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|error| Self::io_error(error, "complete multipart upload", path))?;
+        }
+        tokio::fs::rename(&merged_path, &object_path).await.map_err(|error| Self::io_error(error, "complete multipart upload", path))?;
+        Self::write_meta(
+            &meta_path,
+            &FsObjectMeta {
+                content_type: Some(content_type.to_string()),
+                ..Default::default()
+            },
+        )
+        .await?;
+        tokio::fs::remove_dir_all(&dir).await.map_err(|error| Self::io_error(error, "clean multipart upload", upload_id))
+    }
+
+    async fn object_multipart_abort(&self, _path: &str, upload_id: &str, _bucket_name: Option<&str>) -> TardisResult<()> {
+        let dir = self.multipart_dir(upload_id)?;
+        tokio::fs::remove_dir_all(&dir).await.map_err(|error| Self::io_error(error, "abort multipart upload", upload_id))
+    }
+}
@@ -0,0 +1 @@
+pub mod scheduler_client;
@@ -0,0 +1,631 @@
+//! Job scheduler / 任务调度器
+//!
+//! The jobs are scheduled by the cron expressions or the fixed intervals and run on the tokio runtime, each job by its own task:
+//!
+//! 任务按cron表达式或固定间隔调度，在tokio运行时上运行，每个任务使用独立的task：
+//!
+//! * the cron expressions have the seconds field and are evaluated in UTC, e.g. `0 */5 * * * *`
+//!   / cron表达式包含秒字段并按UTC计算，如 `0 */5 * * * *`
+//! * the intervals are aligned to the Unix epoch, so the instances of a cluster are scheduled at the same time
+//!   / 间隔按Unix纪元对齐，因此集群中的实例在相同时间被调度
+//! * an execution never overlaps the previous one of the same job on the instance, the occurrences missed meanwhile are skipped
+//!   / 同一任务在本实例上的执行不会与上一次重叠，期间错过的调度被跳过
+//! * the errors and the panics of the job are logged and counted in the [`TardisJobStatus`] , the job is still scheduled
+//!   / 任务的错误及panic被记录并计入 [`TardisJobStatus`] ，任务仍会继续调度
+//!
+//! The distributed jobs (with the `cache` feature) are guarded by the distributed lock of the cache,
+//! so only one instance of the cluster runs an occurrence, see [`SchedulerConfig`] for the lock options.
+//!
+//! 分布式任务（需要 `cache` 功能）通过缓存的分布式锁保护，因此集群中只有一个实例运行某次调度，锁的选项见 [`SchedulerConfig`] .
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::TardisFuns;
+//! use tardis::scheduler::scheduler_client::{TardisJob, TardisSchedule};
+//! TardisFuns::scheduler().register_interval("refresh_token", Duration::from_secs(60), || async { Ok(()) })?;
+//! TardisFuns::scheduler().register(TardisJob::new("clean_expired", TardisSchedule::cron("0 0 3 * * *")?, || async { Ok(()) }).distributed(true))?;
+//! for status in TardisFuns::scheduler().jobs() {
+//!     println!("{}: next run at {:?}, {} failures", status.name, status.next_run, status.failures);
+//! }
+//! ```
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, info, trace};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+#[cfg(feature = "cache")]
+use crate::cache::cache_client::{CacheLockGuard, TardisCacheClient};
+use crate::config::config_dto::component::scheduler::SchedulerConfig;
+#[cfg(feature = "cache")]
+use crate::TardisFuns;
+
+type TardisJobHandler = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send>> + Send + Sync>;
+
+/// Schedule of a job / 任务的调度
+#[derive(Clone)]
+pub struct TardisSchedule(TardisScheduleKind);
+
+#[derive(Clone)]
+enum TardisScheduleKind {
+    Cron(String, Box<cron::Schedule>),
+    Interval(Duration),
+}
+
+impl TardisSchedule {
+    /// Schedule by the cron expression with the seconds field, in UTC / 按包含秒字段的cron表达式调度，使用UTC
+    ///
+    /// The fields are `sec min hour day_of_month month day_of_week [year]` , e.g. `0 30 2 * * Mon-Fri` .
+    ///
+    /// 字段依次为 `秒 分 时 日 月 星期 [年]` ，如 `0 30 2 * * Mon-Fri` .
+    pub fn cron(expr: &str) -> TardisResult<Self> {
+        let schedule = cron::Schedule::from_str(expr)
+            .map_err(|error| TardisError::format_error(&format!("[Tardis.Scheduler] Invalid cron expression {expr}: {error}"), "406-tardis-scheduler-cron-error"))?;
+        Ok(TardisSchedule(TardisScheduleKind::Cron(expr.to_string(), Box::new(schedule))))
+    }
+
+    /// Schedule by the fixed interval aligned to the Unix epoch / 按对齐Unix纪元的固定间隔调度
+    pub fn interval(interval: Duration) -> TardisResult<Self> {
+        if interval.as_millis() == 0 {
+            return Err(TardisError::format_error(
+                "[Tardis.Scheduler] The interval should be at least 1 millisecond",
+                "406-tardis-scheduler-interval-error",
+            ));
+        }
+        Ok(TardisSchedule(TardisScheduleKind::Interval(interval)))
+    }
+
+    /// The first occurrence after the time, `None` if there's no more occurrence / 该时间之后的首次调度，没有更多调度时为 `None`
+    pub fn next_after(&self, time: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.0 {
+            TardisScheduleKind::Cron(_, schedule) => schedule.after(time).next(),
+            TardisScheduleKind::Interval(interval) => {
+                let interval_ms = interval.as_millis().min(i64::MAX as u128) as i64;
+                let next_ms = (time.timestamp_millis().div_euclid(interval_ms) + 1).checked_mul(interval_ms)?;
+                Utc.timestamp_millis_opt(next_ms).single()
+            }
+        }
+    }
+}
+
+impl fmt::Display for TardisSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            TardisScheduleKind::Cron(expr, _) => write!(f, "cron({expr})"),
+            TardisScheduleKind::Interval(interval) => write!(f, "every {interval:?}"),
+        }
+    }
+}
+
+impl fmt::Debug for TardisSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TardisSchedule({self})")
+    }
+}
+
+/// Scheduled job / 调度任务
+#[derive(Clone)]
+pub struct TardisJob {
+    name: String,
+    schedule: TardisSchedule,
+    distributed: bool,
+    handler: TardisJobHandler,
+}
+
+impl TardisJob {
+    pub fn new<F, Fut>(name: impl Into<String>, schedule: TardisSchedule, handler: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        TardisJob {
+            name: name.into(),
+            schedule,
+            distributed: false,
+            handler: Arc::new(move || Box::pin(handler())),
+        }
+    }
+
+    /// Run each occurrence on only one instance of the cluster, default by false / 每次调度只在集群中的一个实例上运行，默认为false
+    #[cfg(feature = "cache")]
+    pub fn distributed(mut self, distributed: bool) -> Self {
+        self.distributed = distributed;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn schedule(&self) -> &TardisSchedule {
+        &self.schedule
+    }
+}
+
+/// Status of a job / 任务的状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TardisJobStatus {
+    pub name: String,
+    /// The effective schedule, e.g. `cron(0 0 3 * * *)` or `every 60s` / 生效的调度，如 `cron(0 0 3 * * *)` 或 `every 60s`
+    pub schedule: String,
+    /// Whether the job is enabled by the configuration / 任务是否被配置启用
+    pub enabled: bool,
+    pub distributed: bool,
+    /// Whether the job is running on this instance / 任务是否正在本实例上运行
+    pub running: bool,
+    /// The next occurrence, `None` if the scheduler isn't started / 下次调度，调度器未启动时为 `None`
+    pub next_run: Option<DateTime<Utc>>,
+    /// Start time of the last execution on this instance / 本实例上次执行的开始时间
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_duration: Option<Duration>,
+    /// Error of the last execution, `None` if it succeeded / 上次执行的错误，成功时为 `None`
+    pub last_error: Option<String>,
+    /// Executions on this instance / 本实例的执行次数
+    pub runs: u64,
+    /// Failed executions on this instance / 本实例的失败次数
+    pub failures: u64,
+    /// Occurrences skipped on this instance, i.e. run by another instance or still running / 本实例跳过的调度次数，即由其他实例运行或仍在运行
+    pub skipped: u64,
+}
+
+#[derive(Default)]
+struct TardisJobState {
+    running: bool,
+    next_run: Option<DateTime<Utc>>,
+    last_run: Option<DateTime<Utc>>,
+    last_duration: Option<Duration>,
+    last_error: Option<String>,
+    runs: u64,
+    failures: u64,
+    skipped: u64,
+}
+
+struct TardisJobEntry {
+    job: TardisJob,
+    state: Mutex<TardisJobState>,
+    /// Held while executing, so the executions don't overlap / 执行期间持有，使执行不重叠
+    execution: tokio::sync::Mutex<()>,
+}
+
+impl TardisJobEntry {
+    fn state(&self) -> std::sync::MutexGuard<'_, TardisJobState> {
+        self.state.lock().expect("[Tardis.Scheduler] job state lock poisoned")
+    }
+
+    fn status(&self, config: &SchedulerConfig) -> TardisJobStatus {
+        let (enabled, schedule) = match effective_settings(&self.job, config) {
+            Ok((enabled, schedule)) => (enabled, schedule.to_string()),
+            Err(_) => (false, self.job.schedule.to_string()),
+        };
+        let state = self.state();
+        TardisJobStatus {
+            name: self.job.name.clone(),
+            schedule,
+            enabled,
+            distributed: self.job.distributed,
+            running: state.running,
+            next_run: state.next_run,
+            last_run: state.last_run,
+            last_duration: state.last_duration,
+            last_error: state.last_error.clone(),
+            runs: state.runs,
+            failures: state.failures,
+            skipped: state.skipped,
+        }
+    }
+}
+
+/// The enabled flag and the schedule of the job with the overrides of the configuration / 应用配置覆盖后任务的启用标识及调度
+fn effective_settings(job: &TardisJob, config: &SchedulerConfig) -> TardisResult<(bool, TardisSchedule)> {
+    let Some(job_config) = config.jobs.get(&job.name) else {
+        return Ok((true, job.schedule.clone()));
+    };
+    let schedule = if let Some(cron) = &job_config.cron {
+        TardisSchedule::cron(cron)?
+    } else if let Some(interval_sec) = job_config.interval_sec {
+        TardisSchedule::interval(Duration::from_secs(interval_sec))?
+    } else {
+        job.schedule.clone()
+    };
+    Ok((job_config.enabled, schedule))
+}
+
+#[cfg(feature = "cache")]
+#[derive(Clone)]
+struct TardisJobLock {
+    key: String,
+    ttl: Duration,
+    min_hold: Duration,
+    cache_module: String,
+    cache: Option<Arc<TardisCacheClient>>,
+}
+
+#[cfg(feature = "cache")]
+impl TardisJobLock {
+    async fn acquire(&self) -> TardisResult<Option<CacheLockGuard>> {
+        let cache = match &self.cache {
+            Some(cache) => cache.clone(),
+            None => crate::TARDIS_INST.cache.get(&self.cache_module).or_else(|| crate::TARDIS_INST.cache.get("")).ok_or_else(|| {
+                TardisError::not_found(
+                    &format!("[Tardis.Scheduler] The distributed lock requires the cache {}", self.cache_module),
+                    "404-tardis-scheduler-cache-empty",
+                )
+            })?,
+        };
+        Ok(cache.lock(&self.key, self.ttl).await?.map(CacheLockGuard::auto_renew))
+    }
+
+    /// Keep the lock until `min_hold` after the scheduled time, but not beyond the middle of the following occurrence, then release it
+    /// / 保留锁至计划时间后 `min_hold` ，但不超过与下次调度的中点，之后释放
+    async fn release(&self, guard: CacheLockGuard, scheduled_at: DateTime<Utc>, following: Option<DateTime<Utc>>) {
+        let mut hold_until = scheduled_at + chrono::Duration::from_std(self.min_hold).unwrap_or_else(|_| chrono::Duration::zero());
+        if let Some(following) = following {
+            hold_until = hold_until.min(scheduled_at + (following - scheduled_at) / 2);
+        }
+        let remaining = (hold_until - TardisFuns::clock().now()).to_std().ok().filter(|remaining| !remaining.is_zero());
+        let result = match remaining {
+            Some(remaining) => guard.extend(remaining).await.map(|extended| {
+                guard.detach();
+                extended
+            }),
+            None => guard.unlock().await,
+        };
+        match result {
+            Ok(true) => {}
+            Ok(false) => tracing::warn!("[Tardis.Scheduler] Lock {} is lost before the job finished", self.key),
+            Err(error) => tracing::warn!("[Tardis.Scheduler] Release lock {} error: {error}", self.key),
+        }
+    }
+}
+
+/// Executes the occurrences of a job / 执行任务的调度
+#[derive(Clone)]
+struct TardisJobRunner {
+    entry: Arc<TardisJobEntry>,
+    #[cfg(feature = "cache")]
+    lock: Option<TardisJobLock>,
+}
+
+impl TardisJobRunner {
+    /// Execute the occurrence scheduled at the time, returns `false` if it's skipped / 执行该时间的调度，跳过时返回 `false`
+    ///
+    /// `following` is the next occurrence, if any / `following` 为下次调度（如果有）
+    async fn execute(&self, scheduled_at: DateTime<Utc>, #[allow(unused_variables)] following: Option<DateTime<Utc>>) -> TardisResult<bool> {
+        let name = &self.entry.job.name;
+        let Ok(_execution) = self.entry.execution.try_lock() else {
+            trace!("[Tardis.Scheduler] Job {name} is still running, skip the occurrence at {scheduled_at}");
+            self.entry.state().skipped += 1;
+            return Ok(false);
+        };
+        #[cfg(feature = "cache")]
+        let guard = match &self.lock {
+            Some(lock) => match lock.acquire().await {
+                Ok(Some(guard)) => Some(guard),
+                Ok(None) => {
+                    trace!("[Tardis.Scheduler] Job {name} is run by another instance, skip the occurrence at {scheduled_at}");
+                    self.entry.state().skipped += 1;
+                    return Ok(false);
+                }
+                Err(error) => {
+                    let mut state = self.entry.state();
+                    state.failures += 1;
+                    state.last_error = Some(error.to_string());
+                    return Err(error);
+                }
+            },
+            None => None,
+        };
+        trace!("[Tardis.Scheduler] Job {name} started, scheduled at {scheduled_at}");
+        {
+            let mut state = self.entry.state();
+            state.running = true;
+            state.last_run = Some(TardisFuns::clock().now());
+        }
+        let started = tokio::time::Instant::now();
+        // run in a separate task to catch the panic
+        let result = match tokio::spawn((self.entry.job.handler)()).await {
+            Ok(result) => result,
+            Err(error) => Err(TardisError::internal_error(
+                &format!("[Tardis.Scheduler] Job {name} panicked: {error}"),
+                "500-tardis-scheduler-job-panicked",
+            )),
+        };
+        {
+            let mut state = self.entry.state();
+            state.running = false;
+            state.last_duration = Some(started.elapsed());
+            state.runs += 1;
+            match &result {
+                Ok(_) => state.last_error = None,
+                Err(error) => {
+                    state.failures += 1;
+                    state.last_error = Some(error.to_string());
+                }
+            }
+        }
+        #[cfg(feature = "cache")]
+        if let (Some(lock), Some(guard)) = (&self.lock, guard) {
+            lock.release(guard, scheduled_at, following).await;
+        }
+        result.map(|_| true)
+    }
+
+    fn spawn(self, schedule: TardisSchedule, mut stop: watch::Receiver<()>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let name = self.entry.job.name.clone();
+            loop {
+                let now = TardisFuns::clock().now();
+                let Some(next_run) = schedule.next_after(&now) else {
+                    info!("[Tardis.Scheduler] Job {name} has no more occurrence");
+                    break;
+                };
+                self.entry.state().next_run = Some(next_run);
+                tokio::select! {
+                    _ = tokio::time::sleep((next_run - now).to_std().unwrap_or_default()) => {}
+                    _ = stop.changed() => break,
+                }
+                if let Err(error) = self.execute(next_run, schedule.next_after(&next_run)).await {
+                    error!("[Tardis.Scheduler] Job {name} failed, scheduled at {next_run} | {error}");
+                }
+            }
+            self.entry.state().next_run = None;
+        })
+    }
+}
+
+struct TardisJobTask {
+    /// Dropped to stop the task / drop时停止task
+    stop: watch::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl TardisJobTask {
+    async fn stop(self) {
+        drop(self.stop);
+        if let Err(error) = self.handle.await {
+            if error.is_panic() {
+                error!("[Tardis.Scheduler] Job task panicked: {error}");
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct TardisSchedulerRuntime {
+    started: bool,
+    tasks: HashMap<String, TardisJobTask>,
+}
+
+/// Job scheduler / 任务调度器
+///
+/// The global scheduler is [`TardisFuns::scheduler`](crate::TardisFuns::scheduler) , which is configured by `fw.scheduler` .
+///
+/// 全局调度器为 [`TardisFuns::scheduler`](crate::TardisFuns::scheduler) ，通过 `fw.scheduler` 配置.
+pub struct TardisScheduler {
+    config: RwLock<SchedulerConfig>,
+    jobs: RwLock<HashMap<String, Arc<TardisJobEntry>>>,
+    runtime: Mutex<TardisSchedulerRuntime>,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<TardisCacheClient>>,
+}
+
+impl Default for TardisScheduler {
+    fn default() -> Self {
+        TardisScheduler::new(SchedulerConfig::default())
+    }
+}
+
+impl TardisScheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        TardisScheduler {
+            config: RwLock::new(config),
+            jobs: RwLock::new(HashMap::new()),
+            runtime: Mutex::new(TardisSchedulerRuntime::default()),
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+
+    /// Use the cache client for the distributed locks instead of the `cache_module` / 使用该缓存客户端而非 `cache_module` 作为分布式锁
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, cache: Arc<TardisCacheClient>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn config(&self) -> SchedulerConfig {
+        self.config.read().expect("[Tardis.Scheduler] config lock poisoned").clone()
+    }
+
+    fn runtime(&self) -> std::sync::MutexGuard<'_, TardisSchedulerRuntime> {
+        self.runtime.lock().expect("[Tardis.Scheduler] runtime lock poisoned")
+    }
+
+    /// Register the job, it's started at once if the scheduler is started / 注册任务，调度器已启动时立即启动该任务
+    pub fn register(&self, job: TardisJob) -> TardisResult<()> {
+        let config = self.config();
+        let (enabled, schedule) = effective_settings(&job, &config)?;
+        let name = job.name.clone();
+        let entry = Arc::new(TardisJobEntry {
+            job,
+            state: Mutex::new(TardisJobState::default()),
+            execution: tokio::sync::Mutex::new(()),
+        });
+        // the runtime lock is held, so the job isn't missed by a concurrent start
+        let mut runtime = self.runtime();
+        {
+            let mut jobs = self.jobs.write().expect("[Tardis.Scheduler] jobs lock poisoned");
+            if jobs.contains_key(&name) {
+                return Err(TardisError::conflict(
+                    &format!("[Tardis.Scheduler] Job {name} already exists"),
+                    "409-tardis-scheduler-job-exist",
+                ));
+            }
+            jobs.insert(name.clone(), entry.clone());
+        }
+        info!("[Tardis.Scheduler] Job {name} registered, schedule:{schedule}, enabled:{enabled}");
+        if runtime.started && enabled {
+            let task = self.spawn(entry, schedule, &config);
+            runtime.tasks.insert(name, task);
+        }
+        Ok(())
+    }
+
+    /// Register the job scheduled by the cron expression, see [`TardisSchedule::cron`] / 注册按cron表达式调度的任务，见 [`TardisSchedule::cron`]
+    pub fn register_cron<F, Fut>(&self, name: impl Into<String>, cron: &str, handler: F) -> TardisResult<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.register(TardisJob::new(name, TardisSchedule::cron(cron)?, handler))
+    }
+
+    /// Register the job scheduled by the fixed interval, see [`TardisSchedule::interval`] / 注册按固定间隔调度的任务，见 [`TardisSchedule::interval`]
+    pub fn register_interval<F, Fut>(&self, name: impl Into<String>, interval: Duration, handler: F) -> TardisResult<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.register(TardisJob::new(name, TardisSchedule::interval(interval)?, handler))
+    }
+
+    /// Unregister the job, waiting for the running execution, returns `false` if it doesn't exist
+    /// / 注销任务，等待正在进行的执行，任务不存在时返回 `false`
+    pub async fn unregister(&self, name: &str) -> bool {
+        let task = {
+            let mut runtime = self.runtime();
+            if self.jobs.write().expect("[Tardis.Scheduler] jobs lock poisoned").remove(name).is_none() {
+                return false;
+            }
+            runtime.tasks.remove(name)
+        };
+        if let Some(task) = task {
+            task.stop().await;
+        }
+        info!("[Tardis.Scheduler] Job {name} unregistered");
+        true
+    }
+
+    /// Start scheduling the enabled jobs, nothing is done if the scheduler is disabled by the configuration
+    /// / 开始调度已启用的任务，调度器被配置禁用时不做任何处理
+    ///
+    /// Returns an error if a schedule of the configuration is invalid. It should be called in the tokio runtime.
+    ///
+    /// 配置中的调度无效时返回错误. 应在tokio运行时中调用.
+    pub fn start(&self) -> TardisResult<()> {
+        let config = self.config();
+        let mut runtime = self.runtime();
+        if runtime.started {
+            return Ok(());
+        }
+        if !config.enabled {
+            info!("[Tardis.Scheduler] Scheduler is disabled by the configuration");
+            return Ok(());
+        }
+        let jobs = self.jobs.read().expect("[Tardis.Scheduler] jobs lock poisoned").values().cloned().collect::<Vec<_>>();
+        // check all the schedules before starting any job
+        let mut enabled_jobs = Vec::new();
+        for entry in jobs {
+            let (enabled, schedule) = effective_settings(&entry.job, &config)?;
+            if enabled {
+                enabled_jobs.push((entry, schedule));
+            }
+        }
+        for (entry, schedule) in enabled_jobs {
+            let name = entry.job.name.clone();
+            let task = self.spawn(entry, schedule, &config);
+            runtime.tasks.insert(name, task);
+        }
+        runtime.started = true;
+        info!("[Tardis.Scheduler] Scheduler started, {} jobs scheduled", runtime.tasks.len());
+        Ok(())
+    }
+
+    /// Stop scheduling, waiting for the running executions / 停止调度，等待正在进行的执行
+    pub async fn stop(&self) {
+        let tasks = {
+            let mut runtime = self.runtime();
+            if !runtime.started {
+                return;
+            }
+            runtime.started = false;
+            std::mem::take(&mut runtime.tasks)
+        };
+        for (_, task) in tasks {
+            task.stop().await;
+        }
+        info!("[Tardis.Scheduler] Scheduler stopped");
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.runtime().started
+    }
+
+    /// Apply the configuration, the scheduler is restarted if it's started or enabled / 应用配置，调度器已启动或被启用时重新启动
+    pub(crate) async fn reload(&self, config: SchedulerConfig) -> TardisResult<()> {
+        self.stop().await;
+        *self.config.write().expect("[Tardis.Scheduler] config lock poisoned") = config;
+        self.start()
+    }
+
+    /// Status of the jobs, ordered by name / 任务的状态，按名称排序
+    pub fn jobs(&self) -> Vec<TardisJobStatus> {
+        let config = self.config();
+        let mut jobs = self.jobs.read().expect("[Tardis.Scheduler] jobs lock poisoned").values().map(|entry| entry.status(&config)).collect::<Vec<_>>();
+        jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        jobs
+    }
+
+    pub fn job(&self, name: &str) -> Option<TardisJobStatus> {
+        let config = self.config();
+        self.jobs.read().expect("[Tardis.Scheduler] jobs lock poisoned").get(name).map(|entry| entry.status(&config))
+    }
+
+    /// Run the job now, ignoring its schedule and enabled flag / 立即运行任务，忽略其调度及启用标识
+    ///
+    /// Returns `false` if it's skipped, i.e. it's still running or it's distributed and run by another instance.
+    ///
+    /// 跳过时返回 `false` ，即仍在运行，或为分布式任务且由其他实例运行.
+    pub async fn trigger(&self, name: &str) -> TardisResult<bool> {
+        let entry = self
+            .jobs
+            .read()
+            .expect("[Tardis.Scheduler] jobs lock poisoned")
+            .get(name)
+            .cloned()
+            .ok_or_else(|| TardisError::not_found(&format!("[Tardis.Scheduler] Job {name} doesn't exist"), "404-tardis-scheduler-job-not-exist"))?;
+        let config = self.config();
+        self.runner(entry, &config).execute(TardisFuns::clock().now(), None).await
+    }
+
+    fn runner(&self, entry: Arc<TardisJobEntry>, #[allow(unused_variables)] config: &SchedulerConfig) -> TardisJobRunner {
+        #[cfg(feature = "cache")]
+        let lock = entry.job.distributed.then(|| TardisJobLock {
+            key: format!("{}{}:{}", config.lock_prefix, TardisFuns::fw_config().app.id, entry.job.name),
+            ttl: Duration::from_secs(config.lock_ttl_sec.max(1)),
+            min_hold: Duration::from_millis(config.lock_min_hold_ms),
+            cache_module: config.cache_module.to_lowercase(),
+            cache: self.cache.clone(),
+        });
+        TardisJobRunner {
+            entry,
+            #[cfg(feature = "cache")]
+            lock,
+        }
+    }
+
+    fn spawn(&self, entry: Arc<TardisJobEntry>, schedule: TardisSchedule, config: &SchedulerConfig) -> TardisJobTask {
+        let (stop, stop_receiver) = watch::channel(());
+        let handle = self.runner(entry, config).spawn(schedule, stop_receiver);
+        TardisJobTask { stop, handle }
+    }
+}
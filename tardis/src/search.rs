@@ -1 +1,2 @@
 pub mod search_client;
+pub mod search_query;
@@ -1,14 +1,19 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{debug, info, trace};
 use url::Url;
 
 use crate::basic::error::TardisError;
+use crate::basic::metrics::observe_client;
 use crate::basic::result::TardisResult;
-use crate::config::config_dto::component::search::SearchModuleConfig;
-use crate::config::config_dto::component::web_client::WebClientModuleConfig;
+use crate::config::config_dto::component::search::{SearchEngineKind, SearchModuleConfig};
+use crate::search::search_query::{SearchQuery, SearchQueryBuilder};
 
 use crate::utils::initializer::InitBy;
 use crate::{TardisFuns, TardisWebClient};
@@ -34,6 +39,8 @@ use crate::{TardisFuns, TardisWebClient};
 pub struct TardisSearchClient {
     pub client: TardisWebClient,
     pub server_url: Url,
+    /// Max number of records per `_bulk` request / 每个 `_bulk` 请求的最大记录数
+    pub bulk_chunk_size: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -42,6 +49,154 @@ pub struct TardisRawSearchResp {
     pub took: i32,
     pub _shards: TardisRawSearchShards,
     pub timed_out: bool,
+    /// Id of the scroll context, only returned by the scroll requests / 滚动上下文的标识，仅滚动请求返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub _scroll_id: Option<String>,
+    /// Native format of the aggregation results / 原生格式的聚合结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregations: Option<Value>,
+}
+
+/// Result of an aggregation / 聚合结果
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TardisSearchAgg {
+    /// Buckets of the bucket aggregations, E.g. `terms`, `date_histogram` / 桶聚合的桶，如 `terms` 、 `date_histogram`
+    pub buckets: Vec<TardisSearchAggBucket>,
+    /// Value of the single value metrics, E.g. `cardinality`, `avg` / 单值指标的值，如 `cardinality` 、 `avg`
+    pub value: Option<f64>,
+    /// Result of the `stats` aggregation / `stats` 聚合的结果
+    pub stats: Option<TardisSearchAggStats>,
+    /// Native format of the result / 原生格式的结果
+    pub raw: Value,
+}
+
+/// Bucket of the bucket aggregations / 桶聚合的桶
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TardisSearchAggBucket {
+    pub key: Value,
+    /// Formatted key, E.g. the date of `date_histogram` / 格式化的键，如 `date_histogram` 的日期
+    pub key_as_string: Option<String>,
+    pub doc_count: u64,
+    /// Results of the sub aggregations / 子聚合的结果
+    pub aggs: HashMap<String, TardisSearchAgg>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TardisSearchAggStats {
+    pub count: u64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub avg: Option<f64>,
+    pub sum: f64,
+}
+
+impl TardisSearchAgg {
+    fn from_json(raw: &Value) -> Self {
+        let buckets = match &raw["buckets"] {
+            Value::Array(buckets) => buckets.iter().map(TardisSearchAggBucket::from_json).collect(),
+            // the keyed buckets
+            Value::Object(buckets) => buckets
+                .iter()
+                .map(|(key, bucket)| TardisSearchAggBucket {
+                    key: Value::String(key.clone()),
+                    ..TardisSearchAggBucket::from_json(bucket)
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let stats = if raw.get("count").is_some() && raw.get("sum").is_some() {
+            Some(TardisSearchAggStats {
+                count: raw["count"].as_u64().unwrap_or_default(),
+                min: raw["min"].as_f64(),
+                max: raw["max"].as_f64(),
+                avg: raw["avg"].as_f64(),
+                sum: raw["sum"].as_f64().unwrap_or_default(),
+            })
+        } else {
+            None
+        };
+        TardisSearchAgg {
+            buckets,
+            value: raw["value"].as_f64(),
+            stats,
+            raw: raw.clone(),
+        }
+    }
+
+    fn from_aggs_json(raw: &Value) -> HashMap<String, TardisSearchAgg> {
+        raw.as_object()
+            .map(|aggs| aggs.iter().filter(|(_, agg)| agg.is_object()).map(|(name, agg)| (name.clone(), TardisSearchAgg::from_json(agg))).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl TardisSearchAggBucket {
+    fn from_json(raw: &Value) -> Self {
+        let aggs = raw
+            .as_object()
+            .map(|bucket| {
+                bucket
+                    .iter()
+                    .filter(|(name, agg)| agg.is_object() && !matches!(name.as_str(), "key" | "key_as_string" | "doc_count"))
+                    .map(|(name, agg)| (name.clone(), TardisSearchAgg::from_json(agg)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        TardisSearchAggBucket {
+            key: raw["key"].clone(),
+            key_as_string: raw["key_as_string"].as_str().map(str::to_string),
+            doc_count: raw["doc_count"].as_u64().unwrap_or_default(),
+            aggs,
+        }
+    }
+}
+
+/// Page of the search result / 搜索结果分页
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TardisSearchPage<T> {
+    pub page_size: u64,
+    pub page_number: u64,
+    pub total_size: u64,
+    pub records: Vec<T>,
+}
+
+/// Result of the bulk operations / 批量操作的结果
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TardisSearchBulkResp {
+    /// Primary key values of the succeeded records, in the order of the request / 成功记录的主键值，按请求顺序
+    pub ids: Vec<String>,
+    /// The failed records / 失败的记录
+    pub failures: Vec<TardisSearchBulkFailure>,
+}
+
+impl TardisSearchBulkResp {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Failed record of the bulk operations / 批量操作中失败的记录
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TardisSearchBulkFailure {
+    /// Position of the record in the request / 记录在请求中的位置
+    pub idx: usize,
+    /// Primary key value of the record, if known / 记录的主键值（如已知）
+    pub id: Option<String>,
+    /// Http status of the record / 记录的http状态码
+    pub status: u16,
+    pub reason: String,
+}
+
+impl TardisRawSearchResp {
+    /// Deserialize the sources of the hits / 反序列化命中记录的内容
+    pub fn records<T: DeserializeOwned>(&self) -> TardisResult<Vec<T>> {
+        self.hits.hits.iter().map(|item| item.source()).collect()
+    }
+
+    /// Structured results of the aggregations by name / 按名称的结构化聚合结果
+    pub fn aggs(&self) -> HashMap<String, TardisSearchAgg> {
+        self.aggregations.as_ref().map(TardisSearchAgg::from_aggs_json).unwrap_or_default()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -51,18 +206,50 @@ pub struct TardisRawSearchHits {
     pub max_score: Option<f32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Debug)]
 pub struct TardisRawSearchHitsTotal {
     pub value: i32,
     pub relation: String,
 }
 
+impl<'de> Deserialize<'de> for TardisRawSearchHitsTotal {
+    /// The total is a number in Elasticsearch 6.x or with `rest_total_hits_as_int` / Elasticsearch 6.x或使用 `rest_total_hits_as_int` 时总数为数字
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Total {
+            Object { value: i32, relation: String },
+            Value(i32),
+        }
+        Ok(match Total::deserialize(deserializer)? {
+            Total::Object { value, relation } => TardisRawSearchHitsTotal { value, relation },
+            Total::Value(value) => TardisRawSearchHitsTotal {
+                value,
+                relation: "eq".to_string(),
+            },
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TardisRawSearchHitsItem {
     pub _index: String,
     pub _id: String,
     pub _score: Option<f32>,
     pub _source: Value,
+    /// Sort values of the hit, used by `search_after` / 命中记录的排序值，用于 `search_after`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<Vec<Value>>,
+}
+
+impl TardisRawSearchHitsItem {
+    /// Deserialize the source of the hit / 反序列化命中记录的内容
+    pub fn source<T: DeserializeOwned>(&self) -> TardisResult<T> {
+        TardisFuns::json.json_to_obj(self._source.clone())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -81,12 +268,57 @@ impl InitBy<SearchModuleConfig> for TardisSearchClient {
 
 impl TardisSearchClient {
     /// Initialize configuration / 初始化配置
-    pub fn init(SearchModuleConfig { url, timeout_sec }: &SearchModuleConfig) -> TardisResult<TardisSearchClient> {
-        info!("[Tardis.SearchClient] Initializing");
-        let mut client = TardisWebClient::init(&WebClientModuleConfig::builder().request_timeout_sec(*timeout_sec).build())?;
-        client.set_default_header("Content-Type", "application/json");
+    pub fn init(config: &SearchModuleConfig) -> TardisResult<TardisSearchClient> {
+        info!("[Tardis.SearchClient] Initializing, engine:{:?}", config.engine);
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(config.timeout_sec))
+            .timeout(Duration::from_secs(config.timeout_sec))
+            .danger_accept_invalid_certs(config.tls.accept_invalid_certs);
+        if let Some(ca_cert) = &config.tls.ca_cert {
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(ca_cert.as_bytes())?);
+        }
+        match (&config.tls.client_cert, &config.tls.client_key) {
+            (Some(client_cert), Some(client_key)) => builder = builder.identity(reqwest::Identity::from_pkcs8_pem(client_cert.as_bytes(), client_key.as_bytes())?),
+            (None, None) => {}
+            _ => {
+                return Err(TardisError::format_error(
+                    "[Tardis.SearchClient] The client_cert and client_key must be configured together",
+                    "406-tardis-search-tls-invalid",
+                ))
+            }
+        }
+        let mut client = TardisWebClient::from_client(builder.build()?);
+        match (config.engine, config.compatible_with) {
+            (SearchEngineKind::Elasticsearch, Some(version)) => {
+                let media_type = format!("application/vnd.elasticsearch+json; compatible-with={version}");
+                client.set_default_header("Content-Type", &media_type);
+                client.set_default_header("Accept", &media_type);
+            }
+            _ => client.set_default_header("Content-Type", "application/json"),
+        }
+        let mut server_url = config.url.clone();
+        let authorization = if let Some(api_key) = &config.api_key {
+            Some(format!("ApiKey {api_key}"))
+        } else {
+            config.username.as_ref().map(|username| {
+                format!(
+                    "Basic {}",
+                    general_purpose::STANDARD.encode(format!("{username}:{}", config.password.as_deref().unwrap_or_default()))
+                )
+            })
+        };
+        if let Some(authorization) = authorization {
+            // the credentials in the url would be sent as another authorization header
+            let _ = server_url.set_username("");
+            let _ = server_url.set_password(None);
+            client.set_default_header("Authorization", &authorization);
+        }
         info!("[Tardis.SearchClient] Initialized");
-        TardisResult::Ok(TardisSearchClient { client, server_url: url.clone() })
+        TardisResult::Ok(TardisSearchClient {
+            client,
+            server_url,
+            bulk_chunk_size: config.bulk_chunk_size,
+        })
     }
 
     fn get_url_with_path<'a>(&self, path: impl IntoIterator<Item = &'a str>) -> Url {
@@ -94,6 +326,22 @@ impl TardisSearchClient {
         url.path_segments_mut().expect("search server_url can't be a base").extend(path);
         url
     }
+    /// Check the cluster health, fails if the status is `red` / 检查集群健康状态，状态为 `red` 时失败
+    pub async fn health_check(&self) -> TardisResult<()> {
+        let url = self.get_url_with_path(["_cluster", "health"]);
+        let resp = self.client.get::<Value>(url, None).await?;
+        let status = resp.body.as_ref().and_then(|body| body.get("status")).and_then(|status| status.as_str()).unwrap_or_default().to_string();
+        if resp.code == 200 && status != "red" {
+            Ok(())
+        } else {
+            Err(TardisError::custom(
+                &resp.code.to_string(),
+                &format!("[Tardis.SearchClient] Cluster is unhealthy, status:{status}"),
+                "-1-tardis-search-unhealthy",
+            ))
+        }
+    }
+
     /// Create index / 创建索引
     ///
     /// # Arguments
@@ -107,17 +355,20 @@ impl TardisSearchClient {
     /// ```
     pub async fn create_index(&self, index_name: &str, mappings: Option<&str>) -> TardisResult<()> {
         trace!("[Tardis.SearchClient] Creating index: {}", index_name);
-        let url = self.get_url_with_path(Some(index_name));
-        let resp = self.client.put_str_to_str(url, mappings.unwrap_or_default(), None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            Ok(())
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Create index error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
-        }
+        observe_client("search", "create_index", async {
+            let url = self.get_url_with_path(Some(index_name));
+            let resp = self.client.put_str_to_str(url, mappings.unwrap_or_default(), None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Create index error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// Create record and return primary key value  / 创建记录并返回主键值
@@ -134,18 +385,26 @@ impl TardisSearchClient {
     /// ```
     pub async fn create_record(&self, index_name: &str, data: &str) -> TardisResult<String> {
         trace!("[Tardis.SearchClient] Creating record: {}, data:{}", index_name, data);
-        let url = self.get_url_with_path([index_name, "_doc"]);
-        let resp = self.client.post_str_to_str(url, data, None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            let result = TardisFuns::json.str_to_json(&resp.body.unwrap_or_default())?;
-            Ok(result["_id"].as_str().ok_or_else(|| TardisError::bad_request("[Tardis.SearchClient] [_id] structure not found", "400-tardis-search-id-not-exist"))?.to_string())
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Create record error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
-        }
+        observe_client("search", "create_record", async {
+            let url = self.get_url_with_path([index_name, "_doc"]);
+            let resp = self.client.post_str_to_str(url, data, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                let result = TardisFuns::json.str_to_json(&resp.body.unwrap_or_default())?;
+                Ok(
+                    result["_id"]
+                        .as_str()
+                        .ok_or_else(|| TardisError::bad_request("[Tardis.SearchClient] [_id] structure not found", "400-tardis-search-id-not-exist"))?
+                        .to_string(),
+                )
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Create record error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// Get a record  / 获取一条记录
@@ -162,18 +421,21 @@ impl TardisSearchClient {
     /// ```
     pub async fn get_record(&self, index_name: &str, id: &str) -> TardisResult<String> {
         trace!("[Tardis.SearchClient] Getting record: {}, id:{}", index_name, id);
-        let url = self.get_url_with_path([index_name, "_doc", id]);
-        let resp = self.client.get_to_str(url, None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            let result = TardisFuns::json.str_to_json(&resp.body.unwrap_or_default())?;
-            Ok(result["_source"].to_string())
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Get record error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
-        }
+        observe_client("search", "get_record", async {
+            let url = self.get_url_with_path([index_name, "_doc", id]);
+            let resp = self.client.get_to_str(url, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                let result = TardisFuns::json.str_to_json(&resp.body.unwrap_or_default())?;
+                Ok(result["_source"].to_string())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Get record error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// Simple (global) search  / 简单（全局）搜索
@@ -190,18 +452,21 @@ impl TardisSearchClient {
     /// ```
     pub async fn simple_search(&self, index_name: &str, q: &str) -> TardisResult<Vec<String>> {
         trace!("[Tardis.SearchClient] Simple search: {}, q:{}", index_name, q);
-        let mut url = self.get_url_with_path([index_name, "_search"]);
-        url.query_pairs_mut().append_pair("q", q);
-        let resp = self.client.get_to_str(url, None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            Self::parse_search_result(&resp.body.unwrap_or_default())
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Simple search error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
-        }
+        observe_client("search", "simple_search", async {
+            let mut url = self.get_url_with_path([index_name, "_search"]);
+            url.query_pairs_mut().append_pair("q", q);
+            let resp = self.client.get_to_str(url, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Self::parse_search_result(&resp.body.unwrap_or_default())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Simple search error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// Specified fields search  / 指定字段搜索
@@ -223,12 +488,193 @@ impl TardisSearchClient {
     /// ```
     pub async fn multi_search(&self, index_name: &str, q: HashMap<&str, &str>) -> TardisResult<Vec<String>> {
         trace!("[Tardis.SearchClient] Multi search: {}, q:{:?}", index_name, q);
-        let q = q.into_iter().map(|(k, v)| format!(r#"{{"match": {{"{k}": "{v}"}}}}"#)).collect::<Vec<String>>().join(",");
-        let q = format!(r#"{{ "query": {{ "bool": {{ "must": [{q}]}}}}}}"#);
-        let result = self.raw_search(index_name, &q, None, None, None).await?.hits.hits.iter().map(|item| item._source.clone().to_string()).collect();
+        let query = q.into_iter().fold(SearchQueryBuilder::new(), |query, (k, v)| query.must(SearchQuery::matches(k, v)));
+        let result = self.search(index_name, &query).await?.hits.hits.iter().map(|item| item._source.clone().to_string()).collect();
         Ok(result)
     }
 
+    /// Search by the query builder  / 使用查询构建器搜索
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `query` -  query builder / 查询构建器
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::search::search_query::{SearchQuery, SearchQueryBuilder};
+    /// use tardis::TardisFuns;
+    /// let query = SearchQueryBuilder::new().must(SearchQuery::term("user.id", 1)).must_not(SearchQuery::exists("user.deleted"));
+    /// let users = TardisFuns::search().search("test_index", &query).await?.records::<User>()?;
+    /// ```
+    pub async fn search(&self, index_name: &str, query: &SearchQueryBuilder) -> TardisResult<TardisRawSearchResp> {
+        self.raw_search(index_name, &query.build().to_string(), None, None, None).await
+    }
+
+    /// Paged search  / 分页搜索
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `query` -  query builder / 查询构建器
+    ///  * `page_number` -  page number, starting from 1 / 页码，从1开始
+    ///  * `page_size` -  page size / 每页记录数
+    ///  * `sort` -  sort fields and whether ascending / 排序字段及是否升序
+    ///
+    /// The total size is counted accurately, only the first 10,000 hits can be paged by default, use [`search_after`](Self::search_after) for more.
+    ///
+    /// 总记录数精确统计，默认仅能分页前10000条，更多记录请使用 [`search_after`](Self::search_after).
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::search::search_query::{SearchQuery, SearchQueryBuilder};
+    /// use tardis::TardisFuns;
+    /// let page = TardisFuns::search().search_paged::<User>("test_index", &SearchQueryBuilder::new(), 1, 10, &[("user.id", true)]).await?;
+    /// ```
+    pub async fn search_paged<T: DeserializeOwned>(
+        &self,
+        index_name: &str,
+        query: &SearchQueryBuilder,
+        page_number: u64,
+        page_size: u64,
+        sort: &[(&str, bool)],
+    ) -> TardisResult<TardisSearchPage<T>> {
+        let query = sort
+            .iter()
+            .fold(query.clone(), |query, (field, asc)| query.sort(field, *asc))
+            .from(page_number.max(1).saturating_sub(1).saturating_mul(page_size))
+            .size(page_size)
+            .track_total_hits(true);
+        let resp = self.search(index_name, &query).await?;
+        Ok(TardisSearchPage {
+            page_size,
+            page_number,
+            total_size: resp.hits.total.value.max(0) as u64,
+            records: resp.records()?,
+        })
+    }
+
+    /// Iterate over all the hits by scroll requests  / 通过滚动请求遍历所有命中记录
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `query` -  query builder, the paging is ignored / 查询构建器，忽略分页
+    ///  * `batch_size` -  hits per request / 每次请求的命中记录数
+    ///  * `keep_alive` -  keep alive time of the scroll context, E.g. `1m` / 滚动上下文的保持时间，如 `1m`
+    ///
+    /// The scroll context is released by Elasticsearch after the keep alive time.
+    ///
+    /// 滚动上下文在保持时间后由Elasticsearch释放.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::futures::{pin_mut, StreamExt};
+    /// use tardis::search::search_query::SearchQueryBuilder;
+    /// use tardis::TardisFuns;
+    /// let query = SearchQueryBuilder::new();
+    /// let users = TardisFuns::search().scroll::<User>("test_index", &query, 1000, "1m");
+    /// pin_mut!(users);
+    /// while let Some(user) = users.next().await {
+    ///     let user = user?;
+    /// }
+    /// ```
+    #[cfg(feature = "future")]
+    pub fn scroll<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        index_name: &'a str,
+        query: &SearchQueryBuilder,
+        batch_size: u64,
+        keep_alive: &'a str,
+    ) -> impl futures::Stream<Item = TardisResult<T>> + 'a {
+        let query = query.clone().size(batch_size.max(1)).build().to_string();
+        async_stream::try_stream! {
+            let mut url = self.get_url_with_path([index_name, "_search"]);
+            url.query_pairs_mut().append_pair("scroll", keep_alive);
+            let mut resp = self.search_by_url("scroll", url, &query).await?;
+            while !resp.hits.hits.is_empty() {
+                for item in std::mem::take(&mut resp.hits.hits) {
+                    yield item.source::<T>()?;
+                }
+                let scroll_id = resp._scroll_id.take().ok_or_else(|| TardisError::format_error("[Tardis.SearchClient] [_scroll_id] structure not found", "406-tardis-search-scroll-id-not-exist"))?;
+                let q = json!({ "scroll": keep_alive, "scroll_id": scroll_id }).to_string();
+                resp = self.search_by_url("scroll", self.get_url_with_path(["_search", "scroll"]), &q).await?;
+            }
+        }
+    }
+
+    /// Iterate over all the hits by `search_after` requests  / 通过 `search_after` 请求遍历所有命中记录
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `query` -  query builder, must be sorted by fields with unique values in combination / 查询构建器，必须按组合值唯一的字段排序
+    ///  * `batch_size` -  hits per request / 每次请求的命中记录数
+    ///
+    /// Unlike [`scroll`](Self::scroll), there's no context kept on the server, the changes during the iteration are visible.
+    ///
+    /// 与 [`scroll`](Self::scroll) 不同，服务端不保持上下文，遍历期间的变更可见.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::futures::{pin_mut, StreamExt};
+    /// use tardis::search::search_query::SearchQueryBuilder;
+    /// use tardis::TardisFuns;
+    /// let query = SearchQueryBuilder::new().sort("user.id", true);
+    /// let users = TardisFuns::search().search_after::<User>("test_index", &query, 1000);
+    /// pin_mut!(users);
+    /// while let Some(user) = users.next().await {
+    ///     let user = user?;
+    /// }
+    /// ```
+    #[cfg(feature = "future")]
+    pub fn search_after<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        index_name: &'a str,
+        query: &SearchQueryBuilder,
+        batch_size: u64,
+    ) -> impl futures::Stream<Item = TardisResult<T>> + 'a {
+        let query = query.clone().size(batch_size.max(1));
+        async_stream::try_stream! {
+            let mut search_after = None;
+            loop {
+                let page_query = match search_after.take() {
+                    Some(sort_values) => query.clone().search_after(sort_values),
+                    None => query.clone(),
+                };
+                let resp = self.search(index_name, &page_query).await?;
+                let count = resp.hits.hits.len() as u64;
+                for item in resp.hits.hits {
+                    search_after = item.sort.clone();
+                    yield item.source::<T>()?;
+                }
+                if count < batch_size.max(1) {
+                    break;
+                }
+                search_after
+                    .as_ref()
+                    .ok_or_else(|| TardisError::bad_request("[Tardis.SearchClient] The query of search_after must be sorted", "400-tardis-search-sort-required"))?;
+            }
+        }
+    }
+
+    #[cfg(feature = "future")]
+    async fn search_by_url(&self, operation: &str, url: Url, q: &str) -> TardisResult<TardisRawSearchResp> {
+        observe_client("search", operation, async {
+            let resp = self.client.post_str_to_str(url, q, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Ok(TardisFuns::json.str_to_obj(&resp.body.unwrap_or_default())?)
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Search error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
+    }
+
     /// Search using native format  / 使用原生格式搜索
     ///
     /// # Arguments
@@ -248,28 +694,31 @@ impl TardisSearchClient {
             from,
             track_scores
         );
-        let mut url = self.server_url.clone();
-        url.path_segments_mut().expect("search server_url can't be a base").extend([index_name, "_search"]);
-        if let Some(size) = size {
-            url.query_pairs_mut().append_pair("size", &size.to_string());
-        }
-        if let Some(from) = from {
-            url.query_pairs_mut().append_pair("from", &from.to_string());
-        }
-        if let Some(track_scores) = track_scores {
-            url.query_pairs_mut().append_pair("track_scores", &track_scores.to_string());
-        }
-        let resp = self.client.post_str_to_str(url, q, None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            trace!("[Tardis.SearchClient] resp.body: {:?}", &resp.body);
-            Ok(TardisFuns::json.str_to_obj(&resp.body.unwrap_or_default())?)
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Raw search error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
-        }
+        observe_client("search", "raw_search", async {
+            let mut url = self.server_url.clone();
+            url.path_segments_mut().expect("search server_url can't be a base").extend([index_name, "_search"]);
+            if let Some(size) = size {
+                url.query_pairs_mut().append_pair("size", &size.to_string());
+            }
+            if let Some(from) = from {
+                url.query_pairs_mut().append_pair("from", &from.to_string());
+            }
+            if let Some(track_scores) = track_scores {
+                url.query_pairs_mut().append_pair("track_scores", &track_scores.to_string());
+            }
+            let resp = self.client.post_str_to_str(url, q, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                trace!("[Tardis.SearchClient] resp.body: {:?}", &resp.body);
+                Ok(TardisFuns::json.str_to_obj(&resp.body.unwrap_or_default())?)
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Raw search error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// check index exist  / 检查索引是否存在
@@ -285,17 +734,249 @@ impl TardisSearchClient {
     /// ```
     pub async fn check_index_exist(&self, index_name: &str) -> TardisResult<bool> {
         trace!("[Tardis.SearchClient] Check index exist: {}", index_name);
-        let url = self.get_url_with_path([index_name]);
-        let resp = self.client.head_to_void(url, None).await?;
-        match resp.code {
-            200 => Ok(true),
-            404 => Ok(false),
-            _ => Err(TardisError::custom(
-                &resp.code.to_string(),
-                "[Tardis.SearchClient] Check index exist request failed",
-                "-1-tardis-search-error",
-            )),
+        observe_client("search", "check_index_exist", async {
+            let url = self.get_url_with_path([index_name]);
+            let resp = self.client.head_to_void(url, None).await?;
+            match resp.code {
+                200 => Ok(true),
+                404 => Ok(false),
+                _ => Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    "[Tardis.SearchClient] Check index exist request failed",
+                    "-1-tardis-search-error",
+                )),
+            }
+        })
+        .await
+    }
+
+    /// Create index with mappings and settings / 使用映射及设置创建索引
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `mappings` -  native format of the mappings / 原生格式的映射
+    ///  * `settings` -  native format of the settings / 原生格式的设置
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().create_index_with_mapping("test_index", Some(r#"{"properties":{"name":{"type":"keyword"}}}"#), Some(r#"{"number_of_shards":1}"#)).await.unwrap();
+    /// ```
+    pub async fn create_index_with_mapping(&self, index_name: &str, mappings: Option<&str>, settings: Option<&str>) -> TardisResult<()> {
+        let mut body = serde_json::Map::new();
+        if let Some(mappings) = mappings {
+            body.insert("mappings".to_string(), TardisFuns::json.str_to_json(mappings)?);
+        }
+        if let Some(settings) = settings {
+            body.insert("settings".to_string(), TardisFuns::json.str_to_json(settings)?);
         }
+        self.create_index(index_name, Some(&Value::Object(body).to_string())).await
+    }
+
+    /// Add fields to the mappings of the index / 向索引的映射添加字段
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `mappings` -  native format of the mappings / 原生格式的映射
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().put_mapping("test_index", r#"{"properties":{"desc":{"type":"text"}}}"#).await.unwrap();
+    /// ```
+    pub async fn put_mapping(&self, index_name: &str, mappings: &str) -> TardisResult<()> {
+        trace!("[Tardis.SearchClient] Putting mapping: {}, mappings:{}", index_name, mappings);
+        observe_client("search", "put_mapping", async {
+            let url = self.get_url_with_path([index_name, "_mapping"]);
+            let resp = self.client.put_str_to_str(url, mappings, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Put mapping error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
+    }
+
+    /// Get the indices of the alias / 获取别名指向的索引
+    ///
+    /// # Arguments
+    ///
+    ///  * `alias` -  alias name / 别名
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let indices = TardisFuns::search().get_alias_indices("test_alias").await.unwrap();
+    /// ```
+    pub async fn get_alias_indices(&self, alias: &str) -> TardisResult<Vec<String>> {
+        trace!("[Tardis.SearchClient] Getting alias indices: {}", alias);
+        observe_client("search", "get_alias_indices", async {
+            let url = self.get_url_with_path(["_alias", alias]);
+            let resp = self.client.get_to_str(url, None).await?;
+            match resp.code {
+                200 => {
+                    let result = TardisFuns::json.str_to_json(&resp.body.unwrap_or_default())?;
+                    let mut indices = result.as_object().map(|indices| indices.keys().cloned().collect::<Vec<_>>()).unwrap_or_default();
+                    indices.sort();
+                    Ok(indices)
+                }
+                404 => Ok(Vec::new()),
+                _ => Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Get alias indices error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                )),
+            }
+        })
+        .await
+    }
+
+    /// Add the alias to the index / 为索引添加别名
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `alias` -  alias name / 别名
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().create_alias("test_index_v1", "test_index").await.unwrap();
+    /// ```
+    pub async fn create_alias(&self, index_name: &str, alias: &str) -> TardisResult<()> {
+        self.update_aliases("create_alias", vec![json!({"add": {"index": index_name, "alias": alias}})]).await
+    }
+
+    /// Remove the alias from the index / 从索引移除别名
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `alias` -  alias name / 别名
+    pub async fn delete_alias(&self, index_name: &str, alias: &str) -> TardisResult<()> {
+        self.update_aliases("delete_alias", vec![json!({"remove": {"index": index_name, "alias": alias}})]).await
+    }
+
+    /// Point the alias to the index atomically, the alias is removed from the other indices / 原子地将别名指向索引，别名会从其它索引移除
+    ///
+    /// Used to switch to a rebuilt index without downtime.
+    ///
+    /// 用于无停机地切换到重建的索引.
+    ///
+    /// # Arguments
+    ///
+    ///  * `alias` -  alias name / 别名
+    ///  * `index_name` -  index name / 索引名称
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().switch_alias("test_index", "test_index_v2").await.unwrap();
+    /// ```
+    pub async fn switch_alias(&self, alias: &str, index_name: &str) -> TardisResult<()> {
+        let mut actions = self
+            .get_alias_indices(alias)
+            .await?
+            .into_iter()
+            .filter(|index| index != index_name)
+            .map(|index| json!({"remove": {"index": index, "alias": alias}}))
+            .collect::<Vec<_>>();
+        actions.push(json!({"add": {"index": index_name, "alias": alias}}));
+        self.update_aliases("switch_alias", actions).await
+    }
+
+    async fn update_aliases(&self, operation: &str, actions: Vec<Value>) -> TardisResult<()> {
+        let q = json!({ "actions": actions }).to_string();
+        trace!("[Tardis.SearchClient] Updating aliases: {}", q);
+        observe_client("search", operation, async {
+            let url = self.get_url_with_path(["_aliases"]);
+            let resp = self.client.post_str_to_str(url, &q, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Update aliases error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
+    }
+
+    /// Create or replace the index template / 创建或替换索引模板
+    ///
+    /// # Arguments
+    ///
+    ///  * `template_name` -  template name / 模板名称
+    ///  * `template` -  native format of the template / 原生格式的模板
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().put_index_template("log_template", r#"{"index_patterns":["log-*"],"template":{"mappings":{"properties":{"msg":{"type":"text"}}}}}"#).await.unwrap();
+    /// ```
+    pub async fn put_index_template(&self, template_name: &str, template: &str) -> TardisResult<()> {
+        trace!("[Tardis.SearchClient] Putting index template: {}, template:{}", template_name, template);
+        observe_client("search", "put_index_template", async {
+            let url = self.get_url_with_path(["_index_template", template_name]);
+            let resp = self.client.put_str_to_str(url, template, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Put index template error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
+    }
+
+    /// check index template exist / 检查索引模板是否存在
+    pub async fn check_index_template_exist(&self, template_name: &str) -> TardisResult<bool> {
+        trace!("[Tardis.SearchClient] Check index template exist: {}", template_name);
+        observe_client("search", "check_index_template_exist", async {
+            let url = self.get_url_with_path(["_index_template", template_name]);
+            let resp = self.client.head_to_void(url, None).await?;
+            match resp.code {
+                200 => Ok(true),
+                404 => Ok(false),
+                _ => Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    "[Tardis.SearchClient] Check index template exist request failed",
+                    "-1-tardis-search-error",
+                )),
+            }
+        })
+        .await
+    }
+
+    /// Delete the index template / 删除索引模板
+    pub async fn delete_index_template(&self, template_name: &str) -> TardisResult<()> {
+        trace!("[Tardis.SearchClient] Deleting index template: {}", template_name);
+        observe_client("search", "delete_index_template", async {
+            let url = self.get_url_with_path(["_index_template", template_name]);
+            let resp = self.client.delete_to_void(url, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    "[Tardis.SearchClient] Delete index template request failed",
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// update record / 更新记录
@@ -312,30 +993,33 @@ impl TardisSearchClient {
     /// TardisFuns::search().update("test_index", "111", HashMap::from([("user.id", "1"), ("user.name", "李四")])).await.unwrap();
     /// ```
     pub async fn update(&self, index_name: &str, id: &str, q: HashMap<String, String>) -> TardisResult<()> {
-        let mut source_vec = vec![];
-        let mut params_vec = vec![];
-        for (key, value) in q {
-            let param_key = key.replace('.', "_");
-            source_vec.push(format!(r#"ctx._source.{key}= params.{param_key}"#));
-            params_vec.push(format!(r#""{param_key}": {value}"#));
-        }
-        let source = source_vec.join(";");
-        let params = params_vec.join(",");
-        let q = format!(r#"{{ "script": {{"source": "{source}", "params":{{{params}}}}}}}"#);
-        debug!("[Tardis.SearchClient] Update: {}, q:{}", index_name, q);
-        let mut url = self.get_url_with_path([index_name, "_update", id]);
-        url.query_pairs_mut().append_pair("refresh", "true");
-        let resp = self.client.post_str_to_str(url, &q, None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            trace!("[Tardis.SearchClient] resp.body: {:?}", &resp.body);
-            Ok(())
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Update error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
-        }
+        observe_client("search", "update", async {
+            let mut source_vec = vec![];
+            let mut params_vec = vec![];
+            for (key, value) in q {
+                let param_key = key.replace('.', "_");
+                source_vec.push(format!(r#"ctx._source.{key}= params.{param_key}"#));
+                params_vec.push(format!(r#""{param_key}": {value}"#));
+            }
+            let source = source_vec.join(";");
+            let params = params_vec.join(",");
+            let q = format!(r#"{{ "script": {{"source": "{source}", "params":{{{params}}}}}}}"#);
+            debug!("[Tardis.SearchClient] Update: {}, q:{}", index_name, q);
+            let mut url = self.get_url_with_path([index_name, "_update", id]);
+            url.query_pairs_mut().append_pair("refresh", "true");
+            let resp = self.client.post_str_to_str(url, &q, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                trace!("[Tardis.SearchClient] resp.body: {:?}", &resp.body);
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Update error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
     }
 
     /// Delete record / 删除记录
@@ -351,18 +1035,141 @@ impl TardisSearchClient {
     /// TardisFuns::search().delete_by_query("test_index" r#"{}"#).await.unwrap();
     /// ```
     pub async fn delete_by_query(&self, index_name: &str, q: &str) -> TardisResult<()> {
-        let url = self.get_url_with_path([index_name, "_delete_by_query"]);
-        let resp = self.client.post_str_to_str(url, q, None).await?;
-        if resp.code >= 200 && resp.code <= 300 {
-            debug!("[Tardis.SearchClient] resp.body: {:?}", &resp.body);
-            Ok(())
-        } else {
-            Err(TardisError::custom(
-                &resp.code.to_string(),
-                &format!("[Tardis.SearchClient] Delete by query error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
-                "-1-tardis-search-error",
-            ))
+        observe_client("search", "delete_by_query", async {
+            let url = self.get_url_with_path([index_name, "_delete_by_query"]);
+            let resp = self.client.post_str_to_str(url, q, None).await?;
+            if resp.code >= 200 && resp.code <= 300 {
+                debug!("[Tardis.SearchClient] resp.body: {:?}", &resp.body);
+                Ok(())
+            } else {
+                Err(TardisError::custom(
+                    &resp.code.to_string(),
+                    &format!("[Tardis.SearchClient] Delete by query error: {}", resp.body.as_ref().unwrap_or(&"".to_string())),
+                    "-1-tardis-search-error",
+                ))
+            }
+        })
+        .await
+    }
+
+    /// Create records in bulk / 批量创建记录
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `data` -  record contents / 记录内容集合
+    ///
+    /// The records are sent by `_bulk` requests of at most `bulk_chunk_size` records, the failed records are reported in the result.
+    ///
+    /// 记录通过每次最多 `bulk_chunk_size` 条的 `_bulk` 请求发送，失败的记录在结果中返回.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let resp = TardisFuns::search().bulk_create("test_index", &[r#"{"user":{"id":1,"name":"张三"}}"#, r#"{"user":{"id":2,"name":"李四"}}"#]).await.unwrap();
+    /// assert!(resp.is_success());
+    /// ```
+    pub async fn bulk_create(&self, index_name: &str, data: &[&str]) -> TardisResult<TardisSearchBulkResp> {
+        trace!("[Tardis.SearchClient] Bulk creating records: {}, count:{}", index_name, data.len());
+        let operations = data.iter().map(|data| Ok((json!({"index": {}}), Some(TardisFuns::json.str_to_json(data)?)))).collect::<TardisResult<Vec<_>>>()?;
+        observe_client("search", "bulk_create", self.bulk(index_name, operations)).await
+    }
+
+    /// Update records in bulk / 批量更新记录
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `data` -  record primary key values and the partial contents to merge / 记录主键值及要合并的部分内容
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().bulk_update("test_index", &[("111", r#"{"user":{"name":"李四"}}"#)]).await.unwrap();
+    /// ```
+    pub async fn bulk_update(&self, index_name: &str, data: &[(&str, &str)]) -> TardisResult<TardisSearchBulkResp> {
+        trace!("[Tardis.SearchClient] Bulk updating records: {}, count:{}", index_name, data.len());
+        let operations =
+            data.iter().map(|(id, data)| Ok((json!({"update": {"_id": id}}), Some(json!({"doc": TardisFuns::json.str_to_json(data)?}))))).collect::<TardisResult<Vec<_>>>()?;
+        observe_client("search", "bulk_update", self.bulk(index_name, operations)).await
+    }
+
+    /// Delete records in bulk / 批量删除记录
+    ///
+    /// # Arguments
+    ///
+    ///  * `index_name` -  index name / 索引名称
+    ///  * `ids` -  record primary key values / 记录主键值集合
+    ///
+    /// The records not found are reported as failures with status `404`.
+    ///
+    /// 不存在的记录以状态码 `404` 的失败返回.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::search().bulk_delete("test_index", &["111", "222"]).await.unwrap();
+    /// ```
+    pub async fn bulk_delete(&self, index_name: &str, ids: &[&str]) -> TardisResult<TardisSearchBulkResp> {
+        trace!("[Tardis.SearchClient] Bulk deleting records: {}, count:{}", index_name, ids.len());
+        let operations = ids.iter().map(|id| (json!({"delete": {"_id": id}}), None)).collect();
+        observe_client("search", "bulk_delete", self.bulk(index_name, operations)).await
+    }
+
+    /// Send the operations (action and optional source) by chunks, the chunk failed with a non-2xx status is reported as failed records
+    /// / 分块发送操作（动作及可选的内容），返回非2xx状态码的块作为失败记录返回
+    async fn bulk(&self, index_name: &str, operations: Vec<(Value, Option<Value>)>) -> TardisResult<TardisSearchBulkResp> {
+        let url = self.get_url_with_path([index_name, "_bulk"]);
+        let chunk_size = self.bulk_chunk_size.max(1);
+        let mut result = TardisSearchBulkResp::default();
+        for (chunk_idx, chunk) in operations.chunks(chunk_size).enumerate() {
+            let offset = chunk_idx * chunk_size;
+            let mut body = String::new();
+            for (action, source) in chunk {
+                body.push_str(&action.to_string());
+                body.push('\n');
+                if let Some(source) = source {
+                    body.push_str(&source.to_string());
+                    body.push('\n');
+                }
+            }
+            let resp = self.client.post_str_to_str(url.clone(), &body, None).await?;
+            if resp.code < 200 || resp.code > 300 {
+                let reason = format!("[Tardis.SearchClient] Bulk error: {}", resp.body.unwrap_or_default());
+                result.failures.extend(chunk.iter().enumerate().map(|(idx, (action, _))| TardisSearchBulkFailure {
+                    idx: offset + idx,
+                    id: action.as_object().and_then(|action| action.values().next()).and_then(|meta| meta["_id"].as_str()).map(str::to_string),
+                    status: resp.code,
+                    reason: reason.clone(),
+                }));
+                continue;
+            }
+            let resp = TardisFuns::json.str_to_json(&resp.body.unwrap_or_default())?;
+            let items =
+                resp["items"].as_array().ok_or_else(|| TardisError::format_error("[Tardis.SearchClient] [items] structure not found", "406-tardis-search-items-not-exist"))?;
+            for (idx, item) in items.iter().enumerate() {
+                let Some(item) = item.as_object().and_then(|item| item.values().next()) else {
+                    continue;
+                };
+                let id = item["_id"].as_str().map(str::to_string);
+                let status = item["status"].as_u64().unwrap_or_default() as u16;
+                if item["error"].is_null() && (200..300).contains(&status) {
+                    result.ids.extend(id);
+                } else {
+                    let reason = item["error"]["reason"].as_str().or_else(|| item["result"].as_str()).map(str::to_string).unwrap_or_else(|| item["error"].to_string());
+                    result.failures.push(TardisSearchBulkFailure {
+                        idx: offset + idx,
+                        id,
+                        status,
+                        reason,
+                    });
+                }
+            }
         }
+        if !result.is_success() {
+            debug!("[Tardis.SearchClient] Bulk failures: {}, {:?}", index_name, result.failures);
+        }
+        Ok(result)
     }
 
     fn parse_search_result(result: &str) -> TardisResult<Vec<String>> {
@@ -0,0 +1,300 @@
+use serde_json::{json, Map, Value};
+
+/// Query clause of Elasticsearch / Elasticsearch的查询子句
+///
+/// # Examples
+/// ```ignore
+/// use tardis::search::search_query::{SearchQuery, SearchRange};
+/// SearchQuery::term("user.id", 1);
+/// SearchQuery::matches("user.name", "张三");
+/// SearchQuery::range("user.age", SearchRange::new().gte(18).lt(60));
+/// SearchQuery::nested("orders", SearchQuery::term("orders.status", "paid"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery(Value);
+
+impl SearchQuery {
+    /// Exact value match / 精确值匹配
+    pub fn term(field: &str, value: impl Into<Value>) -> Self {
+        SearchQuery(json!({ "term": { field: value.into() } }))
+    }
+
+    /// Match any of the exact values / 匹配任一精确值
+    pub fn terms<V: Into<Value>>(field: &str, values: impl IntoIterator<Item = V>) -> Self {
+        SearchQuery(json!({ "terms": { field: values.into_iter().map(Into::into).collect::<Vec<Value>>() } }))
+    }
+
+    /// Full text match, the `match` query / 全文匹配，即 `match` 查询
+    pub fn matches(field: &str, value: impl Into<Value>) -> Self {
+        SearchQuery(json!({ "match": { field: value.into() } }))
+    }
+
+    pub fn range(field: &str, range: SearchRange) -> Self {
+        SearchQuery(json!({ "range": { field: Value::Object(range.0) } }))
+    }
+
+    /// The field has a non-null value / 字段存在非空值
+    pub fn exists(field: &str) -> Self {
+        SearchQuery(json!({ "exists": { "field": field } }))
+    }
+
+    /// Query on the nested objects of the path / 对路径下的嵌套对象进行查询
+    pub fn nested(path: &str, query: SearchQuery) -> Self {
+        SearchQuery(json!({ "nested": { "path": path, "query": query.0 } }))
+    }
+
+    /// Compound query of the clauses of the builder, the paging and sorting of the builder are ignored
+    /// / 由构建器子句组成的复合查询，忽略构建器的分页及排序
+    pub fn bool(builder: SearchQueryBuilder) -> Self {
+        SearchQuery(builder.build_query())
+    }
+
+    /// Native format of the query clause / 原生格式的查询子句
+    pub fn raw(query: Value) -> Self {
+        SearchQuery(query)
+    }
+
+    pub fn to_json(&self) -> &Value {
+        &self.0
+    }
+}
+
+/// Bounds of the range query / 范围查询的边界
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchRange(Map<String, Value>);
+
+impl SearchRange {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn gt(self, value: impl Into<Value>) -> Self {
+        self.bound("gt", value)
+    }
+
+    pub fn gte(self, value: impl Into<Value>) -> Self {
+        self.bound("gte", value)
+    }
+
+    pub fn lt(self, value: impl Into<Value>) -> Self {
+        self.bound("lt", value)
+    }
+
+    pub fn lte(self, value: impl Into<Value>) -> Self {
+        self.bound("lte", value)
+    }
+
+    fn bound(mut self, op: &str, value: impl Into<Value>) -> Self {
+        self.0.insert(op.to_string(), value.into());
+        self
+    }
+}
+
+/// Builder of the search request, the clauses are combined by the `bool` query / 搜索请求构建器，子句通过 `bool` 查询组合
+///
+/// # Examples
+/// ```ignore
+/// use tardis::search::search_query::{SearchQuery, SearchQueryBuilder, SearchRange};
+/// use tardis::TardisFuns;
+/// let query = SearchQueryBuilder::new()
+///     .must(SearchQuery::matches("user.name", "李四"))
+///     .filter(SearchQuery::range("user.age", SearchRange::new().gte(18)))
+///     .must_not(SearchQuery::term("user.open", false))
+///     .sort("user.id", true)
+///     .size(10);
+/// let users = TardisFuns::search().search("test_index", &query).await?.records::<User>()?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQueryBuilder {
+    must: Vec<Value>,
+    should: Vec<Value>,
+    filter: Vec<Value>,
+    must_not: Vec<Value>,
+    minimum_should_match: Option<u32>,
+    sort: Vec<Value>,
+    size: Option<u64>,
+    from: Option<u64>,
+    track_total_hits: Option<bool>,
+    search_after: Option<Vec<Value>>,
+    aggs: Map<String, Value>,
+}
+
+impl SearchQueryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The clause must match and contributes to the score / 必须匹配且参与评分
+    pub fn must(mut self, query: SearchQuery) -> Self {
+        self.must.push(query.0);
+        self
+    }
+
+    /// The clause should match, at least one of them if there are no `must` or `filter` clauses
+    /// / 应当匹配，没有 `must` 或 `filter` 子句时至少匹配一个
+    pub fn should(mut self, query: SearchQuery) -> Self {
+        self.should.push(query.0);
+        self
+    }
+
+    /// The clause must match without scoring / 必须匹配且不参与评分
+    pub fn filter(mut self, query: SearchQuery) -> Self {
+        self.filter.push(query.0);
+        self
+    }
+
+    /// The clause must not match / 必须不匹配
+    pub fn must_not(mut self, query: SearchQuery) -> Self {
+        self.must_not.push(query.0);
+        self
+    }
+
+    pub fn minimum_should_match(mut self, minimum_should_match: u32) -> Self {
+        self.minimum_should_match = Some(minimum_should_match);
+        self
+    }
+
+    pub fn sort(mut self, field: &str, asc: bool) -> Self {
+        self.sort.push(json!({ field: { "order": if asc { "asc" } else { "desc" } } }));
+        self
+    }
+
+    pub fn size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn from(mut self, from: u64) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Count the total hits accurately, by default it's accurate up to 10,000 hits / 精确统计总命中数，默认仅精确到10000条
+    pub fn track_total_hits(mut self, track_total_hits: bool) -> Self {
+        self.track_total_hits = Some(track_total_hits);
+        self
+    }
+
+    /// Start after the sort values of the last hit of the previous page / 从上一页最后一条命中记录的排序值之后开始
+    pub fn search_after(mut self, sort_values: Vec<Value>) -> Self {
+        self.search_after = Some(sort_values);
+        self
+    }
+
+    /// Add a named aggregation, set `size` to `0` if only the aggregations are needed / 添加命名的聚合，仅需聚合结果时将 `size` 设置为 `0`
+    pub fn aggregation(mut self, name: &str, aggregation: SearchAggregation) -> Self {
+        self.aggs.insert(name.to_string(), aggregation.build());
+        self
+    }
+
+    /// The `query` part of the request, `match_all` is used if there are no clauses / 请求的 `query` 部分，没有子句时使用 `match_all`
+    pub fn build_query(&self) -> Value {
+        let mut bool_query = Map::new();
+        for (occur, clauses) in [("must", &self.must), ("should", &self.should), ("filter", &self.filter), ("must_not", &self.must_not)] {
+            if !clauses.is_empty() {
+                bool_query.insert(occur.to_string(), Value::Array(clauses.clone()));
+            }
+        }
+        if bool_query.is_empty() {
+            return json!({ "match_all": {} });
+        }
+        if let Some(minimum_should_match) = self.minimum_should_match {
+            bool_query.insert("minimum_should_match".to_string(), minimum_should_match.into());
+        }
+        json!({ "bool": bool_query })
+    }
+
+    /// The request body used by [`raw_search`](crate::search::search_client::TardisSearchClient::raw_search)
+    /// / [`raw_search`](crate::search::search_client::TardisSearchClient::raw_search) 使用的请求体
+    pub fn build(&self) -> Value {
+        let mut body = Map::new();
+        body.insert("query".to_string(), self.build_query());
+        if !self.sort.is_empty() {
+            body.insert("sort".to_string(), Value::Array(self.sort.clone()));
+        }
+        if let Some(size) = self.size {
+            body.insert("size".to_string(), size.into());
+        }
+        if let Some(from) = self.from {
+            body.insert("from".to_string(), from.into());
+        }
+        if let Some(track_total_hits) = self.track_total_hits {
+            body.insert("track_total_hits".to_string(), track_total_hits.into());
+        }
+        if let Some(search_after) = &self.search_after {
+            body.insert("search_after".to_string(), Value::Array(search_after.clone()));
+        }
+        if !self.aggs.is_empty() {
+            body.insert("aggs".to_string(), Value::Object(self.aggs.clone()));
+        }
+        Value::Object(body)
+    }
+}
+
+/// Aggregation of Elasticsearch, the bucket aggregations can be nested arbitrarily / Elasticsearch的聚合，桶聚合可任意嵌套
+///
+/// # Examples
+/// ```ignore
+/// use tardis::search::search_query::{SearchAggregation, SearchQueryBuilder};
+/// use tardis::TardisFuns;
+/// let query = SearchQueryBuilder::new().size(0).aggregation(
+///     "by_status",
+///     SearchAggregation::terms("status", 10).sub_aggregation("per_day", SearchAggregation::date_histogram("created_at", "day").sub_aggregation("amount", SearchAggregation::stats("amount"))),
+/// );
+/// let aggs = TardisFuns::search().search("order_index", &query).await?.aggs();
+/// for bucket in &aggs["by_status"].buckets {
+///     println!("{}: {}", bucket.key, bucket.doc_count);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchAggregation {
+    kind: String,
+    body: Value,
+    aggs: Map<String, Value>,
+}
+
+impl SearchAggregation {
+    /// Buckets of the top `size` values of the field / 字段值前 `size` 个的桶
+    pub fn terms(field: &str, size: u64) -> Self {
+        Self::raw("terms", json!({ "field": field, "size": size }))
+    }
+
+    /// Buckets of the date field by the calendar interval, E.g. `day`, `month` / 按日历间隔（如 `day` 、 `month` ）分组日期字段的桶
+    pub fn date_histogram(field: &str, calendar_interval: &str) -> Self {
+        Self::raw("date_histogram", json!({ "field": field, "calendar_interval": calendar_interval }))
+    }
+
+    /// Count, min, max, avg and sum of the field / 字段的数量、最小值、最大值、平均值及总和
+    pub fn stats(field: &str) -> Self {
+        Self::raw("stats", json!({ "field": field }))
+    }
+
+    /// Approximate count of the distinct values of the field / 字段不同值的近似数量
+    pub fn cardinality(field: &str) -> Self {
+        Self::raw("cardinality", json!({ "field": field }))
+    }
+
+    /// Native format of the aggregation, E.g. `raw("avg", json!({"field": "amount"}))` / 原生格式的聚合，如 `raw("avg", json!({"field": "amount"}))`
+    pub fn raw(kind: &str, body: Value) -> Self {
+        SearchAggregation {
+            kind: kind.to_string(),
+            body,
+            aggs: Map::new(),
+        }
+    }
+
+    /// Add a named aggregation computed for each bucket / 添加对每个桶计算的命名聚合
+    pub fn sub_aggregation(mut self, name: &str, aggregation: SearchAggregation) -> Self {
+        self.aggs.insert(name.to_string(), aggregation.build());
+        self
+    }
+
+    pub fn build(&self) -> Value {
+        let mut aggregation = Map::new();
+        aggregation.insert(self.kind.clone(), self.body.clone());
+        if !self.aggs.is_empty() {
+            aggregation.insert("aggs".to_string(), Value::Object(self.aggs.clone()));
+        }
+        Value::Object(aggregation)
+    }
+}
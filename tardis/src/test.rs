@@ -1 +1,14 @@
+#[cfg(feature = "reldb-core")]
+pub mod db_fixture;
+#[cfg(feature = "cache")]
+pub mod memory_cache;
+#[cfg(feature = "mq")]
+pub mod memory_mq;
+pub mod mock_clock;
+pub mod mock_server;
+pub mod snapshot;
 pub mod test_container;
+pub mod test_env;
+pub mod test_harness;
+#[cfg(feature = "web-server")]
+pub mod web_test_client;
@@ -0,0 +1,156 @@
+//! Database fixtures for tests / 测试用数据库夹具
+//!
+//! Loads entities, SQL or JSON fixtures into the test database inside a transaction,
+//! the transaction is rolled back when the fixture is dropped (even if the test panics), so each test starts with the same data,
+//! this also works with the reused containers of [`TardisTestContainer`](crate::test::test_container::TardisTestContainer).
+//!
+//! 在事务中加载实体、SQL或JSON夹具到测试数据库，夹具被drop时（即使测试panic）事务会回滚，从而每个测试都从相同的数据开始，
+//! 这同样适用于 [`TardisTestContainer`](crate::test::test_container::TardisTestContainer) 复用的容器.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::test::db_fixture::TardisDBFixture;
+//! use tardis::test::test_container::TardisTestContainer;
+//! TardisTestContainer::mysql(Some("tests/sql/init.sql"), |url| async move {
+//!     let fixture = TardisDBFixture::init(&url).await?;
+//!     fixture.load_sql_file("tests/fixtures/users.sql").await?;
+//!     fixture.load_json(r#"{"orders": [{"id": 1, "user_id": 1, "remark": null}]}"#).await?;
+//!     // operate with the transactional connection
+//!     let count = fixture.conn().count_by_sql("SELECT * FROM orders", vec![]).await?;
+//!     Ok(())
+//! })
+//! .await
+//! ```
+use std::path::Path;
+
+use sea_orm::sea_query::{Alias, Keyword, Query, SimpleExpr};
+use sea_orm::Value;
+use tracing::trace;
+
+use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::db::DBModuleConfig;
+use crate::db::migration::split_sql;
+use crate::db::reldb_client::{TardisActiveModel, TardisRelDBClient, TardisRelDBlConnection};
+use crate::TardisFuns;
+
+/// Transactional database fixture / 事务性数据库夹具
+pub struct TardisDBFixture {
+    conn: TardisRelDBlConnection,
+}
+
+impl TardisDBFixture {
+    /// Connect to the database url (e.g. provided by [`TardisTestContainer`](crate::test::test_container::TardisTestContainer)) and begin a transaction
+    /// / 连接到数据库url（如由 [`TardisTestContainer`](crate::test::test_container::TardisTestContainer) 提供）并开启事务
+    pub async fn init(url: &str) -> TardisResult<TardisDBFixture> {
+        let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(url).max_connections(2).min_connections(1).build()).await?;
+        Self::begin(&client).await
+    }
+
+    /// Begin a transaction on the client, e.g. `TardisFuns::reldb()` / 在客户端上开启事务，如 `TardisFuns::reldb()`
+    pub async fn begin(client: &TardisRelDBClient) -> TardisResult<TardisDBFixture> {
+        let mut conn = client.conn();
+        conn.begin().await?;
+        Ok(TardisDBFixture { conn })
+    }
+
+    /// The transactional connection, all the operations of the test should use it to see the fixtures
+    /// / 事务性连接，测试的所有操作都应使用它以看到夹具数据
+    pub fn conn(&self) -> &TardisRelDBlConnection {
+        &self.conn
+    }
+
+    /// Roll back explicitly, same as dropping the fixture / 显式回滚，与drop夹具相同
+    pub async fn rollback(self) -> TardisResult<()> {
+        self.conn.rollback().await
+    }
+
+    /// Load entities / 加载实体
+    pub async fn load_models<T>(&self, models: Vec<T>, ctx: &TardisContext) -> TardisResult<()>
+    where
+        T: TardisActiveModel,
+    {
+        self.conn.insert_many(models, ctx).await
+    }
+
+    /// Load SQL statements separated by `;` / 加载以 `;` 分隔的SQL语句
+    pub async fn load_sql(&self, sql: &str) -> TardisResult<()> {
+        for statement in split_sql(sql) {
+            trace!("[Tardis.DBFixture] Execute: {statement}");
+            self.conn.execute_one(&statement, vec![]).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn load_sql_file(&self, path: impl AsRef<Path>) -> TardisResult<()> {
+        self.load_sql(&std::fs::read_to_string(path)?).await
+    }
+
+    /// Load JSON rows / 加载JSON行数据
+    ///
+    /// The format is `{"<table>": [{"<column>": <value>, ...}, ...], ...}`, the tables are loaded in the order of the keys,
+    /// use an array of such objects to control the order, e.g. for foreign keys.
+    /// The objects and arrays are loaded as JSON values.
+    ///
+    /// 格式为 `{"<表名>": [{"<列名>": <值>, ...}, ...], ...}` ，按键的顺序加载各表，可使用此类对象的数组控制顺序，如用于外键.
+    /// 对象和数组以JSON值加载.
+    pub async fn load_json(&self, json: &str) -> TardisResult<()> {
+        let fixtures = match TardisFuns::json.str_to_json(json)? {
+            serde_json::Value::Array(fixtures) => fixtures,
+            fixtures => vec![fixtures],
+        };
+        for fixture in fixtures {
+            let serde_json::Value::Object(tables) = fixture else {
+                return Err(TardisError::format_error(
+                    "[Tardis.DBFixture] Fixture must be an object of tables",
+                    "406-tardis-db-fixture-format-error",
+                ));
+            };
+            for (table, rows) in tables {
+                let serde_json::Value::Array(rows) = rows else {
+                    return Err(TardisError::format_error(
+                        &format!("[Tardis.DBFixture] Rows of table {table} must be an array"),
+                        "406-tardis-db-fixture-format-error",
+                    ));
+                };
+                for row in rows {
+                    let serde_json::Value::Object(row) = row else {
+                        return Err(TardisError::format_error(
+                            &format!("[Tardis.DBFixture] Row of table {table} must be an object"),
+                            "406-tardis-db-fixture-format-error",
+                        ));
+                    };
+                    let mut statement = Query::insert();
+                    statement.into_table(Alias::new(&table)).columns(row.keys().map(Alias::new));
+                    statement.values(row.into_iter().map(|(_, value)| json_to_expr(value))).map_err(|error| {
+                        TardisError::format_error(
+                            &format!("[Tardis.DBFixture] Build insert of table {table} error: {error}"),
+                            "406-tardis-db-fixture-format-error",
+                        )
+                    })?;
+                    self.conn.execute(&statement).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn load_json_file(&self, path: impl AsRef<Path>) -> TardisResult<()> {
+        self.load_json(&std::fs::read_to_string(path)?).await
+    }
+}
+
+fn json_to_expr(value: serde_json::Value) -> SimpleExpr {
+    match value {
+        serde_json::Value::Null => SimpleExpr::Keyword(Keyword::Null),
+        serde_json::Value::Bool(value) => Value::from(value).into(),
+        serde_json::Value::Number(value) => match (value.as_i64(), value.as_u64()) {
+            (Some(value), _) => Value::from(value).into(),
+            (_, Some(value)) => Value::from(value).into(),
+            _ => Value::from(value.as_f64().unwrap_or_default()).into(),
+        },
+        serde_json::Value::String(value) => Value::from(value).into(),
+        value => Value::Json(Some(Box::new(value))).into(),
+    }
+}
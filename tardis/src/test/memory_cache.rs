@@ -0,0 +1,756 @@
+//! In-memory cache store / 内存缓存存储
+//!
+//! The backend of [`TardisCacheClient`](crate::cache::cache_client::TardisCacheClient) when the url is `mem://`,
+//! simulates the Redis semantics (including the expiration) of the operations provided by the client, so that the logic using
+//! `TardisFuns::cache()` can be tested without Redis.
+//!
+//! 当url为 `mem://` 时 [`TardisCacheClient`](crate::cache::cache_client::TardisCacheClient) 使用的后端，
+//! 模拟了客户端所提供操作的Redis语义（包含过期），从而无需Redis即可测试使用 `TardisFuns::cache()` 的逻辑.
+//!
+//! The expiration is based on [`TardisFuns::clock`](crate::TardisFuns::clock), so it can be fast-forwarded by
+//! [`TardisMockClock`](crate::test::mock_clock::TardisMockClock).
+//!
+//! 过期基于 [`TardisFuns::clock`](crate::TardisFuns::clock) ，因此可通过 [`TardisMockClock`](crate::test::mock_clock::TardisMockClock) 快进.
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use redis::{ErrorKind, RedisError, RedisResult};
+use tracing::error;
+
+use crate::cache::cache_client::{CacheMessageHandler, CacheTopic};
+use crate::TardisFuns;
+
+enum MemoryValue {
+    String(Vec<u8>),
+    List(VecDeque<String>),
+    // keep the insertion order like redis small hashes
+    Hash(Vec<(String, Vec<u8>)>),
+    // sorted by (score, member)
+    ZSet(Vec<(String, f64)>),
+}
+
+struct MemoryEntry {
+    value: MemoryValue,
+    expire_at: Option<DateTime<Utc>>,
+}
+
+type MemorySubscribers = Arc<Mutex<Vec<(u64, CacheTopic, CacheMessageHandler)>>>;
+
+/// In-memory cache store / 内存缓存存储
+#[derive(Default)]
+pub struct TardisMemoryCache {
+    entries: Mutex<HashMap<String, MemoryEntry>>,
+    subscribers: MemorySubscribers,
+    next_subscription_id: AtomicU64,
+}
+
+/// Subscription of the in-memory pub/sub / 内存发布订阅的订阅
+pub struct MemorySubscription {
+    id: u64,
+    subscribers: MemorySubscribers,
+}
+
+impl MemorySubscription {
+    pub(crate) fn unsubscribe(self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|(id, _, _)| *id != self.id);
+        }
+    }
+}
+
+fn wrong_type() -> RedisError {
+    RedisError::from((ErrorKind::TypeError, "WRONGTYPE", "Operation against a key holding the wrong kind of value".to_string()))
+}
+
+fn not_integer() -> RedisError {
+    RedisError::from((ErrorKind::TypeError, "ERR", "value is not an integer or out of range".to_string()))
+}
+
+fn to_integer(value: &[u8]) -> RedisResult<isize> {
+    std::str::from_utf8(value).ok().and_then(|value| value.parse::<isize>().ok()).ok_or_else(not_integer)
+}
+
+fn expire_after(ex_sec: usize) -> DateTime<Utc> {
+    TardisFuns::clock().now() + Duration::seconds(ex_sec as i64)
+}
+
+/// Glob-style match supporting `*` and `?` / 支持 `*` 及 `?` 的glob风格匹配
+fn glob_match(pattern: &[u8], value: &[u8]) -> bool {
+    match (pattern.first(), value.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => glob_match(&pattern[1..], value) || (!value.is_empty() && glob_match(pattern, &value[1..])),
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &value[1..]),
+        (Some(p), Some(v)) if p == v => glob_match(&pattern[1..], &value[1..]),
+        _ => false,
+    }
+}
+
+fn sort_zset(zset: &mut [(String, f64)]) {
+    zset.sort_by(|(m1, s1), (m2, s2)| s1.total_cmp(s2).then_with(|| m1.cmp(m2)));
+}
+
+/// Convert the redis-style range (negative index counts from the end) to the slice range
+/// / 将redis风格的范围（负数索引从末尾计数）转换为切片范围
+fn to_range(len: usize, start: isize, stop: isize) -> Option<std::ops::Range<usize>> {
+    let normalize = |idx: isize| if idx < 0 { len as isize + idx } else { idx };
+    let (start, stop) = (normalize(start).max(0), normalize(stop).min(len as isize - 1));
+    if start > stop {
+        return None;
+    }
+    Some(start as usize..stop as usize + 1)
+}
+
+fn count_bits(bytes: &[u8]) -> usize {
+    bytes.iter().map(|byte| byte.count_ones() as usize).sum()
+}
+
+impl TardisMemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_entries<T>(&self, fun: impl FnOnce(&mut HashMap<String, MemoryEntry>) -> RedisResult<T>) -> RedisResult<T> {
+        let mut entries = self.entries.lock().map_err(|error| RedisError::from((ErrorKind::ClientError, "Memory cache lock error", error.to_string())))?;
+        let now = TardisFuns::clock().now();
+        entries.retain(|_, entry| entry.expire_at.map(|expire_at| expire_at > now).unwrap_or(true));
+        fun(&mut entries)
+    }
+
+    fn with_string<T>(&self, key: &str, fun: impl FnOnce(Option<&Vec<u8>>) -> T) -> RedisResult<T> {
+        self.with_entries(|entries| match entries.get(key).map(|entry| &entry.value) {
+            None => Ok(fun(None)),
+            Some(MemoryValue::String(value)) => Ok(fun(Some(value))),
+            Some(_) => Err(wrong_type()),
+        })
+    }
+
+    fn with_list<T>(&self, key: &str, create: bool, fun: impl FnOnce(&mut VecDeque<String>) -> RedisResult<T>) -> RedisResult<T> {
+        self.with_entries(|entries| {
+            if create && !entries.contains_key(key) {
+                entries.insert(
+                    key.to_string(),
+                    MemoryEntry {
+                        value: MemoryValue::List(VecDeque::new()),
+                        expire_at: None,
+                    },
+                );
+            }
+            let result = match entries.get_mut(key).map(|entry| &mut entry.value) {
+                None => fun(&mut VecDeque::new()),
+                Some(MemoryValue::List(list)) => fun(list),
+                Some(_) => Err(wrong_type()),
+            };
+            // redis removes the empty collections
+            if matches!(entries.get(key), Some(MemoryEntry { value: MemoryValue::List(list), .. }) if list.is_empty()) {
+                entries.remove(key);
+            }
+            result
+        })
+    }
+
+    fn with_hash<T>(&self, key: &str, create: bool, fun: impl FnOnce(&mut Vec<(String, Vec<u8>)>) -> RedisResult<T>) -> RedisResult<T> {
+        self.with_entries(|entries| {
+            if create && !entries.contains_key(key) {
+                entries.insert(
+                    key.to_string(),
+                    MemoryEntry {
+                        value: MemoryValue::Hash(Vec::new()),
+                        expire_at: None,
+                    },
+                );
+            }
+            let result = match entries.get_mut(key).map(|entry| &mut entry.value) {
+                None => fun(&mut Vec::new()),
+                Some(MemoryValue::Hash(hash)) => fun(hash),
+                Some(_) => Err(wrong_type()),
+            };
+            if matches!(entries.get(key), Some(MemoryEntry { value: MemoryValue::Hash(hash), .. }) if hash.is_empty()) {
+                entries.remove(key);
+            }
+            result
+        })
+    }
+
+    fn with_zset<T>(&self, key: &str, create: bool, fun: impl FnOnce(&mut Vec<(String, f64)>) -> RedisResult<T>) -> RedisResult<T> {
+        self.with_entries(|entries| {
+            if create && !entries.contains_key(key) {
+                entries.insert(
+                    key.to_string(),
+                    MemoryEntry {
+                        value: MemoryValue::ZSet(Vec::new()),
+                        expire_at: None,
+                    },
+                );
+            }
+            let result = match entries.get_mut(key).map(|entry| &mut entry.value) {
+                None => fun(&mut Vec::new()),
+                Some(MemoryValue::ZSet(zset)) => fun(zset),
+                Some(_) => Err(wrong_type()),
+            };
+            if matches!(entries.get(key), Some(MemoryEntry { value: MemoryValue::ZSet(zset), .. }) if zset.is_empty()) {
+                entries.remove(key);
+            }
+            result
+        })
+    }
+
+    fn put_string(entries: &mut HashMap<String, MemoryEntry>, key: &str, value: Vec<u8>, expire_at: Option<DateTime<Utc>>) {
+        entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                value: MemoryValue::String(value),
+                expire_at,
+            },
+        );
+    }
+
+    pub fn set(&self, key: &str, value: &str) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            Self::put_string(entries, key, value.as_bytes().to_vec(), None);
+            Ok(())
+        })
+    }
+
+    pub fn set_ex(&self, key: &str, value: &str, ex_sec: usize) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            Self::put_string(entries, key, value.as_bytes().to_vec(), Some(expire_after(ex_sec)));
+            Ok(())
+        })
+    }
+
+    pub fn set_nx(&self, key: &str, value: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            if entries.contains_key(key) {
+                return Ok(false);
+            }
+            Self::put_string(entries, key, value.as_bytes().to_vec(), None);
+            Ok(true)
+        })
+    }
+
+    /// Same as `SET key value NX PX ttl_ms` / 与 `SET key value NX PX ttl_ms` 相同
+    pub fn set_nx_px(&self, key: &str, value: &str, ttl_ms: u64) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            if entries.contains_key(key) {
+                return Ok(false);
+            }
+            Self::put_string(
+                entries,
+                key,
+                value.as_bytes().to_vec(),
+                Some(TardisFuns::clock().now() + Duration::milliseconds(ttl_ms as i64)),
+            );
+            Ok(true)
+        })
+    }
+
+    /// Delete the key if its value is `value` / 若键的值为 `value` 则删除该键
+    pub fn del_if_eq(&self, key: &str, value: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| match entries.get(key).map(|entry| &entry.value) {
+            Some(MemoryValue::String(current)) if current == value.as_bytes() => Ok(entries.remove(key).is_some()),
+            _ => Ok(false),
+        })
+    }
+
+    /// Set the expiration in milliseconds if the value of the key is `value` / 若键的值为 `value` 则设置毫秒级过期时间
+    pub fn pexpire_if_eq(&self, key: &str, value: &str, ttl_ms: u64) -> RedisResult<bool> {
+        self.with_entries(|entries| match entries.get_mut(key) {
+            Some(entry) if matches!(&entry.value, MemoryValue::String(current) if current == value.as_bytes()) => {
+                entry.expire_at = Some(TardisFuns::clock().now() + Duration::milliseconds(ttl_ms as i64));
+                Ok(true)
+            }
+            _ => Ok(false),
+        })
+    }
+
+    pub fn get(&self, key: &str) -> RedisResult<Option<String>> {
+        self.with_string(key, |value| value.map(|value| String::from_utf8_lossy(value).to_string()))
+    }
+
+    pub fn get_bytes(&self, key: &str) -> RedisResult<Option<Vec<u8>>> {
+        self.with_string(key, |value| value.cloned())
+    }
+
+    /// Set the raw value with the optional expiration / 设置原始值及可选的过期时间
+    pub fn set_bytes(&self, key: &str, value: Vec<u8>, ex_sec: Option<usize>) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            Self::put_string(entries, key, value, ex_sec.map(expire_after));
+            Ok(())
+        })
+    }
+
+    pub fn getset(&self, key: &str, value: &str) -> RedisResult<Option<String>> {
+        let old_value = self.get(key)?;
+        self.set(key, value)?;
+        Ok(old_value)
+    }
+
+    pub fn incr(&self, key: &str, delta: isize) -> RedisResult<isize> {
+        self.with_entries(|entries| {
+            let (current, expire_at) = match entries.get(key) {
+                None => (0, None),
+                Some(MemoryEntry {
+                    value: MemoryValue::String(value),
+                    expire_at,
+                }) => (to_integer(value)?, *expire_at),
+                Some(_) => return Err(wrong_type()),
+            };
+            let value = current.checked_add(delta).ok_or_else(not_integer)?;
+            Self::put_string(entries, key, value.to_string().into_bytes(), expire_at);
+            Ok(value)
+        })
+    }
+
+    pub fn del(&self, key: &str) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            entries.remove(key);
+            Ok(())
+        })
+    }
+
+    pub fn exists(&self, key: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| Ok(entries.contains_key(key)))
+    }
+
+    pub fn expire(&self, key: &str, ex_sec: usize) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            if let Some(entry) = entries.get_mut(key) {
+                entry.expire_at = Some(expire_after(ex_sec));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn expire_at(&self, key: &str, timestamp_sec: usize) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            if let Some(entry) = entries.get_mut(key) {
+                entry.expire_at = Utc.timestamp_opt(timestamp_sec as i64, 0).single();
+            }
+            Ok(())
+        })
+    }
+
+    /// Same as redis, returns `-2` (missing) and `-1` (no expiration) as wrapped `usize` / 与redis相同， `-2` （不存在）和 `-1` （不过期）以回绕的 `usize` 返回
+    pub fn ttl(&self, key: &str) -> RedisResult<usize> {
+        self.with_entries(|entries| {
+            Ok(match entries.get(key) {
+                None => -2_isize as usize,
+                Some(MemoryEntry { expire_at: None, .. }) => -1_isize as usize,
+                Some(MemoryEntry { expire_at: Some(expire_at), .. }) => ((*expire_at - TardisFuns::clock().now()).num_milliseconds().max(0) as f64 / 1000.0).round() as usize,
+            })
+        })
+    }
+
+    // list operations
+
+    pub fn lpush(&self, key: &str, value: &str) -> RedisResult<()> {
+        self.with_list(key, true, |list| {
+            list.push_front(value.to_string());
+            Ok(())
+        })
+    }
+
+    pub fn rpush(&self, key: &str, value: &str) -> RedisResult<()> {
+        self.with_list(key, true, |list| {
+            list.push_back(value.to_string());
+            Ok(())
+        })
+    }
+
+    pub fn lrangeall(&self, key: &str) -> RedisResult<Vec<String>> {
+        self.with_list(key, false, |list| Ok(list.iter().cloned().collect()))
+    }
+
+    pub fn llen(&self, key: &str) -> RedisResult<usize> {
+        self.with_list(key, false, |list| Ok(list.len()))
+    }
+
+    /// Remove `count` (from the head if positive, from the tail if negative, or all if zero) elements equal to `value` / 删除 `count` 个（正数从头部开始，负数从尾部开始，零为全部）等于 `value` 的元素
+    pub fn lrem(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
+        self.with_list(key, false, |list| {
+            let limit = if count == 0 { usize::MAX } else { count.unsigned_abs() };
+            let mut indexes = list.iter().enumerate().filter(|(_, item)| *item == value).map(|(idx, _)| idx).collect::<Vec<_>>();
+            if count < 0 {
+                indexes.reverse();
+            }
+            indexes.truncate(limit);
+            indexes.sort_unstable();
+            for idx in indexes.iter().rev() {
+                list.remove(*idx);
+            }
+            Ok(indexes.len())
+        })
+    }
+
+    pub fn linsert_after(&self, key: &str, pivot: isize, value: &str) -> RedisResult<usize> {
+        self.linsert(key, pivot, value, 1)
+    }
+
+    pub fn linsert_before(&self, key: &str, pivot: isize, value: &str) -> RedisResult<usize> {
+        self.linsert(key, pivot, value, 0)
+    }
+
+    fn linsert(&self, key: &str, pivot: isize, value: &str, offset: usize) -> RedisResult<usize> {
+        let pivot = pivot.to_string();
+        self.with_list(key, false, |list| {
+            if list.is_empty() {
+                return Ok(0);
+            }
+            match list.iter().position(|item| *item == pivot) {
+                Some(idx) => {
+                    list.insert(idx + offset, value.to_string());
+                    Ok(list.len())
+                }
+                None => Ok(-1_isize as usize),
+            }
+        })
+    }
+
+    pub fn lset(&self, key: &str, index: isize, value: &str) -> RedisResult<usize> {
+        self.with_list(key, false, |list| {
+            let idx = if index < 0 { list.len() as isize + index } else { index };
+            match usize::try_from(idx).ok().and_then(|idx| list.get_mut(idx)) {
+                Some(item) => {
+                    *item = value.to_string();
+                    Ok(0)
+                }
+                None if list.is_empty() => Err(RedisError::from((ErrorKind::ResponseError, "ERR", "no such key".to_string()))),
+                None => Err(RedisError::from((ErrorKind::ResponseError, "ERR", "index out of range".to_string()))),
+            }
+        })
+    }
+
+    // hash operations
+
+    pub fn hget(&self, key: &str, field: &str) -> RedisResult<Option<String>> {
+        Ok(self.hget_bytes(key, field)?.map(|value| String::from_utf8_lossy(&value).to_string()))
+    }
+
+    pub fn hget_bytes(&self, key: &str, field: &str) -> RedisResult<Option<Vec<u8>>> {
+        self.with_hash(key, false, |hash| Ok(hash.iter().find(|(f, _)| f == field).map(|(_, v)| v.clone())))
+    }
+
+    pub fn hset(&self, key: &str, field: &str, value: &str) -> RedisResult<()> {
+        self.hset_bytes(key, field, value.as_bytes().to_vec())
+    }
+
+    pub fn hset_bytes(&self, key: &str, field: &str, value: Vec<u8>) -> RedisResult<()> {
+        self.with_hash(key, true, |hash| {
+            match hash.iter_mut().find(|(f, _)| f == field) {
+                Some((_, v)) => *v = value,
+                None => hash.push((field.to_string(), value)),
+            }
+            Ok(())
+        })
+    }
+
+    pub fn hset_nx(&self, key: &str, field: &str, value: &str) -> RedisResult<bool> {
+        self.with_hash(key, true, |hash| {
+            if hash.iter().any(|(f, _)| f == field) {
+                return Ok(false);
+            }
+            hash.push((field.to_string(), value.as_bytes().to_vec()));
+            Ok(true)
+        })
+    }
+
+    pub fn hdel(&self, key: &str, field: &str) -> RedisResult<()> {
+        self.with_hash(key, false, |hash| {
+            hash.retain(|(f, _)| f != field);
+            Ok(())
+        })
+    }
+
+    pub fn hincr(&self, key: &str, field: &str, delta: isize) -> RedisResult<isize> {
+        self.with_hash(key, true, |hash| {
+            let (current, idx) = match hash.iter().position(|(f, _)| f == field) {
+                Some(idx) => (to_integer(&hash[idx].1)?, Some(idx)),
+                None => (0, None),
+            };
+            let value = current.checked_add(delta).ok_or_else(not_integer)?;
+            match idx {
+                Some(idx) => hash[idx].1 = value.to_string().into_bytes(),
+                None => hash.push((field.to_string(), value.to_string().into_bytes())),
+            }
+            Ok(value)
+        })
+    }
+
+    pub fn hexists(&self, key: &str, field: &str) -> RedisResult<bool> {
+        self.with_hash(key, false, |hash| Ok(hash.iter().any(|(f, _)| f == field)))
+    }
+
+    pub fn hkeys(&self, key: &str) -> RedisResult<Vec<String>> {
+        self.with_hash(key, false, |hash| Ok(hash.iter().map(|(f, _)| f.clone()).collect()))
+    }
+
+    pub fn hvals(&self, key: &str) -> RedisResult<Vec<String>> {
+        self.with_hash(key, false, |hash| Ok(hash.iter().map(|(_, v)| String::from_utf8_lossy(v).to_string()).collect()))
+    }
+
+    pub fn hgetall(&self, key: &str) -> RedisResult<HashMap<String, String>> {
+        Ok(self.hgetall_bytes(key)?.into_iter().map(|(f, v)| (f, String::from_utf8_lossy(&v).to_string())).collect())
+    }
+
+    pub fn hgetall_bytes(&self, key: &str) -> RedisResult<HashMap<String, Vec<u8>>> {
+        self.with_hash(key, false, |hash| Ok(hash.iter().cloned().collect()))
+    }
+
+    pub fn hlen(&self, key: &str) -> RedisResult<usize> {
+        self.with_hash(key, false, |hash| Ok(hash.len()))
+    }
+
+    // sorted set operations
+
+    pub fn zadd(&self, key: &str, member: &str, score: f64) -> RedisResult<bool> {
+        Ok(self.zadd_members(key, &[(score, member)])? > 0)
+    }
+
+    pub fn zadd_members(&self, key: &str, members: &[(f64, &str)]) -> RedisResult<usize> {
+        self.with_zset(key, true, |zset| {
+            let mut added = 0;
+            for (score, member) in members {
+                match zset.iter_mut().find(|(m, _)| m == member) {
+                    Some((_, s)) => *s = *score,
+                    None => {
+                        zset.push((member.to_string(), *score));
+                        added += 1;
+                    }
+                }
+            }
+            sort_zset(zset);
+            Ok(added)
+        })
+    }
+
+    pub fn zincr(&self, key: &str, member: &str, delta: f64) -> RedisResult<f64> {
+        self.with_zset(key, true, |zset| {
+            let score = match zset.iter_mut().find(|(m, _)| m == member) {
+                Some((_, s)) => {
+                    *s += delta;
+                    *s
+                }
+                None => {
+                    zset.push((member.to_string(), delta));
+                    delta
+                }
+            };
+            sort_zset(zset);
+            Ok(score)
+        })
+    }
+
+    pub fn zrem(&self, key: &str, member: &str) -> RedisResult<bool> {
+        self.with_zset(key, false, |zset| {
+            let len = zset.len();
+            zset.retain(|(m, _)| m != member);
+            Ok(zset.len() < len)
+        })
+    }
+
+    pub fn zmove(&self, source: &str, destination: &str, member: &str, score: f64) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            if entries.get(destination).is_some_and(|entry| !matches!(entry.value, MemoryValue::ZSet(_))) {
+                return Err(wrong_type());
+            }
+            let removed = match entries.get_mut(source).map(|entry| &mut entry.value) {
+                None => false,
+                Some(MemoryValue::ZSet(zset)) => {
+                    let len = zset.len();
+                    zset.retain(|(m, _)| m != member);
+                    zset.len() < len
+                }
+                Some(_) => return Err(wrong_type()),
+            };
+            if !removed {
+                return Ok(false);
+            }
+            if matches!(entries.get(source), Some(MemoryEntry { value: MemoryValue::ZSet(zset), .. }) if zset.is_empty()) {
+                entries.remove(source);
+            }
+            let entry = entries.entry(destination.to_string()).or_insert_with(|| MemoryEntry {
+                value: MemoryValue::ZSet(Vec::new()),
+                expire_at: None,
+            });
+            if let MemoryValue::ZSet(zset) = &mut entry.value {
+                match zset.iter_mut().find(|(m, _)| m == member) {
+                    Some((_, s)) => *s = score,
+                    None => zset.push((member.to_string(), score)),
+                }
+                sort_zset(zset);
+            }
+            Ok(true)
+        })
+    }
+
+    pub fn zcard(&self, key: &str) -> RedisResult<usize> {
+        self.with_zset(key, false, |zset| Ok(zset.len()))
+    }
+
+    pub fn zscore(&self, key: &str, member: &str) -> RedisResult<Option<f64>> {
+        self.with_zset(key, false, |zset| Ok(zset.iter().find(|(m, _)| m == member).map(|(_, s)| *s)))
+    }
+
+    pub fn zrank(&self, key: &str, member: &str) -> RedisResult<Option<usize>> {
+        self.with_zset(key, false, |zset| Ok(zset.iter().position(|(m, _)| m == member)))
+    }
+
+    pub fn zrevrank(&self, key: &str, member: &str) -> RedisResult<Option<usize>> {
+        self.with_zset(key, false, |zset| Ok(zset.iter().rev().position(|(m, _)| m == member)))
+    }
+
+    pub fn zrange_withscores(&self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<(String, f64)>> {
+        self.with_zset(key, false, |zset| {
+            Ok(to_range(zset.len(), start, stop).map(|range| zset[range].to_vec()).unwrap_or_default())
+        })
+    }
+
+    pub fn zrevrange_withscores(&self, key: &str, start: isize, stop: isize) -> RedisResult<Vec<(String, f64)>> {
+        self.with_zset(key, false, |zset| {
+            let reversed = zset.iter().rev().cloned().collect::<Vec<_>>();
+            Ok(to_range(reversed.len(), start, stop).map(|range| reversed[range].to_vec()).unwrap_or_default())
+        })
+    }
+
+    /// `limit` is `(offset, count)`, a negative count returns all the members after the offset
+    /// / `limit` 为 `(偏移, 数量)` ，数量为负数时返回偏移之后的全部成员
+    pub fn zrangebyscore_withscores(&self, key: &str, min: f64, max: f64, limit: Option<(isize, isize)>) -> RedisResult<Vec<(String, f64)>> {
+        self.with_zset(key, false, |zset| {
+            let members = zset.iter().filter(|(_, s)| *s >= min && *s <= max).cloned();
+            Ok(match limit {
+                None => members.collect(),
+                Some((offset, _)) if offset < 0 => Vec::new(),
+                Some((offset, count)) if count < 0 => members.skip(offset as usize).collect(),
+                Some((offset, count)) => members.skip(offset as usize).take(count as usize).collect(),
+            })
+        })
+    }
+
+    pub fn zremrangebyscore(&self, key: &str, min: f64, max: f64) -> RedisResult<usize> {
+        self.with_zset(key, false, |zset| {
+            let len = zset.len();
+            zset.retain(|(_, s)| *s < min || *s > max);
+            Ok(len - zset.len())
+        })
+    }
+
+    pub fn zcount(&self, key: &str, min: f64, max: f64) -> RedisResult<usize> {
+        self.with_zset(key, false, |zset| Ok(zset.iter().filter(|(_, s)| *s >= min && *s <= max).count()))
+    }
+
+    // bitmap operations
+
+    pub fn setbit(&self, key: &str, offset: usize, value: bool) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            let (mut bytes, expire_at) = match entries.remove(key) {
+                None => (Vec::new(), None),
+                Some(MemoryEntry {
+                    value: MemoryValue::String(bytes),
+                    expire_at,
+                }) => (bytes, expire_at),
+                Some(entry) => {
+                    entries.insert(key.to_string(), entry);
+                    return Err(wrong_type());
+                }
+            };
+            let (idx, mask) = (offset / 8, 0x80_u8 >> (offset % 8));
+            if bytes.len() <= idx {
+                bytes.resize(idx + 1, 0);
+            }
+            let old_value = bytes[idx] & mask != 0;
+            if value {
+                bytes[idx] |= mask;
+            } else {
+                bytes[idx] &= !mask;
+            }
+            Self::put_string(entries, key, bytes, expire_at);
+            Ok(old_value)
+        })
+    }
+
+    pub fn getbit(&self, key: &str, offset: usize) -> RedisResult<bool> {
+        self.with_string(key, |bytes| {
+            bytes.and_then(|bytes| bytes.get(offset / 8)).map(|byte| byte & (0x80_u8 >> (offset % 8)) != 0).unwrap_or(false)
+        })
+    }
+
+    pub fn bitcount(&self, key: &str) -> RedisResult<usize> {
+        self.with_string(key, |bytes| bytes.map(|bytes| count_bits(bytes)).unwrap_or(0))
+    }
+
+    /// Count the set bits from the `start` byte to the `end` byte (inclusive) / 统计从第 `start` 字节到第 `end` 字节（包含）中被设置的位数
+    pub fn bitcount_range_by_byte(&self, key: &str, start: usize, end: usize) -> RedisResult<usize> {
+        self.with_string(key, |bytes| {
+            bytes.filter(|bytes| start < bytes.len() && start <= end).map(|bytes| count_bits(&bytes[start..=end.min(bytes.len() - 1)])).unwrap_or(0)
+        })
+    }
+
+    /// Count the set bits from the `start` bit to the `end` bit (inclusive) / 统计从第 `start` 位到第 `end` 位（包含）中被设置的位数
+    pub fn bitcount_range_by_bit(&self, key: &str, start: usize, end: usize) -> RedisResult<usize> {
+        self.with_string(key, |bytes| {
+            bytes
+                .filter(|bytes| start < bytes.len() * 8)
+                .map(|bytes| (start..=end.min((bytes.len() * 8).saturating_sub(1))).filter(|offset| bytes[offset / 8] & (0x80_u8 >> (offset % 8)) != 0).count())
+                .unwrap_or(0)
+        })
+    }
+
+    // pub/sub operations
+
+    /// Deliver the message to the matching subscribers synchronously, returns the number of them / 同步投递消息给匹配的订阅者，返回其数量
+    pub async fn publish(&self, channel: &str, message: &str) -> usize {
+        let handlers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers
+                .iter()
+                .filter(|(_, topic, _)| match topic {
+                    CacheTopic::Channel(subscribed) => subscribed == channel,
+                    CacheTopic::Pattern(pattern) => glob_match(pattern.as_bytes(), channel.as_bytes()),
+                })
+                .map(|(_, _, handler)| handler.clone())
+                .collect::<Vec<_>>(),
+            Err(_) => return 0,
+        };
+        for handler in &handlers {
+            if let Err(error) = handler((channel.to_string(), message.to_string())).await {
+                error!("[Tardis.CacheClient] Receive process error, channel:{channel}, message:{message} | {error}");
+            }
+        }
+        handlers.len()
+    }
+
+    pub(crate) fn subscribe(&self, topic: CacheTopic, handler: CacheMessageHandler) -> MemorySubscription {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push((id, topic, handler));
+        }
+        MemorySubscription {
+            id,
+            subscribers: self.subscribers.clone(),
+        }
+    }
+
+    /// Atomically replace the string with the value computed from the current one, the new value expires after `ttl_ms`
+    /// / 原子地以由当前值计算出的值替换字符串，新值在 `ttl_ms` 后过期
+    pub(crate) fn update_bytes<T>(&self, key: &str, ttl_ms: u64, fun: impl FnOnce(Option<&[u8]>) -> (Vec<u8>, T)) -> RedisResult<T> {
+        self.with_entries(|entries| {
+            let (value, result) = match entries.get(key).map(|entry| &entry.value) {
+                None => fun(None),
+                Some(MemoryValue::String(value)) => fun(Some(value)),
+                Some(_) => return Err(wrong_type()),
+            };
+            Self::put_string(entries, key, value, Some(TardisFuns::clock().now() + Duration::milliseconds(ttl_ms as i64)));
+            Ok(result)
+        })
+    }
+
+    // other operations
+
+    pub fn flushdb(&self) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            entries.clear();
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,272 @@
+//! In-memory MQ broker / 内存MQ代理
+//!
+//! The backend of [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) when the url is `mem://`,
+//! so that the consumer logic can be tested without RabbitMQ.
+//!
+//! 当url为 `mem://` 时 [`TardisMQClient`](crate::mq::mq_client::TardisMQClient) 使用的后端，从而无需RabbitMQ即可测试消费逻辑.
+//!
+//! Messages are delivered synchronously, i.e. `request`/`publish` return after the handlers finished:
+//! a `request` message is delivered to one of the responders in turn (and kept until a responder is registered),
+//! a `publish` message is delivered to all the current subscribers.
+//! A `publish_topic` message is routed by the declared bindings like a `request` message and delivered to the matching topic subscribers,
+//! the queue arguments such as the ttl and the dead-letter exchange are ignored.
+//! The failed messages are retried by the consumer options like the AMQP backend without the concurrency,
+//! use [`failed_messages`](TardisMemoryMQ::failed_messages) to check them,
+//! the dead-lettered messages are kept as the [`pending_messages`](TardisMemoryMQ::pending_messages) of the dead-letter queue.
+//!
+//! 消息同步投递，即 `request`/`publish` 在处理函数执行完成后才返回：
+//! `request` 消息轮流投递给其中一个响应者（没有响应者时保留到注册为止）， `publish` 消息投递给当前所有订阅者.
+//! `publish_topic` 消息按已声明的绑定如 `request` 消息一样路由，并投递给匹配的主题订阅者，忽略ttl、死信交换机等队列参数.
+//! 失败的消息与AMQP后端一样按消费者选项重试（不支持并发），可使用 [`failed_messages`](TardisMemoryMQ::failed_messages) 检查，
+//! 转入死信的消息保留为死信队列的 [`pending_messages`](TardisMemoryMQ::pending_messages) .
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::mq::{MQConsumerConfig, MQExchangeKind, MQTopologyConfig};
+use crate::mq::mq_client::{dead_letter_header, process_message};
+
+type MemoryFn = Arc<dyn Fn((HashMap<String, String>, String)) -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send>> + Send + Sync>;
+
+#[derive(Clone)]
+struct MemoryHandler {
+    fun: MemoryFn,
+    options: MQConsumerConfig,
+}
+
+/// Message sent to the in-memory broker / 发送到内存代理的消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryMessage {
+    /// queue address, topic or exchange / 队列地址、主题或交换机
+    pub destination: String,
+    /// routing key of the topic messages, the address of the request messages / 主题消息的路由键，请求消息的地址
+    pub routing_key: String,
+    pub header: HashMap<String, String>,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct MemoryMQState {
+    responders: HashMap<String, (usize, Vec<MemoryHandler>)>,
+    subscribers: HashMap<String, Vec<MemoryHandler>>,
+    exchanges: HashMap<String, MQExchangeKind>,
+    /// (exchange, binding key, queue)
+    bindings: Vec<(String, String, String)>,
+    /// (exchange, routing key pattern, handler)
+    topic_subscribers: Vec<(String, String, MemoryHandler)>,
+    pending: HashMap<String, Vec<MemoryMessage>>,
+    sent: Vec<MemoryMessage>,
+    failed: Vec<(MemoryMessage, TardisError)>,
+    send_failures: HashMap<String, usize>,
+}
+
+/// In-memory MQ broker / 内存MQ代理
+#[derive(Default)]
+pub struct TardisMemoryMQ {
+    state: Mutex<MemoryMQState>,
+}
+
+impl TardisMemoryMQ {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> TardisResult<std::sync::MutexGuard<'_, MemoryMQState>> {
+        self.state.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MQClient] Memory broker lock error: {error}"), ""))
+    }
+
+    /// Make the next `times` sends to the address or topic fail, like unconfirmed publishing / 使接下来 `times` 次发往该地址或主题的消息失败，如同发布未被确认
+    pub fn fail_next_sends(&self, destination: &str, times: usize) -> TardisResult<()> {
+        *self.lock()?.send_failures.entry(destination.to_string()).or_default() += times;
+        Ok(())
+    }
+
+    /// All the sent messages / 所有发送的消息
+    pub fn sent_messages(&self) -> TardisResult<Vec<MemoryMessage>> {
+        Ok(self.lock()?.sent.clone())
+    }
+
+    /// The messages whose handler still failed after the retries / 重试后处理函数仍失败的消息
+    pub fn failed_messages(&self) -> TardisResult<Vec<(MemoryMessage, TardisError)>> {
+        Ok(self.lock()?.failed.clone())
+    }
+
+    /// The request messages waiting for a responder, or the dead-lettered messages / 等待响应者的请求消息，或转入死信的消息
+    pub fn pending_messages(&self, address: &str) -> TardisResult<Vec<MemoryMessage>> {
+        Ok(self.lock()?.pending.get(address).cloned().unwrap_or_default())
+    }
+
+    pub(crate) async fn request(&self, address: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+        let message = self.accept(address, address, message, header)?;
+        self.enqueue(address, message).await
+    }
+
+    async fn enqueue(&self, address: &str, message: MemoryMessage) -> TardisResult<()> {
+        let handler = {
+            let mut state = self.lock()?;
+            match state.responders.get_mut(address) {
+                Some((next, handlers)) if !handlers.is_empty() => {
+                    let handler = handlers[*next % handlers.len()].clone();
+                    *next += 1;
+                    handler
+                }
+                _ => {
+                    state.pending.entry(address.to_string()).or_default().push(message);
+                    return Ok(());
+                }
+            }
+        };
+        self.deliver(&handler, message).await
+    }
+
+    pub(crate) async fn publish(&self, topic: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+        let message = self.accept(topic, "", message, header)?;
+        let handlers = self.lock()?.subscribers.get(topic).cloned().unwrap_or_default();
+        for handler in handlers {
+            self.deliver(&handler, message.clone()).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn declare_topology(&self, topology: &MQTopologyConfig) -> TardisResult<()> {
+        let mut state = self.lock()?;
+        for exchange in &topology.exchanges {
+            state.exchanges.insert(exchange.name.clone(), exchange.kind);
+        }
+        for queue in &topology.queues {
+            for binding in &queue.bindings {
+                let binding = (binding.exchange.clone(), binding.routing_key.clone(), queue.name.clone());
+                if !state.bindings.contains(&binding) {
+                    state.bindings.push(binding);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn publish_topic(&self, exchange: &str, routing_key: &str, message: String, header: HashMap<String, String>) -> TardisResult<()> {
+        let message = self.accept(exchange, routing_key, message, header)?;
+        let (queues, handlers) = {
+            let state = self.lock()?;
+            let kind = state.exchanges.get(exchange).copied().unwrap_or_default();
+            let mut queues: Vec<String> = Vec::new();
+            for (_, _, queue) in state.bindings.iter().filter(|(bound_exchange, binding_key, _)| bound_exchange == exchange && route_matches(kind, binding_key, routing_key)) {
+                if !queues.contains(queue) {
+                    queues.push(queue.clone());
+                }
+            }
+            let handlers = state
+                .topic_subscribers
+                .iter()
+                .filter(|(bound_exchange, pattern, _)| bound_exchange == exchange && route_matches(kind, pattern, routing_key))
+                .map(|(_, _, handler)| handler.clone())
+                .collect::<Vec<_>>();
+            (queues, handlers)
+        };
+        for queue in queues {
+            self.enqueue(&queue, message.clone()).await?;
+        }
+        for handler in handlers {
+            self.deliver(&handler, message.clone()).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn subscribe_topic<F, T>(&self, exchange: &str, routing_key_pattern: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.lock()?.topic_subscribers.push((exchange.to_string(), routing_key_pattern.to_string(), Self::handler(options, fun)));
+        Ok(())
+    }
+
+    pub(crate) async fn response<F, T>(&self, address: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let handler = Self::handler(options, fun);
+        let pending = {
+            let mut state = self.lock()?;
+            state.responders.entry(address.to_string()).or_default().1.push(handler.clone());
+            state.pending.remove(address).unwrap_or_default()
+        };
+        for message in pending {
+            self.deliver(&handler, message).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn subscribe<F, T>(&self, topic: &str, options: &MQConsumerConfig, fun: F) -> TardisResult<()>
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.lock()?.subscribers.entry(topic.to_string()).or_default().push(Self::handler(options, fun));
+        Ok(())
+    }
+
+    fn handler<F, T>(options: &MQConsumerConfig, fun: F) -> MemoryHandler
+    where
+        F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        MemoryHandler {
+            fun: Arc::new(move |message| Box::pin(fun(message))),
+            options: options.clone(),
+        }
+    }
+
+    fn accept(&self, destination: &str, routing_key: &str, message: String, header: HashMap<String, String>) -> TardisResult<MemoryMessage> {
+        let mut state = self.lock()?;
+        if let Some(failures) = state.send_failures.get_mut(destination).filter(|failures| **failures > 0) {
+            *failures -= 1;
+            return Err(TardisError::internal_error("MQ request confirmation error", "500-tardis-mq-confirm-error"));
+        }
+        let message = MemoryMessage {
+            destination: destination.to_string(),
+            routing_key: routing_key.to_string(),
+            header,
+            message,
+        };
+        state.sent.push(message.clone());
+        Ok(message)
+    }
+
+    async fn deliver(&self, handler: &MemoryHandler, message: MemoryMessage) -> TardisResult<()> {
+        let fun = |message: (HashMap<String, String>, String)| (handler.fun)(message);
+        let Err(error) = process_message(&message.destination, message.header.clone(), message.message.clone(), &fun, &handler.options).await else {
+            return Ok(());
+        };
+        if let Some(dead_letter_queue) = handler.options.dead_letter_queue(&message.destination) {
+            let header = dead_letter_header(message.header.clone(), &message.destination, &error);
+            let dead_letter = self.accept(&dead_letter_queue, &dead_letter_queue, message.message.clone(), header)?;
+            self.lock()?.pending.entry(dead_letter_queue).or_default().push(dead_letter);
+        }
+        self.lock()?.failed.push((message, error));
+        Ok(())
+    }
+}
+
+fn route_matches(kind: MQExchangeKind, binding_key: &str, routing_key: &str) -> bool {
+    match kind {
+        MQExchangeKind::Fanout => true,
+        MQExchangeKind::Direct => binding_key == routing_key,
+        MQExchangeKind::Topic => topic_matches(&binding_key.split('.').collect::<Vec<_>>(), &routing_key.split('.').collect::<Vec<_>>()),
+        MQExchangeKind::Headers => false,
+    }
+}
+
+/// `*` matches one word and `#` matches zero or more words
+fn topic_matches(pattern: &[&str], words: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => words.is_empty(),
+        Some((&"#", rest)) => (0..=words.len()).any(|skip| topic_matches(rest, &words[skip..])),
+        Some((&"*", rest)) => !words.is_empty() && topic_matches(rest, &words[1..]),
+        Some((word, rest)) => words.first() == Some(word) && topic_matches(rest, &words[1..]),
+    }
+}
@@ -0,0 +1,108 @@
+//! Mock clock / 模拟时钟
+//!
+//! Replaces the source of [`TardisFuns::clock`] with a clock that can be frozen and advanced,
+//! so the time-dependent logic (e.g. the expiration of the in-memory cache) is deterministic in tests.
+//!
+//! 将 [`TardisFuns::clock`] 的时间源替换为可冻结及推进的时钟，从而使依赖时间的逻辑（如内存缓存的过期）在测试中可确定.
+//!
+//! The clock is process-wide, so the tests using it should not run in parallel in the same process (e.g. put them in the same test function).
+//!
+//! 时钟为进程级共享，因此使用它的测试不应在同一进程中并行执行（如放在同一测试函数中）.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::test::mock_clock::TardisMockClock;
+//! let clock = TardisMockClock::install_at(TardisFuns::time.parse("2023-08-01 10:00:00")?);
+//! TardisFuns::cache().set_ex("k", "v", 10).await?;
+//! clock.advance(chrono::Duration::seconds(11));
+//! assert!(!TardisFuns::cache().exists("k").await?);
+//! clock.uninstall();
+//! ```
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::basic::clock::TardisClockSource;
+use crate::TardisFuns;
+
+struct MockClockState {
+    /// the time when frozen / 冻结时的时间
+    frozen: Option<DateTime<Utc>>,
+    /// the offset to the system time when running / 运行时相对系统时间的偏移
+    offset: Duration,
+}
+
+/// Mock clock / 模拟时钟
+pub struct TardisMockClock {
+    state: Mutex<MockClockState>,
+}
+
+impl TardisMockClock {
+    /// Install a running clock starting from the system time / 安装从系统时间开始运行的时钟
+    pub fn install() -> Arc<TardisMockClock> {
+        Self::do_install(None)
+    }
+
+    /// Install a clock frozen at `now` / 安装冻结在 `now` 的时钟
+    pub fn install_at(now: DateTime<Utc>) -> Arc<TardisMockClock> {
+        Self::do_install(Some(now))
+    }
+
+    fn do_install(frozen: Option<DateTime<Utc>>) -> Arc<TardisMockClock> {
+        let clock = Arc::new(TardisMockClock {
+            state: Mutex::new(MockClockState { frozen, offset: Duration::zero() }),
+        });
+        TardisFuns::clock().set_source(clock.clone());
+        clock
+    }
+
+    /// Restore [`TardisFuns::clock`] to the system time / 恢复 [`TardisFuns::clock`] 为系统时间
+    pub fn uninstall(&self) {
+        TardisFuns::clock().reset();
+    }
+
+    /// Stop the clock at the current time / 将时钟停在当前时间
+    pub fn freeze(&self) {
+        self.with_state(|state| state.frozen = Some(Self::now_of(state)));
+    }
+
+    /// Resume the clock from the current time / 从当前时间恢复时钟运行
+    pub fn unfreeze(&self) {
+        self.with_state(|state| {
+            if let Some(frozen) = state.frozen.take() {
+                state.offset = frozen - Utc::now();
+            }
+        });
+    }
+
+    /// Move the clock forward (or backward with a negative duration) / 向前（负值则向后）拨动时钟
+    pub fn advance(&self, duration: Duration) {
+        self.with_state(|state| match &mut state.frozen {
+            Some(frozen) => *frozen += duration,
+            None => state.offset += duration,
+        });
+    }
+
+    /// Set the current time, keeping frozen or running / 设置当前时间，保持冻结或运行状态
+    pub fn set(&self, now: DateTime<Utc>) {
+        self.with_state(|state| match &mut state.frozen {
+            Some(frozen) => *frozen = now,
+            None => state.offset = now - Utc::now(),
+        });
+    }
+
+    fn now_of(state: &MockClockState) -> DateTime<Utc> {
+        state.frozen.unwrap_or_else(|| Utc::now() + state.offset)
+    }
+
+    fn with_state<T>(&self, fun: impl FnOnce(&mut MockClockState) -> T) -> T {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        fun(&mut state)
+    }
+}
+
+impl TardisClockSource for TardisMockClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.with_state(|state| Self::now_of(state))
+    }
+}
@@ -0,0 +1,366 @@
+//! In-process HTTP mock server / 进程内HTTP模拟服务
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+//! let server = TardisMockServer::start().await?;
+//! server.expect(MockExpectation::new("POST", "/users").body_contains("tardis").times(1).respond_with(MockResponse::status(201).json(&user)?));
+//! server.expect(MockExpectation::new("GET", "/slow").respond_with(MockResponse::ok().delay(Duration::from_secs(3))));
+//! server.expect(MockExpectation::new("GET", "/broken").respond_with(MockResponse::fault()));
+//! TardisFuns::web_client().post_str_to_str(&format!("{}/users", server.url()), "tardis", None).await?;
+//! server.verify()?;
+//! ```
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{trace, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+/// Request received by the mock server / 模拟服务收到的请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    /// header names are in lowercase
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// Canned response / 预设的响应
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    code: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    delay: Option<Duration>,
+    fault: bool,
+}
+
+impl MockResponse {
+    pub fn status(code: u16) -> Self {
+        MockResponse {
+            code,
+            headers: Vec::new(),
+            body: String::new(),
+            delay: None,
+            fault: false,
+        }
+    }
+
+    pub fn ok() -> Self {
+        Self::status(200)
+    }
+
+    /// Close the connection without any response / 不返回任何响应直接关闭连接
+    pub fn fault() -> Self {
+        MockResponse { fault: true, ..Self::ok() }
+    }
+
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Json body with the `Content-Type: application/json` header / 带 `Content-Type: application/json` 头的Json响应体
+    pub fn json<T: Serialize>(self, body: &T) -> TardisResult<Self> {
+        Ok(self.header("Content-Type", "application/json").body(TardisFuns::json.obj_to_string(body)?))
+    }
+
+    /// Wait before responding, e.g. to test timeouts / 响应前等待，如用于测试超时
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// Expectation of requests / 请求预期
+///
+/// Matches the requests by method, path and the optional header and body matchers,
+/// the first matched expectation in the registration order responds.
+///
+/// 按方法、路径及可选的请求头和请求体条件匹配请求，按注册顺序由第一个匹配的预期进行响应.
+#[derive(Debug, Clone)]
+pub struct MockExpectation {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    body_contains: Vec<String>,
+    body_json: Option<serde_json::Value>,
+    times: Option<usize>,
+    response: MockResponse,
+    matched: usize,
+}
+
+impl MockExpectation {
+    /// The path doesn't include the query / 路径不包含查询参数
+    pub fn new(method: impl Into<String>, path: impl Into<String>) -> Self {
+        MockExpectation {
+            method: method.into().to_uppercase(),
+            path: path.into(),
+            headers: Vec::new(),
+            body: None,
+            body_contains: Vec::new(),
+            body_json: None,
+            times: None,
+            response: MockResponse::ok(),
+            matched: 0,
+        }
+    }
+
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into().to_lowercase(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn body_contains(mut self, body: impl Into<String>) -> Self {
+        self.body_contains.push(body.into());
+        self
+    }
+
+    /// Match the body as a json value, ignoring the formatting and the order of fields / 以Json值匹配请求体，忽略格式和字段顺序
+    pub fn body_json<T: Serialize>(mut self, body: &T) -> TardisResult<Self> {
+        self.body_json = Some(TardisFuns::json.obj_to_json(body)?);
+        Ok(self)
+    }
+
+    /// Expected number of matched requests, the expectation is exhausted after that / 预期匹配的请求数，达到后该预期不再匹配
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+
+    pub fn respond_with(mut self, response: MockResponse) -> Self {
+        self.response = response;
+        self
+    }
+
+    fn matches(&self, request: &MockRequest) -> bool {
+        if self.times.map(|times| self.matched >= times).unwrap_or(false) {
+            return false;
+        }
+        self.method == request.method
+            && self.path == request.path
+            && self.headers.iter().all(|(key, value)| request.headers.get(key) == Some(value))
+            && self.body.as_ref().map(|body| body == &request.body).unwrap_or(true)
+            && self.body_contains.iter().all(|body| request.body.contains(body))
+            && self.body_json.as_ref().map(|body| serde_json::from_str::<serde_json::Value>(&request.body).map(|req_body| &req_body == body).unwrap_or(false)).unwrap_or(true)
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    expectations: Vec<MockExpectation>,
+    requests: Vec<MockRequest>,
+}
+
+/// In-process HTTP mock server, stopped when dropped / 进程内HTTP模拟服务，被drop时停止
+///
+/// Only supports the requests with the `Content-Length` header or without body, each connection serves one request.
+///
+/// 仅支持带 `Content-Length` 头或无请求体的请求，每个连接只处理一个请求.
+pub struct TardisMockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    handle: JoinHandle<()>,
+}
+
+impl TardisMockServer {
+    /// Start the server at a random port of localhost / 在本机随机端口启动服务
+    pub async fn start() -> TardisResult<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let server_state = state.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let state = server_state.clone();
+                        tokio::spawn(async move {
+                            if let Err(error) = Self::serve(stream, state).await {
+                                warn!("[Tardis.MockServer] Serve error: {error}");
+                            }
+                        });
+                    }
+                    Err(error) => warn!("[Tardis.MockServer] Accept error: {error}"),
+                }
+            }
+        });
+        Ok(TardisMockServer { addr, state, handle })
+    }
+
+    /// Base url, e.g. `http://127.0.0.1:12345` / 基础url，如 `http://127.0.0.1:12345`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn expect(&self, expectation: MockExpectation) {
+        if let Ok(mut state) = self.state.lock() {
+            state.expectations.push(expectation);
+        }
+    }
+
+    /// All the received requests / 所有收到的请求
+    pub fn received_requests(&self) -> Vec<MockRequest> {
+        self.state.lock().map(|state| state.requests.clone()).unwrap_or_default()
+    }
+
+    /// Remove all the expectations and the received requests / 删除所有预期及收到的请求
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.expectations.clear();
+            state.requests.clear();
+        }
+    }
+
+    /// Verify that each expectation is matched the expected times (or at least once if `times` is not set) / 校验每个预期均被匹配了预期的次数（未设置 `times` 时至少一次）
+    pub fn verify(&self) -> TardisResult<()> {
+        let state = self.state.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MockServer] {error}"), ""))?;
+        let unsatisfied = state
+            .expectations
+            .iter()
+            .filter(|expectation| expectation.times.map(|times| expectation.matched != times).unwrap_or(expectation.matched == 0))
+            .map(|expectation| {
+                format!(
+                    "{} {} matched {} of {} times",
+                    expectation.method,
+                    expectation.path,
+                    expectation.matched,
+                    expectation.times.unwrap_or(1)
+                )
+            })
+            .collect::<Vec<_>>();
+        if unsatisfied.is_empty() {
+            Ok(())
+        } else {
+            Err(TardisError::internal_error(
+                &format!("[Tardis.MockServer] Unsatisfied expectations: {}", unsatisfied.join("; ")),
+                "500-tardis-mock-server-unsatisfied",
+            ))
+        }
+    }
+
+    async fn serve(mut stream: TcpStream, state: Arc<Mutex<MockState>>) -> TardisResult<()> {
+        let Some(request) = Self::read_request(&mut stream).await? else {
+            return Ok(());
+        };
+        trace!("[Tardis.MockServer] Receive {} {}", request.method, request.path);
+        let response = {
+            let mut state = state.lock().map_err(|error| TardisError::conflict(&format!("[Tardis.MockServer] {error}"), ""))?;
+            state.requests.push(request.clone());
+            match state.expectations.iter_mut().find(|expectation| expectation.matches(&request)) {
+                Some(expectation) => {
+                    expectation.matched += 1;
+                    expectation.response.clone()
+                }
+                None => MockResponse::status(404).body(format!("[Tardis.MockServer] No expectation matched {} {}", request.method, request.path)),
+            }
+        };
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+        if response.fault {
+            return Ok(());
+        }
+        let mut resp = format!(
+            "HTTP/1.1 {} {}\r\ncontent-length: {}\r\nconnection: close\r\n",
+            response.code,
+            reason(response.code),
+            response.body.len()
+        );
+        for (key, value) in &response.headers {
+            resp.push_str(&format!("{key}: {value}\r\n"));
+        }
+        resp.push_str("\r\n");
+        resp.push_str(&response.body);
+        stream.write_all(resp.as_bytes()).await?;
+        stream.flush().await?;
+        Ok(())
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> TardisResult<Option<MockRequest>> {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 4096];
+        let header_end = loop {
+            if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+                break pos;
+            }
+            let size = stream.read(&mut chunk).await?;
+            if size == 0 {
+                return Ok(None);
+            }
+            buf.extend_from_slice(&chunk[..size]);
+        };
+        let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next().unwrap_or_default().split(' ');
+        let method = request_line.next().unwrap_or_default().to_uppercase();
+        let target = request_line.next().unwrap_or_default();
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (target.to_string(), None),
+        };
+        let headers = lines.filter_map(|line| line.split_once(':')).map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_string())).collect::<HashMap<_, _>>();
+        let content_length = headers.get("content-length").and_then(|len| len.parse::<usize>().ok()).unwrap_or(0);
+        let mut body = buf.split_off(header_end + 4);
+        while body.len() < content_length {
+            let size = stream.read(&mut chunk).await?;
+            if size == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..size]);
+        }
+        Ok(Some(MockRequest {
+            method,
+            path,
+            query,
+            headers,
+            body: String::from_utf8_lossy(&body).to_string(),
+        }))
+    }
+}
+
+impl Drop for TardisMockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn reason(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
+    }
+}
@@ -0,0 +1,209 @@
+//! JSON snapshot assertions / JSON快照断言
+//!
+//! Compares JSON values with the snapshots stored in `tests/snapshots/<name>.json` (relative to the crate being tested),
+//! the volatile fields (e.g. ids and timestamps) can be redacted, and the mismatches are reported by JSON path.
+//!
+//! 将JSON值与存储在 `tests/snapshots/<name>.json` （相对于被测crate）中的快照比较，可屏蔽易变字段（如id及时间戳），不匹配项按JSON路径报告.
+//!
+//! The missing snapshots are created, set the `TARDIS_UPDATE_SNAPSHOTS=1` environment variable to overwrite the existing ones.
+//!
+//! 缺失的快照会被创建，设置环境变量 `TARDIS_UPDATE_SNAPSHOTS=1` 可覆盖已有快照.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::test::snapshot::TardisSnapshot;
+//! let resp = client.get("/todo/todos?page_number=1&page_size=10").await?;
+//! let todos: Vec<TodoResp> = resp.assert_page(1, 10, 2)?;
+//! resp.assert_snapshot(&TardisSnapshot::new("todo_page").redact("$.data.records[*].id").redact_field("create_time"))?;
+//! ```
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+/// The value replacing the redacted fields / 替换被屏蔽字段的值
+pub const REDACTED: &str = "[redacted]";
+
+const UPDATE_SNAPSHOTS_ENV: &str = "TARDIS_UPDATE_SNAPSHOTS";
+
+enum Redaction {
+    /// `$.a.b[*].c` like path / 类似 `$.a.b[*].c` 的路径
+    Path(Vec<String>),
+    /// field name at any depth / 任意深度的字段名
+    Field(String),
+}
+
+/// JSON snapshot / JSON快照
+pub struct TardisSnapshot {
+    name: String,
+    dir: PathBuf,
+    redactions: Vec<Redaction>,
+}
+
+impl TardisSnapshot {
+    pub fn new(name: impl Into<String>) -> TardisSnapshot {
+        let root = std::env::var("CARGO_MANIFEST_DIR").map(PathBuf::from).unwrap_or_default();
+        TardisSnapshot {
+            name: name.into(),
+            dir: root.join("tests").join("snapshots"),
+            redactions: Vec::new(),
+        }
+    }
+
+    /// Store the snapshots in another directory / 将快照存储在其他目录
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Redact the value at the path, e.g. `$.data.id` `$.data.records[*].id` `$[0].id`
+    /// / 屏蔽路径上的值，如 `$.data.id` `$.data.records[*].id` `$[0].id`
+    pub fn redact(mut self, path: &str) -> Self {
+        let segments = path
+            .trim_start_matches('$')
+            .replace('[', ".[")
+            .split('.')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.trim_start_matches('[').trim_end_matches(']').to_string())
+            .collect();
+        self.redactions.push(Redaction::Path(segments));
+        self
+    }
+
+    /// Redact the fields with the name at any depth / 屏蔽任意深度中该名称的字段
+    pub fn redact_field(mut self, field: impl Into<String>) -> Self {
+        self.redactions.push(Redaction::Field(field.into()));
+        self
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.json", self.name))
+    }
+
+    /// Apply the redactions / 应用屏蔽
+    pub fn redacted(&self, mut value: Value) -> Value {
+        for redaction in &self.redactions {
+            match redaction {
+                Redaction::Path(segments) => redact_path(&mut value, segments),
+                Redaction::Field(field) => redact_field(&mut value, field),
+            }
+        }
+        value
+    }
+
+    /// Assert the value matches the snapshot, panics with the differences if not / 断言值与快照匹配，不匹配时携带差异panic
+    pub fn assert_json<T: Serialize>(&self, actual: &T) -> TardisResult<()> {
+        let actual = self.redacted(TardisFuns::json.obj_to_json(actual)?);
+        let path = self.path();
+        let update = std::env::var(UPDATE_SNAPSHOTS_ENV).map(|update| update == "1" || update.eq_ignore_ascii_case("true")).unwrap_or(false);
+        if update || !path.exists() {
+            warn!("[Tardis.Snapshot] Write snapshot {}", path.display());
+            std::fs::create_dir_all(&self.dir)?;
+            std::fs::write(&path, format!("{}\n", to_pretty_string(&actual)?))?;
+            return Ok(());
+        }
+        let expected = TardisFuns::json.str_to_json(&std::fs::read_to_string(&path)?)?;
+        let differences = json_diff(&expected, &actual);
+        if !differences.is_empty() {
+            panic!(
+                "snapshot {} mismatched, set {UPDATE_SNAPSHOTS_ENV}=1 to update:\n{}\n\nactual:\n{}",
+                path.display(),
+                differences.join("\n"),
+                to_pretty_string(&actual)?
+            );
+        }
+        Ok(())
+    }
+
+    /// Same as [`assert_json`](Self::assert_json) with a JSON string / 与 [`assert_json`](Self::assert_json) 相同，参数为JSON字符串
+    pub fn assert_str(&self, actual: &str) -> TardisResult<()> {
+        self.assert_json(&TardisFuns::json.str_to_json(actual)?)
+    }
+}
+
+/// Differences between the JSON values by path / 按路径列出JSON值的差异
+///
+/// # Examples
+/// ```ignore
+/// use tardis::test::snapshot::json_diff;
+/// let differences = json_diff(&json!({"a": 1, "b": [1]}), &json!({"a": 2, "b": [1, 2]}));
+/// assert_eq!(differences, vec!["$.a: expected 1, actual 2", "$.b[1]: unexpected 2"]);
+/// ```
+pub fn json_diff(expected: &Value, actual: &Value) -> Vec<String> {
+    let mut differences = Vec::new();
+    do_json_diff("$", expected, actual, &mut differences);
+    differences
+}
+
+fn do_json_diff(path: &str, expected: &Value, actual: &Value, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected) in expected {
+                match actual.get(key) {
+                    Some(actual) => do_json_diff(&format!("{path}.{key}"), expected, actual, differences),
+                    None => differences.push(format!("{path}.{key}: missing, expected {expected}")),
+                }
+            }
+            for (key, actual) in actual.iter().filter(|(key, _)| !expected.contains_key(*key)) {
+                differences.push(format!("{path}.{key}: unexpected {actual}"));
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for (idx, expected) in expected.iter().enumerate() {
+                match actual.get(idx) {
+                    Some(actual) => do_json_diff(&format!("{path}[{idx}]"), expected, actual, differences),
+                    None => differences.push(format!("{path}[{idx}]: missing, expected {expected}")),
+                }
+            }
+            for (idx, actual) in actual.iter().enumerate().skip(expected.len()) {
+                differences.push(format!("{path}[{idx}]: unexpected {actual}"));
+            }
+        }
+        (expected, actual) if expected != actual => differences.push(format!("{path}: expected {expected}, actual {actual}")),
+        _ => {}
+    }
+}
+
+fn redact_path(value: &mut Value, segments: &[String]) {
+    let Some((segment, rest)) = segments.split_first() else {
+        *value = Value::String(REDACTED.to_string());
+        return;
+    };
+    match value {
+        Value::Object(object) => {
+            if let Some(value) = object.get_mut(segment) {
+                redact_path(value, rest);
+            }
+        }
+        Value::Array(array) if segment == "*" => array.iter_mut().for_each(|value| redact_path(value, rest)),
+        Value::Array(array) => {
+            if let Some(value) = segment.parse::<usize>().ok().and_then(|idx| array.get_mut(idx)) {
+                redact_path(value, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact_field(value: &mut Value, field: &str) {
+    match value {
+        Value::Object(object) => object.iter_mut().for_each(|(key, value)| {
+            if key == field {
+                *value = Value::String(REDACTED.to_string());
+            } else {
+                redact_field(value, field);
+            }
+        }),
+        Value::Array(array) => array.iter_mut().for_each(|value| redact_field(value, field)),
+        _ => {}
+    }
+}
+
+fn to_pretty_string(value: &Value) -> TardisResult<String> {
+    serde_json::to_string_pretty(value).map_err(|error| TardisError::format_error(&format!("[Tardis.Snapshot] Serialize error: {error}"), "406-tardis-snapshot-format-error"))
+}
@@ -0,0 +1,186 @@
+//! Test harness / 测试脚手架
+//!
+//! Initializes [`TardisFuns`] with just the requested components and the test defaults, without config files or environment variables.
+//!
+//! 仅使用所需的组件及测试默认值初始化 [`TardisFuns`] ，无需配置文件或环境变量.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::test::test_harness::TardisTestHarness;
+//! use tardis::test::test_container::TardisTestContainer;
+//! TardisTestContainer::redis(|url| async move {
+//!     TardisTestHarness::builder().with_cache(&url).with_db("sqlite::memory:").with_log_level("info,tardis=trace").init().await?;
+//!     TardisFuns::cache().set("k", "v").await?;
+//!     Ok(())
+//! })
+//! .await
+//! ```
+use std::collections::HashMap;
+use std::sync::Once;
+
+use serde::Serialize;
+#[cfg(any(feature = "cache", feature = "mq", feature = "web-client"))]
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::{AppConfig, FrameworkConfig, LogConfig, TardisConfig};
+use crate::TardisFuns;
+
+/// Test harness / 测试脚手架
+///
+/// @see [TardisTestHarnessBuilder]
+pub struct TardisTestHarness;
+
+impl TardisTestHarness {
+    pub fn builder() -> TardisTestHarnessBuilder {
+        TardisTestHarnessBuilder::default()
+    }
+}
+
+/// Builder of the test harness / 测试脚手架的构建器
+///
+/// Only the web client is initialized by default, the application id is `tardis-test`.
+///
+/// 默认仅初始化Web客户端，应用id为 `tardis-test` .
+#[derive(Default)]
+pub struct TardisTestHarnessBuilder {
+    #[cfg(feature = "reldb-core")]
+    db: Option<String>,
+    #[cfg(feature = "cache")]
+    cache: Option<String>,
+    #[cfg(feature = "mq")]
+    mq: Option<String>,
+    #[cfg(feature = "web-client")]
+    search: Option<String>,
+    #[cfg(feature = "web-server")]
+    web_server_port: Option<u16>,
+    log_level: Option<String>,
+    custom_config: HashMap<String, serde_json::Value>,
+    configurers: Vec<Box<dyn FnOnce(&mut FrameworkConfig) + Send>>,
+}
+
+impl TardisTestHarnessBuilder {
+    /// Use the relational database, e.g. `sqlite::memory:` / 使用关系型数据库，如 `sqlite::memory:`
+    #[cfg(feature = "reldb-core")]
+    pub fn with_db(mut self, url: impl Into<String>) -> Self {
+        self.db = Some(url.into());
+        self
+    }
+
+    /// Use the cache, `mem://` for the in-memory cache / 使用缓存， `mem://` 为内存缓存
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, url: impl Into<String>) -> Self {
+        self.cache = Some(url.into());
+        self
+    }
+
+    /// Use the message queue, `mem://` for the in-memory MQ / 使用消息队列， `mem://` 为内存MQ
+    #[cfg(feature = "mq")]
+    pub fn with_mq(mut self, url: impl Into<String>) -> Self {
+        self.mq = Some(url.into());
+        self
+    }
+
+    #[cfg(feature = "web-client")]
+    pub fn with_search(mut self, url: impl Into<String>) -> Self {
+        self.search = Some(url.into());
+        self
+    }
+
+    /// Use the web server listening on `127.0.0.1:<port>` / 使用监听 `127.0.0.1:<port>` 的Web服务
+    #[cfg(feature = "web-server")]
+    pub fn with_web_server(mut self, port: u16) -> Self {
+        self.web_server_port = Some(port);
+        self
+    }
+
+    /// Log level with directives, e.g. `info,tardis=trace`, default is `info` / 带指令的日志级别，如 `info,tardis=trace` ，默认为 `info`
+    pub fn with_log_level(mut self, level: impl Into<String>) -> Self {
+        self.log_level = Some(level.into());
+        self
+    }
+
+    /// Add a custom config, which can be read by [`TardisFuns::cs_config`] / 添加自定义配置，可通过 [`TardisFuns::cs_config`] 读取
+    pub fn with_custom_config<T: Serialize>(mut self, code: impl Into<String>, config: &T) -> TardisResult<Self> {
+        self.custom_config.insert(code.into(), TardisFuns::json.obj_to_json(config)?);
+        Ok(self)
+    }
+
+    /// Adjust the framework config for the rest cases / 调整框架配置以用于其他场景
+    pub fn configure(mut self, fun: impl FnOnce(&mut FrameworkConfig) + Send + 'static) -> Self {
+        self.configurers.push(Box::new(fun));
+        self
+    }
+
+    /// Build the config without initializing / 构建配置但不初始化
+    pub fn build_config(self) -> TardisResult<TardisConfig> {
+        let mut fw = FrameworkConfig::builder().app(AppConfig::builder().id("tardis-test".to_string()).name("Tardis Test".to_string()).build()).build();
+        if let Some(level) = &self.log_level {
+            let mut directives = level.split(',').map(str::trim).filter(|directive| !directive.is_empty());
+            let log = fw.log.get_or_insert_with(LogConfig::default);
+            if let Some(level) = directives.next() {
+                log.level = parse_directive(level)?;
+            }
+            log.directives = directives.map(parse_directive).collect::<TardisResult<Vec<_>>>()?;
+        }
+        #[cfg(feature = "reldb-core")]
+        if let Some(url) = self.db {
+            use crate::config::config_dto::DBModuleConfig;
+            fw.db = Some(DBModuleConfig::builder().url(url).max_connections(5).min_connections(1).build().into());
+        }
+        #[cfg(feature = "cache")]
+        if let Some(url) = self.cache {
+            use crate::config::config_dto::CacheModuleConfig;
+            fw.cache = Some(CacheModuleConfig::builder().url(parse_url(&url)?).build().into());
+        }
+        #[cfg(feature = "mq")]
+        if let Some(url) = self.mq {
+            use crate::config::config_dto::MQModuleConfig;
+            fw.mq = Some(MQModuleConfig::builder().url(parse_url(&url)?).build().into());
+        }
+        #[cfg(feature = "web-client")]
+        if let Some(url) = self.search {
+            use crate::config::config_dto::SearchModuleConfig;
+            fw.search = Some(SearchModuleConfig::builder().url(parse_url(&url)?).timeout_sec(10).build().into());
+        }
+        #[cfg(feature = "web-server")]
+        if let Some(port) = self.web_server_port {
+            use crate::config::config_dto::{WebServerCommonConfig, WebServerConfig, WebServerModuleConfig};
+            fw.web_server = Some(
+                WebServerConfig::builder()
+                    .common(WebServerCommonConfig::builder().host(std::net::Ipv4Addr::LOCALHOST).port(port).build())
+                    .default(WebServerModuleConfig::default())
+                    .build(),
+            );
+        }
+        for configurer in self.configurers {
+            configurer(&mut fw);
+        }
+        Ok(TardisConfig::builder().cs(self.custom_config).fw(fw).build())
+    }
+
+    /// Initialize [`TardisFuns`], the log is initialized only once per process / 初始化 [`TardisFuns`] ，日志在每个进程中仅初始化一次
+    pub async fn init(self) -> TardisResult<()> {
+        let config = self.build_config()?;
+        static LOG_INITIALIZED: Once = Once::new();
+        let mut result = Ok(());
+        LOG_INITIALIZED.call_once(|| result = TardisFuns::init_log());
+        result?;
+        TardisFuns::init_conf(config).await
+    }
+}
+
+fn parse_directive(directive: &str) -> TardisResult<tracing_subscriber::filter::Directive> {
+    directive.parse().map_err(|error| {
+        TardisError::format_error(
+            &format!("[Tardis.TestHarness] Invalid log directive {directive}: {error}"),
+            "406-tardis-test-harness-log-error",
+        )
+    })
+}
+
+#[cfg(any(feature = "cache", feature = "mq", feature = "web-client"))]
+fn parse_url(url: &str) -> TardisResult<Url> {
+    Url::parse(url).map_err(|error| TardisError::format_error(&format!("[Tardis.TestHarness] Invalid url {url}: {error}"), "406-tardis-test-harness-url-error"))
+}
@@ -0,0 +1,205 @@
+//! In-process test client of the web server / Web服务的进程内测试客户端
+//!
+//! Dispatches requests directly to the modules added to [`TardisWebServer`] without binding a port,
+//! so the handler tests are fast and can run in parallel.
+//!
+//! 直接将请求分发到已添加到 [`TardisWebServer`] 的模块，无需绑定端口，从而处理函数的测试更快且可并行执行.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::test::web_test_client::TardisWebTestClient;
+//! TardisFuns::web_server().add_module("todo", TodoApi).await;
+//! let client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?.context(&ctx)?;
+//! let todo: TodoDetailResp = client.post("/todo/todo", &TodoAddReq { .. }).await?.assert_status(200).assert_ok()?;
+//! client.get("/todo/todo/1").await?.assert_code("404-todo-not-found");
+//! ```
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use poem::http::uri::Scheme;
+use poem::http::Method;
+use poem::web::{LocalAddr, RemoteAddr};
+use poem::{Addr, Endpoint, Request, Route};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::test::snapshot::TardisSnapshot;
+use crate::web::web_server::TardisWebServer;
+use crate::TardisFuns;
+
+/// In-process test client / 进程内测试客户端
+pub struct TardisWebTestClient {
+    route: Route,
+    default_headers: Vec<(String, String)>,
+    remote_addr: Option<SocketAddr>,
+}
+
+impl TardisWebTestClient {
+    /// Take out the modules added to the server, the server must not be running
+    /// / 取出已添加到服务的模块，服务必须未运行
+    pub async fn from_server(server: &TardisWebServer) -> TardisResult<TardisWebTestClient> {
+        let route =
+            server.take_route().await.ok_or_else(|| TardisError::conflict("[Tardis.WebTestClient] The web server is already running", "409-tardis-web-test-client-running"))?;
+        Ok(Self::from_route(route))
+    }
+
+    pub fn from_route(route: Route) -> TardisWebTestClient {
+        TardisWebTestClient {
+            route,
+            default_headers: Vec::new(),
+            remote_addr: None,
+        }
+    }
+
+    /// Add a header to all the requests / 为所有请求添加请求头
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Send all the requests from the remote address / 从该远端地址发送所有请求
+    pub fn remote_addr(mut self, remote_addr: SocketAddr) -> Self {
+        self.remote_addr = Some(remote_addr);
+        self
+    }
+
+    /// Inject the context to all the requests by the configured context header / 通过配置的上下文请求头为所有请求注入上下文
+    pub fn context(self, ctx: &TardisContext) -> TardisResult<Self> {
+        let header_name = TardisFuns::fw_config_opt()
+            .and_then(|fw_config| fw_config.web_server.as_ref().map(|web_server| web_server.context_conf.context_header_name.clone()))
+            .unwrap_or_else(|| "Tardis-Context".to_string());
+        let ctx = general_purpose::STANDARD.encode(TardisFuns::json.obj_to_string(ctx)?);
+        Ok(self.header(header_name, ctx))
+    }
+
+    pub async fn get(&self, path: &str) -> TardisResult<TardisTestResponse> {
+        self.request(Method::GET, path, Vec::new(), None).await
+    }
+
+    pub async fn delete(&self, path: &str) -> TardisResult<TardisTestResponse> {
+        self.request(Method::DELETE, path, Vec::new(), None).await
+    }
+
+    pub async fn post<B: Serialize>(&self, path: &str, body: &B) -> TardisResult<TardisTestResponse> {
+        self.request_json(Method::POST, path, body).await
+    }
+
+    pub async fn put<B: Serialize>(&self, path: &str, body: &B) -> TardisResult<TardisTestResponse> {
+        self.request_json(Method::PUT, path, body).await
+    }
+
+    pub async fn patch<B: Serialize>(&self, path: &str, body: &B) -> TardisResult<TardisTestResponse> {
+        self.request_json(Method::PATCH, path, body).await
+    }
+
+    async fn request_json<B: Serialize>(&self, method: Method, path: &str, body: &B) -> TardisResult<TardisTestResponse> {
+        let headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        self.request(method, path, headers, Some(TardisFuns::json.obj_to_string(body)?)).await
+    }
+
+    /// Send a request with the extra headers / 发送带额外请求头的请求
+    pub async fn request(&self, method: Method, path: &str, headers: Vec<(String, String)>, body: Option<String>) -> TardisResult<TardisTestResponse> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(path.parse().map_err(|_| TardisError::format_error(&format!("[Tardis.WebTestClient] Invalid path {path}"), "406-tardis-web-test-client-path-error"))?);
+        for (key, value) in self.default_headers.iter().chain(headers.iter()) {
+            request = request.header(key.as_str(), value.as_str());
+        }
+        let mut request = match body {
+            Some(body) => request.body(body),
+            None => request.finish(),
+        };
+        // the remote address can only be set by converting from the http request
+        if let Some(remote_addr) = self.remote_addr {
+            let http_request: poem::http::Request<_> = request.into();
+            request = Request::from((http_request, LocalAddr::default(), RemoteAddr(Addr::SocketAddr(remote_addr)), Scheme::HTTP));
+        }
+        let response = self.route.get_response(request).await;
+        let status = response.status().as_u16();
+        let headers = response.headers().iter().map(|(key, value)| (key.to_string(), value.to_str().unwrap_or_default().to_string())).collect();
+        let body = response
+            .into_body()
+            .into_string()
+            .await
+            .map_err(|error| TardisError::format_error(&format!("[Tardis.WebTestClient] Read body error: {error}"), "406-tardis-web-test-client-body-error"))?;
+        Ok(TardisTestResponse { status, headers, body })
+    }
+}
+
+/// Response of the in-process test client / 进程内测试客户端的响应
+///
+/// The `assert_*` methods panic with the response body when the assertion fails.
+///
+/// `assert_*` 方法在断言失败时携带响应体panic.
+#[derive(Debug, Clone)]
+pub struct TardisTestResponse {
+    pub status: u16,
+    /// header names are in lowercase
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl TardisTestResponse {
+    pub fn assert_status(&self, status: u16) -> &Self {
+        assert_eq!(self.status, status, "unexpected status, body: {}", self.body);
+        self
+    }
+
+    pub fn assert_header(&self, key: &str, value: &str) -> &Self {
+        assert_eq!(
+            self.headers.get(&key.to_lowercase()).map(String::as_str),
+            Some(value),
+            "unexpected header {key}, body: {}",
+            self.body
+        );
+        self
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> TardisResult<T> {
+        TardisFuns::json.str_to_obj(&self.body)
+    }
+
+    /// The `code` of the [`TardisResp`](crate::web::web_resp::TardisResp) / [`TardisResp`](crate::web::web_resp::TardisResp) 的 `code`
+    pub fn resp_code(&self) -> TardisResult<String> {
+        Ok(self.json::<serde_json::Value>()?.get("code").and_then(|code| code.as_str()).unwrap_or_default().to_string())
+    }
+
+    /// Assert the [`TardisResp`](crate::web::web_resp::TardisResp) code is `code` / 断言 [`TardisResp`](crate::web::web_resp::TardisResp) 的code为 `code`
+    pub fn assert_code(&self, code: &str) -> &Self {
+        assert_eq!(self.resp_code().ok().as_deref(), Some(code), "unexpected code, body: {}", self.body);
+        self
+    }
+
+    /// Assert the [`TardisResp`](crate::web::web_resp::TardisResp) is ok (code `200`) and return the data
+    /// / 断言 [`TardisResp`](crate::web::web_resp::TardisResp) 成功（code为 `200` ）并返回数据
+    pub fn assert_ok<T: DeserializeOwned>(&self) -> TardisResult<T> {
+        self.assert_code("200");
+        let data = self.json::<serde_json::Value>()?.get_mut("data").map(serde_json::Value::take).unwrap_or_default();
+        TardisFuns::json.json_to_obj(data)
+    }
+
+    /// Assert the [`TardisResp`](crate::web::web_resp::TardisResp) is ok and the data is a [`TardisPage`](crate::web::web_resp::TardisPage)
+    /// of the pagination, return the records / 断言 [`TardisResp`](crate::web::web_resp::TardisResp) 成功且数据为该分页的 [`TardisPage`](crate::web::web_resp::TardisPage) ，返回记录
+    pub fn assert_page<T: DeserializeOwned>(&self, page_number: u64, page_size: u64, total_size: u64) -> TardisResult<Vec<T>> {
+        let mut page = self.assert_ok::<serde_json::Value>()?;
+        let field = |name: &str| page.get(name).and_then(serde_json::Value::as_u64);
+        assert_eq!(
+            (field("page_number"), field("page_size"), field("total_size")),
+            (Some(page_number), Some(page_size), Some(total_size)),
+            "unexpected pagination (page_number, page_size, total_size), body: {}",
+            self.body
+        );
+        TardisFuns::json.json_to_obj(page.get_mut("records").map(serde_json::Value::take).unwrap_or_default())
+    }
+
+    /// Assert the body matches the snapshot / 断言响应体与快照匹配
+    pub fn assert_snapshot(&self, snapshot: &TardisSnapshot) -> TardisResult<&Self> {
+        snapshot.assert_str(&self.body)?;
+        Ok(self)
+    }
+}
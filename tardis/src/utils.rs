@@ -2,7 +2,15 @@ pub mod cached_json_value;
 pub(crate) use cached_json_value::*;
 pub mod tardis_component;
 pub(crate) use tardis_component::*;
+pub mod circuit_breaker;
+pub mod consistent_hash;
+pub mod debounce;
+pub mod event_bus;
 pub mod initializer;
 pub mod mapper;
 pub mod package_name;
+pub mod retry;
+pub use retry::retry;
+pub mod single_flight;
+pub mod startup;
 pub mod tardis_static;
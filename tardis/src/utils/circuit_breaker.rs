@@ -0,0 +1,105 @@
+//! Circuit breaker / 熔断器
+//!
+//! The breaker opens after `failure_threshold` consecutive failures and rejects the calls for `open_duration`,
+//! then it's half-open and lets a single trial call through, which closes the breaker on success or opens it again on failure.
+//!
+//! 熔断器在连续失败 `failure_threshold` 次后打开并在 `open_duration` 内拒绝调用，
+//! 之后进入半开状态并放行一次试探调用，试探成功则关闭熔断器，失败则再次打开.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::utils::circuit_breaker::CircuitBreaker;
+//! let breaker = CircuitBreaker::new(5, Duration::from_secs(30));
+//! if !breaker.try_acquire() {
+//!     return Err(TardisError::io_error("dependency is down", ""));
+//! }
+//! match call().await {
+//!     Ok(_) => breaker.on_success(),
+//!     Err(_) => breaker.on_failure(),
+//! }
+//! ```
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// State of the circuit breaker / 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { trial: bool },
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold: failure_threshold.max(1),
+            open_duration,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        match &*self.state.lock().expect("[Tardis.CircuitBreaker] lock poisoned") {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { until } if Instant::now() >= *until => CircuitState::HalfOpen,
+            State::Open { .. } => CircuitState::Open,
+            State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Whether the call is allowed, the caller must report the outcome by [`Self::on_success`] or [`Self::on_failure`] if it's allowed
+    /// / 是否允许调用，允许时调用方须通过 [`Self::on_success`] 或 [`Self::on_failure`] 报告结果
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().expect("[Tardis.CircuitBreaker] lock poisoned");
+        match &mut *state {
+            State::Closed { .. } => true,
+            State::Open { until } => {
+                if Instant::now() >= *until {
+                    *state = State::HalfOpen { trial: true };
+                    true
+                } else {
+                    false
+                }
+            }
+            State::HalfOpen { trial } => {
+                if *trial {
+                    false
+                } else {
+                    *trial = true;
+                    true
+                }
+            }
+        }
+    }
+
+    pub fn on_success(&self) {
+        *self.state.lock().expect("[Tardis.CircuitBreaker] lock poisoned") = State::Closed { failures: 0 };
+    }
+
+    pub fn on_failure(&self) {
+        let mut state = self.state.lock().expect("[Tardis.CircuitBreaker] lock poisoned");
+        match &mut *state {
+            State::Closed { failures } if *failures + 1 < self.failure_threshold => *failures += 1,
+            State::Open { .. } => {}
+            _ => {
+                *state = State::Open {
+                    until: Instant::now() + self.open_duration,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Consistent hashing / 一致性哈希
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::utils::consistent_hash::ConsistentHash;
+//! let mut ring = ConsistentHash::new(160);
+//! ring.add("10.0.0.1:6379".to_string());
+//! ring.add("10.0.0.2:6379".to_string());
+//! let node = ring.get("user:1001").unwrap();
+//! ```
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Consistent hash ring with virtual nodes / 带虚拟节点的一致性哈希环
+///
+/// The hash is stable across processes and platforms,
+/// so different replicas with the same members always locate the same node for a key.
+///
+/// 哈希值在不同进程及平台间保持稳定，因此成员相同的不同副本对同一key总是定位到相同的节点.
+#[derive(Debug, Clone)]
+pub struct ConsistentHash<N> {
+    virtual_nodes: usize,
+    ring: BTreeMap<u64, N>,
+    members: Vec<N>,
+}
+
+impl<N> ConsistentHash<N>
+where
+    N: Display + Clone + PartialEq,
+{
+    /// Create a ring, `virtual_nodes` is the number of virtual nodes per member / 创建哈希环， `virtual_nodes` 为每个成员的虚拟节点数
+    pub fn new(virtual_nodes: usize) -> Self {
+        ConsistentHash {
+            virtual_nodes: virtual_nodes.max(1),
+            ring: BTreeMap::new(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Add a member, ignored if already exists / 添加成员，已存在时忽略
+    pub fn add(&mut self, node: N) {
+        self.add_with_weight(node, 1);
+    }
+
+    /// Add a member with weight, the number of its virtual nodes is `virtual_nodes * weight` / 添加带权重的成员，其虚拟节点数为 `virtual_nodes * weight`
+    pub fn add_with_weight(&mut self, node: N, weight: usize) {
+        if self.members.contains(&node) {
+            return;
+        }
+        let name = node.to_string();
+        for idx in 0..self.virtual_nodes * weight.max(1) {
+            self.ring.entry(Self::hash(format!("{name}#{idx}").as_bytes())).or_insert_with(|| node.clone());
+        }
+        self.members.push(node);
+    }
+
+    /// Remove a member / 移除成员
+    pub fn remove(&mut self, node: &N) {
+        if !self.members.contains(node) {
+            return;
+        }
+        self.ring.retain(|_, value| value != node);
+        self.members.retain(|member| member != node);
+    }
+
+    /// Locate the member of the key / 定位key所属的成员
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&N> {
+        let hash = Self::hash(key.as_ref());
+        self.ring.range(hash..).next().or_else(|| self.ring.iter().next()).map(|(_, node)| node)
+    }
+
+    /// Locate up to `n` distinct members of the key in ring order, E.g. for replicas / 按环的顺序定位key所属的至多 `n` 个不同成员，如用于副本
+    pub fn get_n(&self, key: impl AsRef<[u8]>, n: usize) -> Vec<&N> {
+        let n = n.min(self.members.len());
+        let hash = Self::hash(key.as_ref());
+        let mut nodes: Vec<&N> = Vec::with_capacity(n);
+        for (_, node) in self.ring.range(hash..).chain(self.ring.range(..hash)) {
+            if nodes.len() >= n {
+                break;
+            }
+            if !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+        nodes
+    }
+
+    pub fn members(&self) -> &[N] {
+        &self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// FNV-1a followed by the MurmurHash3 finalizer for better avalanche / FNV-1a 后接 MurmurHash3 的混淆函数以获得更好的雪崩效应
+    fn hash(data: &[u8]) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in data {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+        hash ^= hash >> 33;
+        hash
+    }
+}
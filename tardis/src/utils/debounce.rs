@@ -0,0 +1,60 @@
+//! Debounce bursty triggers / 防抖
+//!
+//! # Examples
+//! ```ignore
+//! use std::time::Duration;
+//! use tardis::utils::debounce::Debouncer;
+//! let debouncer = Debouncer::new(Duration::from_millis(500));
+//! // Only the last trigger within 500ms is executed.
+//! for _ in 0..10 {
+//!     debouncer.trigger(async { reload_config().await });
+//! }
+//! ```
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Debouncer / 防抖器
+///
+/// Each trigger delays the action by `delay`, the pending action is replaced by the newer trigger,
+/// so only the last trigger of a burst is executed.
+/// An action that has already started is never cancelled.
+///
+/// 每次触发会将动作延迟 `delay` 执行，新的触发会替换未执行的动作，因此一组连续触发中只有最后一次会被执行.
+/// 已开始执行的动作不会被取消.
+#[derive(Debug, Clone)]
+pub struct Debouncer {
+    delay: Duration,
+    generation: Arc<AtomicU64>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Debouncer {
+            delay,
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Trigger the action, must be called within a tokio runtime / 触发动作，必须在tokio运行时中调用
+    pub fn trigger<Fut>(&self, action: Fut)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let current = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let generation = self.generation.clone();
+        let delay = self.delay;
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if generation.load(Ordering::SeqCst) == current {
+                action.await;
+            }
+        });
+    }
+
+    /// Cancel the pending action / 取消未执行的动作
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,281 @@
+//! In-process event bus / 进程内事件总线
+//!
+//! Publish/subscribe of typed events between the components of a service, the selected topics can be bridged to the MQ
+//! client so that the events are also propagated to the other services.
+//!
+//! 服务内组件间的类型化事件发布/订阅，可将选定的主题桥接到MQ客户端从而使事件也传播到其他服务.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::utils::event_bus::TardisEvent;
+//! use tardis::TardisFuns;
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct UserCreated {
+//!     id: String,
+//! }
+//!
+//! impl TardisEvent for UserCreated {
+//!     const TOPIC: &'static str = "user.created";
+//! }
+//!
+//! TardisFuns::event().subscribe(|event: UserCreated| async move {
+//!     info!("user {} created", event.id);
+//!     Ok(())
+//! });
+//! // propagate to the other services (optional)
+//! TardisFuns::event().bridge::<UserCreated>().await?;
+//! TardisFuns::event().publish(UserCreated { id: "u1".to_string() }).await?;
+//! ```
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::{error, trace};
+
+use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+#[cfg(feature = "mq")]
+use crate::mq::mq_client::TardisMQClient;
+use crate::TardisFuns;
+
+/// Header of the bridged messages with the id of the publishing bus / 桥接消息中发布方总线id的请求头
+pub const EVENT_SOURCE_HEADER: &str = "tardis-event-source";
+
+/// Typed event / 类型化事件
+///
+/// The topic identifies the event when it is bridged to the MQ client.
+///
+/// 事件桥接到MQ客户端时以主题标识.
+pub trait TardisEvent: Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    /// Topic of the event / 事件主题
+    const TOPIC: &'static str;
+}
+
+type EventFuture = Pin<Box<dyn Future<Output = TardisResult<()>> + Send>>;
+type EventHandler = Arc<dyn Fn(Arc<dyn Any + Send + Sync>) -> EventFuture + Send + Sync>;
+
+struct EventSubscriber {
+    id: u64,
+    handler: EventHandler,
+}
+
+#[derive(Default)]
+struct EventBusInner {
+    id: String,
+    next_subscriber_id: AtomicU64,
+    subscribers: RwLock<HashMap<TypeId, Vec<EventSubscriber>>>,
+    #[cfg(feature = "mq")]
+    bridges: RwLock<HashMap<TypeId, Arc<TardisMQClient>>>,
+}
+
+/// Event bus / 事件总线
+///
+/// The global bus is [`TardisFuns::event`](TardisFuns::event) .
+/// Each subscriber of an event is called in a separate task with the context of the publisher,
+/// the failure or the panic of a subscriber doesn't affect the others.
+///
+/// 全局总线为 [`TardisFuns::event`](TardisFuns::event) .
+/// 事件的每个订阅者在带有发布方上下文的独立task中调用，某个订阅者的失败或panic不影响其他订阅者.
+#[derive(Clone)]
+pub struct TardisEventBus {
+    inner: Arc<EventBusInner>,
+}
+
+impl Default for TardisEventBus {
+    fn default() -> Self {
+        TardisEventBus {
+            inner: Arc::new(EventBusInner {
+                id: TardisFuns::field.nanoid(),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl TardisEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id of the bus, used to ignore the own bridged events / 总线id，用于忽略自身桥接的事件
+    pub fn id(&self) -> &str {
+        &self.inner.id
+    }
+
+    /// Subscribe the event, return the id of the subscription / 订阅事件，返回订阅id
+    pub fn subscribe<E, F, T>(&self, handler: F) -> u64
+    where
+        E: TardisEvent,
+        F: Fn(E) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        let id = self.inner.next_subscriber_id.fetch_add(1, Ordering::SeqCst);
+        let handler: EventHandler = Arc::new(move |event: Arc<dyn Any + Send + Sync>| -> EventFuture {
+            match event.downcast_ref::<E>() {
+                Some(event) => Box::pin(handler(event.clone())),
+                None => Box::pin(async { Ok(()) }),
+            }
+        });
+        self.subscribers_mut().entry(TypeId::of::<E>()).or_default().push(EventSubscriber { id, handler });
+        trace!("[Tardis.EventBus] Subscribed {}, subscription:{}", E::TOPIC, id);
+        id
+    }
+
+    /// Cancel the subscription / 取消订阅
+    pub fn unsubscribe(&self, subscription_id: u64) -> bool {
+        let mut subscribers = self.subscribers_mut();
+        for handlers in subscribers.values_mut() {
+            if let Some(index) = handlers.iter().position(|subscriber| subscriber.id == subscription_id) {
+                handlers.remove(index);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Get the number of the subscribers of the event / 获取事件的订阅者数量
+    pub fn subscribers<E: TardisEvent>(&self) -> usize {
+        self.subscribers_ref().get(&TypeId::of::<E>()).map(|handlers| handlers.len()).unwrap_or(0)
+    }
+
+    /// Publish the event without waiting for the subscribers / 发布事件，不等待订阅者
+    ///
+    /// The failures of the subscribers are logged, the error is returned only when the bridged MQ client fails to publish.
+    ///
+    /// 订阅者的失败仅记录日志，只有桥接的MQ客户端发布失败时才返回错误.
+    pub async fn publish<E: TardisEvent>(&self, event: E) -> TardisResult<()> {
+        #[cfg(feature = "mq")]
+        self.forward(&event).await?;
+        for task in self.dispatch(event) {
+            TardisContext::spawn(async move {
+                let _ = task.await;
+            });
+        }
+        Ok(())
+    }
+
+    /// Publish the event and wait for the subscribers / 发布事件并等待订阅者
+    ///
+    /// Every subscriber is called, then the first error (a panic is returned as an internal error) is returned.
+    ///
+    /// 调用所有订阅者后返回第一个错误（panic作为内部错误返回）.
+    pub async fn publish_and_wait<E: TardisEvent>(&self, event: E) -> TardisResult<()> {
+        #[cfg(feature = "mq")]
+        self.forward(&event).await?;
+        self.dispatch_and_wait(event).await
+    }
+
+    async fn dispatch_and_wait<E: TardisEvent>(&self, event: E) -> TardisResult<()> {
+        let mut result = Ok(());
+        for task in self.dispatch(event) {
+            if let Err(error) = task.await {
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+        }
+        result
+    }
+
+    /// Start a task for each subscriber / 为每个订阅者启动task
+    fn dispatch<E: TardisEvent>(&self, event: E) -> Vec<EventFuture> {
+        let handlers =
+            self.subscribers_ref().get(&TypeId::of::<E>()).map(|handlers| handlers.iter().map(|subscriber| subscriber.handler.clone()).collect::<Vec<_>>()).unwrap_or_default();
+        trace!("[Tardis.EventBus] Dispatch {} to {} subscribers", E::TOPIC, handlers.len());
+        let event: Arc<dyn Any + Send + Sync> = Arc::new(event);
+        handlers
+            .into_iter()
+            .map(|handler| {
+                // run in a separate task to catch the panic
+                let handle = TardisContext::spawn(handler(event.clone()));
+                Box::pin(async move {
+                    let result = match handle.await {
+                        Ok(result) => result,
+                        Err(error) => Err(TardisError::internal_error(
+                            &format!("[Tardis.EventBus] Subscriber of {} panicked: {error}", E::TOPIC),
+                            "500-tardis-event-subscriber-panicked",
+                        )),
+                    };
+                    if let Err(error) = &result {
+                        error!("[Tardis.EventBus] Subscriber of {} failed | {error}", E::TOPIC);
+                    }
+                    result
+                }) as EventFuture
+            })
+            .collect()
+    }
+
+    /// Bridge the event to the default MQ client / 将事件桥接到默认MQ客户端
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "mq")] .
+    #[cfg(feature = "mq")]
+    pub async fn bridge<E: TardisEvent>(&self) -> TardisResult<()> {
+        self.bridge_with::<E>(TardisFuns::mq()).await
+    }
+
+    /// Bridge the event to the MQ client / 将事件桥接到MQ客户端
+    ///
+    /// The published events are also published to the topic of the event in the MQ, and the events published by the other
+    /// services are dispatched to the local subscribers, whose failures are returned to the MQ client to be redelivered.
+    ///
+    /// 发布的事件也会发布到MQ中该事件的主题，其他服务发布的事件会分发给本地订阅者，本地订阅者的失败会返回给MQ客户端以重新投递.
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "mq")] .
+    #[cfg(feature = "mq")]
+    pub async fn bridge_with<E: TardisEvent>(&self, mq: Arc<TardisMQClient>) -> TardisResult<()> {
+        if self.bridges_ref().contains_key(&TypeId::of::<E>()) {
+            return Err(TardisError::conflict(
+                &format!("[Tardis.EventBus] Event {} has been bridged", E::TOPIC),
+                "409-tardis-event-bridged",
+            ));
+        }
+        let bus = self.clone();
+        mq.subscribe(E::TOPIC, move |(header, message)| {
+            let bus = bus.clone();
+            async move {
+                if header.get(EVENT_SOURCE_HEADER).map(|source| source == bus.id()).unwrap_or(false) {
+                    return Ok(());
+                }
+                let event = TardisFuns::json.str_to_obj::<E>(&message)?;
+                bus.dispatch_and_wait(event).await
+            }
+        })
+        .await?;
+        self.bridges_mut().insert(TypeId::of::<E>(), mq);
+        Ok(())
+    }
+
+    #[cfg(feature = "mq")]
+    async fn forward<E: TardisEvent>(&self, event: &E) -> TardisResult<()> {
+        let Some(mq) = self.bridges_ref().get(&TypeId::of::<E>()).cloned() else {
+            return Ok(());
+        };
+        let message = TardisFuns::json.obj_to_string(event)?;
+        mq.publish(E::TOPIC, message, &HashMap::from([(EVENT_SOURCE_HEADER.to_string(), self.id().to_string())])).await
+    }
+
+    fn subscribers_ref(&self) -> RwLockReadGuard<'_, HashMap<TypeId, Vec<EventSubscriber>>> {
+        self.inner.subscribers.read().expect("[Tardis.EventBus] subscribers lock poisoned")
+    }
+
+    fn subscribers_mut(&self) -> RwLockWriteGuard<'_, HashMap<TypeId, Vec<EventSubscriber>>> {
+        self.inner.subscribers.write().expect("[Tardis.EventBus] subscribers lock poisoned")
+    }
+
+    #[cfg(feature = "mq")]
+    fn bridges_ref(&self) -> RwLockReadGuard<'_, HashMap<TypeId, Arc<TardisMQClient>>> {
+        self.inner.bridges.read().expect("[Tardis.EventBus] bridges lock poisoned")
+    }
+
+    #[cfg(feature = "mq")]
+    fn bridges_mut(&self) -> RwLockWriteGuard<'_, HashMap<TypeId, Arc<TardisMQClient>>> {
+        self.inner.bridges.write().expect("[Tardis.EventBus] bridges lock poisoned")
+    }
+}
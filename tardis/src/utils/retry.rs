@@ -0,0 +1,195 @@
+//! Async retry with backoff / 带退避的异步重试
+//!
+//! # Examples
+//! ```ignore
+//! use std::time::Duration;
+//! use tardis::utils::retry::{retry, RetryPolicy};
+//! let policy = RetryPolicy::exponential(Duration::from_millis(100), Duration::from_secs(5))
+//!     .with_jitter()
+//!     .with_max_retries(5)
+//!     .with_max_elapsed(Duration::from_secs(30))
+//!     .retry_on(|e: &TardisError| e.code.starts_with("5"));
+//! let resp = retry(&policy, || async { TardisFuns::web_client().get_to_str("https://example.com", None).await }).await?;
+//! ```
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tracing::debug;
+
+use crate::basic::error::TardisError;
+
+/// Backoff strategy / 退避策略
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// Wait the same delay between attempts / 每次重试等待相同的时长
+    Fixed(Duration),
+    /// Wait `initial * multiplier ^ retries`, capped at `max` / 等待 `initial * multiplier ^ 重试次数` ，最大为 `max`
+    Exponential { initial: Duration, multiplier: f64, max: Duration },
+}
+
+impl Backoff {
+    /// Delay before the `retries`-th retry (starting from 0) / 第 `retries` 次重试（从0开始）前的等待时长
+    pub fn delay(&self, retries: u32) -> Duration {
+        match self {
+            Backoff::Fixed(delay) => *delay,
+            Backoff::Exponential { initial, multiplier, max } => {
+                let delay = initial.as_secs_f64() * multiplier.powi(retries.min(i32::MAX as u32) as i32);
+                if !delay.is_finite() || delay >= max.as_secs_f64() {
+                    *max
+                } else {
+                    Duration::from_secs_f64(delay)
+                }
+            }
+        }
+    }
+}
+
+/// Retry policy / 重试策略
+///
+/// By default, all errors are retried up to 3 times.
+///
+/// 默认对所有错误最多重试3次.
+pub struct RetryPolicy<E = TardisError> {
+    backoff: Backoff,
+    jitter: bool,
+    max_retries: Option<u32>,
+    max_elapsed: Option<Duration>,
+    retry_on: Option<Arc<dyn Fn(&E) -> bool + Send + Sync>>,
+}
+
+impl<E> Clone for RetryPolicy<E> {
+    fn clone(&self) -> Self {
+        RetryPolicy {
+            backoff: self.backoff,
+            jitter: self.jitter,
+            max_retries: self.max_retries,
+            max_elapsed: self.max_elapsed,
+            retry_on: self.retry_on.clone(),
+        }
+    }
+}
+
+impl<E> Debug for RetryPolicy<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("backoff", &self.backoff)
+            .field("jitter", &self.jitter)
+            .field("max_retries", &self.max_retries)
+            .field("max_elapsed", &self.max_elapsed)
+            .field("retry_on", &self.retry_on.is_some())
+            .finish()
+    }
+}
+
+impl<E> RetryPolicy<E> {
+    const DEFAULT_MAX_RETRIES: u32 = 3;
+
+    pub fn new(backoff: Backoff) -> Self {
+        RetryPolicy {
+            backoff,
+            jitter: false,
+            max_retries: Some(Self::DEFAULT_MAX_RETRIES),
+            max_elapsed: None,
+            retry_on: None,
+        }
+    }
+
+    /// Fixed backoff / 固定退避
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(Backoff::Fixed(delay))
+    }
+
+    /// Exponential backoff with multiplier `2` / 倍数为 `2` 的指数退避
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Self::new(Backoff::Exponential { initial, multiplier: 2.0, max })
+    }
+
+    /// Randomize each delay within `[0, delay]` (full jitter) to avoid retry storms / 将每次等待时长随机化到 `[0, delay]` 区间（全抖动），以避免重试风暴
+    pub fn with_jitter(mut self) -> Self {
+        self.jitter = true;
+        self
+    }
+
+    /// Maximum number of retries, not including the first attempt / 最大重试次数，不包含首次执行
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Retry until succeeded or stopped by other limits / 一直重试直到成功或被其它限制终止
+    pub fn with_unlimited_retries(mut self) -> Self {
+        self.max_retries = None;
+        self
+    }
+
+    /// Stop retrying if the next attempt would start after `max_elapsed` since the first attempt / 若下次执行时间距首次执行超过 `max_elapsed` 则停止重试
+    pub fn with_max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = Some(max_elapsed);
+        self
+    }
+
+    /// Only retry the errors matched by the predicate / 仅重试满足条件的错误
+    pub fn retry_on(mut self, predicate: impl Fn(&E) -> bool + Send + Sync + 'static) -> Self {
+        self.retry_on = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Get the delay before the next retry, `None` means no more retries / 获取下次重试前的等待时长， `None` 表示不再重试
+    pub fn next_delay(&self, retries: u32, elapsed: Duration, error: &E) -> Option<Duration> {
+        if self.max_retries.map(|max_retries| retries >= max_retries).unwrap_or(false) {
+            return None;
+        }
+        if let Some(retry_on) = &self.retry_on {
+            if !retry_on(error) {
+                return None;
+            }
+        }
+        let delay = self.backoff.delay(retries);
+        let delay = if self.jitter && !delay.is_zero() {
+            Duration::from_secs_f64(rand::thread_rng().gen_range(0.0..=delay.as_secs_f64()))
+        } else {
+            delay
+        };
+        if self.max_elapsed.map(|max_elapsed| elapsed + delay > max_elapsed).unwrap_or(false) {
+            return None;
+        }
+        Some(delay)
+    }
+}
+
+impl<E> Default for RetryPolicy<E> {
+    fn default() -> Self {
+        Self::exponential(Duration::from_millis(100), Duration::from_secs(10)).with_jitter()
+    }
+}
+
+/// Execute the async operation, retry on failure according to the policy / 执行异步操作，失败时按策略重试
+///
+/// Returns the last error when no more retries are allowed.
+///
+/// 不再允许重试时返回最后一次的错误.
+pub async fn retry<T, E, F, Fut>(policy: &RetryPolicy<E>, mut operation: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Debug,
+{
+    let start = Instant::now();
+    let mut retries = 0;
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) => match policy.next_delay(retries, start.elapsed(), &error) {
+                Some(delay) => {
+                    retries += 1;
+                    debug!("[Tardis.Retry] Attempt {retries} failed: {error:?}, retry after {delay:?}");
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(error),
+            },
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Deduplicate concurrent identical async work / 合并并发的相同异步任务
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::utils::single_flight::SingleFlight;
+//! let flight: SingleFlight<TardisResult<String>> = SingleFlight::new();
+//! // Only one of the concurrent callers with the same key executes the closure, the others wait and share its result.
+//! let token = flight.work("token", || async { fetch_token().await }).await?;
+//! ```
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OnceCell;
+
+/// Single-flight group / 单飞组
+///
+/// Concurrent calls with the same key share a single execution, the result is cloned to every caller.
+/// Once the execution completes, the key is released and the next call executes again.
+///
+/// 相同key的并发调用共享一次执行，结果会复制给每个调用方.
+/// 执行完成后释放该key，下一次调用会重新执行.
+///
+/// If the executing caller is cancelled, one of the waiting callers takes over the execution.
+///
+/// 如果执行中的调用方被取消，等待中的某个调用方会接管执行.
+pub struct SingleFlight<T> {
+    calls: Mutex<HashMap<String, Arc<OnceCell<T>>>>,
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        SingleFlight {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Execute the async work, or wait for the in-flight one with the same key / 执行异步任务，或等待相同key的执行中任务
+    pub async fn work<F, Fut>(&self, key: &str, f: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let call = {
+            let mut calls = self.calls.lock().expect("[Tardis.SingleFlight] calls lock poisoned");
+            calls.entry(key.to_string()).or_insert_with(|| Arc::new(OnceCell::new())).clone()
+        };
+        let value = call.get_or_init(f).await.clone();
+        let mut calls = self.calls.lock().expect("[Tardis.SingleFlight] calls lock poisoned");
+        if calls.get(key).map(|current| Arc::ptr_eq(current, &call)).unwrap_or(false) {
+            calls.remove(key);
+        }
+        value
+    }
+
+    /// Whether there is in-flight work of the key / 该key是否存在执行中的任务
+    pub fn is_in_flight(&self, key: &str) -> bool {
+        self.calls.lock().expect("[Tardis.SingleFlight] calls lock poisoned").contains_key(key)
+    }
+}
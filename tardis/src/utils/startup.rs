@@ -0,0 +1,217 @@
+//! Startup of the components / 组件启动
+//!
+//! The components are initialized in parallel after their dependencies are ready,
+//! each attempt is limited by the timeout and retried with the exponential backoff according to [`StartupConfig`].
+//! The background components start initializing on startup without blocking it, use [`TardisFuns::wait_component`](crate::TardisFuns::wait_component) to wait for them,
+//! or the `try_*` accessors, e.g. [`TardisFuns::try_cache`](crate::TardisFuns::try_cache), which fail instead of panicking before they are ready.
+//!
+//! 组件在其依赖就绪后并行初始化，每次尝试受超时限制，并根据 [`StartupConfig`] 以指数退避重试.
+//! 后台组件在启动时开始初始化而不阻塞启动，使用 [`TardisFuns::wait_component`](crate::TardisFuns::wait_component) 等待，
+//! 或使用 `try_*` 访问函数，如 [`TardisFuns::try_cache`](crate::TardisFuns::try_cache) ，在就绪前失败而不是panic.
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::{StartupComponentConfig, StartupConfig};
+use crate::tardis_static;
+use crate::utils::retry::{retry, RetryPolicy};
+
+type InitFn = Box<dyn Fn() -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send>> + Send + Sync>;
+
+/// State of a component / 组件的状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupState {
+    Pending,
+    Ready,
+    /// Failed with the message of the last error / 失败，附最后一次错误的消息
+    Failed(String),
+}
+
+/// A component to be initialized on startup / 启动时初始化的组件
+pub struct StartupComponent {
+    name: &'static str,
+    depends_on: Vec<&'static str>,
+    init: InitFn,
+}
+
+impl StartupComponent {
+    /// The `init` is called for each attempt / 每次尝试调用一次 `init`
+    pub fn new<F, Fut>(name: &'static str, init: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        StartupComponent {
+            name,
+            depends_on: Vec::new(),
+            init: Box::new(move || Box::pin(init())),
+        }
+    }
+
+    /// Initialize after the component is ready / 在该组件就绪后初始化
+    pub fn depends_on(mut self, name: &'static str) -> Self {
+        self.depends_on.push(name);
+        self
+    }
+}
+
+tardis_static! {
+    states: RwLock<HashMap<String, watch::Sender<StartupState>>>;
+    background_tasks: Mutex<Vec<JoinHandle<TardisResult<()>>>>;
+}
+
+/// Initialize the components, returns the first error of the required foreground components
+/// / 初始化组件，返回必需的前台组件的第一个错误
+///
+/// The background components are not waited, the unknown dependencies are ignored.
+///
+/// 不等待后台组件，忽略未知的依赖.
+pub async fn run(components: Vec<StartupComponent>, config: &StartupConfig) -> TardisResult<()> {
+    abort_background_tasks();
+    {
+        let mut states = states().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        states.clear();
+        for component in &components {
+            states.insert(component.name.to_string(), watch::channel(StartupState::Pending).0);
+        }
+    }
+    let names = components.iter().map(|component| component.name).collect::<Vec<_>>();
+    let mut foreground_tasks = Vec::new();
+    for mut component in components {
+        component.depends_on.retain(|dependency| names.contains(dependency));
+        let component_config = config.component(component.name);
+        let policy = RetryPolicy::exponential(Duration::from_millis(config.initial_backoff_ms), Duration::from_millis(config.max_backoff_ms))
+            .with_max_retries(component_config.retries.unwrap_or(config.retries));
+        let timeout = component_config.timeout_ms.or(config.timeout_ms).map(Duration::from_millis);
+        let background = component_config.background;
+        let task = tokio::spawn(start(component, component_config, policy, timeout));
+        if background {
+            background_tasks().lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(task);
+        } else {
+            foreground_tasks.push(task);
+        }
+    }
+    let mut result = Ok(());
+    // wait for all the tasks, so no component is left initializing on failure
+    for task in foreground_tasks {
+        let task_result = task.await.unwrap_or_else(|error| Err(TardisError::custom("500-tardis-startup-error", &format!("[Tardis.Startup] Task error: {error}"), "")));
+        result = result.and(task_result);
+    }
+    result
+}
+
+async fn start(component: StartupComponent, component_config: StartupComponentConfig, policy: RetryPolicy, timeout: Option<Duration>) -> TardisResult<()> {
+    let name = component.name;
+    for dependency in &component.depends_on {
+        if let Err(error) = wait(dependency).await {
+            let error = TardisError::custom(
+                "500-tardis-startup-dependency-failed",
+                &format!("[Tardis.Startup] Component {name} depends on {dependency}, which failed: {}", error.message),
+                "",
+            );
+            return finish(name, &component_config, Err(error));
+        }
+    }
+    let started = Instant::now();
+    let result = retry(&policy, || {
+        let init = (component.init)();
+        async move {
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, init).await.unwrap_or_else(|_| {
+                    Err(TardisError::custom(
+                        "504-tardis-startup-timeout",
+                        &format!("[Tardis.Startup] Initialize component {name} timeout after {}ms", timeout.as_millis()),
+                        "",
+                    ))
+                }),
+                None => init.await,
+            }
+        }
+    })
+    .await;
+    if result.is_ok() {
+        info!("[Tardis.Startup] Component {name} is ready in {}ms", started.elapsed().as_millis());
+    }
+    finish(name, &component_config, result)
+}
+
+fn finish(name: &str, component_config: &StartupComponentConfig, result: TardisResult<()>) -> TardisResult<()> {
+    let state = match &result {
+        Ok(_) => StartupState::Ready,
+        Err(error) => StartupState::Failed(error.message.clone()),
+    };
+    if let Some(sender) = states().read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(name) {
+        sender.send_replace(state);
+    }
+    match result {
+        Err(error) if component_config.optional => {
+            warn!("[Tardis.Startup] Optional component {name} failed: {error}");
+            Ok(())
+        }
+        Err(error) => {
+            error!("[Tardis.Startup] Component {name} failed: {error}");
+            Err(error)
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+/// State of the component, `None` if it's not configured / 组件的状态，未配置时为 `None`
+pub fn state(name: &str) -> Option<StartupState> {
+    states().read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(name).map(|sender| sender.borrow().clone())
+}
+
+/// Wait for the component to be ready / 等待组件就绪
+pub async fn wait(name: &str) -> TardisResult<()> {
+    let receiver = states().read().unwrap_or_else(|poisoned| poisoned.into_inner()).get(name).map(|sender| sender.subscribe());
+    let Some(mut receiver) = receiver else {
+        return Err(TardisError::custom(
+            "404-tardis-startup-component-not-found",
+            &format!("[Tardis.Startup] Component {name} is not configured"),
+            "",
+        ));
+    };
+    let state = receiver
+        .wait_for(|state| *state != StartupState::Pending)
+        .await
+        .map(|state| state.clone())
+        .map_err(|_| TardisError::custom("500-tardis-startup-restarted", &format!("[Tardis.Startup] Component {name} is restarted"), ""))?;
+    match state {
+        StartupState::Failed(message) => Err(TardisError::custom(
+            "500-tardis-startup-component-failed",
+            &format!("[Tardis.Startup] Component {name} failed: {message}"),
+            "",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The error of the instance of the component that doesn't exist, e.g. it's still initializing in the background
+/// / 组件实例不存在时的错误，如仍在后台初始化
+pub(crate) fn not_ready(name: &str, code: &str) -> TardisError {
+    let reason = match state(name) {
+        Some(StartupState::Pending) => "is initializing".to_string(),
+        Some(StartupState::Failed(message)) => format!("failed: {message}"),
+        _ => "doesn't exist".to_string(),
+    };
+    TardisError::custom(
+        "503-tardis-startup-component-not-ready",
+        &format!("[Tardis.Startup] Component {name} instance [{code}] {reason}"),
+        "",
+    )
+}
+
+/// Abort the background components which are still initializing / 中止仍在初始化的后台组件
+pub(crate) fn abort_background_tasks() {
+    for task in background_tasks().lock().unwrap_or_else(|poisoned| poisoned.into_inner()).drain(..) {
+        task.abort();
+    }
+}
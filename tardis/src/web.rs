@@ -1,3 +1,9 @@
+#[cfg(feature = "web-server-graphql")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server-graphql")))]
+pub use async_graphql;
+#[cfg(feature = "web-server-auth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server-auth")))]
+pub use jsonwebtoken;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub use poem;
@@ -12,13 +18,49 @@ pub use reqwest;
 pub use tokio_tungstenite;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod access_log_mw;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub mod context_extractor;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod context_scope_mw;
+#[cfg(feature = "web-server-graphql")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server-graphql")))]
+pub mod graphql;
+#[cfg(feature = "web-client-grpc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-client-grpc")))]
+pub mod grpc_client;
+#[cfg(all(feature = "web-server", feature = "metrics"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "web-server", feature = "metrics"))))]
+pub mod metrics_mw;
+#[cfg(all(feature = "web-server", feature = "cache"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "web-server", feature = "cache"))))]
+pub mod rate_limit_mw;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod request_id_mw;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod trace_context_mw;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub mod uniform_error_mw;
 #[cfg(feature = "web-client")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-client")))]
+pub mod web_api_client;
+#[cfg(feature = "web-server-auth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server-auth")))]
+pub mod web_auth;
+#[cfg(feature = "web-client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-client")))]
 pub mod web_client;
+#[cfg(feature = "web-client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-client")))]
+pub mod web_client_cache;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod web_health;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub mod web_resp;
@@ -27,6 +69,12 @@ pub mod web_resp;
 pub mod web_server;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod web_sse;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
+pub mod web_upload;
+#[cfg(feature = "web-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub mod web_validation;
 #[cfg(feature = "ws-client")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ws-client")))]
@@ -0,0 +1,45 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+use tracing::info;
+
+/// Access log middleware / 访问日志中间件
+///
+/// Log the method, path, status, elapsed time and request id (by the `X-Request-Id` header) of each request.
+///
+/// 记录每个请求的方法、路径、状态码、耗时及请求标识（取自 `X-Request-Id` 请求头）.
+pub struct AccessLog;
+
+impl<E: Endpoint> Middleware<E> for AccessLog {
+    type Output = AccessLogImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        AccessLogImpl(ep)
+    }
+}
+
+pub struct AccessLogImpl<E>(E);
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for AccessLogImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let start = Instant::now();
+        let method = req.method().to_string();
+        let path = req.uri().path_and_query().map(|path| path.to_string()).unwrap_or_default();
+        let request_id = req.header("X-Request-Id").unwrap_or("-").to_string();
+        let resp = self.0.call(req).await.map(IntoResponse::into_response);
+        let status = match &resp {
+            Ok(resp) => resp.status(),
+            Err(error) => error.status(),
+        };
+        info!(
+            "[Tardis.WebServer] Access {method} {path} {status} {elapsed}ms request_id:{request_id}",
+            status = status.as_u16(),
+            elapsed = start.elapsed().as_millis()
+        );
+        resp
+    }
+}
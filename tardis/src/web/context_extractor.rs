@@ -24,7 +24,7 @@ async fn context_checker(req: &Request, _: ApiKey) -> Option<TardisContext> {
     }
 }
 
-async fn extract_context(req: &Request) -> TardisResult<TardisContext> {
+pub(crate) async fn extract_context(req: &Request) -> TardisResult<TardisContext> {
     let fw_config = TardisFuns::fw_config();
     let web_server_config = fw_config.web_server.as_ref().expect("missing web server config");
     let context_header_name = &web_server_config.context_conf.context_header_name;
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+
+use crate::basic::dto::TardisContext;
+use crate::web::context_extractor::extract_context;
+
+/// Context scope middleware / 上下文作用域中间件
+///
+/// Process each request with the [`TardisContext`] of the context header as the current one (see [`TardisContext::current`]),
+/// the request id of the context is taken from the `X-Request-Id` header if it's empty.
+/// The requests without a valid context header are processed without the current context.
+///
+/// 以上下文请求头中的 [`TardisContext`] 作为当前上下文（见 [`TardisContext::current`] ）处理每个请求，
+/// 上下文的请求Id为空时取自 `X-Request-Id` 请求头. 没有有效上下文请求头的请求在无当前上下文的情况下处理.
+pub struct ContextScope;
+
+impl<E: Endpoint> Middleware<E> for ContextScope {
+    type Output = ContextScopeImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ContextScopeImpl(ep)
+    }
+}
+
+pub struct ContextScopeImpl<E>(E);
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for ContextScopeImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let ctx = if req.headers().contains_key(TardisContext::header_name().as_str()) {
+            extract_context(&req).await.ok()
+        } else {
+            None
+        };
+        match ctx {
+            Some(mut ctx) => {
+                if ctx.request_id.is_empty() {
+                    ctx.request_id = req.header("X-Request-Id").unwrap_or_default().to_string();
+                }
+                ctx.scope(async move { self.0.call(req).await.map(IntoResponse::into_response) }).await
+            }
+            None => self.0.call(req).await.map(IntoResponse::into_response),
+        }
+    }
+}
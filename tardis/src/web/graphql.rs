@@ -0,0 +1,179 @@
+//! GraphQL integration / GraphQL集成
+//!
+//! The [`async-graphql`](https://github.com/async-graphql/async-graphql) schemas are mounted as the modules of the web server
+//! (see [`TardisWebServer::add_graphql_module`](crate::web::web_server::TardisWebServer::add_graphql_module)),
+//! the queries and mutations are served on the module path and the subscriptions on `{module path}/ws` .
+//!
+//! [`async-graphql`](https://github.com/async-graphql/async-graphql) 的Schema作为Web服务的模块挂载
+//! （见 [`TardisWebServer::add_graphql_module`](crate::web::web_server::TardisWebServer::add_graphql_module) ），
+//! 查询及变更在模块路径上提供，订阅在 `{模块路径}/ws` 上提供.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::web::async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema};
+//! use tardis::web::graphql::TardisGraphqlContext;
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn owner(&self, ctx: &Context<'_>) -> Result<String> {
+//!         Ok(ctx.tardis_ctx()?.owner.clone())
+//!     }
+//! }
+//!
+//! TardisFuns::web_server().add_graphql_module("graphql", Schema::new(Query, EmptyMutation, EmptySubscription)).await;
+//! ```
+use async_graphql::http::{GraphiQLSource, ALL_WEBSOCKET_PROTOCOLS};
+use async_graphql::{Data, ErrorExtensions, ObjectType, Schema, ServerError, SubscriptionType, Value};
+use async_graphql_poem::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use async_trait::async_trait;
+use poem::web::websocket::WebSocket;
+use poem::web::Html;
+use poem::{Endpoint, FromRequest, IntoResponse, Request, Response};
+
+use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+
+/// Error code of the invalid requests, e.g. the syntax or validation errors / 无效请求（如语法或校验错误）的错误码
+const GRAPHQL_REQUEST_ERROR_CODE: &str = "400";
+/// Error code of the resolvers without a tardis error / 未返回tardis错误的解析器的错误码
+const GRAPHQL_RESOLVER_ERROR_CODE: &str = "500";
+
+/// Access the [`TardisContext`] in the resolvers / 在解析器中访问 [`TardisContext`]
+pub trait TardisGraphqlContext {
+    /// Get the context of the request, return an unauthorized error if the request has no context
+    /// / 获取请求的上下文，请求没有上下文时返回未授权错误
+    fn tardis_ctx(&self) -> async_graphql::Result<&TardisContext>;
+}
+
+impl TardisGraphqlContext for async_graphql::Context<'_> {
+    fn tardis_ctx(&self) -> async_graphql::Result<&TardisContext> {
+        self.data_opt::<TardisContext>().ok_or_else(|| TardisError::unauthorized("[Tardis.WebServer] Context is not found", "401-tardis-graphql-context-not-found").extend())
+    }
+}
+
+/// Convert to a GraphQL error with the `code` extension / 转换为带有 `code` 扩展的GraphQL错误
+///
+/// The errors returned by `?` are converted as well, so `.extend()` is only needed to add more extensions.
+///
+/// 通过 `?` 返回的错误同样会被转换，因此仅在需要添加更多扩展时才需要 `.extend()` .
+impl ErrorExtensions for TardisError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.message.clone()).extend_with(|_, extensions| {
+            for (key, value) in &self.extensions {
+                extensions.set(key, value.clone());
+            }
+            extensions.set("code", self.code.clone());
+        })
+    }
+}
+
+/// Set the `code` extension of the errors by the tardis error codes, the extensions of the tardis errors are kept
+/// / 按tardis错误码设置错误的 `code` 扩展，保留tardis错误的扩展
+fn map_errors(mut response: async_graphql::Response) -> async_graphql::Response {
+    for error in response.errors.iter_mut() {
+        map_error(error);
+    }
+    response
+}
+
+fn map_error(error: &mut ServerError) {
+    if let Some(tardis_error) = error.source::<TardisError>() {
+        let tardis_error = tardis_error.clone();
+        error.message = tardis_error.message;
+        let extensions = error.extensions.get_or_insert_with(Default::default);
+        for (key, value) in tardis_error.extensions {
+            extensions.set(key, value);
+        }
+        extensions.set("code", tardis_error.code);
+        return;
+    }
+    if error.extensions.as_ref().map(|extensions| extensions.get("code").is_some()).unwrap_or(false) {
+        return;
+    }
+    let code = if error.path.is_empty() {
+        GRAPHQL_REQUEST_ERROR_CODE
+    } else {
+        GRAPHQL_RESOLVER_ERROR_CODE
+    };
+    error.extensions.get_or_insert_with(Default::default).set("code", Value::from(code));
+}
+
+/// Query and mutation endpoint / 查询及变更端点
+///
+/// The [`TardisContext`] of the request is added to the data of the GraphQL request.
+///
+/// 请求的 [`TardisContext`] 会添加到GraphQL请求的数据中.
+pub struct TardisGraphql<Q, M, S> {
+    schema: Schema<Q, M, S>,
+}
+
+impl<Q, M, S> TardisGraphql<Q, M, S> {
+    pub fn new(schema: Schema<Q, M, S>) -> Self {
+        TardisGraphql { schema }
+    }
+}
+
+#[async_trait]
+impl<Q, M, S> Endpoint for TardisGraphql<Q, M, S>
+where
+    Q: ObjectType + 'static,
+    M: ObjectType + 'static,
+    S: SubscriptionType + 'static,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let (req, mut body) = req.split();
+        let GraphQLRequest(mut request) = GraphQLRequest::from_request(&req, &mut body).await?;
+        if let Some(ctx) = TardisContext::current() {
+            request = request.data(ctx);
+        }
+        let response = map_errors(self.schema.execute(request).await);
+        Ok(GraphQLResponse::from(response).into_response())
+    }
+}
+
+/// Subscription endpoint over WebSocket / 基于WebSocket的订阅端点
+///
+/// The [`TardisContext`] of the upgrade request is added to the data of the connection.
+///
+/// 升级请求的 [`TardisContext`] 会添加到连接的数据中.
+pub struct TardisGraphqlSubscription<Q, M, S> {
+    schema: Schema<Q, M, S>,
+}
+
+impl<Q, M, S> TardisGraphqlSubscription<Q, M, S> {
+    pub fn new(schema: Schema<Q, M, S>) -> Self {
+        TardisGraphqlSubscription { schema }
+    }
+}
+
+#[async_trait]
+impl<Q, M, S> Endpoint for TardisGraphqlSubscription<Q, M, S>
+where
+    Q: ObjectType + 'static,
+    M: ObjectType + 'static,
+    S: SubscriptionType + 'static,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let (req, mut body) = req.split();
+        let websocket = WebSocket::from_request(&req, &mut body).await?;
+        let protocol = GraphQLProtocol::from_request(&req, &mut body).await?;
+        let mut data = Data::default();
+        if let Some(ctx) = TardisContext::current() {
+            data.insert(ctx);
+        }
+        let schema = self.schema.clone();
+        Ok(websocket.protocols(ALL_WEBSOCKET_PROTOCOLS).on_upgrade(move |stream| GraphQLWebSocket::new(stream, schema, protocol).with_data(data).serve()).into_response())
+    }
+}
+
+/// GraphiQL page of the module / 模块的GraphiQL页面
+pub(crate) fn graphiql_endpoint(code: &str) -> impl Endpoint<Output = Html<String>> {
+    let page = GraphiQLSource::build().endpoint(&format!("/{code}")).subscription_endpoint(&format!("/{code}/ws")).finish();
+    poem::endpoint::make_sync(move |_| Html(page.clone()))
+}
@@ -0,0 +1,150 @@
+//! gRPC client / gRPC客户端
+//!
+//! The clients generated by `poem-grpc-build` are created by the configured endpoints, and the tracing context and the
+//! [`TardisContext`] are carried in the request metadata by the [`GrpcPropagation`] middleware.
+//!
+//! 由 `poem-grpc-build` 生成的客户端以配置的端点创建，链路上下文及 [`TardisContext`] 由 [`GrpcPropagation`] 中间件在请求元数据中携带.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::TardisFuns;
+//! let grpc = TardisFuns::grpc_client_by_module("user");
+//! let client = grpc.client(UserClient::new)?.with(grpc.propagation());
+//! let user = client.get_user(poem_grpc::Request::new(GetUserRequest { id: "u1".to_string() })).await?;
+//! ```
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use poem::http::{HeaderName, HeaderValue};
+use poem::{Endpoint, Middleware, Request};
+use poem_grpc::ClientConfig;
+use tracing::{info, warn};
+
+use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::basic::tracing::TardisTracing;
+use crate::config::config_dto::component::grpc_client::GrpcClientModuleConfig;
+use crate::utils::initializer::InitBy;
+
+/// gRPC client / gRPC客户端
+#[derive(Debug, Clone)]
+pub struct TardisGrpcClient {
+    config: GrpcClientModuleConfig,
+}
+
+#[async_trait]
+impl InitBy<GrpcClientModuleConfig> for TardisGrpcClient {
+    async fn init_by(config: &GrpcClientModuleConfig) -> TardisResult<Self> {
+        Self::init(config)
+    }
+}
+
+impl TardisGrpcClient {
+    pub fn init(config: &GrpcClientModuleConfig) -> TardisResult<TardisGrpcClient> {
+        info!("[Tardis.GrpcClient] Initializing, uris:{:?}", config.uris);
+        let client = TardisGrpcClient { config: config.clone() };
+        // validate the endpoints
+        client.client_config()?;
+        info!("[Tardis.GrpcClient] Initialized");
+        Ok(client)
+    }
+
+    /// Get the configuration / 获取配置
+    pub fn config(&self) -> &GrpcClientModuleConfig {
+        &self.config
+    }
+
+    /// Build the `poem-grpc` client configuration by the configured endpoints and timeout / 以配置的端点及超时时间构建 `poem-grpc` 客户端配置
+    pub fn client_config(&self) -> TardisResult<ClientConfig> {
+        if self.config.uris.is_empty() {
+            return Err(TardisError::format_error("[Tardis.GrpcClient] The uris are empty", "406-tardis-grpcclient-uris-empty"));
+        }
+        let mut builder = ClientConfig::builder().timeout(Duration::from_secs(self.config.request_timeout_sec));
+        for uri in &self.config.uris {
+            builder = builder.uri(uri.as_str());
+        }
+        builder.build().map_err(|error| TardisError::format_error(&format!("[Tardis.GrpcClient] Invalid config: {error}"), "406-tardis-grpcclient-config-invalid"))
+    }
+
+    /// Create the generated client / 创建生成的客户端
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let client = TardisFuns::grpc_client().client(GreeterClient::new)?.with(TardisFuns::grpc_client().propagation());
+    /// ```
+    pub fn client<C>(&self, new: impl FnOnce(ClientConfig) -> C) -> TardisResult<C> {
+        Ok(new(self.client_config()?))
+    }
+
+    /// The propagation middleware by the configuration, to be added by the `with` method of the generated client
+    /// / 按配置创建的传播中间件，通过生成客户端的 `with` 方法添加
+    pub fn propagation(&self) -> GrpcPropagation {
+        GrpcPropagation {
+            propagate_context: self.config.propagate_context,
+        }
+    }
+}
+
+/// Propagation middleware of the gRPC clients / gRPC客户端的传播中间件
+///
+/// Inject the tracing context (e.g. `traceparent`) and the [`TardisContext`] of the current task into the request metadata,
+/// the web server extracts them from the requests of the gRPC modules.
+///
+/// 将链路上下文（如 `traceparent` ）及当前task的 [`TardisContext`] 注入请求元数据，Web服务从gRPC模块的请求中提取.
+///
+/// The [`TardisContext`] is only injected when enabled by [`GrpcClientModuleConfig::propagate_context`].
+///
+/// 仅在通过 [`GrpcClientModuleConfig::propagate_context`] 启用时注入 [`TardisContext`] .
+#[derive(Debug, Clone)]
+pub struct GrpcPropagation {
+    propagate_context: bool,
+}
+
+impl Default for GrpcPropagation {
+    fn default() -> Self {
+        GrpcPropagation { propagate_context: false }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for GrpcPropagation {
+    type Output = GrpcPropagationImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        GrpcPropagationImpl {
+            ep,
+            propagate_context: self.propagate_context,
+        }
+    }
+}
+
+pub struct GrpcPropagationImpl<E> {
+    ep: E,
+    propagate_context: bool,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for GrpcPropagationImpl<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let mut metadata = HashMap::new();
+        TardisTracing::inject_context(&mut metadata);
+        if self.propagate_context {
+            let header_name = TardisContext::header_name();
+            if !req.headers().contains_key(header_name.as_str()) {
+                TardisContext::inject(&mut metadata);
+            }
+        }
+        for (key, value) in metadata {
+            match (HeaderName::from_bytes(key.as_bytes()), HeaderValue::from_str(&value)) {
+                (Ok(key), Ok(value)) => {
+                    req.headers_mut().insert(key, value);
+                }
+                _ => warn!("[Tardis.GrpcClient] Invalid metadata {key}"),
+            }
+        }
+        self.ep.call(req).await
+    }
+}
@@ -0,0 +1,60 @@
+//! Metrics of the web server / Web服务的监控指标
+//!
+//! @see [metrics](crate::basic::metrics)
+use std::time::Instant;
+
+use async_trait::async_trait;
+use poem::http::StatusCode;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+
+use crate::TardisFuns;
+
+/// Metrics middleware recording the requests of a module, it's added to all the modules of the web server
+/// / 记录模块请求的监控指标中间件，Web服务的所有模块都会添加该中间件
+pub struct HttpMetrics {
+    module: String,
+}
+
+impl HttpMetrics {
+    pub fn new(module: impl Into<String>) -> Self {
+        HttpMetrics { module: module.into() }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for HttpMetrics {
+    type Output = HttpMetricsImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        HttpMetricsImpl { ep, module: self.module.clone() }
+    }
+}
+
+pub struct HttpMetricsImpl<E> {
+    ep: E,
+    module: String,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for HttpMetricsImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let method = req.method().to_string();
+        let start = Instant::now();
+        let result = self.ep.call(req).await.map(IntoResponse::into_response);
+        let status = match &result {
+            Ok(resp) => resp.status(),
+            Err(error) => error.status(),
+        };
+        TardisFuns::metrics().record_http_server(&self.module, &method, status.as_u16(), start.elapsed());
+        result
+    }
+}
+
+/// Endpoint exporting all the metrics in the Prometheus text format / 以Prometheus文本格式导出所有指标的端点
+pub fn metrics_endpoint() -> impl Endpoint<Output = Response> {
+    poem::endpoint::make_sync(|_| match TardisFuns::metrics().export() {
+        Ok(text) => Response::builder().content_type("text/plain; version=0.0.4").body(text),
+        Err(error) => Response::builder().status(StatusCode::INTERNAL_SERVER_ERROR).body(error.to_string()),
+    })
+}
@@ -0,0 +1,132 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use poem::http::header;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+use tracing::warn;
+
+use crate::cache::rate_limiter::{rate_limited, TardisRateLimiter};
+
+type RateLimitKeyExtractor = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// Rate limit middleware / 限流中间件
+///
+/// Limits the requests by the client IP by default, which is the remote address,
+/// or the right-most untrusted `X-Forwarded-For` address if the request comes from the [trusted proxies](Self::trusted_proxies).
+/// The denied requests fail with the `429-tardis-rate-limited` [`TardisError`](crate::basic::error::TardisError) (HTTP status `429`) and the `Retry-After` header,
+/// the allowed responses carry the `X-RateLimit-Limit` and `X-RateLimit-Remaining` headers.
+/// The requests are allowed when the cache is unavailable.
+///
+/// 默认按客户端IP限流，即远端地址，若请求来自 [受信任的代理](Self::trusted_proxies) 则为最右侧的不受信任的 `X-Forwarded-For` 地址.
+/// 被拒绝的请求以 `429-tardis-rate-limited` 的 [`TardisError`](crate::basic::error::TardisError) （HTTP状态码 `429` ）及 `Retry-After` 响应头失败，
+/// 被允许的响应携带 `X-RateLimit-Limit` 及 `X-RateLimit-Remaining` 响应头. 缓存不可用时请求会被放行.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::web::rate_limit_mw::RateLimit;
+/// let rate_limit = RateLimit::new(TardisFuns::cache().rate_limiter())
+///     .key_by(|req| req.header("Tardis-App-Id").map(|app_id| format!("{}:{app_id}", req.uri().path())));
+/// TardisFuns::web_server().add_module("todo", (TodoApi, rate_limit)).await;
+/// ```
+#[derive(Clone)]
+pub struct RateLimit {
+    limiter: Arc<TardisRateLimiter>,
+    key_extractor: Option<RateLimitKeyExtractor>,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+}
+
+impl RateLimit {
+    pub fn new(limiter: TardisRateLimiter) -> RateLimit {
+        RateLimit {
+            limiter: Arc::new(limiter),
+            key_extractor: None,
+            trusted_proxies: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Use the key extracted from the request, the requests without key are not limited
+    /// / 使用从请求中提取的key，无key的请求不受限制
+    pub fn key_by(mut self, fun: impl Fn(&Request) -> Option<String> + Send + Sync + 'static) -> Self {
+        self.key_extractor = Some(Arc::new(fun));
+        self
+    }
+
+    /// Trust the `X-Forwarded-For` header set by the proxies, e.g. the load balancer, the header is ignored by default
+    /// / 信任代理（如负载均衡）设置的 `X-Forwarded-For` 请求头，默认忽略该请求头
+    pub fn trusted_proxies(mut self, proxies: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.trusted_proxies = Arc::new(proxies.into_iter().collect());
+        self
+    }
+}
+
+/// The client IP, the addresses of the `X-Forwarded-For` header are appended by each proxy, so only the ones after the trusted proxies are reliable
+fn client_ip(req: &Request, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let remote_ip = req.remote_addr().as_socket_addr().map(|addr| addr.ip())?;
+    if !trusted_proxies.contains(&remote_ip) {
+        return Some(remote_ip.to_string());
+    }
+    let forwarded_for =
+        req.headers().get_all("X-Forwarded-For").iter().filter_map(|value| value.to_str().ok()).flat_map(|value| value.split(',')).map(str::trim).collect::<Vec<_>>();
+    let client_ip = forwarded_for
+        .iter()
+        .rev()
+        .filter(|ip| !ip.is_empty())
+        .find(|ip| ip.parse::<IpAddr>().map(|ip| !trusted_proxies.contains(&ip)).unwrap_or(true))
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| remote_ip.to_string());
+    Some(client_ip)
+}
+
+impl<E: Endpoint> Middleware<E> for RateLimit {
+    type Output = RateLimitImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RateLimitImpl {
+            ep,
+            limiter: self.limiter.clone(),
+            key_extractor: self.key_extractor.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+        }
+    }
+}
+
+pub struct RateLimitImpl<E> {
+    ep: E,
+    limiter: Arc<TardisRateLimiter>,
+    key_extractor: Option<RateLimitKeyExtractor>,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for RateLimitImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let key = match &self.key_extractor {
+            Some(key_extractor) => key_extractor(&req),
+            None => client_ip(&req, &self.trusted_proxies),
+        };
+        let Some(key) = key else {
+            return Ok(self.ep.call(req).await?.into_response());
+        };
+        let decision = match self.limiter.acquire(&key).await {
+            Ok(decision) => decision,
+            Err(error) => {
+                warn!("[Tardis.WebServer] Rate limit error, the request is allowed, key:{key} | {error}");
+                return Ok(self.ep.call(req).await?.into_response());
+            }
+        };
+        if !decision.allowed {
+            let mut resp = poem::Error::from(rate_limited(&decision)).into_response();
+            // in seconds, rounded up
+            let retry_after = (decision.retry_after.as_millis() as u64).saturating_add(999) / 1000;
+            resp.headers_mut().insert(header::RETRY_AFTER, retry_after.into());
+            return Ok(resp);
+        }
+        let mut resp = self.ep.call(req).await?.into_response();
+        resp.headers_mut().insert("X-RateLimit-Limit", decision.limit.into());
+        resp.headers_mut().insert("X-RateLimit-Remaining", decision.remaining.into());
+        Ok(resp)
+    }
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use poem::http::header::HeaderName;
+use poem::http::HeaderValue;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+
+use crate::TardisFuns;
+
+/// Request id middleware / 请求标识中间件
+///
+/// Use the request id of the request header (default is `X-Request-Id`), or generate one if it's absent,
+/// the id is set to the request header for the handlers and returned in the response header.
+///
+/// 使用请求头（默认为 `X-Request-Id` ）中的请求标识，缺失时生成一个，该标识会设置到请求头供处理函数使用，并在响应头中返回.
+pub struct RequestId {
+    header: HeaderName,
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestId {
+    pub fn new() -> Self {
+        RequestId {
+            header: HeaderName::from_static("x-request-id"),
+        }
+    }
+
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestId {
+    type Output = RequestIdImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestIdImpl { ep, header: self.header.clone() }
+    }
+}
+
+pub struct RequestIdImpl<E> {
+    ep: E,
+    header: HeaderName,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for RequestIdImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let request_id = req
+            .headers()
+            .get(&self.header)
+            .filter(|request_id| !request_id.is_empty())
+            .cloned()
+            .unwrap_or_else(|| HeaderValue::from_str(&TardisFuns::field.nanoid()).expect("[Tardis.WebServer] Nanoid should be a valid header value"));
+        req.headers_mut().insert(self.header.clone(), request_id.clone());
+        // the errors are converted to keep the request id in the response header
+        let mut resp = match self.ep.call(req).await {
+            Ok(resp) => resp.into_response(),
+            Err(error) => error.into_response(),
+        };
+        resp.headers_mut().insert(self.header.clone(), request_id);
+        Ok(resp)
+    }
+}
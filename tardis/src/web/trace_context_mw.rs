@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+use tracing::Instrument;
+
+use crate::basic::tracing::TardisTracing;
+
+/// Trace context middleware / 链路上下文中间件
+///
+/// Process each request in a `http_request` span, whose parent is extracted from the request headers (e.g. `traceparent`),
+/// so that the spans created in the request (including client operations and mq publishes) belong to the same trace.
+///
+/// 在 `http_request` span中处理每个请求，其父span从请求头（如 `traceparent` ）中提取，
+/// 使请求中创建的span（包括客户端操作及mq发布）属于同一链路.
+pub struct TraceContext;
+
+impl<E: Endpoint> Middleware<E> for TraceContext {
+    type Output = TraceContextImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TraceContextImpl(ep)
+    }
+}
+
+pub struct TraceContextImpl<E>(E);
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for TraceContextImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let span = tracing::info_span!(
+            "http_request",
+            otel.name = format!("{} {}", req.method(), req.uri().path()),
+            otel.kind = "server",
+            http.method = req.method().as_str(),
+            http.target = req.uri().path(),
+            http.status_code = tracing::field::Empty,
+        );
+        let headers = req.headers().iter().filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string()))).collect::<HashMap<_, _>>();
+        TardisTracing::set_parent_from(&span, &headers);
+        #[cfg(feature = "sentry")]
+        let hub = {
+            // request info of the reported errors
+            let hub = std::sync::Arc::new(sentry::Hub::new_from_top(sentry::Hub::current()));
+            hub.configure_scope(|scope| {
+                scope.set_tag("http.method", req.method().as_str());
+                scope.set_tag("http.target", req.uri().path());
+                if let Some(request_id) = headers.get("x-request-id") {
+                    scope.set_tag("request_id", request_id);
+                }
+            });
+            hub
+        };
+        let fut = self.0.call(req).instrument(span.clone());
+        #[cfg(feature = "sentry")]
+        let fut = sentry::SentryFutureExt::bind_hub(fut, hub);
+        let resp = fut.await?.into_response();
+        span.record("http.status_code", resp.status().as_u16());
+        Ok(resp)
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::basic::error::TardisError;
 use crate::basic::result::TARDIS_RESULT_SUCCESS_CODE;
 use crate::serde_json::json;
@@ -7,7 +9,7 @@ use poem::http::StatusCode;
 use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
 use tracing::{trace, warn};
 
-use super::web_resp::mapping_http_code_to_error;
+use super::web_resp::{mapping_http_code_to_error, TardisErrorRegistry};
 
 pub struct UniformError;
 
@@ -29,6 +31,18 @@ impl<E: Endpoint> Endpoint for UniformErrorImpl<E> {
         let method = req.method().to_string();
         let url = req.uri().to_string();
         trace!("[Tardis.WebServer] Request {} {}", method, url);
+        let lang = req.header("Accept-Language").and_then(preferred_lang);
+        #[cfg(feature = "sentry")]
+        let (path, request_id) = (req.uri().path().to_string(), req.header("X-Request-Id").map(str::to_string));
+        // the errors are reported here rather than where they are created, so the handled errors aren't reported
+        #[cfg(feature = "sentry")]
+        let report = |error: &TardisError| {
+            let mut tags = vec![("http.method", method.as_str()), ("http.target", path.as_str())];
+            if let Some(request_id) = &request_id {
+                tags.push(("request_id", request_id.as_str()));
+            }
+            crate::basic::error_report::report_with_tags(error, &tags);
+        };
         let resp = self.0.call(req).await;
         match resp {
             Ok(resp) => {
@@ -62,18 +76,18 @@ impl<E: Endpoint> Endpoint for UniformErrorImpl<E> {
                     "application/json; charset=utf8".parse().expect("[Tardis.WebServer] Http head parsing error"),
                 );
 
-                let (bus_code, msg) = if let Some(error) = mapping_http_code_to_error(http_code, &msg) {
-                    (error.code, error.message)
+                let (bus_code, msg, ext) = if let Some(error) = mapping_http_code_to_error(http_code, &msg) {
+                    #[cfg(feature = "sentry")]
+                    report(&error);
+                    (error.code, error.message, error.extensions)
                 } else {
-                    (TARDIS_RESULT_SUCCESS_CODE.to_string(), "".to_string())
+                    (TARDIS_RESULT_SUCCESS_CODE.to_string(), "".to_string(), HashMap::new())
                 };
-                resp.set_body(
-                    json!({
-                        "code": bus_code,
-                        "msg": process_err_msg(bus_code.as_str(),msg),
-                    })
-                    .to_string(),
-                );
+                // the registered status takes precedence over the friendly fallback
+                if let Some(mapping) = TardisErrorRegistry::get(&bus_code) {
+                    resp.set_status(mapping.http_status);
+                }
+                resp.set_body(error_body(bus_code, msg, ext, lang.as_deref()));
                 Ok(resp)
             }
             Err(error) => {
@@ -84,18 +98,38 @@ impl<E: Endpoint> Endpoint for UniformErrorImpl<E> {
                     "[Tardis.WebServer] Process error,request method:{}, url:{}, response code:{}, message:{}",
                     method, url, error.code, error.message
                 );
-                Ok(Response::builder().status(StatusCode::OK).header("Content-Type", "application/json; charset=utf8").body(
-                    json!({
-                        "code": error.code,
-                        "msg": process_err_msg(error.code.as_str(),error.message),
-                    })
-                    .to_string(),
-                ))
+                #[cfg(feature = "sentry")]
+                report(&error);
+                let status = TardisErrorRegistry::get(&error.code).map(|mapping| mapping.http_status).unwrap_or(StatusCode::OK);
+                Ok(Response::builder().status(status).header("Content-Type", "application/json; charset=utf8").body(error_body(
+                    error.code,
+                    error.message,
+                    error.extensions,
+                    lang.as_deref(),
+                )))
             }
         }
     }
 }
 
+/// The extensions of the error are returned in `ext` if any / 如有错误的扩展信息则在 `ext` 中返回
+fn error_body(code: String, msg: String, ext: HashMap<String, String>, lang: Option<&str>) -> String {
+    let msg = TardisErrorRegistry::localized_message(&code, &msg, lang);
+    let mut body = json!({
+        "code": code,
+        "msg": process_err_msg(code.as_str(), msg),
+    });
+    if !ext.is_empty() {
+        body["ext"] = json!(ext);
+    }
+    body.to_string()
+}
+
+/// The first language of the `Accept-Language` header, e.g. `zh-CN` of `zh-CN,zh;q=0.9` / `Accept-Language` 请求头的首个语言
+pub(crate) fn preferred_lang(accept_language: &str) -> Option<String> {
+    accept_language.split(',').next().and_then(|lang| lang.split(';').next()).map(str::trim).filter(|lang| !lang.is_empty() && *lang != "*").map(str::to_string)
+}
+
 fn process_err_msg(code: &str, msg: String) -> String {
     let fw_config = TardisFuns::fw_config();
     match fw_config.web_server.as_ref() {
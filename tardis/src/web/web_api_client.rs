@@ -0,0 +1,258 @@
+//! Typed API client / 类型化API客户端
+//!
+//! [`TardisApiClient`] is the runtime of the clients generated by [`tardis_client`](crate::tardis_client),
+//! it joins the paths to the base url, injects the auth headers, (de)serializes the JSON bodies and maps the failed responses to [`TardisError`].
+//!
+//! [`TardisApiClient`] 是 [`tardis_client`](crate::tardis_client) 生成的客户端的运行时，
+//! 负责拼接基础地址与路径、注入认证头、(反)序列化JSON请求/响应体，并将失败的响应映射为 [`TardisError`] .
+//!
+//! # Examples
+//! ```ignore
+//! #[tardis_client(tardis_resp)]
+//! pub trait TodoApi {
+//!     #[get("/todos/{id}")]
+//!     async fn get_todo(&self, id: i64) -> TardisResult<TodoDetailResp>;
+//!     #[get("/todos")]
+//!     async fn find_todos(&self, #[query] query: &TodoQuery, #[header("X-Tenant")] tenant: &str) -> TardisResult<Vec<TodoDetailResp>>;
+//!     #[post("/todos")]
+//!     async fn add_todo(&self, #[body] todo: &TodoAddReq) -> TardisResult<i64>;
+//! }
+//!
+//! // [fw.web_client.modules.todo]
+//! // base_url = "http://todo-service:8080/todo"
+//! let client = TodoApiClient::from_module("todo")?.with_bearer_token(token);
+//! let todo = client.get_todo(1).await?;
+//! ```
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::web::web_client::{Json, TardisWebClient};
+use crate::TardisFuns;
+
+type AuthHeaderFn = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = TardisResult<(String, String)>> + Send>> + Send + Sync>;
+
+/// Typed API client / 类型化API客户端
+#[derive(Clone)]
+pub struct TardisApiClient {
+    client: Arc<TardisWebClient>,
+    base_url: String,
+    headers: Vec<(String, String)>,
+    auth: Option<AuthHeaderFn>,
+    tardis_resp: bool,
+}
+
+impl TardisApiClient {
+    pub fn new(client: Arc<TardisWebClient>, base_url: impl Into<String>) -> Self {
+        TardisApiClient {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            headers: Vec::new(),
+            auth: None,
+            tardis_resp: false,
+        }
+    }
+
+    /// Create by the web client module, the base url is the `base_url` of the module config
+    /// / 由Web客户端模块创建，基础地址为该模块配置的 `base_url`
+    pub fn from_module(code: &str) -> TardisResult<Self> {
+        let code = code.to_lowercase();
+        let fw_config = TardisFuns::fw_config();
+        let config = fw_config.web_client();
+        let base_url = config.modules.get(&code).unwrap_or(&config.default).base_url.clone().ok_or_else(|| {
+            TardisError::not_found(
+                &format!("[Tardis.ApiClient] The base_url of web client module {code} isn't configured"),
+                "404-tardis-apiclient-base-url-not-exist",
+            )
+        })?;
+        Ok(Self::new(TardisFuns::web_client_by_module_or_default(&code), base_url))
+    }
+
+    /// The responses are wrapped by `TardisResp` (`{"code":"200","msg":"","data":...}`), the `data` is returned if the `code` is `200`,
+    /// otherwise the `code` and the `msg` are returned as the error
+    /// / 响应由 `TardisResp` 包装（ `{"code":"200","msg":"","data":...}` ）， `code` 为 `200` 时返回 `data` ，否则以 `code` 及 `msg` 作为错误返回
+    pub fn with_tardis_resp(mut self) -> Self {
+        self.tardis_resp = true;
+        self
+    }
+
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_bearer_token(self, token: impl Display) -> Self {
+        self.with_header("Authorization", format!("Bearer {token}"))
+    }
+
+    /// Inject the auth header obtained before each request, e.g. a refreshed access token / 每次请求前获取并注入认证头，如刷新后的访问令牌
+    pub fn with_auth_header<F, Fut>(mut self, auth: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<(String, String)>> + Send + 'static,
+    {
+        self.auth = Some(Arc::new(move || Box::pin(auth())));
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Start a request, the `path` is relative to the base url / 开始一个请求， `path` 相对于基础地址
+    pub fn request(&self, method: Method, path: impl AsRef<str>) -> TardisApiRequest<'_> {
+        TardisApiRequest {
+            client: self,
+            method,
+            url: format!("{}/{}", self.base_url, path.as_ref().trim_start_matches('/')),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            error: None,
+        }
+    }
+}
+
+/// Request of [`TardisApiClient`], the serialization errors are deferred to [`Self::send`]
+/// / [`TardisApiClient`] 的请求，序列化错误推迟到 [`Self::send`] 时返回
+pub struct TardisApiRequest<'a> {
+    client: &'a TardisApiClient,
+    method: Method,
+    url: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Option<Value>,
+    error: Option<TardisError>,
+}
+
+impl TardisApiRequest<'_> {
+    /// Add the fields of the struct as the query parameters, the `None` fields are skipped and the sequences are repeated
+    /// / 以结构体的字段作为查询参数， `None` 字段被跳过，序列重复展开
+    pub fn query<Q: Serialize + ?Sized>(mut self, query: &Q) -> Self {
+        match serde_json::to_value(query) {
+            Ok(Value::Object(fields)) => {
+                for (key, value) in fields {
+                    self.push_query(key, value);
+                }
+            }
+            Ok(Value::Null) => {}
+            Ok(_) => self.fail("[Tardis.ApiClient] The query must be serialized to an object"),
+            Err(error) => self.fail(&format!("[Tardis.ApiClient] Serialize the query error: {error}")),
+        }
+        self
+    }
+
+    pub fn query_param<V: Serialize + ?Sized>(mut self, key: &str, value: &V) -> Self {
+        match serde_json::to_value(value) {
+            Ok(value) => self.push_query(key.to_string(), value),
+            Err(error) => self.fail(&format!("[Tardis.ApiClient] Serialize the query parameter {key} error: {error}")),
+        }
+        self
+    }
+
+    fn push_query(&mut self, key: String, value: Value) {
+        match value {
+            Value::Null => {}
+            Value::Array(values) => {
+                for value in values {
+                    self.push_query(key.clone(), value);
+                }
+            }
+            Value::String(value) => self.query.push((key, value)),
+            value => self.query.push((key, value.to_string())),
+        }
+    }
+
+    pub fn header(mut self, key: impl Into<String>, value: impl Display) -> Self {
+        self.headers.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Set the JSON body / 设置JSON请求体
+    pub fn json<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        match serde_json::to_value(body) {
+            Ok(body) => self.body = Some(body),
+            Err(error) => self.fail(&format!("[Tardis.ApiClient] Serialize the body error: {error}")),
+        }
+        self
+    }
+
+    fn fail(&mut self, msg: &str) {
+        if self.error.is_none() {
+            self.error = Some(TardisError::format_error(msg, "406-tardis-apiclient-serialize-error"));
+        }
+    }
+
+    /// Send the request and deserialize the response, the empty body is deserialized as `null`
+    /// / 发送请求并反序列化响应，空响应体作为 `null` 反序列化
+    ///
+    /// The non-`2xx` responses are mapped to the errors coded by the status.
+    ///
+    /// 非 `2xx` 响应映射为以状态码为错误码的错误.
+    pub async fn send<T: DeserializeOwned>(self) -> TardisResult<T> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let mut url = url::Url::parse(&self.url).map_err(|error| TardisError::format_error(&format!("[Tardis.ApiClient] Invalid url {}: {error}", self.url), ""))?;
+        if !self.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query);
+        }
+        let mut headers = self.client.headers.clone();
+        if let Some(auth) = &self.client.auth {
+            headers.push(auth().await?);
+        }
+        headers.extend(self.headers);
+        let method = self.method.to_string();
+        let (code, _, response) = match &self.body {
+            Some(body) => self.client.client.request(self.method, url.clone(), headers, Json(body)).await?,
+            None => self.client.client.request(self.method, url.clone(), headers, ()).await?,
+        };
+        let text = response.text().await?;
+        if !(200..300).contains(&code) {
+            return Err(TardisError::custom(
+                &code.to_string(),
+                &format!("[Tardis.ApiClient] {method} {url} responded {code}: {text}"),
+                "-1-tardis-apiclient-error",
+            ));
+        }
+        let text = if text.trim().is_empty() { "null" } else { text.as_str() };
+        if self.client.tardis_resp {
+            let resp = TardisFuns::json.str_to_obj::<TardisRespBody>(text)?;
+            if resp.code != "200" {
+                return Err(TardisError::custom(&resp.code, &resp.msg, ""));
+            }
+            TardisFuns::json.json_to_obj(resp.data.unwrap_or(Value::Null))
+        } else {
+            TardisFuns::json.str_to_obj(text)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TardisRespBody {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    data: Option<Value>,
+}
+
+/// Percent-encode the path parameter, only the unreserved characters are kept
+/// / 对路径参数进行百分号编码，只保留非保留字符
+pub fn encode_path_param(value: impl Display) -> String {
+    value
+        .to_string()
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
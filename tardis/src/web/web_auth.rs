@@ -0,0 +1,467 @@
+//! Bearer token authentication / Bearer令牌认证
+//!
+//! [`TardisAuthenticator`] verifies the JWTs by the configured JWKS or HMAC secret through [`TardisCryptoJwt::verify`](crate::crypto::crypto_jwt::TardisCryptoJwt::verify),
+//! [`TardisAuth`] middleware puts the verified [`AuthContext`] into the request,
+//! [`AuthGuard`] middleware checks the roles and permissions of it.
+//!
+//! [`TardisAuthenticator`] 通过 [`TardisCryptoJwt::verify`](crate::crypto::crypto_jwt::TardisCryptoJwt::verify) 使用配置的JWKS或HMAC密钥验证JWT， [`TardisAuth`] 中间件将验证后的 [`AuthContext`] 放入请求，
+//! [`AuthGuard`] 中间件检查其角色及权限.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::web::poem::{web::Data, Endpoint, EndpointExt};
+//! use tardis::web::web_auth::{AuthContext, AuthGuard, TardisAuth};
+//!
+//! fn admin_only(ep: impl Endpoint) -> impl Endpoint {
+//!     ep.with(AuthGuard::any_role(["admin"]))
+//! }
+//!
+//! #[poem_openapi::OpenApi]
+//! impl TodoApi {
+//!     #[oai(path = "/", method = "get")]
+//!     async fn list(&self, auth: Data<&AuthContext>) -> TardisApiResult<Vec<String>> {
+//!         auth.require_permission("todo:read")?;
+//!         ...
+//!     }
+//!
+//!     #[oai(path = "/:id", method = "delete", transform = "admin_only")]
+//!     async fn delete(&self, id: Path<i64>) -> TardisApiResult<Void> {
+//!         ...
+//!     }
+//! }
+//!
+//! TardisFuns::web_server().add_module("todo", WebServerModule::from(TodoApi).with_middleware(250, TardisAuth::new())).await;
+//! ```
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use jsonwebtoken::jwk::JwkSet;
+use poem::{Endpoint, FromRequest, IntoResponse, Middleware, Request, RequestBody, Response};
+use poem_openapi::auth::Bearer;
+use poem_openapi::SecurityScheme;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{trace, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::web_server::WebServerAuthConfig;
+use crate::crypto::crypto_jwt::{TardisJwtKey, TardisJwtValidation};
+use crate::{tardis_static, TardisFuns};
+
+tardis_static! {
+    /// Authenticator of the `fw.web_server.auth` configuration / `fw.web_server.auth` 配置的认证器
+    pub authenticator: Arc<TardisAuthenticator> = Arc::new(TardisAuthenticator::from_config());
+}
+
+/// Authenticated identity of the request / 请求的已认证身份
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthContext {
+    /// The `sub` claim / `sub` 声明
+    pub subject: String,
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+    /// All the claims of the token / 令牌的所有声明
+    pub claims: Value,
+}
+
+impl AuthContext {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
+
+    /// Fail with the `403-tardis-webserver-auth-role-required` error if the role is missing
+    /// / 缺少该角色时以 `403-tardis-webserver-auth-role-required` 错误失败
+    pub fn require_role(&self, role: &str) -> TardisResult<()> {
+        if self.has_role(role) {
+            Ok(())
+        } else {
+            Err(TardisError::custom(
+                "403-tardis-webserver-auth-role-required",
+                &format!("[Tardis.WebServer] Role {role} is required"),
+                "",
+            ))
+        }
+    }
+
+    /// Fail with the `403-tardis-webserver-auth-permission-required` error if the permission is missing
+    /// / 缺少该权限时以 `403-tardis-webserver-auth-permission-required` 错误失败
+    pub fn require_permission(&self, permission: &str) -> TardisResult<()> {
+        if self.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(TardisError::custom(
+                "403-tardis-webserver-auth-permission-required",
+                &format!("[Tardis.WebServer] Permission {permission} is required"),
+                "",
+            ))
+        }
+    }
+}
+
+/// Extract the [`AuthContext`] put by the [`TardisAuth`] middleware, fails with the `401-tardis-webserver-auth-required` error if it's absent
+/// / 提取 [`TardisAuth`] 中间件放入的 [`AuthContext`] ，不存在时以 `401-tardis-webserver-auth-required` 错误失败
+#[async_trait]
+impl<'a> FromRequest<'a> for AuthContext {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
+        req.extensions().get::<AuthContext>().cloned().ok_or_else(|| auth_required().into())
+    }
+}
+
+fn auth_required() -> TardisError {
+    TardisError::custom("401-tardis-webserver-auth-required", "[Tardis.WebServer] Authentication is required", "")
+}
+
+/// Bearer token security scheme of the ``OpenAPI`` handlers / ``OpenAPI`` 处理函数的Bearer令牌安全方案
+///
+/// Uses the [`AuthContext`] put by the [`TardisAuth`] middleware, or verifies the token by the [`authenticator`].
+///
+/// 使用 [`TardisAuth`] 中间件放入的 [`AuthContext`] ，或者由 [`authenticator`] 验证令牌.
+#[derive(SecurityScheme)]
+#[oai(ty = "bearer", checker = "auth_checker")]
+pub struct TardisAuthExtractor(pub AuthContext);
+
+async fn auth_checker(req: &Request, bearer: Bearer) -> Option<AuthContext> {
+    if let Some(context) = req.extensions().get::<AuthContext>() {
+        return Some(context.clone());
+    }
+    match authenticator().authenticate(&bearer.token).await {
+        Ok(context) => Some(context),
+        Err(error) => {
+            warn!("[Tardis.WebServer] [{}]{} at {}", error.code, error.message, req.uri());
+            None
+        }
+    }
+}
+
+/// JWT authenticator / JWT认证器
+///
+/// The keys of the JWKS are cached for `jwks_refresh_sec`, and refetched when a token signed by an unknown `kid` is met,
+/// at most once per `jwks_min_refresh_sec`, so the key rotation of the provider is followed.
+///
+/// JWKS的密钥缓存 `jwks_refresh_sec` ，遇到由未知 `kid` 签名的令牌时重新获取（每 `jwks_min_refresh_sec` 最多一次），以跟随提供方的密钥轮换.
+pub struct TardisAuthenticator {
+    config: WebServerAuthConfig,
+    /// The keys of the HMAC secret / HMAC密钥
+    secret_keys: Vec<TardisJwtKey>,
+    jwks: RwLock<JwksCache>,
+    jwks_fetching: Mutex<()>,
+    client: reqwest::Client,
+}
+
+#[derive(Default)]
+struct JwksCache {
+    keys: HashMap<String, TardisJwtKey>,
+    fetched_at: Option<Instant>,
+}
+
+impl JwksCache {
+    fn get(&self, kid: Option<&str>) -> Option<TardisJwtKey> {
+        match kid {
+            Some(kid) => self.keys.get(kid).cloned(),
+            // the token without `kid` is allowed only if the key is unique
+            None if self.keys.len() == 1 => self.keys.values().next().cloned(),
+            None => None,
+        }
+    }
+
+    fn fetched_within(&self, duration: Duration) -> bool {
+        self.fetched_at.map(|fetched_at| fetched_at.elapsed() < duration).unwrap_or(false)
+    }
+}
+
+impl TardisAuthenticator {
+    pub fn new(config: WebServerAuthConfig) -> Self {
+        let secret_keys = match &config.secret {
+            Some(secret) => vec![TardisJwtKey::hs256(secret), TardisJwtKey::hs384(secret), TardisJwtKey::hs512(secret)],
+            None => Vec::new(),
+        };
+        TardisAuthenticator {
+            config,
+            secret_keys,
+            jwks: RwLock::new(JwksCache::default()),
+            jwks_fetching: Mutex::new(()),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(TardisFuns::fw_config().web_server.as_ref().map(|config| config.auth.clone()).unwrap_or_default())
+    }
+
+    pub fn config(&self) -> &WebServerAuthConfig {
+        &self.config
+    }
+
+    /// Verify the token and build the [`AuthContext`] by its claims, fails with the `401-tardis-webserver-auth-token-invalid` error if it's invalid
+    /// / 验证令牌并按其声明构建 [`AuthContext`] ，无效时以 `401-tardis-webserver-auth-token-invalid` 错误失败
+    pub async fn authenticate(&self, token: &str) -> TardisResult<AuthContext> {
+        let header = jsonwebtoken::decode_header(token).map_err(|error| token_invalid(&error.to_string()))?;
+        let keys = match (&self.config.jwks_url, &self.config.secret) {
+            (Some(_), _) if header.kid.is_some() || self.config.secret.is_none() => vec![self.jwks_key(header.kid.as_deref()).await?],
+            (_, Some(_)) => self.secret_keys.clone(),
+            (None, None) => {
+                return Err(TardisError::custom(
+                    "500-tardis-webserver-auth-not-configured",
+                    "[Tardis.WebServer] Neither jwks_url nor secret of the authentication is configured",
+                    "",
+                ))
+            }
+        };
+        let validation = TardisJwtValidation {
+            leeway_sec: self.config.leeway_sec,
+            issuer: self.config.issuer.clone(),
+            audience: self.config.audience.clone(),
+            ..Default::default()
+        };
+        let claims = TardisFuns::crypto.jwt.verify::<Value>(token, &keys, &validation).map_err(|error| token_invalid(&error.message))?;
+        Ok(AuthContext {
+            subject: claims.get("sub").and_then(Value::as_str).unwrap_or_default().to_string(),
+            roles: claim_values(&claims, &self.config.roles_claim),
+            permissions: claim_values(&claims, &self.config.permissions_claim),
+            claims,
+        })
+    }
+
+    /// Refetch the keys of the JWKS / 重新获取JWKS的密钥
+    pub async fn refresh_jwks(&self) -> TardisResult<()> {
+        let Some(jwks_url) = &self.config.jwks_url else {
+            return Ok(());
+        };
+        trace!("[Tardis.WebServer] Fetch the JWKS from {jwks_url}");
+        let jwks = self
+            .client
+            .get(jwks_url)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|error| jwks_unavailable(&error.to_string()))?
+            .json::<JwkSet>()
+            .await
+            .map_err(|error| jwks_unavailable(&error.to_string()))?;
+        let mut keys = HashMap::new();
+        for jwk in &jwks.keys {
+            match TardisJwtKey::from_jwk(jwk) {
+                Ok(key) => {
+                    keys.insert(jwk.common.key_id.clone().unwrap_or_default(), key);
+                }
+                Err(error) => warn!("[Tardis.WebServer] Unsupported key {:?} of the JWKS: {}", jwk.common.key_id, error.message),
+            }
+        }
+        *self.jwks.write().await = JwksCache {
+            keys,
+            fetched_at: Some(Instant::now()),
+        };
+        Ok(())
+    }
+
+    async fn jwks_key(&self, kid: Option<&str>) -> TardisResult<TardisJwtKey> {
+        let refresh = Duration::from_secs(self.config.jwks_refresh_sec);
+        let min_refresh = Duration::from_secs(self.config.jwks_min_refresh_sec);
+        {
+            let jwks = self.jwks.read().await;
+            match jwks.get(kid) {
+                Some(key) if jwks.fetched_within(refresh) => return Ok(key),
+                None if jwks.fetched_within(min_refresh) => return Err(token_invalid(&format!("key {kid:?} is unknown"))),
+                _ => {}
+            }
+        }
+        let _fetching = self.jwks_fetching.lock().await;
+        {
+            // the keys may have been refetched while waiting for the lock
+            let jwks = self.jwks.read().await;
+            if jwks.fetched_within(min_refresh) {
+                return jwks.get(kid).ok_or_else(|| token_invalid(&format!("key {kid:?} is unknown")));
+            }
+        }
+        if let Err(error) = self.refresh_jwks().await {
+            // keep using the stale keys while the provider is unavailable
+            let jwks = self.jwks.read().await;
+            return match jwks.get(kid) {
+                Some(key) => {
+                    warn!("[Tardis.WebServer] Fetch the JWKS failed, the stale keys are used: {error}");
+                    Ok(key)
+                }
+                None => Err(error),
+            };
+        }
+        self.jwks.read().await.get(kid).ok_or_else(|| token_invalid(&format!("key {kid:?} is unknown")))
+    }
+}
+
+/// The values of the claim, which is an array or a space-separated string, nested claims are separated by `.`
+fn claim_values(claims: &Value, claim: &str) -> Vec<String> {
+    let value = claim.split('.').try_fold(claims, |value, key| value.get(key));
+    match value {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(values)) => values.split_whitespace().map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn token_invalid(reason: &str) -> TardisError {
+    TardisError::custom("401-tardis-webserver-auth-token-invalid", &format!("[Tardis.WebServer] Token is invalid: {reason}"), "")
+}
+
+fn jwks_unavailable(reason: &str) -> TardisError {
+    TardisError::custom(
+        "502-tardis-webserver-auth-jwks-unavailable",
+        &format!("[Tardis.WebServer] JWKS is unavailable: {reason}"),
+        "",
+    )
+}
+
+/// Authentication middleware / 认证中间件
+///
+/// Verifies the bearer token of the `Authorization` header and puts the [`AuthContext`] into the request,
+/// the requests without token fail with the `401-tardis-webserver-auth-required` error unless it's [optional](Self::optional),
+/// the invalid tokens fail with the `401-tardis-webserver-auth-token-invalid` error.
+///
+/// 验证 `Authorization` 请求头的Bearer令牌并将 [`AuthContext`] 放入请求，除非是 [可选的](Self::optional) ，无令牌的请求以 `401-tardis-webserver-auth-required` 错误失败，
+/// 无效令牌以 `401-tardis-webserver-auth-token-invalid` 错误失败.
+#[derive(Clone)]
+pub struct TardisAuth {
+    authenticator: Arc<TardisAuthenticator>,
+    optional: bool,
+}
+
+impl Default for TardisAuth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TardisAuth {
+    /// Use the [`authenticator`] of the configuration / 使用配置的 [`authenticator`]
+    pub fn new() -> Self {
+        Self::with_authenticator(authenticator().clone())
+    }
+
+    pub fn with_authenticator(authenticator: Arc<TardisAuthenticator>) -> Self {
+        TardisAuth { authenticator, optional: false }
+    }
+
+    /// Allow the requests without token / 放行无令牌的请求
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for TardisAuth {
+    type Output = TardisAuthImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TardisAuthImpl {
+            ep,
+            authenticator: self.authenticator.clone(),
+            optional: self.optional,
+        }
+    }
+}
+
+pub struct TardisAuthImpl<E> {
+    ep: E,
+    authenticator: Arc<TardisAuthenticator>,
+    optional: bool,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for TardisAuthImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let token = req
+            .header("Authorization")
+            .and_then(|authorization| authorization.split_once(' ').filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer")))
+            .map(|(_, token)| token.trim().to_string());
+        match token {
+            Some(token) => {
+                let context = self.authenticator.authenticate(&token).await?;
+                req.extensions_mut().insert(context);
+            }
+            None if self.optional => {}
+            None => return Err(auth_required().into()),
+        }
+        Ok(self.ep.call(req).await?.into_response())
+    }
+}
+
+/// Authorization middleware checking the [`AuthContext`] put by the [`TardisAuth`] middleware / 检查 [`TardisAuth`] 中间件放入的 [`AuthContext`] 的授权中间件
+///
+/// The requests without [`AuthContext`] fail with the `401-tardis-webserver-auth-required` error,
+/// the requests lacking the roles or permissions fail with the `403-tardis-webserver-auth-role-required` or `403-tardis-webserver-auth-permission-required` error.
+/// It can be attached to the ``OpenAPI`` operations by the `transform` attribute.
+///
+/// 无 [`AuthContext`] 的请求以 `401-tardis-webserver-auth-required` 错误失败，
+/// 缺少角色或权限的请求以 `403-tardis-webserver-auth-role-required` 或 `403-tardis-webserver-auth-permission-required` 错误失败.
+/// 可以通过 `transform` 属性附加到 ``OpenAPI`` 操作上.
+#[derive(Debug, Clone, Default)]
+pub struct AuthGuard {
+    any_roles: Vec<String>,
+    all_permissions: Vec<String>,
+}
+
+impl AuthGuard {
+    /// Require any of the roles / 要求具有任一角色
+    pub fn any_role(roles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        AuthGuard::default().and_any_role(roles)
+    }
+
+    /// Require all the permissions / 要求具有所有权限
+    pub fn all_permissions(permissions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        AuthGuard::default().and_all_permissions(permissions)
+    }
+
+    pub fn and_any_role(mut self, roles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.any_roles.extend(roles.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn and_all_permissions(mut self, permissions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.all_permissions.extend(permissions.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn check(&self, context: &AuthContext) -> TardisResult<()> {
+        if !self.any_roles.is_empty() && !self.any_roles.iter().any(|role| context.has_role(role)) {
+            return Err(TardisError::custom(
+                "403-tardis-webserver-auth-role-required",
+                &format!("[Tardis.WebServer] One of the roles {:?} is required", self.any_roles),
+                "",
+            ));
+        }
+        self.all_permissions.iter().try_for_each(|permission| context.require_permission(permission))
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for AuthGuard {
+    type Output = AuthGuardImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        AuthGuardImpl { ep, guard: self.clone() }
+    }
+}
+
+pub struct AuthGuardImpl<E> {
+    ep: E,
+    guard: AuthGuard,
+}
+
+#[async_trait]
+impl<E: Endpoint> Endpoint for AuthGuardImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let context = req.extensions().get::<AuthContext>().ok_or_else(auth_required)?;
+        self.guard.check(context)?;
+        Ok(self.ep.call(req).await?.into_response())
+    }
+}
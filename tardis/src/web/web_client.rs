@@ -1,20 +1,61 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use reqwest::{Client, IntoUrl, Method, RequestBuilder, Response};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Certificate, Client, ClientBuilder, Identity, IntoUrl, Method, NoProxy, Proxy, RequestBuilder, Response};
 use serde::Deserialize;
-use tracing::{error, info, trace};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, error, info, trace, warn, Instrument};
 
+use crate::basic::dto::TardisContext;
 use crate::basic::error::TardisError;
+use crate::basic::metrics::check_slow_operation;
 use crate::basic::result::TardisResult;
-use crate::config::config_dto::component::web_client::WebClientModuleConfig;
+use crate::basic::tracing::TardisTracing;
+use crate::config::config_dto::component::web_client::{WebClientModuleConfig, WebClientProxyConfig, WebClientTlsConfig};
 use crate::serde::Serialize;
+use crate::utils::circuit_breaker::{CircuitBreaker, CircuitState};
 use crate::utils::initializer::InitBy;
-use crate::TardisFuns;
-
+use crate::utils::retry::RetryPolicy;
+use crate::web::web_client_cache::{cache_ttl, CachedHttpResponse, TardisHttpCache};
+use crate::{tardis_static, TardisFuns};
+
+/// Web client / Web客户端
+///
+/// The client is cheap to clone, the clones share the connection pool and the circuit breakers,
+/// so the per-call policies can be applied by e.g. `client.with_timeout(Duration::from_secs(2)).get_to_str(url, None)`.
+///
+/// 客户端克隆开销很小，克隆体共享连接池及熔断器，因此可以按调用设置策略，如 `client.with_timeout(Duration::from_secs(2)).get_to_str(url, None)` .
+#[derive(Clone)]
 pub struct TardisWebClient {
     default_headers: Vec<(String, String)>,
     client: Client,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    retry_on_status: Vec<u16>,
+    circuit_breakers: Option<Arc<HostCircuitBreakers>>,
+    response_cache: Option<TardisHttpCache>,
+    propagate_context: bool,
+}
+
+/// Circuit breakers keyed by the host
+struct HostCircuitBreakers {
+    failure_threshold: u32,
+    open_duration: Duration,
+    breakers: Mutex<HashMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl HostCircuitBreakers {
+    fn get(&self, host: &str) -> Arc<CircuitBreaker> {
+        self.breakers
+            .lock()
+            .expect("[Tardis.WebClient] circuit breakers lock poisoned")
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(self.failure_threshold, self.open_duration)))
+            .clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -37,7 +78,7 @@ impl TardisRequestBody for () {
 pub struct PlainText<T>(T);
 
 /// Json body for [`TardisWebClient`],
-pub struct Json<'a, T>(&'a T);
+pub struct Json<'a, T>(pub(crate) &'a T);
 
 impl<T: Into<String>> TardisRequestBody for PlainText<T> {
     fn apply_on(self, builder: RequestBuilder) -> RequestBuilder {
@@ -51,20 +92,290 @@ impl<T: Serialize> TardisRequestBody for Json<'_, T> {
     }
 }
 
+impl TardisRequestBody for Form {
+    fn apply_on(self, builder: RequestBuilder) -> RequestBuilder {
+        builder.multipart(self)
+    }
+}
+
+enum TardisMultipartContent {
+    Text(String),
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+/// Part of the multipart body for [`TardisWebClient::post_multipart`] / [`TardisWebClient::post_multipart`] 的multipart请求体的一部分
+pub struct TardisMultipartPart {
+    name: String,
+    content: TardisMultipartContent,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+impl TardisMultipartPart {
+    /// Text field / 文本字段
+    pub fn text(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::new(name, TardisMultipartContent::Text(value.into()), None)
+    }
+
+    /// File from the bytes / 来自字节的文件
+    pub fn bytes(name: impl Into<String>, file_name: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        Self::new(name, TardisMultipartContent::Bytes(content.into()), Some(file_name.into()))
+    }
+
+    /// File from the local path, it's streamed rather than loaded into memory / 来自本地路径的文件，以流的方式发送而不加载到内存
+    pub fn file(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let file_name = path.file_name().map(|file_name| file_name.to_string_lossy().to_string());
+        Self::new(name, TardisMultipartContent::File(path), file_name)
+    }
+
+    fn new(name: impl Into<String>, content: TardisMultipartContent, file_name: Option<String>) -> Self {
+        TardisMultipartPart {
+            name: name.into(),
+            content,
+            file_name,
+            content_type: None,
+        }
+    }
+
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    async fn into_part(self) -> TardisResult<(String, Part)> {
+        let mut part = match self.content {
+            TardisMultipartContent::Text(value) => Part::text(value),
+            TardisMultipartContent::Bytes(content) => Part::bytes(content),
+            TardisMultipartContent::File(path) => {
+                let file = tokio::fs::File::open(&path).await?;
+                let len = file.metadata().await?.len();
+                Part::stream_with_length(file, len)
+            }
+        };
+        if let Some(file_name) = self.file_name {
+            part = part.file_name(file_name);
+        }
+        if let Some(content_type) = self.content_type {
+            part = part.mime_str(&content_type)?;
+        }
+        Ok((self.name, part))
+    }
+}
+
+tardis_static! {
+    default_response_cache: TardisHttpCache = TardisHttpCache::memory(1000);
+}
+
+/// Split the PEM bundle into the blocks of the label, since a certificate is parsed from a single block
+fn pem_blocks(pem: &[u8], label: &str) -> Vec<String> {
+    let begin = format!("-----BEGIN {label}-----");
+    let end = format!("-----END {label}-----");
+    let pem = String::from_utf8_lossy(pem);
+    let mut blocks = Vec::new();
+    let mut rest = pem.as_ref();
+    while let Some(start) = rest.find(&begin) {
+        let Some(len) = rest[start..].find(&end) else {
+            break;
+        };
+        blocks.push(rest[start..start + len + end.len()].to_string());
+        rest = &rest[start + len + end.len()..];
+    }
+    blocks
+}
+
 /// convert a str pair into a string pair, it may be helpful when you want to use a string literal as a header for [`TardisWebClient`]
 pub fn str_pair_to_string_pair(p: (&str, &str)) -> (String, String) {
     (p.0.to_owned(), p.1.to_owned())
 }
 
 impl TardisWebClient {
-    pub fn init(WebClientModuleConfig { connect_timeout_sec, .. }: &WebClientModuleConfig) -> TardisResult<TardisWebClient> {
+    pub fn init(
+        WebClientModuleConfig {
+            connect_timeout_sec,
+            request_timeout_sec,
+            retry,
+            circuit_breaker,
+            proxy,
+            tls,
+            cache,
+            propagate_context,
+            ..
+        }: &WebClientModuleConfig,
+    ) -> TardisResult<TardisWebClient> {
         info!("[Tardis.WebClient] Initializing");
-        let client = reqwest::Client::builder().danger_accept_invalid_certs(true).connect_timeout(Duration::from_secs(*connect_timeout_sec)).https_only(false).build()?;
+        let mut builder = reqwest::Client::builder().connect_timeout(Duration::from_secs(*connect_timeout_sec)).https_only(false);
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(Self::build_proxy(proxy)?);
+        }
+        let client = Self::apply_tls(builder, tls)?.build()?;
+        let mut web_client = TardisWebClient::from_client(client).with_timeout(Duration::from_secs(*request_timeout_sec)).with_context_propagation(*propagate_context);
+        if retry.max_retries > 0 {
+            web_client = web_client
+                .with_retry(
+                    RetryPolicy::exponential(Duration::from_millis(retry.initial_backoff_ms), Duration::from_millis(retry.max_backoff_ms))
+                        .with_jitter()
+                        .with_max_retries(retry.max_retries),
+                )
+                .with_retry_on_status(retry.retry_on_status.clone());
+        }
+        if circuit_breaker.enabled {
+            web_client = web_client.with_circuit_breaker(circuit_breaker.failure_threshold, Duration::from_secs(circuit_breaker.open_sec));
+        }
+        if cache.enabled {
+            web_client = web_client.with_response_cache(match &cache.cache_module {
+                #[cfg(feature = "cache")]
+                Some(code) => TardisHttpCache::cache_module(code.clone()),
+                #[cfg(not(feature = "cache"))]
+                Some(code) => {
+                    warn!("[Tardis.WebClient] The cache module {code} requires the cache feature, the in-memory response cache is used");
+                    TardisHttpCache::memory(cache.max_entries)
+                }
+                None => TardisHttpCache::memory(cache.max_entries),
+            });
+        }
         info!("[Tardis.WebClient] Initialized");
-        TardisResult::Ok(TardisWebClient {
+        TardisResult::Ok(web_client)
+    }
+
+    fn build_proxy(
+        WebClientProxyConfig {
+            url,
+            username,
+            password,
+            no_proxy,
+        }: &WebClientProxyConfig,
+    ) -> TardisResult<Proxy> {
+        let mut proxy = Proxy::all(url.as_str())
+            .map_err(|error| TardisError::format_error(&format!("[Tardis.WebClient] Invalid proxy {url}: {error}"), "406-tardis-webclient-proxy-invalid"))?;
+        if let Some(username) = username {
+            proxy = proxy.basic_auth(username, password.as_deref().unwrap_or_default());
+        }
+        if let Some(no_proxy) = no_proxy {
+            proxy = proxy.no_proxy(NoProxy::from_string(no_proxy));
+        }
+        Ok(proxy)
+    }
+
+    fn apply_tls(mut builder: ClientBuilder, tls: &WebClientTlsConfig) -> TardisResult<ClientBuilder> {
+        let read = |path: &str| {
+            std::fs::read(path).map_err(|error| TardisError::format_error(&format!("[Tardis.WebClient] Read {path} error: {error}"), "406-tardis-webclient-tls-invalid"))
+        };
+        let invalid = |path: &str, error: reqwest::Error| {
+            TardisError::format_error(
+                &format!("[Tardis.WebClient] Invalid certificate or key {path}: {error}"),
+                "406-tardis-webclient-tls-invalid",
+            )
+        };
+        builder = builder.danger_accept_invalid_certs(tls.accept_invalid_certs).tls_built_in_root_certs(!tls.disable_built_in_root_certs);
+        for path in &tls.ca_cert_paths {
+            let pem = read(path)?;
+            for cert in pem_blocks(&pem, "CERTIFICATE") {
+                builder = builder.add_root_certificate(Certificate::from_pem(cert.as_bytes()).map_err(|error| invalid(path, error))?);
+            }
+        }
+        match (&tls.client_cert_path, &tls.client_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let identity = Identity::from_pkcs8_pem(&read(cert_path)?, &read(key_path)?).map_err(|error| invalid(cert_path, error))?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(TardisError::format_error(
+                    "[Tardis.WebClient] Both client_cert_path and client_key_path are required for the mutual TLS",
+                    "406-tardis-webclient-tls-invalid",
+                ))
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Wrap a customized reqwest client, E.g. with TLS client certificates / 包装自定义的reqwest客户端，如带TLS客户端证书
+    pub fn from_client(client: Client) -> TardisWebClient {
+        TardisWebClient {
             client,
             default_headers: Vec::new(),
-        })
+            timeout: None,
+            retry: None,
+            retry_on_status: vec![502, 503, 504],
+            circuit_breakers: None,
+            response_cache: None,
+            propagate_context: false,
+        }
+    }
+
+    /// Timeout of each attempt, from sending the request until the response body is read
+    /// / 每次请求的超时时间，从发送请求直到读取完响应体
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry the idempotent requests (`GET` `HEAD` `PUT` `DELETE` `OPTIONS` `TRACE`) on the connection errors, the timeouts
+    /// and the responses with the retryable status, the errors passed to the policy are coded by the status for the responses
+    /// / 在连接错误、超时及可重试状态码的响应时重试幂等请求（ `GET` `HEAD` `PUT` `DELETE` `OPTIONS` `TRACE` ），响应对应的传给策略的错误以状态码为错误码
+    ///
+    /// The requests with streaming bodies are never retried.
+    ///
+    /// 流式请求体的请求不会重试.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// The response status to retry, `502` `503` `504` by default / 需重试的响应状态码，默认为 `502` `503` `504`
+    pub fn with_retry_on_status(mut self, statuses: impl IntoIterator<Item = u16>) -> Self {
+        self.retry_on_status = statuses.into_iter().collect();
+        self
+    }
+
+    pub fn without_retry(mut self) -> Self {
+        self.retry = None;
+        self
+    }
+
+    /// Open the circuit of a host after `failure_threshold` consecutive failures (connection errors, timeouts or `5xx` responses),
+    /// the requests to the host fail with the `503-tardis-webclient-circuit-open` error during `open_duration`
+    /// / 主机连续失败（连接错误、超时或 `5xx` 响应） `failure_threshold` 次后熔断，在 `open_duration` 内对该主机的请求以 `503-tardis-webclient-circuit-open` 错误失败
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, open_duration: Duration) -> Self {
+        self.circuit_breakers = Some(Arc::new(HostCircuitBreakers {
+            failure_threshold,
+            open_duration,
+            breakers: Mutex::new(HashMap::new()),
+        }));
+        self
+    }
+
+    /// Cache the `GET` responses of [`Self::get_to_str`] and [`Self::get`] as the `Cache-Control` and `ETag`/`Last-Modified` response headers say,
+    /// the cache is also used by [`Self::get_cached`]
+    /// / 按 `Cache-Control` 及 `ETag`/`Last-Modified` 响应头缓存 [`Self::get_to_str`] 及 [`Self::get`] 的 `GET` 响应，该缓存也用于 [`Self::get_cached`]
+    ///
+    /// The cache is keyed by the url only, don't enable it for the responses varying with the request headers, e.g. the user specific ones.
+    ///
+    /// 缓存仅以url为键，不要对随请求头变化的响应（如用户相关的响应）启用.
+    pub fn with_response_cache(mut self, cache: TardisHttpCache) -> Self {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    /// Whether to carry the [`TardisContext`](crate::basic::dto::TardisContext) of the current task in the context header, disabled by default,
+    /// enable it only for the trusted internal services, the context header passed explicitly is kept
+    /// / 是否在上下文请求头中携带当前task的 [`TardisContext`](crate::basic::dto::TardisContext) ，默认禁用，仅应对受信的内部服务启用，显式传入的上下文请求头保持不变
+    pub fn with_context_propagation(mut self, propagate_context: bool) -> Self {
+        self.propagate_context = propagate_context;
+        self
+    }
+
+    /// State of the circuit of the host (`host` or `host:port`), `None` if the circuit breaker is disabled
+    /// / 主机（ `host` 或 `host:port` ）的熔断状态，未启用熔断器时为 `None`
+    pub fn circuit_state(&self, host: &str) -> Option<CircuitState> {
+        self.circuit_breakers.as_ref().map(|breakers| breakers.get(host).state())
     }
 
     pub fn set_default_header(&mut self, key: &str, value: &str) {
@@ -78,15 +389,101 @@ impl TardisWebClient {
     }
 
     pub async fn get_to_str(&self, url: impl IntoUrl, headers: impl IntoIterator<Item = (String, String)>) -> TardisResult<TardisHttpResponse<String>> {
+        if let Some(cache) = &self.response_cache {
+            return self.get_with_cache(cache, url, headers, None).await;
+        }
         let (code, headers, response) = self.request(Method::GET, url, headers, ()).await?;
         self.to_text(code, headers, response).await
     }
 
     pub async fn get<T: for<'de> Deserialize<'de>>(&self, url: impl IntoUrl, headers: impl IntoIterator<Item = (String, String)>) -> TardisResult<TardisHttpResponse<T>> {
+        if let Some(cache) = &self.response_cache {
+            let TardisHttpResponse { code, headers, body } = self.get_with_cache(cache, url, headers, None).await?;
+            let body = match body {
+                Some(body) => Some(
+                    TardisFuns::json.str_to_obj(&body).map_err(|error| TardisError::format_error(&format!("[Tardis.WebClient] {error:?}"), "406-tardis-webclient-json-error"))?,
+                ),
+                None => None,
+            };
+            return Ok(TardisHttpResponse { code, headers, body });
+        }
         let (code, headers, response) = self.request(Method::GET, url, headers, ()).await?;
         self.to_json::<T>(code, headers, response).await
     }
 
+    /// Get the text and cache it for `ttl` regardless of the response headers, the stale response with the validators is revalidated
+    /// / 获取文本并无视响应头缓存 `ttl` 时长，带验证器的过期响应会重新验证
+    ///
+    /// The cache of [`Self::with_response_cache`] is used, or a process-wide in-memory cache if it isn't set.
+    ///
+    /// 使用 [`Self::with_response_cache`] 设置的缓存，未设置时使用进程级内存缓存.
+    pub async fn get_cached(&self, url: impl IntoUrl, ttl: Duration, headers: impl IntoIterator<Item = (String, String)>) -> TardisResult<TardisHttpResponse<String>> {
+        let cache = self.response_cache.as_ref().unwrap_or_else(default_response_cache);
+        self.get_with_cache(cache, url, headers, Some(ttl)).await
+    }
+
+    /// Remove the cached response of the url / 删除该url的已缓存响应
+    pub async fn invalidate_cached(&self, url: impl IntoUrl) -> TardisResult<()> {
+        let cache = self.response_cache.as_ref().unwrap_or_else(default_response_cache);
+        cache.remove(&Self::cache_key(url)?).await
+    }
+
+    fn cache_key(url: impl IntoUrl) -> TardisResult<String> {
+        let mut url = url.into_url()?;
+        TardisFuns::uri.sort_url_query(&mut url);
+        Ok(url.to_string())
+    }
+
+    async fn get_with_cache(
+        &self,
+        cache: &TardisHttpCache,
+        url: impl IntoUrl,
+        headers: impl IntoIterator<Item = (String, String)>,
+        ttl: Option<Duration>,
+    ) -> TardisResult<TardisHttpResponse<String>> {
+        let key = Self::cache_key(url)?;
+        // the cache is an optimization, the backend failures are treated as misses
+        let cached = cache.get(&key).await.unwrap_or_else(|error| {
+            warn!("[Tardis.WebClient] Get the cached response of {key} error: {error}");
+            None
+        });
+        if let Some(cached) = cached.as_ref().filter(|cached| cached.is_fresh()) {
+            trace!("[Tardis.WebClient] Hit the cached response of {key}");
+            return Ok(cached.to_response());
+        }
+        let mut headers = headers.into_iter().collect::<Vec<_>>();
+        if let Some(cached) = &cached {
+            if let Some(etag) = cached.etag() {
+                headers.push(("If-None-Match".to_string(), etag.to_string()));
+            }
+            if let Some(last_modified) = cached.last_modified() {
+                headers.push(("If-Modified-Since".to_string(), last_modified.to_string()));
+            }
+        }
+        let (code, resp_headers, response) = self.request(Method::GET, key.as_str(), headers, ()).await?;
+        let (response, to_cache) = match cached {
+            Some(mut cached) if code == 304 => {
+                cached.refresh(ttl.or_else(|| cache_ttl(&resp_headers)).unwrap_or_default());
+                (cached.to_response(), Some(cached))
+            }
+            _ => {
+                let response = self.to_text(code, resp_headers, response).await?;
+                let to_cache = if code == 200 {
+                    ttl.or_else(|| cache_ttl(&response.headers)).map(|ttl| CachedHttpResponse::new(&response, ttl))
+                } else {
+                    None
+                };
+                (response, to_cache)
+            }
+        };
+        if let Some(to_cache) = to_cache {
+            if let Err(error) = cache.put(&key, &to_cache).await {
+                warn!("[Tardis.WebClient] Cache the response of {key} error: {error}");
+            }
+        }
+        Ok(response)
+    }
+
     pub async fn head_to_void(&self, url: impl IntoUrl, headers: impl IntoIterator<Item = (String, String)>) -> TardisResult<TardisHttpResponse<()>> {
         let (code, headers, _) = self.request(Method::HEAD, url, headers, ()).await?;
         Ok(TardisHttpResponse { code, headers, body: None })
@@ -222,7 +619,80 @@ impl TardisWebClient {
         self.to_json::<T>(code, headers, response).await
     }
 
-    async fn request<K, V>(
+    /// Post a multipart body / 发送multipart请求体
+    pub async fn post_multipart(
+        &self,
+        url: impl IntoUrl,
+        parts: impl IntoIterator<Item = TardisMultipartPart>,
+        headers: impl IntoIterator<Item = (String, String)>,
+    ) -> TardisResult<TardisHttpResponse<String>> {
+        let mut form = Form::new();
+        for part in parts {
+            let (name, part) = part.into_part().await?;
+            form = form.part(name, part);
+        }
+        let (code, headers, response) = self.request(Method::POST, url, headers, form).await?;
+        self.to_text(code, headers, response).await
+    }
+
+    /// Download the response body into the file, returns the size of the file
+    /// / 将响应体下载到文件，返回文件大小
+    ///
+    /// The body is streamed into a `.part` file which is renamed to `path` once completed,
+    /// `progress` is called with the downloaded bytes and the total bytes (if the server provides the `Content-Length`) after each chunk.
+    ///
+    /// 响应体以流的方式写入 `.part` 文件，完成后重命名为 `path` ，
+    /// 每个数据块后以已下载字节数及总字节数（如果服务端提供了 `Content-Length` ）调用 `progress` .
+    ///
+    /// The request timeout covers the whole download, extend it by [`Self::with_timeout`] for the large files.
+    ///
+    /// 请求超时时间覆盖整个下载过程，大文件可通过 [`Self::with_timeout`] 延长.
+    pub async fn download_to_file(
+        &self,
+        url: impl IntoUrl,
+        path: impl AsRef<Path>,
+        headers: impl IntoIterator<Item = (String, String)>,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> TardisResult<u64> {
+        let path = path.as_ref();
+        let (code, _, mut response) = self.request(Method::GET, url, headers, ()).await?;
+        if !(200..300).contains(&code) {
+            return Err(TardisError::custom(
+                &code.to_string(),
+                &format!("[Tardis.WebClient] Download {} error: status {code}", response.url()),
+                "-1-tardis-webclient-download-error",
+            ));
+        }
+        let total = response.content_length();
+        let mut part_path = path.as_os_str().to_owned();
+        part_path.push(".part");
+        let part_path = PathBuf::from(part_path);
+        let mut file = tokio::fs::File::create(&part_path).await?;
+        let mut downloaded = 0;
+        let result: TardisResult<()> = async {
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                downloaded += chunk.len() as u64;
+                progress(downloaded, total);
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+        drop(file);
+        match result {
+            Ok(()) => {
+                tokio::fs::rename(&part_path, path).await?;
+                Ok(downloaded)
+            }
+            Err(error) => {
+                let _ = tokio::fs::remove_file(&part_path).await;
+                Err(error)
+            }
+        }
+    }
+
+    pub(crate) async fn request<K, V>(
         &self,
         method: Method,
         url: impl IntoUrl,
@@ -237,16 +707,103 @@ impl TardisWebClient {
         TardisFuns::uri.sort_url_query(&mut url);
         let method_str = method.to_string();
         trace!("[Tardis.WebClient] Request {}:{}", method_str, &url);
+        let span = tracing::info_span!(
+            "http_client",
+            otel.name = format!("{method_str} {}", url.path()),
+            otel.kind = "client",
+            http.method = method_str.as_str(),
+            http.url = url.as_str(),
+            http.status_code = tracing::field::Empty,
+        );
         let mut result = self.client.request(method, url.clone());
         for (key, value) in &self.default_headers {
             result = result.header(key, value);
         }
+        let mut trace_headers = HashMap::new();
+        span.in_scope(|| TardisTracing::inject_context(&mut trace_headers));
+        for (key, value) in trace_headers {
+            result = result.header(key, value);
+        }
+        let headers = headers.into_iter().map(|(key, value)| (key.into(), value.into())).collect::<Vec<(String, String)>>();
+        let context_header = TardisContext::header_name();
+        if self.propagate_context && !self.default_headers.iter().chain(headers.iter()).any(|(key, _)| key.eq_ignore_ascii_case(&context_header)) {
+            let mut context_headers = HashMap::new();
+            TardisContext::inject(&mut context_headers);
+            for (key, value) in context_headers {
+                result = result.header(key, value);
+            }
+        }
         for (key, value) in headers {
-            result = result.header(key.into(), value.into());
+            result = result.header(key, value);
         }
         result = body.apply_on(result);
-        let response = result.send().await?;
+        if let Some(timeout) = self.timeout {
+            result = result.timeout(timeout);
+        }
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (host, _) => host.unwrap_or_default().to_string(),
+        };
+        let breaker = self.circuit_breakers.as_ref().map(|breakers| breakers.get(&host));
+        let retry = self.retry.as_ref().filter(|_| matches!(method_str.as_str(), "GET" | "HEAD" | "PUT" | "DELETE" | "OPTIONS" | "TRACE"));
+        let first_start = Instant::now();
+        let mut retries = 0;
+        let mut pending = Some(result);
+        let response = loop {
+            let current = pending.take().expect("[Tardis.WebClient] request is pending");
+            pending = retry.and_then(|_| current.try_clone());
+            if let Some(breaker) = &breaker {
+                if !breaker.try_acquire() {
+                    return Err(TardisError::custom(
+                        "503-tardis-webclient-circuit-open",
+                        &format!("[Tardis.WebClient] Circuit of {host} is open, request {method_str}:{url} is rejected"),
+                        "",
+                    ));
+                }
+            }
+            let start = Instant::now();
+            let response = current.send().instrument(span.clone()).await;
+            check_slow_operation("web_client", &method_str, start.elapsed());
+            #[cfg(feature = "metrics")]
+            TardisFuns::metrics().record_http_client(
+                url.host_str().unwrap_or_default(),
+                &method_str,
+                response.as_ref().ok().map(|response| response.status().as_u16()),
+                start.elapsed(),
+            );
+            if let Some(breaker) = &breaker {
+                match &response {
+                    Ok(response) if !response.status().is_server_error() => breaker.on_success(),
+                    _ => breaker.on_failure(),
+                }
+            }
+            let (Some(policy), Some(_)) = (retry, &pending) else {
+                break response;
+            };
+            let error = match &response {
+                Ok(response) if self.retry_on_status.contains(&response.status().as_u16()) => Some(TardisError::custom(
+                    &response.status().as_u16().to_string(),
+                    &format!("[Tardis.WebClient] Request {method_str}:{url} responded {}", response.status()),
+                    "-1-tardis-webclient-error",
+                )),
+                Ok(_) => None,
+                Err(error) => Some(TardisError::wrap(&format!("[Tardis.WebClient] {error:?}"), "-1-tardis-webclient-error")),
+            };
+            let Some(error) = error else {
+                break response;
+            };
+            match policy.next_delay(retries, first_start.elapsed(), &error) {
+                Some(delay) => {
+                    retries += 1;
+                    debug!("[Tardis.WebClient] Request {method_str}:{url} attempt {retries} failed: {error}, retry after {delay:?}");
+                    tokio::time::sleep(delay).await;
+                }
+                None => break response,
+            }
+        };
+        let response = response?;
         let code = response.status().as_u16();
+        span.record("http.status_code", code);
         let headers = response
             .headers()
             .iter()
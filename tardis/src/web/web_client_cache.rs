@@ -0,0 +1,246 @@
+//! Response cache of the web client / Web客户端的响应缓存
+//!
+//! The `GET` responses are cached by the url, the freshness follows the `Cache-Control` response header
+//! (`no-store` isn't cached, `max-age`/`s-maxage` is the ttl, `no-cache` and the responses with only the validators are revalidated each time),
+//! the stale responses with the `ETag`/`Last-Modified` validators are revalidated by the conditional requests.
+//!
+//! `GET` 响应按url缓存，新鲜度遵循 `Cache-Control` 响应头（ `no-store` 不缓存， `max-age`/`s-maxage` 为有效期，
+//! `no-cache` 及只有验证器的响应每次都重新验证），带 `ETag`/`Last-Modified` 验证器的过期响应通过条件请求重新验证.
+//!
+//! # Examples
+//! ```ignore
+//! let client = TardisFuns::web_client().as_ref().clone().with_response_cache(TardisHttpCache::memory(1000));
+//! // cached as the server says
+//! let resp = client.get_to_str("https://metadata.example.com/regions", None).await?;
+//! // cached for 10 minutes regardless of the headers
+//! let resp = client.get_cached("https://metadata.example.com/zones", Duration::from_secs(600), None).await?;
+//! ```
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::basic::result::TardisResult;
+#[cfg(feature = "cache")]
+use crate::cache::cache_client::TardisCacheClient;
+use crate::web::web_client::TardisHttpResponse;
+#[cfg(feature = "cache")]
+use crate::TardisFuns;
+
+/// Stale responses with the validators are kept for revalidation during this period / 带验证器的过期响应在该时长内保留以便重新验证
+const STALE_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+#[cfg(feature = "cache")]
+const CACHE_KEY_PREFIX: &str = "tardis:web_client:cache:";
+
+/// Cached response / 已缓存的响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedHttpResponse {
+    pub code: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    /// Expiration time in milliseconds / 过期时间（毫秒）
+    pub expires_at: i64,
+}
+
+impl CachedHttpResponse {
+    pub(crate) fn new(response: &TardisHttpResponse<String>, ttl: Duration) -> Self {
+        CachedHttpResponse {
+            code: response.code,
+            headers: response.headers.clone(),
+            body: response.body.clone().unwrap_or_default(),
+            expires_at: expires_at(ttl),
+        }
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.expires_at > chrono::Utc::now().timestamp_millis()
+    }
+
+    pub(crate) fn refresh(&mut self, ttl: Duration) {
+        self.expires_at = expires_at(ttl);
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.headers.get("etag").map(String::as_str)
+    }
+
+    pub fn last_modified(&self) -> Option<&str> {
+        self.headers.get("last-modified").map(String::as_str)
+    }
+
+    fn retention(&self) -> Duration {
+        let ttl = Duration::from_millis((self.expires_at - chrono::Utc::now().timestamp_millis()).max(0) as u64);
+        if self.etag().is_some() || self.last_modified().is_some() {
+            ttl + STALE_RETENTION
+        } else {
+            ttl
+        }
+    }
+
+    pub(crate) fn to_response(&self) -> TardisHttpResponse<String> {
+        TardisHttpResponse {
+            code: self.code,
+            headers: self.headers.clone(),
+            body: Some(self.body.clone()),
+        }
+    }
+}
+
+fn expires_at(ttl: Duration) -> i64 {
+    chrono::Utc::now().timestamp_millis() + ttl.as_millis() as i64
+}
+
+/// The ttl by the `Cache-Control` response header, `None` means the response shouldn't be cached
+/// / 由 `Cache-Control` 响应头得出的有效期， `None` 表示不应缓存该响应
+pub fn cache_ttl(headers: &HashMap<String, String>) -> Option<Duration> {
+    let has_validators = headers.contains_key("etag") || headers.contains_key("last-modified");
+    let Some(cache_control) = headers.get("cache-control") else {
+        return has_validators.then_some(Duration::ZERO);
+    };
+    let mut max_age = None;
+    for directive in cache_control.split(',').map(|directive| directive.trim().to_lowercase()) {
+        match directive.split_once('=') {
+            None if directive == "no-store" => return None,
+            None if directive == "no-cache" => return Some(Duration::ZERO),
+            Some(("s-maxage", value)) => max_age = value.trim_matches('"').parse::<u64>().ok().or(max_age),
+            Some(("max-age", value)) if max_age.is_none() => max_age = value.trim_matches('"').parse::<u64>().ok(),
+            _ => {}
+        }
+    }
+    match max_age {
+        Some(max_age) => Some(Duration::from_secs(max_age)),
+        None => has_validators.then_some(Duration::ZERO),
+    }
+}
+
+/// Response cache backend / 响应缓存后端
+#[derive(Clone)]
+pub enum TardisHttpCache {
+    /// In-memory cache of the process / 进程内存缓存
+    Memory(Arc<MemoryHttpCache>),
+    /// Shared cache by the cache client / 通过缓存客户端共享的缓存
+    #[cfg(feature = "cache")]
+    Shared(SharedHttpCache),
+}
+
+/// Cache client of the shared response cache / 共享响应缓存的缓存客户端
+#[cfg(feature = "cache")]
+#[derive(Clone)]
+pub enum SharedHttpCache {
+    Client(Arc<TardisCacheClient>),
+    /// The cache client of the module, resolved on use since the web client is initialized before the cache
+    /// / 该模块的缓存客户端，由于Web客户端先于缓存初始化，使用时才解析
+    Module(String),
+}
+
+#[cfg(feature = "cache")]
+impl SharedHttpCache {
+    fn client(&self) -> Arc<TardisCacheClient> {
+        match self {
+            SharedHttpCache::Client(client) => client.clone(),
+            SharedHttpCache::Module(code) => TardisFuns::cache_by_module_or_default(code),
+        }
+    }
+}
+
+impl TardisHttpCache {
+    /// In-memory cache holding at most `max_entries` responses / 最多保存 `max_entries` 个响应的内存缓存
+    pub fn memory(max_entries: usize) -> Self {
+        TardisHttpCache::Memory(Arc::new(MemoryHttpCache {
+            max_entries: max_entries.max(1),
+            entries: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn cache_client(client: Arc<TardisCacheClient>) -> Self {
+        TardisHttpCache::Shared(SharedHttpCache::Client(client))
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn cache_module(code: impl Into<String>) -> Self {
+        TardisHttpCache::Shared(SharedHttpCache::Module(code.into()))
+    }
+
+    pub async fn get(&self, key: &str) -> TardisResult<Option<CachedHttpResponse>> {
+        match self {
+            TardisHttpCache::Memory(cache) => Ok(cache.get(key)),
+            #[cfg(feature = "cache")]
+            TardisHttpCache::Shared(shared) => match shared.client().get(&format!("{CACHE_KEY_PREFIX}{key}")).await? {
+                Some(value) => Ok(Some(TardisFuns::json.str_to_obj(&value)?)),
+                None => Ok(None),
+            },
+        }
+    }
+
+    pub async fn put(&self, key: &str, response: &CachedHttpResponse) -> TardisResult<()> {
+        let retention = response.retention();
+        if retention.is_zero() {
+            return self.remove(key).await;
+        }
+        match self {
+            TardisHttpCache::Memory(cache) => cache.put(key, response.clone(), retention),
+            #[cfg(feature = "cache")]
+            TardisHttpCache::Shared(shared) => {
+                let value = TardisFuns::json.obj_to_string(response)?;
+                shared.client().set_ex(&format!("{CACHE_KEY_PREFIX}{key}"), &value, retention.as_secs().max(1) as usize).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn remove(&self, key: &str) -> TardisResult<()> {
+        match self {
+            TardisHttpCache::Memory(cache) => {
+                cache.entries.lock().expect("[Tardis.WebClient] cache lock poisoned").remove(key);
+            }
+            #[cfg(feature = "cache")]
+            TardisHttpCache::Shared(shared) => shared.client().del(&format!("{CACHE_KEY_PREFIX}{key}")).await?,
+        }
+        Ok(())
+    }
+}
+
+/// In-memory response cache, the entries retained shortest are evicted first when it's full
+/// / 内存响应缓存，已满时优先淘汰保留期最短的条目
+pub struct MemoryHttpCache {
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (CachedHttpResponse, i64)>>,
+}
+
+impl MemoryHttpCache {
+    fn get(&self, key: &str) -> Option<CachedHttpResponse> {
+        let mut entries = self.entries.lock().expect("[Tardis.WebClient] cache lock poisoned");
+        match entries.get(key) {
+            Some((_, retain_until)) if *retain_until <= chrono::Utc::now().timestamp_millis() => {
+                entries.remove(key);
+                None
+            }
+            Some((response, _)) => Some(response.clone()),
+            None => None,
+        }
+    }
+
+    fn put(&self, key: &str, response: CachedHttpResponse, retention: Duration) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut entries = self.entries.lock().expect("[Tardis.WebClient] cache lock poisoned");
+        if !entries.contains_key(key) && entries.len() >= self.max_entries {
+            entries.retain(|_, (_, retain_until)| *retain_until > now);
+            if entries.len() >= self.max_entries {
+                if let Some(evicted) = entries.iter().min_by_key(|(_, (_, retain_until))| *retain_until).map(|(key, _)| key.clone()) {
+                    entries.remove(&evicted);
+                }
+            }
+        }
+        entries.insert(key.to_string(), (response, now + retention.as_millis() as i64));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("[Tardis.WebClient] cache lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
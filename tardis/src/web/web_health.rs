@@ -0,0 +1,243 @@
+//! Health and readiness probes / 健康及就绪探针
+//!
+//! [`TardisHealth`] checks the initialized components (cache `PING`, relational database `SELECT 1`, MQ connection,
+//! search cluster health, object storage default bucket) and the custom checks concurrently with timeouts,
+//! the `/healthz` liveness probe reports the liveness checks only, the `/readyz` readiness probe reports all of them,
+//! they respond `503` if any check is down.
+//!
+//! [`TardisHealth`] 带超时地并发检查已初始化的组件（缓存 `PING` 、关系型数据库 `SELECT 1` 、MQ连接、搜索集群健康状态、对象存储默认桶）及自定义检查，
+//! `/healthz` 存活探针只报告存活检查， `/readyz` 就绪探针报告所有检查，任一检查失败时响应 `503` .
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::web::web_health::TardisHealth;
+//! let health = TardisHealth::new()
+//!     .component_timeout("search", Duration::from_secs(5))
+//!     .readiness_check("warmup", || async { if warmed_up() { Ok(()) } else { Err(TardisError::io_error("not warmed up", "")) } });
+//! TardisFuns::web_server().add_health_probes(health).await;
+//! // curl http://127.0.0.1:8080/readyz
+//! // {"status":"UP","components":{"cache":{"status":"UP","elapsed_ms":1},"reldb":{"status":"UP","elapsed_ms":2},"warmup":{"status":"UP","elapsed_ms":0}}}
+//! ```
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::future::{join_all, BoxFuture};
+use futures::{Future, FutureExt};
+use poem::http::StatusCode;
+use poem::web::Json;
+use poem::{Endpoint, IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::basic::result::TardisResult;
+
+/// Default timeout of each check / 每个检查的默认超时时间
+pub const HEALTH_DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+type HealthCheckFn = Arc<dyn Fn() -> BoxFuture<'static, TardisResult<()>> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// Status of a component / 组件的状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    pub elapsed_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Health report, the body of the probes / 健康报告，探针的响应体
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+impl IntoResponse for HealthReport {
+    fn into_response(self) -> Response {
+        let status = match self.status {
+            HealthStatus::Up => StatusCode::OK,
+            HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        Json(self).with_status(status).into_response()
+    }
+}
+
+/// Health checker / 健康检查器
+#[derive(Clone)]
+pub struct TardisHealth {
+    components: bool,
+    liveness_checks: Vec<(String, HealthCheckFn)>,
+    readiness_checks: Vec<(String, HealthCheckFn)>,
+    timeout: Duration,
+    timeouts: HashMap<String, Duration>,
+}
+
+impl Default for TardisHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TardisHealth {
+    /// Check the initialized components on the readiness / 就绪检查时检查已初始化的组件
+    pub fn new() -> Self {
+        TardisHealth {
+            components: true,
+            liveness_checks: Vec::new(),
+            readiness_checks: Vec::new(),
+            timeout: HEALTH_DEFAULT_TIMEOUT,
+            timeouts: HashMap::new(),
+        }
+    }
+
+    /// Don't check the initialized components / 不检查已初始化的组件
+    pub fn without_components(mut self) -> Self {
+        self.components = false;
+        self
+    }
+
+    /// Default timeout of each check / 每个检查的默认超时时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Timeout of the check, `name` is the name of the check or the kind of the components like `cache` and `reldb`
+    /// / 检查的超时时间， `name` 为检查名称或 `cache` 、 `reldb` 等组件类型
+    pub fn component_timeout(mut self, name: impl Into<String>, timeout: Duration) -> Self {
+        self.timeouts.insert(name.into(), timeout);
+        self
+    }
+
+    /// Add a check of both the liveness and the readiness, it shouldn't depend on the external services
+    /// / 添加存活及就绪检查，该检查不应依赖外部服务
+    pub fn liveness_check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.liveness_checks.push((name.into(), Arc::new(move || check().boxed())));
+        self
+    }
+
+    /// Add a check of the readiness / 添加就绪检查
+    pub fn readiness_check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        self.readiness_checks.push((name.into(), Arc::new(move || check().boxed())));
+        self
+    }
+
+    pub async fn liveness(&self) -> HealthReport {
+        self.run(self.liveness_checks.iter().map(|(name, check)| (name.clone(), check()))).await
+    }
+
+    pub async fn readiness(&self) -> HealthReport {
+        let mut checks = self.liveness_checks.iter().chain(self.readiness_checks.iter()).map(|(name, check)| (name.clone(), check())).collect::<Vec<_>>();
+        if self.components {
+            checks.extend(component_checks());
+        }
+        self.run(checks).await
+    }
+
+    async fn run(&self, checks: impl IntoIterator<Item = (String, BoxFuture<'static, TardisResult<()>>)>) -> HealthReport {
+        let components = join_all(checks.into_iter().map(|(name, check)| {
+            let timeout = self.timeout_of(&name);
+            async move {
+                let start = Instant::now();
+                let result = tokio::time::timeout(timeout, check).await;
+                let error = match result {
+                    Ok(Ok(())) => None,
+                    Ok(Err(error)) => Some(error.to_string()),
+                    Err(_) => Some(format!("timeout after {}ms", timeout.as_millis())),
+                };
+                let health = ComponentHealth {
+                    status: if error.is_none() { HealthStatus::Up } else { HealthStatus::Down },
+                    elapsed_ms: start.elapsed().as_millis() as u64,
+                    error,
+                };
+                (name, health)
+            }
+        }))
+        .await
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+        let status = if components.values().all(|component| component.status == HealthStatus::Up) {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        };
+        HealthReport { status, components }
+    }
+
+    fn timeout_of(&self, name: &str) -> Duration {
+        self.timeouts.get(name).or_else(|| name.split_once('.').and_then(|(kind, _)| self.timeouts.get(kind))).copied().unwrap_or(self.timeout)
+    }
+
+    /// Liveness probe endpoint / 存活探针端点
+    pub fn liveness_endpoint(&self) -> impl Endpoint<Output = HealthReport> {
+        let health = self.clone();
+        poem::endpoint::make(move |_| {
+            let health = health.clone();
+            async move { health.liveness().await }
+        })
+    }
+
+    /// Readiness probe endpoint / 就绪探针端点
+    pub fn readiness_endpoint(&self) -> impl Endpoint<Output = HealthReport> {
+        let health = self.clone();
+        poem::endpoint::make(move |_| {
+            let health = health.clone();
+            async move { health.readiness().await }
+        })
+    }
+}
+
+/// The components are named by their kind, with the module code if it's not the default one, e.g. `cache` and `reldb.order`
+fn component_name(kind: &str, code: &str) -> String {
+    if code.is_empty() {
+        kind.to_string()
+    } else {
+        format!("{kind}.{code}")
+    }
+}
+
+fn component_checks() -> Vec<(String, BoxFuture<'static, TardisResult<()>>)> {
+    #[allow(unused_mut)]
+    let mut checks: Vec<(String, BoxFuture<'static, TardisResult<()>>)> = Vec::new();
+    #[cfg(feature = "reldb-core")]
+    for (code, client) in crate::TARDIS_INST.reldb.read().iter() {
+        let client = client.clone();
+        checks.push((component_name("reldb", code), async move { client.health_check().await }.boxed()));
+    }
+    #[cfg(feature = "cache")]
+    for (code, client) in crate::TARDIS_INST.cache.read().iter() {
+        let client = client.clone();
+        checks.push((component_name("cache", code), async move { client.health_check().await }.boxed()));
+    }
+    #[cfg(feature = "mq")]
+    for (code, client) in crate::TARDIS_INST.mq.read().iter() {
+        let client = client.clone();
+        checks.push((component_name("mq", code), async move { client.health_check().await }.boxed()));
+    }
+    #[cfg(feature = "web-client")]
+    for (code, client) in crate::TARDIS_INST.search.read().iter() {
+        let client = client.clone();
+        checks.push((component_name("search", code), async move { client.health_check().await }.boxed()));
+    }
+    #[cfg(feature = "os")]
+    for (code, client) in crate::TARDIS_INST.os.read().iter() {
+        let client = client.clone();
+        checks.push((component_name("os", code), async move { client.health_check().await }.boxed()));
+    }
+    checks
+}
@@ -1,6 +1,10 @@
+use std::sync::RwLock;
+
 use crate::basic::error::TardisError;
-use crate::basic::result::{TARDIS_RESULT_ACCEPTED_CODE, TARDIS_RESULT_SUCCESS_CODE};
+use crate::basic::locale::TardisLocale;
+use crate::basic::result::{TardisResult, TARDIS_RESULT_ACCEPTED_CODE, TARDIS_RESULT_SUCCESS_CODE};
 use crate::serde::{Deserialize, Serialize};
+use crate::tardis_static;
 use crate::TardisFuns;
 use poem::http::StatusCode;
 use poem_openapi::payload::Json;
@@ -13,48 +17,80 @@ const TARDIS_ERROR_FLAG: &str = "__TARDIS_ERROR__";
 
 pub type TardisApiResult<T> = poem::Result<Json<TardisResp<T>>>;
 
+tardis_static! {
+    error_mappings: RwLock<Vec<(String, TardisErrorMapping)>>;
+}
+
+/// Mapping of the error code to the http status and the locale key / 错误码到http状态码及多语言key的映射
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TardisErrorMapping {
+    pub http_status: StatusCode,
+    /// The key of the localized message, the message isn't localized if it's empty / 多语言消息的key，为空时不进行本地化
+    pub locale_key: String,
+}
+
+/// Registry of the error mappings used when the web server converts [`TardisError`] into responses
+/// / web服务将 [`TardisError`] 转换为响应时使用的错误映射注册表
+///
+/// The mapping with the longest matched code prefix wins, the codes not registered fall back to the http status by the first three digits.
+/// The message is localized by the locale key and the `Accept-Language` of the request (or the default language of the application).
+///
+/// 匹配最长错误码前缀的映射生效，未注册的错误码按前三位数字取http状态码.
+/// 消息按多语言key及请求的 `Accept-Language` （或应用默认语言）进行本地化.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::web::web_resp::TardisErrorRegistry;
+/// TardisErrorRegistry::register("404-todo", 410, "todo-gone")?;
+/// ```
+pub struct TardisErrorRegistry;
+
+impl TardisErrorRegistry {
+    /// Register a mapping, the mapping of the same prefix is replaced / 注册映射，相同前缀的映射会被替换
+    pub fn register(code_prefix: &str, http_status: u16, locale_key: &str) -> TardisResult<()> {
+        let http_status = StatusCode::from_u16(http_status)
+            .map_err(|_| TardisError::format_error(&format!("[Tardis.WebServer] Invalid http status {http_status}"), "406-tardis-webserver-status-invalid"))?;
+        let mut mappings = error_mappings().write()?;
+        mappings.retain(|(prefix, _)| prefix != code_prefix);
+        mappings.push((
+            code_prefix.to_string(),
+            TardisErrorMapping {
+                http_status,
+                locale_key: locale_key.to_string(),
+            },
+        ));
+        Ok(())
+    }
+
+    /// Remove the mapping of the prefix / 移除前缀的映射
+    pub fn unregister(code_prefix: &str) {
+        if let Ok(mut mappings) = error_mappings().write() {
+            mappings.retain(|(prefix, _)| prefix != code_prefix);
+        }
+    }
+
+    /// The mapping with the longest matched prefix of the code / 匹配错误码最长前缀的映射
+    pub fn get(code: &str) -> Option<TardisErrorMapping> {
+        let mappings = error_mappings().read().ok()?;
+        mappings.iter().filter(|(prefix, _)| code.starts_with(prefix.as_str())).max_by_key(|(prefix, _)| prefix.len()).map(|(_, mapping)| mapping.clone())
+    }
+
+    /// Localize the message of the error code, the message is returned as it is if no locale key is registered
+    /// / 本地化错误码的消息，未注册多语言key时原样返回
+    pub fn localized_message(code: &str, message: &str, lang: Option<&str>) -> String {
+        let Some(mapping) = Self::get(code).filter(|mapping| !mapping.locale_key.is_empty()) else {
+            return message.to_string();
+        };
+        match lang.map(str::to_string).or_else(TardisFuns::default_lang) {
+            Some(lang) => TardisLocale::get_message(&mapping.locale_key, message, &lang).unwrap_or_else(|_| message.to_string()),
+            None => message.to_string(),
+        }
+    }
+}
+
 impl From<TardisError> for poem::Error {
     fn from(error: TardisError) -> Self {
-        let status_code = match &error.code {
-            c if c.starts_with("400") => StatusCode::BAD_REQUEST,
-            c if c.starts_with("401") => StatusCode::UNAUTHORIZED,
-            c if c.starts_with("403") => StatusCode::FORBIDDEN,
-            c if c.starts_with("404") => StatusCode::NOT_FOUND,
-            c if c.starts_with("405") => StatusCode::METHOD_NOT_ALLOWED,
-            c if c.starts_with("406") => StatusCode::NOT_ACCEPTABLE,
-            c if c.starts_with("408") => StatusCode::REQUEST_TIMEOUT,
-            c if c.starts_with("409") => StatusCode::CONFLICT,
-            c if c.starts_with("410") => StatusCode::GONE,
-            c if c.starts_with("411") => StatusCode::LENGTH_REQUIRED,
-            c if c.starts_with("412") => StatusCode::PRECONDITION_FAILED,
-            c if c.starts_with("413") => StatusCode::PAYLOAD_TOO_LARGE,
-            c if c.starts_with("414") => StatusCode::URI_TOO_LONG,
-            c if c.starts_with("415") => StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            c if c.starts_with("416") => StatusCode::RANGE_NOT_SATISFIABLE,
-            c if c.starts_with("417") => StatusCode::EXPECTATION_FAILED,
-            c if c.starts_with("418") => StatusCode::IM_A_TEAPOT,
-            c if c.starts_with("421") => StatusCode::MISDIRECTED_REQUEST,
-            c if c.starts_with("422") => StatusCode::UNPROCESSABLE_ENTITY,
-            c if c.starts_with("423") => StatusCode::LOCKED,
-            c if c.starts_with("424") => StatusCode::FAILED_DEPENDENCY,
-            c if c.starts_with("426") => StatusCode::UPGRADE_REQUIRED,
-            c if c.starts_with("428") => StatusCode::PRECONDITION_REQUIRED,
-            c if c.starts_with("429") => StatusCode::TOO_MANY_REQUESTS,
-            c if c.starts_with("431") => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
-            c if c.starts_with("451") => StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
-            c if c.starts_with("500") => StatusCode::INTERNAL_SERVER_ERROR,
-            c if c.starts_with("501") => StatusCode::NOT_IMPLEMENTED,
-            c if c.starts_with("502") => StatusCode::BAD_GATEWAY,
-            c if c.starts_with("503") => StatusCode::SERVICE_UNAVAILABLE,
-            c if c.starts_with("504") => StatusCode::GATEWAY_TIMEOUT,
-            c if c.starts_with("505") => StatusCode::HTTP_VERSION_NOT_SUPPORTED,
-            c if c.starts_with("506") => StatusCode::VARIANT_ALSO_NEGOTIATES,
-            c if c.starts_with("507") => StatusCode::INSUFFICIENT_STORAGE,
-            c if c.starts_with("508") => StatusCode::LOOP_DETECTED,
-            c if c.starts_with("510") => StatusCode::NOT_EXTENDED,
-            c if c.starts_with("511") => StatusCode::NETWORK_AUTHENTICATION_REQUIRED,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        };
+        let status_code = TardisErrorRegistry::get(&error.code).map(|mapping| mapping.http_status).unwrap_or_else(|| default_status_code(&error.code));
         poem::Error::from_string(
             format!("{}{}", TARDIS_ERROR_FLAG, TardisFuns::json.obj_to_string(&error).unwrap_or_else(|_| "".to_string())),
             status_code,
@@ -62,6 +98,50 @@ impl From<TardisError> for poem::Error {
     }
 }
 
+/// The http status by the first three digits of the code / 按错误码前三位数字取http状态码
+fn default_status_code(code: &str) -> StatusCode {
+    match code {
+        c if c.starts_with("400") => StatusCode::BAD_REQUEST,
+        c if c.starts_with("401") => StatusCode::UNAUTHORIZED,
+        c if c.starts_with("403") => StatusCode::FORBIDDEN,
+        c if c.starts_with("404") => StatusCode::NOT_FOUND,
+        c if c.starts_with("405") => StatusCode::METHOD_NOT_ALLOWED,
+        c if c.starts_with("406") => StatusCode::NOT_ACCEPTABLE,
+        c if c.starts_with("408") => StatusCode::REQUEST_TIMEOUT,
+        c if c.starts_with("409") => StatusCode::CONFLICT,
+        c if c.starts_with("410") => StatusCode::GONE,
+        c if c.starts_with("411") => StatusCode::LENGTH_REQUIRED,
+        c if c.starts_with("412") => StatusCode::PRECONDITION_FAILED,
+        c if c.starts_with("413") => StatusCode::PAYLOAD_TOO_LARGE,
+        c if c.starts_with("414") => StatusCode::URI_TOO_LONG,
+        c if c.starts_with("415") => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        c if c.starts_with("416") => StatusCode::RANGE_NOT_SATISFIABLE,
+        c if c.starts_with("417") => StatusCode::EXPECTATION_FAILED,
+        c if c.starts_with("418") => StatusCode::IM_A_TEAPOT,
+        c if c.starts_with("421") => StatusCode::MISDIRECTED_REQUEST,
+        c if c.starts_with("422") => StatusCode::UNPROCESSABLE_ENTITY,
+        c if c.starts_with("423") => StatusCode::LOCKED,
+        c if c.starts_with("424") => StatusCode::FAILED_DEPENDENCY,
+        c if c.starts_with("426") => StatusCode::UPGRADE_REQUIRED,
+        c if c.starts_with("428") => StatusCode::PRECONDITION_REQUIRED,
+        c if c.starts_with("429") => StatusCode::TOO_MANY_REQUESTS,
+        c if c.starts_with("431") => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+        c if c.starts_with("451") => StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+        c if c.starts_with("500") => StatusCode::INTERNAL_SERVER_ERROR,
+        c if c.starts_with("501") => StatusCode::NOT_IMPLEMENTED,
+        c if c.starts_with("502") => StatusCode::BAD_GATEWAY,
+        c if c.starts_with("503") => StatusCode::SERVICE_UNAVAILABLE,
+        c if c.starts_with("504") => StatusCode::GATEWAY_TIMEOUT,
+        c if c.starts_with("505") => StatusCode::HTTP_VERSION_NOT_SUPPORTED,
+        c if c.starts_with("506") => StatusCode::VARIANT_ALSO_NEGOTIATES,
+        c if c.starts_with("507") => StatusCode::INSUFFICIENT_STORAGE,
+        c if c.starts_with("508") => StatusCode::LOOP_DETECTED,
+        c if c.starts_with("510") => StatusCode::NOT_EXTENDED,
+        c if c.starts_with("511") => StatusCode::NETWORK_AUTHENTICATION_REQUIRED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 pub fn mapping_http_code_to_error(http_code: StatusCode, msg: &str) -> Option<TardisError> {
     if msg.starts_with(TARDIS_ERROR_FLAG) {
         let msg = msg.split_at(TARDIS_ERROR_FLAG.len()).1.to_string();
@@ -131,6 +211,96 @@ where
     pub records: Vec<T>,
 }
 
+impl<T> TardisPage<T>
+where
+    T: ParseFromJSON + ToJSON + Serialize + Send + Sync,
+{
+    /// Build a page from the records of current page and the total size / 通过当前页记录及总记录数构造分页
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let (records, total_size) = TardisFuns::reldb().conn().paginate_dtos(&query, page_number, page_size).await?;
+    /// let page = TardisPage::from_total(records, total_size, page_number, page_size);
+    /// ```
+    pub fn from_total(records: Vec<T>, total_size: u64, page_number: u64, page_size: u64) -> Self {
+        TardisPage {
+            page_size,
+            page_number,
+            total_size,
+            records,
+        }
+    }
+
+    /// Build an empty page / 构造空分页
+    pub fn empty(page_number: u64, page_size: u64) -> Self {
+        Self::from_total(Vec::new(), 0, page_number, page_size)
+    }
+
+    /// Total number of pages / 总页数
+    pub fn total_pages(&self) -> u64 {
+        if self.page_size == 0 {
+            0
+        } else {
+            self.total_size / self.page_size + u64::from(self.total_size % self.page_size != 0)
+        }
+    }
+
+    /// Convert each record, keeping the paging information / 转换每条记录，保留分页信息
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let page: TardisPage<TodoDetailResp> = entity_page.map(TodoDetailResp::from);
+    /// ```
+    pub fn map<U, F>(self, f: F) -> TardisPage<U>
+    where
+        U: ParseFromJSON + ToJSON + Serialize + Send + Sync,
+        F: FnMut(T) -> U,
+    {
+        TardisPage {
+            page_size: self.page_size,
+            page_number: self.page_number,
+            total_size: self.total_size,
+            records: self.records.into_iter().map(f).collect(),
+        }
+    }
+
+    /// Convert each record with a fallible function, keeping the paging information / 使用可能失败的函数转换每条记录，保留分页信息
+    ///
+    /// Returns the first error encountered.
+    ///
+    /// 返回遇到的第一个错误.
+    pub fn try_map<U, E, F>(self, f: F) -> Result<TardisPage<U>, E>
+    where
+        U: ParseFromJSON + ToJSON + Serialize + Send + Sync,
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(TardisPage {
+            page_size: self.page_size,
+            page_number: self.page_number,
+            total_size: self.total_size,
+            records: self.records.into_iter().map(f).collect::<Result<Vec<_>, E>>()?,
+        })
+    }
+
+    /// Build a page by fetching the specified page from a sea-orm paginator / 从sea-orm分页器中获取指定页并构造分页
+    ///
+    /// # Arguments
+    ///
+    ///  * `paginator` - sea-orm paginator, E.g. `Entity::find().into_model::<Dto>().paginate(db, page_size)` / sea-orm分页器
+    ///  * `page_number` - Current page number, starting from 1 / 当前页码，从1开始
+    ///  * `page_size` - Number of records per page, must be the same as the paginator / 每页记录数，需与分页器一致
+    #[cfg(feature = "reldb-core")]
+    pub async fn from_paginator<'db, C, S>(paginator: sea_orm::Paginator<'db, C, S>, page_number: u64, page_size: u64) -> crate::basic::result::TardisResult<Self>
+    where
+        C: sea_orm::ConnectionTrait,
+        S: sea_orm::SelectorTrait<Item = T> + 'db,
+    {
+        let total_size = paginator.num_items().await?;
+        let records = paginator.fetch_page(page_number.saturating_sub(1)).await?;
+        Ok(Self::from_total(records, total_size, page_number, page_size))
+    }
+}
+
 #[derive(Object, Serialize, Clone, Debug, Default, Copy)]
 /// This `Void` is for represent an empty value.
 /// Any value can be deserialized as `Void`.
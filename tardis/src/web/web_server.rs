@@ -1,14 +1,17 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use futures_util::future::BoxFuture;
 use futures_util::lock::Mutex;
 use poem::endpoint::BoxEndpoint;
 use poem::listener::{Listener, RustlsCertificate, RustlsConfig, TcpListener};
 use poem::middleware::Cors;
 use poem::{EndpointExt, Middleware, Route};
-use poem_openapi::{ExtraHeader, OpenApi, OpenApiService, ServerObject};
+use poem_openapi::OpenApi;
 
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
@@ -23,17 +26,25 @@ use crate::config::config_dto::{
     FrameworkConfig,
 };
 use crate::utils::initializer::InitBy;
+use crate::web::context_scope_mw::ContextScope;
+use crate::web::trace_context_mw::TraceContext;
 use crate::web::uniform_error_mw::UniformError;
 mod initializer;
 use initializer::*;
+mod log_level_api;
+pub use log_level_api::*;
 mod module;
 pub use module::*;
+mod openapi;
+pub use openapi::*;
 
 pub type BoxMiddleware<'a, T = BoxEndpoint<'a>> = Box<dyn Middleware<T, Output = T> + Send>;
 type ServerTaskInner = JoinHandle<TardisResult<()>>;
 struct ServerTask {
     pub(self) inner: ServerTaskInner,
     shutdown_trigger: oneshot::Sender<()>,
+    // the hooks are skipped when the server is restarted
+    run_hooks: Arc<AtomicBool>,
 }
 
 /// Server status hold by `TardisWebServer`
@@ -65,6 +76,8 @@ impl ServerState {
     {
         match self {
             ServerState::Halted(server_route) => {
+                #[cfg(feature = "metrics")]
+                let route = route.with(crate::web::metrics_mw::HttpMetrics::new(code));
                 // Solved:  Cannot move out of *** which is behind a mutable reference
                 // https://stackoverflow.com/questions/63353762/cannot-move-out-of-which-is-behind-a-mutable-reference
                 let mut swap_route = Route::default();
@@ -133,6 +146,46 @@ pub struct TardisWebServer {
     /// use `load_initializer` or `load_boxed_initializer` instead
     pub(self) initializers: Mutex<Vec<Box<dyn WebServerInitializer + Send + Sync>>>,
     state: Mutex<ServerState>,
+    /// Routes of the gRPC modules served on the separate `grpc_port`
+    grpc_state: Mutex<ServerState>,
+    /// ``OpenAPI`` spec documents of the added modules, @see [`export_openapi`](Self::export_openapi)
+    openapi_specs: Mutex<HashMap<String, serde_json::Value>>,
+    shutdown_hooks: ShutdownHooks,
+}
+
+type ShutdownHook = Arc<dyn Fn() -> BoxFuture<'static, TardisResult<()>> + Send + Sync>;
+
+/// Hooks run after the in-flight requests are drained / 在途请求排空后执行的钩子
+#[derive(Clone, Default)]
+struct ShutdownHooks(Arc<std::sync::Mutex<Vec<(String, ShutdownHook)>>>);
+
+impl Debug for ShutdownHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.0.lock().map(|hooks| hooks.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>()).unwrap_or_default();
+        f.debug_tuple("ShutdownHooks").field(&names).finish()
+    }
+}
+
+impl ShutdownHooks {
+    /// Move the hooks to another server / 将钩子移交给另一个服务
+    fn move_to(&self, target: &ShutdownHooks) {
+        let hooks = self.0.lock().map(|mut hooks| std::mem::take(&mut *hooks)).unwrap_or_default();
+        if let Ok(mut target) = target.0.lock() {
+            target.extend(hooks);
+        }
+    }
+
+    async fn run(&self, timeout: Duration) {
+        let hooks = self.0.lock().map(|hooks| hooks.clone()).unwrap_or_default();
+        for (name, hook) in hooks {
+            debug!("[Tardis.WebServer] Run shutdown hook {name}");
+            match tokio::time::timeout(timeout, hook()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => error!("[Tardis.WebServer] Shutdown hook {name} error: {error}"),
+                Err(_) => error!("[Tardis.WebServer] Shutdown hook {name} timeout"),
+            }
+        }
+    }
 }
 
 impl Default for TardisWebServer {
@@ -142,7 +195,10 @@ impl Default for TardisWebServer {
             version: "".to_string(),
             config: WebServerConfig::default(),
             state: Mutex::new(ServerState::default()),
+            grpc_state: Mutex::new(ServerState::default()),
+            openapi_specs: Mutex::new(HashMap::new()),
             initializers: Mutex::new(Vec::new()),
+            shutdown_hooks: ShutdownHooks::default(),
         }
     }
 }
@@ -156,7 +212,10 @@ impl InitBy<FrameworkConfig> for TardisWebServer {
             version: conf.app.version.clone(),
             config: conf.web_server.clone().expect("missing web server config"),
             state: Mutex::new(ServerState::Halted(route)),
+            grpc_state: Mutex::new(ServerState::default()),
+            openapi_specs: Mutex::new(HashMap::new()),
             initializers: Mutex::new(Vec::new()),
+            shutdown_hooks: ShutdownHooks::default(),
         })
     }
 }
@@ -168,7 +227,10 @@ impl TardisWebServer {
             version: conf.app.version.clone(),
             config: conf.web_server.clone().expect("missing web server config"),
             state: Mutex::new(ServerState::Halted(route)),
+            grpc_state: Mutex::new(ServerState::default()),
+            openapi_specs: Mutex::new(HashMap::new()),
             initializers: Mutex::new(Vec::new()),
+            shutdown_hooks: ShutdownHooks::default(),
         })
     }
 
@@ -179,7 +241,10 @@ impl TardisWebServer {
             version: "".to_string(),
             config: WebServerConfig::builder().common(WebServerCommonConfig::builder().host(host).port(port).build()).default(WebServerModuleConfig::builder().build()).build(),
             state: Mutex::new(ServerState::Halted(route)),
+            grpc_state: Mutex::new(ServerState::default()),
+            openapi_specs: Mutex::new(HashMap::new()),
             initializers: Mutex::new(Vec::new()),
+            shutdown_hooks: ShutdownHooks::default(),
         })
     }
 
@@ -256,6 +321,31 @@ impl TardisWebServer {
         self
     }
 
+    /// Add a GraphQL module / 添加GraphQL模块
+    ///
+    /// # Usage
+    /// ```ignore
+    /// webserver.add_graphql_module("graphql", Schema::new(Query, EmptyMutation, EmptySubscription)).await;
+    /// // add with middleware and data
+    /// webserver.add_graphql_module("graphql", WebServerGraphqlModule::from(schema).middleware(middleware).data(data)).await;
+    /// ```
+    ///
+    /// @see [graphql](crate::web::graphql)
+    #[cfg(feature = "web-server-graphql")]
+    pub async fn add_graphql_module<Q, M, S, MW, D>(&self, code: &str, module: impl Into<WebServerGraphqlModule<Q, M, S, MW, D>>) -> &Self
+    where
+        Q: async_graphql::ObjectType + 'static,
+        M: async_graphql::ObjectType + 'static,
+        S: async_graphql::SubscriptionType + 'static,
+        D: Clone + Send + Sync + 'static,
+        MW: Clone + Send + Sync + Middleware<BoxEndpoint<'static>> + 'static,
+    {
+        let code = code.to_lowercase();
+        let code = code.as_str();
+        self.load_initializer((code.to_string(), module.into())).await;
+        self
+    }
+
     #[allow(unused_variables, unused_mut)]
     async fn do_add_module_with_data<T, MW, D>(&self, code: &str, module_config: &WebServerModuleConfig, module: WebServerModule<T, MW, D>) -> &Self
     where
@@ -269,15 +359,9 @@ impl TardisWebServer {
             data,
             middleware,
             options: module_options,
+            middlewares,
         } = module;
-        let mut api_serv = OpenApiService::new(apis, &module_config.name, &module_config.version);
-        for (env, url) in &module_config.doc_urls {
-            let url = if !url.ends_with('/') { format!("{url}/{code}") } else { format!("{url}{code}") };
-            api_serv = api_serv.server(ServerObject::new(url).description(env));
-        }
-        for (name, desc) in &module_config.req_headers {
-            api_serv = api_serv.extra_request_header::<String, _>(ExtraHeader::new(name).description(desc));
-        }
+        let api_serv = openapi_service(apis, code, module_config);
         let mut route = Route::new();
         if let Some(ui_path) = &module_config.ui_path {
             #[allow(unused_assignments)]
@@ -303,19 +387,28 @@ impl TardisWebServer {
                 }
             }
         }
-        let spec_serv: String = api_serv.spec_yaml();
-        if let Some(spec_path) = &module_config.spec_path {
-            route = route.at(format!("/{spec_path}"), poem::endpoint::make_sync(move |_| spec_serv.clone()));
+        match openapi_spec(&api_serv, module_config) {
+            Ok(spec) => {
+                if let Some(spec_path) = &module_config.spec_path {
+                    match OpenApiFormat::from_path(spec_path).render(&spec) {
+                        Ok(spec_serv) => route = route.at(format!("/{spec_path}"), poem::endpoint::make_sync(move |_| spec_serv.clone())),
+                        Err(error) => error!("[Tardis.WebServer] Module {code} OpenAPI spec error: {error}"),
+                    }
+                }
+                self.openapi_specs.lock().await.insert(code.to_string(), spec);
+            }
+            Err(error) => error!("[Tardis.WebServer] Module {code} OpenAPI spec error: {error}"),
         }
         route = route.nest("/", api_serv);
-        let cors = if &self.config.allowed_origin == "*" {
+        let allowed_origin = module_config.allowed_origin.as_ref().unwrap_or(&self.config.allowed_origin);
+        let cors = if allowed_origin == "*" {
             // https://github.com/poem-web/poem/issues/161
             Cors::new()
         } else {
-            Cors::new().allow_origin(&self.config.allowed_origin)
+            Cors::new().allow_origin(allowed_origin)
         };
-        let route = route.boxed();
-        let route = route.with(middleware);
+        let route = apply_middlewares(middlewares, route.boxed());
+        let route = route.with(middleware).with(ContextScope).with(TraceContext);
         if module_options.uniform_error || module_config.uniform_error {
             self.state.lock().await.add_route(code, route.with(UniformError).with(cors), data);
         } else {
@@ -349,8 +442,46 @@ impl TardisWebServer {
         }
         route = route.add_service(reflection.build());
         route = route.add_service(poem_grpc::health_service().0);
-        let route = route.with(poem::middleware::Tracing).boxed();
+        let route = route.with(ContextScope).with(TraceContext).boxed();
         let route = route.with(middleware);
+        if self.config.grpc_port.is_some() {
+            self.grpc_state.lock().await.add_route(code, route, data);
+        } else {
+            self.state.lock().await.add_route(code, route, data);
+        }
+        self
+    }
+
+    #[cfg(feature = "web-server-graphql")]
+    async fn do_add_graphql_module_with_data<Q, M, S, MW, D>(&self, code: &str, module_config: &WebServerModuleConfig, module: WebServerGraphqlModule<Q, M, S, MW, D>) -> &Self
+    where
+        Q: async_graphql::ObjectType + 'static,
+        M: async_graphql::ObjectType + 'static,
+        S: async_graphql::SubscriptionType + 'static,
+        D: Clone + Send + Sync + 'static,
+        MW: Middleware<BoxEndpoint<'static>> + 'static,
+    {
+        use crate::web::graphql::{graphiql_endpoint, TardisGraphql, TardisGraphqlSubscription};
+        info!("[Tardis.WebServer] Add graphql module {}", code);
+        let WebServerGraphqlModule { schema, data, middleware } = module;
+        let mut route = Route::new();
+        if let Some(ui_path) = &module_config.ui_path {
+            route = route.at(format!("/{ui_path}"), graphiql_endpoint(code));
+        }
+        if let Some(spec_path) = &module_config.spec_path {
+            let sdl = schema.sdl();
+            route = route.at(format!("/{spec_path}"), poem::endpoint::make_sync(move |_| sdl.clone()));
+        }
+        route = route.at("/ws", TardisGraphqlSubscription::new(schema.clone())).at("/", TardisGraphql::new(schema));
+        let allowed_origin = module_config.allowed_origin.as_ref().unwrap_or(&self.config.allowed_origin);
+        let cors = if allowed_origin == "*" {
+            // https://github.com/poem-web/poem/issues/161
+            Cors::new()
+        } else {
+            Cors::new().allow_origin(allowed_origin)
+        };
+        // the errors are returned in the GraphQL responses, so the `UniformError` middleware isn't used
+        let route = route.boxed().with(middleware).with(ContextScope).with(TraceContext).with(cors);
         self.state.lock().await.add_route(code, route, data);
         self
     }
@@ -358,10 +489,26 @@ impl TardisWebServer {
     /// # Warn
     /// Since `Route` didn't implement `Clone`, module create in this way cannot be reloaded while webserver restart
     pub async fn add_module_raw(&self, code: &str, route: Route) -> &Self {
-        self.state.lock().await.add_route(code, route, Option::<()>::None);
+        self.state.lock().await.add_route(code, route.with(ContextScope).with(TraceContext), Option::<()>::None);
         self
     }
 
+    /// Add the `/healthz` liveness probe and the `/readyz` readiness probe / 添加 `/healthz` 存活探针及 `/readyz` 就绪探针
+    ///
+    /// @see [TardisHealth](crate::web::web_health::TardisHealth)
+    pub async fn add_health_probes(&self, health: crate::web::web_health::TardisHealth) -> &Self {
+        self.add_module_raw("healthz", Route::new().at("/", health.liveness_endpoint())).await;
+        self.add_module_raw("readyz", Route::new().at("/", health.readiness_endpoint())).await
+    }
+
+    /// Add the `/metrics` endpoint exporting the metrics in the Prometheus text format / 添加以Prometheus文本格式导出监控指标的 `/metrics` 端点
+    ///
+    /// It should be protected by a middleware or served on an internal network. / 应使用中间件保护或仅在内部网络提供.
+    #[cfg(feature = "metrics")]
+    pub async fn add_metrics_endpoint(&self) -> &Self {
+        self.add_module_raw("metrics", Route::new().at("/", crate::web::metrics_mw::metrics_endpoint())).await
+    }
+
     /// # Start
     /// Start this webserver
     ///
@@ -393,76 +540,160 @@ impl TardisWebServer {
             return TardisResult::Ok(());
         };
 
+        let grpc_route = self.grpc_state.lock().await.take_route().unwrap_or_default();
+        let route = match self.config.grpc_port {
+            Some(grpc_port) => GrpcPortDispatch {
+                grpc_port,
+                http: route.boxed(),
+                grpc: grpc_route.boxed(),
+            }
+            .boxed(),
+            None => route.boxed(),
+        };
+
         let (tx, rx) = oneshot::channel::<()>();
+        let run_hooks = Arc::new(AtomicBool::new(true));
+        let drain_timeout = Duration::from_secs(self.config.shutdown_timeout_sec);
         let graceful_shutdown_signal = async move {
-            tokio::select! {
+            #[allow(unused_variables)]
+            let by_signal = tokio::select! {
                 _ = tokio::signal::ctrl_c() => {
                     debug!("[Tardis.WebServer] WebServer shutdown (Ctrl+C signal)");
+                    true
+                },
+                _ = terminate_signal() => {
+                    debug!("[Tardis.WebServer] WebServer shutdown (SIGTERM signal)");
+                    true
                 },
                 _ = rx => {
                     debug!("[Tardis.WebServer] WebServer shutdown (WebServer shutdown signal)");
+                    false
                 },
             };
+            // the shutdown triggered by `shutdown()` has deregistered already
+            #[cfg(feature = "discovery")]
+            if by_signal {
+                if let Some(discovery) = crate::TardisFuns::discovery_opt() {
+                    if let Err(error) = discovery.deregister().await {
+                        error!("[Tardis.WebServer] Deregister from the discovery error: {error}");
+                    }
+                }
+            }
+            info!("[Tardis.WebServer] Stop accepting new connections, draining the in-flight requests in {drain_timeout:?}");
         };
-        let boxed_server: ServerTaskInner = if self.config.tls_key.is_some() {
-            let bind = TcpListener::bind(format!("{}:{}", self.config.host, self.config.port)).rustls(
-                RustlsConfig::new().fallback(
-                    RustlsCertificate::new()
-                        .key(self.config.tls_key.clone().expect("[Tardis.WebServer] TLS key clone error"))
-                        .cert(self.config.tls_cert.clone().expect("[Tardis.WebServer] TLS cert clone error")),
-                ),
-            );
-            let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(Duration::from_secs(5)));
-            tokio::spawn(async {
-                server.await?;
-                info!("[Tardis.WebServer] Poem webserver shutdown finished");
-                Ok(())
-            })
-        } else {
-            let bind = TcpListener::bind(format!("{}:{}", self.config.host, self.config.port));
-            let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(Duration::from_secs(5)));
-            tokio::spawn(async {
-                server.await?;
-                info!("[Tardis.WebServer] Poem webserver shutdown finished");
-                Ok(())
-            })
-        };
+        let mut bind = TcpListener::bind(format!("{}:{}", self.config.host, self.config.port)).boxed();
+        if let Some(grpc_port) = self.config.grpc_port {
+            bind = bind.combine(TcpListener::bind(format!("{}:{}", self.config.host, grpc_port))).boxed();
+        }
+        if self.config.tls_key.is_some() {
+            bind = bind
+                .rustls(
+                    RustlsConfig::new().fallback(
+                        RustlsCertificate::new()
+                            .key(self.config.tls_key.clone().expect("[Tardis.WebServer] TLS key clone error"))
+                            .cert(self.config.tls_cert.clone().expect("[Tardis.WebServer] TLS cert clone error")),
+                    ),
+                )
+                .boxed();
+        }
+        let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(drain_timeout));
+        let boxed_server: ServerTaskInner = spawn_server(server, self.shutdown_hooks.clone(), drain_timeout, run_hooks.clone());
         let task = ServerTask {
             inner: boxed_server,
             shutdown_trigger: tx,
+            run_hooks,
         };
         *state_locked = ServerState::Running(task);
         drop(state_locked);
         info!("{}", output_info);
+        #[cfg(feature = "discovery")]
+        {
+            if let Some(discovery) = crate::TardisFuns::discovery_opt() {
+                // the heartbeat will retry to register
+                if let Err(error) = discovery.register_web_server(&self.config).await {
+                    error!("[Tardis.WebServer] Register to the discovery error: {error}");
+                }
+            }
+        }
         TardisResult::Ok(())
     }
 
+    /// Register a hook run after the in-flight requests are drained, e.g. closing the MQ consumers or flushing the cache
+    /// / 注册在途请求排空后执行的钩子，如关闭MQ消费者或刷新缓存
+    ///
+    /// The hooks are run in the registration order on every shutdown, including the ones triggered by `SIGTERM` / `SIGINT`,
+    /// each hook is limited by the `shutdown_timeout_sec` and its error is logged.
+    ///
+    /// 钩子在每次关闭（包括 `SIGTERM` / `SIGINT` 触发的关闭）时按注册顺序执行，每个钩子受 `shutdown_timeout_sec` 限制，其错误仅记录日志.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// TardisFuns::web_server().add_shutdown_hook("close-mq", || async { TardisFuns::mq().close().await });
+    /// ```
+    pub fn add_shutdown_hook<F, T>(&self, name: &str, hook: F) -> &Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: std::future::Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        if let Ok(mut hooks) = self.shutdown_hooks.0.lock() {
+            hooks.push((name.to_string(), Arc::new(move || Box::pin(hook()))));
+        }
+        self
+    }
+
     /// # Shutdown
     /// shutdown this webserver, if it's not running it will return `Ok(())` instantly
+    ///
+    /// It stops accepting new connections, waits for the in-flight requests up to `shutdown_timeout_sec`, then runs the shutdown hooks.
+    ///
+    /// 停止接受新连接，等待在途请求最多 `shutdown_timeout_sec` ，然后执行关闭钩子.
     pub async fn shutdown(&self) -> TardisResult<()> {
+        self.do_shutdown(true).await
+    }
+
+    /// Shutdown without running the hooks, the server is going to be restarted / 关闭但不执行钩子，服务即将重启
+    pub(crate) async fn shutdown_for_restart(&self) -> TardisResult<()> {
+        self.do_shutdown(false).await
+    }
+
+    async fn do_shutdown(&self, run_hooks: bool) -> TardisResult<()> {
         let mut state_locked = self.state.lock().await;
         let mut swap_state = ServerState::default();
         std::mem::swap(&mut *state_locked, &mut swap_state);
         drop(state_locked);
         if let ServerState::Running(task) = swap_state {
+            // deregister first to stop the new requests
+            #[cfg(feature = "discovery")]
+            {
+                if let Some(discovery) = crate::TardisFuns::discovery_opt() {
+                    if let Err(error) = discovery.deregister().await {
+                        error!("[Tardis.WebServer] Deregister from the discovery error: {error}");
+                    }
+                }
+            }
             info!("[Tardis.WebServer] Shutdown web server");
+            task.run_hooks.store(run_hooks, Ordering::SeqCst);
             let send_result = task.shutdown_trigger.send(());
             if send_result.is_err() {
                 warn!("[Tardis.WebServer] Trying to shutdown webserver which seems already closed")
             };
-            match tokio::time::timeout(Duration::from_secs(5), task.inner).await {
-                Ok(Ok(result)) => return result,
-                Ok(Err(e)) => {
-                    error!("[Tardis.WebServer] Fail to join webservert task: {e}")
-                }
+            // the task finishes after the requests are drained and the hooks are run, both are limited by the timeout
+            match task.inner.await {
+                Ok(result) => return result,
                 Err(e) => {
-                    error!("[Tardis.WebServer] Shutdown webserver timeout: {e}")
+                    error!("[Tardis.WebServer] Fail to join webservert task: {e}")
                 }
             }
         }
         Ok(())
     }
 
+    /// Take out the route of the added modules for the in-process test client, the modules are removed from the server
+    #[cfg(feature = "test")]
+    pub(crate) async fn take_route(&self) -> Option<Route> {
+        self.state.lock().await.take_route()
+    }
+
     /// return true if web server is running
     pub async fn is_running(&self) -> bool {
         let state = &*self.state.lock().await;
@@ -470,6 +701,55 @@ impl TardisWebServer {
     }
 }
 
+/// Dispatch the requests accepted by the `grpc_port` listener to the gRPC modules / 将 `grpc_port` 监听接收的请求分发给gRPC模块
+struct GrpcPortDispatch {
+    grpc_port: u16,
+    http: BoxEndpoint<'static>,
+    grpc: BoxEndpoint<'static>,
+}
+
+#[async_trait::async_trait]
+impl poem::Endpoint for GrpcPortDispatch {
+    type Output = poem::Response;
+
+    async fn call(&self, req: poem::Request) -> poem::Result<Self::Output> {
+        if req.local_addr().as_socket_addr().map(|addr| addr.port() == self.grpc_port).unwrap_or(false) {
+            self.grpc.call(req).await
+        } else {
+            self.http.call(req).await
+        }
+    }
+}
+
+fn spawn_server<F>(server: F, shutdown_hooks: ShutdownHooks, hook_timeout: Duration, run_hooks: Arc<AtomicBool>) -> ServerTaskInner
+where
+    F: std::future::Future<Output = std::io::Result<()>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let result = server.await;
+        info!("[Tardis.WebServer] Poem webserver shutdown finished");
+        if run_hooks.load(Ordering::SeqCst) {
+            shutdown_hooks.run(hook_timeout).await;
+        }
+        Ok(result?)
+    })
+}
+
+async fn terminate_signal() {
+    #[cfg(unix)]
+    match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+        Ok(mut signal) => {
+            signal.recv().await;
+        }
+        Err(error) => {
+            warn!("[Tardis.WebServer] Listen to SIGTERM error: {error}");
+            std::future::pending::<()>().await;
+        }
+    }
+    #[cfg(not(unix))]
+    std::future::pending::<()>().await;
+}
+
 /// this await will pending until server is closed
 impl std::future::Future for &TardisWebServer {
     type Output = ();
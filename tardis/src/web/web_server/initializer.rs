@@ -51,6 +51,7 @@ impl WebServerInitializer for TardisWebServer {
             i.init(target).await;
             target_initializers.push(i);
         }
+        self.shutdown_hooks.move_to(&target.shutdown_hooks);
     }
 }
 
@@ -65,6 +66,7 @@ impl WebServerInitializer for Arc<TardisWebServer> {
             i.init(target).await;
             target_initializers.push(i);
         }
+        self.shutdown_hooks.move_to(&target.shutdown_hooks);
     }
 }
 
@@ -99,6 +101,31 @@ where
     }
 }
 
+/*
+    GraphQL support
+*/
+/// a tuple of (Code, WebServerGraphqlModule) can be an initializer
+#[cfg(feature = "web-server-graphql")]
+#[async_trait::async_trait]
+impl<Q, M, S, MW, D> WebServerInitializer for (String, WebServerGraphqlModule<Q, M, S, MW, D>)
+where
+    Q: async_graphql::ObjectType + 'static,
+    M: async_graphql::ObjectType + 'static,
+    S: async_graphql::SubscriptionType + 'static,
+    D: Clone + Send + Sync + 'static,
+    MW: Clone + Middleware<BoxEndpoint<'static>> + Send + Sync + 'static,
+{
+    async fn init(&self, target: &TardisWebServer) {
+        let (code, ref module) = self;
+        if let Some(module_config) = target.config.modules.get(code) {
+            target.do_add_graphql_module_with_data(code, module_config, module.clone()).await;
+        } else {
+            crate::log::debug!("[Tardis.WebServer] Module {code} not found, using a default config.", code = code);
+            target.do_add_graphql_module_with_data(code, &WebServerModuleConfig::default(), module.clone()).await;
+        }
+    }
+}
+
 /*
     loader methods
 */
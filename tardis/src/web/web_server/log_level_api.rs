@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use poem_openapi::param::Query;
+use poem_openapi::payload::Json;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+use crate::web::web_resp::{TardisApiResult, TardisResp, Void};
+use crate::TardisFuns;
+
+/// Admin api to adjust log level at runtime / 运行时调整日志级别的管理接口
+///
+/// This api is not registered by default, and it should be protected by a middleware when registered.
+///
+/// 该接口默认不注册，注册时应使用中间件进行保护.
+///
+/// # Examples
+/// ```ignore
+/// TardisFuns::web_server().add_route((TardisLogLevelApi, AdminAuthMiddleware)).await;
+/// // curl -X PUT http://127.0.0.1:8080/tardis/log/level -H "Content-Type: application/json" \
+/// //   -d '{"target":"tardis","level":"trace","duration_sec":600}'
+/// // curl -X PUT http://127.0.0.1:8080/tardis/log/filter -H "Content-Type: application/json" \
+/// //   -d '{"filter":"info,tardis::cache=trace"}'
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TardisLogLevelApi;
+
+#[derive(Object, Serialize, Deserialize, Clone, Debug)]
+pub struct TardisLogLevelSetReq {
+    /// Log target, e.g. `tardis` or `sqlx::query`, empty means the global level
+    #[oai(default)]
+    pub target: String,
+    /// Log level, e.g. `trace` `debug` `info` `warn` `error` `off`
+    pub level: String,
+    /// Restore the previous level after the duration in seconds, keep the level if not specified
+    pub duration_sec: Option<u64>,
+}
+
+#[derive(Object, Serialize, Deserialize, Clone, Debug)]
+pub struct TardisLogFilterSetReq {
+    /// Log filter, e.g. `info,tardis::cache=trace`, replaces the global level and the directives
+    pub filter: String,
+}
+
+#[derive(Object, Serialize, Deserialize, Clone, Debug)]
+pub struct TardisLogLevelResp {
+    pub level: String,
+    pub directives: Vec<String>,
+}
+
+#[poem_openapi::OpenApi]
+impl TardisLogLevelApi {
+    /// Get current log levels
+    #[oai(path = "/tardis/log/level", method = "get")]
+    async fn get_level(&self) -> TardisApiResult<TardisLogLevelResp> {
+        let config = TardisFuns::tracing().config()?;
+        TardisResp::ok(TardisLogLevelResp {
+            level: config.level.to_string(),
+            directives: config.directives.iter().map(|d| d.to_string()).collect(),
+        })
+    }
+
+    /// Set log level
+    #[oai(path = "/tardis/log/level", method = "put")]
+    async fn set_level(&self, req: Json<TardisLogLevelSetReq>) -> TardisApiResult<Void> {
+        match req.duration_sec {
+            Some(duration_sec) => TardisFuns::tracing().set_level_for(&req.target, &req.level, Duration::from_secs(duration_sec))?,
+            None => TardisFuns::tracing().set_level(&req.target, &req.level)?,
+        }
+        TardisResp::ok(Void {})
+    }
+
+    /// Reload log filter
+    #[oai(path = "/tardis/log/filter", method = "put")]
+    async fn reload_filter(&self, req: Json<TardisLogFilterSetReq>) -> TardisApiResult<Void> {
+        TardisFuns::log_reload(&req.filter)?;
+        TardisResp::ok(Void {})
+    }
+
+    /// Reset log level of the target
+    #[oai(path = "/tardis/log/level", method = "delete")]
+    async fn reset_level(&self, target: Query<String>) -> TardisApiResult<Void> {
+        TardisFuns::tracing().reset_level(&target.0)?;
+        TardisResp::ok(Void {})
+    }
+}
@@ -1,7 +1,5 @@
-use poem::{endpoint::BoxEndpoint, Middleware};
+use poem::{endpoint::BoxEndpoint, EndpointExt, Middleware};
 use poem_openapi::OpenApi;
-#[allow(unused_imports)]
-#[doc = "for grpc"]
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
@@ -26,6 +24,30 @@ impl Default for WebServerModuleOption {
     }
 }
 
+/// Priority of the [`RequestId`](crate::web::request_id_mw::RequestId) middleware added by [`WebServerModule::with_request_id`]
+pub const MIDDLEWARE_PRIORITY_REQUEST_ID: i32 = 300;
+/// Priority of the [`AccessLog`](crate::web::access_log_mw::AccessLog) middleware added by [`WebServerModule::with_access_log`]
+pub const MIDDLEWARE_PRIORITY_ACCESS_LOG: i32 = 200;
+/// Priority of the compression middleware added by [`WebServerModule::with_compression`]
+pub const MIDDLEWARE_PRIORITY_COMPRESSION: i32 = 100;
+
+type BoxMiddlewareFn = Arc<dyn Fn(BoxEndpoint<'static>) -> BoxEndpoint<'static> + Send + Sync>;
+
+/// A middleware of the ordered chain / 有序链中的中间件
+#[derive(Clone)]
+pub(crate) struct OrderedMiddleware {
+    priority: i32,
+    transform: BoxMiddlewareFn,
+}
+
+/// Apply the middlewares, the ones with higher priority are outer, and the earlier added one is outer if the priorities are equal
+/// / 应用中间件，优先级高的在外层，优先级相同时先添加的在外层
+pub(crate) fn apply_middlewares(middlewares: Vec<OrderedMiddleware>, ep: BoxEndpoint<'static>) -> BoxEndpoint<'static> {
+    let mut middlewares = middlewares.into_iter().enumerate().collect::<Vec<_>>();
+    middlewares.sort_by(|(index_a, a), (index_b, b)| a.priority.cmp(&b.priority).then(index_b.cmp(index_a)));
+    middlewares.into_iter().fold(ep, |ep, (_, middleware)| (middleware.transform)(ep))
+}
+
 /// A module of web server
 #[derive(Clone)]
 pub struct WebServerModule<T, MW = EmptyMiddleWare, D = ()> {
@@ -37,6 +59,8 @@ pub struct WebServerModule<T, MW = EmptyMiddleWare, D = ()> {
     pub middleware: MW,
     /// Custom options for this module
     pub options: WebServerModuleOption,
+    /// Ordered middleware chain, @see [`with_middleware`](Self::with_middleware)
+    pub(crate) middlewares: Vec<OrderedMiddleware>,
 }
 
 impl<T, MW, D> Default for WebServerModule<T, MW, D>
@@ -51,6 +75,7 @@ where
             data: Default::default(),
             middleware: Default::default(),
             options: Default::default(),
+            middlewares: Vec::new(),
         }
     }
 }
@@ -91,6 +116,7 @@ impl<T> WebServerModule<T> {
             data: None,
             middleware: EmptyMiddleWare::INSTANCE,
             options: Default::default(),
+            middlewares: Vec::new(),
         }
     }
 }
@@ -106,6 +132,7 @@ impl<T, _MW, _D> WebServerModule<T, _MW, _D> {
             data: Some(broadcast::channel(capacity).0),
             options: self.options,
             middleware: self.middleware,
+            middlewares: self.middlewares,
         }
     }
 
@@ -116,6 +143,7 @@ impl<T, _MW, _D> WebServerModule<T, _MW, _D> {
             data: Some(data),
             options: self.options,
             middleware: self.middleware,
+            middlewares: self.middlewares,
         }
     }
 
@@ -126,6 +154,7 @@ impl<T, _MW, _D> WebServerModule<T, _MW, _D> {
             data: self.data,
             options: self.options,
             middleware,
+            middlewares: self.middlewares,
         }
     }
 
@@ -133,6 +162,51 @@ impl<T, _MW, _D> WebServerModule<T, _MW, _D> {
     pub fn options(self, options: WebServerModuleOption) -> Self {
         WebServerModule { options, ..self }
     }
+
+    /// Add a middleware to the ordered chain of this module / 向该模块的有序链添加中间件
+    ///
+    /// The middlewares with higher priority are outer, i.e. they process the requests earlier and the responses later,
+    /// the earlier added one is outer if the priorities are equal.
+    /// The chain is inside the [`middleware`](Self::middleware) of the module and the trace context, uniform error and CORS handling.
+    ///
+    /// 优先级高的中间件在外层，即更早处理请求、更晚处理响应，优先级相同时先添加的在外层.
+    /// 该链位于模块的 [`middleware`](Self::middleware) 及链路上下文、统一错误、CORS处理之内.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// WebServerModule::from(MyApi).with_request_id().with_access_log().with_middleware(500, AuthMiddleware);
+    /// ```
+    pub fn with_middleware<M>(mut self, priority: i32, middleware: M) -> Self
+    where
+        M: Middleware<BoxEndpoint<'static>> + Send + Sync + 'static,
+        M::Output: 'static,
+    {
+        self.middlewares.push(OrderedMiddleware {
+            priority,
+            transform: Arc::new(move |ep| middleware.transform(ep).map_to_response().boxed()),
+        });
+        self
+    }
+
+    /// Add the [`RequestId`](crate::web::request_id_mw::RequestId) middleware / 添加 [`RequestId`](crate::web::request_id_mw::RequestId) 中间件
+    pub fn with_request_id(self) -> Self {
+        self.with_middleware(MIDDLEWARE_PRIORITY_REQUEST_ID, crate::web::request_id_mw::RequestId::new())
+    }
+
+    /// Add the [`AccessLog`](crate::web::access_log_mw::AccessLog) middleware / 添加 [`AccessLog`](crate::web::access_log_mw::AccessLog) 中间件
+    pub fn with_access_log(self) -> Self {
+        self.with_middleware(MIDDLEWARE_PRIORITY_ACCESS_LOG, crate::web::access_log_mw::AccessLog)
+    }
+
+    /// Compress the responses by the `Accept-Encoding` of the requests / 根据请求的 `Accept-Encoding` 压缩响应
+    ///
+    /// This function needs to be enabled #[cfg(feature = "web-server-compression")] .
+    ///
+    /// 本函数需要启用 #[cfg(feature = "web-server-compression")] .
+    #[cfg(feature = "web-server-compression")]
+    pub fn with_compression(self) -> Self {
+        self.with_middleware(MIDDLEWARE_PRIORITY_COMPRESSION, poem::middleware::Compression::new())
+    }
 }
 
 /// A middleware will do nothing
@@ -225,3 +299,63 @@ where
         WebServerGrpcModule::default().with_grpc_service(api)
     }
 }
+
+/// A GraphQL module of web server / Web服务的GraphQL模块
+///
+/// The queries and mutations are served on the module path, the subscriptions on `{module path}/ws` and
+/// the GraphiQL page on `{module path}/{ui_path}` if the `ui_path` of the module config is set.
+///
+/// 查询及变更在模块路径上提供，订阅在 `{模块路径}/ws` 上提供，模块配置设置了 `ui_path` 时GraphiQL页面在 `{模块路径}/{ui_path}` 上提供.
+#[cfg(feature = "web-server-graphql")]
+pub struct WebServerGraphqlModule<Q, M, S, MW = EmptyMiddleWare, D = ()> {
+    /// The `async-graphql` schema
+    pub schema: async_graphql::Schema<Q, M, S>,
+    /// Shared data for this module
+    pub data: Option<D>,
+    /// Middleware for this module
+    pub middleware: MW,
+}
+
+// the schema is cloneable without the bounds of the derived implementation
+#[cfg(feature = "web-server-graphql")]
+impl<Q, M, S, MW: Clone, D: Clone> Clone for WebServerGraphqlModule<Q, M, S, MW, D> {
+    fn clone(&self) -> Self {
+        WebServerGraphqlModule {
+            schema: self.schema.clone(),
+            data: self.data.clone(),
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "web-server-graphql")]
+impl<Q, M, S> From<async_graphql::Schema<Q, M, S>> for WebServerGraphqlModule<Q, M, S> {
+    fn from(schema: async_graphql::Schema<Q, M, S>) -> Self {
+        WebServerGraphqlModule {
+            schema,
+            data: None,
+            middleware: EmptyMiddleWare::INSTANCE,
+        }
+    }
+}
+
+#[cfg(feature = "web-server-graphql")]
+impl<Q, M, S, _MW, _D> WebServerGraphqlModule<Q, M, S, _MW, _D> {
+    /// set the data for this module, this function will replace the previous data
+    pub fn data<D>(self, data: D) -> WebServerGraphqlModule<Q, M, S, _MW, D> {
+        WebServerGraphqlModule {
+            schema: self.schema,
+            data: Some(data),
+            middleware: self.middleware,
+        }
+    }
+
+    /// set the middleware for this module, this function will replace the previous middleware
+    pub fn middleware<MW>(self, middleware: MW) -> WebServerGraphqlModule<Q, M, S, MW, _D> {
+        WebServerGraphqlModule {
+            schema: self.schema,
+            data: self.data,
+            middleware,
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use poem_openapi::{ExtraHeader, OpenApi, OpenApiService, ServerObject};
+use serde_json::{json, Value};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::web_server::WebServerModuleConfig;
+
+use super::TardisWebServer;
+
+/// Format of the ``OpenAPI`` spec documents / ``OpenAPI`` 规范文档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenApiFormat {
+    Json,
+    Yaml,
+}
+
+impl OpenApiFormat {
+    /// The format by the extension of the path, YAML by default / 按路径扩展名确定格式，默认为YAML
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".json") {
+            OpenApiFormat::Json
+        } else {
+            OpenApiFormat::Yaml
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OpenApiFormat::Json => "json",
+            OpenApiFormat::Yaml => "yaml",
+        }
+    }
+
+    /// Render the spec document / 渲染规范文档
+    pub fn render(&self, spec: &Value) -> TardisResult<String> {
+        match self {
+            OpenApiFormat::Json => serde_json::to_string_pretty(spec).map_err(|error| {
+                TardisError::internal_error(
+                    &format!("[Tardis.WebServer] Render OpenAPI spec error: {error}"),
+                    "500-tardis-webserver-openapi-render-error",
+                )
+            }),
+            OpenApiFormat::Yaml => serde_yaml::to_string(spec).map_err(|error| {
+                TardisError::internal_error(
+                    &format!("[Tardis.WebServer] Render OpenAPI spec error: {error}"),
+                    "500-tardis-webserver-openapi-render-error",
+                )
+            }),
+        }
+    }
+}
+
+/// Create the ``OpenAPI`` service of the module by the metadata of the module config / 按模块配置的元数据创建模块的 ``OpenAPI`` 服务
+pub(crate) fn openapi_service<T: OpenApi>(apis: T, code: &str, module_config: &WebServerModuleConfig) -> OpenApiService<T, ()> {
+    let mut api_serv = OpenApiService::new(apis, &module_config.name, &module_config.version);
+    if let Some(description) = &module_config.description {
+        api_serv = api_serv.description(description);
+    }
+    for (env, url) in &module_config.doc_urls {
+        let url = if !url.ends_with('/') { format!("{url}/{code}") } else { format!("{url}{code}") };
+        api_serv = api_serv.server(ServerObject::new(url).description(env));
+    }
+    for (name, desc) in &module_config.req_headers {
+        api_serv = api_serv.extra_request_header::<String, _>(ExtraHeader::new(name).description(desc));
+    }
+    api_serv
+}
+
+/// Build the spec document with the security schemes of the module config / 构建带有模块配置安全方案的规范文档
+pub(crate) fn openapi_spec<T: OpenApi>(api_serv: &OpenApiService<T, ()>, module_config: &WebServerModuleConfig) -> TardisResult<Value> {
+    let mut spec: Value = serde_json::from_str(&api_serv.spec())
+        .map_err(|error| TardisError::internal_error(&format!("[Tardis.WebServer] Parse OpenAPI spec error: {error}"), "500-tardis-webserver-openapi-parse-error"))?;
+    if !module_config.security_schemes.is_empty() {
+        let mut names = module_config.security_schemes.keys().collect::<Vec<_>>();
+        names.sort();
+        let schemes = spec.as_object_mut().map(|spec| spec.entry("components").or_insert_with(|| json!({}))).and_then(|components| components.as_object_mut());
+        if let Some(components) = schemes {
+            let schemes = components.entry("securitySchemes").or_insert_with(|| json!({}));
+            for name in &names {
+                schemes[name.as_str()] = module_config.security_schemes[name.as_str()].clone();
+            }
+        }
+        spec["security"] = Value::Array(names.iter().map(|name| json!({ name.as_str(): [] })).collect());
+    }
+    Ok(spec)
+}
+
+impl TardisWebServer {
+    /// Export the ``OpenAPI`` spec documents of the added modules to the directory, e.g. for the contract checks in CI
+    /// / 将已添加模块的 ``OpenAPI`` 规范文档导出到目录，如用于CI中的契约检查
+    ///
+    /// Each document is written to ``{dir}/{module code}.{json|yaml}`` ( ``openapi`` for the module added by ``add_route`` ),
+    /// the server doesn't need to be started. Return the paths of the written documents.
+    ///
+    /// 每个文档写入 ``{dir}/{模块编码}.{json|yaml}`` （通过 ``add_route`` 添加的模块为 ``openapi`` ），服务无需启动. 返回写入的文档路径.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// TardisFuns::web_server().add_module("todo", TodoApi).await;
+    /// TardisFuns::web_server().export_openapi("target/openapi", OpenApiFormat::Json).await?;
+    /// ```
+    pub async fn export_openapi(&self, dir: impl AsRef<Path>, format: OpenApiFormat) -> TardisResult<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let specs = self.openapi_specs.lock().await.clone();
+        let mut codes = specs.keys().collect::<Vec<_>>();
+        codes.sort();
+        let mut paths = Vec::with_capacity(codes.len());
+        for code in codes {
+            let file_name = if code.is_empty() { "openapi" } else { code.as_str() };
+            let path = dir.join(format!("{file_name}.{}", format.extension()));
+            std::fs::write(&path, format.render(&specs[code])?)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Get the ``OpenAPI`` spec documents of the added modules by the module code / 按模块编码获取已添加模块的 ``OpenAPI`` 规范文档
+    pub async fn openapi_specs(&self) -> HashMap<String, Value> {
+        self.openapi_specs.lock().await.clone()
+    }
+}
@@ -0,0 +1,280 @@
+//! Server-Sent Events / 服务端推送事件
+//!
+//! [`TardisSse`] turns an async stream or a broadcast channel into a `text/event-stream` response with heartbeats,
+//! [`TardisSseChannel`] keeps the recent events to replay them to the reconnecting clients by the `Last-Event-ID` header.
+//!
+//! [`TardisSse`] 将异步流或广播通道转换为带心跳的 `text/event-stream` 响应，
+//! [`TardisSseChannel`] 保留最近的事件，按 `Last-Event-ID` 请求头向重连的客户端重放.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::web::web_sse::{TardisSseChannel, TardisSseEvent};
+//! let channel = TardisSseChannel::new(100, 1000);
+//! TardisFuns::web_server().add_module_raw("notify", Route::new().at("/events", channel.endpoint())).await;
+//! channel.send(TardisSseEvent::json(&Notice { title: "hi".to_string() })?.event("notice"));
+//! ```
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::stream::BoxStream;
+use futures::{Future, Stream, StreamExt};
+use poem::http::StatusCode;
+use poem::{Body, Endpoint, IntoResponse, Request, Response};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+/// Default heartbeat interval / 默认心跳间隔
+pub const SSE_DEFAULT_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Event of the Server-Sent Events / 服务端推送的事件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TardisSseEvent {
+    pub id: Option<String>,
+    /// Event type, the `message` event is used by the browsers if it's absent / 事件类型，缺省时浏览器使用 `message` 事件
+    pub event: Option<String>,
+    pub data: String,
+}
+
+impl TardisSseEvent {
+    pub fn data(data: impl Into<String>) -> Self {
+        TardisSseEvent {
+            data: data.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn json<T: ?Sized + Serialize>(data: &T) -> TardisResult<Self> {
+        Ok(Self::data(TardisFuns::json.obj_to_string(data)?))
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Frame of the `text/event-stream`, the multi-line data is split into multiple `data` fields
+    /// / `text/event-stream` 的帧，多行数据拆分为多个 `data` 字段
+    pub fn to_frame(&self) -> String {
+        let mut frame = String::new();
+        if let Some(id) = &self.id {
+            frame.push_str(&format!("id: {}\n", single_line(id)));
+        }
+        if let Some(event) = &self.event {
+            frame.push_str(&format!("event: {}\n", single_line(event)));
+        }
+        for line in self.data.split('\n') {
+            frame.push_str(&format!("data: {}\n", line.strip_suffix('\r').unwrap_or(line)));
+        }
+        frame.push('\n');
+        frame
+    }
+}
+
+// the line breaks would break the framing
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], "")
+}
+
+/// Server-Sent Events response / 服务端推送事件响应
+///
+/// A comment line is sent as the heartbeat if there are no events in the interval, to keep the connection alive through the proxies.
+///
+/// 间隔内没有事件时发送注释行作为心跳，以保持经过代理的连接.
+pub struct TardisSse {
+    events: BoxStream<'static, TardisSseEvent>,
+    heartbeat: Option<Duration>,
+    retry: Option<Duration>,
+}
+
+impl TardisSse {
+    pub fn from_stream(events: impl Stream<Item = TardisSseEvent> + Send + 'static) -> Self {
+        TardisSse {
+            events: events.boxed(),
+            heartbeat: Some(SSE_DEFAULT_HEARTBEAT),
+            retry: None,
+        }
+    }
+
+    /// Send the events of the broadcast channel, the events lagged behind are skipped
+    /// / 发送广播通道的事件，滞后的事件会被跳过
+    pub fn from_broadcast(receiver: broadcast::Receiver<TardisSseEvent>) -> Self {
+        Self::from_stream(broadcast_stream(receiver))
+    }
+
+    /// Heartbeat interval, `None` disables the heartbeat / 心跳间隔， `None` 表示关闭心跳
+    pub fn heartbeat(mut self, heartbeat: Option<Duration>) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    /// Reconnection delay of the clients / 客户端的重连延迟
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// The id of the last event received by the reconnecting client / 重连客户端收到的最后一个事件的标识
+    pub fn last_event_id(req: &Request) -> Option<String> {
+        req.header("Last-Event-ID").map(|id| id.to_string()).filter(|id| !id.is_empty())
+    }
+
+    fn into_body_stream(self) -> impl Stream<Item = Result<String, std::io::Error>> + Send + 'static {
+        enum Next {
+            Event(Option<TardisSseEvent>),
+            Heartbeat,
+        }
+        let TardisSse { mut events, heartbeat, retry } = self;
+        async_stream::stream! {
+            if let Some(retry) = retry {
+                yield Ok(format!("retry: {}\n\n", retry.as_millis()));
+            }
+            let mut heartbeat = heartbeat.map(|interval| tokio::time::interval_at(tokio::time::Instant::now() + interval, interval));
+            loop {
+                let next = match heartbeat.as_mut() {
+                    Some(heartbeat) => tokio::select! {
+                        event = events.next() => Next::Event(event),
+                        _ = heartbeat.tick() => Next::Heartbeat,
+                    },
+                    None => Next::Event(events.next().await),
+                };
+                match next {
+                    Next::Event(Some(event)) => {
+                        if let Some(heartbeat) = heartbeat.as_mut() {
+                            heartbeat.reset();
+                        }
+                        yield Ok(event.to_frame());
+                    }
+                    Next::Event(None) => break,
+                    Next::Heartbeat => yield Ok(":\n\n".to_string()),
+                }
+            }
+        }
+    }
+}
+
+impl IntoResponse for TardisSse {
+    fn into_response(self) -> Response {
+        Response::builder()
+            .status(StatusCode::OK)
+            .content_type("text/event-stream")
+            .header("Cache-Control", "no-cache")
+            // disable the response buffering of nginx
+            .header("X-Accel-Buffering", "no")
+            .body(Body::from_bytes_stream(self.into_body_stream()))
+    }
+}
+
+fn broadcast_stream(mut receiver: broadcast::Receiver<TardisSseEvent>) -> impl Stream<Item = TardisSseEvent> + Send + 'static {
+    async_stream::stream! {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => yield event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("[Tardis.WebServer] SSE subscriber lagged behind, {skipped} events are skipped");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+/// Endpoint of the Server-Sent Events, `fun` creates the response with the `Last-Event-ID` of the request
+/// / 服务端推送事件的端点， `fun` 根据请求的 `Last-Event-ID` 创建响应
+///
+/// # Examples
+/// ```ignore
+/// use tardis::web::web_sse::{sse_endpoint, TardisSse, TardisSseEvent};
+/// let route = Route::new().at("/ticks", sse_endpoint(|last_event_id| async move {
+///     let start = last_event_id.and_then(|id| id.parse::<u64>().ok()).map(|id| id + 1).unwrap_or(0);
+///     TardisSse::from_stream(futures::stream::iter(start..).map(|i| TardisSseEvent::data(i.to_string()).id(i.to_string())))
+/// }));
+/// TardisFuns::web_server().add_module_raw("tick", route).await;
+/// ```
+pub fn sse_endpoint<F, Fut>(fun: F) -> impl Endpoint<Output = TardisSse>
+where
+    F: Fn(Option<String>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TardisSse> + Send,
+{
+    poem::endpoint::make(move |req: Request| fun(TardisSse::last_event_id(&req)))
+}
+
+/// Broadcast channel of the Server-Sent Events with the replay history / 带重放历史的服务端推送事件广播通道
+#[derive(Clone)]
+pub struct TardisSseChannel {
+    inner: Arc<SseChannelInner>,
+}
+
+struct SseChannelInner {
+    sender: broadcast::Sender<TardisSseEvent>,
+    history: Mutex<VecDeque<TardisSseEvent>>,
+    history_size: usize,
+    next_id: AtomicU64,
+}
+
+impl TardisSseChannel {
+    /// `capacity` is the number of the events buffered for each subscriber, `history_size` is the number of the events kept for the replay
+    /// / `capacity` 为每个订阅者缓冲的事件数， `history_size` 为保留用于重放的事件数
+    pub fn new(capacity: usize, history_size: usize) -> Self {
+        TardisSseChannel {
+            inner: Arc::new(SseChannelInner {
+                sender: broadcast::channel(capacity).0,
+                history: Mutex::new(VecDeque::with_capacity(history_size)),
+                history_size,
+                next_id: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Send the event to all the subscribers, an incremental id is assigned if it's absent, returns the number of the subscribers
+    /// / 向所有订阅者发送事件，缺少标识时分配递增的标识，返回订阅者数量
+    pub fn send(&self, mut event: TardisSseEvent) -> usize {
+        if event.id.is_none() {
+            event.id = Some(self.inner.next_id.fetch_add(1, Ordering::SeqCst).to_string());
+        }
+        // hold the history lock to keep the order between the replay and the live events of the new subscribers
+        let mut history = self.inner.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if self.inner.history_size > 0 {
+            if history.len() >= self.inner.history_size {
+                history.pop_front();
+            }
+            history.push_back(event.clone());
+        }
+        self.inner.sender.send(event).unwrap_or(0)
+    }
+
+    /// Subscribe the events after `last_event_id`, all the kept events are replayed if it isn't in the history
+    /// / 订阅 `last_event_id` 之后的事件，该标识不在历史中时重放所有保留的事件
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> TardisSse {
+        let history = self.inner.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let receiver = self.inner.sender.subscribe();
+        let replay = match last_event_id {
+            Some(last_event_id) => match history.iter().position(|event| event.id.as_deref() == Some(last_event_id)) {
+                Some(position) => history.iter().skip(position + 1).cloned().collect(),
+                None => history.iter().cloned().collect(),
+            },
+            None => Vec::new(),
+        };
+        drop(history);
+        TardisSse::from_stream(futures::stream::iter(replay).chain(broadcast_stream(receiver)))
+    }
+
+    /// Endpoint subscribing the channel with the `Last-Event-ID` of the request / 以请求的 `Last-Event-ID` 订阅该通道的端点
+    pub fn endpoint(&self) -> impl Endpoint<Output = TardisSse> {
+        let channel = self.clone();
+        sse_endpoint(move |last_event_id| {
+            let sse = channel.subscribe(last_event_id.as_deref());
+            async move { sse }
+        })
+    }
+}
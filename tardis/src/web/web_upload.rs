@@ -0,0 +1,275 @@
+//! Multipart file upload / Multipart文件上传
+//!
+//! [`TardisUploader`] receives the files of a multipart request with the size limits and the content type allow-list,
+//! and streams them to a directory or an object storage bucket.
+//!
+//! [`TardisUploader`] 按大小限制及内容类型白名单接收multipart请求中的文件，并将其以流的方式写入目录或对象存储桶.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::web::poem::{handler, web::Multipart};
+//! use tardis::web::web_upload::{TardisUploadSink, TardisUploader};
+//!
+//! #[handler]
+//! async fn upload(multipart: Multipart) -> tardis::web::poem::Result<Json<Vec<String>>> {
+//!     let uploaded = TardisUploader::new()
+//!         .max_file_size(5 * 1024 * 1024)
+//!         .allow_content_types(["image/*", "application/pdf"])
+//!         .receive(multipart, &TardisUploadSink::Dir("/data/uploads".into()))
+//!         .await?;
+//!     Ok(Json(uploaded.files.into_iter().map(|file| file.location).collect()))
+//! }
+//! ```
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use poem::web::Multipart;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::warn;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Destination of the uploaded files / 上传文件的目的地
+#[derive(Debug, Clone)]
+pub enum TardisUploadSink {
+    /// Write the files into the directory / 写入到目录中
+    Dir(PathBuf),
+    /// Create the objects with the prefix in the bucket, the default bucket is used if `bucket` is `None`
+    /// / 在桶中以该前缀创建对象， `bucket` 为 `None` 时使用默认桶
+    ///
+    /// The object storage client doesn't support the streaming upload, so each file is buffered in memory, up to the `max_file_size`.
+    ///
+    /// 对象存储客户端不支持流式上传，因此每个文件会在内存中缓冲，最多 `max_file_size` .
+    #[cfg(feature = "os")]
+    Os { prefix: String, bucket: Option<String> },
+}
+
+/// Uploaded file / 已上传的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisUploadedFile {
+    /// Name of the form field / 表单字段名
+    pub field: String,
+    /// Original file name provided by the client / 客户端提供的原始文件名
+    pub file_name: Option<String>,
+    pub content_type: String,
+    pub size: u64,
+    /// The file path or the object path, named by a random id with the extension of the original file name to avoid the path traversal
+    /// / 文件路径或对象路径，以随机标识加原始文件名的扩展名命名，以避免路径穿越
+    pub location: String,
+}
+
+/// Received multipart request / 已接收的multipart请求
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisUploadResult {
+    /// The text fields / 文本字段
+    pub fields: HashMap<String, String>,
+    pub files: Vec<TardisUploadedFile>,
+}
+
+/// Multipart file receiver / Multipart文件接收器
+///
+/// The files exceeding the size limit fail with the `413-tardis-webserver-upload-too-large` error,
+/// the files of the disallowed content types fail with the `415-tardis-webserver-upload-content-type-not-allowed` error,
+/// the files already stored by the request are removed on failure.
+///
+/// 超过大小限制的文件以 `413-tardis-webserver-upload-too-large` 错误失败，不允许的内容类型的文件以 `415-tardis-webserver-upload-content-type-not-allowed` 错误失败，
+/// 失败时会删除该请求已存储的文件.
+#[derive(Debug, Clone)]
+pub struct TardisUploader {
+    max_file_size: u64,
+    max_files: usize,
+    allowed_content_types: Vec<String>,
+}
+
+impl Default for TardisUploader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TardisUploader {
+    /// Up to 10 files of 10MB by default, all the content types are allowed / 默认最多10个10MB的文件，允许所有内容类型
+    pub fn new() -> Self {
+        TardisUploader {
+            max_file_size: 10 * 1024 * 1024,
+            max_files: 10,
+            allowed_content_types: Vec::new(),
+        }
+    }
+
+    /// Max size of each file in bytes / 每个文件的最大字节数
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Max number of the files of a request / 每个请求的最大文件数
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Allowed content types, e.g. `image/png` or `image/*`, all the content types are allowed if it's empty
+    /// / 允许的内容类型，如 `image/png` 或 `image/*` ，为空时允许所有内容类型
+    pub fn allow_content_types(mut self, content_types: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.allowed_content_types.extend(content_types.into_iter().map(Into::into));
+        self
+    }
+
+    fn is_allowed(&self, content_type: &str) -> bool {
+        let content_type = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        self.allowed_content_types.is_empty()
+            || self.allowed_content_types.iter().any(|allowed| match allowed.strip_suffix("/*") {
+                Some(kind) => content_type.split('/').next() == Some(kind),
+                None => allowed.eq_ignore_ascii_case(&content_type),
+            })
+    }
+
+    /// Receive the multipart request, the text fields are collected and the files are stored into the sink
+    /// / 接收multipart请求，收集文本字段并将文件存储到目的地
+    pub async fn receive(&self, multipart: Multipart, sink: &TardisUploadSink) -> TardisResult<TardisUploadResult> {
+        let mut result = TardisUploadResult::default();
+        match self.receive_fields(multipart, sink, &mut result).await {
+            Ok(()) => Ok(result),
+            Err(error) => {
+                for file in &result.files {
+                    if let Err(remove_error) = remove(sink, &file.location).await {
+                        warn!("[Tardis.WebServer] Remove the uploaded file {} error: {remove_error}", file.location);
+                    }
+                }
+                Err(error)
+            }
+        }
+    }
+
+    async fn receive_fields(&self, mut multipart: Multipart, sink: &TardisUploadSink, result: &mut TardisUploadResult) -> TardisResult<()> {
+        while let Some(field) = multipart.next_field().await.map_err(|error| invalid_multipart(&error.to_string()))? {
+            let name = field.name().unwrap_or_default().to_string();
+            let Some(file_name) = field.file_name().map(str::to_string) else {
+                let value = field.text().await.map_err(|error| invalid_multipart(&error.to_string()))?;
+                result.fields.insert(name, value);
+                continue;
+            };
+            if result.files.len() >= self.max_files {
+                return Err(TardisError::custom(
+                    "413-tardis-webserver-upload-too-many-files",
+                    &format!("[Tardis.WebServer] At most {} files can be uploaded", self.max_files),
+                    "",
+                ));
+            }
+            let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
+            if !self.is_allowed(&content_type) {
+                return Err(TardisError::custom(
+                    "415-tardis-webserver-upload-content-type-not-allowed",
+                    &format!("[Tardis.WebServer] Content type {content_type} of file {file_name} is not allowed"),
+                    "",
+                ));
+            }
+            let stored_name = format!("{}{}", TardisFuns::field.nanoid(), extension(&file_name));
+            let mut reader = Box::pin(field.into_async_read());
+            let (location, size) = match sink {
+                TardisUploadSink::Dir(dir) => {
+                    let path = dir.join(&stored_name);
+                    let location = path.to_string_lossy().to_string();
+                    match self.write_file(&mut reader, &path).await {
+                        Ok(size) => (location, size),
+                        Err(error) => {
+                            let _ = tokio::fs::remove_file(&path).await;
+                            return Err(error);
+                        }
+                    }
+                }
+                #[cfg(feature = "os")]
+                TardisUploadSink::Os { prefix, bucket } => {
+                    let content = self.read_limited(&mut reader).await?;
+                    let location = format!("{prefix}{stored_name}");
+                    TardisFuns::os().object_create(&location, &content, Some(&content_type), bucket.as_deref()).await?;
+                    (location, content.len() as u64)
+                }
+            };
+            result.files.push(TardisUploadedFile {
+                field: name,
+                file_name: Some(file_name),
+                content_type,
+                size,
+                location,
+            });
+        }
+        Ok(())
+    }
+
+    async fn write_file(&self, reader: &mut (impl tokio::io::AsyncRead + Unpin), path: &Path) -> TardisResult<u64> {
+        let mut file = tokio::fs::File::create(path).await?;
+        let mut buf = vec![0; UPLOAD_CHUNK_SIZE];
+        let mut size = 0;
+        loop {
+            let read = reader.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            size += read as u64;
+            self.check_size(size)?;
+            file.write_all(&buf[..read]).await?;
+        }
+        file.flush().await?;
+        Ok(size)
+    }
+
+    #[cfg(feature = "os")]
+    async fn read_limited(&self, reader: &mut (impl tokio::io::AsyncRead + Unpin)) -> TardisResult<Vec<u8>> {
+        let mut content = Vec::new();
+        let mut buf = vec![0; UPLOAD_CHUNK_SIZE];
+        loop {
+            let read = reader.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            content.extend_from_slice(&buf[..read]);
+            self.check_size(content.len() as u64)?;
+        }
+        Ok(content)
+    }
+
+    fn check_size(&self, size: u64) -> TardisResult<()> {
+        if size > self.max_file_size {
+            Err(TardisError::custom(
+                "413-tardis-webserver-upload-too-large",
+                &format!("[Tardis.WebServer] File size exceeds the limit of {} bytes", self.max_file_size),
+                "",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The extension of the file name, only the alphanumeric ones are kept
+fn extension(file_name: &str) -> String {
+    Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| !extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(|extension| format!(".{}", extension.to_lowercase()))
+        .unwrap_or_default()
+}
+
+fn invalid_multipart(reason: &str) -> TardisError {
+    TardisError::custom(
+        "400-tardis-webserver-upload-invalid",
+        &format!("[Tardis.WebServer] Invalid multipart request: {reason}"),
+        "",
+    )
+}
+
+async fn remove(sink: &TardisUploadSink, location: &str) -> TardisResult<()> {
+    match sink {
+        TardisUploadSink::Dir(_) => Ok(tokio::fs::remove_file(location).await?),
+        #[cfg(feature = "os")]
+        TardisUploadSink::Os { bucket, .. } => TardisFuns::os().object_delete(location, bucket.as_deref()).await,
+    }
+}
@@ -1,13 +1,59 @@
 use std::fmt::{Display, Formatter};
+use std::ops::{Deref, DerefMut};
 
-use poem_openapi::Validator;
+use poem::{Request, RequestBody};
+use poem_openapi::payload::Json;
+use poem_openapi::registry::{MetaRequest, Registry};
+use poem_openapi::types::ParseFromJSON;
+use poem_openapi::{ApiExtractor, ApiExtractorType, ExtractParamOptions, Validator};
 
+use crate::basic::field::FieldRegion;
+use crate::basic::validation::TardisValidate;
+use crate::web::uniform_error_mw::preferred_lang;
 use crate::TardisFuns;
 
 pub struct Phone;
 
 pub struct Mail;
 
+pub struct Ipv4;
+
+pub struct Ipv6;
+
+pub struct Ip;
+
+pub struct Url;
+
+pub struct Mac;
+
+/// Date validator with the specified format / 指定格式的日期校验器
+///
+/// ```ignore
+/// #[oai(validator(custom = "tardis::web::web_validation::Date(\"%Y-%m-%d\")"))]
+/// ```
+pub struct Date(pub &'static str);
+
+/// Date time validator with the specified format / 指定格式的日期时间校验器
+///
+/// ```ignore
+/// #[oai(validator(custom = "tardis::web::web_validation::DateTime(\"%Y-%m-%d %H:%M:%S\")"))]
+/// ```
+pub struct DateTime(pub &'static str);
+
+/// Phone number validator of the specified region / 指定区域的手机号校验器
+///
+/// ```ignore
+/// #[oai(validator(custom = "tardis::web::web_validation::PhoneByRegion(tardis::basic::field::FieldRegion::HK)"))]
+/// ```
+pub struct PhoneByRegion(pub FieldRegion);
+
+/// Identity card number validator of the specified region / 指定区域的身份证号校验器
+///
+/// ```ignore
+/// #[oai(validator(custom = "tardis::web::web_validation::IdCardByRegion(tardis::basic::field::FieldRegion::CN)"))]
+/// ```
+pub struct IdCardByRegion(pub FieldRegion);
+
 impl Display for Phone {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str("Invalid phone number format")
@@ -20,6 +66,60 @@ impl Display for Mail {
     }
 }
 
+impl Display for Ipv4 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid IPv4 address format")
+    }
+}
+
+impl Display for Ipv6 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid IPv6 address format")
+    }
+}
+
+impl Display for Ip {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid IP address format")
+    }
+}
+
+impl Display for Url {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid url format")
+    }
+}
+
+impl Display for Mac {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid MAC address format")
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid date format, expected [{}]", self.0)
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid date time format, expected [{}]", self.0)
+    }
+}
+
+impl Display for PhoneByRegion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid phone number format of region [{:?}]", self.0)
+    }
+}
+
+impl Display for IdCardByRegion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid identity card number format of region [{:?}]", self.0)
+    }
+}
+
 impl Validator<String> for Phone {
     fn check(&self, value: &String) -> bool {
         TardisFuns::field.is_phone(value)
@@ -31,3 +131,113 @@ impl Validator<String> for Mail {
         TardisFuns::field.is_mail(value)
     }
 }
+
+impl Validator<String> for Ipv4 {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_ipv4(value)
+    }
+}
+
+impl Validator<String> for Ipv6 {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_ipv6(value)
+    }
+}
+
+impl Validator<String> for Ip {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_ip(value)
+    }
+}
+
+impl Validator<String> for Url {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_url(value)
+    }
+}
+
+impl Validator<String> for Mac {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_mac(value)
+    }
+}
+
+impl Validator<String> for Date {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_date(value, self.0)
+    }
+}
+
+impl Validator<String> for DateTime {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_datetime(value, self.0)
+    }
+}
+
+impl Validator<String> for PhoneByRegion {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_phone_by_region(value, self.0)
+    }
+}
+
+impl Validator<String> for IdCardByRegion {
+    fn check(&self, value: &String) -> bool {
+        TardisFuns::field.is_id_card_by_region(value, self.0)
+    }
+}
+
+/// JSON payload validated by the declarative rules / 按声明式规则校验的JSON请求体
+///
+/// The invalid bodies are rejected before the handler runs with a 400 error, whose extensions contain the localized
+/// messages and the codes of the invalid fields by the `Accept-Language` header, @see [validation](crate::basic::validation).
+///
+/// 无效请求体在处理函数执行前以400错误拒绝，错误的扩展中包含按 `Accept-Language` 请求头本地化的无效字段消息及错误码，
+/// @see [validation](crate::basic::validation).
+///
+/// ```ignore
+/// #[oai(path = "/todos", method = "post")]
+/// async fn add(&self, req: ValidJson<TodoAddReq>) -> TardisApiResult<i64> {
+///     ...
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidJson<T>(pub T);
+
+impl<T> Deref for ValidJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ValidJson<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[poem::async_trait]
+impl<'a, T: ParseFromJSON + TardisValidate> ApiExtractor<'a> for ValidJson<T> {
+    const TYPES: &'static [ApiExtractorType] = &[ApiExtractorType::RequestObject];
+
+    type ParamType = ();
+    type ParamRawType = ();
+
+    fn register(registry: &mut Registry) {
+        <Json<T> as ApiExtractor<'a>>::register(registry);
+    }
+
+    fn request_meta() -> Option<MetaRequest> {
+        <Json<T> as ApiExtractor<'a>>::request_meta()
+    }
+
+    async fn from_request(request: &'a Request, body: &mut RequestBody, param_opts: ExtractParamOptions<Self::ParamType>) -> poem::Result<Self> {
+        let Json(value) = <Json<T> as ApiExtractor<'a>>::from_request(request, body, param_opts).await?;
+        if let Err(errors) = value.validate() {
+            let lang = request.header("Accept-Language").and_then(preferred_lang);
+            return Err(errors.into_error(lang.as_deref()).into());
+        }
+        Ok(ValidJson(value))
+    }
+}
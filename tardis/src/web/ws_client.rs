@@ -1,13 +1,15 @@
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "future")]
 use futures::{Future, SinkExt, StreamExt};
 use native_tls::TlsConnector;
-use serde::de::Deserialize;
+use serde::de::{Deserialize, DeserializeOwned};
 use serde::Serialize;
 use serde_json::Value;
-use tokio::sync::{mpsc, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio::sync::{mpsc, Notify, OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::Connector;
 use tracing::{debug, info};
@@ -16,18 +18,51 @@ use url::Url;
 
 use crate::basic::error::TardisError;
 use crate::basic::result::TardisResult;
+use crate::config::config_dto::WSClientConfig;
 use crate::TardisFuns;
 
 type OnMsgCbk = Arc<dyn Fn(Message) -> Pin<Box<dyn Future<Output = Option<Message>> + Send + Sync>> + Send + Sync>;
+type OnJsonCbk = Arc<dyn Fn(&Value) + Send + Sync>;
 // with a callback function to handle inbound messages, but never handle inbound messages positively.
 // and then, we should also send messages through this client.
 
+/// WebSocket client / WebSocket客户端
+///
+/// The connection is kept alive by ping frames and reconnected automatically when it's lost unless closed by the server, @see [WSClientConfig](crate::config::config_dto::WSClientConfig).
+///
+/// 连接通过ping帧保活，非服务端主动关闭的断开会自动重连，@see [WSClientConfig](crate::config::config_dto::WSClientConfig).
+///
+/// # Examples
+/// ```ignore
+/// use tardis::TardisFuns;
+/// let client = TardisFuns::ws_client("ws://127.0.0.1:8080/ws", |_| async { None }).await?;
+/// client.subscribe(|event: TodoEvent| async move {
+///     println!("{event:?}");
+/// });
+/// client.send_obj(&TodoReq { id: 1 }).await?;
+/// ```
 #[derive(Clone)]
 pub struct TardisWSClient {
     pub(crate) url: Url,
-    on_message: OnMsgCbk,
-    sender: Arc<RwLock<mpsc::UnboundedSender<Message>>>,
+    inner: Arc<WSClientInner>,
+}
+
+struct WSClientInner {
+    url: Url,
+    config: WSClientConfig,
+    handlers: Arc<WSHandlers>,
+    sender: RwLock<mpsc::UnboundedSender<Message>>,
     connection_semaphore: Arc<Semaphore>,
+    /// notified when a connection is lost / 连接断开时通知
+    disconnected: Arc<Notify>,
+    closed: AtomicBool,
+}
+
+/// The inbound message handlers, shared by the connections / 入站消息处理器，由各连接共享
+struct WSHandlers {
+    on_message: OnMsgCbk,
+    subscribers: std::sync::RwLock<Vec<(u64, OnJsonCbk)>>,
+    next_subscriber_id: AtomicU64,
 }
 
 impl std::fmt::Debug for TardisWSClient {
@@ -37,30 +72,59 @@ impl std::fmt::Debug for TardisWSClient {
 }
 
 impl TardisWSClient {
+    /// Connect with the `ws` configuration of the default web client / 使用默认Web客户端的 `ws` 配置连接
     pub async fn connect<F, T>(str_url: &str, on_message: F) -> TardisResult<TardisWSClient>
+    where
+        F: Fn(Message) -> T + Send + Sync + Copy + 'static,
+        T: Future<Output = Option<Message>> + Send + Sync + 'static,
+    {
+        let config = TardisFuns::fw_config().web_client.as_ref().map(|config| config.default.ws.clone()).unwrap_or_default();
+        Self::connect_with_config(str_url, &config, on_message).await
+    }
+
+    pub async fn connect_with_config<F, T>(str_url: &str, config: &WSClientConfig, on_message: F) -> TardisResult<TardisWSClient>
     where
         F: Fn(Message) -> T + Send + Sync + Copy + 'static,
         T: Future<Output = Option<Message>> + Send + Sync + 'static,
     {
         let url = Url::parse(str_url).map_err(|_| TardisError::format_error(&format!("[Tardis.WSClient] Invalid url {str_url}"), "406-tardis-ws-url-error"))?;
 
+        let handlers = Arc::new(WSHandlers {
+            on_message: Arc::new(move |m| Box::pin(on_message(m))),
+            subscribers: std::sync::RwLock::new(Vec::new()),
+            next_subscriber_id: AtomicU64::new(0),
+        });
+        let disconnected = Arc::new(Notify::new());
         let connection_semaphore = Arc::new(Semaphore::const_new(1));
         let permit = connection_semaphore.clone().acquire_owned().await.expect("newly created semaphore should not fail");
-        let tx = Self::do_connect(&url, Arc::new(move |m| Box::pin(on_message(m))), false, permit).await?;
-        let sender = Arc::new(RwLock::new(tx));
-        Ok(TardisWSClient {
-            url,
-            on_message: Arc::new(move |m| Box::pin(on_message(m))),
-            sender,
+        let tx = Self::do_connect(&url, config, handlers.clone(), disconnected.clone(), false, permit).await?;
+        let inner = Arc::new(WSClientInner {
+            url: url.clone(),
+            config: config.clone(),
+            handlers,
+            sender: RwLock::new(tx),
             connection_semaphore,
-        })
+            disconnected,
+            closed: AtomicBool::new(false),
+        });
+        if config.auto_reconnect {
+            Self::spawn_auto_reconnect(Arc::downgrade(&inner), inner.disconnected.clone(), config.clone());
+        }
+        Ok(TardisWSClient { url, inner })
     }
 
     pub fn is_connected(&self) -> bool {
-        self.connection_semaphore.available_permits() == 0
+        self.inner.is_connected()
     }
 
-    async fn do_connect(url: &Url, on_message: OnMsgCbk, retry: bool, permit: OwnedSemaphorePermit) -> TardisResult<mpsc::UnboundedSender<Message>> {
+    async fn do_connect(
+        url: &Url,
+        config: &WSClientConfig,
+        handlers: Arc<WSHandlers>,
+        disconnected: Arc<Notify>,
+        retry: bool,
+        permit: OwnedSemaphorePermit,
+    ) -> TardisResult<mpsc::UnboundedSender<Message>> {
         info!(
             "[Tardis.WSClient] {}, host:{}, port:{}",
             if retry { "Re-initializing" } else { "Initializing" },
@@ -97,18 +161,17 @@ impl TardisWSClient {
             url.port().unwrap_or(0)
         );
         let (mut ws_tx, mut ws_rx) = stream.split();
-        // let ws_tx = Arc::new(Mutex::new(ws_tx));
-        // let reply = ws_tx.clone();
-        // let (outbound_tx, mut outbound_rx) = mpsc::unbounded_channel::<Message>();
 
         let (outbound_queue_tx, mut outbound_queue_rx) = mpsc::unbounded_channel::<Message>();
+        let last_received = Arc::new(std::sync::Mutex::new(Instant::now()));
+        let closed_by_peer = Arc::new(AtomicBool::new(false));
 
         // there should be two queue:
         // 1. out to client queue
         // 2. client to remote queue
 
         // outbound side
-        let ob_handle = {
+        let mut ob_handle = {
             let url = url.clone();
             tokio::spawn(async move {
                 while let Some(message) = outbound_queue_rx.recv().await {
@@ -125,10 +188,10 @@ impl TardisWSClient {
         };
 
         // inbound side
-        let ib_handle = {
-            let on_message = on_message.clone();
-
+        let mut ib_handle = {
             let outbound_queue_tx = outbound_queue_tx.clone();
+            let last_received = last_received.clone();
+            let closed_by_peer = closed_by_peer.clone();
             let url = url.clone();
             tokio::spawn(async move {
                 // stream would be owned by one single task and
@@ -138,7 +201,16 @@ impl TardisWSClient {
                     match message {
                         Ok(message) => {
                             trace!("[Tardis.WSClient] WS receive: {}", message);
-                            let fut_response = on_message(message);
+                            if let Ok(mut last_received) = last_received.lock() {
+                                *last_received = Instant::now();
+                            }
+                            match message {
+                                Message::Pong(_) => continue,
+                                Message::Close(_) => closed_by_peer.store(true, Ordering::SeqCst),
+                                _ => {}
+                            }
+                            handlers.dispatch(&message);
+                            let fut_response = (handlers.on_message)(message);
                             let outbound_queue_tx = outbound_queue_tx.clone();
                             let url = url.clone();
                             tokio::spawn(async move {
@@ -158,18 +230,86 @@ impl TardisWSClient {
                 }
             })
         };
+
+        // keepalive side, the connection is considered broken if nothing is received within the ping interval plus the pong timeout
+        let mut keepalive_handle = {
+            let outbound_queue_tx = outbound_queue_tx.clone();
+            let url = url.clone();
+            let ping_interval = Duration::from_secs(config.ping_interval_sec);
+            let pong_timeout = Duration::from_secs(config.pong_timeout_sec);
+            tokio::spawn(async move {
+                if ping_interval.is_zero() {
+                    return std::future::pending().await;
+                }
+                let mut interval = tokio::time::interval(ping_interval);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    if last_received.lock().map(|last_received| last_received.elapsed() > ping_interval + pong_timeout).unwrap_or(false) {
+                        warn!("[Tardis.WSClient] client: {url} no pong received in time, the connection is considered broken");
+                        break;
+                    }
+                    if outbound_queue_tx.send(Message::Ping(Vec::new())).is_err() {
+                        break;
+                    }
+                }
+            })
+        };
         tokio::spawn(async move {
             let permit = permit;
             tokio::select! {
-                _ = ib_handle => {},
-                _ = ob_handle => {}
+                _ = &mut ib_handle => {},
+                _ = &mut ob_handle => {},
+                _ = &mut keepalive_handle => {}
+            }
+            ib_handle.abort();
+            ob_handle.abort();
+            keepalive_handle.abort();
+            drop(permit);
+            // the connection closed by the peer deliberately won't be reconnected automatically
+            if !closed_by_peer.load(Ordering::SeqCst) {
+                disconnected.notify_one();
             }
-            drop(permit)
         });
 
         Ok(outbound_queue_tx)
     }
 
+    /// Reconnect with backoff after the connection is lost, until the client is closed or all the client handles are dropped
+    /// / 连接断开后按退避间隔重连，直到客户端关闭或所有客户端句柄被drop
+    fn spawn_auto_reconnect(inner: Weak<WSClientInner>, disconnected: Arc<Notify>, config: WSClientConfig) {
+        tokio::spawn(async move {
+            loop {
+                disconnected.notified().await;
+                let mut interval = Duration::from_millis(config.reconnect_interval_ms);
+                let mut attempts = 0;
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let Some(inner) = inner.upgrade() else {
+                        return;
+                    };
+                    if inner.closed.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    if inner.is_connected() {
+                        break;
+                    }
+                    attempts += 1;
+                    match inner.reconnect().await {
+                        Ok(_) if inner.is_connected() => break,
+                        Ok(_) => {}
+                        Err(error) => warn!("[Tardis.WSClient] client: {} auto reconnect failed, attempts:{attempts}, error:{error}", inner.url),
+                    }
+                    if config.max_reconnect_attempts > 0 && attempts >= config.max_reconnect_attempts {
+                        warn!("[Tardis.WSClient] client: {} give up reconnecting after {attempts} attempts", inner.url);
+                        return;
+                    }
+                    interval = (interval * 2).min(Duration::from_millis(config.max_reconnect_interval_ms.max(config.reconnect_interval_ms)));
+                }
+            }
+        });
+    }
+
     pub async fn send_obj<E: ?Sized + Serialize>(&self, msg: &E) -> TardisResult<()> {
         let message = TardisFuns::json.obj_to_string(msg)?;
         self.send_text(message).await
@@ -206,7 +346,7 @@ impl TardisWSClient {
         if !self.is_connected() {
             return Ok(false);
         }
-        match self.sender.read().await.send(message) {
+        match self.inner.sender.read().await.send(message) {
             Ok(_) => Ok(true),
             Err(_) => Err(TardisError::format_error(
                 &format!("[Tardis.WSClient] Client {url} failed to send message", url = self.url),
@@ -216,15 +356,83 @@ impl TardisWSClient {
     }
 
     pub async fn reconnect(&self) -> TardisResult<()> {
+        self.inner.reconnect().await
+    }
+
+    /// Subscribe to the text messages that can be deserialized into `T`, returns the subscription id
+    /// / 订阅可反序列化为 `T` 的文本消息，返回订阅标识
+    ///
+    /// The subscriptions are kept across reconnections.
+    ///
+    /// 订阅在重连后依然有效.
+    pub fn subscribe<T, F, Fut>(&self, callback: F) -> u64
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handlers = &self.inner.handlers;
+        let id = handlers.next_subscriber_id.fetch_add(1, Ordering::SeqCst);
+        let callback: OnJsonCbk = Arc::new(move |message: &Value| match T::deserialize(message) {
+            Ok(message) => {
+                tokio::spawn(callback(message));
+            }
+            Err(error) => trace!("[Tardis.WSClient] Message {message} doesn't match the subscription: {error}"),
+        });
+        if let Ok(mut subscribers) = handlers.subscribers.write() {
+            subscribers.push((id, callback));
+        }
+        id
+    }
+
+    pub fn unsubscribe(&self, id: u64) {
+        if let Ok(mut subscribers) = self.inner.handlers.subscribers.write() {
+            subscribers.retain(|(subscriber_id, _)| *subscriber_id != id);
+        }
+    }
+
+    /// Close the connection and stop reconnecting / 关闭连接并停止重连
+    pub async fn close(&self) -> TardisResult<()> {
+        self.inner.closed.store(true, Ordering::SeqCst);
+        self.send_raw(Message::Close(None)).await?;
+        Ok(())
+    }
+}
+
+impl WSClientInner {
+    fn is_connected(&self) -> bool {
+        self.connection_semaphore.available_permits() == 0
+    }
+
+    async fn reconnect(&self) -> TardisResult<()> {
         if let Ok(permit) = self.connection_semaphore.clone().try_acquire_owned() {
             info!("[Tardis.WSClient] trying to reconnect {url}", url = self.url);
-            let sender = Self::do_connect(&self.url, self.on_message.clone(), true, permit).await?;
+            let sender = TardisWSClient::do_connect(&self.url, &self.config, self.handlers.clone(), self.disconnected.clone(), true, permit).await?;
             *self.sender.write().await = sender;
         }
         Ok(())
     }
 }
 
+impl WSHandlers {
+    /// Dispatch the text message to the subscribers / 将文本消息分发给订阅者
+    fn dispatch(&self, message: &Message) {
+        let Message::Text(message) = message else {
+            return;
+        };
+        let Ok(subscribers) = self.subscribers.read() else {
+            return;
+        };
+        if subscribers.is_empty() {
+            return;
+        }
+        match TardisFuns::json.str_to_json(message) {
+            Ok(message) => subscribers.iter().for_each(|(_, callback)| callback(&message)),
+            Err(_) => trace!("[Tardis.WSClient] Message {message} isn't json, skip the subscriptions"),
+        }
+    }
+}
+
 pub trait TardisWebSocketMessageExt {
     fn str_to_obj<T: for<'de> Deserialize<'de>>(&self) -> TardisResult<T>;
     fn str_to_json(&self) -> TardisResult<Value>;
@@ -1,5 +1,6 @@
 #[cfg(feature = "cluster")]
 pub mod cluster_protocol;
+pub mod ws_registry;
 #[cfg(feature = "cluster")]
 use crate::cluster::cluster_hashmap::ClusterStaticHashMap;
 
@@ -0,0 +1,412 @@
+//! Websocket connection registry with named groups / 带命名分组的Websocket连接注册表
+//!
+//! Each connection upgraded by [`ws_session`] is registered as a session, it can join / leave groups (rooms) in the handlers,
+//! and the messages can be sent to a group or a session by [`ws_send_to_group`] / [`ws_send_to_session`] anywhere.
+//!
+//! 每个通过 [`ws_session`] 升级的连接注册为一个会话，可在处理函数中加入/离开分组（房间），
+//! 并可在任意位置通过 [`ws_send_to_group`] / [`ws_send_to_session`] 向分组或会话发送消息.
+//!
+//! The registry is local to the instance by default, enable the fan-out by [`TardisWsRegistry::enable_cache_fanout`]
+//! to deliver the messages to the sessions of all the web server instances through the Redis pub/sub.
+//!
+//! 注册表默认仅作用于当前实例，通过 [`TardisWsRegistry::enable_cache_fanout`] 启用扩散后，消息会经由Redis发布订阅投递到所有web服务实例的会话.
+//!
+//! # Examples
+//! ```ignore
+//! use tardis::web::ws_processor::ws_registry::{ws_send_to_group, ws_session};
+//!
+//! #[oai(path = "/ws/room/:room", method = "get")]
+//! async fn ws(&self, room: Path<String>, websocket: WebSocket) -> BoxWebSocketUpgraded {
+//!     ws_session(vec![room.0], HashMap::new(), websocket, |session, text, _| async move {
+//!         if let Some(other) = text.strip_prefix("join:") {
+//!             session.join(other);
+//!             return None;
+//!         }
+//!         let _ = ws_send_to_group("room1", text).await;
+//!         None
+//!     }, |_, _, _| async move {})
+//! }
+//! ```
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use futures::{Future, SinkExt, StreamExt};
+use poem::web::websocket::{BoxWebSocketUpgraded, CloseCode, Message, WebSocket};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{debug, trace, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+#[cfg(feature = "cache")]
+use crate::cache::cache_client::{CacheSubscription, TardisCacheClient};
+use crate::{tardis_static, TardisFuns};
+
+tardis_static! {
+    pub ws_registry: TardisWsRegistry = TardisWsRegistry::new();
+}
+
+/// Websocket connection registry / Websocket连接注册表
+#[derive(Clone)]
+pub struct TardisWsRegistry {
+    inner: Arc<WsRegistryInner>,
+}
+
+#[derive(Default)]
+struct WsRegistryInner {
+    node_id: String,
+    sessions: RwLock<HashMap<String, WsSessionEntry>>,
+    // group -> session ids
+    groups: RwLock<HashMap<String, HashSet<String>>>,
+    #[cfg(feature = "cache")]
+    fanout: RwLock<Option<WsCacheFanout>>,
+}
+
+struct WsSessionEntry {
+    sender: mpsc::UnboundedSender<String>,
+    groups: HashSet<String>,
+}
+
+#[cfg(feature = "cache")]
+struct WsCacheFanout {
+    cache: Arc<TardisCacheClient>,
+    channel: String,
+    subscription: CacheSubscription,
+}
+
+/// Message published to the other instances / 发布到其他实例的消息
+#[cfg(feature = "cache")]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+struct WsFanoutMessage {
+    node_id: String,
+    target: WsFanoutTarget,
+    msg: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(not(feature = "cache"), allow(dead_code))]
+enum WsFanoutTarget {
+    Group(String),
+    Session(String),
+}
+
+/// Handle of a registered session / 已注册会话的句柄
+#[derive(Clone)]
+pub struct TardisWsSession {
+    id: String,
+    registry: TardisWsRegistry,
+}
+
+impl std::fmt::Debug for TardisWsSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TardisWsSession").field("id", &self.id).finish()
+    }
+}
+
+impl TardisWsSession {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn join(&self, group: &str) {
+        self.registry.join_group(&self.id, group);
+    }
+
+    pub fn leave(&self, group: &str) {
+        self.registry.leave_group(&self.id, group);
+    }
+
+    /// Groups joined by the session / 会话加入的分组
+    pub fn groups(&self) -> Vec<String> {
+        self.registry.inner.sessions.read().ok().and_then(|sessions| sessions.get(&self.id).map(|session| session.groups.iter().cloned().collect())).unwrap_or_default()
+    }
+
+    /// Send the message to the session itself / 向会话自身发送消息
+    pub fn send(&self, msg: impl Into<String>) -> bool {
+        self.registry.send_to_local_session(&self.id, msg.into())
+    }
+}
+
+impl Default for TardisWsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TardisWsRegistry {
+    pub fn new() -> Self {
+        TardisWsRegistry {
+            inner: Arc::new(WsRegistryInner {
+                node_id: TardisFuns::field.nanoid(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Register a session, the messages sent to it are received from the returned receiver
+    /// / 注册会话，发送给会话的消息从返回的接收端接收
+    ///
+    /// Used to integrate the custom connections, the session must be removed by [`unregister`](Self::unregister) when the connection is closed.
+    ///
+    /// 用于集成自定义的连接，连接关闭时须通过 [`unregister`](Self::unregister) 移除会话.
+    pub fn register(&self) -> (TardisWsSession, mpsc::UnboundedReceiver<String>) {
+        let id = TardisFuns::field.nanoid();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if let Ok(mut sessions) = self.inner.sessions.write() {
+            sessions.insert(id.clone(), WsSessionEntry { sender, groups: HashSet::new() });
+        }
+        debug!("[Tardis.WebServer] WS session registered: {id}");
+        (TardisWsSession { id, registry: self.clone() }, receiver)
+    }
+
+    /// Remove the session and leave all its groups / 移除会话并离开其所有分组
+    pub fn unregister(&self, session_id: &str) {
+        let Some(session) = self.inner.sessions.write().ok().and_then(|mut sessions| sessions.remove(session_id)) else {
+            return;
+        };
+        if let Ok(mut groups) = self.inner.groups.write() {
+            for group in &session.groups {
+                if let Some(members) = groups.get_mut(group) {
+                    members.remove(session_id);
+                    if members.is_empty() {
+                        groups.remove(group);
+                    }
+                }
+            }
+        }
+        debug!("[Tardis.WebServer] WS session unregistered: {session_id}");
+    }
+
+    /// Join the group, the session must be registered in this instance / 加入分组，会话必须注册在当前实例
+    pub fn join_group(&self, session_id: &str, group: &str) -> bool {
+        let Ok(mut sessions) = self.inner.sessions.write() else {
+            return false;
+        };
+        let Some(session) = sessions.get_mut(session_id) else {
+            return false;
+        };
+        session.groups.insert(group.to_string());
+        if let Ok(mut groups) = self.inner.groups.write() {
+            groups.entry(group.to_string()).or_default().insert(session_id.to_string());
+        }
+        trace!("[Tardis.WebServer] WS session {session_id} joined group {group}");
+        true
+    }
+
+    pub fn leave_group(&self, session_id: &str, group: &str) -> bool {
+        let Ok(mut sessions) = self.inner.sessions.write() else {
+            return false;
+        };
+        let Some(session) = sessions.get_mut(session_id) else {
+            return false;
+        };
+        session.groups.remove(group);
+        if let Ok(mut groups) = self.inner.groups.write() {
+            if let Some(members) = groups.get_mut(group) {
+                members.remove(session_id);
+                if members.is_empty() {
+                    groups.remove(group);
+                }
+            }
+        }
+        trace!("[Tardis.WebServer] WS session {session_id} left group {group}");
+        true
+    }
+
+    /// Sessions of the group in this instance / 当前实例中分组的会话
+    pub fn group_sessions(&self, group: &str) -> Vec<String> {
+        self.inner.groups.read().ok().and_then(|groups| groups.get(group).map(|members| members.iter().cloned().collect())).unwrap_or_default()
+    }
+
+    /// Send the message to all the sessions of the group, returns the number of the sessions in this instance that received it
+    /// / 向分组的所有会话发送消息，返回当前实例中接收到消息的会话数量
+    pub async fn send_to_group(&self, group: &str, msg: impl Into<String>) -> TardisResult<usize> {
+        let msg = msg.into();
+        let delivered = self.send_to_local_group(group, &msg);
+        self.publish(WsFanoutTarget::Group(group.to_string()), msg).await?;
+        Ok(delivered)
+    }
+
+    /// Send the message to the session, it's published to the other instances if the session isn't in this instance
+    /// / 向会话发送消息，会话不在当前实例时发布到其他实例
+    pub async fn send_to_session(&self, session_id: &str, msg: impl Into<String>) -> TardisResult<()> {
+        let msg = msg.into();
+        if self.send_to_local_session(session_id, msg.clone()) {
+            return Ok(());
+        }
+        if self.publish(WsFanoutTarget::Session(session_id.to_string()), msg).await? {
+            return Ok(());
+        }
+        Err(TardisError::not_found(
+            &format!("[Tardis.WebServer] WS session {session_id} not found"),
+            "404-tardis-ws-session-not-found",
+        ))
+    }
+
+    fn send_to_local_group(&self, group: &str, msg: &str) -> usize {
+        let members = self.group_sessions(group);
+        let Ok(sessions) = self.inner.sessions.read() else {
+            return 0;
+        };
+        members.iter().filter_map(|session_id| sessions.get(session_id)).filter(|session| session.sender.send(msg.to_string()).is_ok()).count()
+    }
+
+    fn send_to_local_session(&self, session_id: &str, msg: String) -> bool {
+        self.inner.sessions.read().ok().and_then(|sessions| sessions.get(session_id).map(|session| session.sender.send(msg).is_ok())).unwrap_or(false)
+    }
+
+    /// Publish the message to the other instances, returns `false` if the fan-out isn't enabled
+    /// / 发布消息到其他实例，未启用扩散时返回 `false`
+    #[cfg(feature = "cache")]
+    async fn publish(&self, target: WsFanoutTarget, msg: String) -> TardisResult<bool> {
+        let Some((cache, channel)) = self.inner.fanout.read().ok().and_then(|fanout| fanout.as_ref().map(|fanout| (fanout.cache.clone(), fanout.channel.clone()))) else {
+            return Ok(false);
+        };
+        let message = TardisFuns::json.obj_to_string(&WsFanoutMessage {
+            node_id: self.inner.node_id.clone(),
+            target,
+            msg,
+        })?;
+        cache.publish(&channel, &message).await?;
+        Ok(true)
+    }
+
+    #[cfg(not(feature = "cache"))]
+    async fn publish(&self, _target: WsFanoutTarget, _msg: String) -> TardisResult<bool> {
+        Ok(false)
+    }
+
+    /// Deliver the messages to the sessions of all the instances through the Redis pub/sub channel
+    /// / 通过Redis发布订阅频道将消息投递到所有实例的会话
+    ///
+    /// All the instances must enable the fan-out with the same channel.
+    ///
+    /// 所有实例须使用相同的频道启用扩散.
+    #[cfg(feature = "cache")]
+    pub async fn enable_cache_fanout(&self, cache: Arc<TardisCacheClient>, channel: &str) -> TardisResult<()> {
+        let registry = self.clone();
+        let subscription = cache
+            .subscribe(channel, move |(_, message)| {
+                let registry = registry.clone();
+                async move {
+                    let message = TardisFuns::json.str_to_obj::<WsFanoutMessage>(&message)?;
+                    if message.node_id == registry.inner.node_id {
+                        return Ok(());
+                    }
+                    match message.target {
+                        WsFanoutTarget::Group(group) => {
+                            registry.send_to_local_group(&group, &message.msg);
+                        }
+                        WsFanoutTarget::Session(session_id) => {
+                            registry.send_to_local_session(&session_id, message.msg);
+                        }
+                    }
+                    Ok(())
+                }
+            })
+            .await?;
+        let fanout = WsCacheFanout {
+            cache,
+            channel: channel.to_string(),
+            subscription,
+        };
+        let previous =
+            self.inner.fanout.write().map_err(|_| TardisError::internal_error("[Tardis.WebServer] WS registry is poisoned", "500-tardis-ws-registry-poisoned"))?.replace(fanout);
+        if let Some(previous) = previous {
+            previous.subscription.unsubscribe();
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn disable_cache_fanout(&self) {
+        if let Some(previous) = self.inner.fanout.write().ok().and_then(|mut fanout| fanout.take()) {
+            previous.subscription.unsubscribe();
+        }
+    }
+
+    /// Upgrade the connection and register it as a session of this registry, @see [`ws_session`]
+    /// / 升级连接并注册为该注册表的会话，@see [`ws_session`]
+    pub fn upgrade<PF, PT, CF, CT>(&self, groups: Vec<String>, ext: HashMap<String, String>, websocket: WebSocket, process_fun: PF, close_fun: CF) -> BoxWebSocketUpgraded
+    where
+        PF: Fn(TardisWsSession, String, HashMap<String, String>) -> PT + Send + Sync + 'static,
+        PT: Future<Output = Option<String>> + Send + 'static,
+        CF: Fn(TardisWsSession, Option<(CloseCode, String)>, HashMap<String, String>) -> CT + Send + Sync + 'static,
+        CT: Future<Output = ()> + Send + 'static,
+    {
+        let registry = self.clone();
+        websocket
+            .on_upgrade(move |socket| async move {
+                let (session, mut receiver) = registry.register();
+                for group in &groups {
+                    session.join(group);
+                }
+                let (mut ws_sink, mut ws_stream) = socket.split();
+                let session_id = session.id.clone();
+                let outbound = tokio::spawn(async move {
+                    while let Some(msg) = receiver.recv().await {
+                        if let Err(error) = ws_sink.send(Message::Text(msg)).await {
+                            warn!("[Tardis.WebServer] WS message send to session {session_id} failed: {error}");
+                            break;
+                        }
+                    }
+                });
+                while let Some(Ok(message)) = ws_stream.next().await {
+                    match message {
+                        Message::Text(text) => {
+                            trace!("[Tardis.WebServer] WS message receive: {} by session {}", text, session.id);
+                            if let Some(msg) = process_fun(session.clone(), text, ext.clone()).await {
+                                session.send(msg);
+                            }
+                        }
+                        Message::Close(msg) => {
+                            trace!("[Tardis.WebServer] WS message receive: close {:?}", msg);
+                            close_fun(session.clone(), msg, ext.clone()).await
+                        }
+                        Message::Binary(_) => {
+                            warn!("[Tardis.WebServer] WS message receive: the binary type is not implemented");
+                        }
+                        Message::Ping(_) | Message::Pong(_) => {}
+                    }
+                }
+                registry.unregister(&session.id);
+                outbound.abort();
+            })
+            .boxed()
+    }
+}
+
+/// Upgrade the connection and register it as a session joining the `groups` / 升级连接并注册为加入 `groups` 的会话
+///
+/// The text messages are processed by `process_fun` with the session handle, which can join / leave groups,
+/// the returned message is sent back to the session.
+///
+/// 文本消息由 `process_fun` 结合会话句柄处理，可通过句柄加入/离开分组，返回的消息会发回该会话.
+pub fn ws_session<PF, PT, CF, CT>(groups: Vec<String>, ext: HashMap<String, String>, websocket: WebSocket, process_fun: PF, close_fun: CF) -> BoxWebSocketUpgraded
+where
+    PF: Fn(TardisWsSession, String, HashMap<String, String>) -> PT + Send + Sync + 'static,
+    PT: Future<Output = Option<String>> + Send + 'static,
+    CF: Fn(TardisWsSession, Option<(CloseCode, String)>, HashMap<String, String>) -> CT + Send + Sync + 'static,
+    CT: Future<Output = ()> + Send + 'static,
+{
+    ws_registry().upgrade(groups, ext, websocket, process_fun, close_fun)
+}
+
+/// @see [`TardisWsRegistry::send_to_group`]
+pub async fn ws_send_to_group(group: &str, msg: impl Into<String>) -> TardisResult<usize> {
+    ws_registry().send_to_group(group, msg).await
+}
+
+/// @see [`TardisWsRegistry::send_to_session`]
+pub async fn ws_send_to_session(session_id: &str, msg: impl Into<String>) -> TardisResult<()> {
+    ws_registry().send_to_session(session_id, msg).await
+}
+
+pub fn ws_join_group(session_id: &str, group: &str) -> bool {
+    ws_registry().join_group(session_id, group)
+}
+
+pub fn ws_leave_group(session_id: &str, group: &str) -> bool {
+    ws_registry().leave_group(session_id, group)
+}
@@ -0,0 +1,3 @@
+-- users of the fixture test
+INSERT INTO fixture_user (id, name, remark) VALUES (1, 'tardis', 'with ; in text');
+INSERT INTO fixture_user (id, name, remark) VALUES (2, 'idealworld', NULL);
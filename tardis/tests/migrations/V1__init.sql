@@ -0,0 +1,6 @@
+-- orders of the migration test
+CREATE TABLE IF NOT EXISTS migration_order (
+    id INT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL
+);
+INSERT INTO migration_order VALUES (1, 'first; with a semicolon');
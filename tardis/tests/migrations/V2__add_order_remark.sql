@@ -0,0 +1 @@
+ALTER TABLE migration_order ADD COLUMN remark VARCHAR(255) NOT NULL DEFAULT '';
@@ -17,6 +17,7 @@ async fn test_basic_dto() -> TardisResult<()> {
         owner: "".to_string(),
         roles: vec![],
         groups: vec![],
+        request_id: "".to_string(),
         ext: Arc::new(RwLock::new(HashMap::new())),
         sync_task_fns: Arc::new(Mutex::new(Vec::new())),
         async_task_fns: Arc::new(Mutex::new(Vec::new())),
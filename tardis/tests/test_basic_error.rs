@@ -0,0 +1,27 @@
+use std::error::Error;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::TardisFuns;
+
+#[tokio::test]
+async fn test_basic_error() -> TardisResult<()> {
+    // source
+    let error: TardisError = "abc".parse::<i32>().unwrap_err().into();
+    assert!(error.code.starts_with("406"));
+    assert_eq!(error.source().map(|source| source.to_string()), Some("invalid digit found in string".to_string()));
+    let error = TardisError::not_found("todo not found", "404-todo-not-found");
+    assert!(error.source().is_none());
+
+    // extensions
+    let error = TardisError::bad_request("todo is invalid", "400-todo-invalid").with_extension("code", "must not be empty").with_source(std::fmt::Error);
+    assert_eq!(error.extensions.get("code").map(String::as_str), Some("must not be empty"));
+    let json = TardisFuns::json.obj_to_string(&error)?;
+    assert_eq!(json, r#"{"code":"400","message":"todo is invalid","extensions":{"code":"must not be empty"}}"#);
+    let error = TardisFuns::json.str_to_obj::<TardisError>(&json)?;
+    assert_eq!(error.extensions.get("code").map(String::as_str), Some("must not be empty"));
+    assert!(error.source.is_none());
+    let json = TardisFuns::json.obj_to_string(&TardisError::not_found("todo not found", "404-todo-not-found"))?;
+    assert_eq!(json, r#"{"code":"404","message":"todo not found"}"#);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::error_report;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, SentryConfig, TardisConfig};
+use tardis::TardisFuns;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_error_report() -> TardisResult<()> {
+    // a fake sentry endpoint
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    TardisFuns::init_conf(
+        TardisConfig::builder().fw(FrameworkConfig::builder().sentry(SentryConfig::builder().dsn(format!("http://public@127.0.0.1:{port}/1")).build()).build()).build(),
+    )
+    .await?;
+
+    // the errors are not reported when created
+    let _ = TardisError::internal_error("error report not returned", "");
+    // below the min code
+    error_report::report(&TardisError::bad_request("error report ignored", ""));
+    error_report::report(&TardisError::wrap("error report unknown code", ""));
+    error_report::report(&TardisError::internal_error("error report captured", ""));
+
+    let (mut socket, _) = tokio::time::timeout(Duration::from_secs(10), listener.accept()).await.expect("timeout")?;
+    let mut request = Vec::new();
+    let mut buf = [0; 4096];
+    while !String::from_utf8_lossy(&request).contains("error report captured") {
+        let size = tokio::time::timeout(Duration::from_secs(10), socket.read(&mut buf)).await.expect("timeout")?;
+        assert!(size > 0, "event not reported");
+        request.extend_from_slice(&buf[..size]);
+    }
+    socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await?;
+    let request = String::from_utf8_lossy(&request);
+    assert!(request.contains("\"code\":\"500\""));
+    assert!(!request.contains("error report ignored"));
+    assert!(!request.contains("error report unknown code"));
+    assert!(!request.contains("error report not returned"));
+    Ok(())
+}
@@ -1,10 +1,45 @@
-use tardis::basic::field::TrimString;
+use tardis::basic::field::{FieldRegion, MaskedEmail, MaskedPhone, NanoidAlphabet, TrimString};
 use tardis::basic::result::TardisResult;
+use tardis::regex::Regex;
+use tardis::serde::{Deserialize, Serialize};
 use tardis::TardisFuns;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_basic_field() -> TardisResult<()> {
     assert!(TardisFuns::field.is_phone("18657120202"));
+    assert!(TardisFuns::field.is_phone_by_region("18657120202", FieldRegion::CN));
+    assert!(TardisFuns::field.is_phone_by_region("91234567", FieldRegion::HK));
+    assert!(!TardisFuns::field.is_phone_by_region("18657120202", FieldRegion::HK));
+    assert!(TardisFuns::field.is_phone_by_region("0912345678", FieldRegion::TW));
+    assert!(TardisFuns::field.is_phone_by_region("2025550123", FieldRegion::US));
+    assert!(!TardisFuns::field.is_phone_by_region("1025550123", FieldRegion::US));
+
+    assert!(TardisFuns::field.is_id_card_by_region("11010519491231002X", FieldRegion::CN));
+    assert!(TardisFuns::field.is_id_card_by_region("11010519491231002x", FieldRegion::CN));
+    assert!(!TardisFuns::field.is_id_card_by_region("110105194912310021", FieldRegion::CN));
+    assert!(!TardisFuns::field.is_id_card_by_region("110105194913310021", FieldRegion::CN));
+    assert!(TardisFuns::field.is_id_card_by_region("A123456(7)", FieldRegion::HK));
+    assert!(TardisFuns::field.is_id_card_by_region("A123456789", FieldRegion::TW));
+    assert!(TardisFuns::field.is_id_card_by_region("123-45-6789", FieldRegion::US));
+    assert!(!TardisFuns::field.is_id_card_by_region("666-45-6789", FieldRegion::US));
+    assert!(TardisFuns::field.is_id_card_by_region("S1234567D", FieldRegion::SG));
+
+    assert!(TardisFuns::field.is_ipv4("192.168.0.1"));
+    assert!(!TardisFuns::field.is_ipv4("192.168.0.256"));
+    assert!(TardisFuns::field.is_ipv6("fe80::1"));
+    assert!(!TardisFuns::field.is_ipv6("192.168.0.1"));
+    assert!(TardisFuns::field.is_ip("192.168.0.1"));
+    assert!(TardisFuns::field.is_ip("::1"));
+    assert!(TardisFuns::field.is_url("https://idealworld.group/a?b=1"));
+    assert!(!TardisFuns::field.is_url("ftp://idealworld.group"));
+    assert!(!TardisFuns::field.is_url("idealworld.group"));
+    assert!(TardisFuns::field.is_mac("00:1A:2b:3C:4d:5E"));
+    assert!(TardisFuns::field.is_mac("00-1A-2B-3C-4D-5E"));
+    assert!(!TardisFuns::field.is_mac("00:1A-2B:3C:4D:5E"));
+    assert!(TardisFuns::field.is_date("2023-02-28", "%Y-%m-%d"));
+    assert!(!TardisFuns::field.is_date("2023-02-29", "%Y-%m-%d"));
+    assert!(TardisFuns::field.is_datetime("2023/02/28 23:59:59", "%Y/%m/%d %H:%M:%S"));
+    assert!(!TardisFuns::field.is_datetime("2023/02/28", "%Y/%m/%d %H:%M:%S"));
 
     assert_eq!(TardisFuns::field.incr_by_base36("abcd1").unwrap(), "abcd2");
     assert_eq!(TardisFuns::field.incr_by_base36("abcd12").unwrap(), "abcd13");
@@ -31,6 +66,36 @@ async fn test_basic_field() -> TardisResult<()> {
     assert!(!TardisFuns::field.is_code_ncs("Adw834_dfds"));
     assert_eq!(TardisFuns::field.nanoid().len(), 21);
     assert_eq!(TardisFuns::field.nanoid_len(4).len(), 4);
+    assert_eq!(TardisFuns::field.nanoid_by(10, NanoidAlphabet::UrlSafe)?.len(), 10);
+    assert!(TardisFuns::field.nanoid_by(32, NanoidAlphabet::Lowercase)?.chars().all(|c| c.is_ascii_digit() || c.is_ascii_lowercase()));
+    assert!(TardisFuns::field.nanoid_by(32, NanoidAlphabet::NoLookalike)?.chars().all(|c| !"0Oo1IilL5Ss2Zzuv".contains(c)));
+    assert_eq!(TardisFuns::field.nanoid_by(6, NanoidAlphabet::Custom(&['x']))?, "xxxxxx");
+    assert_eq!(TardisFuns::field.nanoid_by(0, NanoidAlphabet::UrlSafe).unwrap_err().code, "400");
+    assert_eq!(TardisFuns::field.nanoid_by(6, NanoidAlphabet::Custom(&[])).unwrap_err().code, "400");
+    let oversized = (0..256).map(|i| char::from_u32(0x4e00 + i).unwrap()).collect::<Vec<_>>();
+    assert_eq!(TardisFuns::field.nanoid_by(6, NanoidAlphabet::Custom(&oversized)).unwrap_err().code, "400");
+    assert_eq!(TardisFuns::field.nanoid_by(6, NanoidAlphabet::Custom(&oversized[..255]))?.chars().count(), 6);
+
+    assert_eq!(TardisFuns::field.mask_phone("18657120202"), "186****0202");
+    assert_eq!(TardisFuns::field.mask_phone("1234"), "1***");
+    assert_eq!(TardisFuns::field.mask_email("gudaoxuri@gmail.com"), "g*******i@gmail.com");
+    assert_eq!(TardisFuns::field.mask_email("ab@gmail.com"), "a*@gmail.com");
+    assert_eq!(TardisFuns::field.mask_id_card("11010519491231002X"), "110***********002X");
+    assert_eq!(TardisFuns::field.mask_keep("张三丰", 1, 0), "张**");
+    assert_eq!(TardisFuns::field.mask_custom("6222021234567890", &Regex::new(r"^\w{2}(\w+)\w{2}$")?), "62************90");
+    assert_eq!(TardisFuns::field.mask_custom("tel:123,tel:456", &Regex::new(r"tel:(\d)\d(\d)")?), "tel:*2*,tel:*5*");
+    assert_eq!(TardisFuns::field.mask_custom("no match", &Regex::new(r"(\d+)")?), "no match");
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct AccountResp {
+        phone: MaskedPhone,
+        email: MaskedEmail,
+    }
+    let account = TardisFuns::json.str_to_obj::<AccountResp>(r#"{"phone":"18657120202","email":"gudaoxuri@gmail.com"}"#)?;
+    assert_eq!(&*account.phone, "18657120202");
+    assert_eq!(account.email.to_string(), "g*******i@gmail.com");
+    assert_eq!(format!("{account:?}"), r#"AccountResp { phone: "186****0202", email: "g*******i@gmail.com" }"#);
+    assert_eq!(TardisFuns::json.obj_to_string(&account)?, r#"{"phone":"186****0202","email":"g*******i@gmail.com"}"#);
 
     let ts = TrimString::new(" a ".to_string());
     assert_eq!(&*ts, "a");
@@ -0,0 +1,81 @@
+use std::collections::{HashMap, HashSet};
+
+use tardis::basic::field::HtmlSanitizePolicy;
+use tardis::basic::result::TardisResult;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_html() -> TardisResult<()> {
+    let policy = HtmlSanitizePolicy::default();
+    assert_eq!(
+        TardisFuns::field.sanitize_html(r#"<p onclick="x()">hi<script>alert(1)</script></p>"#, &policy)?,
+        "<p>hi</p>"
+    );
+    assert_eq!(
+        TardisFuns::field.sanitize_html(r#"<a href="javascript:alert(1)">a</a><a href="https://idealworld.group" target="_blank">b</a>"#, &policy)?,
+        r#"<a rel="noopener noreferrer">a</a><a href="https://idealworld.group" rel="noopener noreferrer">b</a>"#
+    );
+    assert_eq!(
+        TardisFuns::field.sanitize_html(r#"<img src="x.png" onerror="alert(1)"><!-- c -->"#, &policy)?,
+        r#"<img src="x.png">"#
+    );
+
+    assert_eq!(TardisFuns::field.sanitize_html("<p><b>bold</b> text</p>", &HtmlSanitizePolicy::text_only())?, "bold text");
+
+    let mut policy = HtmlSanitizePolicy::text_only();
+    policy.tags = HashSet::from(["span".to_string()]);
+    policy.generic_attributes = HashSet::from(["class".to_string()]);
+    assert_eq!(
+        TardisFuns::field.sanitize_html(r#"<span class="mark" style="color:red">x</span><p>y</p>"#, &policy)?,
+        r#"<span class="mark">x</span>y"#
+    );
+
+    let mut policy = HtmlSanitizePolicy::text_only();
+    policy.tags = HashSet::from(["span".to_string()]);
+    policy.allowed_classes = HashMap::from([("span".to_string(), HashSet::from(["mark".to_string()]))]);
+    assert_eq!(
+        TardisFuns::field.sanitize_html(r#"<span class="mark other">x</span>"#, &policy)?,
+        r#"<span class="mark">x</span>"#
+    );
+
+    // the policies that ammonia panics on are rejected
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.tags.insert("script".to_string());
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.tags.insert("style".to_string());
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.tag_attributes.get_mut("a").unwrap().insert("rel".to_string());
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.generic_attributes.insert("rel".to_string());
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.tag_attributes.insert("script".to_string(), HashSet::from(["src".to_string()]));
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.tag_attributes.insert("style".to_string(), HashSet::from(["media".to_string()]));
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    for tag in ["animate", "animateTransform", "animateMotion", "animateColor", "set"] {
+        let mut policy = HtmlSanitizePolicy::default();
+        policy.tags.insert(tag.to_string());
+        assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    }
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.allowed_classes.insert("span".to_string(), HashSet::from(["mark".to_string()]));
+    policy.generic_attributes.insert("class".to_string());
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.allowed_classes.insert("span".to_string(), HashSet::from(["mark".to_string()]));
+    policy.tag_attributes.insert("span".to_string(), HashSet::from(["class".to_string()]));
+    assert_eq!(TardisFuns::field.sanitize_html("<p>x</p>", &policy).unwrap_err().code, "400");
+    let mut policy = HtmlSanitizePolicy::default();
+    policy.generic_attributes.insert("rel".to_string());
+    policy.link_rel = None;
+    assert_eq!(
+        TardisFuns::field.sanitize_html(r#"<a href="https://idealworld.group" rel="nofollow">x</a>"#, &policy)?,
+        r#"<a href="https://idealworld.group" rel="nofollow">x</a>"#
+    );
+    Ok(())
+}
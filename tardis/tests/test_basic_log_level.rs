@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use tardis::basic::{result::TardisResult, tracing::TardisTracing};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_log_level() -> TardisResult<()> {
+    let tracing = TardisTracing::initializer().with_fmt_layer().with_env_layer().init();
+
+    tracing.set_level("tardis", "debug")?;
+    tracing.set_level("tardis", "trace")?;
+    let directives = tracing.config()?.directives.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    assert_eq!(directives, vec!["tardis=trace".to_string()]);
+
+    tracing.set_level("", "warn")?;
+    assert_eq!(tracing.config()?.level.to_string(), "warn");
+    assert!(tracing.set_level("tardis", "not-a-level").is_err());
+
+    tracing.reset_level("tardis")?;
+    assert!(tracing.config()?.directives.is_empty());
+
+    // restore after duration
+    tracing.set_level("sqlx", "info")?;
+    tracing.set_level_for("sqlx", "trace", Duration::from_millis(100))?;
+    tracing.set_level_for("app", "debug", Duration::from_millis(100))?;
+    assert!(tracing.config()?.directives.iter().any(|d| d.to_string() == "sqlx=trace"));
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let directives = tracing.config()?.directives.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    assert_eq!(directives, vec!["sqlx=info".to_string()]);
+
+    // reload the whole filter
+    TardisFuns::log_reload("debug, tardis::cache=trace,sqlx=warn")?;
+    assert_eq!(tracing.config()?.level.to_string(), "debug");
+    let directives = tracing.config()?.directives.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    assert_eq!(directives, vec!["tardis::cache=trace".to_string(), "sqlx=warn".to_string()]);
+    TardisFuns::log_reload("tardis=info")?;
+    assert_eq!(tracing.config()?.level.to_string(), "debug");
+    assert_eq!(tracing.config()?.directives.len(), 1);
+    // invalid filters are not applied
+    assert!(TardisFuns::log_reload("info,tardis=not-a-level").is_err());
+    assert_eq!(tracing.config()?.directives[0].to_string(), "tardis=info");
+
+    // the pending restore is skipped once the level changes again
+    tracing.set_level_for("app", "trace", Duration::from_millis(100))?;
+    tracing.set_level("app", "warn")?;
+    tracing.set_level_for("tardis", "trace", Duration::from_millis(100))?;
+    TardisFuns::log_reload("debug,tardis=error")?;
+    tracing.set_level_for("sqlx", "trace", Duration::from_millis(100))?;
+    tracing.set_level_for("sqlx", "debug", Duration::from_secs(60))?;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let directives = tracing.config()?.directives.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    assert_eq!(directives, vec!["tardis=error".to_string(), "sqlx=debug".to_string()]);
+    Ok(())
+}
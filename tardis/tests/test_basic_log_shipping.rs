@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use tardis::basic::log_shipping::LogShippingLayer;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FluentdConfig, LogShippingBatchConfig};
+use tokio::io::AsyncReadExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_log_shipping() -> TardisResult<()> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let layer = LogShippingLayer::fluentd(
+        &FluentdConfig::builder().addr(addr.to_string()).tag("tardis.test").batch(LogShippingBatchConfig::builder().size(2).interval_ms(100).build()).build(),
+    )?;
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+        tracing::info!(user = "u1", "hello");
+        tracing::warn!("world");
+    });
+
+    // the connection is closed after the queued events are sent, since the layer is dropped
+    let (mut socket, _) = listener.accept().await?;
+    let mut buf = Vec::new();
+    tokio::time::timeout(std::time::Duration::from_secs(10), socket.read_to_end(&mut buf)).await.expect("timeout")?;
+    let (tag, entries): (String, Vec<(u64, HashMap<String, String>)>) = rmp_serde::from_slice(&buf).expect("invalid forward message");
+    assert_eq!(tag, "tardis.test");
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].1.get("message").map(String::as_str), Some("hello"));
+    assert_eq!(entries[0].1.get("level").map(String::as_str), Some("info"));
+    assert_eq!(entries[0].1.get("user").map(String::as_str), Some("u1"));
+    assert_eq!(entries[1].1.get("message").map(String::as_str), Some("world"));
+    assert_eq!(entries[1].1.get("level").map(String::as_str), Some("warn"));
+    Ok(())
+}
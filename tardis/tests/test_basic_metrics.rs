@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::config::config_dto::CacheModuleConfig;
+use tardis::prometheus::IntCounter;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_metrics() -> TardisResult<()> {
+    TardisFuns::metrics().record_client("custom", "call", true, Duration::from_millis(10));
+    let text = TardisFuns::metrics().export()?;
+    assert!(text.contains(r#"tardis_client_requests_total{client="custom",operation="call",status="ok"} 1"#));
+    assert!(text.contains("tardis_client_request_duration_seconds_bucket"));
+
+    // built-in clients are recorded automatically
+    let cache_module_config = CacheModuleConfig::builder().url("redis://127.0.0.1:1/0".parse().expect("invalid url")).build();
+    let client = TardisCacheClient::init(&cache_module_config).await?;
+    assert!(client.get("test_key").await.is_err());
+    let text = TardisFuns::metrics().export()?;
+    assert!(text.contains(r#"tardis_client_requests_total{client="cache",operation="get",status="error"} 1"#));
+
+    // custom metrics
+    let counter = IntCounter::new("app_custom_total", "custom counter")?;
+    TardisFuns::metrics().register(Box::new(counter.clone()))?;
+    counter.inc();
+    assert!(TardisFuns::metrics().export()?.contains("app_custom_total 1"));
+    assert!(TardisFuns::metrics().register(Box::new(counter)).is_err());
+    Ok(())
+}
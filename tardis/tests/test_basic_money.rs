@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use tardis::basic::money::{Currency, Decimal, Money, RoundingStrategy};
+use tardis::basic::result::TardisResult;
+use tardis::web::poem_openapi::types::ParseFromJSON;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_money() -> TardisResult<()> {
+    let price = Money::parse("19.99", Currency::CNY)?;
+    assert_eq!(price, Money::from_minor(1999, Currency::CNY));
+    assert_eq!(price.to_minor()?, 1999);
+    assert!(Money::parse("19.999", Currency::CNY).is_err());
+    assert!(Money::parse("1.5", Currency::JPY).is_err());
+    assert_eq!(Money::new(Decimal::from_str("19.990")?, Currency::CNY)?, price);
+    assert_eq!(
+        Money::new_with_rounding(Decimal::from_str("19.995")?, Currency::CNY, RoundingStrategy::MidpointAwayFromZero).to_minor()?,
+        2000
+    );
+    assert_eq!(
+        Money::new_with_rounding(Decimal::from_str("19.995")?, Currency::CNY, RoundingStrategy::MidpointNearestEven).to_minor()?,
+        2000
+    );
+    assert_eq!(
+        Money::new_with_rounding(Decimal::from_str("19.985")?, Currency::CNY, RoundingStrategy::MidpointNearestEven).to_minor()?,
+        1998
+    );
+    assert_eq!(
+        Money::new_with_rounding(Decimal::from_str("19.999")?, Currency::CNY, RoundingStrategy::ToZero).to_minor()?,
+        1999
+    );
+
+    let total = price.checked_mul(3.into(), RoundingStrategy::MidpointAwayFromZero)?;
+    assert_eq!(total.to_minor()?, 5997);
+    assert_eq!(total.checked_add(&price)?.to_minor()?, 7996);
+    assert_eq!(total.checked_sub(&price)?.to_minor()?, 3998);
+    assert!(total.checked_add(&Money::zero(Currency::USD)).is_err());
+    let discounted = price.checked_mul(Decimal::from_str("0.85")?, RoundingStrategy::MidpointAwayFromZero)?;
+    assert_eq!(discounted.to_minor()?, 1699);
+    assert_eq!(price.checked_div(3.into(), RoundingStrategy::ToZero)?.to_minor()?, 666);
+    assert!(price.checked_div(Decimal::ZERO, RoundingStrategy::ToZero).is_err());
+
+    let parts = Money::from_minor(100, Currency::CNY).allocate(&[1, 1, 1])?;
+    assert_eq!(parts.iter().map(|part| part.to_minor().unwrap()).collect::<Vec<_>>(), vec![34, 33, 33]);
+    let parts = Money::from_minor(-100, Currency::CNY).allocate(&[1, 0, 2])?;
+    assert_eq!(parts.iter().map(|part| part.to_minor().unwrap()).collect::<Vec<_>>(), vec![-34, 0, -66]);
+    assert!(price.allocate(&[0, 0]).is_err());
+    // the amounts near the limit of the decimal are out of range instead of panicking
+    let huge = Money::new(Decimal::MAX, Currency::CNY)?;
+    assert_eq!(huge.to_minor().unwrap_err().code, "400");
+    assert_eq!(huge.allocate(&[1, 1]).unwrap_err().code, "400");
+    let huge = TardisFuns::json.str_to_obj::<Money>(r#"{"amount":"79228162514264337593543950335","currency":"CNY"}"#)?;
+    assert!(huge.to_minor().is_err());
+
+    assert_eq!(Money::from_minor(123456789, Currency::CNY).format(), "¥1,234,567.89");
+    assert_eq!(Money::from_minor(-50, Currency::USD).format(), "-$0.50");
+    assert_eq!(Money::from_minor(1000000, Currency::JPY).format(), "JP¥1,000,000");
+    assert_eq!(Money::from_minor(100, Currency::CNY).to_string(), "1.00 CNY");
+
+    let json = TardisFuns::json.obj_to_string(&price)?;
+    assert_eq!(json, r#"{"amount":"19.99","currency":"CNY"}"#);
+    assert_eq!(TardisFuns::json.str_to_obj::<Money>(&json)?, price);
+    // the amounts exceeding the precision of the currency are rejected
+    assert!(TardisFuns::json.str_to_obj::<Money>(r#"{"amount":"19.999","currency":"CNY"}"#).is_err());
+    assert!(TardisFuns::json.str_to_obj::<Money>(r#"{"amount":"1.5","currency":"JPY"}"#).is_err());
+    assert_eq!(Money::parse_from_json(Some(TardisFuns::json.str_to_json(&json)?)).ok(), Some(price));
+    assert!(Money::parse_from_json(Some(TardisFuns::json.str_to_json(r#"{"amount":"19.999","currency":"CNY"}"#)?)).is_err());
+    Ok(())
+}
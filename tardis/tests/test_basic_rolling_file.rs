@@ -0,0 +1,55 @@
+use std::io::Write;
+
+use tardis::basic::result::TardisResult;
+use tardis::basic::rolling_file::RollingFileWriter;
+use tardis::basic::tracing::TardisTracing;
+use tardis::config::config_dto::{LogConfig, TracingAppenderConfig, TracingAppenderRotation};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_rolling_file() -> TardisResult<()> {
+    let dir = std::env::temp_dir().join(format!("tardis-rolling-{}", TardisFuns::field.nanoid()));
+    let config = TracingAppenderConfig::builder().dir(dir.clone()).filename("app.log").rotation(TracingAppenderRotation::Daily).max_size(100).max_files(2).compress(true).build();
+    let mut writer = RollingFileWriter::new(config);
+    for idx in 0..10 {
+        writer.write_all(format!("{idx:0>59}\n").as_bytes())?;
+    }
+    writer.flush()?;
+
+    let mut files = std::fs::read_dir(&dir)?.map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().to_string())).collect::<Result<Vec<_>, _>>()?;
+    files.sort();
+    assert_eq!(files.len(), 3);
+    assert!(files.contains(&"app.log".to_string()));
+    assert!(files.iter().filter(|file| file.starts_with("app.log.") && file.ends_with(".gz")).count() == 2);
+    assert_eq!(std::fs::read_to_string(dir.join("app.log"))?, format!("{:0>59}\n", 9));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_module_appender() -> TardisResult<()> {
+    let dir = std::env::temp_dir().join(format!("tardis-module-appender-{}", TardisFuns::field.nanoid()));
+    let tardis_tracing = TardisTracing::initializer().with_appender_layer().init();
+    let mut config = LogConfig::default();
+    config.tracing_appender = Some(TracingAppenderConfig::builder().dir(dir.clone()).filename("app.log").build());
+    config.module_appenders.insert(
+        "app::payment".to_string(),
+        TracingAppenderConfig::builder().dir(dir.clone()).filename("payment.log").build(),
+    );
+    tardis_tracing.update_config(&config)?;
+
+    tracing::info!(target: "app::payment::refund", "refund created");
+    tracing::info!(target: "app::payments", "payments listed");
+    tracing::info!(target: "app", "app started");
+
+    let payment = std::fs::read_to_string(dir.join("payment.log"))?;
+    assert!(payment.contains("refund created"));
+    assert!(!payment.contains("payments listed"));
+    assert!(!payment.contains("app started"));
+    let app = std::fs::read_to_string(dir.join("app.log"))?;
+    assert!(app.contains("refund created") && app.contains("payments listed") && app.contains("app started"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
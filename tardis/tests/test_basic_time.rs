@@ -0,0 +1,63 @@
+use tardis::basic::result::TardisResult;
+use tardis::chrono::{Duration, TimeZone, Utc};
+use tardis::config::config_dto::{AppConfig, FrameworkConfig, TardisConfig};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_time() -> TardisResult<()> {
+    let cst = TardisFuns::time.parse_offset("+08:00")?;
+    assert_eq!(cst, TardisFuns::time.parse_offset("+0800")?);
+    assert_eq!(cst, TardisFuns::time.parse_offset("+08")?);
+    assert_eq!(TardisFuns::time.parse_offset("-05:30")?.local_minus_utc(), -(5 * 3600 + 30 * 60));
+    assert_eq!(TardisFuns::time.parse_offset("UTC")?.local_minus_utc(), 0);
+    assert!(TardisFuns::time.parse_offset("08:00").is_err());
+    assert!(TardisFuns::time.parse_offset("+25:00").is_err());
+
+    let expected = Utc.with_ymd_and_hms(2023, 8, 1, 2, 0, 0).unwrap();
+    assert_eq!(TardisFuns::time.parse("2023-08-01T10:00:00+08:00")?, expected);
+    assert_eq!(TardisFuns::time.parse("Tue, 1 Aug 2023 10:00:00 +0800")?, expected);
+    assert_eq!(TardisFuns::time.parse_with_offset("2023-08-01 10:00:00", cst)?, expected);
+    assert_eq!(TardisFuns::time.parse_with_offset("2023/08/01 10:00:00", cst)?, expected);
+    assert_eq!(TardisFuns::time.parse_with_offset("2023-08-01T10:00:00.000", cst)?, expected);
+    assert_eq!(TardisFuns::time.parse_with_offset("2023-08-01 10:00", cst)?, expected);
+    assert_eq!(TardisFuns::time.parse_with_offset("20230801100000", cst)?, expected);
+    assert_eq!(TardisFuns::time.parse(&expected.timestamp().to_string())?, expected);
+    assert_eq!(TardisFuns::time.parse(&expected.timestamp_millis().to_string())?, expected);
+    assert_eq!(TardisFuns::time.parse_with_offset("2023-08-01", cst)?, Utc.with_ymd_and_hms(2023, 7, 31, 16, 0, 0).unwrap());
+    assert_eq!(TardisFuns::time.parse("20230801")?, Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap());
+    assert!(TardisFuns::time.parse("01-08-2023").is_err());
+
+    // 2023-08-01 02:00 UTC is already 2023-08-01 10:00 in +08:00, but 2023-07-31 21:00 in -05:00
+    let est = TardisFuns::time.parse_offset("-05:00")?;
+    assert_eq!(
+        TardisFuns::time.truncate_to_day_with_offset(expected, cst),
+        Utc.with_ymd_and_hms(2023, 7, 31, 16, 0, 0).unwrap()
+    );
+    assert_eq!(
+        TardisFuns::time.truncate_to_day_with_offset(expected, est),
+        Utc.with_ymd_and_hms(2023, 7, 31, 5, 0, 0).unwrap()
+    );
+    assert_eq!(TardisFuns::time.truncate_to_day(expected), Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap());
+    // 2023-08-01 is Tuesday
+    assert_eq!(
+        TardisFuns::time.truncate_to_week_with_offset(expected, cst),
+        Utc.with_ymd_and_hms(2023, 7, 30, 16, 0, 0).unwrap()
+    );
+    assert_eq!(TardisFuns::time.truncate_to_week(expected), Utc.with_ymd_and_hms(2023, 7, 31, 0, 0, 0).unwrap());
+
+    assert_eq!(TardisFuns::time.to_business(expected).to_rfc3339(), "2023-08-01T02:00:00+00:00");
+    assert_eq!(TardisFuns::time.to_utc(expected.with_timezone(&cst)), expected);
+
+    assert_eq!(TardisFuns::time.humanize_duration(Duration::seconds(90061)), "1d 1h 1m 1s");
+    assert_eq!(TardisFuns::time.humanize_duration(Duration::seconds(3600)), "1h");
+    assert_eq!(TardisFuns::time.humanize_duration(Duration::minutes(-5)), "-5m");
+    assert_eq!(TardisFuns::time.humanize_duration(Duration::milliseconds(300)), "300ms");
+    assert_eq!(TardisFuns::time.humanize_duration(Duration::zero()), "0ms");
+
+    // the invalid business timezone fails the initialization
+    let error =
+        TardisFuns::init_conf(TardisConfig::builder().fw(FrameworkConfig::builder().app(AppConfig::builder().timezone("GMT+8").build()).build()).build()).await.unwrap_err();
+    assert_eq!(error.code, "406");
+    assert!(error.message.contains("GMT+8"));
+    Ok(())
+}
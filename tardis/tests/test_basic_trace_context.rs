@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use tardis::basic::{result::TardisResult, tracing::TardisTracing};
+use tracing::Instrument;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_trace_context() -> TardisResult<()> {
+    TardisTracing::initializer().with_fmt_layer().with_env_layer().init();
+
+    let span = tracing::info_span!("trace_parent");
+    let span_id = span.id();
+    assert!(span_id.is_some());
+    let (current_id, current_name) =
+        async { TardisTracing::spawn_in_current_span(async { (tracing::Span::current().id(), tracing::Span::current().metadata().map(|meta| meta.name())) }).await.unwrap() }
+            .instrument(span.clone())
+            .await;
+    assert_eq!(current_id, span_id);
+    assert_eq!(current_name, Some("trace_parent"));
+
+    // not in a span
+    let current_id = TardisTracing::spawn_in_current_span(async { tracing::Span::current().id() }).await.unwrap();
+    assert!(current_id.is_none());
+
+    // inject and extract should never fail, even without trace context
+    let mut carrier = HashMap::new();
+    span.in_scope(|| TardisTracing::inject_context(&mut carrier));
+    let child = tracing::info_span!("trace_child");
+    TardisTracing::set_parent_from(&child, &carrier);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use opentelemetry::sdk::trace::ShouldSample;
+use opentelemetry::trace::{SamplingDecision, SpanContext, SpanId, SpanKind, TraceContextExt, TraceFlags, TraceId, TraceState};
+use opentelemetry::{Context, KeyValue};
+use tardis::basic::result::TardisResult;
+use tardis::basic::tracing::TardisSampler;
+use tardis::config::config_dto::{TracingConfig, TracingSampler};
+
+fn sample(sampler: &TardisSampler, parent: Option<&Context>, target: &str) -> SamplingDecision {
+    sampler
+        .should_sample(
+            parent,
+            TraceId::from_u128(1),
+            "http_request",
+            &SpanKind::Server,
+            &[KeyValue::new("http.target", target.to_string())],
+            &[],
+        )
+        .decision
+}
+
+fn parent(sampled: bool) -> Context {
+    let flags = if sampled { TraceFlags::SAMPLED } else { TraceFlags::default() };
+    Context::new().with_remote_span_context(SpanContext::new(TraceId::from_u128(1), SpanId::from_u64(1), flags, true, TraceState::default()))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basic_tracing_sampler() -> TardisResult<()> {
+    let sampler = TardisSampler::new(
+        &TracingConfig::builder().sampler(TracingSampler::AlwaysOff).route_rate_limits(HashMap::from([("/api".to_string(), 1000.0), ("/api/health".to_string(), 1.0)])).build(),
+    );
+    // parent based
+    assert_eq!(sample(&sampler, Some(&parent(true)), "/other"), SamplingDecision::RecordAndSample);
+    assert_eq!(sample(&sampler, Some(&parent(false)), "/api"), SamplingDecision::Drop);
+    // rate limited by the longest matched route
+    assert_eq!(sample(&sampler, None, "/api/health"), SamplingDecision::RecordAndSample);
+    assert_eq!(sample(&sampler, None, "/api/health"), SamplingDecision::Drop);
+    assert_eq!(sample(&sampler, None, "/api/orders"), SamplingDecision::RecordAndSample);
+    assert_eq!(sample(&sampler, None, "/api/orders"), SamplingDecision::RecordAndSample);
+    // fallback to the sampler
+    assert_eq!(sample(&sampler, None, "/other"), SamplingDecision::Drop);
+
+    let sampler = TardisSampler::new(&TracingConfig::builder().sampler(TracingSampler::AlwaysOff).parent_based(false).build());
+    assert_eq!(sample(&sampler, Some(&parent(true)), "/other"), SamplingDecision::Drop);
+    Ok(())
+}
@@ -133,6 +133,128 @@ async fn test_cache_client() -> TardisResult<()> {
         let max: i64 = u32::MAX.into();
         assert!(!client.setbit("bit", max.try_into()?, true).await?);
 
+        // lock
+        let guard = client.lock("lock", Duration::from_millis(300)).await?.unwrap();
+        assert!(client.lock("lock", Duration::from_millis(300)).await?.is_none());
+        assert!(guard.unlock().await?);
+        let guard = client.lock("lock", Duration::from_millis(100)).await?.unwrap();
+        sleep(Duration::from_millis(150)).await;
+        let guard2 = client.lock_wait("lock", Duration::from_millis(300), Duration::from_millis(100)).await?.unwrap();
+        assert!(!guard.unlock().await?);
+        drop(guard2);
+        sleep(Duration::from_millis(100)).await;
+        assert!(!client.exists("lock").await?);
+        let guard = client.lock("lock", Duration::from_millis(300)).await?.unwrap().auto_renew();
+        sleep(Duration::from_millis(700)).await;
+        assert_eq!(client.get("lock").await?.unwrap(), guard.token());
+        assert!(guard.unlock().await?);
+
+        // pub/sub
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let subscription = client
+            .subscribe("news", move |message| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(message).expect("send error");
+                    Ok(())
+                }
+            })
+            .await?;
+        let (ptx, mut prx) = tokio::sync::mpsc::unbounded_channel();
+        let psubscription = client
+            .psubscribe("news*", move |message| {
+                let ptx = ptx.clone();
+                async move {
+                    ptx.send(message).expect("send error");
+                    Ok(())
+                }
+            })
+            .await?;
+        assert_eq!(client.publish("news", "m1").await?, 2);
+        assert_eq!(client.publish("news.sport", "m2").await?, 1);
+        assert_eq!(rx.recv().await.unwrap(), ("news".to_string(), "m1".to_string()));
+        assert_eq!(prx.recv().await.unwrap(), ("news".to_string(), "m1".to_string()));
+        assert_eq!(prx.recv().await.unwrap(), ("news.sport".to_string(), "m2".to_string()));
+        subscription.unsubscribe();
+        psubscription.unsubscribe();
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(client.publish("news", "m3").await?, 0);
+
+        // sorted set operations
+        assert!(client.zadd("board", "u1", 10.0).await?);
+        assert!(!client.zadd("board", "u1", 20.0).await?);
+        assert_eq!(client.zadd_members("board", &[(5.0, "u2"), (30.0, "u3"), (20.0, "u1")]).await?, 2);
+        assert_eq!(client.zincr("board", "u2", 20.0).await?, 25.0);
+        assert_eq!(client.zcard("board").await?, 3);
+        assert_eq!(client.zscore("board", "u2").await?, Some(25.0));
+        assert_eq!(client.zscore("board", "u9").await?, None);
+        assert_eq!(client.zrank("board", "u1").await?, Some(0));
+        assert_eq!(client.zrevrank("board", "u1").await?, Some(2));
+        assert_eq!(client.zrank("board", "u9").await?, None);
+        assert_eq!(client.zrange("board", 0, -1).await?, vec!["u1", "u2", "u3"]);
+        assert_eq!(client.zrange("board", -2, 10).await?, vec!["u2", "u3"]);
+        assert_eq!(client.zrevrange("board", 0, 1).await?, vec!["u3", "u2"]);
+        assert_eq!(client.zrevrange_withscores("board", 0, 0).await?, vec![("u3".to_string(), 30.0)]);
+        assert_eq!(client.zrange_withscores("board", 0, 0).await?, vec![("u1".to_string(), 20.0)]);
+        assert_eq!(client.zrangebyscore("board", 20.0, 25.0).await?, vec!["u1", "u2"]);
+        assert_eq!(client.zrangebyscore_withscores("board", 26.0, f64::INFINITY).await?, vec![("u3".to_string(), 30.0)]);
+        assert_eq!(client.zrangebyscore_limit("board", f64::NEG_INFINITY, f64::INFINITY, 1, 1).await?, vec!["u2"]);
+        assert_eq!(client.zrangebyscore_limit("board", f64::NEG_INFINITY, f64::INFINITY, 1, -1).await?, vec!["u2", "u3"]);
+        assert_eq!(client.zcount("board", 0.0, 25.0).await?, 2);
+        assert!(client.zrem("board", "u3").await?);
+        assert!(!client.zrem("board", "u3").await?);
+        assert_eq!(client.zremrangebyscore("board", 0.0, 20.0).await?, 1);
+        assert_eq!(client.zrange("board", 0, -1).await?, vec!["u2"]);
+        assert!(client.zrem("board", "u2").await?);
+        assert!(!client.exists("board").await?);
+
+        // pipeline
+        let (count, expired, value): (isize, bool, Option<String>) = client.pipeline().set("p1", "v1").ignore().incr("p_incr", 2).expire("p_incr", 10).get("p1").query().await?;
+        assert_eq!((count, expired, value.as_deref()), (2, true, Some("v1")));
+        let (hset, hincr, llen): (bool, isize, usize) = client.atomic().hset("p_hash", "f1", "v1").hincr("p_hash", "f2", 3).lpush("p_list", "v1").query().await?;
+        assert_eq!((hset, hincr, llen), (true, 3, 1));
+        let values: Vec<String> = client.pipeline().cmd(tardis::cache::cmd("PING")).hget("p_hash", "f1").query().await?;
+        assert_eq!(values, vec!["PONG".to_string(), "v1".to_string()]);
+        client.atomic().del("p1").del("p_incr").del("p_hash").del("p_list").execute().await?;
+        assert!(!client.exists("p_hash").await?);
+
+        // stream
+        let id1 = client.xadd("orders", &[("order_id", "1")]).await?;
+        client.xadd("orders", &[("order_id", "2")]).await?;
+        assert_eq!(client.xlen("orders").await?, 2);
+        let streams = client.xread(&[("orders", "0")], 10, None).await?;
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].1[0].id, id1);
+        assert_eq!(streams[0].1[1].get("order_id"), Some("2"));
+        assert!(client.xread(&[("orders", "$")], 10, Some(Duration::from_millis(100))).await?.is_empty());
+
+        assert!(client.xgroup_create("orders", "billing", "0").await?);
+        assert!(!client.xgroup_create("orders", "billing", "0").await?);
+        let entries = client.xreadgroup("orders", "billing", "c1", 1, None).await?;
+        assert_eq!(entries[0].id, id1);
+        // not acknowledged, reclaimed by another consumer
+        sleep(Duration::from_millis(100)).await;
+        let (_, entries) = client.xautoclaim("orders", "billing", "c2", Duration::from_millis(50), "0-0", 10).await?;
+        assert_eq!(entries[0].id, id1);
+        assert_eq!(client.xack("orders", "billing", &[&id1]).await?, 1);
+        assert_eq!(client.xack("orders", "billing", &[&id1]).await?, 0);
+        assert!(client.xgroup_destroy("orders", "billing").await?);
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let consumer = client
+            .stream_consume("orders", "shipping", "c1", move |entry| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(entry.get("order_id").unwrap_or_default().to_string()).expect("send error");
+                    Ok(())
+                }
+            })
+            .await?;
+        client.xadd("orders", &[("order_id", "3")]).await?;
+        assert_eq!(rx.recv().await.unwrap(), "3");
+        consumer.unsubscribe();
+        client.del("orders").await?;
+
         // custom
 
         let mut _s: bool = client.cmd().await?.sadd("s1", "m1").await?;
@@ -0,0 +1,207 @@
+use std::env;
+
+use tokio::time::{sleep, Duration};
+
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::config::config_dto::{CacheConfig, CacheKind, CacheModuleConfig, FrameworkConfig, TardisConfig};
+use tardis::serde::{Deserialize, Serialize};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cache_memory() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    let client = TardisCacheClient::memory();
+
+    // basic operations
+    assert_eq!(client.get("test_key").await?, None);
+    client.set("test_key", "测试").await?;
+    assert_eq!(client.get("test_key").await?.unwrap(), "测试");
+    assert!(!client.set_nx("test_key", "测试2").await?);
+    assert!(client.set_nx("test_key_nx", "测试2").await?);
+    assert_eq!(client.getset("test_key_nx", "测试3").await?.unwrap(), "测试2");
+    assert_eq!(client.incr("incr", 2).await?, 2);
+    assert_eq!(client.incr("incr", -3).await?, -1);
+    assert!(client.incr("test_key", 1).await.is_err());
+    client.del("incr").await?;
+    assert!(!client.exists("incr").await?);
+
+    // expiration
+    client.set_ex("test_key_ex", "测试", 1).await?;
+    client.expire("test_key_nx", 1).await?;
+    assert_eq!(client.ttl("test_key_ex").await?, 1);
+    assert_eq!(client.ttl("test_key").await?, -1_isize as usize);
+    assert_eq!(client.ttl("test_key_none").await?, -2_isize as usize);
+    client.expire_at("test_key_xp", 1893430861).await?;
+    client.set("test_key_xp", "测试").await?;
+    client.expire_at("test_key_xp", 1893430861).await?;
+    assert!(client.ttl("test_key_xp").await? > 0);
+    sleep(Duration::from_millis(1200)).await;
+    assert_eq!(client.get("test_key_ex").await?, None);
+    assert!(!client.exists("test_key_nx").await?);
+    assert!(client.exists("test_key").await?);
+
+    // list operations
+    client.lpush("l", "v1").await?;
+    client.lpush("l", "v2").await?;
+    client.rpush("l", "v1").await?;
+    assert_eq!(client.lrangeall("l").await?, vec!["v2", "v1", "v1"]);
+    assert_eq!(client.lrem("l", -1, "v1").await?, 1);
+    assert_eq!(client.llen("l").await?, 2);
+    assert!(client.get("l").await.is_err());
+
+    // hash operations
+    client.hset("h", "f1", "v1").await?;
+    client.hset("h", "f2", "v2").await?;
+    client.hdel("h", "f1").await?;
+    assert!(client.hset_nx("h", "f0", "v0").await?);
+    assert!(!client.hset_nx("h", "f0", "v0").await?);
+    assert_eq!(client.hincr("h", "f3", 1).await?, 1);
+    assert_eq!(client.hkeys("h").await?, vec!["f2", "f0", "f3"]);
+    assert_eq!(client.hvals("h").await?, vec!["v2", "v0", "1"]);
+    assert_eq!(client.hgetall("h").await?.get("f0").unwrap(), "v0");
+    assert_eq!(client.hlen("h").await?, 3);
+
+    // sorted set operations
+    assert!(client.zadd("board", "u1", 10.0).await?);
+    assert!(!client.zadd("board", "u1", 20.0).await?);
+    assert_eq!(client.zadd_members("board", &[(5.0, "u2"), (30.0, "u3"), (20.0, "u1")]).await?, 2);
+    assert_eq!(client.zincr("board", "u2", 20.0).await?, 25.0);
+    assert_eq!(client.zcard("board").await?, 3);
+    assert_eq!(client.zscore("board", "u2").await?, Some(25.0));
+    assert_eq!(client.zscore("board", "u9").await?, None);
+    assert_eq!(client.zrank("board", "u1").await?, Some(0));
+    assert_eq!(client.zrevrank("board", "u1").await?, Some(2));
+    assert_eq!(client.zrank("board", "u9").await?, None);
+    assert_eq!(client.zrange("board", 0, -1).await?, vec!["u1", "u2", "u3"]);
+    assert_eq!(client.zrange("board", -2, 10).await?, vec!["u2", "u3"]);
+    assert_eq!(client.zrevrange("board", 0, 1).await?, vec!["u3", "u2"]);
+    assert_eq!(client.zrevrange_withscores("board", 0, 0).await?, vec![("u3".to_string(), 30.0)]);
+    assert_eq!(client.zrange_withscores("board", 0, 0).await?, vec![("u1".to_string(), 20.0)]);
+    assert_eq!(client.zrangebyscore("board", 20.0, 25.0).await?, vec!["u1", "u2"]);
+    assert_eq!(client.zrangebyscore_withscores("board", 26.0, f64::INFINITY).await?, vec![("u3".to_string(), 30.0)]);
+    assert_eq!(client.zrangebyscore_limit("board", f64::NEG_INFINITY, f64::INFINITY, 1, 1).await?, vec!["u2"]);
+    assert_eq!(client.zrangebyscore_limit("board", f64::NEG_INFINITY, f64::INFINITY, 1, -1).await?, vec!["u2", "u3"]);
+    assert_eq!(client.zcount("board", 0.0, 25.0).await?, 2);
+    assert!(client.zrem("board", "u3").await?);
+    assert!(!client.zrem("board", "u3").await?);
+    assert!(client.zmove("board", "archived", "u1", 1.0).await?);
+    assert!(!client.zmove("board", "archived", "u1", 2.0).await?);
+    assert_eq!(client.zscore("archived", "u1").await?, Some(1.0));
+    assert!(client.zmove("archived", "board", "u1", 20.0).await?);
+    assert!(!client.exists("archived").await?);
+    assert_eq!(client.zremrangebyscore("board", 0.0, 20.0).await?, 1);
+    assert_eq!(client.zrange("board", 0, -1).await?, vec!["u2"]);
+    assert!(client.zrem("board", "u2").await?);
+    assert!(!client.exists("board").await?);
+
+    // bitmap operations
+    assert!(!client.setbit("bit", 1024, true).await?);
+    assert!(client.setbit("bit", 1024, true).await?);
+    assert!(!client.setbit("bit", 2048, true).await?);
+    assert!(client.getbit("bit", 2048).await?);
+    assert!(!client.getbit("bit", 3333).await?);
+    assert_eq!(client.bitcount("bit").await?, 2);
+    assert_eq!(client.bitcount_range_by_byte("bit", 1, 1023 / 8).await?, 0);
+    assert_eq!(client.bitcount_range_by_byte("bit", 1024 / 8, 2048 / 8).await?, 2);
+    assert_eq!(client.bitcount_range_by_bit("bit", 1025, 2048).await?, 1);
+
+    // typed operations
+    let user = CacheUser {
+        id: 1,
+        name: "张三".to_string(),
+        tags: vec!["a".to_string()],
+    };
+    client.set_obj("user", &user).await?;
+    assert_eq!(client.get("user").await?.unwrap(), r#"{"id":1,"name":"张三","tags":["a"]}"#);
+    assert_eq!(client.get_obj::<CacheUser>("user").await?.unwrap(), user);
+    assert_eq!(client.get_obj::<CacheUser>("user_none").await?, None);
+    assert!(client.get_obj::<u32>("user").await.is_err());
+    client.set_obj_ex("user_ex", &user, 1).await?;
+    assert_eq!(client.ttl("user_ex").await?, 1);
+    client.hset_obj("users", "1", &user).await?;
+    assert_eq!(client.hget_obj::<CacheUser>("users", "1").await?.unwrap(), user);
+    assert_eq!(client.hgetall_obj::<CacheUser>("users").await?.get("1").unwrap(), &user);
+
+    // lock
+    let guard = client.lock("lock", Duration::from_millis(300)).await?.unwrap();
+    assert!(client.lock("lock", Duration::from_millis(300)).await?.is_none());
+    assert!(client.lock_wait("lock", Duration::from_millis(300), Duration::from_millis(100)).await?.is_none());
+    assert!(guard.unlock().await?);
+    let guard = client.lock("lock", Duration::from_millis(100)).await?.unwrap();
+    sleep(Duration::from_millis(150)).await;
+    let guard2 = client.lock_wait("lock", Duration::from_millis(300), Duration::from_millis(100)).await?.unwrap();
+    assert!(!guard.unlock().await?);
+    assert!(guard2.extend(Duration::from_millis(300)).await?);
+    drop(guard2);
+    sleep(Duration::from_millis(50)).await;
+    assert!(!client.exists("lock").await?);
+    let guard = client.lock("lock", Duration::from_millis(300)).await?.unwrap().auto_renew();
+    sleep(Duration::from_millis(700)).await;
+    assert_eq!(client.get("lock").await?.unwrap(), guard.token());
+    assert!(guard.unlock().await?);
+
+    // pub/sub
+    let received = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let subscription = client
+        .psubscribe("news.?", move |message| {
+            let received = received_clone.clone();
+            async move {
+                received.lock().unwrap().push(message);
+                Ok(())
+            }
+        })
+        .await?;
+    assert_eq!(client.publish("news.1", "m1").await?, 1);
+    assert_eq!(client.publish("news.10", "m2").await?, 0);
+    subscription.unsubscribe();
+    assert_eq!(client.publish("news.2", "m3").await?, 0);
+    assert_eq!(*received.lock().unwrap(), vec![("news.1".to_string(), "m1".to_string())]);
+
+    // pipeline
+    let (count, expired, value, hset, llen): (isize, bool, Option<String>, bool, usize) =
+        client.pipeline().set("p1", "v1").ignore().incr("p_incr", 2).expire("p_incr", 10).get("p1").hset("p_hash", "f1", "v1").rpush("p_list", "v1").query().await?;
+    assert_eq!((count, expired, value.as_deref(), hset, llen), (2, true, Some("v1"), true, 1));
+    let values: Vec<Option<String>> = client.atomic().get("p1").get("p_none").query().await?;
+    assert_eq!(values, vec![Some("v1".to_string()), None]);
+    client.pipeline().del("p1").hdel("p_hash", "f1").execute().await?;
+    assert!(!client.exists("p1").await?);
+    assert!(client.pipeline().cmd(tardis::cache::cmd("PING")).execute().await.is_err());
+
+    // streams are not supported
+    assert!(client.xadd("orders", &[("order_id", "1")]).await.is_err());
+    assert!(client.stream_consume("orders", "billing", "c1", |_| async { Ok(()) }).await.is_err());
+
+    // custom commands are not supported
+    assert!(client.cmd().await.is_err());
+
+    client.flushdb().await?;
+    assert!(!client.exists("test_key").await?);
+
+    // Default test
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder().cache(CacheConfig::builder().default(CacheModuleConfig::builder().url("mem://".parse()?).build()).build()).build())
+            .build(),
+    )
+    .await?;
+    TardisFuns::cache().set("test_key", "测试").await?;
+    assert_eq!(TardisFuns::cache().get("test_key").await?.unwrap(), "测试");
+
+    // selected by the kind without Redis
+    let config: CacheModuleConfig = TardisFuns::json.str_to_obj(r#"{"url": "redis://127.0.0.1:6379/0", "kind": "memory"}"#)?;
+    assert_eq!(config.kind(), CacheKind::Memory);
+    let client = TardisCacheClient::init(&config).await?;
+    client.set_ex("test_key", "测试", 1).await?;
+    assert_eq!(client.get("test_key").await?.unwrap(), "测试");
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct CacheUser {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+}
@@ -0,0 +1,31 @@
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::config::config_dto::{CacheCodec, CacheModuleConfig};
+use tardis::serde::{Deserialize, Serialize};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cache_msgpack() -> TardisResult<()> {
+    let client = TardisCacheClient::init(&CacheModuleConfig::builder().url("mem://".parse()?).codec(CacheCodec::MessagePack).build()).await?;
+    let user = CacheUser {
+        id: 1,
+        name: "张三".to_string(),
+        tags: vec!["a".to_string()],
+    };
+    client.set_obj("user", &user).await?;
+    assert_eq!(client.get_obj::<CacheUser>("user").await?.unwrap(), user);
+    client.hset_obj("users", "1", &user).await?;
+    assert_eq!(client.hget_obj::<CacheUser>("users", "1").await?.unwrap(), user);
+    assert_eq!(client.hgetall_obj::<CacheUser>("users").await?.get("1").unwrap(), &user);
+
+    // not readable as JSON
+    client.set("json", r#"{"id":1,"name":"张三","tags":["a"]}"#).await?;
+    assert!(client.get_obj::<CacheUser>("json").await.is_err());
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct CacheUser {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+}
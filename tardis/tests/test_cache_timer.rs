@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::cache::cache_timer::{TardisDelayedTask, TardisTimer};
+use tardis::chrono::{self, TimeZone, Utc};
+use tardis::config::config_dto::TimerConfig;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::test::mock_clock::TardisMockClock;
+use tardis::TardisFuns;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct OrderTimeout {
+    order_id: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cache_timer() -> TardisResult<()> {
+    let clock = TardisMockClock::install_at(Utc.with_ymd_and_hms(2023, 8, 1, 10, 0, 0).unwrap());
+    let cache = TardisCacheClient::memory();
+    let timer = TardisTimer::new(cache.clone(), TimerConfig::builder().queue("order_timeout").max_retries(1).build());
+    let order = OrderTimeout { order_id: "o1".to_string() };
+
+    // delivered once when due
+    let id = timer.schedule_after(Duration::from_secs(1800), &order).await?;
+    assert_eq!(timer.pending().await?, 1);
+    assert_eq!(cache.timer().pending().await?, 0);
+    let task = timer.get::<OrderTimeout>(&id).await?.unwrap();
+    assert_eq!(task.payload, order);
+    assert_eq!(task.queue, "order_timeout");
+    assert_eq!(task.due_at, Utc.with_ymd_and_hms(2023, 8, 1, 10, 30, 0).unwrap());
+    assert_eq!(task.attempts, 0);
+    let delivered = Arc::new(AtomicUsize::new(0));
+    let handler = |task: TardisDelayedTask<OrderTimeout>| {
+        let delivered = delivered.clone();
+        async move {
+            assert_eq!(task.payload.order_id, "o1");
+            delivered.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    };
+    assert_eq!(timer.poll_once(&handler).await?, 0);
+    clock.advance(chrono::Duration::minutes(30));
+    let other_node = TardisTimer::new(cache.clone(), timer.config().clone());
+    let (delivered_by_this, delivered_by_other) = tokio::join!(timer.poll_once(&handler), other_node.poll_once(&handler));
+    assert_eq!(delivered_by_this? + delivered_by_other?, 1);
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+    assert_eq!(timer.pending().await?, 0);
+    assert!(timer.get::<OrderTimeout>(&id).await?.is_none());
+
+    // cancel
+    let id = timer.schedule_after(Duration::from_secs(60), &order).await?;
+    assert!(timer.cancel(&id).await?);
+    assert!(!timer.cancel(&id).await?);
+    clock.advance(chrono::Duration::minutes(1));
+    assert_eq!(timer.poll_once(&handler).await?, 0);
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+
+    // retry and dead letters
+    let id = timer.schedule_at(TardisFuns::clock().now(), &order).await?;
+    let failing = |task: TardisDelayedTask<OrderTimeout>| async move {
+        if task.attempts == 0 {
+            Err(TardisError::internal_error("order service unavailable", ""))
+        } else {
+            panic!("order {} broken", task.payload.order_id)
+        }
+    };
+    assert_eq!(timer.poll_once(&failing).await?, 1);
+    let task = timer.get::<OrderTimeout>(&id).await?.unwrap();
+    assert_eq!(task.attempts, 1);
+    assert!(task.last_error.unwrap().contains("order service unavailable"));
+    assert_eq!(timer.pending().await?, 1);
+    // waiting for the backoff
+    assert_eq!(timer.poll_once(&failing).await?, 0);
+    clock.advance(chrono::Duration::seconds(1));
+    assert_eq!(timer.poll_once(&failing).await?, 1);
+    assert_eq!(timer.pending().await?, 0);
+    let dead_letters = timer.dead_letters::<OrderTimeout>().await?;
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].id, id);
+    assert_eq!(dead_letters[0].attempts, 2);
+    assert!(dead_letters[0].last_error.as_ref().unwrap().contains("panicked"));
+    assert!(timer.remove_dead_letter(&id).await?);
+    assert!(timer.dead_letters::<OrderTimeout>().await?.is_empty());
+    assert!(timer.get::<OrderTimeout>(&id).await?.is_none());
+
+    // the lease expires while delivering, the failure of the late consumer doesn't revive the task delivered again
+    let id = timer.schedule_at(TardisFuns::clock().now(), &order).await?;
+    let lease = chrono::Duration::milliseconds(timer.config().lease_ms as i64 + 1);
+    let redelivered = |_: TardisDelayedTask<OrderTimeout>| async { Ok(()) };
+    let (clock_ref, other_node_ref, redelivered_ref) = (&clock, &other_node, &redelivered);
+    let expiring = move |_: TardisDelayedTask<OrderTimeout>| async move {
+        clock_ref.advance(lease);
+        assert_eq!(other_node_ref.poll_once(redelivered_ref).await.unwrap(), 1);
+        Err(TardisError::internal_error("order service timeout", ""))
+    };
+    assert_eq!(timer.poll_once(&expiring).await?, 1);
+    assert_eq!(timer.pending().await?, 0);
+    assert!(timer.get::<OrderTimeout>(&id).await?.is_none());
+    assert!(timer.dead_letters::<OrderTimeout>().await?.is_empty());
+
+    // the payload that can't be deserialized isn't retried
+    let id = timer.schedule_at(TardisFuns::clock().now(), &"o2").await?;
+    assert_eq!(timer.poll_once(&handler).await?, 1);
+    assert_eq!(timer.pending().await?, 0);
+    assert_eq!(timer.dead_letters::<String>().await?[0].id, id);
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+    timer.remove_dead_letter(&id).await?;
+
+    // subscribe
+    let timer = TardisTimer::new(cache.clone(), TimerConfig::builder().poll_interval_ms(50).build()).queue("reminder");
+    assert_eq!(timer.config().queue, "reminder");
+    assert_eq!(timer.config().poll_interval_ms, 50);
+    timer.schedule_at(TardisFuns::clock().now(), &order).await?;
+    timer.schedule_at(TardisFuns::clock().now() + chrono::Duration::seconds(10), &order).await?;
+    let subscription_delivered = Arc::new(AtomicUsize::new(0));
+    let counter = subscription_delivered.clone();
+    let subscription = timer.subscribe(move |_: TardisDelayedTask<OrderTimeout>| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(subscription_delivered.load(Ordering::SeqCst), 1);
+    clock.advance(chrono::Duration::seconds(10));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(subscription_delivered.load(Ordering::SeqCst), 2);
+    subscription.abort();
+    Ok(())
+}
@@ -2,6 +2,7 @@
 
 use regex::{Captures, Regex};
 use std::env;
+use std::time::Duration;
 use tardis::config::config_dto::FrameworkConfig;
 
 use tardis::basic::result::TardisResult;
@@ -25,6 +26,13 @@ async fn test_config() -> TardisResult<()> {
     assert_eq!(db_config.default.url, "postgres://postgres@test");
     assert_eq!(TardisFuns::cs_config::<TestConfig>("").db_proj.url, "postgres://postgres@test.proj");
     assert_eq!(TardisFuns::fw_config().app.name, "APP1");
+    assert_eq!(
+        TardisFuns::fw_config().log().targets.get("sqlx::query").map(|level| level.to_string().to_lowercase()),
+        Some("warn".to_string())
+    );
+    let slow_operation = &TardisFuns::fw_config().log().slow_operation;
+    assert_eq!(slow_operation.threshold("cache"), Some(Duration::from_millis(100)));
+    assert_eq!(slow_operation.threshold("reldb"), Some(Duration::from_secs(1)));
 
     env::set_var("PROFILE", "prod");
     tardis::log::info!("init the second time");
@@ -0,0 +1,58 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::serde::Deserialize;
+use tardis::TardisFuns;
+
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct TodoConfig {
+    name: String,
+    db_url: String,
+    mq_url: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_include() -> TardisResult<()> {
+    env::set_var("PROFILE", "test");
+    let dir = env::temp_dir().join(format!("tardis-config-include-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("conf-default.toml"), "[fw.app]\nname = \"app-default\"\n")?;
+    std::fs::write(
+        dir.join("conf-base-db.toml"),
+        "include = [\"conf-base-common\"]\n\n[csm.todo]\ndb_url = \"postgres://db\"\n",
+    )?;
+    std::fs::write(dir.join("conf-base-mq.yaml"), "csm:\n  todo:\n    mq_url: amqp://mq\n")?;
+    std::fs::write(
+        dir.join("conf-base-common.toml"),
+        "[csm.todo]\nname = \"common\"\nmq_url = \"amqp://common\"\n\n[fw.app]\nname = \"app-common\"\n",
+    )?;
+    std::fs::write(
+        dir.join("conf-test.toml"),
+        "include = [\"conf-base-db\", \"conf-base-mq\"]\n\n[csm.todo]\nname = \"todo-test\"\n",
+    )?;
+    TardisFuns::init(dir.to_str()).await?;
+    let todo_config = TardisFuns::cs_config::<TodoConfig>("todo");
+    assert_eq!(todo_config.name, "todo-test");
+    assert_eq!(todo_config.db_url, "postgres://db");
+    // the later included file wins
+    assert_eq!(todo_config.mq_url, "amqp://mq");
+    // the files included by the profile file override conf-default
+    assert_eq!(TardisFuns::fw_config().app.name, "app-common");
+
+    // circular include
+    std::fs::write(dir.join("conf-base-common.toml"), "include = [\"conf-base-db\"]\n")?;
+    let error = TardisFuns::init(dir.to_str()).await.unwrap_err();
+    assert_eq!(error.code, "406");
+    assert!(error.message.contains("conf-test -> conf-base-db -> conf-base-common -> conf-base-db"));
+
+    // missing include
+    std::fs::write(dir.join("conf-base-common.toml"), "include = [\"conf-base-missing\"]\n")?;
+    let error = TardisFuns::init(dir.to_str()).await.unwrap_err();
+    assert_eq!(error.code, "406");
+    assert!(error.message.contains("conf-test -> conf-base-db -> conf-base-common -> conf-base-missing"));
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
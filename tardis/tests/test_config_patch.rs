@@ -0,0 +1,71 @@
+use std::env;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_patch::TardisConfigPatch;
+use tardis::serde::Deserialize;
+use tardis::tokio::time::timeout;
+use tardis::TardisFuns;
+
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct TodoConfig {
+    name: String,
+    #[serde(default)]
+    feature_x: bool,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_patch() -> TardisResult<()> {
+    env::set_var("PROFILE", "");
+    let dir = env::temp_dir().join(format!("tardis-config-patch-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(&dir)?;
+    let conf_file = dir.join("conf-default.toml");
+    std::fs::write(
+        &conf_file,
+        "[csm.todo]\nname = \"todo1\"\n\n[fw.app]\nname = \"app1\"\n\n[fw.adv]\nconfig_watch_interval_ms = 100\n",
+    )?;
+    TardisFuns::init(dir.to_str()).await?;
+    let mut changes = TardisFuns::subscribe_config_changed();
+
+    TardisFuns::override_config("csm.todo.feature_x", true).await?;
+    assert!(TardisFuns::cs_config::<TodoConfig>("todo").feature_x);
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "todo1");
+    let changed = changes.recv().await.unwrap();
+    assert_eq!(changed.custom, vec!["todo".to_string()]);
+
+    TardisFuns::patch_config(TardisConfigPatch::new().set("fw.log.level", "debug").set("fw.app.name", "app2")).await?;
+    assert_eq!(TardisFuns::fw_config().app.name, "app2");
+    assert_eq!(TardisFuns::fw_config().log().level.to_string(), "debug");
+    let changed = changes.recv().await.unwrap();
+    assert_eq!(changed.components, vec!["log".to_string()]);
+
+    // invalid path
+    assert!(TardisFuns::override_config("todo.name", "todo3").await.is_err());
+    assert!(TardisFuns::override_config("fw", "todo3").await.is_err());
+
+    // the overrides are kept on reloading
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    std::fs::write(
+        &conf_file,
+        "[csm.todo]\nname = \"todo2\"\n\n[fw.app]\nname = \"app1\"\n\n[fw.adv]\nconfig_watch_interval_ms = 100\n",
+    )?;
+    timeout(Duration::from_secs(5), async {
+        while TardisFuns::cs_config::<TodoConfig>("todo").name != "todo2" {
+            changes.recv().await.unwrap();
+        }
+    })
+    .await
+    .expect("config change not detected");
+    assert!(TardisFuns::cs_config::<TodoConfig>("todo").feature_x);
+    assert_eq!(TardisFuns::fw_config().app.name, "app2");
+
+    // the overrides are cleared on initializing
+    TardisFuns::init(dir.to_str()).await?;
+    assert!(!TardisFuns::cs_config::<TodoConfig>("todo").feature_x);
+    assert_eq!(TardisFuns::fw_config().app.name, "app1");
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
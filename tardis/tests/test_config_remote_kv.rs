@@ -0,0 +1,135 @@
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::TardisConfig;
+use tardis::config::config_processor::ConfCenterProcess;
+use tardis::config_rs::builder::AsyncState;
+use tardis::config_rs::{ConfigBuilder, File, FileFormat};
+use tardis::serde::Deserialize;
+use tardis::serde_json::json;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::tokio::sync::mpsc;
+use tardis::tokio::time::timeout;
+use tardis::TardisFuns;
+
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct TodoConfig {
+    name: String,
+}
+
+/// remote content and reload notifier of the custom config center
+static CUSTOM_CONTENT: Mutex<String> = Mutex::new(String::new());
+static CUSTOM_NOTIFIER: Mutex<Option<mpsc::Sender<()>>> = Mutex::new(None);
+
+#[derive(Debug)]
+struct CustomProcessor {
+    format: FileFormat,
+}
+
+impl ConfCenterProcess for CustomProcessor {
+    fn listen_update(&self, reload_notifier: &mpsc::Sender<()>) {
+        *CUSTOM_NOTIFIER.lock().unwrap() = Some(reload_notifier.clone());
+    }
+
+    fn register_to_config(&self, conf: ConfigBuilder<AsyncState>) -> ConfigBuilder<AsyncState> {
+        conf.add_source(File::from_str(&CUSTOM_CONTENT.lock().unwrap(), self.format))
+    }
+}
+
+fn write_local_config(kind: &str, url: &str) -> TardisResult<PathBuf> {
+    let dir = env::temp_dir().join(format!("tardis-config-{kind}-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("conf-default.toml"),
+        format!("[fw.app]\nid = \"todo\"\n\n[fw.conf_center]\nkind = \"{kind}\"\nurl = \"{url}\"\nusername = \"\"\npassword = \"\"\nconfig_change_polling_interval = 200\n"),
+    )?;
+    Ok(dir)
+}
+
+/// wait until the todo config is reloaded to the expected name
+async fn wait_reloaded(name: &str) {
+    let mut changes = TardisFuns::subscribe_config_changed();
+    timeout(Duration::from_secs(10), async {
+        while TardisFuns::cs_config::<TodoConfig>("todo").name != name {
+            changes.recv().await.unwrap();
+        }
+    })
+    .await
+    .expect("config change not detected");
+}
+
+fn etcd_range_response(value: &str, revision: u64) -> MockResponse {
+    MockResponse::ok()
+        .json(&json!({
+            "header": {"revision": revision.to_string()},
+            "kvs": [{"key": TardisFuns::crypto.base64.encode("default/todo-default"), "value": TardisFuns::crypto.base64.encode(value), "mod_revision": revision.to_string()}],
+            "count": "1"
+        }))
+        .expect("invalid json")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_remote_kv() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info");
+    env::set_var("PROFILE", "");
+
+    // consul
+    let server = TardisMockServer::start().await?;
+    server
+        .expect(MockExpectation::new("GET", "/v1/kv/default/todo-default").respond_with(MockResponse::ok().header("X-Consul-Index", "1").body("[csm.todo]\nname = \"consul1\"\n")));
+    let dir = write_local_config("consul", &server.url())?;
+    TardisFuns::init(dir.to_str()).await?;
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "consul1");
+    server.reset();
+    server
+        .expect(MockExpectation::new("GET", "/v1/kv/default/todo-default").respond_with(MockResponse::ok().header("X-Consul-Index", "2").body("[csm.todo]\nname = \"consul2\"\n")));
+    wait_reloaded("consul2").await;
+    assert!(server.received_requests().iter().any(|req| req.query.as_deref().map(|query| query.contains("index=1")).unwrap_or(false)));
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+
+    // etcd
+    let server = TardisMockServer::start().await?;
+    server.expect(MockExpectation::new("POST", "/v3/kv/range").respond_with(etcd_range_response("[csm.todo]\nname = \"etcd1\"\n", 3)));
+    let dir = write_local_config("etcd", &server.url())?;
+    TardisFuns::init(dir.to_str()).await?;
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "etcd1");
+    server.reset();
+    server.expect(MockExpectation::new("POST", "/v3/kv/range").respond_with(etcd_range_response("[csm.todo]\nname = \"etcd2\"\n", 4)));
+    server.expect(
+        MockExpectation::new("POST", "/v3/watch").body_contains(r#""start_revision":4"#).times(1).respond_with(MockResponse::ok().body(
+            json!({"result": {"header": {"revision": "4"}, "events": [{"kv": {"key": TardisFuns::crypto.base64.encode("default/todo-default"), "mod_revision": "4"}}]}}).to_string()
+                + "\n",
+        )),
+    );
+    wait_reloaded("etcd2").await;
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+
+    // custom
+    TardisConfig::register_conf_center("Custom", |ctx| async move {
+        assert_eq!(ctx.app_id, "todo");
+        Ok(Box::new(CustomProcessor { format: ctx.format }) as Box<dyn ConfCenterProcess>)
+    });
+    *CUSTOM_CONTENT.lock().unwrap() = "[csm.todo]\nname = \"custom1\"\n".to_string();
+    let dir = write_local_config("custom", "http://localhost")?;
+    TardisFuns::init(dir.to_str()).await?;
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "custom1");
+    *CUSTOM_CONTENT.lock().unwrap() = "[csm.todo]\nname = \"custom2\"\n".to_string();
+    let notifier = CUSTOM_NOTIFIER.lock().unwrap().clone().expect("listen_update not called");
+    notifier.send(()).await.unwrap();
+    wait_reloaded("custom2").await;
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+
+    // unknown
+    let dir = write_local_config("unknown", "http://localhost")?;
+    assert!(TardisFuns::init(dir.to_str()).await.is_err());
+    std::fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
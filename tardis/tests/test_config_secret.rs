@@ -0,0 +1,75 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::serde::Deserialize;
+use tardis::serde_json::json;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::TardisFuns;
+
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct DbConfig {
+    url: String,
+    password: String,
+    token: String,
+    api_key: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_secret() -> TardisResult<()> {
+    env::set_var("PROFILE", "");
+    let server = TardisMockServer::start().await?;
+    server.expect(
+        MockExpectation::new("GET", "/v1/secret/data/db")
+            .header("X-Vault-Token", "root")
+            .times(1)
+            .respond_with(MockResponse::ok().json(&json!({"data": {"data": {"token": "vault-token", "api_key": "vault-key"}, "metadata": {"version": 1}}}))?),
+    );
+    env::set_var("VAULT_ADDR", server.url());
+    env::set_var("VAULT_TOKEN", "root");
+
+    let dir = env::temp_dir().join(format!("tardis-config-secret-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("db_password"), "file-password\n")?;
+    env::set_var("TEST_SECRET_DB_USER", "app");
+    env::set_var("TEST_SECRET_TIMEOUT", "42");
+    std::fs::write(
+        dir.join("conf-default.toml"),
+        format!(
+            r#"
+[fw.app]
+name = "${{env:TEST_SECRET_DB_USER}}"
+
+[fw.web_client]
+connect_timeout_sec = "${{env:TEST_SECRET_TIMEOUT}}"
+
+[csm.db]
+url = "postgres://${{env:TEST_SECRET_DB_USER}}:${{file:{}}}@db:5432/app"
+password = "${{file:{}}}"
+token = "${{vault:secret/data/db#token}}"
+api_key = "${{vault:secret/data/db#api_key}}"
+"#,
+            dir.join("db_password").display(),
+            dir.join("db_password").display()
+        ),
+    )?;
+    TardisFuns::init(dir.to_str()).await?;
+    let db_config = TardisFuns::cs_config::<DbConfig>("db");
+    assert_eq!(db_config.url, "postgres://app:file-password@db:5432/app");
+    assert_eq!(db_config.password, "file-password");
+    assert_eq!(db_config.token, "vault-token");
+    assert_eq!(db_config.api_key, "vault-key");
+    assert_eq!(TardisFuns::fw_config().app.name, "app");
+    assert_eq!(TardisFuns::fw_config().web_client().default.connect_timeout_sec, 42);
+    // the vault secret is fetched once
+    server.verify()?;
+
+    // missing secret
+    std::fs::write(dir.join("conf-default.toml"), "[csm.db]\npassword = \"${env:TEST_SECRET_NOT_EXIST}\"\n")?;
+    let error = TardisFuns::init(dir.to_str()).await.unwrap_err();
+    assert_eq!(error.code, "404");
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, LogConfig, TardisConfig};
+use tardis::serde::Deserialize;
+use tardis::serde_json::json;
+use tardis::tokio::time::timeout;
+use tardis::TardisFuns;
+
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct TodoConfig {
+    name: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_watch() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info");
+    env::set_var("PROFILE", "");
+    TardisFuns::init_conf(TardisConfig::builder().cs(HashMap::from([("todo".to_string(), json!({"name": "todo1"}))])).build()).await?;
+    let mut changes = TardisFuns::subscribe_config_changed();
+
+    // nothing changed, no event
+    TardisFuns::hot_reload(TardisConfig::builder().cs(HashMap::from([("todo".to_string(), json!({"name": "todo1"}))])).build()).await?;
+    assert!(changes.try_recv().is_err());
+
+    // custom config changed
+    TardisFuns::hot_reload(TardisConfig::builder().cs(HashMap::from([("todo".to_string(), json!({"name": "todo2"})), ("done".to_string(), json!({}))])).build()).await?;
+    let changed = changes.recv().await.unwrap();
+    assert!(changed.components.is_empty());
+    assert_eq!(changed.custom, vec!["done".to_string(), "todo".to_string()]);
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "todo2");
+
+    // component config changed, the removed custom config is reported too
+    let log_config = TardisFuns::json.json_to_obj::<LogConfig>(json!({"level": "debug"}))?;
+    TardisFuns::hot_reload(
+        TardisConfig::builder().cs(HashMap::from([("todo".to_string(), json!({"name": "todo2"}))])).fw(FrameworkConfig::builder().log(log_config).build()).build(),
+    )
+    .await?;
+    let changed = changes.recv().await.unwrap();
+    assert_eq!(changed.components, vec!["log".to_string()]);
+    assert_eq!(changed.custom, vec!["done".to_string()]);
+
+    // local files changed
+    let dir = env::temp_dir().join(format!("tardis-config-watch-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(&dir)?;
+    let conf_file = dir.join("conf-default.toml");
+    std::fs::write(&conf_file, "[csm.todo]\nname = \"todo3\"\n\n[fw.adv]\nconfig_watch_interval_ms = 100\n")?;
+    TardisFuns::init(dir.to_str()).await?;
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "todo3");
+    let mut changes = TardisFuns::subscribe_config_changed();
+    // make sure the modified time changes
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    std::fs::write(&conf_file, "[csm.todo]\nname = \"todo4\"\n\n[fw.adv]\nconfig_watch_interval_ms = 100\n")?;
+    let changed = timeout(Duration::from_secs(5), changes.recv()).await.expect("config change not detected").unwrap();
+    assert_eq!(changed.custom, vec!["todo".to_string()]);
+    assert_eq!(TardisFuns::cs_config::<TodoConfig>("todo").name, "todo4");
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::basic::tracing::TardisTracing;
+use tardis::config::config_dto::WebClientModuleConfig;
+use tardis::mq::mq_client::TardisMQClient;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::web::web_client::TardisWebClient;
+use tokio::sync::Mutex;
+
+fn ctx() -> TardisContext {
+    TardisContext {
+        own_paths: "t1/a1".to_string(),
+        ak: "ak1".to_string(),
+        owner: "acc1".to_string(),
+        roles: vec!["r1".to_string()],
+        groups: vec!["g1".to_string()],
+        request_id: "req1".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_context_propagation() -> TardisResult<()> {
+    test_scope().await?;
+    test_web_client().await?;
+    test_mq().await?;
+    Ok(())
+}
+
+async fn test_scope() -> TardisResult<()> {
+    assert!(TardisContext::current().is_none());
+    ctx()
+        .scope(async {
+            let current = TardisContext::current().unwrap();
+            assert_eq!(current.owner, "acc1");
+            assert_eq!(current.request_id, "req1");
+            // carried into the spawned tasks
+            let spawned = TardisContext::spawn(async { TardisContext::current().map(|ctx| ctx.owner) }).await.unwrap();
+            assert_eq!(spawned, Some("acc1".to_string()));
+            let spawned = TardisTracing::spawn_in_current_span(async { TardisContext::current().map(|ctx| ctx.roles) }).await.unwrap();
+            assert_eq!(spawned, Some(vec!["r1".to_string()]));
+            // but not by the plain tokio tasks
+            assert!(tokio::spawn(async { TardisContext::current() }).await.unwrap().is_none());
+        })
+        .await;
+    assert!(TardisContext::current().is_none());
+    assert!(TardisContext::spawn(async { TardisContext::current() }).await.unwrap().is_none());
+
+    // headers
+    let mut header = HashMap::new();
+    TardisContext::inject(&mut header);
+    assert!(header.is_empty());
+    ctx().scope(async { TardisContext::inject(&mut header) }).await;
+    assert_eq!(TardisContext::header_name(), "Tardis-Context");
+    assert!(header.contains_key("Tardis-Context"));
+    let extracted = TardisContext::extract(&header).unwrap();
+    assert_eq!(extracted.own_paths, "t1/a1");
+    assert_eq!(extracted.groups, vec!["g1".to_string()]);
+    assert_eq!(extracted.request_id, "req1");
+    // the header names are case-insensitive
+    let header = HashMap::from([("tardis-context".to_string(), header["Tardis-Context"].clone())]);
+    assert_eq!(TardisContext::extract(&header).unwrap().ak, "ak1");
+    // the existing header is kept
+    let mut header = HashMap::from([("tardis-context".to_string(), "other".to_string())]);
+    ctx().scope(async { TardisContext::inject(&mut header) }).await;
+    assert_eq!(header.len(), 1);
+    assert!(TardisContext::extract(&header).is_none());
+    Ok(())
+}
+
+async fn test_web_client() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    server.expect(MockExpectation::new("GET", "/users").respond_with(MockResponse::ok().body("ok")));
+    let url = format!("{}/users", server.url());
+    // disabled by default
+    let client = TardisWebClient::init(&WebClientModuleConfig::default())?;
+    ctx().scope(client.get_to_str(url.as_str(), None)).await?;
+    let client = TardisWebClient::init(&WebClientModuleConfig::builder().propagate_context(true).build())?;
+    client.get_to_str(url.as_str(), None).await?;
+    ctx().scope(client.get_to_str(url.as_str(), None)).await?;
+    ctx().scope(client.with_context_propagation(false).get_to_str(url.as_str(), None)).await?;
+    let requests = server.received_requests();
+    assert_eq!(requests.len(), 4);
+    assert!(!requests[0].headers.contains_key("tardis-context"));
+    assert!(!requests[1].headers.contains_key("tardis-context"));
+    let extracted = TardisContext::extract(&requests[2].headers).unwrap();
+    assert_eq!(extracted.owner, "acc1");
+    assert_eq!(extracted.request_id, "req1");
+    assert!(!requests[3].headers.contains_key("tardis-context"));
+    Ok(())
+}
+
+async fn test_mq() -> TardisResult<()> {
+    let mq = TardisMQClient::memory();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let owners = received.clone();
+    mq.subscribe("user.created", move |_| {
+        let owners = owners.clone();
+        async move {
+            owners.lock().await.push(TardisContext::current().map(|ctx| ctx.owner));
+            Ok(())
+        }
+    })
+    .await?;
+    mq.publish("user.created", "u1".to_string(), &HashMap::new()).await?;
+    ctx().scope(mq.publish("user.created", "u2".to_string(), &HashMap::new())).await?;
+    let sent = mq.as_memory().unwrap().sent_messages()?;
+    assert!(TardisContext::extract(&sent[0].header).is_none());
+    assert_eq!(TardisContext::extract(&sent[1].header).unwrap().owner, "acc1");
+    // the handler runs with the context of the message
+    let mut header = HashMap::new();
+    ctx().scope(async { TardisContext::inject(&mut header) }).await;
+    mq.publish("user.created", "u3".to_string(), &header).await?;
+    assert_eq!(*received.lock().await, vec![None, Some("acc1".to_string()), Some("acc1".to_string())]);
+    Ok(())
+}
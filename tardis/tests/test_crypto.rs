@@ -96,6 +96,11 @@ Rust 拥有出色的文档、友好的编译器和清晰的错误提示信息，
         TardisFuns::crypto.digest.sm3(large_text)?,
         "06717d16b797096e5050adb2f8c2daabf4d8f26d5c3a8da5c6171bec2becb497"
     );
+    assert_eq!(TardisFuns::crypto.sm3.digest("测试")?, TardisFuns::crypto.digest.sm3("测试")?);
+    assert_eq!(
+        TardisFuns::crypto.sm3.hmac("测试", "pwd")?,
+        "67434344159a74667e73830e0f24cd0623b9271b99f838019de106be52d9e8f6"
+    );
 
     // SM4
 
@@ -137,5 +142,20 @@ Rust 拥有出色的文档、友好的编译器和清晰的错误提示信息，
     let encrypted_data = public_key1.encrypt(large_text)?;
     assert_eq!(private_key.decrypt(&encrypted_data)?, large_text);
 
+    // SM2 key exchange
+
+    let private_key_b = TardisFuns::crypto.sm2.new_private_key()?;
+    let public_key_b = TardisFuns::crypto.sm2.new_public_key(&private_key_b)?;
+    let mut initiator = TardisFuns::crypto.sm2.new_key_exchange_initiator(16, "alice@example.com", "bob@example.com", &private_key, &public_key_b)?;
+    let mut responder = TardisFuns::crypto.sm2.new_key_exchange_responder(16, "alice@example.com", "bob@example.com", &public_key1, &private_key_b)?;
+    assert!(responder.exchange4("00").is_err());
+    let r_a = initiator.exchange1()?;
+    let (r_b, s_b) = responder.exchange2(&r_a)?;
+    let s_a = initiator.exchange3(&r_b, &s_b)?;
+    responder.exchange4(&s_a)?;
+    assert_eq!(initiator.key().len(), 32);
+    assert_eq!(initiator.key(), responder.key());
+    assert!(responder.exchange4(&s_b).is_err());
+
     Ok(())
 }
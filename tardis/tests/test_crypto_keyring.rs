@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tardis::basic::result::TardisResult;
+use tardis::crypto::crypto_keyring::{TardisCryptoKeyring, TardisCryptoKeyringConfig};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crypto_keyring() -> TardisResult<()> {
+    let key_v1 = TardisFuns::crypto.key.rand_32_bytes();
+    let key_v2 = TardisFuns::crypto.key.rand_32_bytes();
+
+    // config and file
+    let config = TardisCryptoKeyringConfig::builder().keys(HashMap::from([(1, TardisFuns::crypto.base64.encode(key_v1))])).build();
+    let mut keyring = TardisCryptoKeyring::from_config(&config)?;
+    assert_eq!(keyring.current_version(), 1);
+    let path = std::env::temp_dir().join(format!("tardis-keyring-{}.json", TardisFuns::field.nanoid()));
+    std::fs::write(&path, TardisFuns::json.obj_to_string(&config)?)?;
+    assert_eq!(TardisCryptoKeyring::from_file(&path)?.versions(), vec![1]);
+    std::fs::remove_file(&path)?;
+    assert_eq!(
+        TardisCryptoKeyring::from_config(&TardisCryptoKeyringConfig::builder().current_version(3).keys(config.keys.clone()).build()).err().unwrap().code,
+        "404"
+    );
+    assert_eq!(keyring.add_key(2, [0u8; 16]).err().unwrap().code, "406");
+    assert_eq!(keyring.add_key(1, key_v2).err().unwrap().code, "409");
+
+    // encrypt and decrypt
+    let blob = keyring.encrypt_to_base64("测试", "user-1")?;
+    assert_ne!(blob, keyring.encrypt_to_base64("测试", "user-1")?);
+    assert_eq!(keyring.decrypt_base64_to_string(&blob, "user-1")?, "测试");
+    assert_eq!(keyring.decrypt_base64(&blob, "user-2").err().unwrap().code, "406");
+    assert_eq!(keyring.decrypt(b"invalid", "").err().unwrap().code, "406");
+
+    // rotation
+    keyring.add_key(2, key_v2)?;
+    assert_eq!(keyring.current_version(), 2);
+    assert!(keyring.needs_rotation(&TardisFuns::crypto.base64.decode(&blob)?)?);
+    assert_eq!(keyring.decrypt_base64_to_string(&blob, "user-1")?, "测试");
+    let rotated = keyring.re_encrypt_base64(&blob)?.unwrap();
+    assert_eq!(keyring.version_of(&TardisFuns::crypto.base64.decode(&rotated)?)?, 2);
+    assert!(keyring.re_encrypt_base64(&rotated)?.is_none());
+    assert_eq!(keyring.remove_key(2).err().unwrap().code, "409");
+    keyring.remove_key(1)?;
+    assert_eq!(keyring.decrypt_base64_to_string(&rotated, "user-1")?, "测试");
+    assert_eq!(keyring.decrypt_base64(&blob, "user-1").err().unwrap().code, "404");
+
+    // the stored blobs in batches, by the loader
+    let keyring_v1 = TardisCryptoKeyring::from_loader(|| async move { Ok(vec![(1, key_v1.to_vec())]) }).await?;
+    let keyring = TardisCryptoKeyring::from_loader(|| async move { Ok(vec![(1, key_v1.to_vec()), (2, key_v2.to_vec())]) }).await?;
+    let mut rows = (0..5).map(|id| Ok((id, keyring_v1.encrypt(format!("row-{id}"), id.to_string())?))).collect::<TardisResult<Vec<_>>>()?;
+    rows.push((5, keyring.encrypt("row-5", "5")?));
+    let store = Mutex::new(rows);
+    let rotated_count = keyring
+        .rotate(
+            |last_id: Option<i32>| {
+                let batch = store.lock().unwrap().iter().filter(|(id, _)| last_id.map_or(true, |last_id| *id > last_id)).take(2).cloned().collect::<Vec<_>>();
+                async move { Ok(batch) }
+            },
+            |rotated| {
+                let mut store = store.lock().unwrap();
+                for (id, blob) in rotated {
+                    store.iter_mut().find(|(row_id, _)| *row_id == id).unwrap().1 = blob;
+                }
+                async { Ok(()) }
+            },
+        )
+        .await?;
+    assert_eq!(rotated_count, 5);
+    for (id, blob) in store.lock().unwrap().iter() {
+        assert_eq!(keyring.version_of(blob)?, 2);
+        assert_eq!(String::from_utf8(keyring.decrypt(blob, id.to_string())?)?, format!("row-{id}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::DBModuleConfig;
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::test::db_fixture::TardisDBFixture;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_db_fixture() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace,sqlx=off");
+    TardisFuns::init_log()?;
+    TardisTestContainer::mysql(None, |url| async move {
+        let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+        // DDL is not transactional in mysql
+        client
+            .conn()
+            .execute_one(
+                "CREATE TABLE IF NOT EXISTS fixture_user (id INT PRIMARY KEY, name VARCHAR(255) NOT NULL, remark VARCHAR(255))",
+                vec![],
+            )
+            .await?;
+        client
+            .conn()
+            .execute_one(
+                "CREATE TABLE IF NOT EXISTS fixture_order (id INT PRIMARY KEY, user_id INT NOT NULL, amount DOUBLE NOT NULL, paid BOOL NOT NULL)",
+                vec![],
+            )
+            .await?;
+
+        for _ in 0..2 {
+            let fixture = TardisDBFixture::begin(&client).await?;
+            fixture.load_sql_file("tests/fixtures/db_fixture_users.sql").await?;
+            fixture.load_json_file("tests/fixtures/db_fixture_orders.json").await?;
+            fixture.load_json(r#"{"fixture_user": [{"id": 3, "name": "fixture", "remark": null}]}"#).await?;
+            assert_eq!(fixture.conn().count_by_sql("SELECT * FROM fixture_user", vec![]).await?, 3);
+            assert_eq!(fixture.conn().count_by_sql("SELECT * FROM fixture_order WHERE paid = true", vec![]).await?, 1);
+            let remark = fixture.conn().query_one("SELECT remark FROM fixture_user WHERE id = 1", vec![]).await?.unwrap().try_get::<String>("", "remark")?;
+            assert_eq!(remark, "with ; in text");
+            // invisible out of the transaction
+            assert_eq!(client.conn().count_by_sql("SELECT * FROM fixture_user", vec![]).await?, 0);
+            // rolled back when dropped
+        }
+        assert_eq!(client.conn().count_by_sql("SELECT * FROM fixture_order", vec![]).await?, 0);
+
+        let fixture = TardisDBFixture::init(&url).await?;
+        assert!(fixture.load_json(r#"{"fixture_user": {"id": 4}}"#).await.is_err());
+        fixture.rollback().await?;
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{AppConfig, DiscoveryConfig, FrameworkConfig, TardisConfig, WebServerCommonConfig, WebServerConfig};
+use tardis::serde_json::json;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discovery() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    server.expect(MockExpectation::new("POST", "/v1/ns/instance").times(1).respond_with(MockResponse::ok().body("ok")));
+    server.expect(MockExpectation::new("PUT", "/v1/ns/instance/beat").respond_with(MockResponse::ok().json(&json!({"clientBeatInterval": 5000, "code": 10200}))?));
+    server.expect(MockExpectation::new("DELETE", "/v1/ns/instance").times(1).respond_with(MockResponse::ok().body("ok")));
+    server.expect(MockExpectation::new("GET", "/v1/ns/instance/list").times(1).respond_with(MockResponse::ok().json(&json!({
+        "name": "DEFAULT_GROUP@@done",
+        "hosts": [
+            {"ip": "10.0.0.1", "port": 8080, "weight": 1.0, "healthy": true, "enabled": true, "metadata": {"secure": "true"}},
+            {"ip": "10.0.0.2", "port": 8080, "weight": 1.0, "healthy": true, "enabled": true, "metadata": {}},
+            {"ip": "10.0.0.3", "port": 8080, "weight": 0.0, "healthy": true, "enabled": true, "metadata": {}}
+        ]
+    }))?));
+
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .app(AppConfig::builder().id("todo".to_string()).build())
+                .web_server(WebServerConfig::builder().common(WebServerCommonConfig::builder().host([127, 0, 0, 1]).port(38090).build()).default(Default::default()).build())
+                .discovery(DiscoveryConfig::builder().url(server.url().parse()?).heartbeat_interval_sec(1).metadata([("zone".to_string(), "a".to_string())].into()).build())
+                .build())
+            .build(),
+    )
+    .await?;
+    assert_eq!(TardisFuns::discovery().service_name(), "todo");
+
+    // register on starting
+    TardisFuns::web_server().start().await?;
+    let register = server.received_requests().into_iter().find(|req| req.method == "POST").expect("instance not registered");
+    let query = register.query.unwrap_or_default();
+    assert!(query.contains("serviceName=todo"));
+    assert!(query.contains("groupName=DEFAULT_GROUP"));
+    assert!(query.contains("ip=127.0.0.1"));
+    assert!(query.contains("port=38090"));
+    assert!(query.contains("zone"));
+
+    // heartbeat
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(server.received_requests().iter().any(|req| req.method == "PUT" && req.path == "/v1/ns/instance/beat"));
+
+    // select by round robin, the instances are cached
+    let first = TardisFuns::discovery().select_instance("done").await?;
+    let second = TardisFuns::discovery().select_instance("done").await?;
+    let third = TardisFuns::discovery().select_instance("done").await?;
+    assert_ne!(first.ip, second.ip);
+    assert_eq!(first, third);
+    assert!(![first.ip.as_str(), second.ip.as_str()].contains(&"10.0.0.3"));
+    let secure = if first.ip == "10.0.0.1" { &first } else { &second };
+    assert_eq!(secure.url(), "https://10.0.0.1:8080");
+
+    // deregister on shutdown
+    TardisFuns::shutdown().await?;
+    assert!(server.received_requests().iter().any(|req| req.method == "DELETE" && req.path == "/v1/ns/instance"));
+    server.verify()?;
+    Ok(())
+}
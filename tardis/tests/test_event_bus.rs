@@ -0,0 +1,133 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::mq::mq_client::TardisMQClient;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::utils::event_bus::{TardisEvent, TardisEventBus};
+use tardis::TardisFuns;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct UserCreated {
+    id: String,
+}
+
+impl TardisEvent for UserCreated {
+    const TOPIC: &'static str = "user.created";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct UserDeleted {
+    id: String,
+}
+
+impl TardisEvent for UserDeleted {
+    const TOPIC: &'static str = "user.deleted";
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_event_bus() -> TardisResult<()> {
+    test_local().await?;
+    test_bridge().await?;
+    Ok(())
+}
+
+async fn test_local() -> TardisResult<()> {
+    let created = Arc::new(Mutex::new(Vec::new()));
+    let events = created.clone();
+    let subscription = TardisFuns::event().subscribe(move |event: UserCreated| {
+        let events = events.clone();
+        async move {
+            events.lock().await.push(event.id);
+            Ok(())
+        }
+    });
+    let deleted = Arc::new(AtomicUsize::new(0));
+    let counter = deleted.clone();
+    TardisFuns::event().subscribe(move |_: UserDeleted| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+    assert_eq!(TardisFuns::event().subscribers::<UserCreated>(), 1);
+
+    // the events are dispatched by type
+    TardisFuns::event().publish_and_wait(UserCreated { id: "u1".to_string() }).await?;
+    assert_eq!(*created.lock().await, vec!["u1".to_string()]);
+    assert_eq!(deleted.load(Ordering::SeqCst), 0);
+    TardisFuns::event().publish(UserCreated { id: "u2".to_string() }).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(*created.lock().await, vec!["u1".to_string(), "u2".to_string()]);
+
+    // the failures don't affect the other subscribers
+    TardisFuns::event().subscribe(|_: UserCreated| async { Err(TardisError::internal_error("failed", "")) });
+    TardisFuns::event().subscribe(|event: UserCreated| async move { panic!("user {} broken", event.id) });
+    assert_eq!(TardisFuns::event().subscribers::<UserCreated>(), 3);
+    assert_eq!(TardisFuns::event().publish_and_wait(UserCreated { id: "u3".to_string() }).await.unwrap_err().code, "500");
+    TardisFuns::event().publish(UserCreated { id: "u4".to_string() }).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(created.lock().await.len(), 4);
+
+    assert!(TardisFuns::event().unsubscribe(subscription));
+    assert!(!TardisFuns::event().unsubscribe(subscription));
+    TardisFuns::event().publish(UserCreated { id: "u5".to_string() }).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(created.lock().await.len(), 4);
+    Ok(())
+}
+
+async fn test_bridge() -> TardisResult<()> {
+    let mq = Arc::new(TardisMQClient::memory());
+    let user_service = TardisEventBus::new();
+    let order_service = TardisEventBus::new();
+    assert_ne!(user_service.id(), order_service.id());
+    let local = Arc::new(AtomicUsize::new(0));
+    let counter = local.clone();
+    user_service.subscribe(move |_: UserCreated| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+    let remote = Arc::new(Mutex::new(Vec::new()));
+    let events = remote.clone();
+    order_service.subscribe(move |event: UserCreated| {
+        let events = events.clone();
+        async move {
+            events.lock().await.push(event);
+            Ok(())
+        }
+    });
+    user_service.bridge_with::<UserCreated>(mq.clone()).await?;
+    order_service.bridge_with::<UserCreated>(mq.clone()).await?;
+    assert_eq!(user_service.bridge_with::<UserCreated>(mq.clone()).await.unwrap_err().code, "409");
+
+    user_service.publish_and_wait(UserCreated { id: "u1".to_string() }).await?;
+    sleep(Duration::from_millis(100)).await;
+    // the own bridged event isn't dispatched again
+    assert_eq!(local.load(Ordering::SeqCst), 1);
+    assert_eq!(*remote.lock().await, vec![UserCreated { id: "u1".to_string() }]);
+
+    // the events not bridged stay in the service
+    let deleted = Arc::new(AtomicUsize::new(0));
+    let counter = deleted.clone();
+    order_service.subscribe(move |_: UserDeleted| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+    user_service.publish_and_wait(UserDeleted { id: "u1".to_string() }).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(deleted.load(Ordering::SeqCst), 0);
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use tardis::basic::result::TardisResult;
+use tardis::export::exporter::{TardisExportResponse, TardisExporter};
+use tardis::export::importer::TardisImporter;
+use tardis::export::{TardisExportColumn, TardisExportFormat};
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::http::Method;
+use tardis::web::poem::{get, handler, Request, Route};
+use tardis::TardisFuns;
+
+#[derive(Serialize, Debug, Clone)]
+struct TodoRow {
+    code: String,
+    priority: i32,
+    done: bool,
+    owner: Owner,
+    note: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct Owner {
+    name: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct TodoImport {
+    code: String,
+    priority: i32,
+    done: bool,
+    note: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TodoCode {
+    code: String,
+}
+
+#[derive(Serialize, Debug)]
+struct FlatRow {
+    code: String,
+    priority: i32,
+}
+
+fn columns() -> Vec<TardisExportColumn> {
+    vec![
+        TardisExportColumn::new("code", "Code").locale_code("todo-export-code"),
+        TardisExportColumn::new("priority", "Priority"),
+        TardisExportColumn::new("done", "Done"),
+        TardisExportColumn::new("owner.name", "Owner"),
+        TardisExportColumn::new("note", "Note"),
+    ]
+}
+
+fn rows() -> Vec<TodoRow> {
+    ["t1", "t2", "t3"]
+        .iter()
+        .enumerate()
+        .map(|(index, code)| TodoRow {
+            code: code.to_string(),
+            priority: index as i32 + 1,
+            done: index == 0,
+            owner: Owner { name: "Alice".to_string() },
+            note: if index == 1 { Some("a, \"quoted\" note".to_string()) } else { None },
+        })
+        .collect()
+}
+
+fn imported() -> Vec<TodoImport> {
+    rows()
+        .into_iter()
+        .map(|row| TodoImport {
+            code: row.code,
+            priority: row.priority,
+            done: row.done,
+            note: row.note,
+        })
+        .collect()
+}
+
+#[handler]
+fn export_todos(req: &Request) -> TardisExportResponse {
+    TardisExporter::new()
+        .columns(columns())
+        .accept_language(req.header("Accept-Language").unwrap_or_default())
+        .chunk_size(1)
+        .csv_stream_response("待办.csv", tardis::futures::stream::iter(rows().into_iter().map(Ok)))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_export() -> TardisResult<()> {
+    env::set_var("PROFILE", "default");
+    let dir = env::temp_dir().join(format!("tardis-export-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(dir.join("locale"))?;
+    std::fs::write(dir.join("conf-default.toml"), "[fw.app]\ndefault_lang = \"en\"\n")?;
+    std::fs::write(dir.join("locale").join("zh-cn"), "todo-export-code\t编码\n")?;
+    TardisFuns::init(dir.to_str()).await?;
+
+    // csv with the mapped and localized columns
+    let data = TardisExporter::new().columns(columns()).lang("zh-cn").csv(&rows())?;
+    assert!(data.starts_with(b"\xEF\xBB\xBF"));
+    let text = String::from_utf8(data[3..].to_vec())?;
+    assert_eq!(
+        text.lines().collect::<Vec<_>>(),
+        vec![
+            "编码,Priority,Done,Owner,Note",
+            "t1,1,true,Alice,",
+            "t2,2,false,Alice,\"a, \"\"quoted\"\" note\"",
+            "t3,3,false,Alice,"
+        ]
+    );
+    // the fields of the first row in order if the columns are not configured
+    let data = TardisExporter::new().bom(false).csv(&[FlatRow {
+        code: "t1".to_string(),
+        priority: 1,
+    }])?;
+    assert_eq!(String::from_utf8(data)?.lines().collect::<Vec<_>>(), vec!["code,priority", "t1,1"]);
+    let data = TardisExporter::new().bom(false).csv::<FlatRow>(&[])?;
+    assert_eq!(String::from_utf8(data)?.trim(), "");
+
+    // the formulas are not evaluated
+    let formulas = vec![
+        FlatRow {
+            code: "=HYPERLINK(\"http://evil.com\")".to_string(),
+            priority: -1,
+        },
+        FlatRow {
+            code: "@SUM(A1)".to_string(),
+            priority: 1,
+        },
+    ];
+    let data = TardisExporter::new().bom(false).csv(&formulas)?;
+    assert_eq!(
+        String::from_utf8(data.clone())?.lines().collect::<Vec<_>>(),
+        vec!["code,priority", "\"'=HYPERLINK(\"\"http://evil.com\"\")\",-1", "'@SUM(A1),1"]
+    );
+    let result = TardisImporter::new().parse_csv::<TodoCode>(&data)?;
+    assert_eq!(
+        result.rows.iter().map(|row| row.code.as_str()).collect::<Vec<_>>(),
+        vec![formulas[0].code.as_str(), "@SUM(A1)"]
+    );
+    let result = TardisImporter::new().parse::<TodoCode>(TardisExportFormat::Xlsx, &TardisExporter::new().xlsx(&formulas)?)?;
+    assert_eq!(
+        result.rows.iter().map(|row| row.code.as_str()).collect::<Vec<_>>(),
+        vec![formulas[0].code.as_str(), "@SUM(A1)"]
+    );
+
+    // import by the localized headers, the unknown columns are ignored
+    let importer = TardisImporter::new().columns(columns()).lang("zh-cn");
+    let data = TardisExporter::new().columns(columns()).lang("zh-cn").csv(&rows())?;
+    let result = importer.parse::<TodoImport>(TardisExportFormat::Csv, &data)?;
+    assert!(result.is_ok());
+    assert_eq!(result.rows, imported());
+
+    // errors of the rows
+    let data = "Code,Priority,Done\nt1,1,true\nt2,x,false\n\nt3,2,maybe\n";
+    let result = importer.parse_csv::<TodoImport>(data.as_bytes())?;
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.errors.iter().map(|error| error.row).collect::<Vec<_>>(), vec![3, 5]);
+    assert_eq!(result.errors[0].field.as_deref(), Some("priority"));
+    assert_eq!(result.errors[1].field.as_deref(), Some("done"));
+    let error = result.into_rows().unwrap_err();
+    assert_eq!(error.code, "400-tardis-import-invalid-rows");
+    assert!(error.extensions.get("row.3").unwrap().starts_with("priority: "));
+    assert_eq!(
+        TardisImporter::new().max_rows(1).parse_csv::<TodoImport>(data.as_bytes()).unwrap_err().code,
+        "400-tardis-import-too-many-rows"
+    );
+
+    // xlsx
+    let data = TardisExporter::new().columns(columns()).lang("zh-cn").sheet_name("todos").xlsx(&rows())?;
+    let result = importer.parse::<TodoImport>(TardisExportFormat::Xlsx, &data)?;
+    assert!(result.is_ok(), "{:?}", result.errors);
+    assert_eq!(result.rows, imported());
+    assert_eq!(TardisExportFormat::from_file_name("todos.XLSX"), Some(TardisExportFormat::Xlsx));
+    assert_eq!(TardisExportFormat::from_content_type("text/csv; charset=utf-8"), Some(TardisExportFormat::Csv));
+
+    // streaming web response
+    let client = TardisWebTestClient::from_route(Route::new().at("/todos/export", get(export_todos)));
+    let resp = client.get("/todos/export").await?;
+    resp.assert_status(200)
+        .assert_header("Content-Type", "text/csv; charset=utf-8")
+        .assert_header("Content-Disposition", "attachment; filename=\"__.csv\"; filename*=UTF-8''%E5%BE%85%E5%8A%9E.csv");
+    let lines = resp.body.trim_start_matches('\u{feff}').lines().map(str::to_string).collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "Code,Priority,Done,Owner,Note");
+    let resp = client.request(Method::GET, "/todos/export", vec![("Accept-Language".to_string(), "zh-cn,zh;q=0.9".to_string())], None).await?;
+    assert!(resp.body.trim_start_matches('\u{feff}').starts_with("编码,"));
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use poem_grpc::{Request as GrpcRequest, Response as GrpcResponse, Status as GrpcStatus};
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, GrpcClientConfig, GrpcClientModuleConfig, TardisConfig, WebServerCommonConfig, WebServerConfig, WebServerModuleConfig};
+use tardis::web::grpc_client::TardisGrpcClient;
+use tardis::TardisFuns;
+use tokio::time::sleep;
+
+#[allow(non_snake_case)]
+mod helloworld_grpc {
+    include!("./grpc/rust/helloworld.rs");
+}
+use helloworld_grpc::*;
+
+#[derive(Clone, Default)]
+struct GreeterGrpcService;
+
+#[poem::async_trait]
+impl Greeter for GreeterGrpcService {
+    async fn say_hello(&self, request: GrpcRequest<HelloRequest>) -> Result<GrpcResponse<HelloReply>, GrpcStatus> {
+        let owner = TardisContext::current().map(|ctx| ctx.owner).unwrap_or_default();
+        Ok(GrpcResponse::new(HelloReply {
+            message: format!("Hello {}!{owner}", request.into_inner().name),
+        }))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_grpc_client() -> TardisResult<()> {
+    assert_eq!(TardisGrpcClient::init(&GrpcClientModuleConfig::default()).unwrap_err().code, "406");
+
+    TardisFuns::init_conf(TardisConfig {
+        cs: Default::default(),
+        fw: FrameworkConfig::builder()
+            .web_server(
+                WebServerConfig::builder()
+                    .common(WebServerCommonConfig::builder().port(8095).grpc_port(8096).build())
+                    .modules([("grpc".to_string(), WebServerModuleConfig::default())])
+                    .default(Default::default())
+                    .build(),
+            )
+            .grpc_client(
+                GrpcClientConfig::builder()
+                    .default(GrpcClientModuleConfig::builder().uris(vec!["http://localhost:8096/grpc".to_string()]).propagate_context(true).build())
+                    .modules([(
+                        "http".to_string(),
+                        GrpcClientModuleConfig::builder().uris(vec!["http://localhost:8095/grpc".to_string()]).build(),
+                    )])
+                    .build(),
+            )
+            .build(),
+    })
+    .await?;
+    TardisFuns::web_server().add_grpc_module("grpc", GreeterServer::new(GreeterGrpcService)).await.start().await?;
+    sleep(Duration::from_millis(500)).await;
+
+    // served on the separate listener
+    let grpc = TardisFuns::grpc_client();
+    let client = grpc.client(GreeterClient::new)?.with(grpc.propagation());
+    let reply = client.say_hello(GrpcRequest::new(HelloRequest { name: "Tardis".to_string() })).await.unwrap();
+    assert_eq!(reply.message, "Hello Tardis!");
+    assert!(TardisFuns::grpc_client_by_module_or_default("http")
+        .client(GreeterClient::new)?
+        .say_hello(GrpcRequest::new(HelloRequest { name: "Tardis".to_string() }))
+        .await
+        .is_err());
+
+    // the context is propagated
+    let ctx = TardisContext {
+        owner: "u1".to_string(),
+        ..Default::default()
+    };
+    let reply = ctx.scope(async { client.say_hello(GrpcRequest::new(HelloRequest { name: "Tardis".to_string() })).await }).await.unwrap();
+    assert_eq!(reply.message, "Hello Tardis!u1");
+
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
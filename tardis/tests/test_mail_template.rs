@@ -0,0 +1,70 @@
+use std::env;
+
+use serde_json::json;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::MailModuleConfig;
+use tardis::mail::mail_client::{TardisMailAttachment, TardisMailClient, TardisMailSendReq};
+use tardis::mail::mail_template::TardisMailTemplate;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mail_template() -> TardisResult<()> {
+    env::set_var("PROFILE", "default");
+    let dir = env::temp_dir().join(format!("tardis-mail-template-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(dir.join("locale"))?;
+    std::fs::write(dir.join("conf-default.toml"), "[fw.app]\ndefault_lang = \"en\"\n")?;
+    std::fs::write(dir.join("locale").join("en"), "mail-footer\tSent by Tardis\n")?;
+    std::fs::write(dir.join("locale").join("zh"), "mail-footer\t由Tardis发送\n")?;
+    TardisFuns::init(dir.to_str()).await?;
+
+    let client = TardisMailClient::init(&MailModuleConfig::builder().smtp_host("localhost").default_from("tardis@example.com").build())?;
+    client.register_template(
+        "welcome",
+        Some("en"),
+        &TardisMailTemplate::new("Welcome {{name}}", "Hi {{name}}, {{t \"mail-footer\" \"\"}}").html_body("<p>Hi {{name}}</p><img src=\"cid:logo\">"),
+    )?;
+    client.register_template(
+        "welcome",
+        Some("zh"),
+        &TardisMailTemplate::new("欢迎 {{name}}", "你好 {{name}}，{{t \"mail-footer\" \"\"}}"),
+    )?;
+
+    // the language variants
+    let content = client.render_template("welcome", Some("zh-CN"), &json!({ "name": "<tardis>" }))?;
+    assert_eq!(content.lang, "zh");
+    assert_eq!(content.subject, "欢迎 <tardis>");
+    assert_eq!(content.txt_body, "你好 <tardis>，由Tardis发送");
+    assert!(content.html_body.is_none());
+    // fallback to the default language
+    let content = client.render_template("welcome", Some("fr"), &json!({ "name": "<tardis>" }))?;
+    assert_eq!(content.lang, "en");
+    assert_eq!(content.txt_body, "Hi <tardis>, Sent by Tardis");
+    assert_eq!(content.html_body.as_deref(), Some("<p>Hi &lt;tardis&gt;</p><img src=\"cid:logo\">"));
+
+    // errors
+    assert_eq!(client.render_template("welcome", None, &json!({})).err().unwrap().code, "406");
+    assert_eq!(client.render_template("none", None, &json!({})).err().unwrap().code, "404");
+    assert_eq!(client.register_template("broken", None, &TardisMailTemplate::new("{{#if}}", "")).err().unwrap().code, "406");
+
+    // attachments
+    let content = client.render_template("welcome", Some("en"), &json!({ "name": "tardis" }))?;
+    let mut req = TardisMailSendReq::builder()
+        .subject(content.subject)
+        .txt_body(content.txt_body)
+        .to(["user@example.com".to_string()])
+        .attachments([
+            TardisMailAttachment::new("report.csv", "text/csv", b"a,b\n1,2\n".to_vec()),
+            TardisMailAttachment::new("logo.png", "image/png", vec![0x89, 0x50, 0x4e, 0x47]).inline("logo"),
+        ])
+        .build();
+    req.html_body = content.html_body;
+    let message = String::from_utf8(client.build_message(&req)?.formatted())?;
+    assert!(message.contains("multipart/mixed"));
+    assert!(message.contains("multipart/related"));
+    assert!(message.contains("multipart/alternative"));
+    assert!(message.contains("Content-Disposition: attachment; filename=\"report.csv\""));
+    assert!(message.contains("Content-ID: <logo>"));
+    req.attachments = vec![TardisMailAttachment::new("a.bin", "not a content type", vec![])];
+    assert_eq!(client.build_message(&req).err().unwrap().code, "406");
+    Ok(())
+}
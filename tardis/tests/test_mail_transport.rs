@@ -0,0 +1,115 @@
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::MailModuleConfig;
+use tardis::mail::mail_client::{TardisMailAttachment, TardisMailClient, TardisMailSendReq};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Accept one request and reply with the status, return the raw request
+async fn capture(status: &'static str) -> TardisResult<(String, JoinHandle<String>)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}", listener.local_addr()?);
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        loop {
+            let size = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..size]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head.lines().find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|length| length.trim().parse::<usize>().unwrap())).unwrap_or(0);
+                if body.len() >= length || size == 0 {
+                    break;
+                }
+            }
+        }
+        stream.write_all(format!("HTTP/1.1 {status}\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{{}}").as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&request).to_string()
+    });
+    Ok((endpoint, handle))
+}
+
+fn req() -> TardisMailSendReq {
+    TardisMailSendReq::builder()
+        .subject("Hello")
+        .txt_body("Hello tardis")
+        .html_body("<h1>Hello tardis</h1>")
+        .to(["Tardis <user@example.com>".to_string()])
+        .bcc(["hidden@example.com".to_string()])
+        .build()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mail_transport() -> TardisResult<()> {
+    // config defaults
+    assert_eq!(MailModuleConfig::default().kind, "smtp");
+    assert_eq!(TardisMailClient::init(&MailModuleConfig::builder().kind("pigeon").build()).err().unwrap().code, "501");
+    assert_eq!(
+        TardisMailClient::init(&MailModuleConfig::builder().kind("mailgun").api_key("key").build()).err().unwrap().code,
+        "406"
+    );
+
+    // mock
+    let client = TardisMailClient::init(&MailModuleConfig::builder().kind("mock").default_from("tardis@example.com").build())?;
+    client.send(&req()).await?;
+    client.send(&TardisMailSendReq::builder().subject("Second").from("other@example.com").to(["user@example.com".to_string()]).build()).await?;
+    let sent = client.sent_messages()?;
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].req.from.as_deref(), Some("tardis@example.com"));
+    assert_eq!(sent[0].req.subject, "Hello");
+    assert!(sent[0].raw.contains("To: Tardis <user@example.com>"));
+    assert!(!sent[0].raw.contains("hidden@example.com"));
+    assert_eq!(sent[1].req.from.as_deref(), Some("other@example.com"));
+    client.clear_sent_messages()?;
+    assert!(client.sent_messages()?.is_empty());
+    let smtp = TardisMailClient::init(&MailModuleConfig::builder().smtp_host("localhost").build())?;
+    assert_eq!(smtp.sent_messages().err().unwrap().code, "501");
+
+    // sendgrid
+    let (endpoint, handle) = capture("202 Accepted").await?;
+    let client = TardisMailClient::init(&MailModuleConfig::builder().kind("sendgrid").api_endpoint(endpoint).api_key("sg-key").default_from("tardis@example.com").build())?;
+    let mut sendgrid_req = req();
+    sendgrid_req.attachments = vec![TardisMailAttachment::new("a.txt", "text/plain", b"abc".to_vec())];
+    client.send(&sendgrid_req).await?;
+    let request = handle.await.unwrap();
+    assert!(request.starts_with("POST /v3/mail/send "));
+    assert!(request.to_lowercase().contains("authorization: bearer sg-key"));
+    assert!(request.contains(r#""to":[{"email":"user@example.com","name":"Tardis"}]"#));
+    assert!(request.contains(r#""bcc":[{"email":"hidden@example.com"}]"#));
+    assert!(request.contains(r#""content":"YWJj""#));
+
+    // mailgun
+    let (endpoint, handle) = capture("200 OK").await?;
+    let client = TardisMailClient::init(
+        &MailModuleConfig::builder().kind("mailgun").api_endpoint(endpoint).api_key("mg-key").api_domain("mg.example.com").default_from("tardis@example.com").build(),
+    )?;
+    client.send(&req()).await?;
+    let request = handle.await.unwrap();
+    assert!(request.starts_with("POST /v3/mg.example.com/messages.mime "));
+    assert!(request.contains("user@example.com,hidden@example.com"));
+    assert!(request.contains("Subject: Hello"));
+
+    // aliyun
+    let (endpoint, handle) = capture("200 OK").await?;
+    let client = TardisMailClient::init(
+        &MailModuleConfig::builder().kind("aliyun").api_endpoint(endpoint).api_key("ak").api_secret("sk").default_from("Tardis <tardis@example.com>").build(),
+    )?;
+    assert_eq!(client.send(&req()).await.err().unwrap().code, "501");
+    let mut aliyun_req = req();
+    aliyun_req.bcc = vec![];
+    client.send(&aliyun_req).await?;
+    let request = handle.await.unwrap();
+    assert!(request.contains("Action=SingleSendMail"));
+    assert!(request.contains("AccountName=tardis%40example.com"));
+    assert!(request.contains("FromAlias=Tardis"));
+    assert!(request.contains("RegionId=cn-hangzhou"));
+    assert!(request.contains("Signature="));
+
+    // the failure of the provider
+    let (endpoint, handle) = capture("401 Unauthorized").await?;
+    let client = TardisMailClient::init(&MailModuleConfig::builder().kind("sendgrid").api_endpoint(endpoint).api_key("sg-key").default_from("tardis@example.com").build())?;
+    assert_eq!(client.send(&req()).await.err().unwrap().code, "500");
+    handle.await.unwrap();
+    Ok(())
+}
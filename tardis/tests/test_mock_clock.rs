@@ -0,0 +1,56 @@
+use tardis::chrono::{Duration, TimeZone, Utc};
+
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::test::mock_clock::TardisMockClock;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mock_clock() -> TardisResult<()> {
+    let start = Utc.with_ymd_and_hms(2023, 8, 1, 10, 0, 0).unwrap();
+    let clock = TardisMockClock::install_at(start);
+
+    // frozen
+    assert_eq!(TardisFuns::clock().now(), start);
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    assert_eq!(TardisFuns::clock().now(), start);
+    assert_eq!(TardisFuns::clock().timestamp(), start.timestamp());
+
+    // advance
+    clock.advance(Duration::seconds(90));
+    assert_eq!(TardisFuns::clock().now(), start + Duration::seconds(90));
+    clock.set(start);
+    assert_eq!(TardisFuns::clock().now(), start);
+
+    // running from the mocked time
+    clock.unfreeze();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let now = TardisFuns::clock().now();
+    assert!(now > start && now < start + Duration::seconds(10));
+    clock.advance(Duration::days(1));
+    assert!(TardisFuns::clock().now() >= now + Duration::days(1));
+    clock.freeze();
+    let frozen = TardisFuns::clock().now();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    assert_eq!(TardisFuns::clock().now(), frozen);
+
+    // the expiration of the in-memory cache follows the clock
+    let cache = TardisCacheClient::memory();
+    cache.set_ex("k", "v", 10).await?;
+    cache.set("k_at", "v").await?;
+    cache.expire_at("k_at", (frozen + Duration::seconds(20)).timestamp() as usize).await?;
+    assert_eq!(cache.ttl("k").await?, 10);
+    clock.advance(Duration::seconds(9));
+    assert_eq!(cache.ttl("k").await?, 1);
+    assert!(cache.exists("k").await?);
+    clock.advance(Duration::seconds(1));
+    assert!(!cache.exists("k").await?);
+    assert_eq!(cache.ttl("k_at").await?, 10);
+    clock.advance(Duration::seconds(10));
+    assert_eq!(cache.get("k_at").await?, None);
+
+    // restore the system time
+    clock.uninstall();
+    assert!(TardisFuns::clock().now() > start + Duration::days(365));
+    Ok(())
+}
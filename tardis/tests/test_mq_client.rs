@@ -18,9 +18,7 @@ async fn test_mq_client() -> TardisResult<()> {
     // console_subscriber::init();
     TardisFuns::init_log()?;
     TardisTestContainer::rabbit(|url| async move {
-        let mq_module_config = MQModuleConfig {
-            url: url.parse().expect("invalid url"),
-        };
+        let mq_module_config = MQModuleConfig::builder().url(url.parse().expect("invalid url")).build();
         // Default test
         TardisFuns::init_conf(TardisConfig {
             cs: Default::default(),
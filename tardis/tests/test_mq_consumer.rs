@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{MQConsumerConfig, MQModuleConfig};
+use tardis::mq::mq_client::TardisMQClient;
+use tardis::TardisFuns;
+
+static FLAKY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static PANIC_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static BROKEN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mq_consumer() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    // serde defaults
+    let config = TardisFuns::json.str_to_obj::<MQModuleConfig>(r#"{"url":"mem://","consumer":{"max_retries":3,"dead_letter":true}}"#)?;
+    assert_eq!(config.consumer.max_retries, 3);
+    assert_eq!(config.consumer.prefetch, 1);
+    assert_eq!(config.consumer.concurrency, 1);
+    assert_eq!(config.consumer.dead_letter_queue("order"), Some("order.dlq".to_string()));
+    assert_eq!(MQConsumerConfig::default().dead_letter_queue("order"), None);
+
+    let backoff = MQConsumerConfig::builder().initial_backoff_ms(100).max_backoff_ms(500).build();
+    assert_eq!(backoff.backoff(0), Duration::from_millis(100));
+    assert_eq!(backoff.backoff(2), Duration::from_millis(400));
+    assert_eq!(backoff.backoff(3), Duration::from_millis(500));
+    assert_eq!(backoff.backoff(100), Duration::from_millis(500));
+
+    let client = TardisMQClient::memory();
+    let memory = client.as_memory().expect("not an in-memory client");
+    let header = HashMap::new();
+    let options = MQConsumerConfig::builder().max_retries(2).initial_backoff_ms(1).dead_letter(true).build();
+
+    // succeeds on the last retry
+    client
+        .response_with("flaky", &options, |(_, _)| async move {
+            if FLAKY_COUNTER.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(TardisError::internal_error("not ready", ""))
+            } else {
+                Ok(())
+            }
+        })
+        .await?;
+    client.request("flaky", "1".to_string(), &header).await?;
+    assert_eq!(FLAKY_COUNTER.load(Ordering::SeqCst), 3);
+    assert!(memory.failed_messages()?.is_empty());
+    assert!(memory.pending_messages("flaky.dlq")?.is_empty());
+
+    // the panics are caught and retried
+    client
+        .response_with("panic", &options, |(_, msg)| async move {
+            if PANIC_COUNTER.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("invalid message {msg}");
+            }
+            Ok(())
+        })
+        .await?;
+    client.request("panic", "1".to_string(), &header).await?;
+    assert_eq!(PANIC_COUNTER.load(Ordering::SeqCst), 2);
+    assert!(memory.failed_messages()?.is_empty());
+
+    // dead-lettered after the retries
+    client
+        .response_with("broken", &options, |(_, _)| async move {
+            BROKEN_COUNTER.fetch_add(1, Ordering::SeqCst);
+            Err(TardisError::bad_request("invalid message", ""))
+        })
+        .await?;
+    client.request("broken", "1".to_string(), &HashMap::from([("trace".to_string(), "t1".to_string())])).await?;
+    assert_eq!(BROKEN_COUNTER.load(Ordering::SeqCst), 3);
+    let failed = memory.failed_messages()?;
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].1.code, "400");
+    let dead_letters = memory.pending_messages("broken.dlq")?;
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].message, "1");
+    assert_eq!(dead_letters[0].header.get("trace").map(String::as_str), Some("t1"));
+    assert_eq!(dead_letters[0].header.get("x-tardis-dead-letter-origin").map(String::as_str), Some("broken"));
+    assert!(dead_letters[0].header.get("x-tardis-dead-letter-error").is_some_and(|error| error.contains("invalid message")));
+
+    // the panic without retries and dead-letter is recorded as failed
+    client
+        .subscribe("panic.topic", |(_, msg)| async move {
+            if msg == "1" {
+                panic!("invalid message");
+            }
+            Ok(())
+        })
+        .await?;
+    client.publish("panic.topic", "1".to_string(), &header).await?;
+    let failed = memory.failed_messages()?;
+    assert_eq!(failed.len(), 2);
+    assert_eq!(failed[1].1.code, "500");
+    assert!(memory.pending_messages("panic.topic.dlq")?.is_empty());
+    Ok(())
+}
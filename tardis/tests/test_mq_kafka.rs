@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, MQConfig, MQKafkaCommitStrategy, MQKafkaConfig, MQKafkaOffsetReset, MQKind, MQModuleConfig, TardisConfig};
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+static RESPONSE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static SUBSCRIBE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static GROUP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mq_kafka() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    assert_eq!(
+        MQModuleConfig::builder().url("kafka://127.0.0.1:9092".parse().expect("invalid url")).build().kind(),
+        MQKind::Kafka
+    );
+    assert_eq!(
+        MQModuleConfig::builder().url("amqp://127.0.0.1:5672".parse().expect("invalid url")).build().kind(),
+        MQKind::Amqp
+    );
+    assert_eq!(
+        MQModuleConfig::builder().url("tcp://127.0.0.1:9092".parse().expect("invalid url")).kind(MQKind::Kafka).build().kind(),
+        MQKind::Kafka
+    );
+
+    TardisTestContainer::kafka(|url| async move {
+        TardisFuns::init_conf(TardisConfig {
+            cs: Default::default(),
+            fw: FrameworkConfig::builder()
+                .mq(MQConfig::builder()
+                    .default(
+                        MQModuleConfig::builder()
+                            .url(format!("kafka://{url}?allow.auto.create.topics=true").parse().expect("invalid url"))
+                            .kafka(MQKafkaConfig::builder().group_id("tardis-test").commit(MQKafkaCommitStrategy::Sync).offset_reset(MQKafkaOffsetReset::Earliest).build())
+                            .build(),
+                    )
+                    .build())
+                .build(),
+        })
+        .await?;
+        let client = TardisFuns::mq();
+        client.health_check().await?;
+        let mut header = HashMap::new();
+        header.insert("k1".to_string(), "v1".to_string());
+
+        // create the topics before the subscribers join, so that they start from the latest offsets
+        client.publish("test-topic", "init".to_string(), &header).await?;
+        client.request("test-addr", "init".to_string(), &header).await?;
+        client.publish_with_key("test-group", Some("user-1"), None, "init".to_string(), &header).await?;
+
+        for _ in 0..2 {
+            client
+                .response("test-addr", |(header, msg)| async move {
+                    assert_eq!(header.get("k1").unwrap(), "v1");
+                    if msg != "init" {
+                        RESPONSE_COUNTER.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(())
+                })
+                .await?;
+            client
+                .subscribe("test-topic", |(header, msg)| async move {
+                    assert_eq!(header.get("k1").unwrap(), "v1");
+                    assert_eq!(msg, "测试!");
+                    SUBSCRIBE_COUNTER.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                })
+                .await?;
+            client
+                .subscribe_group("test-group", "group-a", |(_, msg)| async move {
+                    if msg != "init" {
+                        GROUP_COUNTER.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(())
+                })
+                .await?;
+        }
+        // wait for the partition assignment
+        tokio::time::sleep(Duration::from_secs(10)).await;
+
+        for _ in 0..2 {
+            client.request("test-addr", "测试!".to_string(), &header).await?;
+            client.publish("test-topic", "测试!".to_string(), &header).await?;
+            client.publish_with_key("test-group", Some("user-1"), Some(0), "测试!".to_string(), &header).await?;
+        }
+
+        for _ in 0..60 {
+            if RESPONSE_COUNTER.load(Ordering::SeqCst) >= 2 && SUBSCRIBE_COUNTER.load(Ordering::SeqCst) >= 4 && GROUP_COUNTER.load(Ordering::SeqCst) >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        // each message is processed once by the group, and received by all the subscribers
+        assert_eq!(RESPONSE_COUNTER.load(Ordering::SeqCst), 2);
+        assert_eq!(SUBSCRIBE_COUNTER.load(Ordering::SeqCst), 4);
+        assert_eq!(GROUP_COUNTER.load(Ordering::SeqCst), 2);
+
+        // the routing key patterns aren't supported
+        assert_eq!(client.subscribe_topic("test-topic", "a.*", |(_, _)| async move { Ok(()) }).await.unwrap_err().code, "501");
+
+        client.close().await?;
+        Ok(())
+    })
+    .await
+}
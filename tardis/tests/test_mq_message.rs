@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tardis::basic::result::TardisResult;
+use tardis::mq::mq_client::TardisMQClient;
+use tardis::mq::mq_message::{TardisMQMessage, TardisMQPayload, TardisMQRawPayload, HEADER_CONTENT_TYPE, HEADER_MESSAGE_ID};
+
+static ORDER_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static RAW_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct OrderCreated {
+    id: String,
+    amount: u32,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mq_message() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    // encoding
+    let message = TardisMQMessage::new(OrderCreated { id: "o1".to_string(), amount: 10 }).with_header("tenant", "t1");
+    assert_eq!(message.content_type(), "application/json");
+    let (header, body) = message.encode()?;
+    assert_eq!(header.get(HEADER_MESSAGE_ID), Some(&message.id));
+    assert_eq!(header.get(HEADER_CONTENT_TYPE).map(String::as_str), Some("application/json"));
+    let decoded = TardisMQMessage::<OrderCreated>::decode(header.clone(), body.clone())?;
+    assert_eq!(decoded.id, message.id);
+    assert_eq!(decoded.timestamp.timestamp_millis(), message.timestamp.timestamp_millis());
+    assert_eq!(decoded.headers, HashMap::from([("tenant".to_string(), "t1".to_string())]));
+    assert_eq!(decoded.payload, message.payload);
+    // mismatched content type
+    assert_eq!(TardisMQMessage::<TardisMQRawPayload>::decode(header, body).unwrap_err().code, "406");
+    // without the envelope metadata
+    let decoded = TardisMQMessage::<OrderCreated>::decode(HashMap::new(), r#"{"id":"o2","amount":20}"#.to_string())?;
+    assert!(!decoded.id.is_empty());
+    assert_eq!(decoded.payload.amount, 20);
+    assert_eq!(TardisMQRawPayload::content_type(), "application/octet-stream");
+
+    let client = TardisMQClient::memory();
+    let memory = client.as_memory().expect("not an in-memory client");
+    client
+        .subscribe_message("order.created", |message: TardisMQMessage<OrderCreated>| async move {
+            assert_eq!(message.payload.id, "o1");
+            assert_eq!(message.headers.get("tenant").map(String::as_str), Some("t1"));
+            ORDER_COUNTER.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await?;
+    client
+        .response_message("file.uploaded", |message: TardisMQMessage<TardisMQRawPayload>| async move {
+            assert_eq!(message.payload.0, vec![0, 159, 146, 150]);
+            RAW_COUNTER.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await?;
+
+    client.publish_message("order.created", &message).await?;
+    assert_eq!(ORDER_COUNTER.load(Ordering::SeqCst), 1);
+    client.request_message("file.uploaded", &TardisMQMessage::new(TardisMQRawPayload(vec![0, 159, 146, 150]))).await?;
+    assert_eq!(RAW_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        memory.sent_messages()?[1].header.get(HEADER_CONTENT_TYPE).map(String::as_str),
+        Some("application/octet-stream")
+    );
+
+    // the messages failed to decode are failed
+    client.publish("order.created", "not json".to_string(), &HashMap::new()).await?;
+    assert_eq!(ORDER_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(memory.failed_messages()?.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{DBModuleConfig, MQOutboxConfig};
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::mq::mq_client::TardisMQClient;
+use tardis::mq::mq_outbox::TardisMQOutbox;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+static RECEIVED_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mq_outbox() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace,sqlx=off");
+    TardisFuns::init_log()?;
+    TardisTestContainer::mysql(None, |url| async move {
+        let db = Arc::new(TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?);
+        let mq = Arc::new(TardisMQClient::memory());
+        let memory = mq.as_memory().expect("not an in-memory client");
+        let outbox = TardisMQOutbox::new(mq.clone(), db.clone(), &MQOutboxConfig::default());
+        outbox.init_table().await?;
+        mq.subscribe("order.created", |(header, msg)| async move {
+            assert_eq!(msg, "order1");
+            assert_eq!(header.get("tenant").map(String::as_str), Some("t1"));
+            RECEIVED_COUNTER.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await?;
+        let header = HashMap::from([("tenant".to_string(), "t1".to_string())]);
+
+        // a transaction is required
+        let conn = db.conn();
+        assert_eq!(
+            mq.publish_transactional(&conn, "order.created", "order1".to_string(), &header).await.unwrap_err().code,
+            "400"
+        );
+
+        // not published if rolled back
+        let mut conn = db.conn();
+        conn.begin().await?;
+        mq.publish_transactional(&conn, "order.created", "order0".to_string(), &header).await?;
+        conn.rollback().await?;
+
+        // published after committed
+        let mut conn = db.conn();
+        conn.begin().await?;
+        mq.publish_transactional(&conn, "order.created", "order1".to_string(), &header).await?;
+        // invisible to the relay before committed
+        assert_eq!(outbox.relay_once().await?, 0);
+        conn.commit().await?;
+        assert!(memory.sent_messages()?.is_empty());
+
+        assert_eq!(outbox.relay_once().await?, 1);
+        assert_eq!(RECEIVED_COUNTER.load(Ordering::SeqCst), 1);
+        assert_eq!(memory.sent_messages()?.len(), 1);
+        // deleted after published
+        assert_eq!(db.conn().count_by_sql("SELECT * FROM tardis_mq_outbox", vec![]).await?, 0);
+        assert_eq!(outbox.relay_once().await?, 0);
+
+        // retried after the claim timeout if the publishing failed
+        let outbox = TardisMQOutbox::new(mq.clone(), db.clone(), &MQOutboxConfig::builder().claim_timeout_ms(0).build());
+        memory.fail_next_sends("order.created", 1)?;
+        let mut conn = db.conn();
+        conn.begin().await?;
+        mq.publish_transactional(&conn, "order.created", "order1".to_string(), &header).await?;
+        conn.commit().await?;
+        assert_eq!(outbox.relay_once().await?, 0);
+        assert_eq!(db.conn().count_by_sql("SELECT * FROM tardis_mq_outbox WHERE attempts = 1", vec![]).await?, 1);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert_eq!(outbox.relay_once().await?, 1);
+        assert_eq!(RECEIVED_COUNTER.load(Ordering::SeqCst), 2);
+
+        // relayed in the background
+        let relay = TardisMQOutbox::new(mq.clone(), db.clone(), &MQOutboxConfig::builder().poll_interval_ms(50).build()).start();
+        let mut conn = db.conn();
+        conn.begin().await?;
+        mq.publish_transactional(&conn, "order.created", "order1".to_string(), &header).await?;
+        conn.commit().await?;
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert_eq!(RECEIVED_COUNTER.load(Ordering::SeqCst), 3);
+        relay.abort();
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, MQConfig, MQKind, MQModuleConfig, MQRedisStreamConfig, TardisConfig};
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+static RESPONSE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static SUBSCRIBE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mq_redis_stream() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    assert_eq!(
+        MQModuleConfig::builder().url("redis://127.0.0.1:6379/0".parse().expect("invalid url")).build().kind(),
+        MQKind::RedisStream
+    );
+
+    TardisTestContainer::redis(|url| async move {
+        TardisFuns::init_conf(TardisConfig {
+            cs: Default::default(),
+            fw: FrameworkConfig::builder()
+                .mq(MQConfig::builder()
+                    .default(
+                        MQModuleConfig::builder().url(url.parse().expect("invalid url")).redis_stream(MQRedisStreamConfig::builder().max_len(100).block_ms(500).build()).build(),
+                    )
+                    .build())
+                .build(),
+        })
+        .await?;
+        let client = TardisFuns::mq();
+        client.health_check().await?;
+        let mut header = HashMap::new();
+        header.insert("k1".to_string(), "v1".to_string());
+
+        // kept until a responder is registered
+        client.request("test-addr", "测试!".to_string(), &header).await?;
+        for _ in 0..2 {
+            client
+                .response("test-addr", |(header, msg)| async move {
+                    assert_eq!(header.get("k1").unwrap(), "v1");
+                    assert_eq!(msg, "测试!");
+                    RESPONSE_COUNTER.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                })
+                .await?;
+            client
+                .subscribe("test-topic", |(header, msg)| async move {
+                    assert_eq!(header.get("k1").unwrap(), "v1");
+                    SUBSCRIBE_COUNTER.fetch_add(1, Ordering::SeqCst);
+                    if msg == "error" {
+                        return Err(TardisError::bad_request("invalid message", ""));
+                    }
+                    Ok(())
+                })
+                .await?;
+        }
+        client.request("test-addr", "测试!".to_string(), &header).await?;
+        client.request("test-addr", "测试!".to_string(), &header).await?;
+        client.publish("test-topic", "测试!".to_string(), &header).await?;
+        client.publish("test-topic", "error".to_string(), &header).await?;
+
+        for _ in 0..40 {
+            if RESPONSE_COUNTER.load(Ordering::SeqCst) >= 3 && SUBSCRIBE_COUNTER.load(Ordering::SeqCst) >= 4 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        // each request is processed once, each publish is received by all the subscribers
+        assert_eq!(RESPONSE_COUNTER.load(Ordering::SeqCst), 3);
+        assert_eq!(SUBSCRIBE_COUNTER.load(Ordering::SeqCst), 4);
+
+        client.close().await?;
+        Ok(())
+    })
+    .await
+}
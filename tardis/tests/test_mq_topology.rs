@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, MQConfig, MQExchangeConfig, MQExchangeKind, MQModuleConfig, MQQueueConfig, MQTopologyConfig, TardisConfig};
+use tardis::TardisFuns;
+
+static CREATED_COUNTER: AtomicUsize = AtomicUsize::new(0);
+static VIP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mq_topology() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    // serde defaults
+    let config = TardisFuns::json.str_to_obj::<MQModuleConfig>(
+        r#"{"url":"mem://","topology":{"exchanges":[{"name":"order"}],"queues":[{"name":"order.created","message_ttl_ms":60000,"dead_letter_exchange":"order.dlx","bindings":[{"exchange":"order","routing_key":"order.*.created"}]}]}}"#,
+    )?;
+    assert_eq!(config.topology.exchanges[0].kind, MQExchangeKind::Topic);
+    assert!(config.topology.exchanges[0].durable);
+    assert!(config.topology.queues[0].durable);
+    assert_eq!(config.topology.queues[0].message_ttl_ms, Some(60000));
+    assert_eq!(config.topology.queues[0].dead_letter_exchange.as_deref(), Some("order.dlx"));
+    assert!(TardisFuns::json.str_to_obj::<MQModuleConfig>(r#"{"url":"mem://"}"#)?.topology.is_empty());
+
+    let topology = MQTopologyConfig::default()
+        .exchange(MQExchangeConfig::builder().name("order").kind(MQExchangeKind::Topic).build())
+        .exchange(MQExchangeConfig::builder().name("order.dlx").kind(MQExchangeKind::Fanout).build())
+        .queue(MQQueueConfig::builder().name("order.created").message_ttl_ms(60000).dead_letter_exchange("order.dlx").build().bind("order", "order.*.created"))
+        .queue(MQQueueConfig::builder().name("order.all").build().bind("order", "order.#"))
+        .queue(MQQueueConfig::builder().name("order.dead").build().bind("order.dlx", ""));
+    TardisFuns::init_conf(TardisConfig {
+        cs: Default::default(),
+        fw: FrameworkConfig::builder()
+            .mq(MQConfig::builder().default(MQModuleConfig::builder().url("mem://".parse().expect("invalid url")).topology(topology).build()).build())
+            .build(),
+    })
+    .await?;
+    let client = TardisFuns::mq();
+    let memory = client.as_memory().expect("not an in-memory client");
+    let header = HashMap::new();
+
+    client
+        .response("order.created", |(_, msg)| async move {
+            assert_eq!(msg, "created");
+            CREATED_COUNTER.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await?;
+    client
+        .subscribe_topic("order", "order.vip.*", |(_, _)| async move {
+            VIP_COUNTER.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await?;
+
+    // routed by the bindings and the topic subscriptions
+    client.publish_topic("order", "order.vip.created", "created".to_string(), &header).await?;
+    assert_eq!(CREATED_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(VIP_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(memory.pending_messages("order.all")?.len(), 1);
+
+    client.publish_topic("order", "order.normal.paid", "paid".to_string(), &header).await?;
+    assert_eq!(CREATED_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(VIP_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(memory.pending_messages("order.all")?.len(), 2);
+
+    // `#` matches zero words, `*` matches exactly one word
+    client.publish_topic("order", "order", "any".to_string(), &header).await?;
+    client.publish_topic("order", "order.vip.created.again", "any".to_string(), &header).await?;
+    assert_eq!(memory.pending_messages("order.all")?.len(), 4);
+    assert_eq!(CREATED_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(VIP_COUNTER.load(Ordering::SeqCst), 1);
+
+    // fanout exchange ignores the routing key
+    client.publish_topic("order.dlx", "whatever", "dead".to_string(), &header).await?;
+    assert_eq!(memory.pending_messages("order.dead")?.len(), 1);
+
+    // direct exchange declared at runtime
+    client
+        .declare_topology(
+            &MQTopologyConfig::default()
+                .exchange(MQExchangeConfig::builder().name("notify").kind(MQExchangeKind::Direct).build())
+                .queue(MQQueueConfig::builder().name("notify.email").build().bind("notify", "email")),
+        )
+        .await?;
+    client.publish_topic("notify", "sms", "sms".to_string(), &header).await?;
+    client.publish_topic("notify", "email", "email".to_string(), &header).await?;
+    let pending = memory.pending_messages("notify.email")?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].destination, "notify");
+    assert_eq!(pending[0].routing_key, "email");
+    assert_eq!(pending[0].message, "email");
+
+    client.close().await?;
+    Ok(())
+}
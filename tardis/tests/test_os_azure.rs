@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{OSModuleConfig, WebClientModuleConfig};
+use tardis::os::os_client::TardisOSClient;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::web::web_client::TardisWebClient;
+use tardis::TardisFuns;
+
+// the well-known account key of Azurite
+const AZURITE_KEY: &str = "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_os_azure() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    TardisTestContainer::azurite(|url| async move {
+        let client = TardisOSClient::init(
+            &OSModuleConfig::builder().kind("azure").endpoint(url).ak("devstoreaccount1").sk(AZURITE_KEY).default_bucket("test").multipart_part_size(1024).build(),
+        )?;
+        client.bucket_create_simple("test", true).await?;
+        assert_eq!(client.bucket_create_simple("test", true).await.err().unwrap().code, "409");
+        client.health_check().await?;
+
+        client.object_create("a/b.txt", b"hello", Some("text/plain"), None).await?;
+        assert_eq!(client.object_get("a/b.txt", None).await?, b"hello");
+        assert_eq!(client.object_get("a/c.txt", None).await.err().unwrap().code, "404");
+
+        // presigned urls
+        let url = client.object_get_url("a/b.txt", 0, None)?;
+        assert!(url.contains("sp=r"));
+        let response = TardisWebClient::init(&WebClientModuleConfig::default())?.get_to_str(url.as_str(), []).await?;
+        assert_eq!(response.code, 200);
+        assert_eq!(response.body.as_deref(), Some("hello"));
+
+        // metadata and tags
+        let metadata = HashMap::from([("owner".to_string(), "tardis".to_string())]);
+        client.object_create_with_meta("meta.txt", b"meta", Some("text/plain"), &metadata, None).await?;
+        let meta = client.object_head("meta.txt", None).await?;
+        assert_eq!(meta.content_length, Some(4));
+        assert_eq!(meta.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(meta.metadata, metadata);
+        let tags = HashMap::from([("env".to_string(), "test".to_string())]);
+        client.object_tags_put("meta.txt", &tags, None).await?;
+        assert_eq!(client.object_tags_get("meta.txt", None).await?, tags);
+        client.object_tags_delete("meta.txt", None).await?;
+        assert!(client.object_tags_get("meta.txt", None).await?.is_empty());
+
+        // multipart upload by the blocks
+        let file_path = env::temp_dir().join(format!("tardis-azure-{}", TardisFuns::field.nanoid()));
+        let content = (0..3000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        std::fs::write(&file_path, &content)?;
+        client.object_create_multipart("large.bin", &file_path, Some("application/zip"), None).await?;
+        assert_eq!(client.object_get("large.bin", None).await?, content);
+        assert_eq!(client.object_head("large.bin", None).await?.content_type.as_deref(), Some("application/zip"));
+        std::fs::remove_file(&file_path)?;
+
+        // listing, copy and batch deletion
+        client.copy_object("a/b.txt", "a/copied.txt", None, None).await?;
+        let page = client.list_objects("a/", None, None).await?;
+        assert_eq!(page.objects.iter().map(|object| object.path.as_str()).collect::<Vec<_>>(), vec!["a/b.txt", "a/copied.txt"]);
+        assert_eq!(page.objects[1].size, 5);
+        assert!(client.delete_objects(&["a/copied.txt", "meta.txt", "large.bin"], None).await?.is_empty());
+        assert_eq!(client.bucket_lifecycle_get(None).await.err().unwrap().code, "501");
+
+        client.object_delete("a/b.txt", None).await?;
+        assert_eq!(client.object_get("a/b.txt", None).await.err().unwrap().code, "404");
+        client.bucket_delete("test").await?;
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::OSModuleConfig;
+use tardis::os::os_client::{TardisOSClient, TardisOSLifecycleRule};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_os_fs() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    let root = env::temp_dir().join(format!("tardis-os-{}", TardisFuns::field.nanoid()));
+    assert_eq!(
+        TardisOSClient::init(&OSModuleConfig::builder().kind("fs").build()).err().unwrap().code,
+        "406-tardis-os-fs-root-error"
+    );
+    let client =
+        TardisOSClient::init(&OSModuleConfig::builder().kind("fs").endpoint(format!("fs://{}", root.to_string_lossy())).default_bucket("test").multipart_part_size(4).build())?;
+    client.bucket_create_simple("test", true).await?;
+    assert_eq!(client.bucket_create_simple("test", true).await.err().unwrap().code, "409");
+    client.health_check().await?;
+
+    // objects in the bucket directory
+    client.object_create("a/b.txt", b"hello", Some("text/plain"), None).await?;
+    assert_eq!(std::fs::read(root.join("test/a/b.txt"))?, b"hello");
+    assert_eq!(client.object_get("a/b.txt", None).await?, b"hello");
+    assert_eq!(client.object_get("a/c.txt", None).await.err().unwrap().code, "404");
+    assert_eq!(client.object_get("../escape.txt", None).await.err().unwrap().code, "400");
+    assert_eq!(client.object_get("a.txt", Some("../test")).await.err().unwrap().code, "400");
+    assert!(client.object_get_url("a/b.txt", 0, None)?.starts_with("file://"));
+
+    // metadata and tags
+    let metadata = HashMap::from([("owner".to_string(), "tardis".to_string())]);
+    client.object_create_with_meta("meta.txt", b"meta", None, &metadata, None).await?;
+    let meta = client.object_head("meta.txt", None).await?;
+    assert_eq!(meta.content_length, Some(4));
+    assert_eq!(meta.content_type.as_deref(), Some("application/octet-stream"));
+    assert_eq!(meta.metadata, metadata);
+    assert!(meta.etag.is_some() && meta.last_modified.is_some());
+    let tags = HashMap::from([("env".to_string(), "test".to_string())]);
+    client.object_tags_put("meta.txt", &tags, None).await?;
+    assert_eq!(client.object_tags_get("meta.txt", None).await?, tags);
+    client.object_tags_delete("meta.txt", None).await?;
+    assert!(client.object_tags_get("meta.txt", None).await?.is_empty());
+    assert_eq!(client.object_tags_put("none.txt", &tags, None).await.err().unwrap().code, "404");
+
+    // multipart upload
+    let file_path = root.join("source.bin");
+    std::fs::write(&file_path, b"0123456789")?;
+    client.object_create_multipart("large.bin", &file_path, Some("application/zip"), None).await?;
+    assert_eq!(client.object_get("large.bin", None).await?, b"0123456789");
+    assert_eq!(client.object_head("large.bin", None).await?.content_type.as_deref(), Some("application/zip"));
+    let mut upload = client.object_multipart_init("aborted.bin", None, None).await?;
+    client.object_multipart_upload_file(&mut upload, &file_path).await?;
+    assert_eq!(upload.parts.len(), 3);
+    client.object_multipart_abort(&upload).await?;
+    assert_eq!(client.object_multipart_complete(&upload).await.err().unwrap().code, "404");
+
+    // listing, copy and batch deletion
+    client.object_create("a/c.txt", b"c", None, None).await?;
+    let page = client.list_objects("a/", None, None).await?;
+    assert_eq!(page.objects.iter().map(|object| object.path.as_str()).collect::<Vec<_>>(), vec!["a/b.txt", "a/c.txt"]);
+    assert!(page.next_continuation_token.is_none());
+    client.copy_object("meta.txt", "copied/meta.txt", None, None).await?;
+    assert_eq!(client.object_head("copied/meta.txt", None).await?.metadata, metadata);
+    assert!(client.delete_objects(&["a/c.txt", "copied/meta.txt"], None).await?.is_empty());
+    assert_eq!(client.list_objects("", None, None).await?.objects.len(), 3);
+    std::fs::remove_dir(root.join("test/copied"))?;
+
+    // the lifecycle rules are stored only
+    let rules = vec![TardisOSLifecycleRule {
+        id: "expire".to_string(),
+        enabled: true,
+        expiration_days: Some(1),
+        ..Default::default()
+    }];
+    client.bucket_lifecycle_put(&rules, None).await?;
+    assert_eq!(client.bucket_lifecycle_get(None).await?, rules);
+    client.bucket_lifecycle_delete(None).await?;
+    assert!(client.bucket_lifecycle_get(None).await?.is_empty());
+
+    // only the empty buckets are deleted
+    assert!(client.bucket_delete("test").await.is_err());
+    for path in ["a/b.txt", "meta.txt", "large.bin"] {
+        client.object_delete(path, None).await?;
+    }
+    std::fs::remove_dir(root.join("test/a"))?;
+    client.bucket_delete("test").await?;
+    assert_eq!(client.health_check().await.err().unwrap().code, "404");
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, OSModuleConfig, TardisConfig};
+use tardis::os::os_client::TardisOSLifecycleRule;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_os_housekeeping() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    TardisTestContainer::minio(|url| async move {
+        let os_module_config = OSModuleConfig::builder().kind("s3").endpoint(url).ak("minioadmin").sk("minioadmin").region("us-east-1").default_bucket("tmp").build();
+        TardisFuns::init_conf(TardisConfig::builder().fw(FrameworkConfig::builder().os(os_module_config).build()).build()).await?;
+        TardisFuns::os().bucket_create_simple("tmp", true).await?;
+        TardisFuns::os().bucket_create_simple("archive", true).await?;
+
+        // listing by pages
+        for i in 0..3 {
+            TardisFuns::os().object_create(&format!("uploads/{i}.txt"), format!("upload {i}").as_bytes(), None, None).await?;
+        }
+        TardisFuns::os().object_create("other.txt", b"other", None, None).await?;
+        let page = TardisFuns::os().list_objects("uploads/", None, None).await?;
+        assert_eq!(
+            page.objects.iter().map(|object| object.path.as_str()).collect::<Vec<_>>(),
+            vec!["uploads/0.txt", "uploads/1.txt", "uploads/2.txt"]
+        );
+        assert_eq!(page.objects[0].size, 8);
+        assert!(page.next_continuation_token.is_none());
+
+        // server-side copy across the buckets
+        TardisFuns::os().copy_object("uploads/0.txt", "2024/0 copy.txt", None, Some("archive")).await?;
+        assert_eq!(TardisFuns::os().object_get("2024/0 copy.txt", Some("archive")).await?, b"upload 0");
+        assert!(TardisFuns::os().copy_object("uploads/none.txt", "none.txt", None, None).await.is_err());
+
+        // batch deletion
+        let failed = TardisFuns::os().delete_objects(&["uploads/0.txt", "uploads/1.txt", "uploads/2.txt"], None).await?;
+        assert!(failed.is_empty());
+        assert!(TardisFuns::os().list_objects("uploads/", None, None).await?.objects.is_empty());
+
+        // lifecycle rules
+        assert!(TardisFuns::os().bucket_lifecycle_get(None).await?.is_empty());
+        let rules = vec![TardisOSLifecycleRule {
+            id: "expire-uploads".to_string(),
+            prefix: "uploads/".to_string(),
+            enabled: true,
+            expiration_days: Some(7),
+            abort_incomplete_multipart_days: Some(1),
+            ..Default::default()
+        }];
+        TardisFuns::os().bucket_lifecycle_put(&rules, None).await?;
+        assert_eq!(TardisFuns::os().bucket_lifecycle_get(None).await?, rules);
+        TardisFuns::os().bucket_lifecycle_delete(None).await?;
+        assert!(TardisFuns::os().bucket_lifecycle_get(None).await?.is_empty());
+        Ok(())
+    })
+    .await
+}
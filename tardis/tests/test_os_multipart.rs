@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, OSModuleConfig, TardisConfig};
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_os_multipart() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    TardisTestContainer::minio(|url| async move {
+        let os_module_config = OSModuleConfig::builder()
+            .kind("s3")
+            .endpoint(url)
+            .ak("minioadmin")
+            .sk("minioadmin")
+            .region("us-east-1")
+            .presign_expire_sec(600)
+            .multipart_part_size(5 * 1024 * 1024)
+            .multipart_concurrency(2)
+            .build();
+        TardisFuns::init_conf(TardisConfig::builder().fw(FrameworkConfig::builder().os(os_module_config).build()).build()).await?;
+        let bucket_name = Some("multipart");
+        TardisFuns::os().bucket_create_simple("multipart", true).await?;
+
+        // presigned urls with the configured expiry
+        assert!(TardisFuns::os().object_get_url("a.txt", 0, bucket_name)?.contains("X-Amz-Expires=600"));
+        assert!(TardisFuns::os().object_create_url("a.txt", 60, bucket_name)?.contains("X-Amz-Expires=60"));
+
+        // metadata and tags
+        let metadata = HashMap::from([("owner".to_string(), "tardis".to_string())]);
+        TardisFuns::os().object_create_with_meta("meta.txt", b"meta", Some("text/plain"), &metadata, bucket_name).await?;
+        let meta = TardisFuns::os().object_head("meta.txt", bucket_name).await?;
+        assert_eq!(meta.content_length, Some(4));
+        assert_eq!(meta.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(meta.metadata.get("owner").map(String::as_str), Some("tardis"));
+        let tags = HashMap::from([("env".to_string(), "test".to_string())]);
+        TardisFuns::os().object_tags_put("meta.txt", &tags, bucket_name).await?;
+        assert_eq!(TardisFuns::os().object_tags_get("meta.txt", bucket_name).await?, tags);
+        TardisFuns::os().object_tags_delete("meta.txt", bucket_name).await?;
+        assert!(TardisFuns::os().object_tags_get("meta.txt", bucket_name).await?.is_empty());
+
+        // multipart upload of 3 parts
+        let file_path = env::temp_dir().join(format!("tardis-multipart-{}", TardisFuns::field.nanoid()));
+        let content = (0..11 * 1024 * 1024).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        std::fs::write(&file_path, &content)?;
+        TardisFuns::os().object_create_multipart("large.bin", &file_path, None, bucket_name).await?;
+        assert_eq!(TardisFuns::os().object_get("large.bin", bucket_name).await?, content);
+
+        // resume from the persisted state
+        let mut upload = TardisFuns::os().object_multipart_init("resumed.bin", Some("application/octet-stream"), bucket_name).await?;
+        TardisFuns::os().object_multipart_upload_file(&mut upload, &file_path).await?;
+        assert_eq!(upload.parts.iter().map(|part| part.part_number).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let mut upload = TardisFuns::json.str_to_obj::<tardis::os::os_client::TardisOSMultipartUpload>(&TardisFuns::json.obj_to_string(&upload)?)?;
+        upload.parts.retain(|part| part.part_number != 2);
+        TardisFuns::os().object_multipart_upload_file(&mut upload, &file_path).await?;
+        assert_eq!(upload.parts.len(), 3);
+        TardisFuns::os().object_multipart_complete(&upload).await?;
+        assert_eq!(TardisFuns::os().object_head("resumed.bin", bucket_name).await?.content_length, Some(content.len() as u64));
+
+        // the aborted upload can't be completed
+        let upload = TardisFuns::os().object_multipart_init("aborted.bin", None, bucket_name).await?;
+        TardisFuns::os().object_multipart_abort(&upload).await?;
+        assert!(TardisFuns::os().object_multipart_complete(&upload).await.is_err());
+
+        std::fs::remove_file(&file_path)?;
+        for path in ["meta.txt", "large.bin", "resumed.bin"] {
+            TardisFuns::os().object_delete(path, bucket_name).await?;
+        }
+        TardisFuns::os().bucket_delete("multipart").await?;
+        Ok(())
+    })
+    .await
+}
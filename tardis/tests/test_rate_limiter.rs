@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::cache::rate_limiter::TardisRateLimiter;
+use tardis::chrono::{self, TimeZone, Utc};
+use tardis::config::config_dto::{RateLimitStrategy, RateLimiterConfig};
+use tardis::test::mock_clock::TardisMockClock;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::endpoint::make_sync;
+use tardis::web::poem::http::Method;
+use tardis::web::poem::Route;
+use tardis::web::rate_limit_mw::RateLimit;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limiter() -> TardisResult<()> {
+    let clock = TardisMockClock::install_at(Utc.with_ymd_and_hms(2023, 8, 1, 10, 0, 0).unwrap());
+    let cache = TardisCacheClient::memory();
+    let limiter = |strategy| TardisRateLimiter::new(cache.clone(), RateLimiterConfig::builder().strategy(strategy).limit(2).window_ms(1000).build());
+
+    // fixed window
+    let fixed_window = limiter(RateLimitStrategy::FixedWindow);
+    assert_eq!(fixed_window.acquire("u1").await?.remaining, 1);
+    clock.advance(chrono::Duration::milliseconds(700));
+    assert_eq!(fixed_window.acquire("u1").await?.remaining, 0);
+    let decision = fixed_window.acquire("u1").await?;
+    assert!(!decision.allowed);
+    assert_eq!(decision.retry_after, Duration::from_millis(300));
+    assert!(fixed_window.acquire("u2").await?.allowed);
+    assert_eq!(fixed_window.check("u1").await.unwrap_err().code, "429-tardis-rate-limited");
+    clock.advance(chrono::Duration::milliseconds(300));
+    assert!(fixed_window.acquire("u1").await?.allowed);
+
+    // sliding window
+    let sliding_window = limiter(RateLimitStrategy::SlidingWindow);
+    assert!(sliding_window.acquire("u1").await?.allowed);
+    clock.advance(chrono::Duration::milliseconds(500));
+    assert_eq!(sliding_window.acquire("u1").await?.remaining, 0);
+    clock.advance(chrono::Duration::milliseconds(100));
+    let decision = sliding_window.acquire("u1").await?;
+    assert!(!decision.allowed);
+    assert_eq!(decision.retry_after, Duration::from_millis(400));
+    clock.advance(chrono::Duration::milliseconds(400));
+    let decision = sliding_window.acquire("u1").await?;
+    assert!(decision.allowed);
+    assert_eq!(decision.remaining, 0);
+
+    // token bucket
+    let token_bucket = limiter(RateLimitStrategy::TokenBucket);
+    assert_eq!(token_bucket.acquire("u1").await?.remaining, 1);
+    assert_eq!(token_bucket.acquire("u1").await?.remaining, 0);
+    let decision = token_bucket.acquire("u1").await?;
+    assert!(!decision.allowed);
+    assert_eq!(decision.retry_after, Duration::from_millis(500));
+    clock.advance(chrono::Duration::milliseconds(500));
+    assert!(token_bucket.acquire("u1").await?.allowed);
+    assert!(!token_bucket.acquire("u1").await?.allowed);
+    clock.advance(chrono::Duration::seconds(10));
+    assert_eq!(token_bucket.acquire("u1").await?.remaining, 1);
+
+    // middleware
+    let route = Route::new().nest(
+        "/",
+        Route::new().at("/hello", make_sync(|_| "hello")).with(RateLimit::new(limiter(RateLimitStrategy::FixedWindow))),
+    );
+    // the header set by the client is ignored without the trusted proxies
+    let client = TardisWebTestClient::from_route(route).remote_addr("10.0.0.1:1234".parse().unwrap());
+    client.get("/hello").await?.assert_status(200).assert_header("X-RateLimit-Limit", "2").assert_header("X-RateLimit-Remaining", "1");
+    client
+        .request(Method::GET, "/hello", vec![("X-Forwarded-For".to_string(), "1.1.1.1".to_string())], None)
+        .await?
+        .assert_status(200)
+        .assert_header("X-RateLimit-Remaining", "0");
+    let resp = client.request(Method::GET, "/hello", vec![("X-Forwarded-For".to_string(), "2.2.2.2".to_string())], None).await?;
+    resp.assert_status(429).assert_header("Retry-After", "1");
+    assert!(resp.body.contains("429-tardis-rate-limited"));
+
+    // the right-most untrusted address of the trusted proxies
+    let route = Route::new().nest(
+        "/",
+        Route::new()
+            .at("/hello", make_sync(|_| "hello"))
+            .with(RateLimit::new(limiter(RateLimitStrategy::FixedWindow)).trusted_proxies(["10.0.0.2".parse().unwrap(), "10.0.0.3".parse().unwrap()])),
+    );
+    let client = TardisWebTestClient::from_route(route).remote_addr("10.0.0.2:1234".parse().unwrap());
+    let forwarded_for = |value: &str| vec![("X-Forwarded-For".to_string(), value.to_string())];
+    for spoofed in ["3.3.3.3, 4.4.4.4, 10.0.0.3", "5.5.5.5, 4.4.4.4"] {
+        client.request(Method::GET, "/hello", forwarded_for(spoofed), None).await?.assert_status(200);
+    }
+    client.request(Method::GET, "/hello", forwarded_for("6.6.6.6,4.4.4.4"), None).await?.assert_status(429);
+    client.request(Method::GET, "/hello", forwarded_for("4.4.4.4, 7.7.7.7"), None).await?.assert_status(200);
+
+    // requests without key are not limited
+    let route = Route::new().nest(
+        "/",
+        Route::new().at("/hello", make_sync(|_| "hello")).with(RateLimit::new(limiter(RateLimitStrategy::FixedWindow)).key_by(|req| req.header("X-App-Id").map(str::to_string))),
+    );
+    let client = TardisWebTestClient::from_route(route);
+    for _ in 0..3 {
+        client.get("/hello").await?.assert_status(200);
+    }
+
+    clock.uninstall();
+    Ok(())
+}
@@ -64,6 +64,7 @@ async fn test_raw_query(client: &TardisRelDBClient) -> TardisResult<()> {
         ak: "ak1".to_string(),
         roles: vec![],
         groups: vec![],
+        request_id: "".to_string(),
         owner: "acc1".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
@@ -143,6 +144,7 @@ async fn test_advanced_query(client: &TardisRelDBClient) -> TardisResult<()> {
         ak: "ak1".to_string(),
         roles: vec![],
         groups: vec![],
+        request_id: "".to_string(),
         owner: "acc1".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
@@ -347,6 +349,7 @@ async fn test_rel(client: &TardisRelDBClient) -> TardisResult<()> {
         ak: "ak1".to_string(),
         roles: vec![],
         groups: vec![],
+        request_id: "".to_string(),
         owner: "acc1".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
@@ -597,6 +600,7 @@ async fn test_field_type(client: &TardisRelDBClient) -> TardisResult<()> {
         ak: "ak1".to_string(),
         roles: vec![],
         groups: vec![],
+        request_id: "".to_string(),
         owner: "acc1".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
@@ -0,0 +1,71 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::DBModuleConfig;
+use tardis::db::migration::TardisMigration;
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::tardis_migrations;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_migration() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace,sqlx=off");
+    TardisFuns::init_log()?;
+
+    // file names
+    let migration = TardisMigration::from_file("V12__add_order_remark.sql", "")?;
+    assert_eq!((migration.version, migration.name.as_str()), (12, "add_order_remark"));
+    assert_eq!(TardisMigration::from_file("init.sql", "").err().unwrap().code, "406");
+    assert_eq!(TardisMigration::from_file("Vx__init.sql", "").err().unwrap().code, "406");
+
+    // the directory and the embedded files are the same
+    let mut migrations = TardisMigration::from_dir("tests/migrations")?;
+    migrations.sort_by_key(|migration| migration.version);
+    assert_eq!(migrations.iter().map(|migration| migration.version).collect::<Vec<_>>(), vec![1, 2]);
+    let embedded = tardis_migrations!("tests/migrations", ["V1__init.sql", "V2__add_order_remark.sql"])?;
+    assert_eq!(
+        embedded.iter().map(|migration| migration.name.as_str()).collect::<Vec<_>>(),
+        vec!["init", "add_order_remark"]
+    );
+
+    TardisTestContainer::mysql(None, |url| async move {
+        let embedded = tardis_migrations!("tests/migrations", ["V1__init.sql", "V2__add_order_remark.sql"])?;
+        // serde defaults
+        let config = TardisFuns::json.str_to_obj::<DBModuleConfig>(&format!(r#"{{"url":"{url}"}}"#))?;
+        assert!(!config.run_migrations);
+        assert_eq!(config.migrations_dir, "migrations");
+        assert_eq!(config.migration_table, "tardis_schema_history");
+
+        // applied on initialization
+        let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).run_migrations(true).migrations_dir("tests/migrations").build()).await?;
+        assert_eq!(client.conn().count_by_sql("SELECT * FROM tardis_schema_history", vec![]).await?, 2);
+        assert_eq!(client.conn().count_by_sql("SELECT * FROM migration_order WHERE remark = ''", vec![]).await?, 1);
+
+        // the applied migrations are skipped
+        assert!(client.migrate(&embedded).await?.is_empty());
+        TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).run_migrations(true).migrations_dir("tests/migrations").build()).await?;
+        assert_eq!(client.conn().count_by_sql("SELECT * FROM migration_order", vec![]).await?, 1);
+
+        // the pending code migration is applied
+        let mut migrations = embedded.clone();
+        migrations.push(TardisMigration::code(3, "add_second_order", |conn| {
+            Box::pin(async move {
+                conn.execute_one("INSERT INTO migration_order (id, name) VALUES (2, 'second')", vec![]).await?;
+                Ok(())
+            })
+        }));
+        assert_eq!(client.migrate(&migrations).await?, vec![3]);
+        assert_eq!(client.conn().count_by_sql("SELECT * FROM migration_order", vec![]).await?, 2);
+
+        // the modified migration is rejected
+        let modified = vec![TardisMigration::sql(1, "init", "CREATE TABLE migration_other (id INT PRIMARY KEY)")];
+        assert_eq!(client.migrate(&modified).await.err().unwrap().code, "409");
+
+        // the duplicated versions are rejected
+        let duplicated = vec![TardisMigration::sql(4, "a", "SELECT 1"), TardisMigration::sql(4, "b", "SELECT 1")];
+        assert_eq!(client.migrate(&duplicated).await.err().unwrap().code, "409");
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,69 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::DBModuleConfig;
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::db::sea_orm::sea_query::{Alias, Expr, Order, Query};
+use tardis::db::sea_orm::FromQueryResult;
+use tardis::futures::TryStreamExt;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+#[derive(Debug, FromQueryResult)]
+struct PagingRecord {
+    id: i32,
+    name: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_paging() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace,sqlx=off");
+    TardisFuns::init_log()?;
+    TardisTestContainer::mysql(None, |url| async move {
+        let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+        client.conn().execute_one("CREATE TABLE IF NOT EXISTS paging_record (id INT PRIMARY KEY, name VARCHAR(255) NOT NULL)", vec![]).await?;
+        for id in 1..=25 {
+            client.conn().execute_one("INSERT INTO paging_record VALUES (?, ?)", vec![id.into(), format!("record{id}").into()]).await?;
+        }
+        let select = Query::select()
+            .columns([Alias::new("id"), Alias::new("name")])
+            .from(Alias::new("paging_record"))
+            .and_where(Expr::col(Alias::new("id")).gt(5))
+            .order_by(Alias::new("id"), Order::Asc)
+            .to_owned();
+
+        // paginate
+        let (total, records) = client.conn().paginate::<PagingRecord>(&select, 1, 10).await?;
+        assert_eq!(total, 20);
+        assert_eq!(records.iter().map(|record| record.id).collect::<Vec<_>>(), (6..=15).collect::<Vec<_>>());
+        let (total, records) = client.conn().paginate::<PagingRecord>(&select, 2, 15).await?;
+        assert_eq!(total, 20);
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].name, "record21");
+        // beyond the last page
+        let (total, records) = client.conn().paginate::<PagingRecord>(&select, 3, 10).await?;
+        assert_eq!(total, 20);
+        assert!(records.is_empty());
+        // same as the paginate_dtos
+        let (dtos, count) = client.conn().paginate_dtos::<PagingRecord>(&select, 1, 10).await?;
+        assert_eq!((dtos.len() as u64, count), (10, 20));
+
+        // stream
+        let conn = client.conn();
+        let records = conn.stream_query::<PagingRecord>(&select).await?.try_collect::<Vec<_>>().await?;
+        assert_eq!(records.len(), 20);
+        assert_eq!(records.last().unwrap().id, 25);
+        let records = conn.stream_query_by_sql::<PagingRecord>("SELECT id, name FROM paging_record WHERE id <= ?", vec![3.into()]).await?.try_collect::<Vec<_>>().await?;
+        assert_eq!(records.len(), 3);
+
+        // in the transaction
+        let mut conn = client.conn();
+        conn.begin().await?;
+        conn.execute_one("INSERT INTO paging_record VALUES (26, 'record26')", vec![]).await?;
+        assert_eq!(conn.paginate::<PagingRecord>(&select, 1, 10).await?.0, 21);
+        assert_eq!(conn.stream_query::<PagingRecord>(&select).await?.try_collect::<Vec<_>>().await?.len(), 21);
+        conn.rollback().await?;
+        Ok(())
+    })
+    .await
+}
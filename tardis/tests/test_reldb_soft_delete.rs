@@ -0,0 +1,98 @@
+use std::env;
+
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::DBModuleConfig;
+use tardis::db::reldb_client::{TardisActiveModel, TardisRelDBClient, TardisSoftDeleteEntity};
+use tardis::db::sea_orm::*;
+use tardis::test::test_container::TardisTestContainer;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_soft_delete() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace,sqlx=off");
+    TardisFuns::init_log()?;
+    TardisTestContainer::mysql(None, |url| async move {
+        let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+        client.conn().create_table(&todo::ActiveModel::create_table_statement(client.backend())).await?;
+        let ctx = TardisContext {
+            owner: "owner1".to_string(),
+            ..Default::default()
+        };
+
+        // audit fields are filled on insert
+        for id in ["t1", "t2"] {
+            client
+                .conn()
+                .insert_one(
+                    todo::ActiveModel {
+                        id: Set(id.to_string()),
+                        title: Set(format!("todo {id}")),
+                        ..Default::default()
+                    },
+                    &ctx,
+                )
+                .await?;
+        }
+        let t1 = todo::Entity::find_by_id("t1").one(client.conn().raw_conn()).await?.unwrap();
+        assert_eq!(t1.owner, "owner1");
+        assert!(!t1.deleted);
+
+        // the update time is refreshed on update, but the owner and the create time are kept
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        client
+            .conn()
+            .update_one(
+                todo::ActiveModel {
+                    id: Set("t1".to_string()),
+                    title: Set("todo t1 updated".to_string()),
+                    ..Default::default()
+                },
+                &TardisContext {
+                    owner: "owner2".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let updated = todo::Entity::find_by_id("t1").one(client.conn().raw_conn()).await?.unwrap();
+        assert_eq!(updated.owner, "owner1");
+        assert_eq!(updated.create_time, t1.create_time);
+        assert!(updated.update_time > t1.update_time);
+
+        // the deleted records are excluded unless explicitly included
+        let deleted = client.conn().mark_deleted::<todo::Entity>(Condition::all().add(todo::Column::Id.eq("t1"))).await?;
+        assert_eq!(deleted, 1);
+        assert_eq!(client.conn().mark_deleted::<todo::Entity>(Condition::all().add(todo::Column::Id.eq("t1"))).await?, 0);
+        let alive = todo::Entity::find_alive().all(client.conn().raw_conn()).await?;
+        assert_eq!(alive.iter().map(|todo| todo.id.as_str()).collect::<Vec<_>>(), vec!["t2"]);
+        assert_eq!(todo::Entity::find_with_deleted().count(client.conn().raw_conn()).await?, 2);
+
+        assert_eq!(client.conn().restore_deleted::<todo::Entity>(Condition::all().add(todo::Column::Id.eq("t1"))).await?, 1);
+        assert_eq!(todo::Entity::find_alive().count(client.conn().raw_conn()).await?, 2);
+        Ok(())
+    })
+    .await
+}
+
+pub mod todo {
+    use tardis::chrono::{self, Utc};
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "soft_delete_todo")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub title: String,
+        #[fill_ctx(owner)]
+        pub owner: String,
+        #[fill_ctx(create_time)]
+        pub create_time: chrono::DateTime<Utc>,
+        #[fill_ctx(update_time)]
+        pub update_time: chrono::DateTime<Utc>,
+        #[fill_ctx(deleted)]
+        pub deleted: bool,
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::chrono::{TimeZone, Utc};
+use tardis::config::config_dto::{SchedulerConfig, SchedulerJobConfig};
+use tardis::scheduler::scheduler_client::{TardisJob, TardisSchedule, TardisScheduler};
+use tardis::TardisFuns;
+use tokio::time::{sleep, Duration};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scheduler() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log()?;
+    test_schedule()?;
+    test_lifecycle().await?;
+    test_config().await?;
+    test_distributed().await?;
+    Ok(())
+}
+
+fn test_schedule() -> TardisResult<()> {
+    let time = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 7).unwrap();
+    // the intervals are aligned to the epoch
+    let schedule = TardisSchedule::interval(Duration::from_secs(10))?;
+    assert_eq!(schedule.next_after(&time), Some(Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 10).unwrap()));
+    assert_eq!(schedule.to_string(), "every 10s");
+    let schedule = TardisSchedule::cron("0 30 2 * * *")?;
+    assert_eq!(schedule.next_after(&time), Some(Utc.with_ymd_and_hms(2024, 1, 2, 2, 30, 0).unwrap()));
+    assert_eq!(schedule.to_string(), "cron(0 30 2 * * *)");
+    assert_eq!(TardisSchedule::cron("0 0 3 * * * 2020")?.next_after(&time), None);
+
+    assert_eq!(TardisSchedule::cron("every day").unwrap_err().code, "406");
+    assert_eq!(TardisSchedule::interval(Duration::ZERO).unwrap_err().code, "406");
+    Ok(())
+}
+
+async fn test_lifecycle() -> TardisResult<()> {
+    let scheduler = TardisScheduler::new(SchedulerConfig::default());
+    let counter = Arc::new(AtomicUsize::new(0));
+    let job_counter = counter.clone();
+    scheduler.register_interval("count", Duration::from_millis(200), move || {
+        let counter = job_counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })?;
+    scheduler.register_interval("fail", Duration::from_millis(200), || async { Err(TardisError::internal_error("failed", "")) })?;
+    scheduler.register_interval("panic", Duration::from_millis(200), || async { panic!("panicked") })?;
+    assert_eq!(
+        scheduler.register_interval("count", Duration::from_millis(200), || async { Ok(()) }).unwrap_err().code,
+        "409"
+    );
+    assert_eq!(
+        scheduler.jobs().iter().map(|status| status.name.as_str()).collect::<Vec<_>>(),
+        vec!["count", "fail", "panic"]
+    );
+
+    // not started
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+    assert!(!scheduler.is_running());
+    assert!(scheduler.job("count").unwrap().next_run.is_none());
+
+    scheduler.start()?;
+    assert!(scheduler.is_running());
+    sleep(Duration::from_millis(700)).await;
+    assert!(counter.load(Ordering::SeqCst) >= 2);
+    let status = scheduler.job("count").unwrap();
+    assert!(status.enabled);
+    assert!(!status.distributed);
+    assert!(status.next_run.unwrap() > Utc::now());
+    assert!(status.last_run.is_some());
+    assert!(status.runs >= 2);
+    assert_eq!(status.failures, 0);
+    assert!(status.last_error.is_none());
+    let status = scheduler.job("fail").unwrap();
+    assert!(status.runs >= 2);
+    assert_eq!(status.failures, status.runs);
+    assert!(status.last_error.unwrap().contains("failed"));
+    // the job is still scheduled after panicking
+    let status = scheduler.job("panic").unwrap();
+    assert!(status.failures >= 2);
+    assert!(status.last_error.unwrap().contains("panicked"));
+
+    // the jobs registered after starting are started at once
+    let late_counter = Arc::new(AtomicUsize::new(0));
+    let job_counter = late_counter.clone();
+    scheduler.register_interval("late", Duration::from_millis(100), move || {
+        let counter = job_counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })?;
+    sleep(Duration::from_millis(350)).await;
+    assert!(late_counter.load(Ordering::SeqCst) >= 2);
+    assert!(scheduler.unregister("late").await);
+    assert!(!scheduler.unregister("late").await);
+    let late_runs = late_counter.load(Ordering::SeqCst);
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(late_counter.load(Ordering::SeqCst), late_runs);
+
+    // trigger
+    let runs = counter.load(Ordering::SeqCst);
+    assert!(scheduler.trigger("count").await?);
+    assert!(counter.load(Ordering::SeqCst) > runs);
+    assert_eq!(scheduler.trigger("fail").await.unwrap_err().code, "500");
+    assert_eq!(scheduler.trigger("none").await.unwrap_err().code, "404");
+
+    // the executions don't overlap
+    scheduler.register_interval("slow", Duration::from_secs(3600), || async {
+        sleep(Duration::from_millis(300)).await;
+        Ok(())
+    })?;
+    let (first, second) = tokio::join!(scheduler.trigger("slow"), async {
+        sleep(Duration::from_millis(100)).await;
+        scheduler.trigger("slow").await
+    });
+    assert!(first?);
+    assert!(!second?);
+    assert_eq!(scheduler.job("slow").unwrap().skipped, 1);
+
+    scheduler.stop().await;
+    assert!(!scheduler.is_running());
+    assert!(scheduler.job("count").unwrap().next_run.is_none());
+    let runs = counter.load(Ordering::SeqCst);
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(counter.load(Ordering::SeqCst), runs);
+    Ok(())
+}
+
+async fn test_config() -> TardisResult<()> {
+    // disabled on the instance
+    let scheduler = TardisScheduler::new(SchedulerConfig::builder().enabled(false).build());
+    let counter = Arc::new(AtomicUsize::new(0));
+    let job_counter = counter.clone();
+    scheduler.register_interval("count", Duration::from_millis(100), move || {
+        let counter = job_counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })?;
+    scheduler.start()?;
+    assert!(!scheduler.is_running());
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+    // overrides of the jobs
+    let scheduler = TardisScheduler::new(
+        SchedulerConfig::builder()
+            .jobs(HashMap::from([
+                ("disabled".to_string(), SchedulerJobConfig::builder().enabled(false).build()),
+                ("overridden".to_string(), SchedulerJobConfig::builder().cron("0 0 3 * * *").build()),
+            ]))
+            .build(),
+    );
+    let job_counter = counter.clone();
+    scheduler.register_interval("disabled", Duration::from_millis(100), move || {
+        let counter = job_counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })?;
+    let job_counter = counter.clone();
+    scheduler.register_interval("overridden", Duration::from_millis(100), move || {
+        let counter = job_counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    })?;
+    scheduler.start()?;
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+    let status = scheduler.job("disabled").unwrap();
+    assert!(!status.enabled);
+    assert!(status.next_run.is_none());
+    let status = scheduler.job("overridden").unwrap();
+    assert!(status.enabled);
+    assert_eq!(status.schedule, "cron(0 0 3 * * *)");
+    assert!(status.next_run.is_some());
+    scheduler.stop().await;
+
+    // invalid overrides
+    let scheduler =
+        TardisScheduler::new(SchedulerConfig::builder().jobs(HashMap::from([("invalid".to_string(), SchedulerJobConfig::builder().cron("every day").build())])).build());
+    assert_eq!(
+        scheduler.register_interval("invalid", Duration::from_millis(100), || async { Ok(()) }).unwrap_err().code,
+        "406"
+    );
+    Ok(())
+}
+
+async fn test_distributed() -> TardisResult<()> {
+    let cache = Arc::new(TardisCacheClient::memory());
+    let config = SchedulerConfig::builder().lock_min_hold_ms(100).build();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let schedulers = [
+        TardisScheduler::new(config.clone()).with_cache(cache.clone()),
+        TardisScheduler::new(config).with_cache(cache.clone()),
+    ];
+    for scheduler in &schedulers {
+        let job_counter = counter.clone();
+        scheduler.register(
+            TardisJob::new("sync", TardisSchedule::interval(Duration::from_millis(300))?, move || {
+                let counter = job_counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            })
+            .distributed(true),
+        )?;
+        scheduler.start()?;
+    }
+    sleep(Duration::from_millis(1400)).await;
+    for scheduler in &schedulers {
+        scheduler.stop().await;
+    }
+    let (runs, skipped) = schedulers.iter().map(|scheduler| scheduler.job("sync").unwrap()).fold((0, 0), |(runs, skipped), status| {
+        assert!(status.distributed);
+        (runs + status.runs, skipped + status.skipped)
+    });
+    // each occurrence is run by one instance and skipped by the other
+    assert!(runs >= 3);
+    assert_eq!(runs as usize, counter.load(Ordering::SeqCst));
+    assert!(runs.abs_diff(skipped) <= 1);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{SearchEngineKind, SearchModuleConfig, SearchTlsConfig};
+use tardis::search::search_client::TardisSearchClient;
+use tardis::serde_json::json;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_auth() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let resp = MockResponse::ok().json(&json!({
+        "took": 1,
+        "timed_out": false,
+        "_shards": {"total": 1, "successful": 1, "failed": 0},
+        "hits": {"total": 0, "max_score": null, "hits": []}
+    }))?;
+    let url = server.url().replace("http://", "http://user:pass@");
+
+    // the basic auth takes precedence over the credentials in the url
+    let client = TardisSearchClient::init(&SearchModuleConfig::builder().url(url.parse()?).username("admin").password("secret").build())?;
+    server.expect(MockExpectation::new("POST", "/test_index/_search").header("Authorization", "Basic YWRtaW46c2VjcmV0").times(1).respond_with(resp.clone()));
+    // the total hits as a number
+    assert_eq!(client.raw_search("test_index", "{}", None, None, None).await?.hits.total.value, 0);
+    assert!(!client.server_url.as_str().contains("user:pass"));
+
+    // the api key takes precedence over the basic auth, the compatibility headers are sent to Elasticsearch
+    let client = TardisSearchClient::init(&SearchModuleConfig::builder().url(url.parse()?).username("admin").api_key("a2V5").compatible_with(7).build())?;
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_search")
+            .header("Authorization", "ApiKey a2V5")
+            .header("Accept", "application/vnd.elasticsearch+json; compatible-with=7")
+            .header("Content-Type", "application/vnd.elasticsearch+json; compatible-with=7")
+            .times(1)
+            .respond_with(resp.clone()),
+    );
+    client.raw_search("test_index", "{}", None, None, None).await?;
+
+    // OpenSearch ignores the compatibility headers
+    let client = TardisSearchClient::init(&SearchModuleConfig::builder().url(server.url().parse()?).engine(SearchEngineKind::OpenSearch).compatible_with(7).build())?;
+    server.expect(MockExpectation::new("POST", "/test_index/_search").header("Content-Type", "application/json").times(1).respond_with(resp));
+    client.raw_search("test_index", "{}", None, None, None).await?;
+    server.verify()?;
+    assert!(server.received_requests().last().unwrap().headers.get("accept").map(|accept| !accept.contains("compatible-with")).unwrap_or(true));
+
+    // the client certificate requires the key
+    assert!(TardisSearchClient::init(&SearchModuleConfig::builder().url(server.url().parse()?).tls(SearchTlsConfig::builder().client_cert("cert").build()).build()).is_err());
+    Ok(())
+}
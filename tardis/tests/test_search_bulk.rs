@@ -0,0 +1,62 @@
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::SearchModuleConfig;
+use tardis::search::search_client::TardisSearchClient;
+use tardis::serde_json::json;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_bulk() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let client = TardisSearchClient::init(&SearchModuleConfig::builder().url(server.url().parse()?).bulk_chunk_size(2).build())?;
+
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_bulk").body("{\"index\":{}}\n{\"name\":\"r1\"}\n{\"index\":{}}\n{\"name\":\"r2\"}\n").times(1).respond_with(
+            MockResponse::ok().json(&json!({
+                "took": 1,
+                "errors": true,
+                "items": [
+                    {"index": {"_id": "1", "status": 201, "result": "created"}},
+                    {"index": {"_id": "2", "status": 400, "error": {"type": "mapper_parsing_exception", "reason": "failed to parse"}}}
+                ]
+            }))?,
+        ),
+    );
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_bulk").body("{\"index\":{}}\n{\"name\":\"r3\"}\n").times(1).respond_with(MockResponse::status(429).body("too many requests")),
+    );
+    let resp = client.bulk_create("test_index", &[r#"{"name":"r1"}"#, "{\n\"name\": \"r2\"\n}", r#"{"name":"r3"}"#]).await?;
+    assert!(!resp.is_success());
+    assert_eq!(resp.ids, vec!["1"]);
+    assert_eq!(resp.failures.len(), 2);
+    assert_eq!((resp.failures[0].idx, resp.failures[0].id.as_deref(), resp.failures[0].status), (1, Some("2"), 400));
+    assert_eq!(resp.failures[0].reason, "failed to parse");
+    assert_eq!((resp.failures[1].idx, resp.failures[1].id.as_deref(), resp.failures[1].status), (2, None, 429));
+    assert!(resp.failures[1].reason.contains("too many requests"));
+    // invalid json is rejected before sending
+    assert!(client.bulk_create("test_index", &["{"]).await.is_err());
+
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_bulk")
+            .body("{\"update\":{\"_id\":\"1\"}}\n{\"doc\":{\"name\":\"r1-1\"}}\n")
+            .respond_with(MockResponse::ok().json(&json!({"took": 1, "errors": false, "items": [{"update": {"_id": "1", "status": 200, "result": "updated"}}]}))?),
+    );
+    let resp = client.bulk_update("test_index", &[("1", r#"{"name":"r1-1"}"#)]).await?;
+    assert!(resp.is_success());
+    assert_eq!(resp.ids, vec!["1"]);
+
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_bulk").body("{\"delete\":{\"_id\":\"1\"}}\n{\"delete\":{\"_id\":\"9\"}}\n").respond_with(MockResponse::ok().json(&json!({
+            "took": 1,
+            "errors": false,
+            "items": [
+                {"delete": {"_id": "1", "status": 200, "result": "deleted"}},
+                {"delete": {"_id": "9", "status": 404, "result": "not_found"}}
+            ]
+        }))?),
+    );
+    let resp = client.bulk_delete("test_index", &["1", "9"]).await?;
+    assert_eq!(resp.ids, vec!["1"]);
+    assert_eq!((resp.failures[0].idx, resp.failures[0].status, resp.failures[0].reason.as_str()), (1, 404, "not_found"));
+    server.verify()?;
+    Ok(())
+}
@@ -3,9 +3,24 @@ use std::env;
 
 use tardis::basic::result::TardisResult;
 use tardis::config::config_dto::{FrameworkConfig, SearchConfig, SearchModuleConfig, TardisConfig, WebClientConfig};
+use tardis::search::search_query::{SearchAggregation, SearchQuery, SearchQueryBuilder, SearchRange};
+use tardis::serde::Deserialize;
 use tardis::test::test_container::TardisTestContainer;
 use tardis::TardisFuns;
 
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct Record {
+    user: User,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "tardis::serde")]
+struct User {
+    id: i64,
+    name: String,
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_search_client() -> TardisResult<()> {
     env::set_var("RUST_LOG", "info,tardis=trace");
@@ -96,6 +111,73 @@ async fn test_search_client() -> TardisResult<()> {
             r#"{"user":{"id":4,"name":"Tom","open":false,"xxx":["acc01","acc02"]}}"#
         );
 
+        // query builder
+        let users = client
+            .search(
+                index_name,
+                &SearchQueryBuilder::new().must(SearchQuery::matches("user.name", "tom")).filter(SearchQuery::range("user.id", SearchRange::new().gte(4))),
+            )
+            .await?
+            .records::<Record>()?;
+        assert_eq!(users.len(), 1);
+        assert_eq!((users[0].user.id, users[0].user.name.as_str()), (4, "Tom"));
+        let resp = client.search(index_name, &SearchQueryBuilder::new().must_not(SearchQuery::exists("user.xxx"))).await?;
+        assert_eq!(resp.hits.total.value, 2);
+
+        let page = client.search_paged::<Record>(index_name, &SearchQueryBuilder::new(), 1, 2, &[("user.id", false)]).await?;
+        assert_eq!(page.total_size, 3);
+        assert_eq!(page.records.iter().map(|record| record.user.id).collect::<Vec<_>>(), vec![4, 3]);
+
+        let aggs = client
+            .search(
+                index_name,
+                &SearchQueryBuilder::new()
+                    .size(0)
+                    .aggregation(
+                        "names",
+                        SearchAggregation::terms("user.name.keyword", 10).sub_aggregation("ids", SearchAggregation::stats("user.id")),
+                    )
+                    .aggregation("users", SearchAggregation::cardinality("user.id")),
+            )
+            .await?
+            .aggs();
+        assert_eq!(aggs["users"].value, Some(3.0));
+        assert_eq!(aggs["names"].buckets.len(), 3);
+        let tom = aggs["names"].buckets.iter().find(|bucket| bucket.key == "Tom").unwrap();
+        assert_eq!(tom.doc_count, 1);
+        assert_eq!(tom.aggs["ids"].stats.as_ref().unwrap().max, Some(4.0));
+
+        // mappings and aliases
+        client.create_index_with_mapping("test_index_v1", Some(r#"{"properties":{"name":{"type":"keyword"}}}"#), None).await?;
+        client.put_mapping("test_index_v1", r#"{"properties":{"desc":{"type":"text"}}}"#).await?;
+        client.create_index_with_mapping("test_index_v2", None, Some(r#"{"number_of_shards":1}"#)).await?;
+        client.create_alias("test_index_v1", "test_alias").await?;
+        assert_eq!(client.get_alias_indices("test_alias").await?, vec!["test_index_v1"]);
+        client.switch_alias("test_alias", "test_index_v2").await?;
+        assert_eq!(client.get_alias_indices("test_alias").await?, vec!["test_index_v2"]);
+        client.delete_alias("test_index_v2", "test_alias").await?;
+        assert!(client.get_alias_indices("test_alias").await?.is_empty());
+        client
+            .put_index_template(
+                "log_template",
+                r#"{"index_patterns":["log-*"],"template":{"mappings":{"properties":{"msg":{"type":"text"}}}}}"#,
+            )
+            .await?;
+        assert!(client.check_index_template_exist("log_template").await?);
+        client.delete_index_template("log_template").await?;
+        assert!(!client.check_index_template_exist("log_template").await?);
+
+        // bulk
+        let resp = client.bulk_create("test_bulk_index", &[r#"{"user":{"id":5,"name":"Jerry"}}"#, r#"{"user":{"id":6,"name":"Spike"}}"#]).await?;
+        assert!(resp.is_success());
+        assert_eq!(resp.ids.len(), 2);
+        let resp = client.bulk_update("test_bulk_index", &[(resp.ids[0].as_str(), r#"{"user":{"name":"Jerry1"}}"#)]).await?;
+        assert!(resp.is_success());
+        assert_eq!(client.get_record("test_bulk_index", &resp.ids[0]).await?, r#"{"user":{"id":5,"name":"Jerry1"}}"#);
+        let resp = client.bulk_delete("test_bulk_index", &[resp.ids[0].as_str(), "not-exist"]).await?;
+        assert_eq!(resp.ids.len(), 1);
+        assert_eq!(resp.failures[0].status, 404);
+
         Ok(())
     })
     .await
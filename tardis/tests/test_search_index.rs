@@ -0,0 +1,53 @@
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::SearchModuleConfig;
+use tardis::search::search_client::TardisSearchClient;
+use tardis::serde_json::json;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_index() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let client = TardisSearchClient::init(&SearchModuleConfig::builder().url(server.url().parse()?).build())?;
+
+    server.expect(
+        MockExpectation::new("PUT", "/test_index_v1")
+            .body_json(&json!({"mappings": {"properties": {"name": {"type": "keyword"}}}, "settings": {"number_of_shards": 1}}))?
+            .respond_with(MockResponse::ok().json(&json!({"acknowledged": true}))?),
+    );
+    client.create_index_with_mapping("test_index_v1", Some(r#"{"properties":{"name":{"type":"keyword"}}}"#), Some(r#"{"number_of_shards":1}"#)).await?;
+    assert!(client.create_index_with_mapping("test_index_v1", Some("{"), None).await.is_err());
+
+    server.expect(MockExpectation::new("PUT", "/test_index_v1/_mapping").body_contains("desc").respond_with(MockResponse::ok()));
+    client.put_mapping("test_index_v1", r#"{"properties":{"desc":{"type":"text"}}}"#).await?;
+
+    // switch the alias from v1 to v2
+    server.expect(MockExpectation::new("GET", "/_alias/test_index").times(1).respond_with(MockResponse::ok().json(&json!({"test_index_v1": {"aliases": {"test_index": {}}}}))?));
+    server.expect(
+        MockExpectation::new("POST", "/_aliases")
+            .body_json(&json!({"actions": [
+                {"remove": {"index": "test_index_v1", "alias": "test_index"}},
+                {"add": {"index": "test_index_v2", "alias": "test_index"}}
+            ]}))?
+            .times(1)
+            .respond_with(MockResponse::ok()),
+    );
+    client.switch_alias("test_index", "test_index_v2").await?;
+    // the alias doesn't exist yet
+    server.expect(MockExpectation::new("GET", "/_alias/test_index").times(1).respond_with(MockResponse::status(404)));
+    server.expect(
+        MockExpectation::new("POST", "/_aliases")
+            .body_json(&json!({"actions": [{"add": {"index": "test_index_v2", "alias": "test_index"}}]}))?
+            .times(1)
+            .respond_with(MockResponse::ok()),
+    );
+    client.switch_alias("test_index", "test_index_v2").await?;
+
+    server.expect(MockExpectation::new("PUT", "/_index_template/log_template").body_contains("log-*").respond_with(MockResponse::ok()));
+    server.expect(MockExpectation::new("HEAD", "/_index_template/log_template").respond_with(MockResponse::ok()));
+    server.expect(MockExpectation::new("DELETE", "/_index_template/log_template").respond_with(MockResponse::status(404)));
+    client.put_index_template("log_template", r#"{"index_patterns":["log-*"]}"#).await?;
+    assert!(client.check_index_template_exist("log_template").await?);
+    assert_eq!(client.delete_index_template("log_template").await.unwrap_err().code, "404");
+    server.verify()?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::SearchModuleConfig;
+use tardis::futures::{pin_mut, StreamExt};
+use tardis::search::search_client::TardisSearchClient;
+use tardis::search::search_query::{SearchQuery, SearchQueryBuilder};
+use tardis::serde::Deserialize;
+use tardis::serde_json::{json, Value};
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(crate = "tardis::serde")]
+struct User {
+    id: u64,
+}
+
+fn search_resp(total: u64, ids: &[u64], scroll_id: Option<&str>) -> Value {
+    json!({
+        "took": 1,
+        "timed_out": false,
+        "_shards": {"total": 1, "successful": 1, "failed": 0},
+        "_scroll_id": scroll_id,
+        "hits": {
+            "total": {"value": total, "relation": "eq"},
+            "max_score": null,
+            "hits": ids.iter().map(|id| json!({"_index": "test_index", "_id": id.to_string(), "_score": null, "_source": {"id": id}, "sort": [id]})).collect::<Vec<_>>()
+        }
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_paging() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let client = TardisSearchClient::init(&SearchModuleConfig::builder().url(server.url().parse()?).build())?;
+    let query = SearchQueryBuilder::new().filter(SearchQuery::term("open", true));
+
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_search")
+            .body_json(&json!({"query": {"bool": {"filter": [{"term": {"open": true}}]}}, "sort": [{"id": {"order": "desc"}}], "from": 10, "size": 5, "track_total_hits": true}))?
+            .times(1)
+            .respond_with(MockResponse::ok().json(&search_resp(12, &[2, 1], None))?),
+    );
+    let page = client.search_paged::<User>("test_index", &query, 3, 5, &[("id", false)]).await?;
+    assert_eq!((page.page_number, page.page_size, page.total_size), (3, 5, 12));
+    assert_eq!(page.records, vec![User { id: 2 }, User { id: 1 }]);
+    server.verify()?;
+
+    // search_after continues after the sort values of the last hit
+    server.reset();
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_search")
+            .body_json(&json!({"query": {"match_all": {}}, "sort": [{"id": {"order": "asc"}}], "size": 2}))?
+            .times(1)
+            .respond_with(MockResponse::ok().json(&search_resp(3, &[1, 2], None))?),
+    );
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_search")
+            .body_json(&json!({"query": {"match_all": {}}, "sort": [{"id": {"order": "asc"}}], "size": 2, "search_after": [2]}))?
+            .times(1)
+            .respond_with(MockResponse::ok().json(&search_resp(3, &[3], None))?),
+    );
+    let users = client.search_after::<User>("test_index", &SearchQueryBuilder::new().sort("id", true), 2);
+    pin_mut!(users);
+    let mut ids = Vec::new();
+    while let Some(user) = users.next().await {
+        ids.push(user?.id);
+    }
+    assert_eq!(ids, vec![1, 2, 3]);
+    server.verify()?;
+
+    // scroll until no hits
+    server.reset();
+    server.expect(
+        MockExpectation::new("POST", "/test_index/_search")
+            .body_json(&json!({"query": {"match_all": {}}, "size": 2}))?
+            .times(1)
+            .respond_with(MockResponse::ok().json(&search_resp(3, &[1, 2], Some("s1")))?),
+    );
+    server.expect(
+        MockExpectation::new("POST", "/_search/scroll").body_json(&json!({"scroll": "1m", "scroll_id": "s1"}))?.times(1).respond_with(MockResponse::ok().json(&search_resp(
+            3,
+            &[3],
+            Some("s2"),
+        ))?),
+    );
+    server.expect(
+        MockExpectation::new("POST", "/_search/scroll").body_json(&json!({"scroll": "1m", "scroll_id": "s2"}))?.times(1).respond_with(MockResponse::ok().json(&search_resp(
+            3,
+            &[],
+            Some("s2"),
+        ))?),
+    );
+    let users = client.scroll::<User>("test_index", &SearchQueryBuilder::new(), 2, "1m");
+    pin_mut!(users);
+    let mut ids = Vec::new();
+    while let Some(user) = users.next().await {
+        ids.push(user?.id);
+    }
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert!(server.received_requests()[0].query.as_deref().unwrap_or_default().contains("scroll=1m"));
+    server.verify()?;
+    Ok(())
+}
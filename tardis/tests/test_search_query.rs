@@ -0,0 +1,112 @@
+use tardis::search::search_client::TardisRawSearchResp;
+use tardis::search::search_query::{SearchAggregation, SearchQuery, SearchQueryBuilder, SearchRange};
+use tardis::serde_json::{self, json};
+
+#[test]
+fn test_search_query() {
+    assert_eq!(SearchQueryBuilder::new().build(), json!({"query": {"match_all": {}}}));
+
+    let query = SearchQueryBuilder::new()
+        .must(SearchQuery::matches("user.name", "李\"四"))
+        .filter(SearchQuery::range("user.age", SearchRange::new().gte(18).lt(60)))
+        .filter(SearchQuery::terms("user.role", ["admin", "user"]))
+        .must_not(SearchQuery::term("user.open", false))
+        .should(SearchQuery::exists("user.email"))
+        .should(SearchQuery::nested("orders", SearchQuery::term("orders.status", "paid")))
+        .minimum_should_match(1)
+        .sort("user.id", false)
+        .size(10)
+        .from(20);
+    assert_eq!(
+        query.build(),
+        json!({
+            "query": {"bool": {
+                "must": [{"match": {"user.name": "李\"四"}}],
+                "should": [{"exists": {"field": "user.email"}}, {"nested": {"path": "orders", "query": {"term": {"orders.status": "paid"}}}}],
+                "filter": [{"range": {"user.age": {"gte": 18, "lt": 60}}}, {"terms": {"user.role": ["admin", "user"]}}],
+                "must_not": [{"term": {"user.open": false}}],
+                "minimum_should_match": 1
+            }},
+            "sort": [{"user.id": {"order": "desc"}}],
+            "size": 10,
+            "from": 20
+        })
+    );
+
+    let query = SearchQueryBuilder::new().sort("user.id", true).track_total_hits(true).search_after(vec![json!(10)]);
+    assert_eq!(
+        query.build(),
+        json!({"query": {"match_all": {}}, "sort": [{"user.id": {"order": "asc"}}], "track_total_hits": true, "search_after": [10]})
+    );
+
+    // nested bool queries ignore the paging
+    let query = SearchQueryBuilder::new()
+        .should(SearchQuery::bool(SearchQueryBuilder::new().must(SearchQuery::term("a", 1)).size(5)))
+        .should(SearchQuery::raw(json!({"ids": {"values": ["1"]}})));
+    assert_eq!(
+        query.build_query(),
+        json!({"bool": {"should": [{"bool": {"must": [{"term": {"a": 1}}]}}, {"ids": {"values": ["1"]}}]}})
+    );
+}
+
+#[test]
+fn test_search_aggregation() {
+    let query = SearchQueryBuilder::new()
+        .size(0)
+        .aggregation(
+            "by_status",
+            SearchAggregation::terms("status", 5).sub_aggregation(
+                "per_month",
+                SearchAggregation::date_histogram("created_at", "month").sub_aggregation("amount", SearchAggregation::stats("amount")),
+            ),
+        )
+        .aggregation("users", SearchAggregation::cardinality("user_id"));
+    assert_eq!(
+        query.build(),
+        json!({
+            "query": {"match_all": {}},
+            "size": 0,
+            "aggs": {
+                "by_status": {
+                    "terms": {"field": "status", "size": 5},
+                    "aggs": {"per_month": {"date_histogram": {"field": "created_at", "calendar_interval": "month"}, "aggs": {"amount": {"stats": {"field": "amount"}}}}}
+                },
+                "users": {"cardinality": {"field": "user_id"}}
+            }
+        })
+    );
+
+    let resp: TardisRawSearchResp = serde_json::from_value(json!({
+        "took": 1,
+        "timed_out": false,
+        "_shards": {"total": 1, "successful": 1, "failed": 0},
+        "hits": {"total": {"value": 3, "relation": "eq"}, "max_score": null, "hits": []},
+        "aggregations": {
+            "by_status": {
+                "doc_count_error_upper_bound": 0,
+                "sum_other_doc_count": 0,
+                "buckets": [{
+                    "key": "paid",
+                    "doc_count": 3,
+                    "per_month": {"buckets": [{
+                        "key_as_string": "2023-01-01T00:00:00.000Z",
+                        "key": 1672531200000u64,
+                        "doc_count": 3,
+                        "amount": {"count": 3, "min": 1.0, "max": 5.0, "avg": 3.0, "sum": 9.0}
+                    }]}
+                }]
+            },
+            "users": {"value": 2}
+        }
+    }))
+    .unwrap();
+    let aggs = resp.aggs();
+    assert_eq!(aggs["users"].value, Some(2.0));
+    let status = &aggs["by_status"].buckets[0];
+    assert_eq!((status.key.as_str(), status.doc_count), (Some("paid"), 3));
+    let month = &status.aggs["per_month"].buckets[0];
+    assert_eq!(month.key_as_string.as_deref(), Some("2023-01-01T00:00:00.000Z"));
+    let stats = month.aggs["amount"].stats.as_ref().unwrap();
+    assert_eq!((stats.count, stats.min, stats.max, stats.avg, stats.sum), (3, Some(1.0), Some(5.0), Some(3.0), 9.0));
+    assert!(aggs["by_status"].stats.is_none());
+}
@@ -0,0 +1,76 @@
+use serde_json::json;
+
+use tardis::basic::result::TardisResult;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::test::snapshot::{json_diff, TardisSnapshot, REDACTED};
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::Route;
+use tardis::web::poem_openapi::{param::Query, Object, OpenApi, OpenApiService};
+use tardis::web::web_resp::{TardisApiResult, TardisPage, TardisResp};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot() -> TardisResult<()> {
+    // diff
+    assert_eq!(
+        json_diff(&json!({"a": 1, "b": [1], "c": {"d": "x"}}), &json!({"a": 2, "b": [1, 2], "c": {}})),
+        vec!["$.a: expected 1, actual 2", "$.c.d: missing, expected \"x\"", "$.b[1]: unexpected 2"]
+    );
+    assert!(json_diff(&json!({"a": [1, {"b": null}]}), &json!({"a": [1, {"b": null}]})).is_empty());
+
+    // redaction
+    let snapshot = TardisSnapshot::new("redaction").redact("$.data.records[*].id").redact("$.list[0]").redact_field("create_time");
+    assert_eq!(
+        snapshot.redacted(json!({"data": {"records": [{"id": 1, "name": "n1"}, {"id": 2, "create_time": "2023-08-01"}]}, "list": [1, 2], "create_time": 0})),
+        json!({"data": {"records": [{"id": REDACTED, "name": "n1"}, {"id": REDACTED, "create_time": REDACTED}]}, "list": [REDACTED, 2], "create_time": REDACTED})
+    );
+
+    // write the missing snapshot, then compare with it
+    let dir = std::env::temp_dir().join(format!("tardis-snapshot-{}", TardisFuns::field.nanoid()));
+    let snapshot = TardisSnapshot::new("todo").dir(&dir).redact_field("id");
+    snapshot.assert_json(&json!({"id": 1, "name": "n1"}))?;
+    assert!(snapshot.path().exists());
+    snapshot.assert_json(&json!({"id": 2, "name": "n1"}))?;
+    let mismatched = std::panic::catch_unwind(|| snapshot.assert_json(&json!({"id": 3, "name": "n2"})));
+    let message = mismatched.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains(r#"$.name: expected "n1", actual "n2""#));
+    std::fs::remove_dir_all(&dir)?;
+
+    // api responses
+    let client = TardisWebTestClient::from_route(Route::new().nest("/todo", OpenApiService::new(TodoApi, "todo", "1.0")));
+    let resp = client.get("/todo/todos?page_number=1&page_size=2").await?;
+    let todos: Vec<TodoResp> = resp.assert_page(1, 2, 3)?;
+    assert_eq!(todos.len(), 2);
+    resp.assert_snapshot(&TardisSnapshot::new("test_snapshot_todo_page").redact("$.data.records[*].id"))?;
+    Ok(())
+}
+
+#[derive(Object, Serialize, Deserialize, Debug)]
+struct TodoResp {
+    id: String,
+    name: String,
+}
+
+#[derive(Clone)]
+struct TodoApi;
+
+#[OpenApi]
+impl TodoApi {
+    #[oai(path = "/todos", method = "get")]
+    async fn paginate(&self, page_number: Query<u64>, page_size: Query<u64>) -> TardisApiResult<TardisPage<TodoResp>> {
+        let records = (1..=3)
+            .map(|idx| TodoResp {
+                id: TardisFuns::field.nanoid(),
+                name: format!("todo{idx}"),
+            })
+            .skip(((page_number.0 - 1) * page_size.0) as usize)
+            .take(page_size.0 as usize)
+            .collect();
+        TardisResp::ok(TardisPage {
+            page_size: page_size.0,
+            page_number: page_number.0,
+            total_size: 3,
+            records,
+        })
+    }
+}
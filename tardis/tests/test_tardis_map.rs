@@ -0,0 +1,89 @@
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::TardisMap;
+
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    pub id: i32,
+    pub code: String,
+    pub r#type: String,
+    pub done: bool,
+    pub priority: String,
+}
+
+#[derive(Debug, TardisMap)]
+#[tardis_map(from = "Model", into = "Model", into_default)]
+pub struct TodoResp {
+    pub id: i32,
+    #[tardis_map(rename = "code")]
+    pub todo_code: String,
+    // the keywords are mapped to the raw identifiers
+    #[tardis_map(rename = "type")]
+    pub kind: String,
+    #[tardis_map(with = "done_to_status", into_with = "status_to_done")]
+    pub status: String,
+    #[tardis_map(skip)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, TardisMap)]
+#[tardis_map(from = "Model")]
+pub struct TodoPriorityResp {
+    pub id: i64,
+    #[tardis_map(try_with = "parse_priority")]
+    pub priority: u8,
+}
+
+fn done_to_status(done: bool) -> String {
+    if done {
+        "done".to_string()
+    } else {
+        "todo".to_string()
+    }
+}
+
+fn status_to_done(status: String) -> bool {
+    status == "done"
+}
+
+fn parse_priority(priority: String) -> TardisResult<u8> {
+    priority.parse::<u8>().map_err(|_| TardisError::bad_request(&format!("invalid priority: {priority}"), ""))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tardis_map() -> TardisResult<()> {
+    let model = Model {
+        id: 1,
+        code: "t001".to_string(),
+        r#type: "task".to_string(),
+        done: true,
+        priority: "3".to_string(),
+    };
+    let resp = TodoResp::from(model.clone());
+    assert_eq!(resp.id, 1);
+    assert_eq!(resp.todo_code, "t001");
+    assert_eq!(resp.kind, "task");
+    assert_eq!(resp.status, "done");
+    assert!(resp.tags.is_empty());
+
+    let model_from_resp = Model::from(TodoResp {
+        status: "todo".to_string(),
+        tags: vec!["ignored".to_string()],
+        ..resp
+    });
+    assert_eq!(model_from_resp.id, 1);
+    assert_eq!(model_from_resp.code, "t001");
+    assert_eq!(model_from_resp.r#type, "task");
+    assert!(!model_from_resp.done);
+    // not in the resp, filled by the default
+    assert_eq!(model_from_resp.priority, "");
+
+    assert_eq!(TodoPriorityResp::try_from(model.clone())?.priority, 3);
+    let error = TodoPriorityResp::try_from(Model {
+        priority: "high".to_string(),
+        ..model
+    })
+    .unwrap_err();
+    assert_eq!(error.code, "400");
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use tardis::basic::result::TardisResult;
+use tardis::test::test_env::{TardisTestEnv, TardisTestService};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_test_env() -> TardisResult<()> {
+    let env = TardisTestEnv::up(&[TardisTestService::Redis, TardisTestService::RabbitMQ, TardisTestService::Redis]).await?;
+    assert!(env.url(TardisTestService::Redis).unwrap().starts_with("redis://"));
+    assert!(env.url(TardisTestService::RabbitMQ).unwrap().starts_with("amqp://"));
+    assert!(env.url(TardisTestService::Mysql).is_none());
+    assert_eq!(TardisFuns::fw_config().cache().default.url.as_str(), env.url(TardisTestService::Redis).unwrap());
+
+    TardisFuns::cache().set("k", "v").await?;
+    assert_eq!(TardisFuns::cache().get("k").await?.unwrap(), "v");
+    TardisFuns::mq().publish("test-env", "msg".to_string(), &Default::default()).await?;
+
+    env.down().await?;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use tardis::basic::result::TardisResult;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::test::test_harness::TardisTestHarness;
+use tardis::TardisFuns;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CustomConfig {
+    project_name: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_test_harness() -> TardisResult<()> {
+    TardisTestHarness::builder()
+        .with_cache("mem://")
+        .with_mq("mem://")
+        .with_log_level("info,tardis=trace")
+        .with_custom_config(
+            "",
+            &CustomConfig {
+                project_name: "harness".to_string(),
+            },
+        )?
+        .configure(|fw| fw.app.version = "1.0.0".to_string())
+        .init()
+        .await?;
+
+    assert_eq!(TardisFuns::fw_config().app.id, "tardis-test");
+    assert_eq!(TardisFuns::fw_config().app.version, "1.0.0");
+    assert_eq!(TardisFuns::fw_config().log.as_ref().unwrap().directives.len(), 1);
+    assert_eq!(TardisFuns::cs_config::<CustomConfig>("").project_name, "harness");
+    TardisFuns::cache().set("k", "v").await?;
+    assert_eq!(TardisFuns::cache().get("k").await?.unwrap(), "v");
+    TardisFuns::mq().publish("topic", "msg".to_string(), &Default::default()).await?;
+    assert_eq!(TardisFuns::mq().as_memory().unwrap().sent_messages()?.len(), 1);
+
+    // init again with other components
+    TardisTestHarness::builder().with_cache("mem://").init().await?;
+    assert_eq!(TardisFuns::cache().get("k").await?, None);
+
+    assert!(TardisTestHarness::builder().with_cache("invalid url").init().await.is_err());
+    Ok(())
+}
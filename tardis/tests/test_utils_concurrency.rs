@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::utils::debounce::Debouncer;
+use tardis::utils::single_flight::SingleFlight;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_single_flight() -> TardisResult<()> {
+    let flight = Arc::new(SingleFlight::<u32>::new());
+    let counter = Arc::new(AtomicU32::new(0));
+    let tasks = (0..10)
+        .map(|_| {
+            let flight = flight.clone();
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                flight
+                    .work("refresh", || async {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        counter.fetch_add(1, Ordering::SeqCst) + 1
+                    })
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        assert_eq!(task.await.unwrap(), 1);
+    }
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    assert!(!flight.is_in_flight("refresh"));
+
+    // executed again after the previous one completed
+    assert_eq!(flight.work("refresh", || async { counter.fetch_add(1, Ordering::SeqCst) + 1 }).await, 2);
+    // different keys are executed separately
+    assert_eq!(flight.work("other", || async { 100 }).await, 100);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debounce() -> TardisResult<()> {
+    let debouncer = Debouncer::new(Duration::from_millis(100));
+    let counter = Arc::new(AtomicU32::new(0));
+    for i in 1..=5 {
+        let counter = counter.clone();
+        debouncer.trigger(async move {
+            counter.fetch_add(i, Ordering::SeqCst);
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    // only the last trigger is executed
+    assert_eq!(counter.load(Ordering::SeqCst), 5);
+
+    let counter_clone = counter.clone();
+    debouncer.trigger(async move {
+        counter_clone.fetch_add(1, Ordering::SeqCst);
+    });
+    debouncer.cancel();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(counter.load(Ordering::SeqCst), 5);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use tardis::basic::result::TardisResult;
+use tardis::utils::consistent_hash::ConsistentHash;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_utils_consistent_hash() -> TardisResult<()> {
+    let mut ring = ConsistentHash::new(160);
+    assert!(ring.is_empty());
+    assert!(ring.get("key").is_none());
+
+    ring.add("node1".to_string());
+    ring.add("node2".to_string());
+    ring.add("node3".to_string());
+    ring.add("node3".to_string());
+    assert_eq!(ring.len(), 3);
+
+    let keys = (0..3000).map(|i| format!("key{i}")).collect::<Vec<_>>();
+    let mut distribution: HashMap<String, usize> = HashMap::new();
+    let located = keys
+        .iter()
+        .map(|key| {
+            let node = ring.get(key).unwrap().clone();
+            *distribution.entry(node.clone()).or_default() += 1;
+            node
+        })
+        .collect::<Vec<_>>();
+    // roughly balanced
+    assert!(distribution.values().all(|count| *count > 600 && *count < 1400));
+    // stable
+    assert_eq!(ring.get("key1"), ring.clone().get("key1"));
+
+    // only the keys of the removed node are moved
+    ring.remove(&"node2".to_string());
+    assert_eq!(ring.len(), 2);
+    for (key, node) in keys.iter().zip(located.iter()) {
+        let new_node = ring.get(key).unwrap();
+        if node != "node2" {
+            assert_eq!(new_node, node);
+        } else {
+            assert_ne!(new_node, "node2");
+        }
+    }
+
+    let replicas = ring.get_n("key1", 3);
+    assert_eq!(replicas.len(), 2);
+    assert_ne!(replicas[0], replicas[1]);
+    assert_eq!(replicas[0], ring.get("key1").unwrap());
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::utils::retry::{Backoff, RetryPolicy};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_utils_retry() -> TardisResult<()> {
+    assert_eq!(Backoff::Fixed(Duration::from_millis(10)).delay(5), Duration::from_millis(10));
+    let exponential = Backoff::Exponential {
+        initial: Duration::from_millis(10),
+        multiplier: 2.0,
+        max: Duration::from_millis(50),
+    };
+    assert_eq!(exponential.delay(0), Duration::from_millis(10));
+    assert_eq!(exponential.delay(2), Duration::from_millis(40));
+    assert_eq!(exponential.delay(3), Duration::from_millis(50));
+    assert_eq!(exponential.delay(u32::MAX), Duration::from_millis(50));
+
+    // succeed after retries
+    let counter = Arc::new(AtomicU32::new(0));
+    let policy = RetryPolicy::fixed(Duration::from_millis(10)).with_max_retries(5);
+    let result = tardis::utils::retry(&policy, || {
+        let counter = counter.clone();
+        async move {
+            if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(TardisError::internal_error("failed", "500-test"))
+            } else {
+                Ok("ok")
+            }
+        }
+    })
+    .await?;
+    assert_eq!(result, "ok");
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+    // exceed max retries
+    let counter = Arc::new(AtomicU32::new(0));
+    let policy = RetryPolicy::exponential(Duration::from_millis(1), Duration::from_millis(5)).with_jitter().with_max_retries(2);
+    let result: Result<(), TardisError> = tardis::utils::retry(&policy, || {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Err(TardisError::internal_error("failed", "500-test"))
+        }
+    })
+    .await;
+    assert_eq!(result.unwrap_err().code, "500");
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+    // retry on predicate
+    let counter = Arc::new(AtomicU32::new(0));
+    let policy = RetryPolicy::fixed(Duration::from_millis(1)).retry_on(|e: &TardisError| e.code.starts_with("500"));
+    let result: Result<(), TardisError> = tardis::utils::retry(&policy, || {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Err(TardisError::bad_request("bad request", "400-test"))
+        }
+    })
+    .await;
+    assert_eq!(result.unwrap_err().code, "400");
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    // max elapsed
+    let start = Instant::now();
+    let policy = RetryPolicy::<TardisError>::fixed(Duration::from_millis(50)).with_unlimited_retries().with_max_elapsed(Duration::from_millis(120));
+    let result: Result<(), TardisError> = tardis::utils::retry(&policy, || async { Err(TardisError::internal_error("failed", "500-test")) }).await;
+    assert!(result.is_err());
+    assert!(start.elapsed() < Duration::from_millis(500));
+
+    Ok(())
+}
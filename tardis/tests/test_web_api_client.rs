@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::WebClientModuleConfig;
+use tardis::tardis_client;
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::web::web_api_client::{encode_path_param, TardisApiClient};
+use tardis::web::web_client::TardisWebClient;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Todo {
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TodoQuery {
+    name: Option<String>,
+    tags: Vec<String>,
+}
+
+#[tardis_client]
+pub trait TodoApi {
+    #[get("/todos/{id}")]
+    async fn get_todo(&self, id: &str) -> TardisResult<Todo>;
+    #[get("/todos")]
+    async fn find_todos(&self, page: u32, #[query] query: &TodoQuery, #[header("X-Tenant")] tenant: &str) -> TardisResult<Vec<Todo>>;
+    #[post("/todos")]
+    async fn add_todo(&self, #[body] todo: &Todo) -> TardisResult<i64>;
+    #[delete("/todos/{id}")]
+    async fn delete_todo(&self, id: i64) -> TardisResult<()>;
+}
+
+#[tardis_client(tardis_resp, name = "WrappedTodoClient")]
+pub trait WrappedTodoApi {
+    #[get("/todos/{id}")]
+    async fn get_todo(&self, id: i64) -> TardisResult<Todo>;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_api_client() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let web_client = Arc::new(TardisWebClient::init(&WebClientModuleConfig::default())?);
+    let client = TodoApiClient::new(TardisApiClient::new(web_client.clone(), format!("{}/api/", server.url()))).with_bearer_token("t0ken");
+    let todo = Todo { id: 1, name: "test".to_string() };
+
+    server.expect(MockExpectation::new("GET", "/api/todos/a%2Fb").header("authorization", "Bearer t0ken").respond_with(MockResponse::ok().json(&todo)?));
+    server.expect(MockExpectation::new("GET", "/api/todos").header("x-tenant", "t1").respond_with(MockResponse::ok().json(&vec![todo.clone()])?));
+    server.expect(MockExpectation::new("POST", "/api/todos").body_json(&todo)?.respond_with(MockResponse::ok().body("1")));
+    server.expect(MockExpectation::new("DELETE", "/api/todos/1").respond_with(MockResponse::status(204)));
+    server.expect(MockExpectation::new("DELETE", "/api/todos/2").respond_with(MockResponse::status(409).body("conflict")));
+
+    assert_eq!(client.get_todo("a/b").await?, todo);
+    let query = TodoQuery {
+        name: None,
+        tags: vec!["a".to_string(), "b c".to_string()],
+    };
+    assert_eq!(client.find_todos(2, &query, "t1").await?, vec![todo.clone()]);
+    assert_eq!(server.received_requests()[1].query.as_deref(), Some("page=2&tags=a&tags=b+c"));
+    assert_eq!(client.add_todo(&todo).await?, 1);
+    client.delete_todo(1).await?;
+    let error = client.delete_todo(2).await.unwrap_err();
+    assert_eq!(error.code, "409");
+    assert!(error.message.contains("conflict"));
+
+    // unwrap the TardisResp
+    let client = WrappedTodoClient::new(TardisApiClient::new(web_client, format!("{}/api", server.url())));
+    server.reset();
+    server.expect(MockExpectation::new("GET", "/api/todos/1").respond_with(MockResponse::ok().body(r#"{"code":"200","msg":"","data":{"id":1,"name":"test"}}"#)));
+    server.expect(MockExpectation::new("GET", "/api/todos/2").respond_with(MockResponse::ok().body(r#"{"code":"404-todo-not-found","msg":"todo not found"}"#)));
+    assert_eq!(client.get_todo(1).await?, todo);
+    let error = client.get_todo(2).await.unwrap_err();
+    assert_eq!(error.code, "404-todo-not-found");
+    assert_eq!(error.message, "todo not found");
+
+    assert_eq!(encode_path_param("a b/中"), "a%20b%2F%E4%B8%AD");
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde_json::{json, Value};
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::WebServerAuthConfig;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use tardis::web::poem::http::Method;
+use tardis::web::poem::listener::TcpListener;
+use tardis::web::poem::web::Json;
+use tardis::web::poem::{handler, EndpointExt, Route, Server};
+use tardis::web::web_auth::{AuthContext, AuthGuard, TardisAuth, TardisAuthenticator};
+
+#[handler]
+async fn whoami(auth: AuthContext) -> String {
+    auth.subject
+}
+
+#[handler]
+async fn delete(auth: AuthContext) -> tardis::web::poem::Result<String> {
+    auth.require_permission("todo:delete")?;
+    Ok("deleted".to_string())
+}
+
+fn token(kid: Option<&str>, secret: &str, claims: Value) -> String {
+    let mut header = Header::new(Algorithm::HS256);
+    header.kid = kid.map(str::to_string);
+    let exp = tardis::chrono::Utc::now().timestamp() + 60;
+    let mut claims = claims;
+    claims["exp"] = json!(exp);
+    encode(&header, &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+}
+
+fn bearer(token: &str) -> Vec<(String, String)> {
+    vec![("Authorization".to_string(), format!("Bearer {token}"))]
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_auth_secret() -> TardisResult<()> {
+    let authenticator = Arc::new(TardisAuthenticator::new(
+        WebServerAuthConfig::builder().secret("secret-1").issuer("https://idp.example.com").audience(vec!["todo".to_string()]).build(),
+    ));
+    let route = Route::new()
+        .at("/whoami", whoami)
+        .at("/admin", whoami.with(AuthGuard::any_role(["admin", "ops"])))
+        .at("/delete", delete)
+        .with(TardisAuth::with_authenticator(authenticator.clone()));
+    let client = TardisWebTestClient::from_route(route);
+
+    let claims = json!({"sub": "u1", "iss": "https://idp.example.com", "aud": "todo", "roles": ["ops"], "scope": "todo:read todo:delete"});
+    let resp = client.request(Method::GET, "/whoami", bearer(&token(None, "secret-1", claims.clone())), None).await?;
+    resp.assert_status(200);
+    assert_eq!(resp.body, "u1");
+    client.request(Method::GET, "/admin", bearer(&token(None, "secret-1", claims.clone())), None).await?.assert_status(200);
+    client.request(Method::GET, "/delete", bearer(&token(None, "secret-1", claims)), None).await?.assert_status(200);
+
+    // missing and invalid tokens
+    let resp = client.get("/whoami").await?;
+    resp.assert_status(401);
+    assert!(resp.body.contains("401-tardis-webserver-auth-required"));
+    let resp = client
+        .request(
+            Method::GET,
+            "/whoami",
+            bearer(&token(None, "secret-2", json!({"sub": "u1", "iss": "https://idp.example.com", "aud": "todo"}))),
+            None,
+        )
+        .await?;
+    resp.assert_status(401);
+    assert!(resp.body.contains("401-tardis-webserver-auth-token-invalid"));
+    let resp = client
+        .request(
+            Method::GET,
+            "/whoami",
+            bearer(&token(None, "secret-1", json!({"sub": "u1", "iss": "https://other.example.com", "aud": "todo"}))),
+            None,
+        )
+        .await?;
+    resp.assert_status(401);
+
+    // lacking roles and permissions
+    let claims = json!({"sub": "u2", "iss": "https://idp.example.com", "aud": "todo", "roles": ["user"], "scope": "todo:read"});
+    let resp = client.request(Method::GET, "/admin", bearer(&token(None, "secret-1", claims.clone())), None).await?;
+    resp.assert_status(403);
+    assert!(resp.body.contains("403-tardis-webserver-auth-role-required"));
+    let resp = client.request(Method::GET, "/delete", bearer(&token(None, "secret-1", claims)), None).await?;
+    resp.assert_status(403);
+    assert!(resp.body.contains("403-tardis-webserver-auth-permission-required"));
+
+    // optional authentication
+    let route = Route::new()
+        .at("/whoami", whoami)
+        .at("/anonymous", tardis::web::poem::endpoint::make_sync(|_| "anonymous"))
+        .with(TardisAuth::with_authenticator(authenticator).optional());
+    let client = TardisWebTestClient::from_route(route);
+    client.get("/anonymous").await?.assert_status(200);
+    client.get("/whoami").await?.assert_status(401);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_auth_jwks_rotation() -> TardisResult<()> {
+    let oct = |kid: &str, secret: &str| json!({"kty": "oct", "kid": kid, "alg": "HS256", "k": general_purpose::URL_SAFE_NO_PAD.encode(secret)});
+    let jwks = Arc::new(RwLock::new(json!({ "keys": [oct("k1", "secret-1")] })));
+    let jwks_server = jwks.clone();
+    tokio::spawn(async move {
+        let route = Route::new().at("/jwks", tardis::web::poem::endpoint::make_sync(move |_| Json(jwks_server.read().unwrap().clone())));
+        Server::new(TcpListener::bind("127.0.0.1:8100")).run(route).await
+    });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let authenticator = Arc::new(TardisAuthenticator::new(
+        WebServerAuthConfig::builder().jwks_url("http://127.0.0.1:8100/jwks").jwks_min_refresh_sec(1).roles_claim("realm_access.roles").build(),
+    ));
+    let context = authenticator.authenticate(&token(Some("k1"), "secret-1", json!({"sub": "u1", "realm_access": {"roles": ["admin"]}}))).await?;
+    assert_eq!(context.subject, "u1");
+    assert!(context.has_role("admin"));
+    assert!(authenticator.authenticate(&token(Some("k1"), "secret-2", json!({"sub": "u1"}))).await.is_err());
+
+    // the provider rotates the key, the unknown kid is refetched at most once per `jwks_min_refresh_sec`
+    *jwks.write().unwrap() = json!({ "keys": [oct("k2", "secret-2")] });
+    let rotated = token(Some("k2"), "secret-2", json!({"sub": "u2"}));
+    let error = authenticator.authenticate(&rotated).await.unwrap_err();
+    assert_eq!(error.code, "401-tardis-webserver-auth-token-invalid");
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(authenticator.authenticate(&rotated).await?.subject, "u2");
+    assert!(authenticator.authenticate(&token(Some("k1"), "secret-1", json!({"sub": "u1"}))).await.is_err());
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::config::config_dto::{WebClientCacheConfig, WebClientModuleConfig};
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::web::web_client::TardisWebClient;
+use tardis::web::web_client_cache::TardisHttpCache;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_cache_by_headers() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let client = TardisWebClient::init(&WebClientModuleConfig::builder().cache(WebClientCacheConfig::builder().enabled(true).build()).build())?;
+
+    // fresh by max-age
+    server.expect(MockExpectation::new("GET", "/regions").respond_with(MockResponse::ok().header("Cache-Control", "public, max-age=60").body(r#"["cn","us"]"#)));
+    assert_eq!(client.get_to_str(format!("{}/regions?b=2&a=1", server.url()), None).await?.body.unwrap(), r#"["cn","us"]"#);
+    assert_eq!(
+        client.get::<Vec<String>>(format!("{}/regions?a=1&b=2", server.url()), None).await?.body.unwrap(),
+        vec!["cn", "us"]
+    );
+    assert_eq!(server.received_requests().len(), 1);
+
+    // revalidated by the etag
+    server.expect(MockExpectation::new("GET", "/zones").times(1).respond_with(MockResponse::ok().header("Cache-Control", "no-cache").header("ETag", r#""v1""#).body("zones-v1")));
+    server.expect(MockExpectation::new("GET", "/zones").header("If-None-Match", r#""v1""#).respond_with(MockResponse::status(304)));
+    assert_eq!(client.get_to_str(format!("{}/zones", server.url()), None).await?.body.unwrap(), "zones-v1");
+    let response = client.get_to_str(format!("{}/zones", server.url()), None).await?;
+    assert_eq!(response.code, 200);
+    assert_eq!(response.body.unwrap(), "zones-v1");
+    assert_eq!(server.received_requests().len(), 3);
+
+    // not cached
+    server.expect(MockExpectation::new("GET", "/private").respond_with(MockResponse::ok().header("Cache-Control", "no-store, max-age=60").body("private")));
+    server.expect(MockExpectation::new("GET", "/error").respond_with(MockResponse::status(500).header("Cache-Control", "max-age=60")));
+    client.get_to_str(format!("{}/private", server.url()), None).await?;
+    client.get_to_str(format!("{}/private", server.url()), None).await?;
+    client.get_to_str(format!("{}/error", server.url()), None).await?;
+    client.get_to_str(format!("{}/error", server.url()), None).await?;
+    assert_eq!(server.received_requests().len(), 7);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_get_cached() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let client = TardisWebClient::init(&WebClientModuleConfig::default())?.with_response_cache(TardisHttpCache::cache_client(Arc::new(TardisCacheClient::memory())));
+    server.expect(MockExpectation::new("GET", "/metadata").respond_with(MockResponse::ok().body("metadata")));
+
+    // cached regardless of the headers, until expired or invalidated
+    let url = format!("{}/metadata", server.url());
+    assert_eq!(client.get_cached(&url, Duration::from_secs(1), None).await?.body.unwrap(), "metadata");
+    assert_eq!(client.get_cached(&url, Duration::from_secs(1), None).await?.body.unwrap(), "metadata");
+    assert_eq!(server.received_requests().len(), 1);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    client.get_cached(&url, Duration::from_secs(60), None).await?;
+    assert_eq!(server.received_requests().len(), 2);
+    client.invalidate_cached(&url).await?;
+    client.get_cached(&url, Duration::from_secs(60), None).await?;
+    assert_eq!(server.received_requests().len(), 3);
+
+    // the in-memory cache evicts the entries retained shortest
+    let cache = TardisHttpCache::memory(2);
+    let client = TardisWebClient::init(&WebClientModuleConfig::default())?.with_response_cache(cache.clone());
+    server.expect(MockExpectation::new("GET", "/a").respond_with(MockResponse::ok()));
+    server.expect(MockExpectation::new("GET", "/b").respond_with(MockResponse::ok()));
+    client.get_cached(format!("{}/metadata", server.url()), Duration::from_secs(60), None).await?;
+    client.get_cached(format!("{}/a", server.url()), Duration::from_secs(10), None).await?;
+    client.get_cached(format!("{}/b", server.url()), Duration::from_secs(60), None).await?;
+    let TardisHttpCache::Memory(memory) = &cache else { panic!("memory cache expected") };
+    assert_eq!(memory.len(), 2);
+    let requests = server.received_requests().len();
+    client.get_cached(format!("{}/metadata", server.url()), Duration::from_secs(60), None).await?;
+    assert_eq!(server.received_requests().len(), requests);
+    client.get_cached(format!("{}/a", server.url()), Duration::from_secs(60), None).await?;
+    assert_eq!(server.received_requests().len(), requests + 1);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::WebClientModuleConfig;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::web::web_client::{str_pair_to_string_pair, TardisWebClient};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_mock() -> TardisResult<()> {
+    let client = TardisWebClient::init(&WebClientModuleConfig::default())?;
+    let server = TardisMockServer::start().await?;
+    let user = User {
+        id: 1,
+        name: "tardis".to_string(),
+    };
+
+    server.expect(MockExpectation::new("GET", "/users/1").header("X-Tenant", "t1").respond_with(MockResponse::ok().json(&user)?));
+    server.expect(MockExpectation::new("POST", "/users").body_json(&user)?.times(1).respond_with(MockResponse::status(201).json(&user)?));
+    server.expect(MockExpectation::new("PUT", "/users/1").body_contains("renamed").respond_with(MockResponse::status(204)));
+
+    let response = client.get::<User>(format!("{}/users/1?fields=all", server.url()), [str_pair_to_string_pair(("X-Tenant", "t1"))]).await?;
+    assert_eq!(response.code, 200);
+    assert_eq!(response.body, Some(user.clone()));
+
+    // header mismatch
+    let response = client.get_to_str(format!("{}/users/1", server.url()), None).await?;
+    assert_eq!(response.code, 404);
+
+    let response = client.post::<User, User>(format!("{}/users", server.url()), &user, None).await?;
+    assert_eq!(response.code, 201);
+    assert_eq!(response.body, Some(user.clone()));
+    // exhausted
+    let response = client.post_obj_to_str(format!("{}/users", server.url()), &user, None).await?;
+    assert_eq!(response.code, 404);
+
+    let response = client.put_str_to_str(format!("{}/users/1", server.url()), r#"{"name":"renamed"}"#, None).await?;
+    assert_eq!(response.code, 204);
+
+    let requests = server.received_requests();
+    assert_eq!(requests.len(), 5);
+    assert_eq!(requests[0].path, "/users/1");
+    assert_eq!(requests[0].query, Some("fields=all".to_string()));
+    assert_eq!(requests[0].headers.get("x-tenant"), Some(&"t1".to_string()));
+    assert_eq!(requests[4].method, "PUT");
+    assert!(requests[4].body.contains("renamed"));
+    server.verify()?;
+
+    // delay and fault injection
+    server.reset();
+    server.expect(MockExpectation::new("GET", "/slow").respond_with(MockResponse::ok().body("slow").delay(Duration::from_millis(500))));
+    server.expect(MockExpectation::new("GET", "/broken").respond_with(MockResponse::fault()));
+    server.expect(MockExpectation::new("GET", "/never"));
+    let start = Instant::now();
+    let response = client.get_to_str(format!("{}/slow", server.url()), None).await?;
+    assert!(start.elapsed() >= Duration::from_millis(500));
+    assert_eq!(response.body, Some("slow".to_string()));
+    assert!(client.get_to_str(format!("{}/broken", server.url()), None).await.is_err());
+    let error = server.verify().unwrap_err();
+    assert!(error.message.contains("GET /never"));
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{WebClientModuleConfig, WebClientProxyConfig, WebClientTlsConfig};
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::web::web_client::TardisWebClient;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_proxy() -> TardisResult<()> {
+    let proxy = TardisMockServer::start().await?;
+    let direct = TardisMockServer::start().await?;
+    let client = TardisWebClient::init(
+        &WebClientModuleConfig::builder().proxy(WebClientProxyConfig::builder().url(proxy.url()).username("svc").password("secret").no_proxy("127.0.0.1").build()).build(),
+    )?;
+
+    // the proxy receives the absolute url with the proxy credentials
+    proxy.expect(MockExpectation::new("GET", "http://upstream.example.com/hello").respond_with(MockResponse::ok().body("proxied")));
+    let response = client.get_to_str("http://upstream.example.com/hello", None).await?;
+    assert_eq!(response.body, Some("proxied".to_string()));
+    let requests = proxy.received_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].headers.get("proxy-authorization"),
+        Some(&format!("Basic {}", general_purpose::STANDARD.encode("svc:secret")))
+    );
+
+    // the hosts in no_proxy are requested directly
+    direct.expect(MockExpectation::new("GET", "/hello").respond_with(MockResponse::ok().body("direct")));
+    let response = client.get_to_str(format!("{}/hello", direct.url()), None).await?;
+    assert_eq!(response.body, Some("direct".to_string()));
+    assert_eq!(proxy.received_requests().len(), 1);
+
+    let error = TardisWebClient::init(&WebClientModuleConfig::builder().proxy(WebClientProxyConfig::builder().url("not a url").build()).build()).err().unwrap();
+    assert_eq!(error.code, "406");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_tls_config() -> TardisResult<()> {
+    assert!(TardisWebClient::init(&WebClientModuleConfig::builder().tls(WebClientTlsConfig::builder().accept_invalid_certs(false).build()).build()).is_ok());
+
+    let error = TardisWebClient::init(&WebClientModuleConfig::builder().tls(WebClientTlsConfig::builder().ca_cert_paths(vec!["/not/exist/ca.pem".to_string()]).build()).build())
+        .err()
+        .unwrap();
+    assert!(error.message.contains("/not/exist/ca.pem"));
+
+    // the client certificate and the key are required together
+    let error = TardisWebClient::init(&WebClientModuleConfig::builder().tls(WebClientTlsConfig::builder().client_cert_path("/etc/pki/client.crt").build()).build()).err().unwrap();
+    assert!(error.message.contains("client_key_path"));
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{WebClientCircuitBreakerConfig, WebClientModuleConfig, WebClientRetryConfig};
+use tardis::test::mock_server::{MockExpectation, MockResponse, TardisMockServer};
+use tardis::utils::circuit_breaker::CircuitState;
+use tardis::utils::retry::RetryPolicy;
+use tardis::web::web_client::TardisWebClient;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_retry_and_timeout() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let client = TardisWebClient::init(&WebClientModuleConfig::builder().retry(WebClientRetryConfig::builder().max_retries(3).initial_backoff_ms(10).build()).build())?;
+
+    // the idempotent requests are retried on the retryable status
+    server.expect(MockExpectation::new("GET", "/flaky").times(2).respond_with(MockResponse::status(503)));
+    server.expect(MockExpectation::new("GET", "/flaky").respond_with(MockResponse::ok().body("ok")));
+    let response = client.get_to_str(format!("{}/flaky", server.url()), None).await?;
+    assert_eq!(response.code, 200);
+    assert_eq!(response.body, Some("ok".to_string()));
+    assert_eq!(server.received_requests().len(), 3);
+
+    // the last response is returned when the retries are exhausted
+    server.reset();
+    server.expect(MockExpectation::new("GET", "/down").respond_with(MockResponse::status(502)));
+    assert_eq!(client.get_to_str(format!("{}/down", server.url()), None).await?.code, 502);
+    assert_eq!(server.received_requests().len(), 4);
+
+    // neither the non-idempotent requests nor the other status are retried
+    server.reset();
+    server.expect(MockExpectation::new("POST", "/orders").respond_with(MockResponse::status(503)));
+    server.expect(MockExpectation::new("GET", "/bad").respond_with(MockResponse::status(500)));
+    assert_eq!(client.post_str_to_str(format!("{}/orders", server.url()), "{}", None).await?.code, 503);
+    assert_eq!(client.get_to_str(format!("{}/bad", server.url()), None).await?.code, 500);
+    assert_eq!(server.received_requests().len(), 2);
+
+    // per-call timeout and retry policy
+    server.reset();
+    server.expect(MockExpectation::new("GET", "/slow").respond_with(MockResponse::ok().delay(Duration::from_millis(500))));
+    let start = Instant::now();
+    assert!(client.with_timeout(Duration::from_millis(100)).without_retry().get_to_str(format!("{}/slow", server.url()), None).await.is_err());
+    assert!(start.elapsed() < Duration::from_millis(400));
+    assert_eq!(server.received_requests().len(), 1);
+    let client = client.with_timeout(Duration::from_millis(100)).with_retry(RetryPolicy::fixed(Duration::from_millis(10)).with_max_retries(1));
+    assert!(client.get_to_str(format!("{}/slow", server.url()), None).await.is_err());
+    assert_eq!(server.received_requests().len(), 3);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_client_circuit_breaker() -> TardisResult<()> {
+    let server = TardisMockServer::start().await?;
+    let host = server.url().trim_start_matches("http://").to_string();
+    let client = TardisWebClient::init(
+        &WebClientModuleConfig::builder().circuit_breaker(WebClientCircuitBreakerConfig::builder().enabled(true).failure_threshold(2).open_sec(1).build()).build(),
+    )?;
+
+    server.expect(MockExpectation::new("GET", "/down").times(3).respond_with(MockResponse::status(500)));
+    server.expect(MockExpectation::new("GET", "/down").respond_with(MockResponse::ok()));
+    assert_eq!(client.get_to_str(format!("{}/down", server.url()), None).await?.code, 500);
+    assert_eq!(client.circuit_state(&host), Some(CircuitState::Closed));
+    assert_eq!(client.get_to_str(format!("{}/down", server.url()), None).await?.code, 500);
+    assert_eq!(client.circuit_state(&host), Some(CircuitState::Open));
+
+    // fail fast while the circuit is open, the clones share the circuit
+    let error = client.clone().get_to_str(format!("{}/down", server.url()), None).await.unwrap_err();
+    assert_eq!(error.code, "503-tardis-webclient-circuit-open");
+    assert_eq!(server.received_requests().len(), 2);
+
+    // the failed trial opens the circuit again, the succeeded one closes it
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(client.get_to_str(format!("{}/down", server.url()), None).await?.code, 500);
+    assert_eq!(client.circuit_state(&host), Some(CircuitState::Open));
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(client.get_to_str(format!("{}/down", server.url()), None).await?.code, 200);
+    assert_eq!(client.circuit_state(&host), Some(CircuitState::Closed));
+    assert_eq!(TardisWebClient::init(&WebClientModuleConfig::default())?.circuit_state(&host), None);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::env;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::http::{Method, StatusCode};
+use tardis::web::poem_openapi::{param::Path, OpenApi};
+use tardis::web::web_resp::{TardisApiResult, TardisErrorRegistry, TardisResp, Void};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_error_registry() -> TardisResult<()> {
+    env::set_var("PROFILE", "default");
+    let dir = env::temp_dir().join(format!("tardis-web-error-registry-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(dir.join("locale"))?;
+    std::fs::write(dir.join("conf-default.toml"), "[fw.app]\ndefault_lang = \"en\"\n\n[fw.web_server]\nport = 8094\n")?;
+    std::fs::write(dir.join("locale").join("en"), "todo-gone\tTodo is gone\n")?;
+    std::fs::write(dir.join("locale").join("zh-cn"), "todo-gone\t待办已删除\n")?;
+    TardisFuns::init(dir.to_str()).await?;
+
+    assert!(TardisErrorRegistry::register("404-todo", 1000, "todo-gone").is_err());
+    TardisErrorRegistry::register("404-todo", 410, "todo-gone")?;
+    TardisErrorRegistry::register("404", 404, "")?;
+    // the longest prefix wins
+    assert_eq!(TardisErrorRegistry::get("404-todo-gone").unwrap().http_status, StatusCode::GONE);
+    assert_eq!(TardisErrorRegistry::get("404-user-not-found").unwrap().http_status, StatusCode::NOT_FOUND);
+    assert!(TardisErrorRegistry::get("400-todo-invalid").is_none());
+    assert_eq!(TardisErrorRegistry::localized_message("404-user-not-found", "user not found", None), "user not found");
+
+    TardisFuns::web_server().add_module("todo", TodoApi).await;
+    let client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?;
+    // the default language of the application is used without `Accept-Language`
+    let resp = client.get("/todo/todos/0").await?;
+    resp.assert_status(410).assert_code("404-todo-gone");
+    assert_eq!(resp.json::<tardis::serde_json::Value>()?["msg"], "Todo is gone");
+    let resp = client.request(Method::GET, "/todo/todos/0", vec![("Accept-Language".to_string(), "zh-CN,zh;q=0.9".to_string())], None).await?;
+    resp.assert_status(410);
+    assert_eq!(resp.json::<tardis::serde_json::Value>()?["msg"], "待办已删除");
+    // the codes not registered keep the friendly fallback
+    client.get("/todo/todos/1").await?.assert_status(200).assert_code("400-todo-invalid");
+
+    TardisErrorRegistry::unregister("404-todo");
+    assert_eq!(TardisErrorRegistry::get("404-todo-gone").unwrap().http_status, StatusCode::NOT_FOUND);
+    let resp = client.get("/todo/todos/0").await?;
+    resp.assert_status(404);
+    assert_eq!(resp.json::<tardis::serde_json::Value>()?["msg"], "todo is gone");
+    TardisErrorRegistry::unregister("404");
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[derive(Clone)]
+struct TodoApi;
+
+#[OpenApi]
+impl TodoApi {
+    #[oai(path = "/todos/:id", method = "get")]
+    async fn get(&self, id: Path<i64>) -> TardisApiResult<Void> {
+        if id.0 == 0 {
+            return TardisResp::err(TardisError::custom("404-todo-gone", "todo is gone", ""));
+        }
+        TardisResp::err(TardisError::custom("400-todo-invalid", "todo is invalid", ""))
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::test::test_harness::TardisTestHarness;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::Route;
+use tardis::web::web_health::{HealthReport, HealthStatus, TardisHealth};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_health() -> TardisResult<()> {
+    TardisTestHarness::builder().with_cache("mem://").with_mq("mem://").init().await?;
+
+    let health = TardisHealth::new().liveness_check("self", || async { Ok(()) });
+    let client = TardisWebTestClient::from_route(Route::new().at("/healthz", health.liveness_endpoint()).at("/readyz", health.readiness_endpoint()));
+    let resp = client.get("/healthz").await?;
+    resp.assert_status(200);
+    let report: HealthReport = resp.json()?;
+    assert_eq!(report.components.keys().collect::<Vec<_>>(), vec!["self"]);
+    let resp = client.get("/readyz").await?;
+    resp.assert_status(200);
+    let report: HealthReport = resp.json()?;
+    assert_eq!(report.status, HealthStatus::Up);
+    assert_eq!(report.components.keys().collect::<Vec<_>>(), vec!["cache", "mq", "self"]);
+
+    // the failing and timed out checks make the readiness down, but not the liveness
+    let health = TardisHealth::new()
+        .timeout(Duration::from_secs(1))
+        .component_timeout("slow", Duration::from_millis(100))
+        .readiness_check("slow", || async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok(())
+        })
+        .readiness_check("failing", || async { Err(TardisError::io_error("unreachable", "")) });
+    let client = TardisWebTestClient::from_route(Route::new().at("/healthz", health.liveness_endpoint()).at("/readyz", health.readiness_endpoint()));
+    client.get("/healthz").await?.assert_status(200);
+    let resp = client.get("/readyz").await?;
+    resp.assert_status(503);
+    let report: HealthReport = resp.json()?;
+    assert_eq!(report.status, HealthStatus::Down);
+    assert_eq!(report.components["cache"].status, HealthStatus::Up);
+    assert_eq!(report.components["slow"].status, HealthStatus::Down);
+    assert!(report.components["slow"].error.as_ref().unwrap().contains("timeout"));
+    assert!(report.components["slow"].elapsed_ms < 500);
+    assert_eq!(report.components["failing"].error.as_deref(), Some("503:unreachable"));
+
+    let report = TardisHealth::new().without_components().readiness().await;
+    assert!(report.components.is_empty());
+    assert_eq!(report.status, HealthStatus::Up);
+    Ok(())
+}
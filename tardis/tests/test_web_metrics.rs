@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::WebClientModuleConfig;
+use tardis::consts::IP_LOCALHOST;
+use tardis::mq::mq_client::TardisMQClient;
+use tardis::web::poem::{endpoint::make_sync, Route};
+use tardis::web::web_client::TardisWebClient;
+use tardis::web::web_server::TardisWebServer;
+use tardis::TardisFuns;
+use tokio::time::sleep;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_metrics() -> TardisResult<()> {
+    let serv = TardisWebServer::init_simple(IP_LOCALHOST, 8101)?;
+    serv.add_module_raw("hello", Route::new().at("/", make_sync(|_| "hello"))).await;
+    serv.add_metrics_endpoint().await;
+    serv.start().await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let client = TardisWebClient::init(&WebClientModuleConfig::default())?;
+    assert_eq!(client.get_to_str("http://127.0.0.1:8101/hello", None).await?.body.unwrap(), "hello");
+    assert_eq!(client.get_to_str("http://127.0.0.1:8101/hello/not-found", None).await?.code, 404);
+    assert!(client.get_to_str("http://127.0.0.1:1/unreachable", None).await.is_err());
+
+    let mq = TardisMQClient::memory();
+    mq.publish("orders", "created".to_string(), &Default::default()).await?;
+
+    let resp = client.get_to_str("http://127.0.0.1:8101/metrics", None).await?;
+    assert!(resp.headers.get("content-type").unwrap().starts_with("text/plain"));
+    let text = resp.body.unwrap();
+    assert!(text.contains(r#"tardis_http_server_requests_total{method="GET",module="hello",status="200"} 1"#));
+    assert!(text.contains(r#"tardis_http_server_requests_total{method="GET",module="hello",status="404"} 1"#));
+    assert!(text.contains(r#"tardis_http_server_request_duration_seconds_count{method="GET",module="hello"} 2"#));
+    assert!(text.contains(r#"tardis_http_client_requests_total{host="127.0.0.1",method="GET",status="200"} 1"#));
+    assert!(text.contains(r#"tardis_http_client_requests_total{host="127.0.0.1",method="GET",status="error"} 1"#));
+    assert!(text.contains(r#"tardis_mq_messages_total{direction="publish",status="ok",topic="orders"} 1"#));
+    assert!(TardisFuns::metrics().export()?.contains(r#"module="metrics""#));
+
+    serv.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::web::web_resp::TardisPage;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_resp_page() -> TardisResult<()> {
+    let page = TardisPage::from_total(vec![1, 2, 3], 23, 2, 3);
+    assert_eq!(page.total_pages(), 8);
+    assert_eq!(TardisPage::<i32>::empty(1, 10).total_pages(), 0);
+    assert_eq!(TardisPage::<i32>::empty(1, 0).total_pages(), 0);
+
+    let mapped = page.clone().map(|record| format!("r{record}"));
+    assert_eq!(mapped.records, vec!["r1", "r2", "r3"]);
+    assert_eq!(mapped.page_number, 2);
+    assert_eq!(mapped.page_size, 3);
+    assert_eq!(mapped.total_size, 23);
+
+    let mapped = page.clone().try_map(|record| Ok::<_, TardisError>(record as u64 * 10))?;
+    assert_eq!(mapped.records, vec![10, 20, 30]);
+    let result = page.try_map(|record| if record < 2 { Ok(record) } else { Err(TardisError::bad_request("invalid record", "")) });
+    assert_eq!(result.unwrap_err().code, "400");
+    Ok(())
+}
@@ -443,6 +443,7 @@ async fn test_context(url: &str) -> TardisResult<()> {
         ak: "ak1".to_string(),
         roles: vec!["r1".to_string(), "管理员".to_string()],
         groups: vec!["g1".to_string()],
+        request_id: "".to_string(),
         owner: "acc1".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
@@ -493,6 +494,7 @@ async fn test_context(url: &str) -> TardisResult<()> {
         ak: "ak1".to_string(),
         roles: vec!["r1".to_string(), "管理员".to_string()],
         groups: vec!["g1".to_string()],
+        request_id: "".to_string(),
         owner: "acc1".to_string(),
         ext: Default::default(),
         sync_task_fns: Default::default(),
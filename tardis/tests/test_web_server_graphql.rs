@@ -0,0 +1,70 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::serde_json::{self, json};
+use tardis::test::test_harness::TardisTestHarness;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::async_graphql::{Context, EmptySubscription, Error, Object, Result, Schema};
+use tardis::web::graphql::TardisGraphqlContext;
+use tardis::TardisFuns;
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn owner(&self, ctx: &Context<'_>) -> Result<String> {
+        Ok(ctx.tardis_ctx()?.owner.clone())
+    }
+
+    async fn todo(&self, id: i32) -> Result<String> {
+        if id == 0 {
+            return Err(TardisError::not_found("todo not found", "404-todo-not-found").into());
+        }
+        Ok(format!("todo{id}"))
+    }
+}
+
+struct Mutation;
+
+#[Object]
+impl Mutation {
+    async fn add_todo(&self, code: String) -> Result<String> {
+        if code.is_empty() {
+            return Err(Error::new("code is empty"));
+        }
+        Ok(code)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_server_graphql() -> TardisResult<()> {
+    TardisTestHarness::builder().with_web_server(8091).init().await?;
+    let schema = Schema::new(Query, Mutation, EmptySubscription);
+    // the test client takes out the modules
+    TardisFuns::web_server().add_graphql_module("graphql", schema.clone()).await;
+    let ctx = TardisContext {
+        owner: "a1".to_string(),
+        ..Default::default()
+    };
+    let ctx_client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?.context(&ctx)?;
+    TardisFuns::web_server().add_graphql_module("graphql", schema).await;
+    let client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?;
+
+    // the context is injected into the resolvers
+    let body = ctx_client.post("/graphql", &json!({"query": "{ owner todo(id: 1) }"})).await?.assert_status(200).json::<serde_json::Value>()?;
+    assert_eq!(body["data"], json!({"owner": "a1", "todo": "todo1"}));
+    let body = client.post("/graphql", &json!({"query": "mutation { addTodo(code: \"c1\") }"})).await?.json::<serde_json::Value>()?;
+    assert_eq!(body["data"]["addTodo"], "c1");
+
+    // the errors are mapped to the tardis error codes
+    let body = client.post("/graphql", &json!({"query": "{ owner }"})).await?.json::<serde_json::Value>()?;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "401");
+    let body = client.post("/graphql", &json!({"query": "{ todo(id: 0) }"})).await?.json::<serde_json::Value>()?;
+    assert_eq!(body["errors"][0]["message"], "todo not found");
+    assert_eq!(body["errors"][0]["extensions"]["code"], "404");
+    let body = client.post("/graphql", &json!({"query": "mutation { addTodo(code: \"\") }"})).await?.json::<serde_json::Value>()?;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "500");
+    let body = client.post("/graphql", &json!({"query": "{ notExist }"})).await?.json::<serde_json::Value>()?;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "400");
+    Ok(())
+}
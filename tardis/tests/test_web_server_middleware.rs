@@ -0,0 +1,92 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::http::Method;
+use tardis::web::poem::{Endpoint, Middleware, Request};
+use tardis::web::poem_openapi::{payload::PlainText, OpenApi};
+use tardis::web::web_server::WebServerModule;
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_server_middleware() -> TardisResult<()> {
+    env::set_var("PROFILE", "default");
+    let dir = env::temp_dir().join(format!("tardis-web-middleware-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("conf-default.toml"),
+        "[fw.web_server]\nport = 8099\nallowed_origin = \"https://a.example.com\"\n\n[fw.web_server.modules.open]\nallowed_origin = \"https://b.example.com\"\n",
+    )?;
+    TardisFuns::init(dir.to_str()).await?;
+
+    TardisFuns::web_server()
+        .add_module(
+            "chain",
+            WebServerModule::from(Api).with_middleware(10, Tag("a")).with_middleware(20, Tag("b")).with_middleware(10, Tag("c")).with_request_id().with_access_log(),
+        )
+        .await;
+    TardisFuns::web_server().add_module("open", Api).await;
+    let client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?;
+
+    // higher priority is outer, the earlier added one is outer on the same priority
+    let resp = client.get("/chain/trace").await?;
+    resp.assert_status(200);
+    assert_eq!(resp.body, "b,a,c");
+    let request_id = resp.headers.get("x-request-id").cloned().unwrap();
+    assert!(!request_id.is_empty());
+    // the request id of the request is kept, also for the errors
+    let resp = client.request(Method::GET, "/chain/not-found", vec![("X-Request-Id".to_string(), "req-1".to_string())], None).await?;
+    resp.assert_header("X-Request-Id", "req-1");
+    assert!(client.get("/open/trace").await?.headers.get("x-request-id").is_none());
+
+    // CORS per module
+    let cors_headers = |origin: &str| vec![("Origin".to_string(), origin.to_string())];
+    let resp = client.request(Method::GET, "/chain/trace", cors_headers("https://a.example.com"), None).await?;
+    resp.assert_header("Access-Control-Allow-Origin", "https://a.example.com");
+    let resp = client.request(Method::GET, "/open/trace", cors_headers("https://b.example.com"), None).await?;
+    resp.assert_header("Access-Control-Allow-Origin", "https://b.example.com");
+    let resp = client.request(Method::GET, "/open/trace", cors_headers("https://a.example.com"), None).await?;
+    assert!(resp.headers.get("access-control-allow-origin").is_none());
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// Append the name to the `X-Trace` request header
+struct Tag(&'static str);
+
+impl<E: Endpoint> Middleware<E> for Tag {
+    type Output = TagImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TagImpl(ep, self.0)
+    }
+}
+
+struct TagImpl<E>(E, &'static str);
+
+#[tardis::async_trait::async_trait]
+impl<E: Endpoint> Endpoint for TagImpl<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> tardis::web::poem::Result<Self::Output> {
+        let trace = match req.header("X-Trace") {
+            Some(trace) => format!("{trace},{}", self.1),
+            None => self.1.to_string(),
+        };
+        req.headers_mut().insert("X-Trace", trace.parse().unwrap());
+        self.0.call(req).await
+    }
+}
+
+#[derive(Clone)]
+struct Api;
+
+#[OpenApi]
+impl Api {
+    #[oai(path = "/trace", method = "get")]
+    async fn trace(&self, req: &Request) -> PlainText<String> {
+        PlainText(req.header("X-Trace").unwrap_or_default().to_string())
+    }
+}
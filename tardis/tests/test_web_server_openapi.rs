@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, WebServerCommonConfig, WebServerConfig, WebServerModuleConfig};
+use tardis::serde_json::{self, json};
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem_openapi::{param::Path, payload::PlainText, OpenApi};
+use tardis::web::web_server::{OpenApiFormat, TardisWebServer};
+use tardis::TardisFuns;
+
+struct TodoApi;
+
+#[OpenApi]
+impl TodoApi {
+    #[oai(path = "/todos/:id", method = "get")]
+    async fn get(&self, id: Path<i64>) -> PlainText<String> {
+        PlainText(format!("todo{}", id.0))
+    }
+}
+
+struct PartnerApi;
+
+#[OpenApi]
+impl PartnerApi {
+    #[oai(path = "/orders", method = "get")]
+    async fn orders(&self) -> PlainText<String> {
+        PlainText("orders".to_string())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_server_openapi() -> TardisResult<()> {
+    let fw_config = FrameworkConfig::builder()
+        .web_server(
+            WebServerConfig::builder()
+                .common(WebServerCommonConfig::builder().port(8092).build())
+                .modules([
+                    ("todo".to_string(), WebServerModuleConfig::builder().name("todo app").spec_path("spec.yaml").build()),
+                    (
+                        "partner".to_string(),
+                        WebServerModuleConfig::builder()
+                            .name("partner api")
+                            .version("2.0.0")
+                            .description("API for the partners")
+                            .doc_urls([("prod env".to_string(), "https://api.example.com".to_string())])
+                            .security_schemes(HashMap::from([("bearer".to_string(), json!({"type": "http", "scheme": "bearer"}))]))
+                            .spec_path("openapi.json")
+                            .build(),
+                    ),
+                ])
+                .default(Default::default())
+                .build(),
+        )
+        .build();
+    let server = TardisWebServer::init_by_conf(&fw_config)?;
+    server.add_module("todo", TodoApi).await.add_module("partner", PartnerApi).await;
+
+    // separate spec documents per module
+    let specs = server.openapi_specs().await;
+    assert_eq!(specs["todo"]["info"]["title"], "todo app");
+    assert!(specs["todo"]["paths"].get("/todos/{id}").is_some());
+    assert!(specs["todo"]["paths"].get("/orders").is_none());
+    assert!(specs["todo"].get("security").is_none());
+    let partner = &specs["partner"];
+    assert_eq!(partner["info"]["title"], "partner api");
+    assert_eq!(partner["info"]["version"], "2.0.0");
+    assert_eq!(partner["info"]["description"], "API for the partners");
+    assert_eq!(partner["servers"][0]["url"], "https://api.example.com/partner");
+    assert_eq!(partner["components"]["securitySchemes"]["bearer"], json!({"type": "http", "scheme": "bearer"}));
+    assert_eq!(partner["security"], json!([{"bearer": []}]));
+
+    // export
+    let dir = std::env::temp_dir().join(format!("tardis-openapi-{}", std::process::id()));
+    let paths = server.export_openapi(&dir, OpenApiFormat::Json).await?;
+    assert_eq!(paths, vec![dir.join("partner.json"), dir.join("todo.json")]);
+    let exported: serde_json::Value = TardisFuns::json.str_to_obj(&std::fs::read_to_string(dir.join("partner.json"))?)?;
+    assert_eq!(&exported, partner);
+    let paths = server.export_openapi(&dir, OpenApiFormat::Yaml).await?;
+    assert_eq!(paths[1], dir.join("todo.yaml"));
+    assert!(std::fs::read_to_string(&paths[1])?.contains("title: todo app"));
+    std::fs::remove_dir_all(&dir)?;
+
+    // served by the configured paths in the configured formats
+    let client = TardisWebTestClient::from_server(&server).await?;
+    let resp = client.get("/partner/openapi.json").await?;
+    resp.assert_status(200);
+    assert_eq!(&resp.json::<serde_json::Value>()?, partner);
+    let resp = client.get("/todo/spec.yaml").await?;
+    resp.assert_status(200);
+    assert!(resp.body.contains("title: todo app"));
+    client.get("/todo/todos/1").await?.assert_status(200);
+    Ok(())
+}
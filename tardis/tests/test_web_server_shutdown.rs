@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::consts::IP_LOCALHOST;
+use tardis::web::poem_openapi::{payload::PlainText, OpenApi};
+use tardis::web::reqwest;
+use tardis::web::web_server::TardisWebServer;
+use tokio::time::sleep;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_server_shutdown() -> TardisResult<()> {
+    let hook_counter = Arc::new(AtomicUsize::new(0));
+    let serv = TardisWebServer::init_simple(IP_LOCALHOST, 8098)?;
+    serv.add_module("slow", SlowApi).await;
+    {
+        let hook_counter = hook_counter.clone();
+        serv.add_shutdown_hook("count", move || {
+            let hook_counter = hook_counter.clone();
+            async move {
+                hook_counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+    }
+    serv.start().await?;
+    sleep(Duration::from_millis(500)).await;
+
+    // the in-flight request is drained before the server stops
+    let in_flight = tokio::spawn(async { reqwest::get("http://127.0.0.1:8098/slow/slow").await?.text().await });
+    sleep(Duration::from_millis(200)).await;
+    serv.shutdown().await?;
+    assert_eq!(in_flight.await.unwrap().unwrap(), "done");
+    assert_eq!(hook_counter.load(Ordering::SeqCst), 1);
+    assert!(!serv.is_running().await);
+
+    // new connections are refused
+    assert!(reqwest::get("http://127.0.0.1:8098/slow/slow").await.is_err());
+    Ok(())
+}
+
+#[derive(Clone)]
+struct SlowApi;
+
+#[OpenApi]
+impl SlowApi {
+    #[oai(path = "/slow", method = "get")]
+    async fn slow(&self) -> PlainText<String> {
+        sleep(Duration::from_millis(1000)).await;
+        PlainText("done".to_string())
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use tardis::basic::result::TardisResult;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::{IntoResponse, Route};
+use tardis::web::web_sse::{sse_endpoint, TardisSse, TardisSseChannel, TardisSseEvent};
+use tokio::time::timeout;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_sse() -> TardisResult<()> {
+    test_stream().await?;
+    test_heartbeat().await?;
+    test_channel().await?;
+    Ok(())
+}
+
+async fn test_stream() -> TardisResult<()> {
+    let route = Route::new().at(
+        "/ticks",
+        sse_endpoint(|last_event_id| async move {
+            let start = last_event_id.and_then(|id| id.parse::<u64>().ok()).map(|id| id + 1).unwrap_or(0);
+            let events = futures::stream::iter(start..3).map(|i| TardisSseEvent::data(format!("tick {i}\nline 2")).id(i.to_string()).event("tick"));
+            TardisSse::from_stream(events).retry(Duration::from_secs(3))
+        }),
+    );
+    let client = TardisWebTestClient::from_route(route);
+    let resp = client.get("/ticks").await?;
+    resp.assert_status(200).assert_header("Content-Type", "text/event-stream").assert_header("Cache-Control", "no-cache");
+    assert_eq!(
+        resp.body,
+        "retry: 3000\n\nid: 0\nevent: tick\ndata: tick 0\ndata: line 2\n\nid: 1\nevent: tick\ndata: tick 1\ndata: line 2\n\nid: 2\nevent: tick\ndata: tick 2\ndata: line 2\n\n"
+    );
+    // resume after the last event id
+    let resp = client.request(tardis::web::poem::http::Method::GET, "/ticks", vec![("Last-Event-ID".to_string(), "1".to_string())], None).await?;
+    assert_eq!(resp.body, "retry: 3000\n\nid: 2\nevent: tick\ndata: tick 2\ndata: line 2\n\n");
+    Ok(())
+}
+
+async fn test_heartbeat() -> TardisResult<()> {
+    let sse = TardisSse::from_stream(futures::stream::pending()).heartbeat(Some(Duration::from_millis(100)));
+    let mut body = sse.into_response().into_body().into_bytes_stream().boxed();
+    assert_eq!(next_frame(&mut body).await, ":\n\n");
+    Ok(())
+}
+
+async fn test_channel() -> TardisResult<()> {
+    let channel = TardisSseChannel::new(10, 2);
+    assert_eq!(channel.send(TardisSseEvent::data("a")), 0);
+    channel.send(TardisSseEvent::data("b"));
+    channel.send(TardisSseEvent::data("c"));
+
+    let mut live = channel.subscribe(None).heartbeat(None).into_response().into_body().into_bytes_stream().boxed();
+    // only the last 2 events are kept
+    let mut replay_unknown = channel.subscribe(Some("1")).heartbeat(None).into_response().into_body().into_bytes_stream().boxed();
+    let mut replay = channel.subscribe(Some("2")).heartbeat(None).into_response().into_body().into_bytes_stream().boxed();
+    assert_eq!(channel.send(TardisSseEvent::data("d").event("notice")), 3);
+
+    assert_eq!(next_frame(&mut live).await, "id: 4\nevent: notice\ndata: d\n\n");
+    assert_eq!(next_frame(&mut replay_unknown).await, "id: 2\ndata: b\n\n");
+    assert_eq!(next_frame(&mut replay_unknown).await, "id: 3\ndata: c\n\n");
+    assert_eq!(next_frame(&mut replay_unknown).await, "id: 4\nevent: notice\ndata: d\n\n");
+    assert_eq!(next_frame(&mut replay).await, "id: 3\ndata: c\n\n");
+    assert_eq!(next_frame(&mut replay).await, "id: 4\nevent: notice\ndata: d\n\n");
+    Ok(())
+}
+
+async fn next_frame<S, B>(body: &mut S) -> String
+where
+    S: Stream<Item = Result<B, std::io::Error>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    let frame = timeout(Duration::from_millis(500), body.next()).await.expect("frame timeout").expect("stream ended").expect("stream error");
+    String::from_utf8_lossy(frame.as_ref()).to_string()
+}
@@ -0,0 +1,108 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::serde_json;
+use tardis::test::test_harness::TardisTestHarness;
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::context_extractor::TardisContextExtractor;
+use tardis::web::poem_openapi::{param::Header, param::Path, payload::Json, Object, OpenApi};
+use tardis::web::web_resp::{TardisApiResult, TardisResp};
+use tardis::TardisFuns;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_test_client() -> TardisResult<()> {
+    TardisTestHarness::builder().with_web_server(8090).init().await?;
+    TardisFuns::web_server().add_module("todo", TodoApi).await;
+    let ctx = TardisContext {
+        own_paths: "t1".to_string(),
+        owner: "a1".to_string(),
+        ..Default::default()
+    };
+    let client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?.context(&ctx)?.header("X-Source", "test");
+
+    let todo: TodoResp = client
+        .post(
+            "/todo/todos",
+            &TodoAddReq {
+                code: "c1".to_string(),
+                description: "first".to_string(),
+            },
+        )
+        .await?
+        .assert_status(200)
+        .assert_ok()?;
+    assert_eq!(todo.code, "c1");
+    assert_eq!(todo.owner, "a1");
+    assert_eq!(todo.source, "test");
+
+    let todo: TodoResp = client.get("/todo/todos/1").await?.assert_status(200).assert_ok()?;
+    assert_eq!(todo.id, 1);
+
+    client.get("/todo/todos/0").await?.assert_code("404-todo-not-found");
+    // the extensions of the error are returned in `ext`
+    let resp = client
+        .post(
+            "/todo/todos",
+            &TodoAddReq {
+                code: "".to_string(),
+                description: "".to_string(),
+            },
+        )
+        .await?;
+    resp.assert_code("400-todo-invalid");
+    let body = resp.json::<serde_json::Value>()?;
+    assert_eq!(body["ext"]["code"], "must not be empty");
+    assert_eq!(body["ext"]["description"], "must not be empty");
+    assert!(body.get("data").is_none());
+    client.get("/todo/not-exist").await?.assert_status(404);
+    Ok(())
+}
+
+#[derive(Object, Serialize, Deserialize, Debug)]
+struct TodoAddReq {
+    code: String,
+    description: String,
+}
+
+#[derive(Object, Serialize, Deserialize, Debug)]
+struct TodoResp {
+    id: i64,
+    code: String,
+    owner: String,
+    source: String,
+}
+
+#[derive(Clone)]
+struct TodoApi;
+
+#[OpenApi]
+impl TodoApi {
+    #[oai(path = "/todos", method = "post")]
+    async fn add(&self, req: Json<TodoAddReq>, #[oai(name = "X-Source")] source: Header<String>, ctx: TardisContextExtractor) -> TardisApiResult<TodoResp> {
+        if req.0.code.is_empty() {
+            return TardisResp::err(
+                TardisError::custom("400-todo-invalid", "todo is invalid", "").with_extension("code", "must not be empty").with_extension("description", "must not be empty"),
+            );
+        }
+        TardisResp::ok(TodoResp {
+            id: 1,
+            code: req.0.code,
+            owner: ctx.0.owner,
+            source: source.0,
+        })
+    }
+
+    #[oai(path = "/todos/:id", method = "get")]
+    async fn get(&self, id: Path<i64>, ctx: TardisContextExtractor) -> TardisApiResult<TodoResp> {
+        if id.0 == 0 {
+            return TardisResp::err(TardisError::custom("404-todo-not-found", "todo not found", ""));
+        }
+        TardisResp::ok(TodoResp {
+            id: id.0,
+            code: "c1".to_string(),
+            owner: ctx.0.owner,
+            source: "".to_string(),
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::WebClientModuleConfig;
+use tardis::consts::IP_LOCALHOST;
+use tardis::web::poem::web::{Data, Json, Multipart};
+use tardis::web::poem::{endpoint::make_sync, handler, EndpointExt, Route};
+use tardis::web::web_client::{TardisMultipartPart, TardisWebClient};
+use tardis::web::web_server::TardisWebServer;
+use tardis::web::web_upload::{TardisUploadResult, TardisUploadSink, TardisUploader};
+use tardis::TardisFuns;
+use tokio::time::sleep;
+
+#[handler]
+async fn upload(multipart: Multipart, dir: Data<&std::path::PathBuf>) -> tardis::web::poem::Result<Json<TardisUploadResult>> {
+    let result =
+        TardisUploader::new().max_file_size(1024).max_files(2).allow_content_types(["text/plain", "image/*"]).receive(multipart, &TardisUploadSink::Dir(dir.0.clone())).await?;
+    Ok(Json(result))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_upload() -> TardisResult<()> {
+    let dir = std::env::temp_dir().join(format!("tardis-upload-{}", TardisFuns::field.nanoid()));
+    tokio::fs::create_dir_all(&dir).await?;
+    let serv = TardisWebServer::init_simple(IP_LOCALHOST, 8102)?;
+    serv.add_module_raw(
+        "file",
+        Route::new().at("/upload", upload.data(dir.clone())).at("/download", make_sync(|_| "x".repeat(200 * 1024))),
+    )
+    .await;
+    serv.start().await?;
+    sleep(Duration::from_millis(500)).await;
+    let client = TardisWebClient::init(&WebClientModuleConfig::default())?;
+
+    // upload the text field, the bytes and the local file
+    let local = dir.join("local.txt");
+    tokio::fs::write(&local, "local file").await?;
+    let resp = client
+        .post_multipart(
+            "http://127.0.0.1:8102/file/upload",
+            [
+                TardisMultipartPart::text("title", "report"),
+                TardisMultipartPart::bytes("avatar", "avatar.PNG", vec![1, 2, 3]).content_type("image/png"),
+                TardisMultipartPart::file("doc", &local).content_type("text/plain"),
+            ],
+            None,
+        )
+        .await?;
+    assert_eq!(resp.code, 200);
+    let result = TardisFuns::json.str_to_obj::<TardisUploadResult>(&resp.body.unwrap())?;
+    assert_eq!(result.fields.get("title").unwrap(), "report");
+    assert_eq!(result.files.len(), 2);
+    assert_eq!(result.files[0].file_name.as_deref(), Some("avatar.PNG"));
+    assert_eq!(result.files[0].size, 3);
+    assert!(result.files[0].location.starts_with(dir.to_str().unwrap()) && result.files[0].location.ends_with(".png"));
+    assert_eq!(tokio::fs::read(&result.files[0].location).await?, vec![1, 2, 3]);
+    assert_eq!(tokio::fs::read_to_string(&result.files[1].location).await?, "local file");
+
+    // too large, disallowed content type and too many files, the stored files are removed
+    let count = || std::fs::read_dir(&dir).unwrap().count();
+    let before = count();
+    let resp = client
+        .post_multipart(
+            "http://127.0.0.1:8102/file/upload",
+            [
+                TardisMultipartPart::bytes("a", "a.txt", "a").content_type("text/plain"),
+                TardisMultipartPart::bytes("b", "b.txt", vec![b'b'; 2048]).content_type("text/plain"),
+            ],
+            None,
+        )
+        .await?;
+    assert_eq!(resp.code, 413);
+    assert!(resp.body.unwrap().contains("413-tardis-webserver-upload-too-large"));
+    let resp = client
+        .post_multipart(
+            "http://127.0.0.1:8102/file/upload",
+            [TardisMultipartPart::bytes("a", "a.pdf", "a").content_type("application/pdf")],
+            None,
+        )
+        .await?;
+    assert_eq!(resp.code, 415);
+    let resp = client
+        .post_multipart(
+            "http://127.0.0.1:8102/file/upload",
+            ["a", "b", "c"].map(|name| TardisMultipartPart::bytes(name, format!("{name}.txt"), name).content_type("text/plain")),
+            None,
+        )
+        .await?;
+    assert_eq!(resp.code, 413);
+    assert_eq!(count(), before);
+
+    // download with progress
+    let target = dir.join("download.txt");
+    let mut progress = Vec::new();
+    let size = client.download_to_file("http://127.0.0.1:8102/file/download", &target, None, |downloaded, total| progress.push((downloaded, total))).await?;
+    assert_eq!(size, 200 * 1024);
+    assert_eq!(tokio::fs::metadata(&target).await?.len(), 200 * 1024);
+    assert_eq!(progress.last(), Some(&(200 * 1024, Some(200 * 1024))));
+    assert!(progress.windows(2).all(|w| w[0].0 < w[1].0));
+    let error = client.download_to_file("http://127.0.0.1:8102/file/not-found", dir.join("missing.txt"), None, |_, _| {}).await.unwrap_err();
+    assert_eq!(error.code, "404");
+    assert!(!dir.join("missing.txt").exists() && !dir.join("missing.txt.part").exists());
+
+    serv.shutdown().await?;
+    tokio::fs::remove_dir_all(&dir).await?;
+    Ok(())
+}
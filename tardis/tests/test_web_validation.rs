@@ -0,0 +1,150 @@
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::basic::validation::{TardisValidate, VALIDATION_LENGTH_CODE, VALIDATION_ONE_OF_CODE, VALIDATION_RANGE_CODE, VALIDATION_REGEX_CODE};
+use tardis::serde_json::{json, Value};
+use tardis::test::web_test_client::TardisWebTestClient;
+use tardis::web::poem::http::Method;
+use tardis::web::poem_openapi::{self, Object, OpenApi};
+use tardis::web::web_resp::{TardisApiResult, TardisResp};
+use tardis::web::web_validation::ValidJson;
+use tardis::TardisFuns;
+use tardis::TardisValidate;
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Object, Serialize, Deserialize, Debug, TardisValidate)]
+struct TagReq {
+    #[validate(length(min = 1, max = 8))]
+    name: String,
+}
+
+#[derive(Object, Serialize, Deserialize, Debug, TardisValidate)]
+struct TodoAddReq {
+    #[validate(length(min = 1, max = 16), regex = "^[a-z0-9-]+$")]
+    code: String,
+    #[validate(range(min = 1, max = 5))]
+    priority: i32,
+    #[validate(one_of = ["todo", "done"])]
+    status: String,
+    #[validate(custom = "check_description")]
+    description: Option<String>,
+    #[validate(length(max = 2), nested)]
+    tags: Vec<TagReq>,
+}
+
+fn check_description(description: &String) -> TardisResult<()> {
+    if description.contains("spam") {
+        return Err(TardisError::bad_request("must not contain spam", "400-todo-description-spam"));
+    }
+    Ok(())
+}
+
+fn valid_req() -> Value {
+    json!({
+        "code": "todo-1",
+        "priority": 3,
+        "status": "todo",
+        "tags": [{"name": "work"}]
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_web_validation() -> TardisResult<()> {
+    // direct validation
+    let req: TodoAddReq = TardisFuns::json.json_to_obj(valid_req())?;
+    assert!(req.validate().is_ok());
+    let req = TodoAddReq {
+        code: "Todo_1".to_string(),
+        priority: 9,
+        status: "doing".to_string(),
+        description: Some("spam".to_string()),
+        tags: vec![TagReq { name: "work".to_string() }, TagReq { name: "".to_string() }, TagReq { name: "home".to_string() }],
+    };
+    let errors = req.validate().unwrap_err();
+    let fields = errors.0.iter().map(|error| (error.field.as_str(), error.code.as_str())).collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("code", VALIDATION_REGEX_CODE),
+            ("priority", VALIDATION_RANGE_CODE),
+            ("status", VALIDATION_ONE_OF_CODE),
+            ("description", "400-todo-description-spam"),
+            ("tags", VALIDATION_LENGTH_CODE),
+            ("tags[1].name", VALIDATION_LENGTH_CODE),
+        ]
+    );
+    // the optional fields are checked only if present
+    let req = TodoAddReq { description: None, ..req };
+    assert!(req.validate().unwrap_err().0.iter().all(|error| error.field != "description"));
+
+    env::set_var("PROFILE", "default");
+    let dir = env::temp_dir().join(format!("tardis-web-validation-{}", TardisFuns::field.nanoid()));
+    std::fs::create_dir_all(dir.join("locale"))?;
+    std::fs::write(dir.join("conf-default.toml"), "[fw.app]\ndefault_lang = \"en\"\n\n[fw.web_server]\nport = 8097\n")?;
+    std::fs::write(
+        dir.join("locale").join("zh-cn"),
+        format!("{VALIDATION_LENGTH_CODE}\t长度必须在{{1}}到{{2}}之间\tbetween (\\d+) and (\\d+)\n{VALIDATION_ONE_OF_CODE}\t不在可选值范围内\n"),
+    )?;
+    TardisFuns::init(dir.to_str()).await?;
+    TardisFuns::web_server().add_module("todo", TodoApi).await;
+    let client = TardisWebTestClient::from_server(&TardisFuns::web_server()).await?;
+
+    client.post("/todo/todos", &valid_req()).await?.assert_status(200).assert_code("200");
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 1);
+
+    // the invalid body is rejected before the handler runs
+    let mut req = valid_req();
+    req["code"] = json!("");
+    req["status"] = json!("doing");
+    let resp = client.post("/todo/todos", &req).await?;
+    resp.assert_code("400");
+    let body = resp.json::<Value>()?;
+    assert_eq!(body["ext"]["code.code"], VALIDATION_LENGTH_CODE);
+    assert_eq!(body["ext"]["code"], "length must be between 1 and 16");
+    assert_eq!(body["ext"]["status.code"], VALIDATION_ONE_OF_CODE);
+    assert_eq!(body["ext"]["status"], "must be one of [todo, done]");
+    assert!(body["msg"].as_str().unwrap().contains("status: must be one of [todo, done]"));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 1);
+
+    // localized by the `Accept-Language` header
+    let resp = client
+        .request(
+            Method::POST,
+            "/todo/todos",
+            vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Accept-Language".to_string(), "zh-cn,zh;q=0.9".to_string()),
+            ],
+            Some(TardisFuns::json.obj_to_string(&req)?),
+        )
+        .await?;
+    let body = resp.json::<Value>()?;
+    assert_eq!(body["ext"]["code"], "长度必须在1到16之间");
+    assert_eq!(body["ext"]["status"], "不在可选值范围内");
+
+    // nested fields
+    let mut req = valid_req();
+    req["tags"] = json!([{"name": "work"}, {"name": "a-too-long-tag"}]);
+    let body = client.post("/todo/todos", &req).await?.json::<Value>()?;
+    assert_eq!(body["ext"]["tags[1].name.code"], VALIDATION_LENGTH_CODE);
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 1);
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+struct TodoApi;
+
+#[OpenApi]
+impl TodoApi {
+    #[oai(path = "/todos", method = "post")]
+    async fn add(&self, req: ValidJson<TodoAddReq>) -> TardisApiResult<String> {
+        HANDLED.fetch_add(1, Ordering::SeqCst);
+        TardisResp::ok(req.code.clone())
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use poem::web::websocket::{BoxWebSocketUpgraded, Message, WebSocket};
+use serde::{Deserialize, Serialize};
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::WSClientConfig;
+use tardis::consts::IP_LOCALHOST;
+use tardis::web::web_server::TardisWebServer;
+use tardis::web::ws_client::TardisWSClient;
+use tardis::TardisFuns;
+use tokio::time::sleep;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TodoEvent {
+    id: u64,
+    done: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserEvent {
+    #[allow(dead_code)]
+    user_id: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ws_client() -> TardisResult<()> {
+    static TODO_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    static USER_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    static RAW_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    TardisFuns::init_log()?;
+    let serv = TardisWebServer::init_simple(IP_LOCALHOST, 8096)?;
+    serv.add_module("echo", EchoApi).await;
+    serv.start().await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let config = WSClientConfig::builder().reconnect_interval_ms(100).ping_interval_sec(1).pong_timeout_sec(1).build();
+    let client = TardisWSClient::connect_with_config("ws://127.0.0.1:8096/echo/ws", &config, |_| async move {
+        RAW_COUNTER.fetch_add(1, Ordering::SeqCst);
+        None
+    })
+    .await?;
+    let todo_subscription = client.subscribe(|event: TodoEvent| async move {
+        assert_eq!(event.id, 1);
+        TODO_COUNTER.fetch_add(1, Ordering::SeqCst);
+    });
+    client.subscribe(|_: UserEvent| async move {
+        USER_COUNTER.fetch_add(1, Ordering::SeqCst);
+    });
+
+    client.send_obj(&TodoEvent { id: 1, done: false }).await?;
+    client.send_text("not json".to_string()).await?;
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(TODO_COUNTER.load(Ordering::SeqCst), 1);
+    assert_eq!(USER_COUNTER.load(Ordering::SeqCst), 0);
+    assert_eq!(RAW_COUNTER.load(Ordering::SeqCst), 2);
+
+    // the pongs keep the connection alive
+    sleep(Duration::from_millis(3500)).await;
+    assert!(client.is_connected());
+    assert_eq!(RAW_COUNTER.load(Ordering::SeqCst), 2);
+
+    // the server drops the connection, and the client reconnects automatically with the subscriptions
+    client.send_text("bye".to_string()).await?;
+    sleep(Duration::from_millis(500)).await;
+    assert!(client.is_connected());
+    client.send_obj(&TodoEvent { id: 1, done: true }).await?;
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(TODO_COUNTER.load(Ordering::SeqCst), 2);
+
+    client.unsubscribe(todo_subscription);
+    client.send_obj(&TodoEvent { id: 1, done: true }).await?;
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(TODO_COUNTER.load(Ordering::SeqCst), 2);
+
+    client.close().await?;
+    sleep(Duration::from_millis(500)).await;
+    assert!(!client.is_connected());
+
+    serv.shutdown().await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct EchoApi;
+
+#[poem_openapi::OpenApi]
+impl EchoApi {
+    #[oai(path = "/ws", method = "get")]
+    async fn ws(&self, websocket: WebSocket) -> BoxWebSocketUpgraded {
+        websocket
+            .on_upgrade(|mut socket| async move {
+                while let Some(Ok(message)) = socket.next().await {
+                    match message {
+                        // drop the connection without the closing handshake
+                        Message::Text(text) if text == "bye" => return,
+                        Message::Text(text) => {
+                            if socket.send(Message::Text(text)).await.is_err() {
+                                return;
+                            }
+                        }
+                        Message::Close(_) => return,
+                        _ => {}
+                    }
+                }
+            })
+            .boxed()
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use poem::web::websocket::{BoxWebSocketUpgraded, WebSocket};
+use poem_openapi::param::Path;
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::consts::IP_LOCALHOST;
+use tardis::web::web_server::TardisWebServer;
+use tardis::web::ws_processor::ws_registry::{ws_registry, ws_send_to_group, ws_send_to_session, ws_session, TardisWsRegistry};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ws_registry() -> TardisResult<()> {
+    test_groups().await?;
+    test_cache_fanout().await?;
+    Ok(())
+}
+
+async fn test_groups() -> TardisResult<()> {
+    let serv = TardisWebServer::init_simple(IP_LOCALHOST, 8097)?;
+    serv.add_module("chat", ChatApi).await;
+    serv.start().await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let (a_tx, mut a_rx) = mpsc::unbounded_channel::<String>();
+    let (b_tx, mut b_rx) = mpsc::unbounded_channel::<String>();
+    let mut client_a = connect("ws://127.0.0.1:8097/chat/ws/room1", a_tx).await?;
+    let mut client_b = connect("ws://127.0.0.1:8097/chat/ws/room2", b_tx).await?;
+
+    // the handler replies the session id
+    send(&mut client_a, "id").await?;
+    let a_session_id = recv(&mut a_rx).await.unwrap();
+    assert_eq!(ws_registry().group_sessions("room1"), vec![a_session_id.clone()]);
+
+    assert_eq!(ws_send_to_group("room1", "hi room1").await?, 1);
+    assert_eq!(recv(&mut a_rx).await.unwrap(), "hi room1");
+    assert_eq!(recv(&mut b_rx).await, None);
+
+    // join / leave inside the handler
+    send(&mut client_b, "join:room1").await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(ws_send_to_group("room1", "hi all").await?, 2);
+    assert_eq!(recv(&mut a_rx).await.unwrap(), "hi all");
+    assert_eq!(recv(&mut b_rx).await.unwrap(), "hi all");
+    send(&mut client_b, "leave:room1").await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(ws_send_to_group("room1", "hi again").await?, 1);
+    assert_eq!(recv(&mut b_rx).await, None);
+    assert_eq!(recv(&mut a_rx).await.unwrap(), "hi again");
+
+    ws_send_to_session(&a_session_id, "only a").await?;
+    assert_eq!(recv(&mut a_rx).await.unwrap(), "only a");
+    assert!(ws_send_to_session("not-exist", "nobody").await.is_err());
+
+    // the session is unregistered when the connection is closed
+    client_a.send(Message::Close(None)).await.map_err(|error| TardisError::format_error(&error.to_string(), ""))?;
+    sleep(Duration::from_millis(200)).await;
+    assert!(ws_registry().group_sessions("room1").is_empty());
+    assert_eq!(ws_send_to_group("room1", "hi").await?, 0);
+
+    serv.shutdown().await?;
+    Ok(())
+}
+
+async fn test_cache_fanout() -> TardisResult<()> {
+    let cache = Arc::new(TardisCacheClient::memory());
+    // registries of two web server instances
+    let registry_a = TardisWsRegistry::new();
+    let registry_b = TardisWsRegistry::new();
+    registry_a.enable_cache_fanout(cache.clone(), "tardis:ws").await?;
+    registry_b.enable_cache_fanout(cache.clone(), "tardis:ws").await?;
+
+    let (session_a, mut a_rx) = registry_a.register();
+    let (session_b, mut b_rx) = registry_b.register();
+    session_a.join("room");
+    session_b.join("room");
+
+    // delivered locally and to the other instance exactly once
+    assert_eq!(registry_b.send_to_group("room", "hi room").await?, 1);
+    assert_eq!(recv(&mut a_rx).await.unwrap(), "hi room");
+    assert_eq!(recv(&mut b_rx).await.unwrap(), "hi room");
+    assert_eq!(recv(&mut b_rx).await, None);
+
+    registry_b.send_to_session(session_a.id(), "hi a").await?;
+    assert_eq!(recv(&mut a_rx).await.unwrap(), "hi a");
+
+    registry_a.disable_cache_fanout();
+    registry_b.send_to_group("room", "local only").await?;
+    assert_eq!(recv(&mut b_rx).await.unwrap(), "local only");
+    assert_eq!(recv(&mut a_rx).await, None);
+
+    registry_b.disable_cache_fanout();
+    assert!(registry_b.send_to_session(session_a.id(), "hi a").await.is_err());
+    Ok(())
+}
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+async fn connect(url: &str, tx: mpsc::UnboundedSender<String>) -> TardisResult<WsSink> {
+    let (stream, _) = tokio_tungstenite::connect_async(url).await.map_err(|error| TardisError::format_error(&error.to_string(), ""))?;
+    let (sink, mut stream) = stream.split();
+    tokio::spawn(async move {
+        while let Some(Ok(message)) = stream.next().await {
+            if let Message::Text(text) = message {
+                let _ = tx.send(text);
+            }
+        }
+    });
+    Ok(sink)
+}
+
+async fn send(sink: &mut WsSink, text: &str) -> TardisResult<()> {
+    sink.send(Message::Text(text.to_string())).await.map_err(|error| TardisError::format_error(&error.to_string(), ""))
+}
+
+async fn recv(rx: &mut mpsc::UnboundedReceiver<String>) -> Option<String> {
+    timeout(Duration::from_millis(300), rx.recv()).await.ok().flatten()
+}
+
+#[derive(Debug, Clone)]
+struct ChatApi;
+
+#[poem_openapi::OpenApi]
+impl ChatApi {
+    #[oai(path = "/ws/:room", method = "get")]
+    async fn ws(&self, room: Path<String>, websocket: WebSocket) -> BoxWebSocketUpgraded {
+        ws_session(
+            vec![room.0],
+            HashMap::new(),
+            websocket,
+            |session, text, _| async move {
+                if text == "id" {
+                    return Some(session.id().to_string());
+                }
+                if let Some(group) = text.strip_prefix("join:") {
+                    session.join(group);
+                } else if let Some(group) = text.strip_prefix("leave:") {
+                    session.leave(group);
+                }
+                None
+            },
+            |_, _, _| async move {},
+        )
+    }
+}